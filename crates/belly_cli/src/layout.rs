@@ -0,0 +1,335 @@
+//! Converts the JSON layout description (the Figma REST API node tree or
+//! the same shaped export of the custom editor) to the `.eml` scene and
+//! the `.ess` stylesheet with the rule per element.
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use serde_json::Value;
+
+pub struct Layout {
+    pub eml: String,
+    pub ess: String,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Bounds {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+#[derive(PartialEq)]
+enum Kind {
+    Frame,
+    Text,
+    Image,
+}
+
+struct Importer<'a> {
+    images: &'a str,
+    classes: HashSet<String>,
+    eml: String,
+    ess: String,
+}
+
+/// Converts the layout: the frames, groups & shapes become `<span>`s, the
+/// text nodes become `<label>`s and the nodes with the image fill become
+/// `<img>`s. Each element gets the unique class made from the node name.
+/// The image fills refer the `{images}/{imageRef}.png` files unless the
+/// node has the explicit `src`.
+pub fn import(source: &str, images: &str) -> Result<Layout, String> {
+    let json: Value = serde_json::from_str(source).map_err(|e| format!("Invalid json: {e}"))?;
+    // the Figma file & node responses wrap the tree into the `document`
+    let root = match (json.get("document"), json.get("nodes")) {
+        (Some(document), _) => document,
+        (None, Some(Value::Object(nodes))) => nodes
+            .values()
+            .find_map(|n| n.get("document"))
+            .ok_or("The `nodes` response has no documents")?,
+        _ => &json,
+    };
+    // the document & canvas (page) nodes are not drawn, start from the frame
+    let mut root = root;
+    while matches!(node_type(root).as_str(), "DOCUMENT" | "CANVAS") {
+        root = children(root)
+            .find(|c| visible(c))
+            .ok_or("The layout has no frames")?;
+    }
+    let mut importer = Importer {
+        images,
+        classes: HashSet::new(),
+        eml: String::new(),
+        ess: String::new(),
+    };
+    importer.node(root, None, false, 0);
+    if importer.eml.is_empty() {
+        return Err("The layout has no visible nodes".to_string());
+    }
+    Ok(Layout {
+        eml: importer.eml,
+        ess: importer.ess,
+    })
+}
+
+impl Importer<'_> {
+    fn node(&mut self, node: &Value, parent: Option<Bounds>, flow: bool, depth: usize) {
+        if !visible(node) {
+            return;
+        }
+        let kind = kind(node);
+        let bounds = bounds(node);
+        let class = self.class(node);
+        let indent = "    ".repeat(depth);
+        let mut rule = String::new();
+
+        match parent {
+            // the children of the auto layout frames are placed by the flex
+            Some(_) if flow => {}
+            Some(parent) => {
+                rule += "    position-type: absolute;\n";
+                let _ = writeln!(rule, "    left: {};", px(bounds.x - parent.x));
+                let _ = writeln!(rule, "    top: {};", px(bounds.y - parent.y));
+            }
+            None => {}
+        }
+        if kind != Kind::Text {
+            let _ = writeln!(rule, "    width: {};", px(bounds.width));
+            let _ = writeln!(rule, "    height: {};", px(bounds.height));
+        }
+        let fill = solid_fill(node);
+        match (&kind, fill) {
+            (Kind::Text, Some(color)) => {
+                let _ = writeln!(rule, "    color: {color};");
+            }
+            (Kind::Frame, Some(color)) => {
+                let _ = writeln!(rule, "    background-color: {color};");
+            }
+            _ => {}
+        }
+        if kind == Kind::Text {
+            if let Some(size) = node.pointer("/style/fontSize").and_then(Value::as_f64) {
+                let _ = writeln!(rule, "    font-size: {};", px(size));
+            }
+        }
+        if let Some(opacity) = number(node, "opacity").filter(|o| *o < 1.) {
+            let _ = writeln!(rule, "    opacity: {};", num(opacity));
+        }
+        let auto_layout = match node.get("layoutMode").and_then(Value::as_str) {
+            Some("HORIZONTAL") => Some("row"),
+            Some("VERTICAL") => Some("column"),
+            _ => None,
+        };
+        if let Some(direction) = auto_layout {
+            let _ = writeln!(rule, "    flex-direction: {direction};");
+            let gap = match direction {
+                "row" => "column-gap",
+                _ => "row-gap",
+            };
+            if let Some(spacing) = number(node, "itemSpacing").filter(|s| *s > 0.) {
+                let _ = writeln!(rule, "    {gap}: {};", px(spacing));
+            }
+            for side in ["top", "right", "bottom", "left"] {
+                let key = format!("padding{}{}", side[..1].to_uppercase(), &side[1..]);
+                if let Some(padding) = number(node, &key).filter(|p| *p > 0.) {
+                    let _ = writeln!(rule, "    padding-{side}: {};", px(padding));
+                }
+            }
+        }
+        let _ = writeln!(self.ess, ".{class} {{\n{rule}}}\n");
+
+        match kind {
+            Kind::Text => {
+                let text = node
+                    .get("characters")
+                    .or_else(|| node.get("text"))
+                    .and_then(Value::as_str)
+                    .unwrap_or_default();
+                let _ = writeln!(
+                    self.eml,
+                    "{indent}<label class=\"{class}\" value=\"{}\"/>",
+                    escape(text)
+                );
+            }
+            Kind::Image => {
+                let src = match (node.get("src"), image_ref(node)) {
+                    (Some(Value::String(src)), _) => src.clone(),
+                    (_, Some(reference)) => format!("{}/{reference}.png", self.images),
+                    _ => String::new(),
+                };
+                let _ = writeln!(
+                    self.eml,
+                    "{indent}<img class=\"{class}\" src=\"{}\"/>",
+                    escape(&src)
+                );
+            }
+            Kind::Frame => {
+                let mut nodes = children(node).filter(|c| visible(c)).peekable();
+                if nodes.peek().is_none() {
+                    let _ = writeln!(self.eml, "{indent}<span class=\"{class}\"/>");
+                    return;
+                }
+                let _ = writeln!(self.eml, "{indent}<span class=\"{class}\">");
+                for child in nodes {
+                    self.node(child, Some(bounds), auto_layout.is_some(), depth + 1);
+                }
+                let _ = writeln!(self.eml, "{indent}</span>");
+            }
+        }
+    }
+
+    fn class(&mut self, node: &Value) -> String {
+        let name = node.get("name").and_then(Value::as_str).unwrap_or_default();
+        let mut base = String::new();
+        for ch in name.chars() {
+            if ch.is_ascii_alphanumeric() {
+                base.push(ch.to_ascii_lowercase());
+            } else if !base.is_empty() && !base.ends_with('-') {
+                base.push('-');
+            }
+        }
+        let base = base.trim_end_matches('-');
+        let base = match base.chars().next() {
+            None => "node".to_string(),
+            Some(c) if c.is_ascii_digit() => format!("node-{base}"),
+            _ => base.to_string(),
+        };
+        let mut class = base.clone();
+        let mut idx = 1;
+        while !self.classes.insert(class.clone()) {
+            idx += 1;
+            class = format!("{base}-{idx}");
+        }
+        class
+    }
+}
+
+fn node_type(node: &Value) -> String {
+    node.get("type")
+        .and_then(Value::as_str)
+        .unwrap_or("FRAME")
+        .to_uppercase()
+}
+
+fn kind(node: &Value) -> Kind {
+    match node_type(node).as_str() {
+        "TEXT" => Kind::Text,
+        "IMAGE" => Kind::Image,
+        _ if image_ref(node).is_some() && children(node).next().is_none() => Kind::Image,
+        _ => Kind::Frame,
+    }
+}
+
+fn children(node: &Value) -> impl Iterator<Item = &Value> {
+    node.get("children")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+}
+
+fn visible(node: &Value) -> bool {
+    node.get("visible").and_then(Value::as_bool).unwrap_or(true)
+}
+
+fn number(node: &Value, key: &str) -> Option<f64> {
+    node.get(key).and_then(Value::as_f64)
+}
+
+fn bounds(node: &Value) -> Bounds {
+    let rect = node.get("absoluteBoundingBox").unwrap_or(node);
+    Bounds {
+        x: number(rect, "x").unwrap_or_default(),
+        y: number(rect, "y").unwrap_or_default(),
+        width: number(rect, "width").unwrap_or_default(),
+        height: number(rect, "height").unwrap_or_default(),
+    }
+}
+
+fn fills(node: &Value) -> impl Iterator<Item = &Value> {
+    node.get("fills")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|f| visible(f))
+}
+
+fn image_ref(node: &Value) -> Option<&str> {
+    fills(node)
+        .filter(|f| f.get("type").and_then(Value::as_str) == Some("IMAGE"))
+        .find_map(|f| f.get("imageRef").and_then(Value::as_str))
+}
+
+/// The first solid fill as `#rrggbbaa`, the Figma colors are `0..1` floats
+fn solid_fill(node: &Value) -> Option<String> {
+    let fill = fills(node).find(|f| f.get("type").and_then(Value::as_str) == Some("SOLID"))?;
+    let color = fill.get("color")?;
+    let channel = |key: &str| number(color, key).unwrap_or(1.);
+    let alpha = channel("a") * number(fill, "opacity").unwrap_or(1.);
+    let byte = |v: f64| (v.clamp(0., 1.) * 255.).round() as u8;
+    Some(format!(
+        "#{:02x}{:02x}{:02x}{:02x}",
+        byte(channel("r")),
+        byte(channel("g")),
+        byte(channel("b")),
+        byte(alpha)
+    ))
+}
+
+fn num(value: f64) -> String {
+    let value = (value * 100.).round() / 100.;
+    format!("{value}")
+}
+
+fn px(value: f64) -> String {
+    format!("{}px", num(value))
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\n', "&#10;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn import_figma_frames() {
+        let source = r#"{"document": {"type": "DOCUMENT", "children": [{
+            "type": "CANVAS", "name": "Page 1", "children": [{
+                "type": "FRAME", "name": "Main Menu", "layoutMode": "VERTICAL",
+                "itemSpacing": 8, "paddingTop": 16,
+                "absoluteBoundingBox": {"x": 100, "y": 50, "width": 320, "height": 240},
+                "fills": [{"type": "SOLID", "color": {"r": 1, "g": 0, "b": 0, "a": 1}}],
+                "children": [
+                    {"type": "TEXT", "name": "Title", "characters": "Play & win",
+                     "style": {"fontSize": 24}},
+                    {"type": "RECTANGLE", "name": "Logo",
+                     "fills": [{"type": "IMAGE", "imageRef": "abc"}]},
+                    {"type": "TEXT", "name": "Title", "characters": "Hidden",
+                     "visible": false},
+                    {"type": "TEXT", "name": "Title", "characters": "Quit"}
+                ]
+            }]
+        }]}}"#;
+        let layout = import(source, "images").unwrap();
+        assert_eq!(
+            layout.eml,
+            "<span class=\"main-menu\">\n    \
+             <label class=\"title\" value=\"Play &amp; win\"/>\n    \
+             <img class=\"logo\" src=\"images/abc.png\"/>\n    \
+             <label class=\"title-2\" value=\"Quit\"/>\n\
+             </span>\n"
+        );
+        assert!(layout.ess.contains(".main-menu {\n    width: 320px;\n"));
+        assert!(layout.ess.contains("    background-color: #ff0000ff;\n"));
+        assert!(layout.ess.contains("    flex-direction: column;\n    row-gap: 8px;\n"));
+        assert!(layout.ess.contains(".title {\n    font-size: 24px;\n}"));
+        assert!(!layout.ess.contains("position-type"));
+        assert!(import(r#"{"visible": false}"#, "images").is_err());
+    }
+}
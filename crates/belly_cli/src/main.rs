@@ -1,11 +1,34 @@
 // usage from inside belly crate:
 // cargo run -p belly_cli -- gen widget-reference > docs/widgets.md
-use std::{collections::HashMap, fs::File, io::BufReader};
+// cargo run -p belly_cli -- gen schema --format xsd > eml.xsd
+// cargo run -p belly_cli -- lint assets/
+// cargo run -p belly_cli -- import menu.json --out assets/menu
+mod layout;
 
-use clap::{Parser, Subcommand};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+use belly_core::eml::{parse::EmlParser, WidgetRegistry};
+use belly_core::ess::{
+    EssLocation, PropertyExtractor, PropertyTransformer, SelectorElement, StyleSheetParser,
+};
+use belly_core::ElementsCorePlugin;
+use belly_widgets::WidgetsPlugin;
+use bevy::{
+    input::InputPlugin,
+    prelude::*,
+    ui::UiStack,
+    window::{ExitCondition, WindowPlugin},
+};
+
+use clap::{Parser, Subcommand, ValueEnum};
 
 use rustdoc_types::{Crate, Id, Item, ItemEnum, ItemKind, Module, Type};
-use serde_json::from_reader;
+use serde_json::{from_reader, json, Map, Value};
 
 #[derive(Debug, Parser)]
 #[command(name = "cargo-polako")]
@@ -19,6 +42,23 @@ struct Cli {
 enum Command {
     #[command(subcommand)]
     Gen(Gen),
+    /// Checks the `.ess` & `.eml` assets, exits with non-zero code on errors
+    Lint {
+        #[arg(default_value = "assets")]
+        path: PathBuf,
+    },
+    /// Converts the JSON layout (exported from Figma or the custom editor)
+    /// to the `.eml` scene & the `.ess` stylesheet
+    Import {
+        input: PathBuf,
+        /// The path of the generated files without the extension, the
+        /// input file name next to the input file by default
+        #[arg(long)]
+        out: Option<PathBuf>,
+        /// The directory with the exported images, relative to the assets
+        #[arg(long, default_value = "images")]
+        images: String,
+    },
 }
 
 #[derive(Debug, Subcommand)]
@@ -26,6 +66,17 @@ enum Command {
 enum Gen {
     StyleReference,
     WidgetReference,
+    /// Schema of the widgets, their params & signals for the `.eml` editors
+    Schema {
+        #[arg(long, value_enum, default_value = "json")]
+        format: SchemaFormat,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum SchemaFormat {
+    Json,
+    Xsd,
 }
 
 fn main() {
@@ -33,10 +84,146 @@ fn main() {
     match args.command {
         Command::Gen(Gen::StyleReference) => gen_style_docs(),
         Command::Gen(Gen::WidgetReference) => gen_widget_docs(),
+        Command::Gen(Gen::Schema { format }) => gen_schema(format),
+        Command::Lint { path } => lint(&path),
+        Command::Import { input, out, images } => import_layout(&input, out, &images),
     }
 }
 
-fn gen_widget_docs() {
+fn import_layout(input: &Path, out: Option<PathBuf>, images: &str) {
+    let source = std::fs::read_to_string(input).unwrap_or_else(|e| {
+        eprintln!("error: can't read {}: {e}", input.display());
+        std::process::exit(1);
+    });
+    let layout = layout::import(&source, images).unwrap_or_else(|e| {
+        eprintln!("error: {e}\n  --> {}", input.display());
+        std::process::exit(1);
+    });
+    let out = out.unwrap_or_else(|| input.with_extension(""));
+    for (ext, content) in [("eml", &layout.eml), ("ess", &layout.ess)] {
+        let path = out.with_extension(ext);
+        if let Err(e) = std::fs::write(&path, content) {
+            eprintln!("error: can't write {}: {e}", path.display());
+            std::process::exit(1);
+        }
+        eprintln!("{} written", path.display());
+    }
+}
+
+fn collect_assets(path: &Path, assets: &mut Vec<PathBuf>) {
+    if path.is_dir() {
+        let Ok(entries) = std::fs::read_dir(path) else {
+            eprintln!("Can't read {}", path.display());
+            return;
+        };
+        let mut entries: Vec<_> = entries.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+        entries.sort();
+        entries.iter().for_each(|e| collect_assets(e, assets));
+    } else if matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("ess") | Some("eml")
+    ) {
+        assets.push(path.to_path_buf());
+    }
+}
+
+/// The app without the window & the renderer, registers all the
+/// built-in widgets & properties
+fn headless_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        InputPlugin,
+        WindowPlugin {
+            primary_window: None,
+            exit_condition: ExitCondition::DontExit,
+            close_when_requested: false,
+        },
+    ));
+    app.init_asset::<Image>();
+    app.init_asset::<Font>();
+    app.init_asset::<TextureAtlasLayout>();
+    app.init_resource::<UiStack>();
+    app.add_plugins((ElementsCorePlugin, WidgetsPlugin));
+    app.update();
+    app
+}
+
+fn lint(path: &Path) {
+    let mut assets = vec![];
+    collect_assets(path, &mut assets);
+    let app = headless_app();
+    let world = &app.world;
+    let registry = world.resource::<WidgetRegistry>().clone();
+    let transformer = world.resource::<PropertyTransformer>().clone();
+    let extractor = world.resource::<PropertyExtractor>().clone();
+    let ess = StyleSheetParser::new(transformer.clone(), extractor.clone());
+    let eml = EmlParser::new(registry, transformer, extractor);
+
+    let (mut errors, mut warnings) = (0, 0);
+    let mut defined_classes: BTreeMap<String, (String, EssLocation)> = BTreeMap::new();
+    let mut used_classes = HashSet::new();
+    let mut eml_assets = 0;
+    for asset in assets.iter() {
+        let name = asset.display().to_string();
+        let Ok(source) = std::fs::read_to_string(asset) else {
+            eprintln!("error: can't read {name}\n");
+            errors += 1;
+            continue;
+        };
+        if asset.extension().map_or(false, |e| e == "ess") {
+            let (rules, diagnostics) = ess.parse_with_diagnostics(&source, Some(&name));
+            for diagnostic in diagnostics {
+                if diagnostic.is_error() {
+                    errors += 1;
+                    eprintln!("error: {diagnostic}\n");
+                } else {
+                    warnings += 1;
+                    eprintln!("warning: {diagnostic}\n");
+                }
+            }
+            for rule in rules.iter() {
+                for element in rule.selector.elements.iter() {
+                    if let SelectorElement::Class(class) = element {
+                        defined_classes
+                            .entry(class.to_string())
+                            .or_insert_with(|| (name.clone(), rule.location));
+                    }
+                }
+            }
+        } else {
+            eml_assets += 1;
+            match eml.parse(&source) {
+                Ok(root) => used_classes.extend(root.classes().iter().map(|c| c.to_string())),
+                Err(e) => {
+                    errors += 1;
+                    eprintln!("error: {e}\n  --> {name}\n");
+                }
+            }
+        }
+    }
+    // the classes are often added from the code, so the unused
+    // classes are reported only when there are eml assets at all
+    if eml_assets > 0 {
+        for (class, (name, location)) in defined_classes.iter() {
+            if !used_classes.contains(class) {
+                warnings += 1;
+                eprintln!("warning: class `.{class}` is not used by any .eml asset");
+                eprintln!("  --> {name}:{location}\n");
+            }
+        }
+    }
+    eprintln!(
+        "{} assets checked: {errors} errors, {warnings} warnings",
+        assets.len()
+    );
+    if errors > 0 {
+        std::process::exit(1);
+    }
+}
+
+fn load_widgets_crate() -> Crate {
     let json_path = rustdoc_json::Builder::default()
         .toolchain("nightly")
         .manifest_path("crates/belly_widgets/Cargo.toml")
@@ -47,7 +234,11 @@ fn gen_widget_docs() {
     let f = File::open(&json_path)
         .unwrap_or_else(|_| panic!("Could not open {}", json_path.to_str().unwrap()));
     let rdr = BufReader::new(f);
-    let crt: Crate = from_reader(rdr).unwrap_or_else(|e| panic!("Can't parse json: {e:?}"));
+    from_reader(rdr).unwrap_or_else(|e| panic!("Can't parse json: {e:?}"))
+}
+
+fn gen_widget_docs() {
+    let crt = load_widgets_crate();
     let mut widgets = fetch_widgets(&crt);
     widgets.sort_by_key(|k| k.name.clone());
     for widget in widgets.iter() {
@@ -63,10 +254,152 @@ fn gen_widget_docs() {
         if !params.is_empty() {
             println!("\nParams:\n\n{params}")
         }
+        let parts = widget.docs_parts();
+        if !parts.is_empty() {
+            println!("\nParts:\n\n{parts}")
+        }
         println!("");
     }
 }
 
+fn gen_schema(format: SchemaFormat) {
+    let crt = load_widgets_crate();
+    let mut widgets = fetch_widgets(&crt);
+    widgets.sort_by_key(|k| k.name.clone());
+    match format {
+        SchemaFormat::Json => println!("{}", json_schema(&widgets)),
+        SchemaFormat::Xsd => println!("{}", xsd_schema(&widgets)),
+    }
+}
+
+fn json_type(ty: &str) -> &'static str {
+    match ty {
+        "bool" => "boolean",
+        "String" | "Tag" => "string",
+        "f32" | "f64" => "number",
+        "i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" | "u64" | "usize" => "integer",
+        _ => "string",
+    }
+}
+
+fn xsd_type(ty: &str) -> &'static str {
+    match json_type(ty) {
+        "boolean" => "xs:boolean",
+        "number" => "xs:decimal",
+        "integer" => "xs:integer",
+        _ => "xs:string",
+    }
+}
+
+fn json_schema(widgets: &[Widget]) -> String {
+    let mut definitions = Map::new();
+    for widget in widgets.iter() {
+        let mut properties = Map::new();
+        for param in widget.params() {
+            properties.insert(
+                param.name.clone(),
+                json!({
+                    "type": json_type(&param.ty),
+                    "x-rust-type": param.ty,
+                    "description": param.docs,
+                }),
+            );
+        }
+        for signal in widget.signals() {
+            properties.insert(
+                format!("on:{}", signal.name),
+                json!({
+                    "type": "string",
+                    "x-event": signal.ty,
+                    "description": signal.docs,
+                }),
+            );
+        }
+        definitions.insert(
+            widget.name.clone(),
+            json!({
+                "type": "object",
+                "description": widget.docs_body(),
+                "x-extends": widget.extends_chain(),
+                "x-parts": widget.parts(),
+                "properties": properties,
+                "patternProperties": {
+                    "^(c|s|on|bind|with):": { "type": "string" },
+                },
+            }),
+        );
+    }
+    let schema = json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "eml",
+        "description": "The belly widgets, their params & signals",
+        "definitions": Value::Object(definitions),
+    });
+    serde_json::to_string_pretty(&schema).unwrap()
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn xsd_schema(widgets: &[Widget]) -> String {
+    let mut xsd = String::new();
+    xsd += "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n";
+    xsd += "<!-- THIS SCHEMA IS GENERATED FROM RUST DOCSTRINGS -->\n";
+    xsd += "<xs:schema xmlns:xs=\"http://www.w3.org/2001/XMLSchema\">\n";
+    for widget in widgets.iter() {
+        let name = xml_escape(&widget.name);
+        xsd += &format!("  <xs:element name=\"{name}\">\n");
+        xsd += "    <xs:annotation>\n";
+        xsd += &format!(
+            "      <xs:documentation>{}</xs:documentation>\n",
+            xml_escape(&widget.docs_body())
+        );
+        xsd += "      <xs:appinfo>\n";
+        for extends in widget.extends_chain() {
+            xsd += &format!("        <extends>{}</extends>\n", xml_escape(&extends));
+        }
+        // the signals are connected with the `on:signal` attributes, the prefixed
+        // attributes are not valid in xsd, so the signals are listed here
+        for signal in widget.signals() {
+            xsd += &format!(
+                "        <signal name=\"{}\" event=\"{}\">{}</signal>\n",
+                xml_escape(&signal.name),
+                xml_escape(&signal.ty),
+                xml_escape(&signal.docs)
+            );
+        }
+        xsd += "      </xs:appinfo>\n";
+        xsd += "    </xs:annotation>\n";
+        xsd += "    <xs:complexType mixed=\"true\">\n";
+        xsd += "      <xs:sequence>\n";
+        xsd += "        <xs:any minOccurs=\"0\" maxOccurs=\"unbounded\"";
+        xsd += " processContents=\"lax\"/>\n";
+        xsd += "      </xs:sequence>\n";
+        for param in widget.params() {
+            xsd += &format!(
+                "      <xs:attribute name=\"{}\" type=\"{}\">\n",
+                xml_escape(&param.name),
+                xsd_type(&param.ty)
+            );
+            xsd += &format!(
+                "        <xs:annotation><xs:documentation>{}</xs:documentation></xs:annotation>\n",
+                xml_escape(&param.docs)
+            );
+            xsd += "      </xs:attribute>\n";
+        }
+        xsd += "      <xs:anyAttribute processContents=\"skip\"/>\n";
+        xsd += "    </xs:complexType>\n";
+        xsd += "  </xs:element>\n";
+    }
+    xsd += "</xs:schema>";
+    xsd
+}
+
 fn gen_style_docs() {
     let json_path = rustdoc_json::Builder::default()
         .toolchain("nightly-2022-12-18")
@@ -558,6 +891,117 @@ impl<'a> Widget<'a> {
     }
 }
 
+impl<'a> Widget<'a> {
+    /// The names of the extended widgets, from the closest one
+    pub fn extends_chain(&self) -> Vec<String> {
+        let mut result = vec![];
+        let mut widget = self.extends.as_ref();
+        while let Some(w) = widget {
+            result.push(w.name.clone());
+            widget = w.extends.as_ref();
+        }
+        result
+    }
+
+    /// The params of the widget and the widgets it extends
+    pub fn params(&self) -> Vec<DocItem> {
+        self.collect_items("widget-params")
+    }
+
+    /// The signals of the widget and the widgets it extends
+    pub fn signals(&self) -> Vec<DocItem> {
+        self.collect_items("widget-signals")
+    }
+
+    /// The names of the parts declared by the widget and the widgets it extends
+    pub fn parts(&self) -> Vec<String> {
+        let mut result: Vec<String> = vec![];
+        let mut widget = Some(self);
+        while let Some(w) = widget {
+            let block = w.docs.block("widget-parts").unwrap_or_default();
+            for line in block.lines() {
+                let part = line.trim().strip_prefix("- `::").and_then(|l| l.split('`').next());
+                if let Some(part) = part.filter(|p| !result.iter().any(|r| r == p)) {
+                    result.push(part.to_string());
+                }
+            }
+            widget = w.extends.as_ref().map(|b| b.as_ref());
+        }
+        result
+    }
+
+    /// The parts docs of the widget and the widgets it extends
+    pub fn docs_parts(&self) -> String {
+        let mut result = "".to_string();
+        let mut widget = Some(self);
+        while let Some(w) = widget {
+            let parts = w.docs.block("widget-parts").unwrap_or_default().trim();
+            if !parts.is_empty() {
+                if w.name != self.name {
+                    result += format!("\nfrom `<{}>`\n", w.name).as_str();
+                }
+                result += parts;
+            }
+            widget = w.extends.as_ref().map(|b| b.as_ref());
+        }
+        result.trim().into()
+    }
+
+    fn collect_items(&self, block: &str) -> Vec<DocItem> {
+        let mut result: Vec<DocItem> = vec![];
+        let mut widget = Some(self);
+        while let Some(w) = widget {
+            for item in w.docs.block(block).map(docitems).unwrap_or_default() {
+                // the extending widget overrides the params of the base one
+                if !result.iter().any(|i| i.name == item.name) {
+                    result.push(item);
+                }
+            }
+            widget = w.extends.as_ref().map(|b| b.as_ref());
+        }
+        result
+    }
+}
+
+/// The param or signal from the `@widget-params` or `@widget-signals` block
+pub struct DocItem {
+    name: String,
+    ty: String,
+    docs: String,
+}
+
+/// Parses the `- `name:` [`Type`]` lists generated by the `#[widget]` macro
+fn docitems(block: &str) -> Vec<DocItem> {
+    let mut result: Vec<DocItem> = vec![];
+    for line in block.lines() {
+        let line = line.trim();
+        let signature = line
+            .strip_prefix("- `")
+            .and_then(|l| l.split_once(":`"))
+            .map(|(name, rest)| {
+                let ty = rest.trim().trim_start_matches("[`");
+                let ty = ty.split("`]").next().unwrap_or_default();
+                (name, ty)
+            });
+        if let Some((name, ty)) = signature {
+            result.push(DocItem {
+                name: name.to_string(),
+                ty: ty.to_string(),
+                docs: String::new(),
+            });
+        } else if let Some(item) = result.last_mut() {
+            if line.is_empty() || line.starts_with("<!--") {
+                continue;
+            }
+            if !item.docs.is_empty() {
+                item.docs += " ";
+            }
+            item.docs += line;
+        }
+    }
+    result
+}
+
 enum PropertyType {
     Unknown,
     Inline(String),
@@ -699,3 +1143,95 @@ fn docblock_replace<'a, F: Fn(&'a str) -> String>(
     }
     docstring.to_string()
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// The docs the `#[widget]` macro generates for the `<button>`
+    const BUTTON_DOCS: &str = "<!-- @widget-name=button -->
+<!-- @widget-body-begin -->
+The `<button>` tag defines a clickable button.
+<!-- @widget-body-end -->
+
+Params:
+<!-- @widget-params-begin -->
+- `pressed:` [`bool`]
+if button is pressed or not
+
+- `mode:` [`BtnMode`]
+Specifies the button behavior
+
+<!-- @widget-params-end -->
+
+Signals:
+<!-- @widget-signals-begin -->
+- `press:` [`BtnEvent`]
+emits when the button is pressed
+
+<!-- @widget-signals-end -->
+<!-- @widget-parts-begin -->
+<!-- @widget-parts-end -->";
+
+    fn generate(format: SchemaFormat) -> String {
+        let crt = Crate {
+            root: Id("0:0".into()),
+            crate_version: None,
+            includes_private: false,
+            index: HashMap::new(),
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            format_version: rustdoc_types::FORMAT_VERSION,
+        };
+        let module = Module {
+            is_crate: false,
+            items: vec![],
+            is_stripped: false,
+        };
+        let widgets = [Widget {
+            crt: &crt,
+            links: HashMap::new(),
+            name: "button".into(),
+            module: &module,
+            extends: None,
+            docs: Doc::new(BUTTON_DOCS),
+        }];
+        match format {
+            SchemaFormat::Json => json_schema(&widgets),
+            SchemaFormat::Xsd => xsd_schema(&widgets),
+        }
+    }
+
+    #[test]
+    fn json_schema_describes_button() {
+        let schema: Value = serde_json::from_str(&generate(SchemaFormat::Json)).unwrap();
+        let button = &schema["definitions"]["button"];
+        assert_eq!(
+            button["description"],
+            "The `<button>` tag defines a clickable button."
+        );
+        assert_eq!(button["properties"]["pressed"]["type"], "boolean");
+        assert_eq!(
+            button["properties"]["pressed"]["description"],
+            "if button is pressed or not"
+        );
+        assert_eq!(button["properties"]["mode"]["type"], "string");
+        assert_eq!(button["properties"]["mode"]["x-rust-type"], "BtnMode");
+        assert_eq!(button["properties"]["on:press"]["x-event"], "BtnEvent");
+        assert_eq!(button["x-extends"], json!([]));
+    }
+
+    #[test]
+    fn xsd_schema_describes_button() {
+        let schema = generate(SchemaFormat::Xsd);
+        assert!(schema.contains("<xs:element name=\"button\">"));
+        assert!(schema.contains(
+            "<xs:documentation>The `&lt;button&gt;` tag defines a clickable button.</xs:documentation>"
+        ));
+        assert!(schema.contains("<xs:attribute name=\"pressed\" type=\"xs:boolean\">"));
+        assert!(schema.contains("<xs:attribute name=\"mode\" type=\"xs:string\">"));
+        assert!(schema.contains(
+            "<signal name=\"press\" event=\"BtnEvent\">emits when the button is pressed</signal>"
+        ));
+    }
+}
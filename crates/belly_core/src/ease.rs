@@ -0,0 +1,332 @@
+use crate::eml::Variant;
+use crate::ess::PropertyParser;
+use crate::ess::StyleProperty;
+use crate::ess::StylePropertyMethods;
+use crate::ess::StylePropertyToken;
+use crate::ElementsError;
+use bevy::prelude::*;
+use std::f32::consts::PI;
+use std::str::FromStr;
+
+/// The easing function: maps the progress of the animation in `0..1`
+/// range to the progress of the animated value. Shared by everything
+/// animating over time: the eased binds, the `<label tween=..>`
+/// counters and the style properties referencing the easing by name:
+/// ```rust,ignore
+/// from!(player, Health:current).ease(Ease::OutCubic, 0.2) >> to!(bar, HealthBar:value)
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default, Reflect)]
+pub enum Ease {
+    #[default]
+    Linear,
+    InQuad,
+    OutQuad,
+    InOutQuad,
+    InCubic,
+    OutCubic,
+    InOutCubic,
+    InSine,
+    OutSine,
+    InOutSine,
+    InBack,
+    OutBack,
+    InOutBack,
+    OutElastic,
+    OutBounce,
+    /// The damped spring: the whole animation takes the first second of
+    /// the spring motion, so the weak springs may not settle at the end
+    Spring { stiffness: f32, damping: f32 },
+    /// Jumps through the `n` equal steps
+    Steps(u32),
+}
+
+/// The names of the easing functions without arguments
+const NAMES: &[(&str, Ease)] = &[
+    ("linear", Ease::Linear),
+    ("ease", Ease::InOutCubic),
+    ("ease-in", Ease::InCubic),
+    ("ease-out", Ease::OutCubic),
+    ("ease-in-out", Ease::InOutCubic),
+    ("ease-in-quad", Ease::InQuad),
+    ("ease-out-quad", Ease::OutQuad),
+    ("ease-in-out-quad", Ease::InOutQuad),
+    ("ease-in-cubic", Ease::InCubic),
+    ("ease-out-cubic", Ease::OutCubic),
+    ("ease-in-out-cubic", Ease::InOutCubic),
+    ("ease-in-sine", Ease::InSine),
+    ("ease-out-sine", Ease::OutSine),
+    ("ease-in-out-sine", Ease::InOutSine),
+    ("ease-in-back", Ease::InBack),
+    ("ease-out-back", Ease::OutBack),
+    ("ease-in-out-back", Ease::InOutBack),
+    ("ease-out-elastic", Ease::OutElastic),
+    ("ease-out-bounce", Ease::OutBounce),
+];
+
+impl Ease {
+    /// Returns the eased progress at the progress `t`, the result is `0.`
+    /// at the start and `1.` at the end, but may overshoot between them.
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0., 1.);
+        const C1: f32 = 1.70158;
+        const C2: f32 = C1 * 1.525;
+        const C3: f32 = C1 + 1.;
+        match *self {
+            Ease::Linear => t,
+            Ease::InQuad => t * t,
+            Ease::OutQuad => 1. - (1. - t).powi(2),
+            Ease::InOutQuad if t < 0.5 => 2. * t * t,
+            Ease::InOutQuad => 1. - (2. - 2. * t).powi(2) / 2.,
+            Ease::InCubic => t.powi(3),
+            Ease::OutCubic => 1. - (1. - t).powi(3),
+            Ease::InOutCubic if t < 0.5 => 4. * t.powi(3),
+            Ease::InOutCubic => 1. - (2. - 2. * t).powi(3) / 2.,
+            Ease::InSine => 1. - (t * PI / 2.).cos(),
+            Ease::OutSine => (t * PI / 2.).sin(),
+            Ease::InOutSine => (1. - (t * PI).cos()) / 2.,
+            Ease::InBack => C3 * t.powi(3) - C1 * t * t,
+            Ease::OutBack => 1. + C3 * (t - 1.).powi(3) + C1 * (t - 1.).powi(2),
+            Ease::InOutBack if t < 0.5 => (2. * t).powi(2) * ((C2 + 1.) * 2. * t - C2) / 2.,
+            Ease::InOutBack => {
+                ((2. * t - 2.).powi(2) * ((C2 + 1.) * (2. * t - 2.) + C2) + 2.) / 2.
+            }
+            Ease::OutElastic if t == 0. || t == 1. => t,
+            Ease::OutElastic => {
+                2f32.powf(-10. * t) * ((10. * t - 0.75) * 2. * PI / 3.).sin() + 1.
+            }
+            Ease::OutBounce => out_bounce(t),
+            Ease::Spring { .. } if t == 1. => 1.,
+            Ease::Spring { stiffness, damping } => spring(t, stiffness, damping),
+            Ease::Steps(_) if t == 1. => 1.,
+            Ease::Steps(steps) => {
+                let steps = steps.max(1) as f32;
+                (t * steps).floor() / steps
+            }
+        }
+    }
+}
+
+fn out_bounce(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+    if t < 1. / D {
+        N * t * t
+    } else if t < 2. / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
+
+/// The position of the unit mass on the spring released at `0.` and
+/// pulled to `1.` after `t` seconds
+fn spring(t: f32, stiffness: f32, damping: f32) -> f32 {
+    let omega = stiffness.max(f32::EPSILON).sqrt();
+    let zeta = damping.max(0.) / (2. * omega);
+    if zeta < 1. {
+        let omega_d = omega * (1. - zeta * zeta).sqrt();
+        let decay = (-zeta * omega * t).exp();
+        1. - decay * ((omega_d * t).cos() + zeta * omega / omega_d * (omega_d * t).sin())
+    } else {
+        // the overdamped springs are approximated with the critically damped one
+        1. - (-omega * t).exp() * (1. + omega * t)
+    }
+}
+
+/// <!-- @property-type=$ease -->
+/// Easing function: the name like `linear`, `ease-out` or `ease-in-out-cubic`,
+/// the spring `spring($stiffness, $damping)` or the steps `steps($n)`.
+pub struct EaseParser;
+impl PropertyParser<Ease> for EaseParser {
+    fn parse(prop: &StyleProperty) -> Result<Ease, ElementsError> {
+        let err = || {
+            ElementsError::InvalidPropertyValue(format!(
+                "Expected $ease|spring($num, $num)|steps($num), got `{}`",
+                prop.to_string()
+            ))
+        };
+        if prop.len() != 1 {
+            return Err(err());
+        }
+        match &prop[0] {
+            StylePropertyToken::Identifier(name) => NAMES
+                .iter()
+                .find(|(ease, _)| ease == name)
+                .map(|(_, ease)| *ease)
+                .ok_or_else(err),
+            StylePropertyToken::Function(func) if func.name == "steps" => {
+                match func.args.as_slice() {
+                    [StylePropertyToken::Number(steps)] if steps.to_float() >= 1. => {
+                        Ok(Ease::Steps(steps.to_int() as u32))
+                    }
+                    _ => Err(err()),
+                }
+            }
+            StylePropertyToken::Function(func) if func.name == "spring" => {
+                match func.args.as_slice() {
+                    [StylePropertyToken::Number(stiffness), StylePropertyToken::Number(damping)]
+                        if stiffness.to_float() > 0. && damping.to_float() >= 0. =>
+                    {
+                        Ok(Ease::Spring {
+                            stiffness: stiffness.to_float(),
+                            damping: damping.to_float(),
+                        })
+                    }
+                    _ => Err(err()),
+                }
+            }
+            _ => Err(err()),
+        }
+    }
+}
+
+impl FromStr for Ease {
+    type Err = ElementsError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        EaseParser::parse(&StyleProperty::from_str(s)?)
+    }
+}
+
+impl TryFrom<Variant> for Ease {
+    type Error = String;
+    fn try_from(value: Variant) -> Result<Self, Self::Error> {
+        value.get_or_parse()
+    }
+}
+
+impl From<Ease> for Variant {
+    fn from(ease: Ease) -> Self {
+        Variant::boxed(ease)
+    }
+}
+
+/// The values the [`Ease`] animates: `interpolate` returns the value
+/// between `self` at `t = 0.` and `to` at `t = 1.`. The eased progress
+/// may overshoot the `0..1` range, so should the result.
+pub trait Interpolate: Clone {
+    fn interpolate(&self, to: &Self, t: f32) -> Self;
+}
+
+impl Interpolate for f32 {
+    fn interpolate(&self, to: &Self, t: f32) -> Self {
+        self + (to - self) * t
+    }
+}
+
+impl Interpolate for f64 {
+    fn interpolate(&self, to: &Self, t: f32) -> Self {
+        self + (to - self) * t as f64
+    }
+}
+
+macro_rules! interpolate_integers {
+    ($($int:ty),*) => {$(
+        impl Interpolate for $int {
+            fn interpolate(&self, to: &Self, t: f32) -> Self {
+                (*self as f64).interpolate(&(*to as f64), t).round() as $int
+            }
+        }
+    )*};
+}
+interpolate_integers!(i32, i64, u32, u64, usize);
+
+impl Interpolate for Vec2 {
+    fn interpolate(&self, to: &Self, t: f32) -> Self {
+        self.lerp(*to, t)
+    }
+}
+
+impl Interpolate for Vec3 {
+    fn interpolate(&self, to: &Self, t: f32) -> Self {
+        self.lerp(*to, t)
+    }
+}
+
+impl Interpolate for Vec4 {
+    fn interpolate(&self, to: &Self, t: f32) -> Self {
+        self.lerp(*to, t)
+    }
+}
+
+impl Interpolate for Quat {
+    fn interpolate(&self, to: &Self, t: f32) -> Self {
+        self.slerp(*to, t)
+    }
+}
+
+impl Interpolate for Color {
+    fn interpolate(&self, to: &Self, t: f32) -> Self {
+        let from = Vec4::from(self.as_rgba_f32());
+        let to = Vec4::from(to.as_rgba_f32());
+        let [r, g, b, a] = from.interpolate(&to, t).to_array();
+        Color::rgba(r, g, b, a)
+    }
+}
+
+impl Interpolate for Val {
+    /// Interpolates the values of the same units, the values of the
+    /// different units jump at the end.
+    fn interpolate(&self, to: &Self, t: f32) -> Self {
+        match (*self, *to) {
+            (Val::Px(from), Val::Px(to)) => Val::Px(from.interpolate(&to, t)),
+            (Val::Percent(from), Val::Percent(to)) => Val::Percent(from.interpolate(&to, t)),
+            (Val::Vw(from), Val::Vw(to)) => Val::Vw(from.interpolate(&to, t)),
+            (Val::Vh(from), Val::Vh(to)) => Val::Vh(from.interpolate(&to, t)),
+            (Val::VMin(from), Val::VMin(to)) => Val::VMin(from.interpolate(&to, t)),
+            (Val::VMax(from), Val::VMax(to)) => Val::VMax(from.interpolate(&to, t)),
+            (from, _) if t < 1. => from,
+            (_, to) => to,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ease_functions_start_and_end() {
+        let spring = Ease::Spring {
+            stiffness: 170.,
+            damping: 26.,
+        };
+        for (_, ease) in NAMES.iter().chain(&[("", spring), ("", Ease::Steps(4))]) {
+            assert!(ease.apply(0.).abs() < 1e-4, "{ease:?} should start at 0");
+            assert!((ease.apply(1.) - 1.).abs() < 1e-4, "{ease:?} should end at 1");
+        }
+        assert_eq!(Ease::Steps(4).apply(0.3), 0.25);
+        assert!(Ease::OutCubic.apply(0.5) > 0.5);
+        assert!(Ease::InCubic.apply(0.5) < 0.5);
+        assert!(Ease::OutBack.apply(0.8) > 1., "OutBack should overshoot");
+    }
+
+    #[test]
+    fn parse_ease() {
+        assert_eq!("ease-in-out-cubic".parse::<Ease>().unwrap(), Ease::InOutCubic);
+        assert_eq!("steps(3)".parse::<Ease>().unwrap(), Ease::Steps(3));
+        assert_eq!(
+            "spring(100, 10)".parse::<Ease>().unwrap(),
+            Ease::Spring {
+                stiffness: 100.,
+                damping: 10.
+            }
+        );
+        assert!("ease-sideways".parse::<Ease>().is_err());
+        assert!("steps(0)".parse::<Ease>().is_err());
+        assert!("linear linear".parse::<Ease>().is_err());
+    }
+
+    #[test]
+    fn interpolate_values() {
+        assert_eq!(2f32.interpolate(&4., 0.5), 3.);
+        assert_eq!(10u32.interpolate(&0, 0.26), 7);
+        assert_eq!(Val::Px(0.).interpolate(&Val::Px(10.), 0.5), Val::Px(5.));
+        assert_eq!(Val::Px(0.).interpolate(&Val::Auto, 0.5), Val::Px(0.));
+        assert_eq!(Val::Px(0.).interpolate(&Val::Auto, 1.), Val::Auto);
+    }
+}
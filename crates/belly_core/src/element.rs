@@ -4,12 +4,13 @@ use bevy::ecs::system::{Command, CommandQueue, SystemMeta, SystemParam};
 use bevy::ecs::world::unsafe_world_cell::UnsafeWorldCell;
 use bevy::ui::UiSystem;
 use bevy::utils::{HashMap, HashSet};
-use smallvec::SmallVec;
+use smallvec::{smallvec, SmallVec};
 use std::ops::Deref;
 use std::ops::DerefMut;
 
 use crate::eml::Eml;
-use crate::ess::{ElementsBranch, PropertyValue, Selector};
+use crate::ess::{preceding_siblings, ElementsBranch, PropertyValue, Selector, SortedRules};
+use crate::states::{RegisterWidgetStates, WidgetStates};
 use crate::tags;
 use crate::tags::*;
 use bevy::prelude::*;
@@ -18,12 +19,15 @@ pub struct ElementsPlugin;
 impl Plugin for ElementsPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<ElementIdIndex>();
+        app.init_resource::<UiLayers>();
         app.add_systems(
             PostUpdate,
             invalidate_elements
                 .in_set(InvalidateElements)
                 .before(UiSystem::Layout),
         );
+        app.add_systems(PostUpdate, apply_ui_layers.before(UiSystem::Layout));
+        app.register_widget_states::<Loading>();
     }
 }
 
@@ -102,6 +106,12 @@ pub struct Element {
     pub classes: HashSet<Tag>,
     pub state: HashSet<Tag>,
     pub styles: HashMap<Tag, PropertyValue>,
+    /// The scalar params the element was built with, matched by
+    /// the `[name=value]` selectors
+    pub attributes: HashMap<Tag, String>,
+    /// The sources of the inline styles, kept to save the element into
+    /// the scene (see [`ElementSnapshot`](crate::scene::ElementSnapshot))
+    pub(crate) style_sources: HashMap<Tag, String>,
 }
 
 impl Element {
@@ -119,6 +129,13 @@ impl Element {
     pub fn hovered(&self) -> bool {
         self.state.contains(&tags::hover())
     }
+
+    pub fn disabled(&self) -> bool {
+        self.state.contains(&tags::disabled())
+    }
+    pub fn id(&self) -> Option<Tag> {
+        self.id
+    }
     pub fn invalidate_entity(entity: Entity) -> impl Command {
         move |world: &mut World| {
             if let Some(mut entity) = world.get_entity_mut(entity) {
@@ -130,9 +147,201 @@ impl Element {
     }
 }
 
+/// Formats the element the way the selector describes it:
+/// `button#save.primary:hover`. Classes and states are sorted.
+impl std::fmt::Display for Element {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for name in self.names.iter() {
+            write!(f, "{name}")?;
+        }
+        if let Some(id) = self.id {
+            write!(f, "#{id}")?;
+        }
+        let mut classes: Vec<_> = self.classes.iter().map(|c| c.as_str()).collect();
+        classes.sort();
+        for class in classes {
+            write!(f, ".{class}")?;
+        }
+        let mut states: Vec<_> = self.state.iter().map(|s| s.as_str()).collect();
+        states.sort();
+        for state in states {
+            write!(f, ":{state}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Disables the element and all its descendants: they get the
+/// `:disabled` state, don't receive the pointer input and can't be
+/// focused. Inserted by the `disabled` param:
+/// ```rust,ignore
+/// eml! { <div disabled=true><button>"Can't press me"</button></div> }
+/// ```
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Disabled {
+    pub value: bool,
+}
+
+/// Marks the element which content is still loading: the element gets
+/// the `:loading` state while the `value` is `true`. Inserted by the
+/// `loading` param, the widgets loading the content asynchronously (like
+/// the `<img>`) toggle it themselves, so the placeholders can follow:
+/// ```rust,ignore
+/// eml! { <div c:profile loading=true><skeleton/></div> }
+/// ```
+/// ```css
+/// .profile:not(:loading) skeleton { display: none; }
+/// ```
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Loading {
+    pub value: bool,
+}
+
+impl WidgetStates for Loading {
+    fn states(&self) -> Vec<(Tag, bool)> {
+        vec![(tags::loading(), self.value)]
+    }
+}
+
 #[derive(Resource, Deref, DerefMut, Default)]
 pub struct ElementIdIndex(HashMap<Tag, Entity>);
 
+/// The registry of the UI layers. Each layer has its own global z-index,
+/// so the floating elements (popups, tooltips, drag ghosts) are stacked
+/// deterministically. The builtin layers are `base`, `popup`, `tooltip`,
+/// `drag` and `debug` (from the bottom to the top), register your own
+/// layers with [`RegisterUiLayer::register_ui_layer`].
+#[derive(Resource, Debug, Clone)]
+pub struct UiLayers(HashMap<Tag, i32>);
+
+impl Default for UiLayers {
+    fn default() -> Self {
+        UiLayers(
+            [
+                (tag!("base"), 0),
+                (tag!("popup"), 1000),
+                (tag!("tooltip"), 2000),
+                (tag!("drag"), 3000),
+                (tag!("debug"), 4000),
+            ]
+            .into_iter()
+            .collect(),
+        )
+    }
+}
+
+impl UiLayers {
+    /// The global z-index of the `layer`.
+    pub fn z_index(&self, layer: impl Into<Tag>) -> Option<i32> {
+        self.0.get(&layer.into()).copied()
+    }
+
+    pub fn insert(&mut self, layer: impl Into<Tag>, z_index: i32) {
+        self.0.insert(layer.into(), z_index);
+    }
+}
+
+pub trait RegisterUiLayer {
+    fn register_ui_layer(&mut self, layer: impl Into<Tag>, z_index: i32) -> &mut Self;
+}
+
+impl RegisterUiLayer for App {
+    fn register_ui_layer(&mut self, layer: impl Into<Tag>, z_index: i32) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(UiLayers::default)
+            .insert(layer, z_index);
+        self
+    }
+}
+
+/// The UI layer the element is placed to. Inserted by the `layer` param:
+/// ```rust,ignore
+/// eml! { <div layer="tooltip">"Hint"</div> }
+/// ```
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct Layer(pub Tag);
+
+/// The full-screen container of the elements moved to the layer
+/// with [`Elements::move_to_layer`].
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct LayerRoot(pub Tag);
+
+pub fn apply_ui_layers(
+    layers: Res<UiLayers>,
+    mut elements: Query<(Ref<Layer>, Option<&mut ZIndex>, Entity)>,
+    mut commands: Commands,
+) {
+    for (layer, z_index, entity) in elements.iter_mut() {
+        if !layers.is_changed() && !layer.is_changed() {
+            continue;
+        }
+        let Some(z) = layers.z_index(layer.0) else {
+            warn!("Unknown UI layer '{}'", layer.0);
+            continue;
+        };
+        match z_index {
+            Some(mut z_index) => {
+                if !matches!(*z_index, ZIndex::Global(current) if current == z) {
+                    *z_index = ZIndex::Global(z);
+                }
+            }
+            None => {
+                commands.entity(entity).insert(ZIndex::Global(z));
+            }
+        }
+    }
+}
+
+pub struct MoveToLayerCommand {
+    pub entity: Entity,
+    pub layer: Tag,
+}
+
+impl Command for MoveToLayerCommand {
+    fn apply(self, world: &mut World) {
+        if world.get_entity(self.entity).is_none() {
+            return;
+        }
+        let Some(z) = world.resource::<UiLayers>().z_index(self.layer) else {
+            warn!("Unknown UI layer '{}'", self.layer);
+            return;
+        };
+        let root = world
+            .query::<(Entity, &LayerRoot)>()
+            .iter(world)
+            .find(|(_, root)| root.0 == self.layer)
+            .map(|(entity, _)| entity);
+        let root = root.unwrap_or_else(|| {
+            world
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            left: Val::Px(0.),
+                            top: Val::Px(0.),
+                            width: Val::Percent(100.),
+                            height: Val::Percent(100.),
+                            ..default()
+                        },
+                        z_index: ZIndex::Global(z),
+                        ..default()
+                    },
+                    Element::default(),
+                    LayerRoot(self.layer),
+                    Name::new(format!("layer:{}", self.layer)),
+                ))
+                .id()
+        });
+        world
+            .entity_mut(self.entity)
+            .set_parent(root)
+            .insert(Layer(self.layer));
+        InvalidateElementCommand(self.entity).apply(world);
+    }
+}
+
 #[derive(QueryData)]
 pub struct ElementsQuery {
     pub entity: Entity,
@@ -146,6 +355,14 @@ impl<'w, 's> Deref for Elements<'w, 's> {
     }
 }
 
+impl<'w> ElementsQueryItem<'w> {
+    /// Returns the element with the lifetime of the world, so it
+    /// may outlive the query item.
+    pub fn element(&self) -> &'w Element {
+        self.element
+    }
+}
+
 impl Deref for ElementsQueryItem<'_> {
     type Target = Element;
     fn deref(&self) -> &Self::Target {
@@ -172,6 +389,8 @@ pub struct Elements<'w, 's> {
     pub(crate) commands: ElementCommands<'w, 's>,
     pub(crate) elements: Query<'w, 's, ElementsQuery, ()>,
     pub(crate) children: Query<'w, 's, ChildrenQuery, ()>,
+    pub(crate) parents: Query<'w, 's, &'static Parent>,
+    pub(crate) nodes: Query<'w, 's, &'static Node>,
     pub(crate) id_index: Res<'w, ElementIdIndex>,
     states: Local<'s, HashMap<Entity, HashMap<Tag, bool>>>,
     classes: Local<'s, HashMap<Entity, HashSet<Tag>>>,
@@ -182,6 +401,87 @@ impl<'w, 's> Elements<'w, 's> {
         self.commands().add(InvalidateElementCommand(tree));
     }
 
+    /// Moves the `entity` out of its parent to the full-screen container
+    /// of the `layer`, so it is not clipped by the ancestors and is stacked
+    /// above the lower layers. Useful for the drag ghosts and the popups
+    /// spawned deep inside the scrolled content.
+    pub fn move_to_layer(&mut self, entity: Entity, layer: impl Into<Tag>) {
+        self.commands().add(MoveToLayerCommand {
+            entity,
+            layer: layer.into(),
+        });
+    }
+
+    /// Invalidates the elements matching any of the `selectors` with their
+    /// descendants, so only the affected part of the tree is restyled when
+    /// the stylesheet changes.
+    pub fn invalidate_matching(&mut self, selectors: &[Selector]) {
+        let mut matched = vec![];
+        let mut branch = vec![];
+        for root in self.roots.iter() {
+            self.match_branch(root, &mut branch, selectors, &mut matched);
+        }
+        for entity in matched {
+            self.invalidate(entity);
+        }
+    }
+
+    /// Re-resolves the styles of the elements matching any of the `selectors`
+    /// with their descendants without invalidating them: the `Element`s stay
+    /// unchanged, so the widgets keep their state. Used by the stylesheet
+    /// hot reload, returns the number of the matched elements.
+    pub fn restyle_matching(&mut self, selectors: &[Selector]) -> usize {
+        let mut matched = vec![];
+        let mut branch = vec![];
+        for root in self.roots.iter() {
+            self.match_branch(root, &mut branch, selectors, &mut matched);
+        }
+        let restyled = matched.len();
+        for entity in matched {
+            self.commands().add(RestyleElementCommand(entity));
+        }
+        restyled
+    }
+
+    fn match_branch(
+        &self,
+        entity: Entity,
+        element_ptrs: &mut Vec<*const Element>,
+        selectors: &[Selector],
+        result: &mut Vec<Entity>,
+    ) {
+        let Ok(elem) = self.elements.get(entity) else {
+            return;
+        };
+        let is_virtual = elem.is_virtual();
+        if !is_virtual {
+            element_ptrs.push(&*elem as *const Element);
+            let branch = if selectors.iter().any(|s| s.has_siblings()) {
+                self.sibling_branch(entity)
+            } else {
+                let mut branch = ElementsBranch::new();
+                for e in element_ptrs.iter() {
+                    branch.append(unsafe { e.as_ref().unwrap() })
+                }
+                branch
+            };
+            if selectors.iter().any(|s| s.matches(&branch)) {
+                // the whole subtree is invalidated with the element
+                result.push(entity);
+                element_ptrs.pop();
+                return;
+            }
+        }
+        if let Ok(children) = self.children.get(entity) {
+            for ch in children.children {
+                self.match_branch(*ch, element_ptrs, selectors, result);
+            }
+        }
+        if !is_virtual {
+            element_ptrs.pop();
+        }
+    }
+
     pub fn invalidate_all(&mut self) {
         self.roots
             .iter()
@@ -209,13 +509,58 @@ impl<'w, 's> Elements<'w, 's> {
     /// example and [Selector](https://github.com/jkb0o/belly#selectors)
     /// chapter in readme.
     pub fn select<'e>(&'e mut self, query: &str) -> SelectedElements<'w, 's, 'e> {
-        let selector: Selector = query.into();
+        let entities = self.matching(query);
+        SelectedElements {
+            elements: self,
+            entities,
+        }
+    }
+
+    /// Returns the element with the `id` param (`<span id="score"/>`).
+    /// The ids are indexed, so the lookup doesn't walk the tree:
+    /// ```rust
+    /// # use belly_core::prelude::*;
+    /// # use bevy::prelude::*;
+    /// fn system(elements: Elements, mut texts: Query<&mut Text>) {
+    ///   let Some(score) = elements.by_id("score") else {
+    ///     return;
+    ///   };
+    ///   if let Ok(mut text) = texts.get_mut(score) {
+    ///     text.sections[0].value = "42".into();
+    ///   }
+    /// }
+    /// ```
+    pub fn by_id(&self, id: impl Into<Tag>) -> Option<Entity> {
+        self.id_index.get(&id.into()).copied()
+    }
+
+    /// Returns the entities matching the `ess` query without
+    /// borrowing the `Elements` mutably, so the result can be
+    /// combined with the other queries of the system:
+    /// ```rust
+    /// # use belly_core::prelude::*;
+    /// # use bevy::prelude::*;
+    /// fn system(elements: Elements, mut styles: Query<&mut Style>) {
+    ///   for entity in elements.matching(".inventory button:enabled") {
+    ///     if let Ok(mut style) = styles.get_mut(entity) {
+    ///       style.width = Val::Px(64.);
+    ///     }
+    ///   }
+    /// }
+    /// ```
+    /// `:enabled` matches the elements without the `disabled` state.
+    /// The malformed query is reported and matches nothing.
+    pub fn matching(&self, query: &str) -> Vec<Entity> {
         let mut result = vec![];
+        let selector = match Selector::parse(query) {
+            Ok(selector) => selector,
+            Err(e) => {
+                error!("Can't select elements by `{query}`: {e}");
+                return result;
+            }
+        };
         if selector.is_empty() {
-            return SelectedElements {
-                elements: self,
-                entities: result,
-            };
+            return result;
         }
         let mut branch = vec![];
         if let Some(id) = selector.get_root_id() {
@@ -227,14 +572,112 @@ impl<'w, 's> Elements<'w, 's> {
             }
         } else {
             for root in self.roots.iter() {
-                // branch.append(&*entuty);
                 self.select_branch(root, &mut branch, &selector, &mut result);
             }
         }
-        SelectedElements {
-            elements: self,
-            entities: result,
+        result
+    }
+
+    /// Returns the entities matching the `ess` query along with the
+    /// path to each of them, handy for printing to the console:
+    /// ```rust
+    /// # use belly_core::prelude::*;
+    /// # use bevy::prelude::*;
+    /// fn system(elements: Elements) {
+    ///   for (entity, path) in elements.find_all(".inventory button") {
+    ///     info!("{entity:?}: {path}");
+    ///   }
+    /// }
+    /// ```
+    pub fn find_all(&self, query: &str) -> Vec<(Entity, String)> {
+        self.matching(query)
+            .into_iter()
+            .map(|entity| (entity, self.path(entity)))
+            .collect()
+    }
+
+    /// The element and its ancestors formatted like the selector:
+    /// `body div.inventory button:hover`
+    pub fn path(&self, entity: Entity) -> String {
+        let mut path = vec![];
+        let mut tail = entity;
+        loop {
+            if let Ok(element) = self.elements.get(tail) {
+                if !element.is_virtual() {
+                    path.push(element.to_string());
+                }
+            }
+            match self.parents.get(tail) {
+                Ok(parent) => tail = parent.get(),
+                Err(_) => break,
+            }
+        }
+        path.reverse();
+        path.join(" ")
+    }
+
+    /// Returns the indented dump of the `root` and its descendants with
+    /// their tags, ids, classes, states and node sizes. Virtual elements
+    /// are prefixed with `~`, the nodes without `Element` are shown as `-`.
+    /// Use it when the selector silently doesn't match:
+    /// ```rust
+    /// # use belly_core::prelude::*;
+    /// # use bevy::prelude::*;
+    /// fn system(elements: Elements, roots: Query<Entity, (With<Node>, Without<Parent>)>) {
+    ///   for root in roots.iter() {
+    ///     info!("\n{}", elements.debug_tree(root));
+    ///   }
+    /// }
+    /// ```
+    pub fn debug_tree(&self, root: Entity) -> String {
+        let mut result = String::new();
+        self.debug_branch(root, 0, &mut result);
+        result
+    }
+
+    fn debug_branch(&self, entity: Entity, depth: usize, result: &mut String) {
+        let indent = "  ".repeat(depth);
+        let description = match self.elements.get(entity) {
+            Ok(element) if element.is_virtual() => format!("~{}", *element),
+            Ok(element) => element.to_string(),
+            Err(_) => "-".to_string(),
+        };
+        result.push_str(&format!("{indent}{description} [{entity:?}]"));
+        if let Ok(node) = self.nodes.get(entity) {
+            let size = node.size();
+            result.push_str(&format!(" {}x{}", size.x, size.y));
+        }
+        result.push('\n');
+        if let Ok(children) = self.children.get(entity) {
+            for child in children.children.iter() {
+                self.debug_branch(*child, depth + 1, result);
+            }
+        }
+    }
+
+    /// The branch of the `entity` with the preceding siblings of each
+    /// element, required by the selectors with the `+` combinator.
+    /// The virtual elements are skipped like in the style matching.
+    fn sibling_branch(&self, entity: Entity) -> ElementsBranch {
+        let mut branch = ElementsBranch::new();
+        let mut tail = entity;
+        while let Ok(item) = self.elements.get(tail) {
+            let parent = self.parents.get(tail).map(|p| p.get()).ok();
+            if !item.is_virtual() {
+                let siblings = match parent.and_then(|p| self.children.get(p).ok()) {
+                    Some(siblings) => preceding_siblings(tail, siblings.children, |e| {
+                        self.elements.get(e).ok().map(|e| e.element)
+                    }),
+                    None => smallvec![],
+                };
+                branch.insert_with_siblings(item.element, siblings);
+            }
+            match parent {
+                Some(parent) => tail = parent,
+                None => break,
+            }
         }
+        branch
     }
 
     fn select_branch(
@@ -249,10 +692,15 @@ impl<'w, 's> Elements<'w, 's> {
         };
         let elem = &*elem as *const Element;
         element_ptrs.push(elem);
-        let mut branch = ElementsBranch::new();
-        for e in element_ptrs.iter() {
-            branch.append(unsafe { e.as_ref().unwrap() })
-        }
+        let branch = if selector.has_siblings() {
+            self.sibling_branch(entity)
+        } else {
+            let mut branch = ElementsBranch::new();
+            for e in element_ptrs.iter() {
+                branch.append(unsafe { e.as_ref().unwrap() })
+            }
+            branch
+        };
         if selector.matches(&branch) {
             result.push(entity);
         }
@@ -368,6 +816,18 @@ impl<'w, 's, 'e> SelectedElements<'w, 's, 'e> {
     pub fn entities(self) -> Vec<Entity> {
         self.entities
     }
+
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.entities.iter().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
     pub fn add_class<T: Into<Tag>>(&mut self, class: T) -> &mut Self {
         let class = class.into();
         for entity in self.entities.iter() {
@@ -472,7 +932,7 @@ unsafe impl<'w, 's> SystemParam for ElementCommands<'w, 's> {
     }
 }
 
-pub struct InvalidateElementCommand(Entity);
+pub struct InvalidateElementCommand(pub(crate) Entity);
 impl Command for InvalidateElementCommand {
     fn apply(self, world: &mut World) {
         if let Some(mut entity) = world.get_entity_mut(self.0) {
@@ -536,7 +996,7 @@ impl Command for RemoveClassCommand {
     }
 }
 
-pub struct CleanupElementCommand(Entity);
+pub struct CleanupElementCommand(pub(crate) Entity);
 impl Command for CleanupElementCommand {
     fn apply(self, world: &mut World) {
         if let Some(mut entity) = world.get_entity_mut(self.0) {
@@ -547,20 +1007,92 @@ impl Command for CleanupElementCommand {
 
 #[derive(Component, Default)]
 pub struct InvalidateElement;
+
+/// Marks the element restyled without the `Element` change, the style
+/// properties are applied to it the same way they are applied to the
+/// invalidated elements. Removed after the properties are applied.
+#[derive(Component, Default)]
+pub struct Restyle;
+
+pub struct RestyleElementCommand(pub(crate) Entity);
+impl Command for RestyleElementCommand {
+    fn apply(self, world: &mut World) {
+        let mut tail = vec![self.0];
+        while let Some(entity) = tail.pop() {
+            let Some(mut entity) = world.get_entity_mut(entity) else {
+                continue;
+            };
+            if entity.contains::<Element>() {
+                entity.insert(Restyle);
+            }
+            if let Some(children) = entity.get::<Children>() {
+                tail.extend(children.iter().copied());
+            }
+        }
+    }
+}
+
+pub(crate) fn clear_restyle_system(mut commands: Commands, restyled: Query<Entity, With<Restyle>>) {
+    for entity in restyled.iter() {
+        commands.entity(entity).remove::<Restyle>();
+    }
+}
+/// Invalidates the marked elements with their descendants. When any of the
+/// rules uses the `+` combinator, the following siblings of the marked
+/// elements and the children following the inserted or removed ones are
+/// invalidated too.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn invalidate_elements(
     invalid: Query<Entity, With<InvalidateElement>>,
+    reordered: Query<(Entity, &Children), (Changed<Children>, With<Element>)>,
+    mut childless: RemovedComponents<Children>,
     children: Query<&Children>,
+    parents: Query<&Parent>,
     mut elements: Query<&mut Element>,
+    rules: Option<Res<SortedRules>>,
     mut invalidated: Local<HashSet<Entity>>,
+    mut known_children: Local<HashMap<Entity, Vec<Entity>>>,
     mut commands: Commands,
 ) {
     invalidated.clear();
+    for parent in childless.read() {
+        known_children.remove(&parent);
+    }
+    let siblings = rules.map(|rules| rules.has_siblings()).unwrap_or(false);
     for entity in invalid.iter() {
         invalidate_children(entity, &children, &mut elements, invalidated.deref_mut());
+        if siblings {
+            let parent = parents.get(entity).map(|p| p.get());
+            let following = parent
+                .and_then(|p| children.get(p))
+                .map(|ch| ch.iter().skip_while(|ch| **ch != entity).skip(1));
+            for sibling in following.into_iter().flatten() {
+                invalidate_children(*sibling, &children, &mut elements, invalidated.deref_mut());
+            }
+        }
         if let Some(mut entity) = commands.get_entity(entity) {
             entity.remove::<InvalidateElement>();
         }
     }
+    if !siblings {
+        known_children.clear();
+        return;
+    }
+    // the children before the first inserted or removed one keep their
+    // preceding siblings, the rest are restyled
+    for (parent, current) in reordered.iter() {
+        let known = known_children.entry(parent).or_default();
+        let kept = known
+            .iter()
+            .zip(current.iter())
+            .take_while(|(known, current)| known == current)
+            .count();
+        for child in current.iter().skip(kept) {
+            invalidate_children(*child, &children, &mut elements, invalidated.deref_mut());
+        }
+        known.clear();
+        known.extend(current.iter().copied());
+    }
 }
 
 pub fn invalidate_children(
@@ -582,3 +1114,205 @@ pub fn invalidate_children(
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use smallvec::smallvec;
+
+    #[test]
+    fn select_enabled_elements() {
+        let mut world = World::new();
+        world.init_resource::<ElementIdIndex>();
+        let button = |world: &mut World, disabled: bool| {
+            let mut element = Element {
+                names: smallvec![tag!("button")],
+                ..default()
+            };
+            if disabled {
+                element.state.insert(tags::disabled());
+            }
+            world.spawn(element).id()
+        };
+        let enabled = button(&mut world, false);
+        let disabled = button(&mut world, true);
+        let outside = button(&mut world, false);
+        let mut inventory = Element {
+            names: smallvec![tag!("div")],
+            ..default()
+        };
+        inventory.classes.insert(tag!("inventory"));
+        world
+            .spawn(inventory)
+            .push_children(&[enabled, disabled]);
+
+        let selected = world.run_system_once(|elements: Elements| {
+            elements.matching(".inventory button:enabled")
+        });
+        assert_eq!(selected, vec![enabled]);
+        let selected = world.run_system_once(|elements: Elements| elements.matching("button"));
+        assert_eq!(selected.len(), 3);
+        assert!(selected.contains(&outside));
+    }
+
+    #[test]
+    fn select_sibling_elements() {
+        let mut world = World::new();
+        world.init_resource::<ElementIdIndex>();
+        let element = |world: &mut World, name: &str| {
+            let element = Element {
+                names: smallvec![name.as_tag()],
+                ..default()
+            };
+            world.spawn(element).id()
+        };
+        let label = element(&mut world, "span");
+        let first = element(&mut world, "button");
+        let second = element(&mut world, "button");
+        let mut menu = Element {
+            names: smallvec![tag!("div")],
+            ..default()
+        };
+        menu.classes.insert(tag!("menu"));
+        world.spawn(menu).push_children(&[label, first, second]);
+
+        let selected =
+            world.run_system_once(|elements: Elements| elements.matching("span + button"));
+        assert_eq!(selected, vec![first]);
+        let selected = world.run_system_once(|elements: Elements| {
+            elements.matching(".menu > button + button")
+        });
+        assert_eq!(selected, vec![second]);
+        let selected = world.run_system_once(|elements: Elements| {
+            elements.matching(".menu > :not(span)")
+        });
+        assert_eq!(selected, vec![first, second]);
+    }
+
+    #[test]
+    fn malformed_query_selects_nothing() {
+        let mut world = World::new();
+        world.init_resource::<ElementIdIndex>();
+        world.spawn(Element::default());
+        let found = world.run_system_once(|mut elements: Elements| {
+            (
+                elements.matching("button:not()"),
+                elements.find_all("slider::part(1)"),
+                elements.select("[=vertical]").entities().len(),
+            )
+        });
+        assert_eq!(found, (vec![], vec![], 0));
+    }
+
+    #[test]
+    fn lookup_element_by_id() {
+        let mut world = World::new();
+        world.init_resource::<ElementIdIndex>();
+        let score = world.spawn(Element::default()).id();
+        world
+            .resource_mut::<ElementIdIndex>()
+            .insert(tag!("score"), score);
+        let found = world.run_system_once(|elements: Elements| {
+            (elements.by_id("score"), elements.by_id("missing"))
+        });
+        assert_eq!(found, (Some(score), None));
+    }
+
+    #[test]
+    fn layers_set_z_index() {
+        let mut world = World::new();
+        world.init_resource::<ElementIdIndex>();
+        world.init_resource::<UiLayers>();
+        let tooltip = world
+            .spawn((Element::default(), Layer(tag!("tooltip"))))
+            .id();
+        world.run_system_once(apply_ui_layers);
+        assert!(matches!(
+            world.get::<ZIndex>(tooltip),
+            Some(ZIndex::Global(2000))
+        ));
+
+        let scroll = world.spawn(Element::default()).id();
+        let ghost = world.spawn(Element::default()).set_parent(scroll).id();
+        MoveToLayerCommand {
+            entity: ghost,
+            layer: tag!("drag"),
+        }
+        .apply(&mut world);
+        let root = world.get::<Parent>(ghost).unwrap().get();
+        assert_eq!(world.get::<LayerRoot>(root), Some(&LayerRoot(tag!("drag"))));
+        assert!(matches!(world.get::<ZIndex>(root), Some(ZIndex::Global(3000))));
+        assert_eq!(world.get::<Layer>(ghost), Some(&Layer(tag!("drag"))));
+    }
+
+    #[test]
+    fn dump_elements_tree() {
+        let mut world = World::new();
+        world.init_resource::<ElementIdIndex>();
+        let mut body = Element {
+            names: smallvec![tag!("body")],
+            id: Some(tag!("root")),
+            ..default()
+        };
+        body.classes.insert(tag!("dark"));
+        let mut button = Element {
+            names: smallvec![tag!("button")],
+            ..default()
+        };
+        button.state.insert(tag!("hover"));
+        let button = world.spawn(button).id();
+        let label = world.spawn(Element::default()).id();
+        let body = world.spawn(body).push_children(&[button, label]).id();
+
+        let tree = world.run_system_once(move |elements: Elements| elements.debug_tree(body));
+        let expected = format!(
+            "body#root.dark [{body:?}]\n  button:hover [{button:?}]\n  ~ [{label:?}]\n"
+        );
+        assert_eq!(tree, expected);
+
+        let found = world.run_system_once(|elements: Elements| elements.find_all(".dark button"));
+        assert_eq!(found, vec![(button, "body#root.dark button:hover".to_string())]);
+    }
+
+    #[test]
+    fn invalidate_matching_branches_only() {
+        let mut world = World::new();
+        world.init_resource::<ElementIdIndex>();
+        let element = |name: &str, class: &str| {
+            let mut element = Element {
+                names: smallvec![Tag::new(name)],
+                ..default()
+            };
+            element.classes.insert(Tag::new(class));
+            element
+        };
+        let label = world.spawn(Element::default()).id();
+        let primary = world.spawn(element("button", "primary")).add_child(label).id();
+        let other = world.spawn(element("button", "secondary")).id();
+        let body = world
+            .spawn(element("body", "dark"))
+            .push_children(&[primary, other])
+            .id();
+
+        world.run_system_once(|mut elements: Elements| {
+            elements.invalidate_matching(&[Selector::from(".dark .primary")])
+        });
+        assert!(world.get::<InvalidateElement>(primary).is_some());
+        assert!(world.get::<InvalidateElement>(other).is_none());
+        assert!(world.get::<InvalidateElement>(body).is_none());
+
+        world.run_system_once(invalidate_elements);
+        let mut changed = world.query_filtered::<Entity, Changed<Element>>();
+        world.clear_trackers();
+        world.run_system_once(|mut elements: Elements| {
+            elements.invalidate_matching(&[Selector::from("button")])
+        });
+        world.run_system_once(invalidate_elements);
+        let mut invalidated: Vec<_> = changed.iter(&world).collect();
+        invalidated.sort();
+        let mut expected = vec![primary, other, label];
+        expected.sort();
+        assert_eq!(invalidated, expected);
+    }
+}
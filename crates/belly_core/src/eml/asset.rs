@@ -1,12 +1,18 @@
 use crate::element::Element;
 use crate::eml::WidgetData;
-use crate::eml::{parse, Param, Slots};
+use crate::eml::{parse, Param, SourceLocation, StrictMode};
 use crate::ess::{PropertyExtractor, PropertyTransformer};
+use crate::relations::reflect::{PathBind, PathEntity, PathSource, PathTarget};
+use crate::scene::ElementBinds;
 use bevy::asset::io::Reader;
 use bevy::asset::AsyncReadExt;
 use bevy::reflect::TypePath;
 use bevy::utils::BoxedFuture;
-use bevy::{asset::AssetLoader, prelude::*, utils::HashMap};
+use bevy::{
+    asset::AssetLoader,
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
 use std::sync::Arc;
 use tagstr::*;
 use thiserror::Error;
@@ -20,11 +26,38 @@ pub enum EmlNode {
     Slot(Tag, Vec<EmlNode>),
 }
 
+impl EmlNode {
+    /// The classes set with the `class` param by the node and its descendants.
+    pub fn classes(&self) -> HashSet<Tag> {
+        let mut classes = HashSet::default();
+        self.collect_classes(&mut classes);
+        classes
+    }
+
+    fn collect_classes(&self, classes: &mut HashSet<Tag>) {
+        match self {
+            EmlNode::Element(element) => {
+                if let Some(class) = element.params.get("class") {
+                    classes.extend(class.split_whitespace().map(Tag::new));
+                }
+                element
+                    .children
+                    .iter()
+                    .for_each(|c| c.collect_classes(classes));
+            }
+            EmlNode::Slot(_, children) => children.iter().for_each(|c| c.collect_classes(classes)),
+            EmlNode::Text(_) => {}
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct EmlElement {
     pub(crate) name: Tag,
     pub(crate) params: HashMap<String, String>,
     pub(crate) children: Vec<EmlNode>,
+    /// The line and column of the tag in the source
+    pub(crate) position: Option<(u32, u32)>,
 }
 
 impl EmlElement {
@@ -47,17 +80,18 @@ impl EmlScene {
 #[derive(Clone, TypePath, Asset)]
 // #[uuid = "f8d22a65-d671-4fa6-ae8f-0dccdb387ddd"]
 pub struct EmlAsset {
-    root: Arc<EmlNode>,
+    pub(crate) root: Arc<EmlNode>,
+    pub(crate) path: String,
 }
 
 impl EmlAsset {
     pub fn write(&self, world: &mut World, parent: Entity) {
         // let node = E
-        walk(&self.root, world, Some(parent));
+        walk(&self.root, &self.path, world, Some(parent));
     }
 }
 
-fn walk(node: &EmlNode, world: &mut World, parent: Option<Entity>) -> Option<Entity> {
+fn walk(node: &EmlNode, path: &str, world: &mut World, parent: Option<Entity>) -> Option<Entity> {
     match node {
         EmlNode::Text(text) => {
             let entity = world
@@ -69,32 +103,54 @@ fn walk(node: &EmlNode, world: &mut World, parent: Option<Entity>) -> Option<Ent
                 .id();
             Some(entity)
         }
-        EmlNode::Slot(name, elements) => {
-            let slots = world.resource::<Slots>().clone();
-            let entities: Vec<Entity> = elements
-                .iter()
-                .filter_map(|e| walk(e, world, None))
-                .collect();
-            slots.insert(*name, entities);
+        EmlNode::Slot(name, _) => {
+            warn!("Ignoring slot '{}' defined outside of the widget", name);
             None
         }
         EmlNode::Element(elem) => {
+            let location = elem
+                .position
+                .map(|(line, column)| SourceLocation::new(path.to_string(), line, column));
             let Some(builder) = world.resource::<WidgetRegistry>().get(elem.name) else {
-                error!("Invalid tag name: {}", elem.name.as_str());
+                StrictMode::unknown_tag(world, elem.name, location.as_ref());
                 return None;
             };
             let entity = parent.unwrap_or_else(|| world.spawn_empty().id());
             let mut data = WidgetData::new(entity);
+            data.location = location;
+            let mut binds = vec![];
+            let mut bind_sources = vec![];
             for (name, value) in elem.params.iter() {
+                // bind:Component.field="#source Component.field|fmt.name"
+                if let Some(target) = name.strip_prefix("bind:") {
+                    binds.push(PathBind::new(
+                        PathSource::parse(value, entity),
+                        PathTarget::new(PathEntity::Entity(entity), target),
+                    ));
+                    bind_sources.push((target.to_string(), value.clone()));
+                    continue;
+                }
                 let attr = Param::new(name, value.clone().into());
                 data.params.add(attr);
             }
             for child in elem.children.iter() {
-                if let Some(entity) = walk(child, world, None) {
+                if let EmlNode::Slot(name, elements) = child {
+                    let entities = elements
+                        .iter()
+                        .filter_map(|e| walk(e, path, world, None))
+                        .collect();
+                    data.slots.insert(*name, entities);
+                } else if let Some(entity) = walk(child, path, world, None) {
                     data.children.push(entity);
                 }
             }
             builder.build(world, data);
+            for bind in binds {
+                bind.write(world);
+            }
+            if !bind_sources.is_empty() {
+                world.entity_mut(entity).insert(ElementBinds(bind_sources));
+            }
             Some(entity)
         }
     }
@@ -139,6 +195,7 @@ impl AssetLoader for EmlLoader {
                 Ok(root) => {
                     let asset = EmlAsset {
                         root: Arc::new(root),
+                        path: load_context.path().to_string_lossy().to_string(),
                     };
                     // load_context.add_labeled_asset("default".to_string(), LoadedAsset::from(asset));
                     Ok(asset)
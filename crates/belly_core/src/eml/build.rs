@@ -1,9 +1,11 @@
-use super::{Params, StyleParams, Variant};
+use super::{Params, SourceLocation, StrictMode, StyleParams, Variant};
 use crate::{
-    element::{Element, ElementIdIndex},
-    ess::{PropertyExtractor, PropertyTransformer, StyleRule, StyleSheetParser},
+    element::{Disabled, Element, ElementIdIndex, Layer, Loading},
+    ess::{part_class, PropertyExtractor, PropertyTransformer, StyleRule, StyleSheetParser},
     relations::connect::{EventFilter, EventSource},
+    scene::style_source,
     tags,
+    time::Lifetime,
 };
 use bevy::{
     ecs::system::{Command, CommandQueue, EntityCommands},
@@ -13,6 +15,7 @@ use bevy::{
 };
 use itertools::Itertools;
 use std::{
+    any::{type_name, Any},
     mem,
     sync::{Arc, RwLock},
 };
@@ -66,6 +69,7 @@ impl RegisterWidget for App {
         let name = Widget::name(widget);
 
         registry.write().unwrap().insert(name, widget.as_builder());
+        Widget::register_style_props(widget, self);
         self
     }
 }
@@ -79,6 +83,10 @@ pub struct WidgetData {
     pub children: Vec<Entity>,
     /// Attributes defined within the tag
     pub params: Params,
+    /// Slots content provided within the tag
+    pub slots: Slots,
+    /// Where the tag is declared, reported by [`StrictMode`]
+    pub location: Option<SourceLocation>,
 }
 
 impl WidgetData {
@@ -87,6 +95,8 @@ impl WidgetData {
             entity,
             children: vec![],
             params: Params::default(),
+            slots: Slots::default(),
+            location: None,
         }
     }
 }
@@ -98,6 +108,7 @@ pub struct WidgetContext<'w, 's> {
     asset_server: AssetServer,
     extractor: PropertyExtractor,
     transformer: PropertyTransformer,
+    state: Option<Box<dyn WidgetState>>,
 }
 
 impl<'w, 's> WidgetContext<'w, 's> {
@@ -132,8 +143,14 @@ impl<'w, 's> WidgetContext<'w, 's> {
         commands
     }
 
+    /// Renders the `elements` into the widget entity. The slots provided
+    /// to the widget are available only to the `<slot define="...">` tags
+    /// of the rendered `elements`, the unused ones are despawned.
     pub fn render(&mut self, elements: Eml) {
-        self.commands.add(elements.render_to(self.data.entity));
+        let slots = mem::take(&mut self.data.slots);
+        let entity = self.data.entity;
+        self.commands
+            .add(move |world: &mut World| render_with_slots(world, entity, elements, slots));
     }
 
     pub fn entity(&self) -> Entity {
@@ -169,6 +186,23 @@ impl<'w, 's> WidgetContext<'w, 's> {
         mem::take(&mut self.data.params)
     }
 
+    /// Returns the local state of the widget declared with
+    /// `#[widget(state = S)]`. The state is inserted into the widget
+    /// entity after the build, so it may be queried and bound later.
+    ///
+    /// Panics if the widget doesn't declare the state of type `S`.
+    pub fn state<S: Component>(&mut self) -> &mut S {
+        self.state
+            .as_mut()
+            .and_then(|state| state.as_any_mut().downcast_mut::<S>())
+            .unwrap_or_else(|| {
+                panic!(
+                    "The widget has no `{}` state, declare it with #[widget(state = ...)]",
+                    type_name::<S>()
+                )
+            })
+    }
+
     pub fn id(&mut self) -> Option<Tag> {
         self.data.params.id()
     }
@@ -185,6 +219,9 @@ impl<'w, 's> WidgetContext<'w, 's> {
         if let Some(attr_commands) = self.data.params.commands(tags::with()) {
             attr_commands(&mut self.commands.entity(self.entity()));
         }
+        if let Some(state_commands) = self.data.params.commands(tags::state_visibility()) {
+            state_commands(&mut self.commands.entity(self.entity()));
+        }
     }
 
     pub fn update_element<F: FnOnce(&mut Element) + Send + Sync + 'static>(&mut self, update: F) {
@@ -288,6 +325,56 @@ macro_rules! impl_components {
 }
 impl_components! { A, B, C, D, E, F, G, H, }
 
+/// Fails the compilation of the `eml!` element missing any of the
+/// `required` params, generated by `#[widget]` as `(name, error)` pairs.
+#[doc(hidden)]
+pub const fn check_required_params(required: &[(&str, &str)], provided: &[&str]) {
+    let mut idx = 0;
+    while idx < required.len() {
+        let (name, error) = required[idx];
+        let mut found = false;
+        let mut provided_idx = 0;
+        while provided_idx < provided.len() && !found {
+            found = str_eq(name, provided[provided_idx]);
+            provided_idx += 1;
+        }
+        if !found {
+            panic!("{}", error);
+        }
+        idx += 1;
+    }
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut idx = 0;
+    while idx < a.len() {
+        if a[idx] != b[idx] {
+            return false;
+        }
+        idx += 1;
+    }
+    true
+}
+
+/// The local state of the widget, see [`WidgetContext::state`]
+pub trait WidgetState: Send + Sync + 'static {
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn insert(self: Box<Self>, commands: &mut EntityCommands);
+}
+
+impl<T: Component> WidgetState for T {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+    fn insert(self: Box<Self>, commands: &mut EntityCommands) {
+        commands.insert(*self);
+    }
+}
+
 /// Only the way I know to get the ref to the unit struct
 pub trait Singleton: 'static {
     fn instance() -> &'static Self;
@@ -361,6 +448,16 @@ pub trait Widget {
         components: Self::Components,
     ) -> (Self::BuildComponents, Self::OtherComponents);
 
+    /// Create the local state of the widget declared with
+    /// `#[widget(state = MyState)]`. This method is generated by `#[widget]` macro.
+    fn instantiate_state(
+        &self,
+        _world: &mut World,
+        _params: &mut Params,
+    ) -> Option<Box<dyn WidgetState>> {
+        None
+    }
+
     // TODO: implement attribute-based binds
     // fn bind_components(&self);
 
@@ -380,7 +477,9 @@ pub trait Widget {
     }
 
     fn build(&self, world: &mut World, mut data: WidgetData) {
+        let attributes = data.params.attributes();
         let components = self.instantiate_components(world, &mut data.params);
+        let state = self.instantiate_state(world, &mut data.params);
         let mut queue = CommandQueue::default();
         let commands = Commands::new(&mut queue, world);
         let asset_server = world.resource::<AssetServer>().clone();
@@ -392,14 +491,22 @@ pub trait Widget {
             asset_server,
             transformer,
             extractor,
+            state,
         };
 
         // TODO: implement attribute-based binds
         // self.bind_components(&mut ctx, &components);
         let (mut build_components, other_components) = self.split_components(components);
         self.build_widget(&mut ctx, &mut build_components);
+        let unused = mem::take(&mut ctx.data.slots);
+        if !unused.is_empty() {
+            ctx.commands.add(move |world: &mut World| unused.despawn(world));
+        }
         build_components.write(ctx.this());
         other_components.write(ctx.this());
+        if let Some(state) = ctx.state.take() {
+            state.insert(&mut ctx.this());
+        }
 
         // post process
         ctx.apply_commands();
@@ -413,6 +520,36 @@ pub trait Widget {
             ctx.insert(policy);
             ctx.insert(Interaction::default());
         }
+        match ctx.param(tag!("disabled")).map(bool::try_from) {
+            Some(Ok(value)) => {
+                ctx.insert(Disabled { value });
+            }
+            Some(Err(e)) => error!("Ignoring disabled param: {}", e),
+            None => {}
+        }
+        match ctx.param(tag!("loading")).map(bool::try_from) {
+            Some(Ok(value)) => {
+                ctx.insert(Loading { value });
+            }
+            Some(Err(e)) => error!("Ignoring loading param: {}", e),
+            None => {}
+        }
+        if let Some(layer) = ctx.param(tag!("layer")) {
+            match String::try_from(layer) {
+                Ok(layer) => {
+                    ctx.insert(Layer(Tag::new(layer)));
+                }
+                Err(e) => error!("Ignoring layer param: {}", e),
+            }
+        }
+        if let Some(lifetime) = ctx.param(tag!("lifetime")) {
+            match Lifetime::try_from(lifetime) {
+                Ok(lifetime) => {
+                    ctx.insert(lifetime);
+                }
+                Err(e) => error!("Ignoring lifetime param: {}", e),
+            }
+        }
         ctx.insert(Name::new(self.name().as_str()));
         let names = vec![self.name()].into();
         let aliases = if let Some(alias) = self.alias() {
@@ -421,8 +558,16 @@ pub trait Widget {
             vec![].into()
         };
         let id = ctx.id();
-        let classes = ctx.classes();
-        let styles = ctx.styles().transform(|tag, variant| {
+        let mut classes = ctx.classes();
+        if let Some(part) = ctx.param(tag!("part")).and_then(|p| String::try_from(p).ok()) {
+            classes.insert(part_class(&part));
+        }
+        let styles = ctx.styles();
+        let style_sources: HashMap<Tag, String> = styles
+            .iter()
+            .filter_map(|(tag, variant)| Some((*tag, style_source(variant)?)))
+            .collect();
+        let styles = styles.transform(|tag, variant| {
             if ctx.extractor.is_compound_property(tag) {
                 match ctx.extractor.extract(tag, variant) {
                     Ok(mut props) => props.drain().collect(),
@@ -441,6 +586,9 @@ pub trait Widget {
                 }
             }
         });
+        let mut unknown: Vec<Tag> = ctx.data.params.rest.keys().copied().collect();
+        unknown.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        let location = ctx.data.location.take();
         let entity = ctx.entity();
         ctx.commands.add(move |world: &mut World| {
             world
@@ -453,13 +601,24 @@ pub trait Widget {
             element.id = id;
             element.classes.extend(classes);
             element.styles.extend(styles);
+            element.style_sources.extend(style_sources);
+            element.attributes = attributes;
         });
 
-        queue.apply(world)
+        queue.apply(world);
+        StrictMode::unknown_params(world, self.name(), &unknown, location.as_ref());
     }
     fn default_styles(&self) -> &str {
         ""
     }
+    /// The names of the internal parts declared with `#[part(name)]`,
+    /// they may be styled with the `widget::name` selectors
+    fn parts(&self) -> &'static [&'static str] {
+        &[]
+    }
+    /// Registers the style properties declared with `#[style_prop(name: Type)]`,
+    /// called by [`RegisterWidget::register_widget`]
+    fn register_style_props(&self, _app: &mut App) {}
     fn as_builder(&'static self) -> WidgetBuilder
     where
         Self: Sized + Sync + Send + 'static,
@@ -480,6 +639,9 @@ impl WidgetBuilder {
     pub fn default_styles(&self) -> &str {
         self.0.default_styles()
     }
+    pub fn parts(&self) -> &'static [&'static str] {
+        self.0.parts()
+    }
 }
 
 pub trait WidgetUntyped: Send + Sync {
@@ -489,6 +651,8 @@ pub trait WidgetUntyped: Send + Sync {
     fn build(&self, world: &mut World, data: WidgetData);
 
     fn default_styles(&self) -> &str;
+
+    fn parts(&self) -> &'static [&'static str];
 }
 
 impl<T: Widget + Send + Sync> WidgetUntyped for T {
@@ -501,6 +665,9 @@ impl<T: Widget + Send + Sync> WidgetUntyped for T {
     fn default_styles(&self) -> &str {
         self.default_styles()
     }
+    fn parts(&self) -> &'static [&'static str] {
+        self.parts()
+    }
 }
 
 pub struct DefaultWidget;
@@ -568,19 +735,85 @@ impl Command for Eml {
     }
 }
 
-#[derive(Resource, Default, Clone)]
-pub struct Slots(Arc<RwLock<HashMap<Tag, Vec<Entity>>>>);
+/// The content of the `<slot name>` tags provided to the widget. The slots
+/// are scoped to the single widget render: while the widget content is
+/// rendered, its slots are available as resource to the `<slot define>` tags.
+#[derive(Resource, Default, Clone, Debug)]
+pub struct Slots(HashMap<Tag, Vec<Entity>>);
 
 impl Slots {
-    pub fn insert(&self, tag: Tag, entities: Vec<Entity>) {
-        self.0.write().unwrap().insert(tag, entities);
+    pub fn insert(&mut self, tag: Tag, entities: Vec<Entity>) {
+        self.0.insert(tag, entities);
     }
 
-    pub fn remove(&self, tag: Tag) -> Option<Vec<Entity>> {
-        self.0.write().unwrap().remove(&tag)
+    pub fn remove(&mut self, tag: Tag) -> Option<Vec<Entity>> {
+        self.0.remove(&tag)
     }
 
     pub fn keys(&self) -> HashSet<Tag> {
-        self.0.read().unwrap().keys().copied().collect()
+        self.0.keys().copied().collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn despawn(self, world: &mut World) {
+        for (slot, entities) in self.0 {
+            warn!("Detected unused slot '{}', despawning it content.", slot);
+            for entity in entities {
+                if let Some(entity) = world.get_entity_mut(entity) {
+                    entity.despawn_recursive();
+                }
+            }
+        }
+    }
+}
+
+fn render_with_slots(world: &mut World, entity: Entity, elements: Eml, slots: Slots) {
+    let outer = world.remove_resource::<Slots>();
+    world.insert_resource(slots);
+    elements.render_to(entity)(world);
+    let unused = world.remove_resource::<Slots>().unwrap_or_default();
+    if let Some(outer) = outer {
+        world.insert_resource(outer);
+    }
+    unused.despawn(world);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn slots_scoped_to_render() {
+        let mut world = World::new();
+        let outer_content = world.spawn_empty().id();
+        let mut outer = Slots::default();
+        outer.insert(tag!("header"), vec![outer_content]);
+        world.insert_resource(outer);
+
+        let used = world.spawn_empty().id();
+        let unused = world.spawn_empty().id();
+        let mut slots = Slots::default();
+        slots.insert(tag!("separator"), vec![used]);
+        slots.insert(tag!("footer"), vec![unused]);
+        let widget = world.spawn_empty().id();
+        let eml = Eml::new(|world, parent| {
+            let mut slots = world.resource_mut::<Slots>();
+            assert_eq!(slots.remove(tag!("header")), None);
+            let content = slots.remove(tag!("separator")).unwrap();
+            let parent = parent.unwrap();
+            world.entity_mut(parent).push_children(&content);
+            parent
+        });
+        render_with_slots(&mut world, widget, eml, slots);
+
+        assert_eq!(world.get::<Parent>(used).map(|p| p.get()), Some(widget));
+        assert!(world.get_entity(unused).is_none());
+        assert_eq!(
+            world.resource::<Slots>().keys(),
+            [tag!("header")].into_iter().collect()
+        );
     }
 }
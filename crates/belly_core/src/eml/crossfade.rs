@@ -0,0 +1,206 @@
+use super::Eml;
+use crate::{
+    element::Elements,
+    ess::impls::opacity::{Opacity, PointerEvents},
+    time::ElementsTime,
+};
+use bevy::{ecs::system::Command, prelude::*, ui::FocusPolicy};
+use std::time::Duration;
+
+impl<'w, 's> Elements<'w, 's> {
+    /// Replaces the children of the `entity` with the `eml` content,
+    /// fading the old content out and the new one in during the `duration`
+    /// of the element clock. The old content is overlaid above the new one
+    /// and the pointer input to both of them is blocked until the
+    /// transition ends:
+    /// ```rust,ignore
+    /// ctx.swap_children_crossfade(page, eml! { <settings/> }, Duration::from_millis(250));
+    /// ```
+    pub fn swap_children_crossfade(&mut self, entity: Entity, eml: Eml, duration: Duration) {
+        self.commands().add(CrossfadeChildrenCommand {
+            entity,
+            eml,
+            duration,
+        });
+    }
+}
+
+/// The cross-fade in progress, inserted to the element
+/// which children are swapped.
+#[derive(Component, Debug)]
+pub struct Crossfade {
+    from: Entity,
+    to: Entity,
+    blocker: Entity,
+    duration: f32,
+    elapsed: f32,
+}
+
+impl Crossfade {
+    pub fn progress(&self) -> f32 {
+        if self.duration <= 0. {
+            1.
+        } else {
+            (self.elapsed / self.duration).clamp(0., 1.)
+        }
+    }
+
+    fn finish(&self, world: &mut World) {
+        for entity in [self.from, self.blocker] {
+            if let Some(entity) = world.get_entity_mut(entity) {
+                entity.despawn_recursive();
+            }
+        }
+        if let Some(mut opacity) = world.get_mut::<Opacity>(self.to) {
+            opacity.value = 1.;
+        }
+    }
+}
+
+pub struct CrossfadeChildrenCommand {
+    pub entity: Entity,
+    pub eml: Eml,
+    pub duration: Duration,
+}
+
+impl Command for CrossfadeChildrenCommand {
+    fn apply(self, world: &mut World) {
+        let Some(mut target) = world.get_entity_mut(self.entity) else {
+            return;
+        };
+        if let Some(running) = target.take::<Crossfade>() {
+            running.finish(world);
+        }
+        let old_children = world
+            .get::<Children>(self.entity)
+            .map(|c| c.to_vec())
+            .unwrap_or_default();
+
+        // the old content keeps the layout of the parent
+        // while stacked above the new one
+        let mut style = world.get::<Style>(self.entity).cloned().unwrap_or_default();
+        style.position_type = PositionType::Absolute;
+        style.left = Val::Px(0.);
+        style.top = Val::Px(0.);
+        style.width = Val::Percent(100.);
+        style.height = Val::Percent(100.);
+        style.margin = UiRect::all(Val::Px(0.));
+        style.border = UiRect::all(Val::Px(0.));
+        let from = world
+            .spawn((
+                NodeBundle {
+                    style,
+                    background_color: BackgroundColor(Color::NONE),
+                    ..default()
+                },
+                Opacity { value: 1. },
+            ))
+            .push_children(&old_children)
+            .id();
+
+        let to = self.eml.build(world);
+        world.entity_mut(to).insert(Opacity { value: 0. });
+
+        let blocker = world
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        left: Val::Px(0.),
+                        top: Val::Px(0.),
+                        width: Val::Percent(100.),
+                        height: Val::Percent(100.),
+                        ..default()
+                    },
+                    background_color: BackgroundColor(Color::NONE),
+                    focus_policy: FocusPolicy::Block,
+                    ..default()
+                },
+                Interaction::None,
+                PointerEvents::All,
+            ))
+            .id();
+
+        world
+            .entity_mut(self.entity)
+            .push_children(&[to, from, blocker])
+            .insert(Crossfade {
+                from,
+                to,
+                blocker,
+                duration: self.duration.as_secs_f32(),
+                elapsed: 0.,
+            });
+    }
+}
+
+pub fn crossfade_system(
+    time: ElementsTime,
+    mut commands: Commands,
+    mut fades: Query<(Entity, &mut Crossfade)>,
+    mut opacities: Query<&mut Opacity>,
+) {
+    for (entity, mut fade) in fades.iter_mut() {
+        fade.elapsed += time.delta_seconds(entity);
+        let progress = fade.progress();
+        if let Ok(mut opacity) = opacities.get_mut(fade.from) {
+            opacity.value = 1. - progress;
+        }
+        if let Ok(mut opacity) = opacities.get_mut(fade.to) {
+            opacity.value = progress;
+        }
+        if progress >= 1. {
+            commands.entity(entity).remove::<Crossfade>();
+            commands.entity(fade.from).despawn_recursive();
+            commands.entity(fade.blocker).despawn_recursive();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::time::UiTimePlugin;
+    use bevy::ecs::system::RunSystemOnce;
+
+    #[test]
+    fn crossfade_children() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, UiTimePlugin));
+        // the first update only starts the clock
+        app.world
+            .resource_mut::<Time<Real>>()
+            .update_with_duration(Duration::ZERO);
+        let old = app.world.spawn(NodeBundle::default()).id();
+        let page = app
+            .world
+            .spawn(NodeBundle::default())
+            .push_children(&[old])
+            .id();
+        CrossfadeChildrenCommand {
+            entity: page,
+            eml: Eml::new(|world, _| world.spawn(NodeBundle::default()).id()),
+            duration: Duration::from_millis(200),
+        }
+        .apply(&mut app.world);
+
+        let fade = app.world.get::<Crossfade>(page).unwrap();
+        let (from, to, blocker) = (fade.from, fade.to, fade.blocker);
+        assert_eq!(app.world.get::<Parent>(old).map(|p| p.get()), Some(from));
+
+        let advance = |app: &mut App, ms| {
+            app.world
+                .resource_mut::<Time<Real>>()
+                .update_with_duration(Duration::from_millis(ms));
+            app.world.run_system_once(crossfade_system);
+        };
+        advance(&mut app, 100);
+        assert_eq!(app.world.get::<Opacity>(from).unwrap().value, 0.5);
+        assert_eq!(app.world.get::<Opacity>(to).unwrap().value, 0.5);
+        advance(&mut app, 100);
+        assert!(app.world.get_entity(old).is_none());
+        assert!(app.world.get_entity(blocker).is_none());
+        assert!(app.world.get::<Crossfade>(page).is_none());
+        assert_eq!(app.world.get::<Children>(page).unwrap().to_vec(), vec![to]);
+    }
+}
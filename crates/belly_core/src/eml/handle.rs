@@ -0,0 +1,119 @@
+use super::{morph::collect_tree, Eml};
+use crate::element::Element;
+use bevy::{prelude::*, utils::HashMap};
+use std::sync::{Arc, RwLock};
+use tagstr::Tag;
+
+impl Eml {
+    /// Queues the `eml` to the `commands` (like `commands.add(eml)` does)
+    /// and returns the handle resolving to the root entity and the map of
+    /// `id`-ed entities once the commands are applied. It lets you wire
+    /// the game side to the spawned elements without looking them up:
+    /// ```rust,ignore
+    /// fn setup(mut commands: Commands) {
+    ///     let ui = eml! {
+    ///         <body>
+    ///             <span id="score"/>
+    ///             <button id="restart">"Restart"</button>
+    ///         </body>
+    ///     }
+    ///     .spawn(&mut commands);
+    ///     commands.insert_resource(Hud(ui));
+    /// }
+    ///
+    /// fn wire_hud(mut commands: Commands, hud: Res<Hud>) {
+    ///     let Some(score) = hud.0.get("score") else { return };
+    ///     commands.entity(score).insert(ScoreLabel);
+    /// }
+    /// ```
+    pub fn spawn(self, commands: &mut Commands) -> EmlHandle {
+        let handle = EmlHandle::default();
+        let resolve = handle.clone();
+        commands.add(move |world: &mut World| {
+            let root = self.build(world);
+            resolve.resolve(world, root);
+        });
+        handle
+    }
+}
+
+/// The result of [`Eml::spawn`]. It is empty until the queued commands
+/// are applied.
+#[derive(Clone, Default)]
+pub struct EmlHandle(Arc<RwLock<Option<BuiltEml>>>);
+
+struct BuiltEml {
+    root: Entity,
+    ids: HashMap<Tag, Entity>,
+}
+
+impl EmlHandle {
+    fn resolve(&self, world: &World, root: Entity) {
+        let mut tree = vec![];
+        collect_tree(world, root, &mut tree);
+        let ids = tree
+            .into_iter()
+            .filter_map(|e| world.get::<Element>(e).and_then(|el| el.id).map(|id| (id, e)))
+            .collect();
+        *self.0.write().unwrap() = Some(BuiltEml { root, ids });
+    }
+
+    /// Returns `true` when the `eml` is built.
+    pub fn is_ready(&self) -> bool {
+        self.0.read().unwrap().is_some()
+    }
+
+    /// Returns the root entity of the built `eml`.
+    pub fn root(&self) -> Option<Entity> {
+        self.0.read().unwrap().as_ref().map(|b| b.root)
+    }
+
+    /// Returns the built entity with the `id`.
+    pub fn get(&self, id: impl Into<Tag>) -> Option<Entity> {
+        let id = id.into();
+        self.0
+            .read()
+            .unwrap()
+            .as_ref()
+            .and_then(|b| b.ids.get(&id).copied())
+    }
+
+    /// Returns the map of the built entities by their ids.
+    pub fn ids(&self) -> HashMap<Tag, Entity> {
+        self.0
+            .read()
+            .unwrap()
+            .as_ref()
+            .map(|b| b.ids.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bevy::ecs::system::CommandQueue;
+    use tagstr::tag;
+
+    #[test]
+    fn handle_resolves_ids() {
+        let mut world = World::new();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let handle = Eml::new(|world, _| {
+            let mut element = Element::default();
+            element.id = Some(tag!("score"));
+            let score = world.spawn(element).id();
+            world.spawn(Element::default()).push_children(&[score]).id()
+        })
+        .spawn(&mut commands);
+        assert!(!handle.is_ready());
+        assert_eq!(handle.get("score"), None);
+
+        queue.apply(&mut world);
+        let root = handle.root().unwrap();
+        let score = handle.get("score").unwrap();
+        assert_eq!(world.get::<Parent>(score).map(|p| p.get()), Some(root));
+        assert_eq!(handle.ids().len(), 1);
+    }
+}
@@ -0,0 +1,494 @@
+use super::asset::{EmlAsset, EmlElement, EmlLoader, EmlNode};
+use super::build::WidgetRegistry;
+use super::parse::validate_style;
+use super::text::{decode_entities, trim, WhiteSpace};
+use crate::ess::{PropertyExtractor, PropertyTransformer};
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt};
+use bevy::prelude::*;
+use bevy::utils::BoxedFuture;
+use std::sync::Arc;
+use tagstr::AsTag;
+use thiserror::Error;
+
+/// The elements without the content and the closing tag
+const VOID_ELEMENTS: &[&str] = &["br", "hr", "img", "input", "meta", "link"];
+/// The elements which content is skipped entirely
+const RAW_ELEMENTS: &[&str] = &["script", "style"];
+/// The elements skipped with their content
+const SKIPPED_ELEMENTS: &[&str] = &["head", "title", "meta", "link", "template", "noscript", "hr"];
+/// The block elements converted to the `<div>` with the class of their name
+const BLOCK_ELEMENTS: &[&str] = &[
+    "section", "header", "footer", "nav", "main", "article", "aside", "form", "ul", "ol", "li",
+    "p", "h1", "h2", "h3", "h4", "h5", "h6",
+];
+/// The inline elements converted to the `<span>` with the class of their name
+const INLINE_ELEMENTS: &[&str] = &["a", "label", "em", "i", "small", "code"];
+
+/// Possible errors of the [`HtmlImporter`]
+#[non_exhaustive]
+#[derive(Debug, Error, PartialEq)]
+pub enum HtmlImportError {
+    #[error("Invalid html: {0}")]
+    Syntax(String),
+    #[error("Html element converts to <{0}>, but the widget is not registered")]
+    UnknownWidget(String),
+    #[error("Html document has no elements")]
+    Empty,
+}
+
+/// Converts the constrained HTML subset into the `eml` tree, so the
+/// mockups and the web-based game UIs can be loaded as the `.html`
+/// assets: `asset_server.load::<EmlAsset>("menu.html")`.
+///
+/// The supported elements are:
+/// - `div`, `span`, `body`, `br`, `img`, `button` and `strong` (or `b`)
+///   become the widgets with the same name
+/// - the block elements (`p`, `h1`..`h6`, `section`, `ul`, `li`, etc.)
+///   become the `<div>` with the class of the element name: `<div c:h1>`
+/// - the inline elements (`a`, `label`, `em`, `i`, `small`, `code`)
+///   become the `<span>` with the class of the element name
+/// - `input` becomes the `<textinput>`, the `range` input becomes the
+///   `<slider>`, the `checkbox` and `radio` inputs become the toggle
+///   `<button>`s, the `button` and `submit` inputs become the `<button>`s;
+///   `textarea` becomes the `<textinput>` as well
+///
+/// The `id`, `class`, `disabled` and `hidden` attributes are kept, the
+/// `style` declarations become the `s:` params (the unsupported ones are
+/// skipped with the warning). The `head`, `script` and `style` elements
+/// are skipped, the unknown elements become the `<div>`s.
+pub struct HtmlImporter(EmlLoader);
+
+impl HtmlImporter {
+    pub fn new(
+        registry: WidgetRegistry,
+        transformer: PropertyTransformer,
+        extractor: PropertyExtractor,
+    ) -> HtmlImporter {
+        HtmlImporter(EmlLoader {
+            registry,
+            transformer,
+            extractor,
+        })
+    }
+
+    /// Converts the html `source` into the `eml` tree. Several top level
+    /// elements are wrapped into the `<div>`.
+    pub fn import(&self, source: &str) -> Result<EmlNode, HtmlImportError> {
+        let mut nodes = vec![];
+        for node in parse_html(source)?.iter() {
+            self.convert(node, &mut nodes)?;
+        }
+        let mut elements: Vec<_> = nodes
+            .into_iter()
+            .filter(|n| matches!(n, EmlNode::Element(_)))
+            .collect();
+        match elements.len() {
+            0 => Err(HtmlImportError::Empty),
+            1 => Ok(elements.pop().unwrap()),
+            _ => {
+                let mut root = EmlElement::new("div".as_tag());
+                root.children = elements;
+                Ok(EmlNode::Element(root))
+            }
+        }
+    }
+
+    fn convert(&self, node: &HtmlNode, nodes: &mut Vec<EmlNode>) -> Result<(), HtmlImportError> {
+        let html = match node {
+            HtmlNode::Text(text) => {
+                let text = WhiteSpace::Normal.normalize(trim(text));
+                if !text.is_empty() {
+                    nodes.push(EmlNode::Text(text));
+                }
+                return Ok(());
+            }
+            HtmlNode::Element(html) => html,
+        };
+        let name = html.name.as_str();
+        let (tag, class) = match name {
+            "html" => {
+                for child in html.children.iter() {
+                    self.convert(child, nodes)?;
+                }
+                return Ok(());
+            }
+            name if SKIPPED_ELEMENTS.contains(&name) => return Ok(()),
+            "input" | "textarea" => return self.convert_input(html, nodes),
+            "body" | "div" | "span" | "br" | "img" | "button" | "strong" => (name, None),
+            "b" => ("strong", None),
+            name if BLOCK_ELEMENTS.contains(&name) => ("div", Some(name)),
+            name if INLINE_ELEMENTS.contains(&name) => ("span", Some(name)),
+            name => {
+                warn!("Unsupported html element <{name}>, converting it to <div>");
+                ("div", Some(name))
+            }
+        };
+        let mut element = self.element(tag, html, class)?;
+        if tag == "img" {
+            if let Some(src) = html.attr("src") {
+                element.params.insert("src".into(), src.to_string());
+            }
+        }
+        for child in html.children.iter() {
+            self.convert(child, &mut element.children)?;
+        }
+        nodes.push(EmlNode::Element(element));
+        Ok(())
+    }
+
+    fn convert_input(
+        &self,
+        html: &HtmlElement,
+        nodes: &mut Vec<EmlNode>,
+    ) -> Result<(), HtmlImportError> {
+        let kind = match html.name.as_str() {
+            "textarea" => "text",
+            _ => html.attr("type").unwrap_or("text"),
+        };
+        let element = match kind {
+            "text" | "password" | "email" | "search" | "number" | "tel" | "url" => {
+                let mut element = self.element("textinput", html, None)?;
+                let value = match html.name.as_str() {
+                    "textarea" => Some(html.text()),
+                    _ => html.attr("value").map(|v| v.to_string()),
+                };
+                if let Some(value) = value {
+                    element.params.insert("value".into(), value);
+                }
+                element
+            }
+            "range" => {
+                let mut element = self.element("slider", html, None)?;
+                for (attr, param) in [
+                    ("min", "minimum"),
+                    ("max", "maximum"),
+                    ("value", "value"),
+                    ("step", "step"),
+                ] {
+                    if let Some(value) = html.attr(attr) {
+                        element.params.insert(param.into(), value.to_string());
+                    }
+                }
+                element
+            }
+            "checkbox" | "radio" => {
+                let mut element = self.element("button", html, None)?;
+                element.params.insert("mode".into(), "toggle".into());
+                if html.attr("checked").is_some() {
+                    element.params.insert("pressed".into(), "true".into());
+                }
+                element
+            }
+            "button" | "submit" | "reset" => {
+                let mut element = self.element("button", html, None)?;
+                if let Some(value) = html.attr("value") {
+                    element.children.push(EmlNode::Text(value.to_string()));
+                }
+                element
+            }
+            kind => {
+                warn!("Unsupported html input type `{kind}`, skipping it");
+                return Ok(());
+            }
+        };
+        nodes.push(EmlNode::Element(element));
+        Ok(())
+    }
+
+    /// Creates the `tag` widget element with the common attributes of the
+    /// `html` element
+    fn element(
+        &self,
+        tag: &str,
+        html: &HtmlElement,
+        class: Option<&str>,
+    ) -> Result<EmlElement, HtmlImportError> {
+        if !self.0.registry.has(tag.as_tag()) {
+            return Err(HtmlImportError::UnknownWidget(tag.to_string()));
+        }
+        let mut element = EmlElement::new(tag.as_tag());
+        for (name, value) in html.attrs.iter() {
+            match name.as_str() {
+                "id" | "class" => {
+                    element.params.insert(name.clone(), value.clone());
+                }
+                "disabled" => {
+                    element.params.insert("disabled".into(), "true".into());
+                }
+                "hidden" => {
+                    element.params.insert("s:display".into(), "none".into());
+                }
+                "style" => {
+                    for (property, value) in style_declarations(value) {
+                        let tag = property.as_str().as_tag();
+                        match validate_style(tag, &value, &self.0) {
+                            Ok(_) => {
+                                element.params.insert(format!("s:{property}"), value);
+                            }
+                            Err(e) => warn!("Skipping html style `{property}: {value}`: {e}"),
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        if let Some(class) = class {
+            element.params.insert("class".into(), class_list(&element, class));
+        }
+        Ok(element)
+    }
+}
+
+impl AssetLoader for HtmlImporter {
+    type Settings = ();
+    type Error = HtmlImportError;
+    type Asset = EmlAsset;
+
+    fn extensions(&self) -> &[&str] {
+        &["html", "htm"]
+    }
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _: &'a Self::Settings,
+        load_context: &'a mut bevy::asset::LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut source = String::new();
+            reader
+                .read_to_string(&mut source)
+                .await
+                .map_err(|e| HtmlImportError::Syntax(e.to_string()))?;
+            match self.import(&source) {
+                Ok(root) => Ok(EmlAsset {
+                    root: Arc::new(root),
+                    path: load_context.path().to_string_lossy().to_string(),
+                }),
+                Err(err) => {
+                    let path = load_context.path();
+                    error!("Error importing {}: {}", path.display(), err);
+                    Err(err)
+                }
+            }
+        })
+    }
+}
+
+/// Appends the `class` to the `class` param of the `element`
+fn class_list(element: &EmlElement, class: &str) -> String {
+    match element.params.get("class") {
+        Some(classes) => format!("{classes} {class}"),
+        None => class.to_string(),
+    }
+}
+
+/// Splits the inline css into the `(property, value)` pairs
+fn style_declarations(style: &str) -> Vec<(String, String)> {
+    style
+        .split(';')
+        .filter_map(|declaration| declaration.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .filter(|(name, value)| !name.is_empty() && !value.is_empty())
+        .collect()
+}
+
+#[derive(Debug, PartialEq)]
+enum HtmlNode {
+    Element(HtmlElement),
+    Text(String),
+}
+
+#[derive(Debug, PartialEq, Default)]
+struct HtmlElement {
+    name: String,
+    attrs: Vec<(String, String)>,
+    children: Vec<HtmlNode>,
+}
+
+impl HtmlElement {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn text(&self) -> String {
+        self.children
+            .iter()
+            .map(|child| match child {
+                HtmlNode::Text(text) => text.clone(),
+                HtmlNode::Element(element) => element.text(),
+            })
+            .collect()
+    }
+
+    fn push_text(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        let text = decode_entities(text);
+        if let Some(HtmlNode::Text(last)) = self.children.last_mut() {
+            last.push_str(&text);
+        } else {
+            self.children.push(HtmlNode::Text(text.into_owned()));
+        }
+    }
+}
+
+/// Parses the html into the tree the way the browsers do for the simple
+/// documents: the void elements don't need the closing tags, the closing
+/// tag closes the unclosed elements inside it, the unmatched closing tags
+/// are ignored.
+fn parse_html(source: &str) -> Result<Vec<HtmlNode>, HtmlImportError> {
+    let error = |msg: &str| HtmlImportError::Syntax(msg.to_string());
+    let mut stack = vec![HtmlElement::default()];
+    let mut rest = source;
+    while let Some(start) = rest.find('<') {
+        stack.last_mut().unwrap().push_text(&rest[..start]);
+        rest = &rest[start..];
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            let end = comment.find("-->").ok_or_else(|| error("unterminated comment"))?;
+            rest = &comment[end + 3..];
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            let end = rest.find('>').ok_or_else(|| error("unterminated declaration"))?;
+            rest = &rest[end + 1..];
+        } else if let Some(close) = rest.strip_prefix("</") {
+            let end = close.find('>').ok_or_else(|| error("unterminated closing tag"))?;
+            let name = close[..end].trim().to_ascii_lowercase();
+            rest = &close[end + 1..];
+            if let Some(idx) = stack.iter().skip(1).rposition(|e| e.name == name) {
+                while stack.len() > idx + 1 {
+                    let element = stack.pop().unwrap();
+                    let parent = stack.last_mut().unwrap();
+                    parent.children.push(HtmlNode::Element(element));
+                }
+            }
+        } else if rest[1..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+            let (element, closed, tail) = parse_tag(&rest[1..])?;
+            rest = tail;
+            if RAW_ELEMENTS.contains(&element.name.as_str()) {
+                let end = format!("</{}", element.name);
+                let idx = rest.to_ascii_lowercase().find(&end).unwrap_or(rest.len());
+                rest = &rest[idx..];
+                rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+            } else if closed || VOID_ELEMENTS.contains(&element.name.as_str()) {
+                let parent = stack.last_mut().unwrap();
+                parent.children.push(HtmlNode::Element(element));
+            } else {
+                stack.push(element);
+            }
+        } else {
+            stack.last_mut().unwrap().push_text("<");
+            rest = &rest[1..];
+        }
+    }
+    stack.last_mut().unwrap().push_text(rest);
+    while stack.len() > 1 {
+        let element = stack.pop().unwrap();
+        stack.last_mut().unwrap().children.push(HtmlNode::Element(element));
+    }
+    Ok(stack.pop().unwrap().children)
+}
+
+/// Parses the opening tag after the `<`, returns the element, whether it
+/// is self-closed and the rest of the source
+fn parse_tag(source: &str) -> Result<(HtmlElement, bool, &str), HtmlImportError> {
+    let name_end = source
+        .find(|c: char| !c.is_ascii_alphanumeric() && c != '-')
+        .unwrap_or(source.len());
+    let mut element = HtmlElement {
+        name: source[..name_end].to_ascii_lowercase(),
+        ..default()
+    };
+    let tag = element.name.clone();
+    let unterminated = || HtmlImportError::Syntax(format!("unterminated tag <{tag}>"));
+    let mut rest = &source[name_end..];
+    loop {
+        rest = rest.trim_start();
+        if let Some(tail) = rest.strip_prefix("/>") {
+            return Ok((element, true, tail));
+        } else if let Some(tail) = rest.strip_prefix('>') {
+            return Ok((element, false, tail));
+        } else if rest.is_empty() {
+            return Err(unterminated());
+        } else if let Some(tail) = rest.strip_prefix('/') {
+            rest = tail;
+            continue;
+        }
+        let name_end = rest
+            .find(|c: char| c.is_ascii_whitespace() || c == '=' || c == '>' || c == '/')
+            .unwrap_or(rest.len());
+        let name = rest[..name_end].to_ascii_lowercase();
+        rest = rest[name_end..].trim_start();
+        let mut value = String::new();
+        if let Some(tail) = rest.strip_prefix('=') {
+            rest = tail.trim_start();
+            if let Some(quote) = rest.chars().next().filter(|c| *c == '"' || *c == '\'') {
+                let end = rest[1..].find(quote).ok_or_else(unterminated)?;
+                value = decode_entities(&rest[1..end + 1]).into_owned();
+                rest = &rest[end + 2..];
+            } else {
+                let end = rest
+                    .find(|c: char| c.is_ascii_whitespace() || c == '>')
+                    .unwrap_or(rest.len());
+                value = decode_entities(&rest[..end]).into_owned();
+                rest = &rest[end..];
+            }
+        }
+        element.attrs.push((name, value));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_html_subset() {
+        let source = r#"
+            <!DOCTYPE html>
+            <div class="menu" hidden>
+                <!-- the title -->
+                <h1>Tom &amp; Jerry</h1>
+                <img src='logo.png'>
+                <input type=range min=0 max=10>
+                <script>if (a < b) { run(); }</script>
+                <p>unclosed
+            </div>
+        "#;
+        let nodes = parse_html(source).unwrap();
+        let nodes: Vec<_> = nodes
+            .into_iter()
+            .filter(|n| matches!(n, HtmlNode::Element(_)))
+            .collect();
+        let [HtmlNode::Element(menu)] = &nodes[..] else {
+            panic!("Expected single element, got {nodes:?}");
+        };
+        assert_eq!(menu.attr("class"), Some("menu"));
+        assert_eq!(menu.attr("hidden"), Some(""));
+        let children: Vec<_> = menu
+            .children
+            .iter()
+            .filter_map(|n| match n {
+                HtmlNode::Element(e) => Some(e),
+                _ => None,
+            })
+            .collect();
+        let names: Vec<_> = children.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["h1", "img", "input", "p"]);
+        assert_eq!(children[0].text(), "Tom & Jerry");
+        assert_eq!(children[1].attr("src"), Some("logo.png"));
+        assert_eq!(children[2].attr("max"), Some("10"));
+        assert_eq!(children[3].text().trim(), "unclosed");
+        assert_eq!(
+            style_declarations("width: 50%; Color:red;;bogus"),
+            vec![
+                ("width".to_string(), "50%".to_string()),
+                ("color".to_string(), "red".to_string())
+            ]
+        );
+    }
+}
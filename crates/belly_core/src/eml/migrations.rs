@@ -0,0 +1,174 @@
+use bevy::{prelude::*, utils::HashMap};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, RwLock},
+};
+use tagstr::Tag;
+use thiserror::Error;
+
+/// The saved state of the widget: the version of the widget the state
+/// was saved by and the values of the state.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SavedWidgetState {
+    pub version: u32,
+    pub values: HashMap<String, String>,
+}
+
+pub type MigrationFn = Arc<dyn Fn(&mut SavedWidgetState) + Send + Sync>;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum MigrationError {
+    #[error("State of `{widget}` is saved by version {version}, newer than current {current}")]
+    NewerVersion {
+        widget: Tag,
+        version: u32,
+        current: u32,
+    },
+    #[error("State of `{widget}` is saved by unknown version {version}")]
+    UnknownVersion { widget: Tag, version: u32 },
+}
+
+/// Migrations of the saved widget states, registered per widget and version.
+/// Migration to version `N` receives the state of the previous registered
+/// version (or of version `0`) and is expected to convert it into the state
+/// of version `N`. States of newer or unknown versions are rejected instead
+/// of being dropped or partially restored.
+#[derive(Resource, Clone, Default, Deref)]
+pub struct WidgetMigrations(Arc<RwLock<HashMap<Tag, BTreeMap<u32, MigrationFn>>>>);
+
+impl WidgetMigrations {
+    /// The current version of the `widget` state: the latest version
+    /// with registered migration or `0`.
+    pub fn current_version<T: Into<Tag>>(&self, widget: T) -> u32 {
+        self.0
+            .read()
+            .unwrap()
+            .get(&widget.into())
+            .and_then(|migrations| migrations.keys().last().copied())
+            .unwrap_or(0)
+    }
+
+    /// Runs the migrations of the `widget` one by one until the `state`
+    /// reaches the current version. The state is left untouched if it
+    /// can't be migrated.
+    pub fn migrate<T: Into<Tag>>(
+        &self,
+        widget: T,
+        state: &mut SavedWidgetState,
+    ) -> Result<(), MigrationError> {
+        let widget = widget.into();
+        let migrations = self.0.read().unwrap();
+        let Some(migrations) = migrations.get(&widget) else {
+            return match state.version {
+                0 => Ok(()),
+                version => Err(MigrationError::NewerVersion {
+                    widget,
+                    version,
+                    current: 0,
+                }),
+            };
+        };
+        let current = migrations.keys().last().copied().unwrap_or(0);
+        if state.version > current {
+            return Err(MigrationError::NewerVersion {
+                widget,
+                version: state.version,
+                current,
+            });
+        }
+        if state.version != 0 && !migrations.contains_key(&state.version) {
+            return Err(MigrationError::UnknownVersion {
+                widget,
+                version: state.version,
+            });
+        }
+        let mut migrated = state.clone();
+        for (version, migrate) in migrations.range(state.version + 1..) {
+            migrate(&mut migrated);
+            migrated.version = *version;
+        }
+        *state = migrated;
+        Ok(())
+    }
+}
+
+pub trait RegisterWidgetMigration {
+    /// Registers the migration of the `widget` state to the `version`:
+    /// ```rust,ignore
+    /// app.register_widget_migration("slider", 2, |state| {
+    ///     // the `value` was renamed to `absolute` in version 2
+    ///     if let Some(value) = state.values.remove("value") {
+    ///         state.values.insert("absolute".to_string(), value);
+    ///     }
+    /// });
+    /// ```
+    fn register_widget_migration<F>(
+        &mut self,
+        widget: &str,
+        version: u32,
+        migrate: F,
+    ) -> &mut Self
+    where
+        F: Fn(&mut SavedWidgetState) + Send + Sync + 'static;
+}
+
+impl RegisterWidgetMigration for App {
+    fn register_widget_migration<F>(
+        &mut self,
+        widget: &str,
+        version: u32,
+        migrate: F,
+    ) -> &mut Self
+    where
+        F: Fn(&mut SavedWidgetState) + Send + Sync + 'static,
+    {
+        let migrations = self
+            .world
+            .get_resource_or_insert_with(WidgetMigrations::default)
+            .clone();
+        migrations
+            .write()
+            .unwrap()
+            .entry(Tag::new(widget))
+            .or_default()
+            .insert(version, Arc::new(migrate));
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn migrate_widget_state() {
+        let mut app = App::new();
+        app.register_widget_migration("slider", 1, |state| {
+            state.values.insert("min".into(), "0".into());
+        });
+        app.register_widget_migration("slider", 2, |state| {
+            if let Some(value) = state.values.remove("value") {
+                state.values.insert("absolute".into(), value);
+            }
+        });
+        let migrations = app.world.resource::<WidgetMigrations>().clone();
+        assert_eq!(migrations.current_version("slider"), 2);
+
+        let mut state = SavedWidgetState::default();
+        state.values.insert("value".into(), "0.5".into());
+        migrations.migrate("slider", &mut state).unwrap();
+        assert_eq!(state.version, 2);
+        assert_eq!(state.values.get("absolute").map(|s| s.as_str()), Some("0.5"));
+        assert_eq!(state.values.get("min").map(|s| s.as_str()), Some("0"));
+
+        let mut state = SavedWidgetState {
+            version: 3,
+            ..default()
+        };
+        assert!(matches!(
+            migrations.migrate("slider", &mut state),
+            Err(MigrationError::NewerVersion { version: 3, .. })
+        ));
+        assert_eq!(state.version, 3);
+    }
+}
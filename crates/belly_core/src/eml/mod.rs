@@ -1,12 +1,25 @@
 pub mod asset;
 pub mod build;
 pub mod content;
+pub mod crossfade;
+pub mod handle;
+pub mod html;
+pub mod migrations;
+pub mod morph;
 pub mod params;
 pub mod parse;
+pub mod plugin;
+pub mod pool;
+pub mod strict;
+pub mod text;
 pub mod variant;
+pub mod view;
 pub use self::build::*;
 pub use self::params::*;
+pub use self::plugin::WidgetPlugin;
+pub use self::strict::{AttributeParams, RegisterAttributeParam, SourceLocation, StrictMode};
 pub use self::variant::*;
+pub use self::view::View;
 use crate::ess::{PropertyExtractor, PropertyTransformer};
 use asset::{update_eml_scene, EmlAsset, EmlLoader};
 use bevy::prelude::*;
@@ -30,11 +43,21 @@ impl Plugin for EmlPlugin {
             .get_resource_or_insert_with(WidgetRegistry::default)
             .clone();
 
+        app.register_asset_loader(html::HtmlImporter::new(
+            registry.clone(),
+            validator.clone(),
+            extractor.clone(),
+        ));
         app.register_asset_loader(EmlLoader {
             transformer: validator,
             extractor,
             registry,
         });
+        app.init_resource::<AttributeParams>();
+        app.add_systems(Last, strict::cache_style_attributes_system);
         app.add_systems(Update, update_eml_scene);
+        app.add_systems(Update, crossfade::crossfade_system);
+        app.init_resource::<pool::ElementPool>();
+        app.add_systems(Last, pool::release_parked_elements_system);
     }
 }
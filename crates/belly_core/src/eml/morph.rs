@@ -0,0 +1,223 @@
+use super::{pool::recycle, Eml};
+use crate::element::{
+    CleanupElementCommand, Element, ElementIdIndex, Elements, InvalidateElementCommand,
+};
+use bevy::{ecs::system::Command, prelude::*, utils::HashSet};
+use std::mem;
+
+impl<'w, 's> Elements<'w, 's> {
+    /// Despawns the content of the `entity` and builds the `eml` in place
+    /// of it. The `entity` itself is reused, so it is safe to keep it.
+    pub fn replace(&mut self, entity: Entity, eml: Eml) {
+        self.commands().add(ReplaceElementCommand { entity, eml });
+    }
+
+    /// Updates the `entity` tree to look like the `eml` tree, but only
+    /// spawns and despawns the difference. The children are matched by
+    /// the tag name and id in order, matched elements keep their entity,
+    /// state (like `:focus`), binds and connections, and take the classes,
+    /// styles and text of the new tree:
+    /// ```rust,ignore
+    /// fn update_list(mut elements: Elements, items: Res<Items>) {
+    ///     let Some(list) = elements.matching("#items").first().copied() else { return };
+    ///     elements.morph(list, eml! {
+    ///         <div id="items">
+    ///             <for item in=items.names.clone()>
+    ///                 <button>{item}</button>
+    ///             </for>
+    ///         </div>
+    ///     });
+    /// }
+    /// ```
+    pub fn morph(&mut self, entity: Entity, eml: Eml) {
+        self.commands().add(MorphElementCommand { entity, eml });
+    }
+}
+
+pub struct ReplaceElementCommand {
+    pub entity: Entity,
+    pub eml: Eml,
+}
+
+impl Command for ReplaceElementCommand {
+    fn apply(self, world: &mut World) {
+        let Some(entity) = world.get_entity(self.entity) else {
+            return;
+        };
+        let children: Vec<_> = entity
+            .get::<Children>()
+            .map(|c| c.to_vec())
+            .unwrap_or_default();
+        for child in children {
+            recycle(world, child);
+        }
+        CleanupElementCommand(self.entity).apply(world);
+        self.eml.render_to(self.entity)(world);
+    }
+}
+
+pub struct MorphElementCommand {
+    pub entity: Entity,
+    pub eml: Eml,
+}
+
+impl Command for MorphElementCommand {
+    fn apply(self, world: &mut World) {
+        if world.get_entity(self.entity).is_none() {
+            return;
+        }
+        // the ids of the new tree would replace (and despawn)
+        // the elements being morphed, so unindex them first
+        let mut tree = vec![];
+        collect_tree(world, self.entity, &mut tree);
+        let ids: Vec<_> = tree
+            .iter()
+            .filter_map(|e| world.get::<Element>(*e).and_then(|e| e.id))
+            .collect();
+        let mut index = world.resource_mut::<ElementIdIndex>();
+        for id in ids {
+            index.remove(&id);
+        }
+
+        let source = self.eml.build(world);
+        morph_element(world, self.entity, source);
+        world.entity_mut(source).despawn_recursive();
+
+        tree.clear();
+        collect_tree(world, self.entity, &mut tree);
+        for entity in tree {
+            if let Some(id) = world.get::<Element>(entity).and_then(|e| e.id) {
+                world.resource_mut::<ElementIdIndex>().insert(id, entity);
+            }
+        }
+        InvalidateElementCommand(self.entity).apply(world);
+    }
+}
+
+pub(crate) fn collect_tree(world: &World, entity: Entity, result: &mut Vec<Entity>) {
+    result.push(entity);
+    if let Some(children) = world.get::<Children>(entity) {
+        for child in children.iter() {
+            collect_tree(world, *child, result);
+        }
+    }
+}
+
+fn same_element(world: &World, a: Entity, b: Entity) -> bool {
+    match (world.get::<Element>(a), world.get::<Element>(b)) {
+        (Some(a), Some(b)) => a.names == b.names && a.id == b.id,
+        _ => false,
+    }
+}
+
+/// Moves the look of the `source` element to the `target` one and matches
+/// the children. Unmatched `source` children are moved to the `target`,
+/// unmatched `target` children are despawned.
+fn morph_element(world: &mut World, target: Entity, source: Entity) {
+    let element = world
+        .get_mut::<Element>(source)
+        .map(|mut e| mem::take(&mut *e));
+    if let (Some(new), Some(mut old)) = (element, world.get_mut::<Element>(target)) {
+        old.names = new.names;
+        old.aliases = new.aliases;
+        old.id = new.id;
+        old.classes = new.classes;
+        old.styles = new.styles;
+        old.attributes = new.attributes;
+    }
+    if let Some(text) = world.get::<Text>(source).cloned() {
+        if let Some(mut old) = world.get_mut::<Text>(target) {
+            *old = text;
+        }
+    }
+
+    let children = |world: &World, entity| {
+        world
+            .get::<Children>(entity)
+            .map(|c| c.to_vec())
+            .unwrap_or_default()
+    };
+    let old_children = children(world, target);
+    let mut matched = HashSet::new();
+    let mut result = vec![];
+    for child in children(world, source) {
+        let existed = old_children
+            .iter()
+            .copied()
+            .find(|old| !matched.contains(old) && same_element(world, *old, child));
+        if let Some(old) = existed {
+            matched.insert(old);
+            morph_element(world, old, child);
+            result.push(old);
+        } else {
+            result.push(child);
+        }
+    }
+    for old in old_children {
+        if !matched.contains(&old) {
+            world.entity_mut(old).despawn_recursive();
+        }
+    }
+    world.entity_mut(target).replace_children(&result);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tagstr::{tag, Tag};
+
+    fn spawn(world: &mut World, name: &str, id: Option<&str>, class: &str) -> Entity {
+        let mut element = Element::default();
+        element.names.push(Tag::new(name));
+        element.id = id.map(Tag::new);
+        element.classes.insert(Tag::new(class));
+        let entity = world.spawn(element).id();
+        if let Some(id) = id {
+            world
+                .resource_mut::<ElementIdIndex>()
+                .insert(Tag::new(id), entity);
+        }
+        entity
+    }
+
+    #[test]
+    fn morph_keeps_matched_elements() {
+        let mut world = World::new();
+        world.init_resource::<ElementIdIndex>();
+        let root = spawn(&mut world, "div", None, "list");
+        let first = spawn(&mut world, "button", Some("first"), "old");
+        let second = spawn(&mut world, "button", None, "old");
+        let label = spawn(&mut world, "span", None, "old");
+        world.entity_mut(root).push_children(&[first, second, label]);
+        world
+            .get_mut::<Element>(first)
+            .unwrap()
+            .state
+            .insert(tag!("focus"));
+
+        MorphElementCommand {
+            entity: root,
+            eml: Eml::new(|world, _| {
+                let root = spawn(world, "div", None, "list");
+                let first = spawn(world, "button", Some("first"), "new");
+                let image = spawn(world, "img", None, "new");
+                let second = spawn(world, "button", None, "new");
+                world.entity_mut(root).push_children(&[first, image, second]);
+                root
+            }),
+        }
+        .apply(&mut world);
+
+        let children = world.get::<Children>(root).unwrap().to_vec();
+        assert_eq!(children.len(), 3);
+        assert_eq!(children[0], first);
+        assert_eq!(children[2], second);
+        assert!(world.get_entity(label).is_none());
+        let element = world.get::<Element>(first).unwrap();
+        assert!(element.focused());
+        assert!(element.classes.contains(&tag!("new")));
+        assert!(!element.classes.contains(&tag!("old")));
+        assert_eq!(world.resource::<ElementIdIndex>().get(&tag!("first")), Some(&first));
+        assert_eq!(world.query::<&Element>().iter(&world).count(), 4);
+    }
+}
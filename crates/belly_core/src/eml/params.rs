@@ -153,6 +153,26 @@ impl Params {
         }
     }
 
+    /// The string, bool and number params formatted as strings,
+    /// stored on the element for the `[name=value]` selectors.
+    pub fn attributes(&self) -> HashMap<Tag, String> {
+        self.rest
+            .iter()
+            .filter_map(|(name, param)| {
+                let value = match &param.value {
+                    Variant::String(value) => value.clone(),
+                    Variant::Bool(value) => value.to_string(),
+                    value => match (value.get::<f32>(), value.get::<i32>()) {
+                        (Some(value), _) => value.to_string(),
+                        (_, Some(value)) => value.to_string(),
+                        _ => return None,
+                    },
+                };
+                Some((*name, value))
+            })
+            .collect()
+    }
+
     pub fn commands(&mut self, name: Tag) -> Option<ApplyCommands> {
         self.drop::<ApplyCommands>(name)
     }
@@ -209,9 +229,9 @@ impl Params {
         }
     }
 
-    // pub fn contains(&self, tag: Tag) -> bool {
-    //     self.rest.contains_key(&tag)
-    // }
+    pub fn contains(&self, tag: Tag) -> bool {
+        self.rest.contains_key(&tag)
+    }
 
     // pub fn transform<I: IntoIterator<Item = (Tag, PropertyValue)>, F: Fn(Tag, Variant) -> I>(
     //     mut self,
@@ -227,6 +247,30 @@ impl Params {
     // }
 }
 
+/// The value spread into the element params with `<button {..value}>`.
+/// Derive it for the struct to pass its fields as the params, the field
+/// names become the param names (`_` is replaced with `-`), the `None`
+/// options are skipped:
+/// ```rust,ignore
+/// #[derive(IntoParams)]
+/// struct ButtonParams {
+///     #[param(name = "c:primary")]
+///     primary: bool,
+///     #[param(name = "s:width")]
+///     width: String,
+///     mode: Option<String>,
+/// }
+/// ```
+pub trait IntoParams {
+    fn into_params(self) -> Params;
+}
+
+impl IntoParams for Params {
+    fn into_params(self) -> Params {
+        self
+    }
+}
+
 #[macro_export]
 macro_rules! bindattr {
     ($ctx:ident, $key:ident:$typ:ty => $($target:tt)*) => {
@@ -1,16 +1,46 @@
 use super::asset::{EmlElement, EmlLoader, EmlNode};
+use super::build::WidgetRegistry;
+use super::text::{trim, xml_entities, WhiteSpace};
 use super::Variant;
+use crate::ess::{PropertyExtractor, PropertyTransformer};
 use crate::{ess::StyleProperty, ElementsError};
 use std::fmt::Display;
 use tagstr::{AsTag, Tag};
 
 const NS_STYLE: &str = "s";
+const NS_BIND: &str = "bind";
+/// The lines taken by the `<skip:root>` wrapper of the source
+const LINE_OFFSET: u32 = 1;
+const WHITE_SPACE: &str = "white-space";
 
 pub(crate) fn parse(source: &str, loader: &EmlLoader) -> Result<EmlNode, ParseError> {
-    let source = EmlSource::new(source);
+    let source = xml_entities(source);
+    let source = EmlSource::new(&source);
     parse_internal(&source, loader).map_err(|e| ParseError::new(e, &source))
 }
 
+/// Parses the `eml` sources the same way the asset loader does, but
+/// outside of the app: used by the tools like `belly lint`.
+pub struct EmlParser(EmlLoader);
+
+impl EmlParser {
+    pub fn new(
+        registry: WidgetRegistry,
+        transformer: PropertyTransformer,
+        extractor: PropertyExtractor,
+    ) -> EmlParser {
+        EmlParser(EmlLoader {
+            registry,
+            transformer,
+            extractor,
+        })
+    }
+
+    pub fn parse(&self, source: &str) -> Result<EmlNode, ParseError> {
+        parse(source, &self.0)
+    }
+}
+
 enum Error {
     InvalidElement(String, roxmltree::TextPos),
     InvalidStyleValue(String, roxmltree::TextPos),
@@ -93,9 +123,11 @@ struct EmlSource {
 
 impl EmlSource {
     fn new(data: &str) -> EmlSource {
-        let prefix = format!("<skip:root xmlns:skip=\"skip\" xmlns:{NS_STYLE}=\"{NS_STYLE}\">\n");
+        let prefix = format!(
+            "<skip:root xmlns:skip=\"skip\" xmlns:{NS_STYLE}=\"{NS_STYLE}\" xmlns:{NS_BIND}=\"{NS_BIND}\">\n"
+        );
         let suffix = "\n</skip:root>";
-        let line_offset = 1;
+        let line_offset = LINE_OFFSET;
         let data = prefix + data + suffix;
         EmlSource { line_offset, data }
     }
@@ -132,9 +164,7 @@ fn walk(node: roxmltree::Node, loader: &EmlLoader) -> Result<EmlNode, Error> {
     let pos = doc.text_pos_at(node.position());
     if node.is_text() {
         let text = node.text().unwrap();
-        let text = text.trim();
-        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
-        Ok(EmlNode::Text(text))
+        Ok(EmlNode::Text(WhiteSpace::Normal.apply(trim(text))))
     } else if node.is_element() && node.tag_name().name() == "slot" {
         let slot_name = node.attribute("replace").ok_or_else(|| {
             Error::InvalidElement(format!("<slot> tag should have 'for' attribute."), pos)
@@ -151,7 +181,17 @@ fn walk(node: roxmltree::Node, loader: &EmlLoader) -> Result<EmlNode, Error> {
         }
 
         let mut elem = EmlElement::new(node_name);
+        elem.position = Some((pos.row - LINE_OFFSET, pos.col));
+        let mut white_space = WhiteSpace::Normal;
         for attr in node.attributes() {
+            if attr.namespace().is_none() && attr.name() == WHITE_SPACE {
+                white_space = WhiteSpace::parse(attr.value()).ok_or_else(|| {
+                    let pos = doc.text_pos_at(attr.position());
+                    let value = attr.value();
+                    Error::InvalidElement(format!("Invalid {WHITE_SPACE} value: {value}"), pos)
+                })?;
+                continue;
+            }
             let pos = doc.text_pos_at(attr.position());
             let name = if let Some(ns) = attr.namespace() {
                 if ns == NS_STYLE {
@@ -175,11 +215,14 @@ fn walk(node: roxmltree::Node, loader: &EmlLoader) -> Result<EmlNode, Error> {
         for ch in node.children() {
             if ch.is_text() {
                 let text = ch.text().unwrap();
-                let text = text.trim();
-                let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
-                if text.len() > 0 {
-                    elem.children.push(EmlNode::Text(text));
+                if trim(text).is_empty() {
+                    continue;
                 }
+                let text = match white_space {
+                    WhiteSpace::Normal => white_space.apply(trim(text)),
+                    _ => white_space.apply(text),
+                };
+                elem.children.push(EmlNode::Text(text));
             } else if ch.is_element() {
                 elem.children.push(walk(ch, loader)?);
             }
@@ -193,7 +236,7 @@ fn walk(node: roxmltree::Node, loader: &EmlLoader) -> Result<EmlNode, Error> {
     }
 }
 
-fn validate_style(name: Tag, value: &str, loader: &EmlLoader) -> Result<(), ElementsError> {
+pub(crate) fn validate_style(name: Tag, value: &str, loader: &EmlLoader) -> Result<(), ElementsError> {
     let props = Variant::style(TryInto::<StyleProperty>::try_into(value)?);
     if loader.extractor.is_compound_property(name) {
         loader.extractor.extract(name, props)?;
@@ -202,3 +245,24 @@ fn validate_style(name: Tag, value: &str, loader: &EmlLoader) -> Result<(), Elem
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_reports_unknown_widgets() {
+        let parser = EmlParser::new(Default::default(), Default::default(), Default::default());
+        let err = parser.parse("<unknown/>").err().unwrap();
+        assert!(err.to_string().starts_with("Invalid element: unknown"), "{err}");
+
+        let mut child = EmlElement::new("span".as_tag());
+        child.params.insert("class".into(), "title big".into());
+        let mut root = EmlElement::new("div".as_tag());
+        root.params.insert("class".into(), "panel".into());
+        root.children.push(EmlNode::Element(child));
+        let classes = EmlNode::Element(root).classes();
+        assert_eq!(classes.len(), 3);
+        assert!(classes.contains(&"big".as_tag()));
+    }
+}
@@ -0,0 +1,150 @@
+use super::{RegisterWidget, Widget};
+use crate::ess::{CompoundProperty, Defaults, Property, RegisterProperty, RegisterTheme, Theme};
+use crate::relations::format::RegisterTransformer;
+use crate::relations::signal::RegisterSignal;
+use crate::states::{RegisterWidgetStates, WidgetStates};
+use bevy::prelude::*;
+use std::sync::Mutex;
+use tagstr::Tag;
+
+type Registration = Box<dyn FnOnce(&mut App) + Send + Sync>;
+
+/// Registers the widgets of the widget pack together with their
+/// properties, signals, transformers and styles, so the third-party
+/// crate may ship the pack as a single plugin:
+/// ```rust,ignore
+/// use belly::build::*;
+///
+/// pub struct KnobsPlugin;
+/// impl Plugin for KnobsPlugin {
+///     fn build(&self, app: &mut App) {
+///         app.add_plugins(
+///             WidgetPlugin::new("knobs")
+///                 .widget::<KnobWidget>()
+///                 .property::<KnobTrackProperty>()
+///                 .signal::<KnobEvent>("turn")
+///                 .transformer("degrees", |v: &f32| format!("{v:.0}°"))
+///                 .styles("knob { width: 32px; height: 32px; }"),
+///         );
+///     }
+/// }
+/// ```
+/// The `name` identifies the plugin, adding the plugins with the same
+/// name panics. The `BellyPlugin` should be added before the packs.
+pub struct WidgetPlugin {
+    name: &'static str,
+    registrations: Mutex<Vec<Registration>>,
+}
+
+impl WidgetPlugin {
+    pub fn new(name: &'static str) -> WidgetPlugin {
+        WidgetPlugin {
+            name,
+            registrations: Mutex::new(vec![]),
+        }
+    }
+
+    fn register(self, registration: impl FnOnce(&mut App) + Send + Sync + 'static) -> Self {
+        self.registrations
+            .lock()
+            .unwrap()
+            .push(Box::new(registration));
+        self
+    }
+
+    /// Registers the widget, its default styles and `#[style_prop]` properties
+    pub fn widget<T: Widget + Sync + Send + 'static>(self) -> Self {
+        self.register(|app| {
+            app.register_widget::<T>();
+        })
+    }
+
+    /// Registers the states driven by the widget component
+    pub fn widget_states<T: WidgetStates>(self) -> Self {
+        self.register(|app| {
+            app.register_widget_states::<T>();
+        })
+    }
+
+    pub fn property<T: Property + 'static>(self) -> Self {
+        self.register(|app| {
+            app.register_property::<T>();
+        })
+    }
+
+    pub fn compound_property<T: CompoundProperty + 'static>(self) -> Self {
+        self.register(|app| {
+            app.register_compound_property::<T>();
+        })
+    }
+
+    /// Registers the signal connected with `on:name` within `eml!`
+    pub fn signal<T: Send + Sync + 'static>(self, name: &'static str) -> Self {
+        self.register(move |app| {
+            app.register_signal::<T>(name);
+        })
+    }
+
+    /// Registers the format transformer used as `|fmt.name` in the binds
+    pub fn transformer<S, F>(self, name: &'static str, format: F) -> Self
+    where
+        S: 'static,
+        F: Fn(&S) -> String + Send + Sync + 'static,
+    {
+        self.register(move |app| {
+            app.register_transformer::<S, F>(name, format);
+        })
+    }
+
+    pub fn theme(self, name: impl Into<Tag>, theme: Theme) -> Self {
+        let name = name.into();
+        self.register(move |app| {
+            app.register_theme(name, theme);
+        })
+    }
+
+    /// Adds the default styles of the pack, they are applied with the
+    /// default styles of the widgets, so any stylesheet overrides them
+    pub fn styles(self, styles: impl Into<String>) -> Self {
+        let styles = styles.into();
+        self.register(move |app| {
+            app.world
+                .get_resource_or_insert_with(Defaults::default)
+                .styles
+                .push(styles);
+        })
+    }
+}
+
+impl Plugin for WidgetPlugin {
+    fn build(&self, app: &mut App) {
+        for register in self.registrations.lock().unwrap().drain(..) {
+            register(app);
+        }
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::relations::format::Formatters;
+
+    #[test]
+    fn register_widget_pack() {
+        let mut app = App::new();
+        app.add_plugins(
+            WidgetPlugin::new("pack")
+                .transformer("pack-percent", |v: &f32| format!("{}%", v * 100.))
+                .styles("span { width: 10px; }"),
+        );
+        assert_eq!(app.world.resource::<Defaults>().styles, vec!["span { width: 10px; }"]);
+        assert_eq!(
+            app.world.resource::<Formatters>().format("pack-percent", &0.5f32),
+            Some("50%".to_string())
+        );
+    }
+}
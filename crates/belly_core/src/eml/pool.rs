@@ -0,0 +1,181 @@
+use super::morph::collect_tree;
+use bevy::{
+    ecs::{entity::Entities, system::Command},
+    prelude::*,
+};
+use std::mem;
+
+/// Marks the entity parked in the [`ElementPool`]. The relations treat
+/// the pooled entities as despawned and drop their binds and connections.
+#[derive(Component)]
+pub struct Pooled;
+
+/// The entities of the recycled element trees. The `eml!` builder, the
+/// list binds and `Elements::replace` take the entities from the pool
+/// instead of spawning the new ones, so the dynamic UIs (like the lists
+/// rebuilt on every change) don't allocate the entities again and again.
+#[derive(Resource)]
+pub struct ElementPool {
+    /// The entities ready for the reuse
+    available: Vec<Entity>,
+    /// The entities recycled during the current frame, they become available
+    /// on the next frame, when the relations dropped their binds and connections
+    parked: Vec<Entity>,
+    /// The max number of the pooled entities, the rest is despawned
+    pub capacity: usize,
+}
+
+impl Default for ElementPool {
+    fn default() -> Self {
+        ElementPool {
+            available: vec![],
+            parked: vec![],
+            capacity: 4096,
+        }
+    }
+}
+
+impl ElementPool {
+    pub fn len(&self) -> usize {
+        self.available.len() + self.parked.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The entities recycled during the current frame, the relations
+    /// forget the state of the binds targeting them.
+    pub fn recycled(&self) -> &[Entity] {
+        &self.parked
+    }
+
+    /// Takes the available entity out of the pool, the caller is responsible
+    /// for removing the [`Pooled`] marker.
+    pub fn take(&mut self, entities: &Entities) -> Option<Entity> {
+        while let Some(entity) = self.available.pop() {
+            if entities.contains(entity) {
+                return Some(entity);
+            }
+        }
+        None
+    }
+}
+
+/// Returns `true` when the `entity` is despawned or parked in the pool.
+pub fn is_released(world: &World, entity: Entity) -> bool {
+    world
+        .get_entity(entity)
+        .map(|entity| entity.contains::<Pooled>())
+        .unwrap_or(true)
+}
+
+/// Returns the empty entity, reused from the pool when possible.
+pub fn spawn(world: &mut World) -> Entity {
+    let pooled = world
+        .get_resource_mut::<ElementPool>()
+        .map(|mut pool| mem::take(&mut pool.available));
+    let Some(mut available) = pooled else {
+        return world.spawn_empty().id();
+    };
+    let mut reused = None;
+    while let Some(entity) = available.pop() {
+        if let Some(mut entity) = world.get_entity_mut(entity) {
+            entity.remove::<Pooled>();
+            reused = Some(entity.id());
+            break;
+        }
+    }
+    world.resource_mut::<ElementPool>().available = available;
+    reused.unwrap_or_else(|| world.spawn_empty().id())
+}
+
+/// Detaches the `entity` tree and parks its entities in the pool with all
+/// the components removed. Works like `despawn_recursive` when there is no
+/// pool or it is full.
+pub fn recycle(world: &mut World, entity: Entity) {
+    let pooled = world.contains_resource::<ElementPool>();
+    let Some(mut root) = world.get_entity_mut(entity) else {
+        return;
+    };
+    if !pooled {
+        root.despawn_recursive();
+        return;
+    }
+    root.remove_parent();
+    let mut tree = vec![];
+    collect_tree(world, entity, &mut tree);
+    let free = {
+        let pool = world.resource::<ElementPool>();
+        pool.capacity.saturating_sub(pool.len())
+    };
+    for (idx, entity) in tree.into_iter().enumerate() {
+        if idx < free {
+            world.entity_mut(entity).insert(Pooled).retain::<Pooled>();
+            world.resource_mut::<ElementPool>().parked.push(entity);
+        } else {
+            world.entity_mut(entity).despawn();
+        }
+    }
+}
+
+pub struct RecycleElementCommand(pub Entity);
+
+impl Command for RecycleElementCommand {
+    fn apply(self, world: &mut World) {
+        recycle(world, self.0);
+    }
+}
+
+pub(crate) fn release_parked_elements_system(mut pool: ResMut<ElementPool>) {
+    if !pool.parked.is_empty() {
+        let mut parked = mem::take(&mut pool.parked);
+        pool.available.append(&mut parked);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::element::Element;
+    use bevy::ecs::system::RunSystemOnce;
+
+    #[test]
+    fn recycled_entities_are_reused() {
+        let mut world = World::new();
+        world.init_resource::<ElementPool>();
+        let label = world.spawn((Element::default(), Text::default())).id();
+        let button = world.spawn(Element::default()).add_child(label).id();
+        let root = world.spawn(Element::default()).add_child(button).id();
+
+        recycle(&mut world, button);
+        assert_eq!(world.get::<Children>(root).map(|c| c.len()).unwrap_or(0), 0);
+        assert!(world.get::<Element>(label).is_none());
+        assert!(world.get::<Pooled>(label).is_some());
+        assert_eq!(world.resource::<ElementPool>().len(), 2);
+
+        // parked entities are not available until the next frame
+        let spawned = spawn(&mut world);
+        assert!(spawned != button && spawned != label);
+
+        world.run_system_once(release_parked_elements_system);
+        let reused = [spawn(&mut world), spawn(&mut world)];
+        assert!(reused.contains(&button) && reused.contains(&label));
+        assert!(world.get::<Pooled>(label).is_none());
+        assert!(world.resource::<ElementPool>().is_empty());
+    }
+
+    #[test]
+    fn full_pool_despawns_elements() {
+        let mut world = World::new();
+        world.insert_resource(ElementPool {
+            capacity: 1,
+            ..default()
+        });
+        let label = world.spawn(Element::default()).id();
+        let button = world.spawn(Element::default()).add_child(label).id();
+        recycle(&mut world, button);
+        assert!(world.get::<Pooled>(button).is_some());
+        assert!(world.get_entity(label).is_none());
+    }
+}
@@ -0,0 +1,248 @@
+use crate::ess::StyleSheet;
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use std::{borrow::Cow, fmt::Display};
+use tagstr::Tag;
+
+/// Turns the unknown tags and params into the loud errors, insert the
+/// resource to catch the typos during development:
+/// ```rust,ignore
+/// app.insert_resource(StrictMode::enabled());
+/// ```
+/// The debug builds panic with the element source location, the release
+/// builds only log the error.
+#[derive(Resource, Debug, Clone, Copy, Default)]
+pub struct StrictMode {
+    pub enabled: bool,
+}
+
+impl StrictMode {
+    pub fn enabled() -> StrictMode {
+        StrictMode { enabled: true }
+    }
+
+    /// Reports the `params` the widget `tag` was built with but never used.
+    /// The params read from the element attributes (by the `[name]`
+    /// selectors or the registered attribute params) are not reported.
+    pub fn unknown_params(
+        world: &World,
+        tag: Tag,
+        params: &[Tag],
+        location: Option<&SourceLocation>,
+    ) {
+        let params = params
+            .iter()
+            .filter(|p| !Self::is_attribute(world, **p))
+            .map(|p| format!("`{p}`"))
+            .collect::<Vec<_>>();
+        if params.is_empty() {
+            return;
+        }
+        let at = location.map(|l| format!(" at {l}")).unwrap_or_default();
+        Self::report(
+            world,
+            format!("Unknown params {} of <{tag}>{at}", params.join(", ")),
+        );
+    }
+
+    /// Reports the `tag` which is not registered with `register_widget`.
+    pub fn unknown_tag(world: &World, tag: Tag, location: Option<&SourceLocation>) {
+        let at = location.map(|l| format!(" at {l}")).unwrap_or_default();
+        let message = format!("Unknown tag <{tag}>{at}");
+        if Self::is_enabled(world) && cfg!(debug_assertions) {
+            panic!("{message}");
+        } else {
+            error!("{message}");
+        }
+    }
+
+    fn is_attribute(world: &World, param: Tag) -> bool {
+        world
+            .get_resource::<AttributeParams>()
+            .map(|attributes| attributes.contains(param))
+            .unwrap_or(false)
+    }
+
+    fn is_enabled(world: &World) -> bool {
+        world
+            .get_resource::<StrictMode>()
+            .map(|strict| strict.enabled)
+            .unwrap_or(false)
+    }
+
+    fn report(world: &World, message: String) {
+        if !Self::is_enabled(world) {
+            debug!("{message}");
+        } else if cfg!(debug_assertions) {
+            panic!("{message}");
+        } else {
+            error!("{message}");
+        }
+    }
+}
+
+/// The params the systems read from `Element::attributes` instead of the
+/// widgets, see [`RegisterAttributeParam`], and the `[name]` attributes
+/// the style sheets select by.
+#[derive(Resource, Debug, Default)]
+pub struct AttributeParams {
+    registered: HashSet<Tag>,
+    /// Collected by [`cache_style_attributes_system`] when the sheet
+    /// is loaded or changed
+    styles: HashMap<AssetId<StyleSheet>, HashSet<Tag>>,
+}
+
+impl AttributeParams {
+    pub fn contains(&self, param: Tag) -> bool {
+        self.registered.contains(&param) || self.styles.values().any(|s| s.contains(&param))
+    }
+}
+
+/// Keeps the `[name]` attributes of the style sheet selectors in
+/// [`AttributeParams`], so [`StrictMode`] doesn't scan the rules on
+/// each report.
+pub fn cache_style_attributes_system(
+    mut events: EventReader<AssetEvent<StyleSheet>>,
+    sheets: Res<Assets<StyleSheet>>,
+    mut attributes: ResMut<AttributeParams>,
+) {
+    for event in events.read() {
+        match event {
+            AssetEvent::Removed { id } => {
+                attributes.styles.remove(id);
+            }
+            AssetEvent::Added { id } | AssetEvent::Modified { id } => {
+                let Some(sheet) = sheets.get(*id) else {
+                    continue;
+                };
+                let mut names = HashSet::default();
+                sheet
+                    .iter()
+                    .for_each(|rule| rule.selector.collect_attributes(&mut names));
+                attributes.styles.insert(*id, names);
+            }
+            _ => {}
+        }
+    }
+}
+
+pub trait RegisterAttributeParam {
+    /// Marks the `name` param as used, so [`StrictMode`] doesn't report it:
+    /// ```rust,ignore
+    /// app.register_attribute_param("aria-label");
+    /// ```
+    fn register_attribute_param(&mut self, name: &str) -> &mut Self;
+}
+
+impl RegisterAttributeParam for App {
+    fn register_attribute_param(&mut self, name: &str) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(AttributeParams::default)
+            .registered
+            .insert(Tag::new(name));
+        self
+    }
+}
+
+/// Where the element is declared: captured by `eml!` from the tag
+/// or taken from the `.eml` asset position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLocation {
+    pub file: Cow<'static, str>,
+    pub line: u32,
+    pub column: u32,
+}
+
+impl SourceLocation {
+    pub fn new(file: impl Into<Cow<'static, str>>, line: u32, column: u32) -> SourceLocation {
+        SourceLocation {
+            file: file.into(),
+            line,
+            column,
+        }
+    }
+}
+
+impl Display for SourceLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ess::StyleRule;
+    use tagstr::tag;
+
+    #[test]
+    fn unknown_params_ignored_without_strict_mode() {
+        let mut world = World::new();
+        StrictMode::unknown_params(&world, tag!("button"), &[tag!("colr")], None);
+        world.insert_resource(StrictMode::default());
+        StrictMode::unknown_params(&world, tag!("button"), &[tag!("colr")], None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown params `colr` of <button> at src/main.rs:3:5")]
+    fn unknown_params_panic_in_strict_mode() {
+        let mut world = World::new();
+        world.insert_resource(StrictMode::enabled());
+        let location = SourceLocation::new("src/main.rs", 3, 5);
+        StrictMode::unknown_params(&world, tag!("button"), &[], Some(&location));
+        StrictMode::unknown_params(&world, tag!("button"), &[tag!("colr")], Some(&location));
+    }
+
+    fn strict_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(AssetPlugin::default());
+        app.init_asset::<StyleSheet>();
+        app.init_resource::<AttributeParams>();
+        app.add_systems(Last, cache_style_attributes_system);
+        app.insert_resource(StrictMode::enabled());
+        app
+    }
+
+    fn sheet_selecting(selector: &str) -> StyleSheet {
+        StyleSheet::new([StyleRule {
+            selector: selector.into(),
+            properties: default(),
+            themed: default(),
+            location: default(),
+            property_locations: default(),
+            declarations: default(),
+        }])
+    }
+
+    #[test]
+    fn attribute_params_are_not_reported() {
+        let mut app = strict_app();
+        app.register_attribute_param("aria-label");
+        let _sheet = app
+            .world
+            .resource_mut::<Assets<StyleSheet>>()
+            .add(sheet_selecting("slider[mode=vertical]"));
+        app.update();
+        let params = [tag!("aria-label"), tag!("mode")];
+        StrictMode::unknown_params(&app.world, tag!("slider"), &params, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown params `mode` of <slider>")]
+    fn removed_sheet_attributes_are_reported() {
+        let mut app = strict_app();
+        let sheet = app
+            .world
+            .resource_mut::<Assets<StyleSheet>>()
+            .add(sheet_selecting("slider:not([mode])"));
+        app.update();
+        StrictMode::unknown_params(&app.world, tag!("slider"), &[tag!("mode")], None);
+        app.world
+            .resource_mut::<Assets<StyleSheet>>()
+            .remove(sheet.id());
+        app.update();
+        StrictMode::unknown_params(&app.world, tag!("slider"), &[tag!("mode")], None);
+    }
+}
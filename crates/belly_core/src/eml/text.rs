@@ -0,0 +1,169 @@
+use std::borrow::Cow;
+
+/// The named entities supported in the `eml` texts, `&#NNN;` and `&#xHHH;`
+/// character references are supported as well.
+const ENTITIES: &[(&str, char)] = &[
+    ("amp", '&'),
+    ("lt", '<'),
+    ("gt", '>'),
+    ("quot", '"'),
+    ("apos", '\''),
+    ("nbsp", '\u{a0}'),
+    ("ensp", '\u{2002}'),
+    ("emsp", '\u{2003}'),
+    ("thinsp", '\u{2009}'),
+    ("ndash", '\u{2013}'),
+    ("mdash", '\u{2014}'),
+    ("hellip", '\u{2026}'),
+    ("copy", '\u{a9}'),
+];
+
+/// How the whitespace of the `eml` text is handled. Set it with the
+/// `white-space` attribute of the element, it applies to the texts
+/// of the element: `<span white-space="pre-line">...</span>`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WhiteSpace {
+    /// Collapses the whitespace (including the line breaks) into the
+    /// single space
+    #[default]
+    Normal,
+    /// Keeps the line breaks, collapses the rest of whitespace and
+    /// strips the indentation of the lines
+    PreLine,
+    /// Keeps the text as is
+    Pre,
+}
+
+impl WhiteSpace {
+    pub fn parse(value: &str) -> Option<WhiteSpace> {
+        match value {
+            "normal" => Some(WhiteSpace::Normal),
+            "pre-line" => Some(WhiteSpace::PreLine),
+            "pre" => Some(WhiteSpace::Pre),
+            _ => None,
+        }
+    }
+
+    /// Normalizes the whitespace of the `text` and decodes the entities.
+    /// The non-breaking spaces (`&nbsp;`) are never collapsed.
+    pub fn normalize(&self, text: &str) -> String {
+        decode_entities(&self.apply(text)).into_owned()
+    }
+
+    /// Normalizes the whitespace of the `text` without decoding the entities.
+    pub fn apply(&self, text: &str) -> String {
+        match self {
+            WhiteSpace::Normal => collapse(text),
+            WhiteSpace::PreLine => {
+                let lines: Vec<_> = text
+                    .lines()
+                    .map(|line| collapse(line.trim_matches(is_space)))
+                    .collect();
+                let first = lines.iter().position(|l| !l.is_empty()).unwrap_or(lines.len());
+                let last = lines.iter().rposition(|l| !l.is_empty()).map_or(first, |l| l + 1);
+                lines[first..last].join("\n")
+            }
+            WhiteSpace::Pre => text.to_string(),
+        }
+    }
+}
+
+fn is_space(ch: char) -> bool {
+    ch.is_ascii_whitespace()
+}
+
+/// Trims the whitespace except the non-breaking spaces.
+pub(crate) fn trim(text: &str) -> &str {
+    text.trim_matches(is_space)
+}
+
+fn collapse(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut space = false;
+    for ch in text.chars() {
+        if is_space(ch) {
+            space = true;
+            continue;
+        }
+        if space {
+            result.push(' ');
+            space = false;
+        }
+        result.push(ch);
+    }
+    if space {
+        result.push(' ');
+    }
+    result
+}
+
+fn entity(name: &str) -> Option<char> {
+    if let Some(code) = name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+        u32::from_str_radix(code, 16).ok().and_then(char::from_u32)
+    } else if let Some(code) = name.strip_prefix('#') {
+        code.parse().ok().and_then(char::from_u32)
+    } else {
+        ENTITIES.iter().find(|(n, _)| *n == name).map(|(_, ch)| *ch)
+    }
+}
+
+/// Replaces the `&name;` entities with the characters, the unknown
+/// entities are left as is.
+pub fn decode_entities(text: &str) -> Cow<str> {
+    if !text.contains('&') {
+        return Cow::Borrowed(text);
+    }
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest[1..]
+            .find(';')
+            .and_then(|end| entity(&rest[1..end + 1]).map(|ch| (ch, end + 2)));
+        if let Some((ch, len)) = decoded {
+            result.push(ch);
+            rest = &rest[len..];
+        } else {
+            result.push('&');
+            rest = &rest[1..];
+        }
+    }
+    result.push_str(rest);
+    Cow::Owned(result)
+}
+
+/// Replaces the named entities unknown to xml with the character
+/// references, so `&nbsp;` may be used in the `.eml` assets.
+pub(crate) fn xml_entities(source: &str) -> Cow<str> {
+    let mut result = Cow::Borrowed(source);
+    // the first five entities are known to xml
+    for (name, ch) in ENTITIES.iter().skip(5) {
+        let entity = format!("&{name};");
+        if result.contains(&entity) {
+            result = Cow::Owned(result.replace(&entity, &format!("&#{};", *ch as u32)));
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn normalize_white_space() {
+        let text = "\n    Hello,   &lt;world&gt;!\n\n    Second&nbsp;&nbsp;line &#x41;&unknown;\n";
+        assert_eq!(
+            WhiteSpace::Normal.normalize(text),
+            " Hello, <world>! Second\u{a0}\u{a0}line A&unknown; "
+        );
+        assert_eq!(
+            WhiteSpace::PreLine.normalize(text),
+            "Hello, <world>!\n\nSecond\u{a0}\u{a0}line A&unknown;"
+        );
+        assert_eq!(WhiteSpace::Pre.normalize("  a\n b &amp;"), "  a\n b &");
+        assert_eq!(WhiteSpace::parse("pre-line"), Some(WhiteSpace::PreLine));
+        assert_eq!(xml_entities("a&nbsp;b&amp;"), "a&#160;b&amp;");
+    }
+}
@@ -73,6 +73,66 @@ impl From<u8> for Variant {
     }
 }
 
+impl TryFrom<Variant> for Vec<f32> {
+    type Error = String;
+    fn try_from(variant: Variant) -> Result<Self, Self::Error> {
+        variant
+            .take::<Vec<f32>>()
+            .ok_or_else(|| format!("Can't cast Variant to Vec<f32>"))
+    }
+}
+
+impl From<Vec<f32>> for Variant {
+    fn from(v: Vec<f32>) -> Self {
+        Variant::boxed(v)
+    }
+}
+
+impl TryFrom<Variant> for Vec<(f32, f32)> {
+    type Error = String;
+    fn try_from(variant: Variant) -> Result<Self, Self::Error> {
+        variant
+            .take::<Vec<(f32, f32)>>()
+            .ok_or_else(|| format!("Can't cast Variant to Vec<(f32, f32)>"))
+    }
+}
+
+impl From<Vec<(f32, f32)>> for Variant {
+    fn from(v: Vec<(f32, f32)>) -> Self {
+        Variant::boxed(v)
+    }
+}
+
+impl TryFrom<Variant> for Vec<Vec<f32>> {
+    type Error = String;
+    fn try_from(variant: Variant) -> Result<Self, Self::Error> {
+        variant
+            .take::<Vec<Vec<f32>>>()
+            .ok_or_else(|| "Can't cast Variant to Vec<Vec<f32>>".to_string())
+    }
+}
+
+impl From<Vec<Vec<f32>>> for Variant {
+    fn from(v: Vec<Vec<f32>>) -> Self {
+        Variant::boxed(v)
+    }
+}
+
+impl TryFrom<Variant> for Vec<String> {
+    type Error = String;
+    fn try_from(variant: Variant) -> Result<Self, Self::Error> {
+        variant
+            .take::<Vec<String>>()
+            .ok_or_else(|| format!("Can't cast Variant to Vec<String>"))
+    }
+}
+
+impl From<Vec<String>> for Variant {
+    fn from(v: Vec<String>) -> Self {
+        Variant::boxed(v)
+    }
+}
+
 impl TryFrom<Variant> for bool {
     type Error = String;
     fn try_from(variant: Variant) -> Result<Self, Self::Error> {
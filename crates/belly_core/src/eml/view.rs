@@ -0,0 +1,102 @@
+use super::pool::RecycleElementCommand;
+use super::Eml;
+use bevy::prelude::*;
+use std::{marker::PhantomData, sync::Arc};
+
+/// The `eml!` tree bound to the view-model component `T`: the screen is
+/// the root entity holding the view-model and the content rendered into
+/// it. The builder receives the root entity, so the binds and the
+/// handlers of the tree are wired to the view-model:
+/// ```rust,ignore
+/// #[derive(Component, Default)]
+/// struct Score {
+///     points: usize,
+/// }
+///
+/// let view = View::<Score>::new(|root| eml! {
+///     <div c:score>
+///         <label bind:value=from!(root, Score:points|fmt.p("Points: {p}"))/>
+///         <button on:press=run!(for root |s: &mut Score| s.points += 1)>"+1"</button>
+///     </div>
+/// });
+/// let screen = view.mount(&mut commands, Score::default());
+/// // later
+/// View::<Score>::unmount(&mut commands, screen);
+/// ```
+pub struct View<T: Component> {
+    builder: Arc<dyn Fn(Entity) -> Eml + Send + Sync>,
+    model: PhantomData<T>,
+}
+
+impl<T: Component> Clone for View<T> {
+    fn clone(&self) -> Self {
+        View {
+            builder: self.builder.clone(),
+            model: PhantomData,
+        }
+    }
+}
+
+impl<T: Component> View<T> {
+    pub fn new(builder: impl Fn(Entity) -> Eml + Send + Sync + 'static) -> View<T> {
+        View {
+            builder: Arc::new(builder),
+            model: PhantomData,
+        }
+    }
+
+    /// Spawns the root entity with the `model` and renders the view into
+    /// it. Returns the root entity.
+    pub fn mount(&self, commands: &mut Commands, model: T) -> Entity {
+        let root = commands.spawn(model).id();
+        commands.add((self.builder)(root).render_to(root));
+        root
+    }
+
+    /// Mounts the view as the last child of the `parent`.
+    pub fn mount_to(&self, commands: &mut Commands, parent: Entity, model: T) -> Entity {
+        let root = self.mount(commands, model);
+        commands.entity(parent).add_child(root);
+        root
+    }
+
+    /// Removes the view mounted to the `root` with its content, the binds
+    /// and the connections of the view are dropped as well.
+    pub fn unmount(commands: &mut Commands, root: Entity) {
+        commands.add(RecycleElementCommand(root));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::element::Element;
+    use bevy::ecs::system::CommandQueue;
+
+    #[derive(Component)]
+    struct Model(usize);
+
+    #[test]
+    fn mount_and_unmount_view() {
+        let mut world = World::new();
+        let view = View::<Model>::new(|root| {
+            Eml::new(move |world, entity| {
+                let entity = entity.unwrap_or_else(|| world.spawn_empty().id());
+                assert_eq!(entity, root);
+                world.entity_mut(entity).insert(Element::inline());
+                entity
+            })
+        });
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let root = view.mount(&mut commands, Model(7));
+        queue.apply(&mut world);
+        assert_eq!(world.get::<Model>(root).map(|m| m.0), Some(7));
+        assert!(world.get::<Element>(root).is_some());
+
+        let mut commands = Commands::new(&mut queue, &world);
+        View::<Model>::unmount(&mut commands, root);
+        queue.apply(&mut world);
+        assert!(world.get_entity(root).is_none());
+    }
+}
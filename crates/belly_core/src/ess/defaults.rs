@@ -1,4 +1,5 @@
 use crate::eml::WidgetRegistry;
+use crate::ess::impls::bitmap_font::BitmapFont;
 use crate::ess::PropertyExtractor;
 use crate::ess::PropertyTransformer;
 use crate::ess::StyleSheet;
@@ -11,7 +12,14 @@ pub struct Defaults {
     pub italic_font: Handle<Font>,
     pub bold_font: Handle<Font>,
     pub bold_italic_font: Handle<Font>,
+    /// The bitmap font the texts with the `regular` font are drawn with,
+    /// see [`BitmapFontPlugin`](crate::ess::impls::bitmap_font::BitmapFontPlugin)
+    pub bitmap_font: Option<Handle<BitmapFont>>,
     pub style_sheet: Handle<StyleSheet>,
+    /// The default styles added by the plugins (see
+    /// [`WidgetPlugin::styles`](crate::eml::WidgetPlugin::styles)),
+    /// they follow the default styles of the widgets
+    pub styles: Vec<String>,
 }
 
 pub fn setup_defaults(
@@ -49,10 +57,17 @@ pub fn setup_defaults(
                 display: flex;
                 background-color: transparent;
             }
+            *:focus-visible {
+                outline: 2px #4a90e2;
+                outline-offset: 2px;
+            }
         "#,
     );
     for rule in widgets.default_styles(&parser) {
         rules.push(rule);
     }
+    for styles in defaults.styles.iter() {
+        rules.extend(parser.parse(styles));
+    }
     commands.add(StyleSheet::add_default(rules));
 }
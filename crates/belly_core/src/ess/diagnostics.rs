@@ -0,0 +1,116 @@
+use super::StyleSheet;
+use bevy::prelude::*;
+use std::fmt::{Display, Formatter, Result};
+
+/// The position in the `ess` source, both `line` and `column` start from 1.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EssLocation {
+    pub line: u32,
+    pub column: u32,
+}
+
+impl From<cssparser::SourceLocation> for EssLocation {
+    fn from(location: cssparser::SourceLocation) -> Self {
+        EssLocation {
+            line: location.line + 1,
+            column: location.column,
+        }
+    }
+}
+
+impl Display for EssLocation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EssSeverity {
+    /// The rule or the property is dropped
+    Error,
+    /// The property is ignored, i.e. it isn't registered
+    Warning,
+}
+
+/// The problem found while parsing the `ess` source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EssDiagnostic {
+    pub severity: EssSeverity,
+    pub message: String,
+    /// The asset path of the stylesheet, if it was loaded
+    pub path: Option<String>,
+    pub location: EssLocation,
+    /// The source line with the caret under the `location`
+    pub snippet: String,
+}
+
+impl EssDiagnostic {
+    pub(crate) fn new(
+        severity: EssSeverity,
+        message: String,
+        source: &str,
+        path: Option<&str>,
+        location: EssLocation,
+    ) -> EssDiagnostic {
+        let line = source
+            .lines()
+            .nth(location.line.saturating_sub(1) as usize)
+            .unwrap_or_default();
+        let number = location.line.to_string();
+        let gutter = " ".repeat(number.len());
+        let offset: String = line
+            .chars()
+            .take(location.column.saturating_sub(1) as usize)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let snippet = format!("{gutter} |\n{number} | {line}\n{gutter} | {offset}^");
+        EssDiagnostic {
+            severity,
+            message,
+            path: path.map(str::to_string),
+            location,
+            snippet,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == EssSeverity::Error
+    }
+
+    /// Writes the diagnostic to the log.
+    pub fn log(&self) {
+        match self.severity {
+            EssSeverity::Error => error!("{self}"),
+            EssSeverity::Warning => warn!("{self}"),
+        }
+    }
+}
+
+impl Display for EssDiagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let path = self.path.as_deref().unwrap_or("<ess>");
+        writeln!(f, "{}", self.message)?;
+        writeln!(f, "  --> {path}:{}", self.location)?;
+        write!(f, "{}", self.snippet)
+    }
+}
+
+/// Sent each time the stylesheet with the parse problems is added or
+/// modified, so the problems can be shown to the user (in the overlay
+/// for example) instead of being buried in the logs:
+/// ```rust
+/// # use belly_core::build::*;
+/// # use bevy::prelude::*;
+/// fn report(mut events: EventReader<EssDiagnostics>) {
+///     for event in events.read() {
+///         for diagnostic in event.diagnostics.iter().filter(|d| d.is_error()) {
+///             println!("{diagnostic}");
+///         }
+///     }
+/// }
+/// ```
+#[derive(Event, Debug, Clone)]
+pub struct EssDiagnostics {
+    pub stylesheet: AssetId<StyleSheet>,
+    pub diagnostics: Vec<EssDiagnostic>,
+}
@@ -1,10 +1,14 @@
 mod defaults;
+mod diagnostics;
 mod parser;
 pub mod property;
 mod selector;
+mod theme;
 
+pub use self::defaults::Defaults;
+pub use self::diagnostics::{EssDiagnostic, EssDiagnostics, EssLocation, EssSeverity};
 pub use self::parser::StyleSheetParser;
-use crate::{element::Elements, ess::defaults::Defaults};
+use crate::element::{clear_restyle_system, Elements};
 use anyhow::Error;
 use bevy::{
     asset::{io::Reader, AssetLoader, AsyncReadExt},
@@ -15,6 +19,7 @@ use bevy::{
 };
 pub use property::*;
 pub use selector::*;
+pub use theme::{RegisterTheme, Theme, Themes};
 use smallvec::SmallVec;
 use std::ops::Deref;
 use tagstr::Tag;
@@ -28,7 +33,7 @@ impl Plugin for EssPlugin {
         app.init_resource::<Styles>();
 
         // TODO: may be desabled with feature
-        app.insert_resource(Defaults::default());
+        app.init_resource::<Defaults>();
         app.add_systems(Startup, crate::ess::defaults::setup_defaults);
 
         app.init_asset::<StyleSheet>();
@@ -44,9 +49,18 @@ impl Plugin for EssPlugin {
             validator,
             extractor,
         });
+        app.add_event::<EssDiagnostics>();
+        app.add_event::<StylesReloaded>();
         app.add_systems(Update, process_styles_system);
+        app.add_systems(PostUpdate, clear_restyle_system.after(ApplyStyleProperties));
+        app.init_resource::<Themes>();
+        app.add_systems(Update, theme::resolve_themes_system.after(process_styles_system));
         app.add_plugins(property::PropertyPlugin);
         app.add_plugins(bevy_stylebox::StyleboxPlugin);
+        app.add_plugins(property::impls::gradient::GradientPlugin);
+        app.add_plugins(property::impls::bitmap_font::BitmapFontPlugin);
+        #[cfg(feature = "backdrop-blur")]
+        app.add_plugins(property::impls::backdrop_blur::BackdropBlurPlugin);
 
         // app.register_property::<impls::BackgroundColorProperty>();
         // app.register_property::<impls::ScaleProperty>();
@@ -81,17 +95,19 @@ impl AssetLoader for EssLoader {
         &'a self,
         reader: &'a mut Reader,
         _: &'a Self::Settings,
-        _: &'a mut bevy::asset::LoadContext,
+        load_context: &'a mut bevy::asset::LoadContext,
     ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
         Box::pin(async move {
             let mut source = String::new();
             reader.read_to_string(&mut source).await.unwrap();
             let parser = StyleSheetParser::new(self.validator.clone(), self.extractor.clone());
-            let rules = parser.parse(source.as_str());
+            let path = load_context.path().to_string_lossy();
+            let (rules, diagnostics) = parser.parse_with_diagnostics(&source, Some(&path));
             let mut stylesheet = StyleSheet::default();
             for rule in rules {
                 stylesheet.add_rule(rule)
             }
+            stylesheet.diagnostics = diagnostics;
             // load_context.set_default_asset(LoadedAsset::new(stylesheet));
             Ok(stylesheet)
         })
@@ -103,6 +119,25 @@ impl AssetLoader for EssLoader {
 pub struct StyleSheet {
     weight: usize,
     rules: Vec<StyleRule>,
+    theme_generation: Option<usize>,
+    /// The problems found while parsing the stylesheet
+    pub diagnostics: Vec<EssDiagnostic>,
+    diagnostics_reported: bool,
+    /// The weight is set and the elements are invalidated, the following
+    /// `Modified` events are caused by the processing itself
+    processed: bool,
+}
+
+/// Sent when the modified stylesheet (the `.ess` asset changed on disk)
+/// is applied. The hot reload restyles only the elements matched by the
+/// old and the new rules, without invalidating them: the widget state,
+/// focus and scroll positions are kept.
+#[derive(Event, Debug, Clone)]
+pub struct StylesReloaded {
+    pub stylesheet: AssetId<StyleSheet>,
+    /// The number of the elements matched by the old and the new rules,
+    /// their descendants are restyled as well
+    pub restyled: usize,
 }
 
 unsafe impl Send for StyleSheet {}
@@ -123,8 +158,9 @@ impl Command for ParseCommand {
         let extractor = world.resource::<PropertyExtractor>().clone();
         let validator = world.resource::<PropertyTransformer>().clone();
         let parser = StyleSheetParser::new(validator, extractor);
-        let rules = parser.parse(&self.source);
-        let stylesheet = StyleSheet::new(rules);
+        let (rules, diagnostics) = parser.parse_with_diagnostics(&self.source, None);
+        let mut stylesheet = StyleSheet::new(rules);
+        stylesheet.diagnostics = diagnostics;
         let mut styles = world.resource_mut::<Styles>();
         let mut assets = world.resource_mut::<Assets<StyleSheet>>();
         let handle = assets.add(stylesheet);
@@ -205,6 +241,11 @@ impl StyleSheet {
         self.rules.push(rule);
     }
 
+    /// Returns `true` if any of the rules refers to the theme tokens.
+    pub fn is_themed(&self) -> bool {
+        self.rules.iter().any(|r| !r.themed.is_empty())
+    }
+
     pub(crate) fn extra_weight(&self) -> usize {
         self.weight
     }
@@ -230,6 +271,16 @@ pub struct StyleRule {
     pub selector: Selector,
     // pub properties: HashMap<Tag, StyleProperty>,
     pub properties: HashMap<Tag, PropertyValue>,
+    /// The properties with `theme()` values, resolved into the `properties`
+    /// each time the active theme changes.
+    pub themed: HashMap<Tag, StyleProperty>,
+    /// The location of the rule in the stylesheet source
+    pub location: EssLocation,
+    /// The locations of the property declarations, the properties
+    /// extracted from the compound one share its location
+    pub property_locations: HashMap<Tag, EssLocation>,
+    /// The declared values as they are written in the source
+    pub declarations: HashMap<Tag, StyleProperty>,
 }
 
 #[derive(Default, Resource)]
@@ -262,17 +313,37 @@ fn process_styles_system(
     mut styles: ResMut<Styles>,
     mut assets: ResMut<Assets<StyleSheet>>,
     mut events: EventReader<AssetEvent<StyleSheet>>,
+    mut diagnostics: EventWriter<EssDiagnostics>,
+    mut reloads: EventWriter<StylesReloaded>,
     mut elements: Elements,
+    mut sheet_selectors: Local<HashMap<AssetId<StyleSheet>, Vec<Selector>>>,
     defaults: Res<Defaults>,
 ) {
-    let mut styles_changed = false;
+    // the selectors of the added, removed and changed rules
+    let mut changed: Vec<Selector> = vec![];
     for event in events.read() {
-        styles_changed = true;
         match event {
-            AssetEvent::Removed { id: _ } => styles_changed = true,
+            AssetEvent::Removed { id } => {
+                changed.extend(sheet_selectors.remove(id).into_iter().flatten());
+            }
             AssetEvent::Added { id }
             | AssetEvent::Modified { id }
             | AssetEvent::LoadedWithDependencies { id } => {
+                let Some(sheet) = assets.get(*id) else {
+                    continue;
+                };
+                if sheet.processed {
+                    continue;
+                }
+                if !sheet.diagnostics_reported && !sheet.diagnostics.is_empty() {
+                    let sheet = assets.get_mut(*id).unwrap();
+                    sheet.diagnostics_reported = true;
+                    sheet.diagnostics.iter().for_each(EssDiagnostic::log);
+                    diagnostics.send(EssDiagnostics {
+                        stylesheet: *id,
+                        diagnostics: sheet.diagnostics.clone(),
+                    });
+                }
                 if let Some(handle) = asset_server.get_id_handle(*id) {
                     if handle == defaults.style_sheet {
                         if assets.get(*id).unwrap().extra_weight() != 0 {
@@ -286,11 +357,85 @@ fn process_styles_system(
                         }
                     }
                 }
+                let sheet = assets.get_mut(*id).unwrap();
+                sheet.processed = true;
+                let selectors: Vec<_> = sheet.iter().map(|rule| rule.selector.clone()).collect();
+                match sheet_selectors.insert(*id, selectors.clone()) {
+                    // the known stylesheet is replaced by the reloaded one:
+                    // restyle the elements keeping them untouched
+                    Some(mut previous) => {
+                        previous.extend(selectors);
+                        let restyled = elements.restyle_matching(&previous);
+                        reloads.send(StylesReloaded {
+                            stylesheet: *id,
+                            restyled,
+                        });
+                    }
+                    None => changed.extend(selectors),
+                }
             },
             _ => { info!("Unused") }
         }
     }
-    if styles_changed {
-        elements.invalidate_all();
+    if !changed.is_empty() {
+        elements.invalidate_matching(&changed);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::element::{Element, ElementIdIndex, InvalidateElement, Restyle};
+    use crate::tag;
+
+    #[test]
+    fn reload_restyles_without_invalidation() {
+        let mut properties = App::new();
+        properties.add_plugins(PropertyPlugin);
+        let parser = StyleSheetParser::new(
+            properties.world.resource::<PropertyTransformer>().clone(),
+            properties.world.resource::<PropertyExtractor>().clone(),
+        );
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()));
+        app.init_asset::<StyleSheet>();
+        app.init_resource::<ElementIdIndex>();
+        app.init_resource::<Styles>();
+        app.init_resource::<Defaults>();
+        app.add_event::<EssDiagnostics>();
+        app.add_event::<StylesReloaded>();
+        app.add_systems(Update, process_styles_system);
+
+        let mut panel = Element::default();
+        panel.names.push(tag!("div"));
+        panel.classes.insert(tag!("panel"));
+        let panel = app.world.spawn(panel).id();
+        let sheet = StyleSheet::new(parser.parse(".panel { width: 10px; }"));
+        let handle = app.world.resource_mut::<Assets<StyleSheet>>().add(sheet);
+        // the asset events are sent after the `Update`, they are processed
+        // by the next frame
+        app.update();
+        app.update();
+        assert!(app.world.get::<InvalidateElement>(panel).is_some());
+        app.world.entity_mut(panel).remove::<InvalidateElement>();
+
+        // the processing itself doesn't cause the reload
+        app.update();
+        assert!(app.world.resource::<Events<StylesReloaded>>().is_empty());
+
+        let sheet = StyleSheet::new(parser.parse(".panel { width: 20px; }"));
+        app.world
+            .resource_mut::<Assets<StyleSheet>>()
+            .insert(&handle, sheet);
+        app.update();
+        app.update();
+        assert!(app.world.get::<InvalidateElement>(panel).is_none());
+        assert!(app.world.get::<Restyle>(panel).is_some());
+        let reloads = app.world.resource::<Events<StylesReloaded>>();
+        let mut reader = reloads.get_reader();
+        let reload = reader.read(reloads).last().unwrap();
+        assert_eq!(reload.stylesheet, handle.id());
+        assert_eq!(reload.restyled, 1);
     }
 }
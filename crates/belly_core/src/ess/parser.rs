@@ -1,5 +1,6 @@
-use bevy::log::*;
+use bevy::utils::HashMap;
 use smallvec::{smallvec, SmallVec};
+use std::cell::RefCell;
 
 use cssparser::*;
 use tagstr::{AsTag, Tag};
@@ -9,6 +10,8 @@ use crate::{
     ess::SelectorElement, ess::StyleProperty, ess::StyleRule, ElementsError,
 };
 
+use super::diagnostics::{EssDiagnostic, EssLocation, EssSeverity};
+use super::theme::is_themed;
 use super::StylePropertyFunction;
 use super::StylePropertyToken;
 
@@ -24,28 +27,52 @@ impl StyleSheetParser {
             transformer,
         }
     }
+
+    /// Parses the `content` and writes the problems to the log.
     pub fn parse(&self, content: &str) -> SmallVec<[StyleRule; 8]> {
+        let (rules, diagnostics) = self.parse_with_diagnostics(content, None);
+        diagnostics.iter().for_each(EssDiagnostic::log);
+        rules
+    }
+
+    /// Parses the `content` loaded from the `path` and returns the rules
+    /// along with the problems found. The invalid rules and properties are
+    /// dropped, the rest of the stylesheet is still parsed.
+    pub fn parse_with_diagnostics(
+        &self,
+        content: &str,
+        path: Option<&str>,
+    ) -> (SmallVec<[StyleRule; 8]>, Vec<EssDiagnostic>) {
+        let rules_parser = RulesParser {
+            parser: self,
+            content,
+            path,
+            diagnostics: Default::default(),
+        };
         let mut input = ParserInput::new(content);
         let mut parser = Parser::new(&mut input);
-        RuleListParser::new_for_stylesheet(&mut parser, self)
+        let rules = RuleListParser::new_for_stylesheet(&mut parser, &rules_parser)
             .into_iter()
             .filter_map(|result| match result {
                 Ok(rule) => Some(rule),
                 Err((err, rule)) => {
-                    error!(
-                        "Failed to parse rule: {}. Error: {}",
-                        rule,
+                    let location = err.location;
+                    let message = format!(
+                        "Failed to parse rule `{}`: {}",
+                        rule.trim(),
                         format_error(err)
                     );
+                    rules_parser.report(EssSeverity::Error, message, location);
                     None
                 }
             })
-            .collect()
+            .collect();
+        (rules, rules_parser.diagnostics.into_inner())
     }
 }
 
 fn format_error(error: ParseError<ElementsError>) -> String {
-    let error_description = match error.kind {
+    match error.kind {
         cssparser::ParseErrorKind::Basic(b) => match b {
             cssparser::BasicParseErrorKind::UnexpectedToken(token) => {
                 format!("Unexpected token {}", token.to_css_string())
@@ -60,12 +87,23 @@ fn format_error(error: ParseError<ElementsError>) -> String {
             cssparser::BasicParseErrorKind::QualifiedRuleInvalid => "Invalid rule".to_string(),
         },
         cssparser::ParseErrorKind::Custom(c) => c.to_string(),
-    };
+    }
+}
 
-    format!(
-        "{} at {}:{}",
-        error_description, error.location.line, error.location.column
-    )
+/// Parses the rules of the single source and collects the diagnostics.
+struct RulesParser<'a> {
+    parser: &'a StyleSheetParser,
+    content: &'a str,
+    path: Option<&'a str>,
+    diagnostics: RefCell<Vec<EssDiagnostic>>,
+}
+
+impl<'a> RulesParser<'a> {
+    fn report(&self, severity: EssSeverity, message: String, location: impl Into<EssLocation>) {
+        let diagnostic =
+            EssDiagnostic::new(severity, message, self.content, self.path, location.into());
+        self.diagnostics.borrow_mut().push(diagnostic);
+    }
 }
 
 #[derive(Default)]
@@ -74,9 +112,10 @@ enum NextElement {
     Tag,
     Class,
     Attribute,
+    Part,
 }
 
-impl<'i> QualifiedRuleParser<'i> for &StyleSheetParser {
+impl<'i, 'a> QualifiedRuleParser<'i> for &RulesParser<'a> {
     type Prelude = Selector;
     type QualifiedRule = StyleRule;
     type Error = ElementsError;
@@ -103,6 +142,7 @@ impl<'i> QualifiedRuleParser<'i> for &StyleSheetParser {
                         NextElement::Attribute => {
                             elements.insert(0, SelectorElement::State(v.to_string().as_tag()))
                         }
+                        NextElement::Part => super::selector::insert_part(&mut elements, v),
                     };
                     next = NextElement::Tag;
                 }
@@ -121,20 +161,53 @@ impl<'i> QualifiedRuleParser<'i> for &StyleSheetParser {
                     }
                     elements.insert(0, SelectorElement::AnyChild);
                 }
+                SquareBracketBlock => {
+                    let attribute = input.parse_nested_block(|p| {
+                        super::selector::parse_attribute::<()>(p)
+                            .map_err(|_| p.new_custom_error(ElementsError::InvalidSelector))
+                    })?;
+                    elements.insert(0, attribute);
+                }
                 Delim(c) if *c == '.' => next = NextElement::Class,
                 Delim(c) if *c == '*' => elements.insert(0, SelectorElement::Any),
-                Delim(c) if *c == '>' => {
+                Delim(c) if *c == '>' || *c == '+' => {
+                    let combinator = match *c {
+                        '>' => SelectorElement::DirectChild,
+                        _ => SelectorElement::AdjacentSibling,
+                    };
                     if let Some(token) = elements.first() {
                         if token.is_any_child() {
-                            elements[0] = SelectorElement::DirectChild;
+                            elements[0] = combinator;
                             continue;
                         }
                     }
-                    elements.insert(0, SelectorElement::DirectChild);
+                    elements.insert(0, combinator);
+                }
+                Colon => {
+                    next = match next {
+                        NextElement::Attribute => NextElement::Part,
+                        _ => NextElement::Attribute,
+                    }
+                }
+                Function(name)
+                    if matches!(next, NextElement::Attribute) && name.as_ref() == "not" =>
+                {
+                    let not = input.parse_nested_block(|p| {
+                        super::selector::parse_not::<()>(p)
+                            .map_err(|_| p.new_custom_error(ElementsError::InvalidSelector))
+                    })?;
+                    elements.insert(0, not);
+                    next = NextElement::Tag;
+                }
+                Function(name) if matches!(next, NextElement::Part) && name.as_ref() == "part" => {
+                    let part = input.parse_nested_block(|p| {
+                        p.expect_ident_cloned()
+                            .map_err(|_| p.new_custom_error(ElementsError::InvalidSelector))
+                    })?;
+                    super::selector::insert_part(&mut elements, &part);
+                    next = NextElement::Tag;
                 }
-                Colon => next = NextElement::Attribute,
                 _ => {
-                    warn!("Unexpected token: {:?}", token);
                     let token = token.to_css_string();
                     return Err(input.new_custom_error(ElementsError::UnexpectedToken(token)));
                 }
@@ -162,36 +235,66 @@ impl<'i> QualifiedRuleParser<'i> for &StyleSheetParser {
     fn parse_block<'t>(
         &mut self,
         prelude: Self::Prelude,
-        _start: &cssparser::ParserState,
+        start: &cssparser::ParserState,
         input: &mut Parser<'i, 't>,
     ) -> Result<Self::QualifiedRule, ParseError<'i, Self::Error>> {
         let mut rule = StyleRule {
             selector: prelude,
             properties: Default::default(),
+            themed: Default::default(),
+            location: start.source_location().into(),
+            property_locations: Default::default(),
+            declarations: Default::default(),
         };
 
         for property in DeclarationListParser::new(input, PropertyParser) {
-            match property {
-                Ok((name, property)) => {
-                    if self.extractor.is_compound_property(name) {
-                        let extracted = match self.extractor.extract(name, Variant::style(property))
-                        {
-                            Err(e) => return Err(input.new_custom_error(e)),
-                            Ok(extracted) => extracted,
-                        };
+            let (name, property, location) = match property {
+                Ok(declaration) => declaration,
+                Err((err, declaration)) => {
+                    let location = err.location;
+                    let message = format!(
+                        "Invalid declaration `{}`: {}",
+                        declaration.trim(),
+                        format_error(err)
+                    );
+                    self.report(EssSeverity::Error, message, location);
+                    continue;
+                }
+            };
+            let declared = property.clone();
+            let extracted = if is_themed(&property) {
+                rule.themed.insert(name, property);
+                Ok([(name, location)].into_iter().collect())
+            } else if self.parser.extractor.is_compound_property(name) {
+                self.parser
+                    .extractor
+                    .extract(name, Variant::style(property))
+                    .map(|extracted| {
+                        let mut locations = HashMap::default();
                         for (name, property) in extracted {
                             rule.properties.insert(name, property);
+                            locations.insert(name, location);
                         }
-                    } else {
-                        match self.transformer.transform(name, Variant::style(property)) {
-                            Ok(variant) => {
-                                rule.properties.insert(name, variant);
-                            }
-                            Err(e) => return Err(input.new_custom_error(e)),
-                        }
-                    }
+                        locations
+                    })
+            } else {
+                self.parser
+                    .transformer
+                    .transform(name, Variant::style(property))
+                    .map(|property| {
+                        rule.properties.insert(name, property);
+                        [(name, location)].into_iter().collect()
+                    })
+            };
+            match extracted {
+                Ok(locations) => {
+                    rule.property_locations.extend(locations);
+                    rule.declarations.insert(name, declared);
                 }
-                Err((err, a)) => println!("Failed: {:?} ({})", err, a),
+                Err(e @ ElementsError::UnsupportedProperty(_)) => {
+                    self.report(EssSeverity::Warning, e.to_string(), location)
+                }
+                Err(e) => self.report(EssSeverity::Error, format!("`{name}`: {e}"), location),
             }
         }
 
@@ -199,7 +302,7 @@ impl<'i> QualifiedRuleParser<'i> for &StyleSheetParser {
     }
 }
 
-impl<'i> AtRuleParser<'i> for &StyleSheetParser {
+impl<'i, 'a> AtRuleParser<'i> for &RulesParser<'a> {
     type Prelude = ();
     type AtRule = StyleRule;
     type Error = ElementsError;
@@ -208,7 +311,7 @@ impl<'i> AtRuleParser<'i> for &StyleSheetParser {
 struct PropertyParser;
 
 impl<'i> DeclarationParser<'i> for PropertyParser {
-    type Declaration = (Tag, StyleProperty);
+    type Declaration = (Tag, StyleProperty, EssLocation);
 
     type Error = ElementsError;
 
@@ -217,16 +320,19 @@ impl<'i> DeclarationParser<'i> for PropertyParser {
         name: cssparser::CowRcStr<'i>,
         parser: &mut Parser<'i, 't>,
     ) -> Result<Self::Declaration, ParseError<'i, ElementsError>> {
+        parser.skip_whitespace();
+        let location = parser.current_source_location().into();
         Ok((
             name.to_string().as_tag(),
             StyleProperty(parse_values(parser)?),
+            location,
         ))
     }
 }
 
 impl<'i> AtRuleParser<'i> for PropertyParser {
     type Prelude = ();
-    type AtRule = (Tag, StyleProperty);
+    type AtRule = (Tag, StyleProperty, EssLocation);
     type Error = ElementsError;
 }
 
@@ -370,6 +476,11 @@ mod tests {
             // StyleSheetParser::parse(content, self.validator.clone(), self.extractor.clone())
         }
 
+        fn diagnostics(&self, content: &str) -> Vec<EssDiagnostic> {
+            let parser = StyleSheetParser::new(self.transformer.clone(), self.extractor.clone());
+            parser.parse_with_diagnostics(content, Some("test.ess")).1
+        }
+
         // fn
     }
 
@@ -733,4 +844,28 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn report_diagnostics() {
+        let parser = TestParser::new();
+        let source = "a {\n    b: c;\n    unknown: c;\n}\na ~ b {\n    c: d;\n}\n";
+        let rules = parser.parse(source);
+        assert_eq!(rules.len(), 1, "Should drop the rule with the invalid selector");
+        assert_eq!(rules[0].location, EssLocation { line: 1, column: 1 });
+        assert_eq!(
+            rules[0].property_locations.get(&"b".as_tag()),
+            Some(&EssLocation { line: 2, column: 8 })
+        );
+
+        let diagnostics = parser.diagnostics(source);
+        assert_eq!(diagnostics.len(), 2, "{diagnostics:?}");
+        let unknown = &diagnostics[0];
+        assert_eq!(unknown.severity, EssSeverity::Warning);
+        assert_eq!(unknown.location.line, 3);
+        assert_eq!(unknown.path.as_deref(), Some("test.ess"));
+        assert_eq!(unknown.snippet, "  |\n3 |     unknown: c;\n  |              ^");
+        let selector = &diagnostics[1];
+        assert_eq!(selector.severity, EssSeverity::Error);
+        assert_eq!(selector.location.line, 5);
+    }
 }
@@ -31,6 +31,79 @@ impl ColorFromHexExtension for Color {
     }
 }
 
+/// Access to the HSL components and the relative luminance of the [`Color`].
+/// Setters keep the alpha and produce the sRGB color.
+pub trait ColorHslExtension {
+    /// Hue in degrees, `0.0..360.0`
+    fn hue(&self) -> f32;
+    fn set_hue(&mut self, hue: f32);
+    fn saturation(&self) -> f32;
+    fn set_saturation(&mut self, saturation: f32);
+    fn lightness(&self) -> f32;
+    fn set_lightness(&mut self, lightness: f32);
+    /// Relative luminance: `0.2126 * R + 0.7152 * G + 0.0722 * B` of linear RGB.
+    fn luminance(&self) -> f32;
+    fn set_luminance(&mut self, luminance: f32);
+}
+
+fn hsla(color: &Color) -> [f32; 4] {
+    match color.as_hsla() {
+        Color::Hsla {
+            hue,
+            saturation,
+            lightness,
+            alpha,
+        } => [hue, saturation, lightness, alpha],
+        _ => unreachable!("Color::as_hsla() always returns Color::Hsla"),
+    }
+}
+
+fn set_hsla(color: &mut Color, update: impl FnOnce(&mut [f32; 4])) {
+    let mut components = hsla(color);
+    update(&mut components);
+    let [hue, saturation, lightness, alpha] = components;
+    *color = Color::hsla(hue, saturation, lightness, alpha).as_rgba();
+}
+
+impl ColorHslExtension for Color {
+    fn hue(&self) -> f32 {
+        hsla(self)[0]
+    }
+    fn set_hue(&mut self, hue: f32) {
+        set_hsla(self, |c| c[0] = hue.rem_euclid(360.));
+    }
+    fn saturation(&self) -> f32 {
+        hsla(self)[1]
+    }
+    fn set_saturation(&mut self, saturation: f32) {
+        set_hsla(self, |c| c[1] = saturation.clamp(0., 1.));
+    }
+    fn lightness(&self) -> f32 {
+        hsla(self)[2]
+    }
+    fn set_lightness(&mut self, lightness: f32) {
+        set_hsla(self, |c| c[2] = lightness.clamp(0., 1.));
+    }
+    fn luminance(&self) -> f32 {
+        let [r, g, b, _] = self.as_linear_rgba_f32();
+        0.2126 * r + 0.7152 * g + 0.0722 * b
+    }
+    fn set_luminance(&mut self, luminance: f32) {
+        let luminance = luminance.clamp(0., 1.);
+        let current = self.luminance();
+        let [r, g, b, a] = self.as_linear_rgba_f32();
+        let [r, g, b] = if current > 0. {
+            // scaling keeps the hue, channels are clamped for the
+            // luminance not reachable with the same hue
+            let k = luminance / current;
+            [(r * k).min(1.), (g * k).min(1.), (b * k).min(1.)]
+        } else {
+            [luminance; 3]
+        };
+        *self = Color::rgba_linear(r, g, b, a).as_rgba();
+    }
+}
+
 pub(super) fn parse_hex_color(hex: &str) -> Result<Color, ElementsError> {
     let color = cssparser::Color::parse_hash(hex.as_bytes()).map_err(|_| {
         ElementsError::InvalidPropertyValue(format!("Can't parse color from '{hex}'"))
@@ -216,3 +289,23 @@ pub(super) fn parse_named_color(name: &str) -> Option<Color> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn hsl_and_luminance() {
+        let mut color = Color::rgb(1., 0., 0.);
+        assert!((color.hue() - 0.).abs() < 0.01);
+        color.set_hue(120.);
+        assert!((color.g() - 1.).abs() < 0.01 && color.r() < 0.01);
+        color.set_lightness(1.);
+        assert!((color.r() - 1.).abs() < 0.01 && (color.b() - 1.).abs() < 0.01);
+
+        let mut color = Color::rgb(0.2, 0.2, 0.2);
+        color.set_luminance(0.5);
+        assert!((color.luminance() - 0.5).abs() < 0.01);
+        assert!((Color::WHITE.luminance() - 1.).abs() < 0.01);
+    }
+}
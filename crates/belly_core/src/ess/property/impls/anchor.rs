@@ -0,0 +1,351 @@
+use crate::ess::{PropertyParser, StyleProperty, StylePropertyMethods, StylePropertyToken};
+use crate::style_property;
+use crate::ElementsError;
+use bevy::prelude::*;
+
+/// The screen insets (in logical pixels) the anchored elements keep away
+/// from: notches, rounded corners, system bars. `bevy` doesn't report them
+/// yet, so the platform specific code should fill this resource, the
+/// insets are zero by default.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq)]
+pub struct SafeAreaInsets {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+/// The point of the screen the element is attached to,
+/// defined by `anchor` property.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AnchorPoint {
+    #[default]
+    None,
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AnchorAlign {
+    Start,
+    Center,
+    End,
+}
+
+impl AnchorPoint {
+    fn align(&self) -> Option<(AnchorAlign, AnchorAlign)> {
+        use AnchorAlign::*;
+        match self {
+            AnchorPoint::None => None,
+            AnchorPoint::TopLeft => Some((Start, Start)),
+            AnchorPoint::Top => Some((Center, Start)),
+            AnchorPoint::TopRight => Some((End, Start)),
+            AnchorPoint::Left => Some((Start, Center)),
+            AnchorPoint::Center => Some((Center, Center)),
+            AnchorPoint::Right => Some((End, Center)),
+            AnchorPoint::BottomLeft => Some((Start, End)),
+            AnchorPoint::Bottom => Some((Center, End)),
+            AnchorPoint::BottomRight => Some((End, End)),
+        }
+    }
+}
+
+impl TryFrom<&StyleProperty> for AnchorPoint {
+    type Error = ElementsError;
+    fn try_from(value: &StyleProperty) -> Result<Self, Self::Error> {
+        let err = || {
+            ElementsError::InvalidPropertyValue(format!(
+                "Expected none|top-left|top|top-right|left|center|right|bottom-left|bottom|bottom-right, got `{}`",
+                value.to_string()
+            ))
+        };
+        if value.len() != 1 {
+            return Err(err());
+        }
+        let Some(StylePropertyToken::Identifier(ident)) = value.first() else {
+            return Err(err());
+        };
+        match ident.as_str() {
+            "none" => Ok(AnchorPoint::None),
+            "top-left" => Ok(AnchorPoint::TopLeft),
+            "top" => Ok(AnchorPoint::Top),
+            "top-right" => Ok(AnchorPoint::TopRight),
+            "left" => Ok(AnchorPoint::Left),
+            "center" => Ok(AnchorPoint::Center),
+            "right" => Ok(AnchorPoint::Right),
+            "bottom-left" => Ok(AnchorPoint::BottomLeft),
+            "bottom" => Ok(AnchorPoint::Bottom),
+            "bottom-right" => Ok(AnchorPoint::BottomRight),
+            _ => Err(err()),
+        }
+    }
+}
+
+/// Collected values of `anchor`, `anchor-offset` and `safe-area` properties.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct Anchored {
+    pub point: AnchorPoint,
+    pub offset: Vec2,
+    pub safe_area: bool,
+}
+
+impl Default for Anchored {
+    fn default() -> Self {
+        Anchored {
+            point: AnchorPoint::None,
+            offset: Vec2::ZERO,
+            safe_area: true,
+        }
+    }
+}
+
+impl Anchored {
+    /// Writes the absolute position of the anchored element to the `style`.
+    /// The offset moves the element away from the anchored edges, or right
+    /// and down along the centered axis.
+    pub fn apply(&self, insets: &SafeAreaInsets, style: &mut Style) {
+        let Some((horizontal, vertical)) = self.point.align() else {
+            return;
+        };
+        let insets = if self.safe_area {
+            *insets
+        } else {
+            SafeAreaInsets::default()
+        };
+        let (left, right, margin_left, margin_right) =
+            resolve_axis(horizontal, self.offset.x, insets.left, insets.right);
+        let (top, bottom, margin_top, margin_bottom) =
+            resolve_axis(vertical, self.offset.y, insets.top, insets.bottom);
+        let update = |target: &mut Val, value: Val| {
+            if *target != value {
+                *target = value;
+            }
+        };
+        if style.position_type != PositionType::Absolute {
+            style.position_type = PositionType::Absolute;
+        }
+        update(&mut style.left, left);
+        update(&mut style.right, right);
+        update(&mut style.top, top);
+        update(&mut style.bottom, bottom);
+        update(&mut style.margin.left, margin_left);
+        update(&mut style.margin.right, margin_right);
+        update(&mut style.margin.top, margin_top);
+        update(&mut style.margin.bottom, margin_bottom);
+    }
+}
+
+fn resolve_axis(align: AnchorAlign, offset: f32, start: f32, end: f32) -> (Val, Val, Val, Val) {
+    match align {
+        AnchorAlign::Start => (Val::Px(start + offset), Val::Auto, Val::Px(0.), Val::Px(0.)),
+        AnchorAlign::End => (Val::Auto, Val::Px(end + offset), Val::Px(0.), Val::Px(0.)),
+        AnchorAlign::Center => (
+            Val::Px(start + offset),
+            Val::Px(end - offset),
+            Val::Auto,
+            Val::Auto,
+        ),
+    }
+}
+
+fn update_anchored<F: FnOnce(&mut Anchored) + Send + 'static>(
+    commands: &mut Commands,
+    entity: Entity,
+    update: F,
+) {
+    commands.add(move |world: &mut World| {
+        let Some(mut entity) = world.get_entity_mut(entity) else {
+            return;
+        };
+        if let Some(mut anchored) = entity.get_mut::<Anchored>() {
+            update(&mut anchored);
+        } else {
+            let mut anchored = Anchored::default();
+            update(&mut anchored);
+            entity.insert(anchored);
+        }
+    });
+}
+
+/// <!-- @property-type=$anchor -->
+/// Screen anchor: `none`, `top-left`, `top`, `top-right`, `left`, `center`,
+/// `right`, `bottom-left`, `bottom` or `bottom-right`.
+pub struct AnchorPointParser;
+impl PropertyParser<AnchorPoint> for AnchorPointParser {
+    fn parse(value: &StyleProperty) -> Result<AnchorPoint, ElementsError> {
+        AnchorPoint::try_from(value)
+    }
+}
+
+/// <!-- @property-type=$offset -->
+/// Offset: one or two `px` suffixed values (or numbers), the single
+/// value is used for both axes (`16px`, `16px 8px`).
+pub struct OffsetParser;
+impl PropertyParser<Vec2> for OffsetParser {
+    fn parse(value: &StyleProperty) -> Result<Vec2, ElementsError> {
+        let px = |token: &StylePropertyToken| match token {
+            StylePropertyToken::Number(val) => Ok(val.to_float()),
+            StylePropertyToken::Dimension(val, unit) if unit == "px" => Ok(val.to_float()),
+            token => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected $px, got `{}`",
+                token.to_string()
+            ))),
+        };
+        match value.len() {
+            1 => Ok(Vec2::splat(px(&value[0])?)),
+            2 => Ok(Vec2::new(px(&value[0])?, px(&value[1])?)),
+            _ => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected $px or $px $px, got `{}`",
+                value.to_string()
+            ))),
+        }
+    }
+}
+
+/// <!-- @property-type=$safearea -->
+/// Safe area mode: `inset` or `ignore`.
+pub struct SafeAreaParser;
+impl PropertyParser<bool> for SafeAreaParser {
+    fn parse(value: &StyleProperty) -> Result<bool, ElementsError> {
+        match value.first() {
+            Some(token) if value.len() == 1 && token.is_ident("inset") => Ok(true),
+            Some(token) if value.len() == 1 && token.is_ident("ignore") => Ok(false),
+            _ => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected inset|ignore, got `{}`",
+                value.to_string()
+            ))),
+        }
+    }
+}
+
+style_property! {
+    #[doc = " Attaches the element to the corner, the edge or the center of the"]
+    #[doc = " screen. Anchored elements are positioned absolutely, so the property"]
+    #[doc = " is meant for the root level HUD elements:"]
+    #[doc = " ```css"]
+    #[doc = " anchor: bottom-right;"]
+    #[doc = " ```"]
+    #[doc = " "]
+    #[doc = " The anchor overrides `position-type`, `left`, `right`, `top`, `bottom`"]
+    #[doc = " and the margins of the element. `none` leaves them untouched."]
+    #[doc = " <!-- @property-type=$anchor -->"]
+    #[doc = " <!-- @property-category=Layout Control -->"]
+    AnchorProperty("anchor") {
+        Default = "none";
+        Item = AnchorPoint;
+        Components = ();
+        Filters = With<Node>;
+        Parser = AnchorPointParser;
+        Apply = |value, _components, _assets, commands, entity| {
+            let value = *value;
+            update_anchored(commands, entity, move |a| a.point = value);
+        };
+    }
+}
+
+style_property! {
+    #[doc = " Moves the anchored element away from the anchored edges:"]
+    #[doc = " ```css"]
+    #[doc = " anchor-offset: 16px 16px;"]
+    #[doc = " ```"]
+    #[doc = " "]
+    #[doc = " Along the centered axis the offset moves the element right or down."]
+    #[doc = " <!-- @property-type=$offset -->"]
+    #[doc = " <!-- @property-category=Layout Control -->"]
+    AnchorOffsetProperty("anchor-offset") {
+        Default = "0px 0px";
+        Item = Vec2;
+        Components = ();
+        Filters = With<Node>;
+        Parser = OffsetParser;
+        Apply = |value, _components, _assets, commands, entity| {
+            let value = *value;
+            update_anchored(commands, entity, move |a| a.offset = value);
+        };
+    }
+}
+
+style_property! {
+    #[doc = " Specify if the anchored element keeps away from the screen notches"]
+    #[doc = " and system bars reported by `SafeAreaInsets` resource:"]
+    #[doc = " ```css"]
+    #[doc = " safe-area: ignore;"]
+    #[doc = " ```"]
+    #[doc = " "]
+    #[doc = " Supported values:"]
+    #[doc = " - `inset`: the insets are added to the anchor offset"]
+    #[doc = " - `ignore`: the element is anchored to the screen edges"]
+    #[doc = " <!-- @property-type=$safearea -->"]
+    #[doc = " <!-- @property-category=Layout Control -->"]
+    SafeAreaProperty("safe-area") {
+        Default = "inset";
+        Item = bool;
+        Components = ();
+        Filters = With<Node>;
+        Parser = SafeAreaParser;
+        Apply = |value, _components, _assets, commands, entity| {
+            let value = *value;
+            update_anchored(commands, entity, move |a| a.safe_area = value);
+        };
+    }
+}
+
+pub fn apply_anchors_system(
+    insets: Res<SafeAreaInsets>,
+    mut elements: Query<(Ref<Anchored>, &mut Style)>,
+) {
+    for (anchored, mut style) in elements.iter_mut() {
+        if !insets.is_changed() && !anchored.is_changed() {
+            continue;
+        }
+        anchored.apply(&insets, style.bypass_change_detection());
+        style.set_changed();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn anchor_positions_element() {
+        let p = StyleProperty::from_str("bottom-right").unwrap();
+        let point = AnchorPointParser::parse(&p).unwrap();
+        let p = StyleProperty::from_str("16px 8px").unwrap();
+        let offset = OffsetParser::parse(&p).unwrap();
+        assert_eq!(offset, Vec2::new(16., 8.));
+        assert!(AnchorPointParser::parse(&StyleProperty::from_str("corner").unwrap()).is_err());
+
+        let insets = SafeAreaInsets {
+            bottom: 20.,
+            ..default()
+        };
+        let mut anchored = Anchored {
+            point,
+            offset,
+            safe_area: true,
+        };
+        let mut style = Style::default();
+        anchored.apply(&insets, &mut style);
+        assert_eq!(style.position_type, PositionType::Absolute);
+        assert_eq!(style.right, Val::Px(16.));
+        assert_eq!(style.bottom, Val::Px(28.));
+        assert_eq!(style.left, Val::Auto);
+
+        anchored.safe_area = false;
+        anchored.point = AnchorPoint::Top;
+        anchored.apply(&insets, &mut style);
+        assert_eq!(style.left, Val::Px(16.));
+        assert_eq!(style.right, Val::Px(-16.));
+        assert_eq!(style.margin.left, Val::Auto);
+        assert_eq!(style.top, Val::Px(8.));
+    }
+}
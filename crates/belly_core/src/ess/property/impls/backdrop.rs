@@ -0,0 +1,95 @@
+use crate::ess::PropertyParser;
+use crate::ess::StyleProperty;
+use crate::ess::StylePropertyMethods;
+use crate::ess::StylePropertyToken;
+use crate::style_property;
+use crate::ElementsError;
+use bevy::prelude::*;
+
+/// The filter applied to the area behind the element, set by the
+/// `backdrop-filter` property. Rendered by the `BackdropBlurPlugin`
+/// when the `backdrop-blur` feature is enabled.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq)]
+pub struct BackdropFilter {
+    /// The blur radius in logical pixels, `0.` disables the filter
+    pub blur: f32,
+}
+
+/// <!-- @property-type=$backdrop -->
+/// Backdrop filter: `none` or the blur radius in pixels: `blur(8px)`.
+pub struct BackdropFilterParser;
+impl PropertyParser<f32> for BackdropFilterParser {
+    fn parse(prop: &StyleProperty) -> Result<f32, ElementsError> {
+        let err = || {
+            ElementsError::InvalidPropertyValue(format!(
+                "Expected none|blur($px), got `{}`",
+                prop.to_string()
+            ))
+        };
+        if prop.len() != 1 {
+            return Err(err());
+        }
+        match &prop[0] {
+            StylePropertyToken::Identifier(ident) if ident == "none" => Ok(0.),
+            StylePropertyToken::Function(func) if func.name == "blur" => {
+                match func.args.as_slice() {
+                    [StylePropertyToken::Dimension(val, unit)] if unit == "px" => {
+                        Ok(val.to_float().max(0.))
+                    }
+                    [StylePropertyToken::Number(val)] if val.to_float() == 0. => Ok(0.),
+                    _ => Err(err()),
+                }
+            }
+            _ => Err(err()),
+        }
+    }
+}
+
+style_property! {
+    #[doc = " Specify the filter applied to the area behind the element:"]
+    #[doc = " ```css"]
+    #[doc = " backdrop-filter: blur(8px);"]
+    #[doc = " backdrop-filter: none;"]
+    #[doc = " ```"]
+    #[doc = " "]
+    #[doc = " The blurred backdrop is tinted with the `background-color` of the"]
+    #[doc = " element. Only the camera image is blurred, the ui elements below"]
+    #[doc = " the element are not. The filter is drawn only when belly is built"]
+    #[doc = " with the `backdrop-blur` feature (enabled by default)."]
+    #[doc = " <!-- @property-type=$backdrop -->"]
+    #[doc = " <!-- @property-category=General -->"]
+    BackdropFilterProperty("backdrop-filter") {
+        Default = "none";
+        Item = f32;
+        Components = Option<&'static mut BackdropFilter>;
+        Filters = With<Node>;
+        Parser = BackdropFilterParser;
+        Apply = |value, filter, _assets, commands, entity| {
+            match filter {
+                Some(mut filter) if filter.blur != *value => filter.blur = *value,
+                Some(_) => {}
+                None if *value > 0. => {
+                    commands.entity(entity).insert(BackdropFilter { blur: *value });
+                }
+                None => {}
+            }
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn parse_backdrop_filter() {
+        let parse = |s: &str| BackdropFilterParser::parse(&StyleProperty::from_str(s).unwrap());
+        assert_eq!(parse("blur(8px)").unwrap(), 8.);
+        assert_eq!(parse("blur(0)").unwrap(), 0.);
+        assert_eq!(parse("none").unwrap(), 0.);
+        assert!(parse("blur(50%)").is_err());
+        assert!(parse("blur(8px) blur(2px)").is_err());
+        assert!(parse("8px").is_err());
+    }
+}
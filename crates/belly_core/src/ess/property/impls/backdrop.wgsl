@@ -0,0 +1,22 @@
+#import bevy_ui::ui_vertex_output::UiVertexOutput
+
+struct Backdrop {
+    tint: vec4<f32>,
+    opacity: f32,
+};
+
+@group(1) @binding(0)
+var<uniform> backdrop: Backdrop;
+@group(1) @binding(1)
+var backdrop_texture: texture_2d<f32>;
+@group(1) @binding(2)
+var backdrop_sampler: sampler;
+
+@fragment
+fn fragment(in: UiVertexOutput) -> @location(0) vec4<f32> {
+    // the blurred image covers the whole window
+    let uv = in.position.xy / vec2<f32>(textureDimensions(backdrop_texture));
+    let color = textureSample(backdrop_texture, backdrop_sampler, uv);
+    let tinted = mix(color.rgb, backdrop.tint.rgb, backdrop.tint.a);
+    return vec4<f32>(tinted, backdrop.opacity);
+}
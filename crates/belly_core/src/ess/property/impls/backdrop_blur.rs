@@ -0,0 +1,343 @@
+use super::backdrop::BackdropFilter;
+use super::opacity::ComputedOpacity;
+use bevy::asset::load_internal_asset;
+use bevy::core_pipeline::core_2d::graph::{Core2d, Node2d};
+use bevy::core_pipeline::core_3d::graph::{Core3d, Node3d};
+use bevy::core_pipeline::fullscreen_vertex_shader::fullscreen_shader_vertex_state;
+use bevy::ecs::query::QueryItem;
+use bevy::prelude::*;
+use bevy::render::extract_resource::{ExtractResource, ExtractResourcePlugin};
+use bevy::render::render_asset::{RenderAssetUsages, RenderAssets};
+use bevy::render::render_graph::{
+    NodeRunError, RenderGraphApp, RenderGraphContext, RenderLabel, ViewNode, ViewNodeRunner,
+};
+use bevy::render::render_resource::binding_types::{sampler, texture_2d, uniform_buffer};
+use bevy::render::render_resource::encase::UniformBuffer;
+use bevy::render::render_resource::*;
+use bevy::render::renderer::{RenderContext, RenderDevice};
+use bevy::render::texture::BevyDefault;
+use bevy::render::view::ViewTarget;
+use bevy::render::RenderApp;
+use bevy::ui::graph::NodeUi;
+use bevy::ui::UiSystem;
+use bevy::window::PrimaryWindow;
+
+const BLUR_SHADER_HANDLE: Handle<Shader> =
+    Handle::weak_from_u128(0x2b7e_91c4_0d53_4f8a_a61e_37c9_58d2_e04b);
+const BACKDROP_SHADER_HANDLE: Handle<Shader> =
+    Handle::weak_from_u128(0x8c14_d7a2_6e39_41b5_9f08_c2a7_1d6e_53f9);
+
+/// Renders the `backdrop-filter` of the elements: blurs the image of the
+/// camera before the ui pass and draws the blurred image behind the
+/// elements with the [`BackdropMaterial`].
+pub struct BackdropBlurPlugin;
+impl Plugin for BackdropBlurPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(app, BLUR_SHADER_HANDLE, "backdrop_blur.wgsl", Shader::from_wgsl);
+        load_internal_asset!(app, BACKDROP_SHADER_HANDLE, "backdrop.wgsl", Shader::from_wgsl);
+        let backdrop = {
+            let mut images = app.world.resource_mut::<Assets<Image>>();
+            BackdropBlur {
+                radius: 0.,
+                blurred: images.add(backdrop_image()),
+                scratch: images.add(backdrop_image()),
+            }
+        };
+        app.insert_resource(backdrop);
+        app.add_plugins(ExtractResourcePlugin::<BackdropBlur>::default());
+        app.add_plugins(UiMaterialPlugin::<BackdropMaterial>::default());
+        app.add_systems(
+            PostUpdate,
+            update_backdrop_system.after(UiSystem::Layout),
+        );
+
+        let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .add_render_graph_node::<ViewNodeRunner<BackdropBlurNode>>(Core2d, BackdropBlurLabel)
+            .add_render_graph_edges(
+                Core2d,
+                (Node2d::EndMainPassPostProcessing, BackdropBlurLabel, NodeUi::UiPass),
+            )
+            .add_render_graph_node::<ViewNodeRunner<BackdropBlurNode>>(Core3d, BackdropBlurLabel)
+            .add_render_graph_edges(
+                Core3d,
+                (Node3d::EndMainPassPostProcessing, BackdropBlurLabel, NodeUi::UiPass),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.init_resource::<BackdropBlurPipeline>();
+        }
+    }
+}
+
+/// Returns the empty image the blurred camera image is rendered to
+fn backdrop_image() -> Image {
+    let size = Extent3d {
+        width: 1,
+        height: 1,
+        depth_or_array_layers: 1,
+    };
+    let mut image = Image::new_fill(
+        size,
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::bevy_default(),
+        RenderAssetUsages::default(),
+    );
+    image.texture_descriptor.usage =
+        TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::RENDER_ATTACHMENT;
+    image
+}
+
+/// The blurred image of the camera shared by all the elements with
+/// the `backdrop-filter`. The image is blurred once per frame with the
+/// largest radius of the visible elements.
+#[derive(Resource, Clone, ExtractResource)]
+pub struct BackdropBlur {
+    /// The blur radius in physical pixels, `0.` skips the blur
+    pub radius: f32,
+    /// The blurred image of the camera
+    pub blurred: Handle<Image>,
+    /// The image blurred horizontally
+    scratch: Handle<Image>,
+}
+
+/// Draws the blurred camera image tinted with the `background-color`
+/// of the element.
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+pub struct BackdropMaterial {
+    #[uniform(0)]
+    tint: Color,
+    #[uniform(0)]
+    opacity: f32,
+    #[texture(1)]
+    #[sampler(2)]
+    backdrop: Handle<Image>,
+}
+
+impl UiMaterial for BackdropMaterial {
+    fn fragment_shader() -> ShaderRef {
+        BACKDROP_SHADER_HANDLE.into()
+    }
+}
+
+/// Resizes the backdrop images to the window, updates the blur radius and
+/// gives the elements with the [`BackdropFilter`] the [`BackdropMaterial`].
+pub fn update_backdrop_system(
+    mut commands: Commands,
+    mut backdrop: ResMut<BackdropBlur>,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<BackdropMaterial>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    filters: Query<(
+        Entity,
+        &BackdropFilter,
+        &InheritedVisibility,
+        Option<&BackgroundColor>,
+        Option<&ComputedOpacity>,
+        Option<&Handle<BackdropMaterial>>,
+    )>,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let radius = filters
+        .iter()
+        .filter(|(_, _, visibility, ..)| visibility.get())
+        .fold(0f32, |radius, (_, filter, ..)| radius.max(filter.blur))
+        * window.scale_factor();
+    if backdrop.radius != radius {
+        backdrop.radius = radius;
+    }
+    let mut resized = false;
+    if radius > 0. {
+        let size = Extent3d {
+            width: window.physical_width().max(1),
+            height: window.physical_height().max(1),
+            depth_or_array_layers: 1,
+        };
+        for handle in [backdrop.blurred.clone(), backdrop.scratch.clone()] {
+            if images.get(&handle).map_or(false, |i| i.texture_descriptor.size != size) {
+                if let Some(image) = images.get_mut(&handle) {
+                    image.resize(size);
+                    resized = true;
+                }
+            }
+        }
+    }
+    for (entity, filter, _, background, opacity, handle) in filters.iter() {
+        if filter.blur <= 0. {
+            commands
+                .entity(entity)
+                .remove::<BackdropFilter>()
+                .remove::<Handle<BackdropMaterial>>();
+            continue;
+        }
+        let tint = background.map_or(Color::NONE, |c| c.0);
+        let opacity = opacity.map_or(1., |o| o.get());
+        let Some(handle) = handle else {
+            commands.entity(entity).insert(materials.add(BackdropMaterial {
+                tint,
+                opacity,
+                backdrop: backdrop.blurred.clone(),
+            }));
+            continue;
+        };
+        let changed = materials
+            .get(handle)
+            .map_or(false, |m| m.tint != tint || m.opacity != opacity);
+        // the bind group of the material points to the old texture
+        // after the resize, modifying the material rebuilds it
+        if changed || resized {
+            if let Some(material) = materials.get_mut(handle) {
+                material.tint = tint;
+                material.opacity = opacity;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+struct BackdropBlurLabel;
+
+#[derive(ShaderType)]
+struct BlurUniform {
+    /// The step between the samples in uv
+    texel: Vec2,
+    /// The blur radius in physical pixels
+    radius: f32,
+}
+
+#[derive(Resource)]
+struct BackdropBlurPipeline {
+    layout: BindGroupLayout,
+    sampler: Sampler,
+    pipeline_id: CachedRenderPipelineId,
+}
+
+impl FromWorld for BackdropBlurPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let layout = render_device.create_bind_group_layout(
+            "backdrop_blur_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    texture_2d(TextureSampleType::Float { filterable: true }),
+                    sampler(SamplerBindingType::Filtering),
+                    uniform_buffer::<BlurUniform>(false),
+                ),
+            ),
+        );
+        let sampler = render_device.create_sampler(&SamplerDescriptor::default());
+        let pipeline_id = world.resource_mut::<PipelineCache>().queue_render_pipeline(
+            RenderPipelineDescriptor {
+                label: Some("backdrop_blur_pipeline".into()),
+                layout: vec![layout.clone()],
+                vertex: fullscreen_shader_vertex_state(),
+                fragment: Some(FragmentState {
+                    shader: BLUR_SHADER_HANDLE,
+                    shader_defs: vec![],
+                    entry_point: "fragment".into(),
+                    targets: vec![Some(ColorTargetState {
+                        format: TextureFormat::bevy_default(),
+                        blend: None,
+                        write_mask: ColorWrites::ALL,
+                    })],
+                }),
+                primitive: default(),
+                depth_stencil: None,
+                multisample: default(),
+                push_constant_ranges: vec![],
+            },
+        );
+        BackdropBlurPipeline {
+            layout,
+            sampler,
+            pipeline_id,
+        }
+    }
+}
+
+/// Blurs the camera image into the [`BackdropBlur`] images with two
+/// gaussian passes: horizontal into the scratch image and vertical into
+/// the blurred one.
+#[derive(Default)]
+struct BackdropBlurNode;
+impl ViewNode for BackdropBlurNode {
+    type ViewQuery = &'static ViewTarget;
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        view_target: QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let Some(backdrop) = world.get_resource::<BackdropBlur>() else {
+            return Ok(());
+        };
+        if backdrop.radius <= 0. {
+            return Ok(());
+        }
+        let blur_pipeline = world.resource::<BackdropBlurPipeline>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let Some(pipeline) = pipeline_cache.get_render_pipeline(blur_pipeline.pipeline_id) else {
+            return Ok(());
+        };
+        let images = world.resource::<RenderAssets<Image>>();
+        let (Some(blurred), Some(scratch)) =
+            (images.get(&backdrop.blurred), images.get(&backdrop.scratch))
+        else {
+            return Ok(());
+        };
+        let passes = [
+            (view_target.main_texture_view(), scratch, Vec2::X),
+            (&scratch.texture_view, blurred, Vec2::Y),
+        ];
+        for (source, target, direction) in passes {
+            let mut uniform = UniformBuffer::new(Vec::new());
+            uniform
+                .write(&BlurUniform {
+                    texel: direction / target.size,
+                    radius: backdrop.radius,
+                })
+                .unwrap();
+            let buffer = render_context
+                .render_device()
+                .create_buffer_with_data(&BufferInitDescriptor {
+                    label: Some("backdrop_blur_uniform"),
+                    contents: uniform.as_ref(),
+                    usage: BufferUsages::UNIFORM,
+                });
+            let bind_group = render_context.render_device().create_bind_group(
+                "backdrop_blur_bind_group",
+                &blur_pipeline.layout,
+                &BindGroupEntries::sequential((
+                    source,
+                    &blur_pipeline.sampler,
+                    buffer.as_entire_binding(),
+                )),
+            );
+            let mut pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+                label: Some("backdrop_blur_pass"),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: &target.texture_view,
+                    resolve_target: None,
+                    ops: Operations::default(),
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            pass.set_render_pipeline(pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.draw(0..3, 0..1);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+struct Blur {
+    // the step between the samples in uv
+    texel: vec2<f32>,
+    // the blur radius in physical pixels
+    radius: f32,
+};
+
+@group(0) @binding(0)
+var source: texture_2d<f32>;
+@group(0) @binding(1)
+var source_sampler: sampler;
+@group(0) @binding(2)
+var<uniform> blur: Blur;
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    // up to 16 samples on each side spread over the radius
+    let samples = min(ceil(blur.radius), 16.0);
+    let spacing = blur.radius / max(samples, 1.0);
+    let sigma = max(blur.radius / 2.0, 0.5);
+    var color = textureSample(source, source_sampler, in.uv);
+    var total = 1.0;
+    for (var idx = 1.0; idx <= samples; idx += 1.0) {
+        let offset = idx * spacing;
+        let weight = exp(-offset * offset / (2.0 * sigma * sigma));
+        let delta = blur.texel * offset;
+        color += textureSample(source, source_sampler, in.uv + delta) * weight;
+        color += textureSample(source, source_sampler, in.uv - delta) * weight;
+        total += 2.0 * weight;
+    }
+    return vec4<f32>(color.rgb / total, 1.0);
+}
@@ -0,0 +1,650 @@
+use crate::ess::parse;
+use crate::ess::ApplyStyleProperties;
+use crate::ess::StylePropertyFunction;
+use crate::ess::StylePropertyToken;
+use crate::style_property;
+use crate::ElementsError;
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt};
+use bevy::prelude::*;
+use bevy::render::render_resource::TextureFormat;
+use bevy::render::texture::{ImageLoaderSettings, ImageSampler};
+use bevy::render::{Extract, RenderApp};
+use bevy::ui::{
+    CalculatedClip, ContentSize, DefaultUiCamera, ExtractedUiNode, ExtractedUiNodes,
+    RenderUiSystem, UiStack, UiSystem,
+};
+use bevy::utils::{BoxedFuture, HashMap};
+use std::path::Path;
+use thiserror::Error;
+
+/// Draws the texts using the spritesheet based [`BitmapFont`]s instead of
+/// the glyphs of the `ttf` fonts. The bitmap fonts are loaded from the
+/// BMFont `.fnt` files or cut from the grid atlas by the `font` property:
+/// ```css
+/// font: "fonts/retro.fnt";
+/// font: grid("fonts/retro.png", 8px 8px, " !\"#$%&'()*+,-./0123456789");
+/// ```
+pub struct BitmapFontPlugin;
+impl Plugin for BitmapFontPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<BitmapFont>();
+        app.register_asset_loader(BitmapFontLoader);
+        app.init_resource::<GridFonts>();
+        app.add_systems(
+            PostUpdate,
+            (cut_grid_fonts_system, layout_bitmap_text_system)
+                .chain()
+                .after(ApplyStyleProperties)
+                .after(bevy::ui::widget::measure_text_system)
+                .before(UiSystem::Layout),
+        );
+        app.sub_app_mut(RenderApp).add_systems(
+            ExtractSchedule,
+            extract_bitmap_text.after(RenderUiSystem::ExtractNode),
+        );
+    }
+}
+
+/// Possible errors of the [`BitmapFontLoader`]
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum BitmapFontError {
+    #[error("Could not read bitmap font: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid `{1}` in bitmap font line {0}")]
+    InvalidAttribute(usize, String),
+    #[error("Bitmap font has no page")]
+    NoPage,
+}
+
+/// The glyph of the [`BitmapFont`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BitmapGlyph {
+    /// The region of the font image with the glyph, in pixels
+    pub rect: Rect,
+    /// The offset of the glyph from the pen position at the top of the line
+    pub offset: Vec2,
+    /// How far the pen moves after the glyph
+    pub advance: f32,
+}
+
+/// The grid atlas the [`BitmapFont`] is cut from: the cells of the image
+/// contain the `chars` row by row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitmapGrid {
+    /// The size of the cell in pixels
+    pub cell: UVec2,
+    pub chars: String,
+    /// Advance each glyph by the width of its opaque pixels instead
+    /// of the cell width
+    pub proportional: bool,
+}
+
+impl BitmapGrid {
+    /// Cuts the glyphs from the grid atlas `image`
+    pub fn glyphs(&self, image: &Image) -> HashMap<char, BitmapGlyph> {
+        let size = image.size();
+        let cell = self.cell.max(UVec2::ONE);
+        let columns = (size.x / cell.x).max(1);
+        let alpha = match image.texture_descriptor.format {
+            TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => {
+                Some(|x: u32, y: u32| image.data[((y * size.x + x) * 4 + 3) as usize])
+            }
+            _ => None,
+        };
+        let mut glyphs = HashMap::default();
+        for (idx, char) in self.chars.chars().enumerate() {
+            let min = UVec2::new(idx as u32 % columns, idx as u32 / columns) * cell;
+            if min.y + cell.y > size.y {
+                break;
+            }
+            let mut advance = cell.x as f32;
+            if let Some(alpha) = alpha.filter(|_| self.proportional) {
+                let opaque = (0..cell.x)
+                    .rev()
+                    .find(|x| (0..cell.y).any(|y| alpha(min.x + x, min.y + y) > 0));
+                // the empty cells are the spaces, they take a half of the cell
+                advance = opaque.map_or(cell.x as f32 / 2., |x| x as f32 + 2.);
+            }
+            glyphs.insert(
+                char,
+                BitmapGlyph {
+                    rect: Rect::from_corners(min.as_vec2(), (min + cell).as_vec2()),
+                    offset: Vec2::ZERO,
+                    advance,
+                },
+            );
+        }
+        glyphs
+    }
+}
+
+/// The glyph of the [`BitmapText`] placed by the layout
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BitmapQuad {
+    /// The region of the font image with the glyph, in pixels
+    pub rect: Rect,
+    /// The top left corner of the glyph relative to the node
+    pub position: Vec2,
+    pub size: Vec2,
+    /// The index of the text section the glyph belongs to
+    pub section: usize,
+}
+
+/// The font drawn from the spritesheet, loaded from the BMFont `.fnt`
+/// file or cut from the grid atlas.
+#[derive(Asset, TypePath, Debug, Clone, Default)]
+pub struct BitmapFont {
+    pub image: Handle<Image>,
+    /// The size of the font in pixels: the texts with this `font-size`
+    /// draw the glyphs unscaled
+    pub size: f32,
+    pub line_height: f32,
+    pub glyphs: HashMap<char, BitmapGlyph>,
+    /// The additional advance between the pairs of chars
+    pub kerning: HashMap<(char, char), f32>,
+    /// The grid the glyphs are cut from when the image is loaded
+    pub grid: Option<BitmapGrid>,
+}
+
+impl BitmapFont {
+    /// Creates the font cut from the grid atlas `image`, the glyphs
+    /// are filled by the [`cut_grid_fonts_system`].
+    pub fn from_grid(image: Handle<Image>, grid: BitmapGrid) -> BitmapFont {
+        BitmapFont {
+            image,
+            size: grid.cell.y as f32,
+            line_height: grid.cell.y as f32,
+            grid: Some(grid),
+            ..default()
+        }
+    }
+
+    /// Returns the glyph of the `char`, the missing lowercase letters
+    /// are drawn with the uppercase ones.
+    pub fn glyph(&self, char: char) -> Option<&BitmapGlyph> {
+        self.glyphs
+            .get(&char)
+            .or_else(|| self.glyphs.get(&char.to_ascii_uppercase()))
+    }
+
+    /// Places the glyphs of the `text`: each section is scaled by its
+    /// `font_size`, the lines are broken only at the `\n`. Returns
+    /// the glyphs and the size of the text.
+    pub fn layout(&self, text: &Text, letter_spacing: f32) -> (Vec<BitmapQuad>, Vec2) {
+        let mut quads = vec![];
+        // the first glyph and the width of each line
+        let mut lines = vec![];
+        let mut line_start = 0;
+        let mut line_width = 0.;
+        let mut line_height: f32 = 0.;
+        let mut pen = Vec2::ZERO;
+        let mut size = Vec2::ZERO;
+        let mut previous = None;
+        for (section, text_section) in text.sections.iter().enumerate() {
+            let scale = text_section.style.font_size / self.size.max(1.);
+            for char in text_section.value.chars() {
+                line_height = line_height.max(self.line_height * scale);
+                if char == '\n' {
+                    lines.push((line_start, line_width));
+                    size.x = size.x.max(line_width);
+                    line_start = quads.len();
+                    line_width = 0.;
+                    pen = Vec2::new(0., pen.y + line_height);
+                    line_height = 0.;
+                    previous = None;
+                    continue;
+                }
+                let Some(glyph) = self.glyph(char) else {
+                    previous = None;
+                    continue;
+                };
+                if let Some(previous) = previous {
+                    pen.x += self.kerning.get(&(previous, char)).unwrap_or(&0.) * scale;
+                }
+                if glyph.rect.width() > 0. && glyph.rect.height() > 0. {
+                    quads.push(BitmapQuad {
+                        rect: glyph.rect,
+                        position: pen + glyph.offset * scale,
+                        size: glyph.rect.size() * scale,
+                        section,
+                    });
+                }
+                line_width = pen.x + glyph.advance * scale;
+                pen.x = line_width + letter_spacing;
+                previous = Some(char);
+            }
+        }
+        lines.push((line_start, line_width));
+        size.x = size.x.max(line_width);
+        size.y = pen.y + line_height;
+        let align = match text.justify {
+            JustifyText::Center => 0.5,
+            JustifyText::Right => 1.,
+            _ => 0.,
+        };
+        for (idx, (start, width)) in lines.iter().enumerate() {
+            let end = lines.get(idx + 1).map_or(quads.len(), |(next, _)| *next);
+            let shift = ((size.x - width) * align).round();
+            quads[*start..end]
+                .iter_mut()
+                .for_each(|quad| quad.position.x += shift);
+        }
+        (quads, size)
+    }
+}
+
+/// Splits the BMFont line into the tag and the `key=value` attributes
+fn bmfont_line(line: &str) -> (&str, Vec<(&str, &str)>) {
+    let line = line.trim();
+    let (tag, mut rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let mut attributes = vec![];
+    while let Some((key, tail)) = rest.trim_start().split_once('=') {
+        let (value, tail) = match tail.strip_prefix('"') {
+            Some(quoted) => quoted.split_once('"').unwrap_or((quoted, "")),
+            None => tail.split_once(char::is_whitespace).unwrap_or((tail, "")),
+        };
+        attributes.push((key.trim(), value));
+        rest = tail;
+    }
+    (tag, attributes)
+}
+
+/// Parses the text BMFont file, returns the font without the image and
+/// the path of the image relative to the file. Only the first page of the
+/// font is used.
+pub fn parse_bmfont(source: &str) -> Result<(BitmapFont, String), BitmapFontError> {
+    let mut font = BitmapFont::default();
+    let mut page = None;
+    for (idx, line) in source.lines().enumerate() {
+        let (tag, attributes) = bmfont_line(line);
+        let attribute = |name: &str| -> Result<f32, BitmapFontError> {
+            attributes
+                .iter()
+                .find(|(key, _)| *key == name)
+                .and_then(|(_, value)| value.parse().ok())
+                .ok_or_else(|| BitmapFontError::InvalidAttribute(idx + 1, name.to_string()))
+        };
+        let char_attribute = |name: &str| attribute(name).map(|id| char::from_u32(id as u32));
+        match tag {
+            // the generators write the negative size for the pixel fonts
+            "info" => font.size = attribute("size")?.abs(),
+            "common" => font.line_height = attribute("lineHeight")?,
+            "page" if attribute("id")? == 0. => {
+                page = attributes
+                    .iter()
+                    .find(|(key, _)| *key == "file")
+                    .map(|(_, file)| file.to_string());
+            }
+            "char" if attribute("page").unwrap_or(0.) == 0. => {
+                let Some(char) = char_attribute("id")? else {
+                    continue;
+                };
+                let min = Vec2::new(attribute("x")?, attribute("y")?);
+                let size = Vec2::new(attribute("width")?, attribute("height")?);
+                font.glyphs.insert(
+                    char,
+                    BitmapGlyph {
+                        rect: Rect::from_corners(min, min + size),
+                        offset: Vec2::new(attribute("xoffset")?, attribute("yoffset")?),
+                        advance: attribute("xadvance")?,
+                    },
+                );
+            }
+            "kerning" => {
+                let pair = (char_attribute("first")?, char_attribute("second")?);
+                if let (Some(first), Some(second)) = pair {
+                    font.kerning.insert((first, second), attribute("amount")?);
+                }
+            }
+            _ => {}
+        }
+    }
+    if font.size == 0. {
+        font.size = font.line_height;
+    }
+    Ok((font, page.ok_or(BitmapFontError::NoPage)?))
+}
+
+/// Loads the text BMFont `.fnt` files, the image of the font is loaded
+/// with the nearest sampler to keep the pixels sharp.
+pub struct BitmapFontLoader;
+impl AssetLoader for BitmapFontLoader {
+    type Settings = ();
+    type Error = BitmapFontError;
+    type Asset = BitmapFont;
+
+    fn extensions(&self) -> &[&str] {
+        &["fnt"]
+    }
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _: &'a Self::Settings,
+        load_context: &'a mut bevy::asset::LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut source = String::new();
+            reader.read_to_string(&mut source).await?;
+            let (mut font, page) = parse_bmfont(&source)?;
+            let path = load_context.path().parent().unwrap_or(Path::new("")).join(page);
+            font.image = load_context.load_with_settings(path, nearest_sampler);
+            Ok(font)
+        })
+    }
+}
+
+fn nearest_sampler(settings: &mut ImageLoaderSettings) {
+    settings.sampler = ImageSampler::nearest();
+}
+
+/// Returns `true` if the font `path` points to the bitmap font
+pub fn is_bitmap_font(path: &str) -> bool {
+    path.ends_with(".fnt")
+}
+
+/// The fonts created by the `grid(...)` values of the `font` property
+#[derive(Resource, Default)]
+pub struct GridFonts(HashMap<(String, BitmapGrid), Handle<BitmapFont>>);
+
+/// Returns the font cut from the grid atlas at the `path`, creates it once
+/// for each image and grid.
+pub fn grid_font(world: &mut World, path: &str, grid: &BitmapGrid) -> Handle<BitmapFont> {
+    let key = (path.to_string(), grid.clone());
+    if let Some(font) = world.get_resource::<GridFonts>().and_then(|f| f.0.get(&key)) {
+        return font.clone();
+    }
+    let Some(asset_server) = world.get_resource::<AssetServer>() else {
+        return Handle::default();
+    };
+    let image = asset_server.load_with_settings(path.to_string(), nearest_sampler);
+    let mut font = BitmapFont::from_grid(image, grid.clone());
+    let images = world.get_resource::<Assets<Image>>();
+    if let Some(image) = images.and_then(|images| images.get(&font.image)) {
+        font.glyphs = grid.glyphs(image);
+    }
+    let Some(mut fonts) = world.get_resource_mut::<Assets<BitmapFont>>() else {
+        return Handle::default();
+    };
+    let font = fonts.add(font);
+    world.resource_mut::<GridFonts>().0.insert(key, font.clone());
+    font
+}
+
+/// Parses the `grid("image.png", $width $height, "chars"[, proportional])`
+/// value of the `font` property
+pub fn parse_grid_font(
+    func: &StylePropertyFunction,
+) -> Result<(String, BitmapGrid), ElementsError> {
+    let err = || {
+        ElementsError::InvalidPropertyValue(format!(
+            "Expected grid($string, $px $px, $string[, proportional]), got `{}`",
+            StylePropertyToken::Function(func.clone()).to_string()
+        ))
+    };
+    let px = |token: &StylePropertyToken| match token {
+        StylePropertyToken::Dimension(val, unit) if unit == "px" && val.to_float() >= 1. => {
+            Ok(val.to_float() as u32)
+        }
+        _ => Err(err()),
+    };
+    let (path, cell, chars, rest) = match func.args.as_slice() {
+        [StylePropertyToken::String(path), cell, StylePropertyToken::String(chars), rest @ ..] => {
+            (path, cell, chars, rest)
+        }
+        _ => return Err(err()),
+    };
+    let cell = match cell {
+        StylePropertyToken::Tokens(size) if size.len() == 2 => {
+            UVec2::new(px(&size[0])?, px(&size[1])?)
+        }
+        size => UVec2::splat(px(size)?),
+    };
+    let proportional = match rest {
+        [] => false,
+        [StylePropertyToken::Identifier(ident)] if ident == "proportional" => true,
+        _ => return Err(err()),
+    };
+    let chars = chars.clone();
+    Ok((path.clone(), BitmapGrid { cell, chars, proportional }))
+}
+
+/// The text drawn with the [`BitmapFont`], inserted by the `font` property
+#[derive(Component, Debug, Clone, Default)]
+pub struct BitmapText {
+    pub font: Handle<BitmapFont>,
+    /// The glyphs placed by the [`layout_bitmap_text_system`]
+    pub glyphs: Vec<BitmapQuad>,
+    pub size: Vec2,
+}
+
+impl BitmapText {
+    pub fn new(font: Handle<BitmapFont>) -> BitmapText {
+        BitmapText { font, ..default() }
+    }
+}
+
+/// The additional space between the chars of the bitmap texts
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq)]
+pub struct LetterSpacing(pub f32);
+
+style_property! {
+    #[doc = " Specify the additional space between the chars of the text drawn"]
+    #[doc = " with the bitmap font:"]
+    #[doc = " ```css"]
+    #[doc = " letter-spacing: 1px;"]
+    #[doc = " ```"]
+    #[doc = " <!-- @property-type=$num -->"]
+    #[doc = " <!-- @property-category=Text -->"]
+    LetterSpacingProperty("letter-spacing") {
+        Default = "0px";
+        Item = f32;
+        Components = Option<&'static mut LetterSpacing>;
+        Filters = With<Node>;
+        AffectsVirtual = true;
+        Parser = parse::NumParser;
+        Apply = |value, spacing, _assets, commands, entity| {
+            match spacing {
+                Some(mut spacing) if spacing.0 != *value => spacing.0 = *value,
+                Some(_) => {}
+                None if *value != 0. => {
+                    commands.entity(entity).insert(LetterSpacing(*value));
+                }
+                None => {}
+            }
+        };
+    }
+}
+
+/// Cuts the glyphs of the grid fonts when their images are loaded
+pub fn cut_grid_fonts_system(
+    images: Res<Assets<Image>>,
+    mut fonts: ResMut<Assets<BitmapFont>>,
+    mut events: EventReader<AssetEvent<Image>>,
+) {
+    for event in events.read() {
+        let (AssetEvent::Added { id } | AssetEvent::Modified { id }) = event else {
+            continue;
+        };
+        let Some(image) = images.get(*id) else {
+            continue;
+        };
+        let cut: Vec<_> = fonts
+            .iter()
+            .filter(|(_, font)| font.grid.is_some() && font.image.id() == *id)
+            .map(|(font, _)| font)
+            .collect();
+        for font in cut {
+            let font = fonts.get_mut(font).unwrap();
+            font.glyphs = font.grid.as_ref().unwrap().glyphs(image);
+        }
+    }
+}
+
+/// Places the glyphs of the bitmap texts and sizes the nodes by them.
+/// Runs after bevy measures the text with the `ttf` font, the glyphs of
+/// that font are hidden by the
+/// [`hide_stroked_text_system`](super::text::hide_stroked_text_system).
+pub fn layout_bitmap_text_system(
+    fonts: Res<Assets<BitmapFont>>,
+    mut font_events: EventReader<AssetEvent<BitmapFont>>,
+    mut texts: Query<(
+        Ref<Text>,
+        &mut BitmapText,
+        Option<Ref<LetterSpacing>>,
+        &mut ContentSize,
+    )>,
+) {
+    let fonts_changed = font_events.read().count() > 0;
+    for (text, mut bitmap, spacing, mut content_size) in texts.iter_mut() {
+        let spacing_changed = spacing.as_ref().map_or(false, |s| s.is_changed());
+        if !fonts_changed
+            && !text.is_changed()
+            && !bitmap.is_changed()
+            && !spacing_changed
+            && !content_size.is_changed()
+        {
+            continue;
+        }
+        let Some(font) = fonts.get(&bitmap.font) else {
+            continue;
+        };
+        let (glyphs, size) = font.layout(&text, spacing.map_or(0., |s| s.0));
+        bitmap.glyphs = glyphs;
+        bitmap.size = size;
+        *content_size = ContentSize::fixed_size(size);
+    }
+}
+
+pub fn extract_bitmap_text(
+    mut commands: Commands,
+    mut extracted_uinodes: ResMut<ExtractedUiNodes>,
+    ui_stack: Extract<Res<UiStack>>,
+    fonts: Extract<Res<Assets<BitmapFont>>>,
+    images: Extract<Res<Assets<Image>>>,
+    default_ui_camera: Extract<DefaultUiCamera>,
+    texts: Extract<
+        Query<(
+            &Node,
+            &GlobalTransform,
+            &Text,
+            &BitmapText,
+            &InheritedVisibility,
+            Option<&CalculatedClip>,
+            Option<&TargetCamera>,
+        )>,
+    >,
+) {
+    for (stack_index, entity) in ui_stack.uinodes.iter().enumerate() {
+        let Ok((node, transform, text, bitmap, visibility, clip, camera)) = texts.get(*entity)
+        else {
+            continue;
+        };
+        let Some(camera_entity) = camera.map(TargetCamera::entity).or(default_ui_camera.get())
+        else {
+            continue;
+        };
+        if !visibility.get() || bitmap.glyphs.is_empty() {
+            continue;
+        }
+        let Some(font) = fonts.get(&bitmap.font) else {
+            continue;
+        };
+        let Some(image) = images.get(&font.image) else {
+            continue;
+        };
+        let atlas_size = image.size().as_vec2();
+        let origin =
+            transform.compute_matrix() * Mat4::from_translation((-0.5 * node.size()).extend(0.));
+        for glyph in bitmap.glyphs.iter() {
+            let color = text
+                .sections
+                .get(glyph.section)
+                .map_or(Color::WHITE, |s| s.style.color);
+            let center = glyph.position + 0.5 * glyph.size;
+            let scale = glyph.size / glyph.rect.size();
+            extracted_uinodes.uinodes.insert(
+                commands.spawn_empty().id(),
+                ExtractedUiNode {
+                    stack_index: stack_index as u32,
+                    transform: origin
+                        * Mat4::from_translation(center.extend(0.))
+                        * Mat4::from_scale(scale.extend(1.)),
+                    color,
+                    rect: glyph.rect,
+                    image: font.image.id(),
+                    atlas_size: Some(atlas_size),
+                    clip: clip.map(|clip| clip.clip),
+                    flip_x: false,
+                    flip_y: false,
+                    camera_entity,
+                },
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ess::StyleProperty;
+    use std::str::FromStr;
+
+    const FONT: &str = r#"info face="Retro" size=-8 bold=0 italic=0
+common lineHeight=10 base=8 scaleW=64 scaleH=64 pages=1
+page id=0 file="retro.png"
+chars count=3
+char id=65   x=0  y=0  width=6 height=8 xoffset=0 yoffset=1 xadvance=7 page=0
+char id=66   x=8  y=0  width=6 height=8 xoffset=1 yoffset=1 xadvance=7 page=0
+char id=32   x=0  y=0  width=0 height=0 xoffset=0 yoffset=0 xadvance=4 page=0
+kernings count=1
+kerning first=65 second=66 amount=-1
+"#;
+
+    #[test]
+    fn parse_and_layout_bmfont() {
+        let (font, page) = parse_bmfont(FONT).unwrap();
+        assert_eq!(page, "retro.png");
+        assert_eq!(font.size, 8.);
+        assert_eq!(font.line_height, 10.);
+        assert_eq!(font.glyphs.len(), 3);
+        assert_eq!(font.kerning.get(&('A', 'B')), Some(&-1.));
+
+        let mut text = Text::from_section("AB a", TextStyle { font_size: 16., ..default() });
+        let (quads, size) = font.layout(&text, 0.);
+        // the space has no glyph, the lowercase `a` is drawn with `A`
+        assert_eq!(quads.len(), 3);
+        assert_eq!(quads[0].position, Vec2::new(0., 2.));
+        assert_eq!(quads[0].size, Vec2::new(12., 16.));
+        assert_eq!(quads[1].position, Vec2::new(14. - 2. + 2., 2.));
+        assert_eq!(quads[2].position.x, 12. + 14. + 8.);
+        assert_eq!(size, Vec2::new(12. + 14. + 8. + 14., 20.));
+
+        text.sections[0].value = "AB\nA".into();
+        text.justify = JustifyText::Right;
+        let (quads, size) = font.layout(&text, 1.);
+        assert_eq!(size, Vec2::new(14. - 2. + 1. + 14., 40.));
+        assert_eq!(quads[2].position, Vec2::new(size.x - 14., 22.));
+
+        assert!(parse_bmfont("info size=8").is_err());
+    }
+
+    #[test]
+    fn parse_grid_fonts() {
+        let parse = |s: &str| match &StyleProperty::from_str(s).unwrap()[0] {
+            StylePropertyToken::Function(func) => parse_grid_font(func),
+            _ => unreachable!(),
+        };
+        let (path, grid) = parse("grid(\"retro.png\", 8px 10px, \"ABC\")").unwrap();
+        assert_eq!(path, "retro.png");
+        assert_eq!(grid.cell, UVec2::new(8, 10));
+        assert!(!grid.proportional);
+        let (_, grid) = parse("grid(\"retro.png\", 8px, \"ABC\", proportional)").unwrap();
+        assert_eq!(grid.cell, UVec2::splat(8));
+        assert!(grid.proportional);
+        assert!(parse("grid(\"retro.png\", 8px)").is_err());
+        assert!(parse("grid(\"retro.png\", 50%, \"ABC\")").is_err());
+    }
+}
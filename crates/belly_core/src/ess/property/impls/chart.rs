@@ -0,0 +1,208 @@
+use crate::ess::parse;
+use crate::ess::PropertyParser;
+use crate::ess::StyleProperty;
+use crate::ess::StylePropertyMethods;
+use crate::ess::StylePropertyToken;
+use crate::style_property;
+use crate::ElementsError;
+use bevy::prelude::*;
+
+/// The styling of the chart widgets, driven by the `chart-palette` and
+/// `chart-axis` properties. The chart widgets insert this component on
+/// build and read it when painting the data.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct ChartStyle {
+    pub palette: Vec<Color>,
+    pub axis: ChartAxis,
+}
+
+impl Default for ChartStyle {
+    fn default() -> Self {
+        ChartStyle {
+            palette: DEFAULT_PALETTE.to_vec(),
+            axis: ChartAxis::default(),
+        }
+    }
+}
+
+const DEFAULT_PALETTE: [Color; 4] = [
+    Color::rgb(0.298, 0.686, 0.314),
+    Color::rgb(0.129, 0.588, 0.953),
+    Color::rgb(1., 0.596, 0.),
+    Color::rgb(0.914, 0.118, 0.388),
+];
+
+impl ChartStyle {
+    /// The color of the data series with `index`, the palette
+    /// is repeated when there are more series than colors.
+    pub fn color(&self, index: usize) -> Color {
+        if self.palette.is_empty() {
+            return Color::WHITE;
+        }
+        self.palette[index % self.palette.len()]
+    }
+
+    /// The color of the heatmap cell with normalized value `t`: the
+    /// palette is treated as the gradient from the first to the last color.
+    pub fn gradient(&self, t: f32) -> Color {
+        match self.palette.len() {
+            0 => Color::WHITE,
+            1 => self.palette[0],
+            len => {
+                let pos = t.clamp(0., 1.) * (len - 1) as f32;
+                let idx = (pos.floor() as usize).min(len - 2);
+                let t = pos - idx as f32;
+                let from = self.palette[idx].as_rgba_f32();
+                let to = self.palette[idx + 1].as_rgba_f32();
+                Color::rgba(
+                    from[0] + (to[0] - from[0]) * t,
+                    from[1] + (to[1] - from[1]) * t,
+                    from[2] + (to[2] - from[2]) * t,
+                    from[3] + (to[3] - from[3]) * t,
+                )
+            }
+        }
+    }
+}
+
+/// The axis lines of the chart, the width of `0` hides the axis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChartAxis {
+    pub width: f32,
+    pub color: Color,
+}
+
+impl Default for ChartAxis {
+    fn default() -> Self {
+        ChartAxis {
+            width: 1.,
+            color: Color::rgb(0.62, 0.62, 0.62),
+        }
+    }
+}
+
+/// <!-- @property-type=$palette -->
+/// Palette: the list of colors separated by spaces (`#4caf50 #2196f3 orange`).
+pub struct PaletteParser;
+impl PropertyParser<Vec<Color>> for PaletteParser {
+    fn parse(prop: &StyleProperty) -> Result<Vec<Color>, ElementsError> {
+        if prop.is_empty() {
+            return Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected $color list, got nothing"
+            )));
+        }
+        prop.iter()
+            .map(|token| parse::color(&StyleProperty(std::iter::once(token.clone()).collect())))
+            .collect()
+    }
+}
+
+/// <!-- @property-type=$axis -->
+/// Axis: `none` or the width (number or `px` suffixed value)
+/// followed by the color (`1px #9e9e9e`).
+pub struct ChartAxisParser;
+impl PropertyParser<ChartAxis> for ChartAxisParser {
+    fn parse(prop: &StyleProperty) -> Result<ChartAxis, ElementsError> {
+        let width = match prop.first() {
+            Some(StylePropertyToken::Identifier(ident)) if ident == "none" && prop.len() == 1 => {
+                return Ok(ChartAxis {
+                    width: 0.,
+                    color: Color::NONE,
+                })
+            }
+            Some(StylePropertyToken::Number(val)) => val.to_float(),
+            Some(StylePropertyToken::Dimension(val, unit)) if unit == "px" => val.to_float(),
+            Some(token) => {
+                return Err(ElementsError::InvalidPropertyValue(format!(
+                    "Expected none|$px $color, got `{}`",
+                    token.to_string()
+                )))
+            }
+            None => {
+                return Err(ElementsError::InvalidPropertyValue(format!(
+                    "Expected none|$px $color, got nothing"
+                )))
+            }
+        };
+        if prop.len() != 2 {
+            return Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected none|$px $color, got `{}`",
+                prop.to_string()
+            )));
+        }
+        let color = parse::color(&StyleProperty(prop[1..].iter().cloned().collect()))?;
+        Ok(ChartAxis { width, color })
+    }
+}
+
+style_property! {
+    #[doc = " Specify the colors the chart paints the data with:"]
+    #[doc = " ```css"]
+    #[doc = " chart-palette: #4caf50 #2196f3 orange;"]
+    #[doc = " ```"]
+    #[doc = " "]
+    #[doc = " Each data series (or each bar of the stacked bars) takes the next"]
+    #[doc = " color from the palette. Heatmaps use the palette as a gradient"]
+    #[doc = " from the lowest to the highest value."]
+    #[doc = " <!-- @property-type=$palette -->"]
+    #[doc = " <!-- @property-category=Chart -->"]
+    ChartPaletteProperty("chart-palette") {
+        Default = "#4caf50 #2196f3 #ff9800 #e91e63";
+        Item = Vec<Color>;
+        Components = &'static mut ChartStyle;
+        Filters = With<Node>;
+        Parser = PaletteParser;
+        Apply = |value, style, _assets, _commands, _entity| {
+            if &style.palette != value {
+                style.palette = value.clone();
+            }
+        };
+    }
+}
+
+style_property! {
+    #[doc = " Specify the axis lines of the chart:"]
+    #[doc = " ```css"]
+    #[doc = " chart-axis: 1px #9e9e9e;"]
+    #[doc = " chart-axis: none;"]
+    #[doc = " ```"]
+    #[doc = " <!-- @property-type=$axis -->"]
+    #[doc = " <!-- @property-category=Chart -->"]
+    ChartAxisProperty("chart-axis") {
+        Default = "1px #9e9e9e";
+        Item = ChartAxis;
+        Components = &'static mut ChartStyle;
+        Filters = With<Node>;
+        Parser = ChartAxisParser;
+        Apply = |value, style, _assets, _commands, _entity| {
+            if &style.axis != value {
+                style.axis = *value;
+            }
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn parse_chart_style() {
+        let p = StyleProperty::from_str("#ff0000 #0000ff").unwrap();
+        let palette = PaletteParser::parse(&p).unwrap();
+        assert_eq!(palette, vec![Color::RED, Color::BLUE]);
+
+        let p = StyleProperty::from_str("2px white").unwrap();
+        assert_eq!(ChartAxisParser::parse(&p).unwrap().width, 2.);
+        let p = StyleProperty::from_str("none").unwrap();
+        assert_eq!(ChartAxisParser::parse(&p).unwrap().width, 0.);
+
+        let style = ChartStyle {
+            palette,
+            ..default()
+        };
+        assert_eq!(style.color(3), Color::BLUE);
+        assert_eq!(style.gradient(0.5), Color::rgba(0.5, 0., 0.5, 1.));
+    }
+}
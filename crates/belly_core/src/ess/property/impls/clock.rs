@@ -0,0 +1,89 @@
+use crate::ess::PropertyParser;
+use crate::ess::StyleProperty;
+use crate::ess::StylePropertyMethods;
+use crate::ess::StylePropertyToken;
+use crate::style_property;
+use crate::time::UiClock;
+use crate::ElementsError;
+use bevy::prelude::*;
+
+/// <!-- @property-type=$clock -->
+/// Clock: `inherit`, `real`, `virtual` or the number to scale the real time with.
+pub struct UiClockParser;
+impl PropertyParser<Option<UiClock>> for UiClockParser {
+    fn parse(value: &StyleProperty) -> Result<Option<UiClock>, ElementsError> {
+        match value.first() {
+            Some(StylePropertyToken::Identifier(ident)) if value.len() == 1 => {
+                match ident.as_str() {
+                    "inherit" => Ok(None),
+                    "real" => Ok(Some(UiClock::Real)),
+                    "virtual" => Ok(Some(UiClock::Virtual)),
+                    _ => Err(ElementsError::InvalidPropertyValue(format!(
+                        "Expected inherit|real|virtual|$number, got `{}`",
+                        ident
+                    ))),
+                }
+            }
+            Some(StylePropertyToken::Number(num)) if value.len() == 1 => {
+                Ok(Some(UiClock::Scaled(num.to_float())))
+            }
+            Some(_) => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected inherit|real|virtual|$number, got `{}`",
+                value.to_string()
+            ))),
+            None => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected inherit|real|virtual|$number, got nothing"
+            ))),
+        }
+    }
+}
+
+style_property! {
+    #[doc = " Specify the clock the element and its children animate with:"]
+    #[doc = " ```css"]
+    #[doc = " .pause-menu { ui-clock: real; }"]
+    #[doc = " .hud { ui-clock: virtual; }"]
+    #[doc = " .slow-motion { ui-clock: 0.5; }"]
+    #[doc = " ```"]
+    #[doc = " "]
+    #[doc = " `real` keeps animating while the game is paused, `virtual` follows"]
+    #[doc = " the game time, the number scales the real time. `inherit` uses the"]
+    #[doc = " clock of the parent (or `DefaultUiClock` for the roots)."]
+    #[doc = " <!-- @property-type=$clock -->"]
+    #[doc = " <!-- @property-category=General -->"]
+    UiClockProperty("ui-clock") {
+        Default = "inherit";
+        Item = Option<UiClock>;
+        Components = Option<&'static mut UiClock>;
+        Filters = With<Node>;
+        Parser = UiClockParser;
+        Apply = |value, clock, _assets, commands, entity| {
+            match (value, clock) {
+                (Some(value), Some(mut component)) => {
+                    if *component != *value {
+                        *component = *value;
+                    }
+                }
+                (Some(value), None) => { commands.entity(entity).insert(*value); }
+                (None, None) => { }
+                _ => { commands.entity(entity).remove::<UiClock>(); }
+            }
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn parse_ui_clock() {
+        let parse = |s| UiClockParser::parse(&StyleProperty::from_str(s).unwrap());
+        assert_eq!(parse("inherit"), Ok(None));
+        assert_eq!(parse("real"), Ok(Some(UiClock::Real)));
+        assert_eq!(parse("virtual"), Ok(Some(UiClock::Virtual)));
+        assert_eq!(parse("0.5"), Ok(Some(UiClock::Scaled(0.5))));
+        assert!(parse("fast").is_err());
+    }
+}
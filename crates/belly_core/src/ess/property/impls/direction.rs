@@ -0,0 +1,470 @@
+use super::parse;
+use crate::style_property;
+use bevy::prelude::*;
+
+style_property! {
+    #[doc = " Specify the direction of the element content by providing value to `Style.direction`:"]
+    #[doc = " ```css"]
+    #[doc = " direction: rtl;"]
+    #[doc = " ```"]
+    #[doc = " "]
+    #[doc = " Supported values:"]
+    #[doc = " - `inherit`: the direction is inherited from the parent element"]
+    #[doc = " - `ltr`: left-to-right content"]
+    #[doc = " - `rtl`: right-to-left content. Rows are reversed, horizontal paddings and margins"]
+    #[doc = "   are swapped, left-aligned texts become right-aligned and texts are reordered"]
+    #[doc = "   according to the bidirectional text rules."]
+    #[doc = " <!-- @property-category=Layout Control -->"]
+    DirectionProperty("direction") {
+        Default = "inherit";
+        Item = Direction;
+        Components = &'static mut Style;
+        Filters = With<Node>;
+        Parser = parse::IdentifierParser<Direction>;
+        Apply = |value, style, _assets, _commands, _entity| {
+            if &style.direction != value {
+                style.direction = *value;
+            }
+        };
+    }
+}
+
+/// Keeps the logical (as styled) and the physical (as written) values
+/// of the element mirrored by the `rtl` direction.
+#[derive(Component, Default, Debug)]
+pub struct DirectionMirror {
+    flex_direction: FlexDirection,
+    padding: UiRect,
+    margin: UiRect,
+    justify: Option<JustifyText>,
+}
+
+/// Keeps the logical (as written by the widgets) and the visual (shaped
+/// and reordered) values of the text sections. It is added to the texts
+/// of the right-to-left elements and to the texts with the right-to-left
+/// chars (Hebrew, Arabic, etc.)
+#[derive(Component, Default, Debug)]
+pub struct BidiText {
+    rtl: bool,
+    sections: Vec<(String, String)>,
+}
+
+fn mirror_flex_direction(direction: FlexDirection) -> FlexDirection {
+    match direction {
+        FlexDirection::Row => FlexDirection::RowReverse,
+        FlexDirection::RowReverse => FlexDirection::Row,
+        other => other,
+    }
+}
+
+fn mirror_rect(rect: UiRect) -> UiRect {
+    UiRect {
+        left: rect.right,
+        right: rect.left,
+        ..rect
+    }
+}
+
+fn mirror_justify(justify: JustifyText) -> JustifyText {
+    match justify {
+        JustifyText::Left => JustifyText::Right,
+        JustifyText::Right => JustifyText::Left,
+        other => other,
+    }
+}
+
+/// Resolves the inherited `direction` of the ui tree, mirrors the layout
+/// of the right-to-left elements and shapes & reorders the bidirectional texts.
+pub fn mirror_direction_system(
+    mut commands: Commands,
+    roots: Query<Entity, (With<Node>, Without<Parent>)>,
+    children: Query<&Children>,
+    mut nodes: Query<(
+        &mut Style,
+        Option<&mut Text>,
+        Option<&mut DirectionMirror>,
+        Option<&mut BidiText>,
+    )>,
+) {
+    let mut stack: Vec<(Entity, bool)> = roots.iter().map(|e| (e, false)).collect();
+    while let Some((entity, parent_rtl)) = stack.pop() {
+        let mut rtl = parent_rtl;
+        if let Ok((mut style, mut text, mirror, bidi)) = nodes.get_mut(entity) {
+            rtl = match style.direction {
+                Direction::LeftToRight => false,
+                Direction::RightToLeft => true,
+                Direction::Inherit => parent_rtl,
+            };
+            match (rtl, mirror) {
+                (true, Some(mut mirror)) => {
+                    mirror.update(&mut style, text.as_mut());
+                }
+                (true, None) => {
+                    let mut mirror = DirectionMirror::default();
+                    mirror.apply(&mut style, text.as_mut());
+                    commands.entity(entity).insert(mirror);
+                }
+                (false, Some(mirror)) => {
+                    mirror.restore(&mut style, text.as_mut());
+                    commands.entity(entity).remove::<DirectionMirror>();
+                }
+                (false, None) => {}
+            }
+            match (text, bidi) {
+                (Some(mut text), Some(mut bidi)) => {
+                    bidi.update(&mut text, rtl);
+                    if !bidi.is_required() {
+                        bidi.restore(&mut text);
+                        commands.entity(entity).remove::<BidiText>();
+                    }
+                }
+                (Some(mut text), None)
+                    if rtl || text.sections.iter().any(|s| has_rtl(&s.value)) =>
+                {
+                    let mut bidi = BidiText::default();
+                    bidi.update(&mut text, rtl);
+                    commands.entity(entity).insert(bidi);
+                }
+                _ => {}
+            }
+        }
+        if let Ok(children) = children.get(entity) {
+            stack.extend(children.iter().map(|c| (*c, rtl)));
+        }
+    }
+}
+
+impl DirectionMirror {
+    fn apply(&mut self, style: &mut Mut<Style>, text: Option<&mut Mut<Text>>) {
+        self.flex_direction = mirror_flex_direction(style.flex_direction);
+        self.padding = mirror_rect(style.padding);
+        self.margin = mirror_rect(style.margin);
+        style.flex_direction = self.flex_direction;
+        style.padding = self.padding;
+        style.margin = self.margin;
+        if let Some(text) = text {
+            self.justify = Some(mirror_justify(text.justify));
+            text.justify = self.justify.unwrap();
+        }
+    }
+
+    fn update(&mut self, style: &mut Mut<Style>, text: Option<&mut Mut<Text>>) {
+        // only the values changed by somebody else since the last
+        // mirroring are logical and should be mirrored again
+        if style.flex_direction != self.flex_direction {
+            self.flex_direction = mirror_flex_direction(style.flex_direction);
+            style.flex_direction = self.flex_direction;
+        }
+        if style.padding != self.padding {
+            self.padding = mirror_rect(style.padding);
+            style.padding = self.padding;
+        }
+        if style.margin != self.margin {
+            self.margin = mirror_rect(style.margin);
+            style.margin = self.margin;
+        }
+        let Some(text) = text else {
+            return;
+        };
+        if Some(text.justify) != self.justify {
+            self.justify = Some(mirror_justify(text.justify));
+            text.justify = self.justify.unwrap();
+        }
+    }
+
+    fn restore(&self, style: &mut Mut<Style>, text: Option<&mut Mut<Text>>) {
+        if style.flex_direction == self.flex_direction {
+            style.flex_direction = mirror_flex_direction(self.flex_direction);
+        }
+        if style.padding == self.padding {
+            style.padding = mirror_rect(self.padding);
+        }
+        if style.margin == self.margin {
+            style.margin = mirror_rect(self.margin);
+        }
+        let Some(text) = text else {
+            return;
+        };
+        if let Some(justify) = self.justify.filter(|j| *j == text.justify) {
+            text.justify = mirror_justify(justify);
+        }
+    }
+}
+
+impl BidiText {
+    fn update(&mut self, text: &mut Mut<Text>, rtl: bool) {
+        let rtl_changed = self.rtl != rtl;
+        self.rtl = rtl;
+        self.sections.resize(text.sections.len(), Default::default());
+        for (idx, (logical, visual)) in self.sections.iter_mut().enumerate() {
+            // the sections changed by somebody else since the last
+            // reordering are logical and should be reordered again
+            if &text.sections[idx].value != visual {
+                *logical = text.sections[idx].value.clone();
+            } else if !rtl_changed {
+                continue;
+            }
+            *visual = reorder_visual(&shape_arabic(logical), rtl);
+            if &text.sections[idx].value != visual {
+                text.sections[idx].value = visual.clone();
+            }
+        }
+    }
+
+    fn is_required(&self) -> bool {
+        self.rtl || self.sections.iter().any(|(logical, _)| has_rtl(logical))
+    }
+
+    fn restore(&self, text: &mut Mut<Text>) {
+        for (idx, (logical, visual)) in self.sections.iter().enumerate() {
+            if &text.sections[idx].value == visual {
+                text.sections[idx].value = logical.clone();
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BidiClass {
+    Ltr,
+    Rtl,
+    Neutral,
+}
+
+fn bidi_class(ch: char) -> BidiClass {
+    match ch as u32 {
+        // Hebrew, Arabic, Syriac, Thaana, NKo, Samaritan, Mandaic
+        0x0590..=0x08FF => BidiClass::Rtl,
+        // Hebrew and Arabic presentation forms
+        0xFB1D..=0xFDFF | 0xFE70..=0xFEFF => BidiClass::Rtl,
+        _ if ch.is_alphanumeric() => BidiClass::Ltr,
+        _ => BidiClass::Neutral,
+    }
+}
+
+/// Returns `true` if the `text` contains the right-to-left chars
+pub fn has_rtl(text: &str) -> bool {
+    text.chars().any(|c| bidi_class(c) == BidiClass::Rtl)
+}
+
+/// The isolated presentation forms of the Arabic letters `U+0621..=U+064A`
+/// and the number of their forms: `1` for the non-joining letters, `2` for
+/// the letters joining the previous one only (isolated & final), `4` for
+/// the dual-joining letters (isolated, final, initial & medial). Tatweel
+/// joins both sides but has no forms.
+const ARABIC_FORMS: [(u32, u32); 42] = [
+    (0xFE80, 1), // hamza
+    (0xFE81, 2), // alef with madda above
+    (0xFE83, 2), // alef with hamza above
+    (0xFE85, 2), // waw with hamza above
+    (0xFE87, 2), // alef with hamza below
+    (0xFE89, 4), // yeh with hamza above
+    (0xFE8D, 2), // alef
+    (0xFE8F, 4), // beh
+    (0xFE93, 2), // teh marbuta
+    (0xFE95, 4), // teh
+    (0xFE99, 4), // theh
+    (0xFE9D, 4), // jeem
+    (0xFEA1, 4), // hah
+    (0xFEA5, 4), // khah
+    (0xFEA9, 2), // dal
+    (0xFEAB, 2), // thal
+    (0xFEAD, 2), // reh
+    (0xFEAF, 2), // zain
+    (0xFEB1, 4), // seen
+    (0xFEB5, 4), // sheen
+    (0xFEB9, 4), // sad
+    (0xFEBD, 4), // dad
+    (0xFEC1, 4), // tah
+    (0xFEC5, 4), // zah
+    (0xFEC9, 4), // ain
+    (0xFECD, 4), // ghain
+    (0, 0),
+    (0, 0),
+    (0, 0),
+    (0, 0),
+    (0, 0),
+    (0, 4), // tatweel
+    (0xFED1, 4), // feh
+    (0xFED5, 4), // qaf
+    (0xFED9, 4), // kaf
+    (0xFEDD, 4), // lam
+    (0xFEE1, 4), // meem
+    (0xFEE5, 4), // noon
+    (0xFEE9, 4), // heh
+    (0xFEED, 2), // waw
+    (0xFEEF, 2), // alef maksura
+    (0xFEF1, 4), // yeh
+];
+
+fn arabic_forms(ch: char) -> Option<(u32, u32)> {
+    match ch as u32 {
+        code @ 0x0621..=0x064A => Some(ARABIC_FORMS[(code - 0x0621) as usize]),
+        _ => None,
+    }
+}
+
+/// The harakat and the other marks don't break the joining
+fn is_transparent(ch: char) -> bool {
+    matches!(ch as u32, 0x0610..=0x061A | 0x064B..=0x065F | 0x0670 | 0x06D6..=0x06ED)
+}
+
+/// Replaces the Arabic letters of the logical `text` with their contextual
+/// (isolated, initial, medial or final) presentation forms, so the letters
+/// are joined when rendered with the font having these forms. Every char
+/// is replaced with a single char (the lam-alef ligatures are not formed),
+/// so the indices of the shaped text match the indices of the `text`.
+pub fn shape_arabic(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let joins_next = |ch: Option<&char>| {
+        ch.and_then(|c| arabic_forms(*c))
+            .map_or(false, |(_, forms)| forms == 4)
+    };
+    let joins_prev = |ch: Option<&char>| {
+        ch.and_then(|c| arabic_forms(*c))
+            .map_or(false, |(_, forms)| forms >= 2)
+    };
+    let mut shaped = String::with_capacity(text.len());
+    for (idx, ch) in chars.iter().enumerate() {
+        let Some((isolated, forms)) = arabic_forms(*ch).filter(|(iso, _)| *iso > 0) else {
+            shaped.push(*ch);
+            continue;
+        };
+        let prev = chars[..idx].iter().rev().find(|c| !is_transparent(**c));
+        let next = chars[idx + 1..].iter().find(|c| !is_transparent(**c));
+        let joined_prev = forms >= 2 && joins_next(prev);
+        let joined_next = forms == 4 && joins_prev(next);
+        let offset = match (joined_prev, joined_next) {
+            (false, false) => 0,
+            (true, false) => 1,
+            (false, true) => 2,
+            (true, true) => 3,
+        };
+        shaped.push(char::from_u32(isolated + offset).unwrap_or(*ch));
+    }
+    shaped
+}
+
+/// The visual order of the bidirectional text, see [`bidi_layout`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BidiLayout {
+    /// The logical index of the char at each visual (left-to-right) position
+    pub order: Vec<usize>,
+    /// The resolved direction of each char, by the logical index
+    pub rtl: Vec<bool>,
+}
+
+/// Resolves the visual order of the logical `text` using a simplified
+/// bidirectional algorithm: strong right-to-left runs are reversed,
+/// neutrals take the direction of the surrounding runs (or the base
+/// direction when surrounded by runs of different direction), and for the
+/// right-to-left base direction the order of runs is reversed. Every line
+/// of the text is reordered separately, the line breaks keep their places.
+pub fn bidi_layout(text: &str, rtl: bool) -> BidiLayout {
+    let chars: Vec<char> = text.chars().collect();
+    let mut layout = BidiLayout::default();
+    let mut line_start = 0;
+    for line in text.split('\n') {
+        let len = line.chars().count();
+        let (order, directions) = reorder_line(&chars[line_start..line_start + len], rtl);
+        layout.order.extend(order.into_iter().map(|idx| idx + line_start));
+        layout.rtl.extend(directions);
+        line_start += len;
+        if line_start < chars.len() {
+            layout.order.push(line_start);
+            layout.rtl.push(rtl);
+            line_start += 1;
+        }
+    }
+    layout
+}
+
+/// Reorders the logical `text` into the visual (left-to-right) order,
+/// see [`bidi_layout`].
+pub fn reorder_visual(text: &str, rtl: bool) -> String {
+    if !rtl && !has_rtl(text) {
+        return text.to_string();
+    }
+    let chars: Vec<char> = text.chars().collect();
+    bidi_layout(text, rtl)
+        .order
+        .into_iter()
+        .map(|idx| chars[idx])
+        .collect()
+}
+
+fn reorder_line(chars: &[char], rtl: bool) -> (Vec<usize>, Vec<bool>) {
+    let base = if rtl { BidiClass::Rtl } else { BidiClass::Ltr };
+    let mut classes: Vec<BidiClass> = chars.iter().map(|c| bidi_class(*c)).collect();
+    // resolve neutrals
+    let mut idx = 0;
+    while idx < classes.len() {
+        if classes[idx] != BidiClass::Neutral {
+            idx += 1;
+            continue;
+        }
+        let start = idx;
+        while idx < classes.len() && classes[idx] == BidiClass::Neutral {
+            idx += 1;
+        }
+        let before = if start == 0 { base } else { classes[start - 1] };
+        let after = if idx == classes.len() {
+            base
+        } else {
+            classes[idx]
+        };
+        let resolved = if before == after { before } else { base };
+        classes[start..idx].fill(resolved);
+    }
+    // split into runs of the same direction
+    let mut runs: Vec<(BidiClass, Vec<usize>)> = vec![];
+    for (idx, class) in classes.iter().enumerate() {
+        match runs.last_mut() {
+            Some((last, run)) if last == class => run.push(idx),
+            _ => runs.push((*class, vec![idx])),
+        }
+    }
+    for (class, run) in runs.iter_mut() {
+        if *class == BidiClass::Rtl {
+            run.reverse();
+        }
+    }
+    if rtl {
+        runs.reverse();
+    }
+    let order = runs.into_iter().flat_map(|(_, run)| run).collect();
+    let directions = classes.into_iter().map(|c| c == BidiClass::Rtl).collect();
+    (order, directions)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reorder_bidi_text() {
+        assert_eq!(reorder_visual("hello world", false), "hello world");
+        assert_eq!(reorder_visual("שלום", false), "םולש");
+        assert_eq!(reorder_visual("abc שלום def", false), "abc םולש def");
+        assert_eq!(reorder_visual("שלום abc", true), "abc םולש");
+        assert_eq!(reorder_visual("שלום\nעולם", true), "םולש\nםלוע");
+    }
+
+    #[test]
+    fn shape_and_layout_arabic() {
+        // beh + alef + beh: initial, final, isolated
+        assert_eq!(shape_arabic("\u{0628}\u{0627}\u{0628}"), "\u{FE91}\u{FE8E}\u{FE8F}");
+        // beh + fatha + beh + beh: the mark doesn't break the joining
+        assert_eq!(
+            shape_arabic("\u{0628}\u{064E}\u{0628}\u{0628}"),
+            "\u{FE91}\u{064E}\u{FE92}\u{FE90}"
+        );
+        assert_eq!(shape_arabic("abc"), "abc");
+
+        let layout = bidi_layout("ab שלום", false);
+        assert_eq!(layout.order, vec![0, 1, 2, 6, 5, 4, 3]);
+        assert_eq!(layout.rtl, vec![false, false, false, true, true, true, true]);
+        let layout = bidi_layout("א\nb", true);
+        assert_eq!(layout.order, vec![0, 1, 2]);
+    }
+}
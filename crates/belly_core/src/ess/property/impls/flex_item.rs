@@ -1,5 +1,7 @@
 use super::parse;
 use crate::style_property;
+use crate::ess::units::{apply_length, LengthParser, RelativeLengths};
+use crate::ess::Length;
 use bevy::prelude::*;
 
 style_property! {
@@ -80,14 +82,19 @@ style_property! {
     #[doc = " <!-- @property-category=Flex Item -->"]
     FlexBasisProperty("flex-basis") {
         Default = "auto";
-        Item = Val;
-        Components = &'static mut Style;
+        Item = Length;
+        Components = (&'static mut Style, Option<&'static mut RelativeLengths>);
         Filters = With<Node>;
-        Parser = parse::ValParser;
-        Apply = |value, style, _assets, _commands, _entity| {
-            if &style.flex_basis != value {
-                style.flex_basis = *value;
-            }
+        Parser = LengthParser;
+        Apply = |value, components, _assets, commands, entity| {
+            apply_length(
+                value,
+                components,
+                commands,
+                entity,
+                Self::name(),
+                |s| &mut s.flex_basis,
+            );
         };
     }
 }
@@ -0,0 +1,385 @@
+use super::opacity::ComputedOpacity;
+use crate::compound_style_property;
+use crate::eml::Variant;
+use crate::ess::parse;
+use crate::ess::PropertyParser;
+use crate::ess::PropertyValue;
+use crate::ess::StyleProperty;
+use crate::ess::StylePropertyFunction;
+use crate::ess::StylePropertyMethods;
+use crate::ess::StylePropertyToken;
+use crate::style_property;
+use crate::ElementsError;
+use bevy::asset::load_internal_asset;
+use bevy::prelude::*;
+use bevy::render::render_resource::{AsBindGroup, ShaderRef, ShaderType};
+use bevy::ui::UiSystem;
+use bevy::utils::HashMap;
+use std::f32::consts::PI;
+use tagstr::tag;
+
+/// The number of the color stops the gradient can have
+pub const MAX_GRADIENT_STOPS: usize = 8;
+
+const GRADIENT_SHADER_HANDLE: Handle<Shader> =
+    Handle::weak_from_u128(0x5f3c_2a91_7d4e_4b06_9c1a_e2b8_4f70_d613);
+
+/// Renders the `background-image` gradients with the [`GradientMaterial`]
+pub struct GradientPlugin;
+impl Plugin for GradientPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(app, GRADIENT_SHADER_HANDLE, "gradient.wgsl", Shader::from_wgsl);
+        app.add_plugins(UiMaterialPlugin::<GradientMaterial>::default());
+        app.add_systems(
+            PostUpdate,
+            update_gradient_materials_system.after(UiSystem::Layout),
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GradientShape {
+    /// The gradient along the line at the `angle` (in radians, `0.`
+    /// points up, `PI / 2.` points right)
+    Linear { angle: f32 },
+    /// The elliptical gradient from the center to the corners
+    Ellipse,
+    /// The circular gradient from the center to the corners
+    Circle,
+}
+
+/// The gradient drawn by the `background-image` property
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    pub shape: GradientShape,
+    /// The colors and their positions from `0.` to `1.`
+    pub stops: Vec<(Color, f32)>,
+}
+
+impl Gradient {
+    fn from_function(func: &StylePropertyFunction) -> Result<Gradient, ElementsError> {
+        let mut args = func.args.as_slice();
+        let shape = match func.name.as_str() {
+            "linear-gradient" => match args.first().map(linear_angle) {
+                Some(Some(angle)) => {
+                    args = &args[1..];
+                    GradientShape::Linear { angle: angle? }
+                }
+                _ => GradientShape::Linear { angle: PI },
+            },
+            "radial-gradient" => match args.first() {
+                Some(StylePropertyToken::Identifier(shape)) if shape == "circle" => {
+                    args = &args[1..];
+                    GradientShape::Circle
+                }
+                Some(StylePropertyToken::Identifier(shape)) if shape == "ellipse" => {
+                    args = &args[1..];
+                    GradientShape::Ellipse
+                }
+                _ => GradientShape::Ellipse,
+            },
+            name => {
+                return Err(ElementsError::InvalidPropertyValue(format!(
+                    "Expected linear-gradient|radial-gradient, got `{name}`"
+                )))
+            }
+        };
+        if args.len() < 2 || args.len() > MAX_GRADIENT_STOPS {
+            return Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected 2 to {MAX_GRADIENT_STOPS} color stops in `{}`",
+                StylePropertyToken::Function(func.clone()).to_string()
+            )));
+        }
+        let mut stops = args.iter().map(color_stop).collect::<Result<Vec<_>, _>>()?;
+        // the missing positions are spread evenly between the known ones
+        let last = stops.len() - 1;
+        stops[0].1 = stops[0].1.or(Some(0.));
+        stops[last].1 = stops[last].1.or(Some(1.));
+        let mut known = 0;
+        for idx in 1..=last {
+            let Some(position) = stops[idx].1 else {
+                continue;
+            };
+            let from = stops[known].1.unwrap();
+            for missing in known + 1..idx {
+                let k = (missing - known) as f32 / (idx - known) as f32;
+                stops[missing].1 = Some(from + (position - from) * k);
+            }
+            known = idx;
+        }
+        let mut max = 0f32;
+        let stops = stops
+            .into_iter()
+            .map(|(color, position)| {
+                // the stops can't go back
+                max = max.max(position.unwrap());
+                (color, max)
+            })
+            .collect();
+        Ok(Gradient { shape, stops })
+    }
+}
+
+/// The angle of the `linear-gradient`: `45deg`, `0.5turn`, `1rad` or
+/// the direction: `to right`, `to top left`, etc. Returns `None` if the
+/// token is not the angle.
+fn linear_angle(token: &StylePropertyToken) -> Option<Result<f32, ElementsError>> {
+    match token {
+        StylePropertyToken::Dimension(val, unit) => Some(match unit.as_str() {
+            "deg" => Ok(val.to_float().to_radians()),
+            "rad" => Ok(val.to_float()),
+            "turn" => Ok(val.to_float() * 2. * PI),
+            unit => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected deg|rad|turn angle, got `{unit}`"
+            ))),
+        }),
+        StylePropertyToken::Tokens(tokens)
+            if tokens.first().map_or(false, |t| t.is_ident("to")) =>
+        {
+            let side = |name| tokens[1..].iter().any(|t| t.is_ident(name));
+            let (x, y) = (
+                side("right") as i32 - side("left") as i32,
+                side("bottom") as i32 - side("top") as i32,
+            );
+            if (x, y) == (0, 0) || tokens.len() > 3 {
+                return Some(Err(ElementsError::InvalidPropertyValue(format!(
+                    "Expected the direction like `to top right`, got `{}`",
+                    token.to_string()
+                ))));
+            }
+            // 0 points up, the angle grows clockwise
+            Some(Ok((x as f32).atan2(-y as f32)))
+        }
+        _ => None,
+    }
+}
+
+fn single(token: &StylePropertyToken) -> StyleProperty {
+    StyleProperty(std::iter::once(token.clone()).collect())
+}
+
+fn color_stop(token: &StylePropertyToken) -> Result<(Color, Option<f32>), ElementsError> {
+    match token {
+        StylePropertyToken::Tokens(tokens) => match tokens.as_slice() {
+            [color, StylePropertyToken::Percentage(position)] => Ok((
+                parse::color(&single(color))?,
+                Some(position.to_float() / 100.),
+            )),
+            _ => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected $color $percent color stop, got `{}`",
+                token.to_string()
+            ))),
+        },
+        color => Ok((parse::color(&single(color))?, None)),
+    }
+}
+
+/// <!-- @property-type=$gradient -->
+/// Gradient: `none`, `linear-gradient(angle, color stops)` or
+/// `radial-gradient(shape, color stops)`. The angle (`45deg`, `0.25turn`,
+/// `to top right`) and the shape (`circle` or `ellipse`) are optional,
+/// each color stop is the color optionally followed by the percent
+/// position: `linear-gradient(to right, #000, #fff 80%)`.
+pub struct GradientParser;
+impl PropertyParser<Option<Gradient>> for GradientParser {
+    fn parse(prop: &StyleProperty) -> Result<Option<Gradient>, ElementsError> {
+        match prop.as_slice() {
+            [StylePropertyToken::Identifier(ident)] if ident == "none" => Ok(None),
+            [StylePropertyToken::Function(func)] => Gradient::from_function(func).map(Some),
+            _ => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected none|$gradient, got `{}`",
+                prop.to_string()
+            ))),
+        }
+    }
+}
+
+/// The gradient of the element set by the `background-image` property
+#[derive(Component, Debug, Clone, Default)]
+pub struct BackgroundGradient {
+    pub gradient: Option<Gradient>,
+}
+
+style_property! {
+    #[doc = " Specify the gradient drawn over the `background-color` of the element:"]
+    #[doc = " ```css"]
+    #[doc = " background-image: linear-gradient(45deg, #000, #fff);"]
+    #[doc = " background-image: linear-gradient(to right, red, yellow 30%, green);"]
+    #[doc = " background-image: radial-gradient(circle, #ffffff80, transparent);"]
+    #[doc = " ```"]
+    #[doc = " "]
+    #[doc = " The gradient can have up to 8 color stops. The colors are faded by"]
+    #[doc = " the `opacity` of the element."]
+    #[doc = " <!-- @property-type=$gradient -->"]
+    #[doc = " <!-- @property-category=General -->"]
+    BackgroundImageProperty("background-image") {
+        Default = "none";
+        Item = Option<Gradient>;
+        Components = Option<&'static mut BackgroundGradient>;
+        Filters = With<Node>;
+        Parser = GradientParser;
+        Apply = |value, gradient, _assets, commands, entity| {
+            match gradient {
+                Some(mut gradient) if gradient.gradient != *value => {
+                    gradient.gradient = value.clone();
+                }
+                Some(_) => {}
+                None if value.is_some() => {
+                    commands.entity(entity).insert(BackgroundGradient {
+                        gradient: value.clone(),
+                    });
+                }
+                None => {}
+            }
+        };
+    }
+}
+
+compound_style_property! {
+    #[doc = " Specify the `background-color` and the `background-image` at once:"]
+    #[doc = " ```css"]
+    #[doc = " background: #2f2f2f;"]
+    #[doc = " background: linear-gradient(#3f3f3f, #2f2f2f);"]
+    #[doc = " background: #2f2f2f radial-gradient(circle, #ffffff20, transparent);"]
+    #[doc = " ```"]
+    #[doc = " "]
+    #[doc = " The omitted color is `transparent`, the omitted gradient is `none`."]
+    #[doc = " <!-- @property-type=$color $gradient -->"]
+    #[doc = " <!-- @property-category=General -->"]
+    BackgroundProperty("background", value) {
+        let props = match value {
+            Variant::String(unparsed) => StyleProperty::try_from(unparsed)?,
+            Variant::Style(prop) => prop,
+            v => return Self::error(format!("Don't know how to extract background from {v:?}")),
+        };
+        let mut color = Color::NONE;
+        let mut gradient = None;
+        for token in props.iter() {
+            match token {
+                StylePropertyToken::Function(func) if func.name.ends_with("-gradient") => {
+                    gradient = Some(Gradient::from_function(func)?);
+                }
+                token => {
+                    color = parse::color(&single(token))?;
+                }
+            }
+        }
+        let mut result = HashMap::default();
+        result.insert(tag!("background-color"), PropertyValue::new(color));
+        result.insert(tag!("background-image"), PropertyValue::new(gradient));
+        Ok(result)
+    }
+}
+
+/// The uniform of the [`GradientMaterial`], the layout matches `gradient.wgsl`
+#[derive(ShaderType, Debug, Clone, Default, PartialEq)]
+pub struct GradientUniform {
+    pub colors: [Vec4; MAX_GRADIENT_STOPS],
+    pub stops: [Vec4; MAX_GRADIENT_STOPS / 4],
+    pub count: u32,
+    pub kind: u32,
+    pub angle: f32,
+    pub size: Vec2,
+}
+
+impl GradientUniform {
+    pub fn new(gradient: &Gradient, size: Vec2, opacity: f32) -> GradientUniform {
+        let mut uniform = GradientUniform {
+            count: gradient.stops.len().min(MAX_GRADIENT_STOPS) as u32,
+            size,
+            ..default()
+        };
+        (uniform.kind, uniform.angle) = match gradient.shape {
+            GradientShape::Linear { angle } => (0, angle),
+            GradientShape::Ellipse => (1, 0.),
+            GradientShape::Circle => (2, 0.),
+        };
+        for (idx, (color, position)) in gradient.stops.iter().take(MAX_GRADIENT_STOPS).enumerate() {
+            let color = color.with_a(color.a() * opacity);
+            uniform.colors[idx] = Vec4::from_array(color.as_linear_rgba_f32());
+            uniform.stops[idx / 4][idx % 4] = *position;
+        }
+        uniform
+    }
+}
+
+/// Draws the gradient over the node, see `gradient.wgsl`
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+pub struct GradientMaterial {
+    #[uniform(0)]
+    pub gradient: GradientUniform,
+}
+
+impl UiMaterial for GradientMaterial {
+    fn fragment_shader() -> ShaderRef {
+        GRADIENT_SHADER_HANDLE.into()
+    }
+}
+
+/// Keeps the [`GradientMaterial`]s of the elements in sync with their
+/// [`BackgroundGradient`]s, sizes and opacity.
+pub fn update_gradient_materials_system(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<GradientMaterial>>,
+    gradients: Query<(
+        Entity,
+        &BackgroundGradient,
+        &Node,
+        Option<&ComputedOpacity>,
+        Option<&Handle<GradientMaterial>>,
+    )>,
+) {
+    for (entity, gradient, node, opacity, handle) in gradients.iter() {
+        let Some(gradient) = &gradient.gradient else {
+            commands
+                .entity(entity)
+                .remove::<BackgroundGradient>()
+                .remove::<Handle<GradientMaterial>>();
+            continue;
+        };
+        let opacity = opacity.map(|o| o.get()).unwrap_or(1.);
+        let uniform = GradientUniform::new(gradient, node.size(), opacity);
+        match handle {
+            Some(handle) => {
+                if materials.get(handle).map_or(false, |m| m.gradient != uniform) {
+                    if let Some(material) = materials.get_mut(handle) {
+                        material.gradient = uniform;
+                    }
+                }
+            }
+            None => {
+                let material = materials.add(GradientMaterial { gradient: uniform });
+                commands.entity(entity).insert(material);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn parse_gradients() {
+        let parse = |s: &str| GradientParser::parse(&StyleProperty::from_str(s).unwrap());
+        let positions = |g: &Gradient| g.stops.iter().map(|(_, p)| *p).collect::<Vec<_>>();
+        let gradient = parse("linear-gradient(0.25turn, #000, red 20%, #fff)").unwrap().unwrap();
+        assert_eq!(gradient.shape, GradientShape::Linear { angle: PI / 2. });
+        assert_eq!(positions(&gradient), vec![0., 0.2, 1.]);
+
+        let gradient = parse("linear-gradient(to top, #000, #111, #222, #333, #fff)").unwrap();
+        let gradient = gradient.unwrap();
+        assert_eq!(gradient.shape, GradientShape::Linear { angle: 0. });
+        assert_eq!(positions(&gradient), vec![0., 0.25, 0.5, 0.75, 1.]);
+        let gradient = parse("linear-gradient(#000 50%, #fff 20%)").unwrap().unwrap();
+        assert_eq!(gradient.shape, GradientShape::Linear { angle: PI });
+        assert_eq!(positions(&gradient), vec![0.5, 0.5]);
+
+        let gradient = parse("radial-gradient(circle, #fff, transparent)").unwrap().unwrap();
+        assert_eq!(gradient.shape, GradientShape::Circle);
+        assert_eq!(parse("none").unwrap(), None);
+        assert!(parse("linear-gradient(#000)").is_err());
+        assert!(parse("conic-gradient(#000, #fff)").is_err());
+    }
+}
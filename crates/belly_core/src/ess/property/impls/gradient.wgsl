@@ -0,0 +1,45 @@
+#import bevy_ui::ui_vertex_output::UiVertexOutput
+
+struct Gradient {
+    colors: array<vec4<f32>, 8>,
+    // the positions of the color stops, 4 per item
+    stops: array<vec4<f32>, 2>,
+    count: u32,
+    // 0 - linear, 1 - radial ellipse, 2 - radial circle
+    kind: u32,
+    // the angle of the linear gradient in radians, 0 points up
+    angle: f32,
+    size: vec2<f32>,
+};
+
+@group(1) @binding(0)
+var<uniform> gradient: Gradient;
+
+fn stop(idx: u32) -> f32 {
+    return gradient.stops[idx / 4u][idx % 4u];
+}
+
+@fragment
+fn fragment(in: UiVertexOutput) -> @location(0) vec4<f32> {
+    let size = max(gradient.size, vec2<f32>(1.0, 1.0));
+    let pos = (in.uv - vec2<f32>(0.5, 0.5)) * size;
+    var t = 0.0;
+    if gradient.kind == 0u {
+        let dir = vec2<f32>(sin(gradient.angle), -cos(gradient.angle));
+        let len = abs(size.x * dir.x) + abs(size.y * dir.y);
+        t = dot(pos, dir) / len + 0.5;
+    } else if gradient.kind == 1u {
+        t = length(pos / (size * 0.5)) / sqrt(2.0);
+    } else {
+        t = length(pos) / (length(size) * 0.5);
+    }
+    t = clamp(t, 0.0, 1.0);
+    var color = gradient.colors[0];
+    for (var idx = 1u; idx < gradient.count; idx++) {
+        let from = stop(idx - 1u);
+        let to = stop(idx);
+        let k = clamp((t - from) / max(to - from, 0.00001), 0.0, 1.0);
+        color = mix(color, gradient.colors[idx], k);
+    }
+    return color;
+}
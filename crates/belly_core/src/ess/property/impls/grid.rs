@@ -1,10 +1,12 @@
 use super::parse;
 use crate::build::StyleProperty;
-use crate::ess::{PropertyParser, StylePropertyToken};
+use crate::ess::{PropertyParser, StylePropertyMethods, StylePropertyToken};
 use crate::style_property;
 use crate::ElementsError;
 use bevy::prelude::*;
+use bevy::utils::HashMap;
 use smallvec::smallvec;
+use tagstr::Tag;
 
 style_property! {
     #[doc = " Controls whether automatically placed grid items are placed row-wise or"]
@@ -449,6 +451,222 @@ style_property! {
     }
 }
 
+/// Named grid areas of the grid container, defined by `grid-template-areas`
+/// property. Each area is stored as `(row, column)` placement pair.
+#[derive(Component, Default, Debug, Clone, PartialEq)]
+pub struct GridTemplateAreas(HashMap<Tag, (GridPlacement, GridPlacement)>);
+
+impl GridTemplateAreas {
+    pub fn get(&self, area: Tag) -> Option<(GridPlacement, GridPlacement)> {
+        self.0.get(&area).copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+pub fn grid_template_areas(prop: &StyleProperty) -> Result<GridTemplateAreas, ElementsError> {
+    if prop.len() == 1 && prop[0].is_ident("none") {
+        return Ok(GridTemplateAreas::default());
+    }
+    let mut cells: Vec<Vec<String>> = vec![];
+    for token in prop.iter() {
+        let StylePropertyToken::String(row) = token else {
+            return Err(ElementsError::InvalidPropertyValue(format!(
+                "grid-template-areas expects quoted rows, got `{}`",
+                token.to_string()
+            )));
+        };
+        let row: Vec<String> = row.split_whitespace().map(|c| c.to_string()).collect();
+        if row.is_empty() {
+            return Err(ElementsError::InvalidPropertyValue(format!(
+                "grid-template-areas rows should not be empty"
+            )));
+        }
+        if let Some(first) = cells.first() {
+            if first.len() != row.len() {
+                return Err(ElementsError::InvalidPropertyValue(format!(
+                    "all grid-template-areas rows should have the same number of columns"
+                )));
+            }
+        }
+        cells.push(row);
+    }
+
+    // name -> (row_start, row_end, column_start, column_end), zero based, inclusive
+    let mut bounds: HashMap<&str, (usize, usize, usize, usize)> = HashMap::default();
+    for (row, names) in cells.iter().enumerate() {
+        for (column, name) in names.iter().enumerate() {
+            if name.chars().all(|c| c == '.') {
+                continue;
+            }
+            let entry = bounds
+                .entry(name.as_str())
+                .or_insert((row, row, column, column));
+            entry.0 = entry.0.min(row);
+            entry.1 = entry.1.max(row);
+            entry.2 = entry.2.min(column);
+            entry.3 = entry.3.max(column);
+        }
+    }
+    let mut areas = HashMap::default();
+    for (name, (row_start, row_end, column_start, column_end)) in bounds {
+        for row in row_start..=row_end {
+            for column in column_start..=column_end {
+                if cells[row][column] != name {
+                    return Err(ElementsError::InvalidPropertyValue(format!(
+                        "grid area `{name}` should be rectangular"
+                    )));
+                }
+            }
+        }
+        let row = GridPlacement::default()
+            .set_start(row_start as i16 + 1)
+            .set_end(row_end as i16 + 2);
+        let column = GridPlacement::default()
+            .set_start(column_start as i16 + 1)
+            .set_end(column_end as i16 + 2);
+        areas.insert(Tag::new(name), (row, column));
+    }
+    Ok(GridTemplateAreas(areas))
+}
+
+/// <!-- @property-type=$gridareas
+pub struct GridTemplateAreasParser;
+impl PropertyParser<GridTemplateAreas> for GridTemplateAreasParser {
+    fn parse(value: &StyleProperty) -> Result<GridTemplateAreas, ElementsError> {
+        grid_template_areas(value)
+    }
+}
+
+style_property! {
+    #[doc = " Defines named grid areas of the grid container. Each quoted string"]
+    #[doc = " is a row, each word in the row is a cell. Cells with the same name"]
+    #[doc = " form the area, `.` marks an unnamed cell. Children are placed into"]
+    #[doc = " the areas with `grid-area` property."]
+    #[doc = " ```css"]
+    #[doc = " grid-template-areas: \"header header\" \"nav main\";"]
+    #[doc = " ```"]
+    #[doc = " "]
+    #[doc = " <https://developer.mozilla.org/en-US/docs/Web/CSS/grid-template-areas>"]
+    #[doc = " <!-- @property-type=$gridareas -->"]
+    #[doc = " <!-- @property-category=Grid -->"]
+    GridTemplateAreasProperty("grid-template-areas") {
+        Default = "none";
+        Item = GridTemplateAreas;
+        Components = Option<&'static mut GridTemplateAreas>;
+        Filters = With<Node>;
+        Parser = GridTemplateAreasParser;
+        Apply = |value, areas, _assets, commands, entity| {
+            match areas {
+                Some(_) if value.is_empty() => {
+                    commands.entity(entity).remove::<GridTemplateAreas>();
+                }
+                Some(mut areas) => {
+                    if &*areas != value {
+                        *areas = value.clone();
+                    }
+                }
+                None if !value.is_empty() => {
+                    commands.entity(entity).insert(value.clone());
+                }
+                None => { }
+            }
+        };
+    }
+}
+
+/// The name of the parent grid area the item is placed into,
+/// defined by `grid-area` property.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct GridArea(pub Tag);
+
+/// <!-- @property-type=$gridarea
+pub struct GridAreaParser;
+impl PropertyParser<Option<Tag>> for GridAreaParser {
+    fn parse(value: &StyleProperty) -> Result<Option<Tag>, ElementsError> {
+        match value.first() {
+            Some(StylePropertyToken::Identifier(ident)) if value.len() == 1 && ident == "auto" => {
+                Ok(None)
+            }
+            Some(StylePropertyToken::Identifier(ident)) if value.len() == 1 => {
+                Ok(Some(Tag::new(ident)))
+            }
+            _ => Err(ElementsError::InvalidPropertyValue(format!(
+                "grid-area expects auto|$ident, got `{}`",
+                value.to_string()
+            ))),
+        }
+    }
+}
+
+style_property! {
+    #[doc = " Places the grid item into the named area defined by `grid-template-areas`"]
+    #[doc = " of the parent grid container. Overrides `grid-row` and `grid-column`."]
+    #[doc = " ```css"]
+    #[doc = " grid-area: main;"]
+    #[doc = " ```"]
+    #[doc = " "]
+    #[doc = " <https://developer.mozilla.org/en-US/docs/Web/CSS/grid-area>"]
+    #[doc = " <!-- @property-type=$gridarea -->"]
+    #[doc = " <!-- @property-category=Grid -->"]
+    GridAreaProperty("grid-area") {
+        Default = "auto";
+        Item = Option<Tag>;
+        Components = Option<&'static mut GridArea>;
+        Filters = With<Node>;
+        Parser = GridAreaParser;
+        Apply = |value, area, _assets, commands, entity| {
+            match (value, area) {
+                (Some(name), Some(mut area)) => {
+                    if &area.0 != name {
+                        area.0 = *name;
+                    }
+                }
+                (Some(name), None) => { commands.entity(entity).insert(GridArea(*name)); }
+                (None, None) => { }
+                _ => { commands.entity(entity).remove::<GridArea>(); }
+            }
+        };
+    }
+}
+
+/// Resolves `grid-area` of the items into the `grid-row` & `grid-column`
+/// using `grid-template-areas` of the parent. Only the items with the changed
+/// area or parent and the children of the changed templates are resolved, the
+/// items with the area missing from the parent template keep their placement.
+#[allow(clippy::type_complexity)]
+pub fn apply_grid_areas_system(
+    changed_items: Query<Entity, (With<GridArea>, Or<(Changed<GridArea>, Changed<Parent>)>)>,
+    changed_containers: Query<&Children, Changed<GridTemplateAreas>>,
+    containers: Query<&GridTemplateAreas>,
+    mut items: Query<(&GridArea, &Parent, &mut Style)>,
+) {
+    let changed = changed_containers
+        .iter()
+        .flat_map(|children| children.iter().copied())
+        .chain(changed_items.iter());
+    for entity in changed {
+        let Ok((area, parent, mut style)) = items.get_mut(entity) else {
+            continue;
+        };
+        let Some((row, column)) = containers
+            .get(parent.get())
+            .ok()
+            .and_then(|template| template.get(area.0))
+        else {
+            continue;
+        };
+        if style.grid_row != row {
+            style.grid_row = row;
+        }
+        if style.grid_column != column {
+            style.grid_column = column;
+        }
+    }
+}
+
 style_property! {
     #[doc = " For Flexbox items:"]
     #[doc = "   - This property has no effect. See `justify-content` for main-axis alignment of flex items."]
@@ -543,6 +761,77 @@ mod tests {
         assert_eq!(g, GridPlacement::span(2));
     }
 
+    #[test]
+    fn parse_grid_template_areas() {
+        let p = StyleProperty::from_str("\"header header\" \"nav main\" \". main\"").unwrap();
+        let areas = GridTemplateAreasParser::parse(&p).unwrap();
+        let placement = |start, end| GridPlacement::default().set_start(start).set_end(end);
+        assert_eq!(
+            areas.get(Tag::new("header")),
+            Some((placement(1, 2), placement(1, 3)))
+        );
+        assert_eq!(
+            areas.get(Tag::new("nav")),
+            Some((placement(2, 3), placement(1, 2)))
+        );
+        assert_eq!(
+            areas.get(Tag::new("main")),
+            Some((placement(2, 4), placement(2, 3)))
+        );
+
+        let p = StyleProperty::from_str("\"a b\" \"b a\"").unwrap();
+        assert!(GridTemplateAreasParser::parse(&p).is_err());
+
+        let p = StyleProperty::from_str("\"a b\" \"a\"").unwrap();
+        assert!(GridTemplateAreasParser::parse(&p).is_err());
+    }
+
+    #[test]
+    fn apply_grid_areas() {
+        let mut app = App::new();
+        app.add_systems(Update, apply_grid_areas_system);
+        let p = StyleProperty::from_str("\"header header\" \"nav main\"").unwrap();
+        let areas = GridTemplateAreasParser::parse(&p).unwrap();
+        let container = app.world.spawn(areas).id();
+        let item = app
+            .world
+            .spawn((GridArea(Tag::new("main")), Style::default()))
+            .set_parent(container)
+            .id();
+        let placement = |start, end| GridPlacement::default().set_start(start).set_end(end);
+
+        app.update();
+        let style = app.world.get::<Style>(item).unwrap();
+        assert_eq!(style.grid_row, placement(2, 3));
+        assert_eq!(style.grid_column, placement(2, 3));
+
+        // unresolved area keeps the placement
+        app.world.get_mut::<GridArea>(item).unwrap().0 = Tag::new("footer");
+        app.update();
+        let style = app.world.get::<Style>(item).unwrap();
+        assert_eq!(style.grid_row, placement(2, 3));
+        assert_eq!(style.grid_column, placement(2, 3));
+
+        app.world.get_mut::<GridArea>(item).unwrap().0 = Tag::new("nav");
+        app.update();
+        let style = app.world.get::<Style>(item).unwrap();
+        assert_eq!(style.grid_column, placement(1, 2));
+
+        // unchanged areas are not resolved again
+        app.world.get_mut::<Style>(item).unwrap().grid_column = GridPlacement::DEFAULT;
+        app.update();
+        let style = app.world.get::<Style>(item).unwrap();
+        assert_eq!(style.grid_column, GridPlacement::DEFAULT);
+
+        let p = StyleProperty::from_str("\"nav main\"").unwrap();
+        let areas = GridTemplateAreasParser::parse(&p).unwrap();
+        app.world.entity_mut(container).insert(areas);
+        app.update();
+        let style = app.world.get::<Style>(item).unwrap();
+        assert_eq!(style.grid_row, placement(1, 2));
+        assert_eq!(style.grid_column, placement(1, 2));
+    }
+
     #[test]
     fn parse_grid_track_repeated() {
         let p = StyleProperty::from_str("auto flex(1.0) 20px").unwrap();
@@ -1,7 +1,8 @@
 use super::parse;
 use crate::compound_style_property;
-use crate::ess::ToRectMap;
 use crate::style_property;
+use crate::ess::units::{apply_length, LengthParser, RelativeLengths};
+use crate::ess::{Length, LengthRect};
 use bevy::prelude::*;
 
 style_property! {
@@ -42,7 +43,7 @@ compound_style_property! {
     #[doc = " <!-- @property-type=$rect -->"]
     #[doc = " <!-- @property-category=Layout Control -->"]
     PositionProperty("position", value) {
-        let rect = UiRect::try_from(value)?;
+        let rect = LengthRect::try_from(value)?;
         Ok(rect.to_rect_map(""))
     }
 }
@@ -55,14 +56,19 @@ style_property! {
     #[doc = " <!-- @property-category=Layout Control Positioning -->"]
     LeftProperty("left") {
         Default = "undefined";
-        Item = Val;
-        Components = &'static mut Style;
+        Item = Length;
+        Components = (&'static mut Style, Option<&'static mut RelativeLengths>);
         Filters = With<Node>;
-        Parser = parse::ValParser;
-        Apply = |value, style, _assets, _commands, _entity| {
-            if &style.left != value {
-                style.left = *value;
-            }
+        Parser = LengthParser;
+        Apply = |value, components, _assets, commands, entity| {
+            apply_length(
+                value,
+                components,
+                commands,
+                entity,
+                Self::name(),
+                |s| &mut s.left,
+            );
         };
     }
 }
@@ -75,14 +81,19 @@ style_property! {
     #[doc = " <!-- @property-category=Layout Control Positioning -->"]
     RightProperty("right") {
         Default = "undefined";
-        Item = Val;
-        Components = &'static mut Style;
+        Item = Length;
+        Components = (&'static mut Style, Option<&'static mut RelativeLengths>);
         Filters = With<Node>;
-        Parser = parse::ValParser;
-        Apply = |value, style, _assets, _commands, _entity| {
-            if &style.right != value {
-                style.right = *value;
-            }
+        Parser = LengthParser;
+        Apply = |value, components, _assets, commands, entity| {
+            apply_length(
+                value,
+                components,
+                commands,
+                entity,
+                Self::name(),
+                |s| &mut s.right,
+            );
         };
     }
 }
@@ -95,14 +106,19 @@ style_property! {
     #[doc = " <!-- @property-category=Layout Control Positioning -->"]
     TopProperty("top") {
         Default = "undefined";
-        Item = Val;
-        Components = &'static mut Style;
+        Item = Length;
+        Components = (&'static mut Style, Option<&'static mut RelativeLengths>);
         Filters = With<Node>;
-        Parser = parse::ValParser;
-        Apply = |value, style, _assets, _commands, _entity| {
-            if &style.top != value {
-                style.top = *value;
-            }
+        Parser = LengthParser;
+        Apply = |value, components, _assets, commands, entity| {
+            apply_length(
+                value,
+                components,
+                commands,
+                entity,
+                Self::name(),
+                |s| &mut s.top,
+            );
         };
     }
 }
@@ -115,21 +131,36 @@ style_property! {
     #[doc = " <!-- @property-category=Layout Control Positioning -->"]
     BottomProperty("bottom") {
         Default = "undefined";
-        Item = Val;
-        Components = &'static mut Style;
+        Item = Length;
+        Components = (&'static mut Style, Option<&'static mut RelativeLengths>);
         Filters = With<Node>;
-        Parser = parse::ValParser;
-        Apply = |value, style, _assets, _commands, _entity| {
-            if &style.bottom != value {
-                style.bottom = *value;
-            }
+        Parser = LengthParser;
+        Apply = |value, components, _assets, commands, entity| {
+            apply_length(
+                value,
+                components,
+                commands,
+                entity,
+                Self::name(),
+                |s| &mut s.bottom,
+            );
         };
     }
 }
 
-// todo!(add back Overflow)
 style_property! {
-    #[doc = " TODO: add OverflowProperty descripion"]
+    #[doc = " Specify whether the element clips its content by providing value"]
+    #[doc = " to `Style.overflow`:"]
+    #[doc = " ```css"]
+    #[doc = " overflow: clip;"]
+    #[doc = " overflow: clip visible;"]
+    #[doc = " ```"]
+    #[doc = " "]
+    #[doc = " The clipped descendants are drawn only inside the bounds of the"]
+    #[doc = " element and receive the pointer input only there, so any element can"]
+    #[doc = " be the viewport of the scrolled or the moving content. The clip is"]
+    #[doc = " rectangular. The nested clips intersect."]
+    #[doc = " <!-- @property-type=$overflow -->"]
     #[doc = " <!-- @property-category=Layout Control -->"]
     OverflowProperty("overflow") {
         Default = "visible";
@@ -176,3 +207,21 @@ style_property! {
         };
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ess::{PropertyParser, StyleProperty};
+    use std::str::FromStr;
+
+    #[test]
+    fn parse_overflow() {
+        let overflow = |s: &str| parse::OverflowParser::parse(&StyleProperty::from_str(s).unwrap());
+        assert_eq!(overflow("clip").unwrap(), Overflow::clip());
+        assert_eq!(overflow("hidden").unwrap(), Overflow::clip());
+        assert_eq!(overflow("visible clip").unwrap(), Overflow::clip_y());
+        assert_eq!(overflow("clip_x").unwrap(), Overflow::clip_x());
+        assert!(overflow("scroll").is_err());
+        assert!(overflow("clip clip clip").is_err());
+    }
+}
@@ -0,0 +1,212 @@
+use crate::ess::PropertyParser;
+use crate::ess::StyleProperty;
+use crate::ess::StylePropertyMethods;
+use crate::ess::StylePropertyToken;
+use crate::style_property;
+use crate::ElementsError;
+use bevy::prelude::*;
+use bevy::render::render_resource::TextureFormat;
+
+/// The image masking the element, set by the `mask-image` property.
+/// The elements with the image (`<img>`) get the copy of their image with
+/// the alpha multiplied by the alpha of the mask. The elements without the
+/// image use the mask as the image, so their background takes its shape.
+#[derive(Component, Debug, Default)]
+pub struct MaskImage {
+    pub mask: Option<Handle<Image>>,
+    /// The image of the element before masking
+    source: Option<Handle<Image>>,
+    /// The masked copy of the `source`
+    masked: Option<Handle<Image>>,
+    /// The mask the `masked` image was made with
+    baked_with: Option<AssetId<Image>>,
+    /// The element had no image, the mask is used as the image
+    shaped: bool,
+}
+
+/// <!-- @property-type=$mask -->
+/// Mask: `none` or the path to the image: `url("masks/circle.png")`.
+pub struct MaskImageParser;
+impl PropertyParser<Option<String>> for MaskImageParser {
+    fn parse(prop: &StyleProperty) -> Result<Option<String>, ElementsError> {
+        let err = || {
+            ElementsError::InvalidPropertyValue(format!(
+                "Expected none|url(\"path\"), got `{}`",
+                prop.to_string()
+            ))
+        };
+        if prop.len() != 1 {
+            return Err(err());
+        }
+        match &prop[0] {
+            StylePropertyToken::Identifier(ident) if ident == "none" => Ok(None),
+            StylePropertyToken::String(path) => Ok(Some(path.clone())),
+            StylePropertyToken::Function(func) if func.name == "url" => match func.args.as_slice() {
+                [StylePropertyToken::String(path)] => Ok(Some(path.clone())),
+                _ => Err(err()),
+            },
+            _ => Err(err()),
+        }
+    }
+}
+
+style_property! {
+    #[doc = " Specify the image masking the element by its alpha:"]
+    #[doc = " ```css"]
+    #[doc = " mask-image: url(\"masks/circle.png\");"]
+    #[doc = " mask-image: none;"]
+    #[doc = " ```"]
+    #[doc = " "]
+    #[doc = " The mask is stretched over the element. The `<img>` elements are"]
+    #[doc = " masked with the copy of their image (only the 8-bit RGBA images can"]
+    #[doc = " be masked), the other elements draw their `background-color` in the"]
+    #[doc = " shape of the mask. The descendants of the element are not masked."]
+    #[doc = " <!-- @property-type=$mask -->"]
+    #[doc = " <!-- @property-category=General -->"]
+    MaskImageProperty("mask-image") {
+        Default = "none";
+        Item = Option<String>;
+        Components = Option<&'static mut MaskImage>;
+        Filters = With<Node>;
+        Parser = MaskImageParser;
+        Apply = |value, mask, assets, commands, entity| {
+            let handle: Option<Handle<Image>> =
+                value.as_ref().map(|path| assets.load(path.clone()));
+            match mask {
+                Some(mut mask) if mask.mask != handle => mask.mask = handle,
+                Some(_) => {}
+                None if handle.is_some() => {
+                    commands.entity(entity).insert(MaskImage {
+                        mask: handle,
+                        ..default()
+                    });
+                }
+                None => {}
+            }
+        };
+    }
+}
+
+/// Returns the copy of the `source` image with the alpha multiplied by the
+/// alpha of the `mask` stretched over it, or `None` if any of the images is
+/// empty or is not the 8-bit RGBA image.
+pub fn bake_mask(source: &Image, mask: &Image) -> Option<Image> {
+    let rgba = |image: &Image| {
+        matches!(
+            image.texture_descriptor.format,
+            TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb
+        )
+    };
+    if !rgba(source) || !rgba(mask) {
+        return None;
+    }
+    let (width, height) = (source.width() as usize, source.height() as usize);
+    let (mask_width, mask_height) = (mask.width() as usize, mask.height() as usize);
+    if width * height * mask_width * mask_height == 0 {
+        return None;
+    }
+    let mut masked = source.clone();
+    for y in 0..height {
+        let mask_y = y * mask_height / height;
+        for x in 0..width {
+            let mask_x = x * mask_width / width;
+            let alpha = mask.data[(mask_y * mask_width + mask_x) * 4 + 3] as u16;
+            let idx = (y * width + x) * 4 + 3;
+            masked.data[idx] = (masked.data[idx] as u16 * alpha / 255) as u8;
+        }
+    }
+    Some(masked)
+}
+
+/// Masks the images of the elements with the [`MaskImage`]s once the images
+/// are loaded, masks them again when the element changes its image and
+/// restores the images when the mask is removed.
+pub fn apply_mask_system(
+    mut commands: Commands,
+    mut masks: Query<(Entity, &mut MaskImage, Option<&mut UiImage>)>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    for (entity, mut mask, image) in masks.iter_mut() {
+        let Some(mask_handle) = mask.mask.clone() else {
+            match image {
+                Some(_) if mask.shaped => {
+                    commands.entity(entity).remove::<UiImage>();
+                }
+                Some(mut image) if mask.masked.as_ref() == Some(&image.texture) => {
+                    if let Some(source) = mask.source.clone() {
+                        image.texture = source;
+                    }
+                }
+                _ => {}
+            }
+            commands.entity(entity).remove::<MaskImage>();
+            continue;
+        };
+        let Some(mut image) = image else {
+            commands.entity(entity).insert(UiImage::new(mask_handle));
+            mask.shaped = true;
+            continue;
+        };
+        if mask.shaped {
+            if image.texture != mask_handle {
+                image.texture = mask_handle;
+            }
+            continue;
+        }
+        if mask.masked.as_ref() != Some(&image.texture)
+            && mask.source.as_ref() != Some(&image.texture)
+        {
+            // the image is changed by the widget
+            mask.source = Some(image.texture.clone());
+            mask.baked_with = None;
+        }
+        if mask.baked_with == Some(mask_handle.id()) {
+            continue;
+        }
+        let source = mask.source.as_ref().and_then(|source| images.get(source));
+        let (Some(source), Some(mask_image)) = (source, images.get(&mask_handle)) else {
+            continue;
+        };
+        let baked = bake_mask(source, mask_image);
+        mask.baked_with = Some(mask_handle.id());
+        match baked {
+            Some(baked) => {
+                let handle = images.add(baked);
+                image.texture = handle.clone();
+                mask.masked = Some(handle);
+            }
+            None => warn!("mask-image: only the 8-bit RGBA images can be masked"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bevy::render::render_asset::RenderAssetUsages;
+    use bevy::render::render_resource::{Extent3d, TextureDimension};
+
+    fn image(width: u32, height: u32, data: Vec<u8>, format: TextureFormat) -> Image {
+        let size = Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        let usage = RenderAssetUsages::default();
+        Image::new(size, TextureDimension::D2, data, format, usage)
+    }
+
+    #[test]
+    fn bake_mask_multiplies_alpha() {
+        let source = image(2, 2, vec![255; 16], TextureFormat::Rgba8UnormSrgb);
+        // the left half is opaque, the right one is half-transparent
+        let mask = image(2, 1, vec![0, 0, 0, 255, 0, 0, 0, 128], TextureFormat::Rgba8Unorm);
+        let masked = bake_mask(&source, &mask).unwrap();
+        let alpha: Vec<u8> = masked.data.chunks(4).map(|px| px[3]).collect();
+        assert_eq!(alpha, vec![255, 128, 255, 128]);
+        assert_eq!(masked.data[0..3], [255, 255, 255]);
+
+        let float = image(1, 1, vec![0; 16], TextureFormat::Rgba32Float);
+        assert!(bake_mask(&float, &mask).is_none());
+    }
+}
@@ -0,0 +1,262 @@
+use super::opacity::ComputedOpacity;
+use crate::ess::PropertyParser;
+use crate::ess::StyleProperty;
+use crate::ess::StylePropertyMethods;
+use crate::ess::StylePropertyToken;
+use crate::style_property;
+use crate::ElementsError;
+use bevy::prelude::*;
+use bevy::ui::UiSystem;
+use bevy::utils::HashMap;
+use std::any::Any;
+use std::hash::Hash;
+use tagstr::Tag;
+
+/// The values of the element forwarded to its material
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct UiMaterialInputs {
+    /// The size of the node in logical pixels
+    pub size: Vec2,
+    /// The `background-color` with the `opacity` applied
+    pub background: Color,
+    /// The `border-color` with the `opacity` applied
+    pub border: Color,
+    /// The opacity of the element multiplied by the opacity of its parents
+    pub opacity: f32,
+}
+
+/// The [`UiMaterial`] the elements can be drawn with, see [`RegisterUiMaterial`]
+pub trait ElementMaterial: UiMaterial {
+    /// Copies the inputs of the element into the material uniforms. Called
+    /// when the material is made for the element and when the inputs change.
+    fn update_inputs(&mut self, _inputs: &UiMaterialInputs) {}
+}
+
+type MaterialFactory<M> = Box<dyn Fn() -> M + Send + Sync>;
+
+/// The materials registered with [`RegisterUiMaterial`] by their names
+#[derive(Resource, Default)]
+pub struct UiMaterials(HashMap<Tag, Box<dyn Any + Send + Sync>>);
+
+impl UiMaterials {
+    fn factory<M: ElementMaterial>(&self, name: Tag) -> Option<&MaterialFactory<M>> {
+        self.0.get(&name)?.downcast_ref()
+    }
+
+    /// Returns `true` if the material with the `name` is registered
+    pub fn contains(&self, name: Tag) -> bool {
+        self.0.contains_key(&name)
+    }
+}
+
+pub trait RegisterUiMaterial {
+    /// Makes the material available for the `ui-material: name` property.
+    /// Every element gets its own material made by the `factory`, so the
+    /// uniforms set by [`ElementMaterial::update_inputs`] are per element:
+    /// ```rust,ignore
+    /// app.register_ui_material("hologram", || HologramMaterial::default());
+    /// ```
+    fn register_ui_material<M>(
+        &mut self,
+        name: &str,
+        factory: impl Fn() -> M + Send + Sync + 'static,
+    ) -> &mut Self
+    where
+        M: ElementMaterial,
+        M::Data: PartialEq + Eq + Hash + Clone;
+}
+
+impl RegisterUiMaterial for App {
+    fn register_ui_material<M>(
+        &mut self,
+        name: &str,
+        factory: impl Fn() -> M + Send + Sync + 'static,
+    ) -> &mut Self
+    where
+        M: ElementMaterial,
+        M::Data: PartialEq + Eq + Hash + Clone,
+    {
+        if !self.is_plugin_added::<UiMaterialPlugin<M>>() {
+            self.add_plugins(UiMaterialPlugin::<M>::default());
+            self.add_systems(
+                PostUpdate,
+                update_element_materials_system::<M>.after(UiSystem::Layout),
+            );
+        }
+        let factory: MaterialFactory<M> = Box::new(factory);
+        self.world
+            .get_resource_or_insert_with(UiMaterials::default)
+            .0
+            .insert(Tag::new(name), Box::new(factory));
+        self
+    }
+}
+
+/// The material of the element set by the `ui-material` property
+#[derive(Component, Debug, Default)]
+pub struct UiMaterialStyle {
+    pub name: Option<Tag>,
+    /// The name of the material the element is drawn with
+    applied: Option<Tag>,
+    /// The inputs last forwarded to the material
+    inputs: UiMaterialInputs,
+}
+
+/// <!-- @property-type=$material -->
+/// Material: `none` or the name of the material registered with
+/// `app.register_ui_material("name", ...)`.
+pub struct UiMaterialParser;
+impl PropertyParser<Option<Tag>> for UiMaterialParser {
+    fn parse(prop: &StyleProperty) -> Result<Option<Tag>, ElementsError> {
+        match prop.as_slice() {
+            [StylePropertyToken::Identifier(ident)] if ident == "none" => Ok(None),
+            [StylePropertyToken::Identifier(name)] | [StylePropertyToken::String(name)] => {
+                Ok(Some(Tag::new(name)))
+            }
+            _ => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected none|$ident, got `{}`",
+                prop.to_string()
+            ))),
+        }
+    }
+}
+
+style_property! {
+    #[doc = " Specify the material the element is drawn with:"]
+    #[doc = " ```css"]
+    #[doc = " ui-material: hologram;"]
+    #[doc = " ui-material: none;"]
+    #[doc = " ```"]
+    #[doc = " "]
+    #[doc = " The material is registered with `app.register_ui_material(\"hologram\", ...)`"]
+    #[doc = " and gets the size, the colors and the opacity of the element. It is"]
+    #[doc = " drawn over the `background-color` of the element."]
+    #[doc = " <!-- @property-type=$material -->"]
+    #[doc = " <!-- @property-category=General -->"]
+    UiMaterialProperty("ui-material") {
+        Default = "none";
+        Item = Option<Tag>;
+        Components = Option<&'static mut UiMaterialStyle>;
+        Filters = With<Node>;
+        Parser = UiMaterialParser;
+        Apply = |value, style, _assets, commands, entity| {
+            match style {
+                Some(mut style) if style.name != *value => style.name = *value,
+                Some(_) => {}
+                None if value.is_some() => {
+                    commands.entity(entity).insert(UiMaterialStyle {
+                        name: *value,
+                        ..default()
+                    });
+                }
+                None => {}
+            }
+        };
+    }
+}
+
+/// Gives the elements the materials of the type `M` they are styled with,
+/// forwards the [`UiMaterialInputs`] to them and removes the materials the
+/// elements are not styled with anymore.
+pub fn update_element_materials_system<M: ElementMaterial>(
+    mut commands: Commands,
+    registry: Res<UiMaterials>,
+    mut materials: ResMut<Assets<M>>,
+    mut elements: Query<(
+        Entity,
+        &mut UiMaterialStyle,
+        &Node,
+        Option<&BackgroundColor>,
+        Option<&BorderColor>,
+        Option<&ComputedOpacity>,
+        Option<&Handle<M>>,
+    )>,
+) {
+    for (entity, mut style, node, background, border, opacity, handle) in elements.iter_mut() {
+        let Some(factory) = style.name.and_then(|name| registry.factory::<M>(name)) else {
+            if handle.is_some() {
+                commands.entity(entity).remove::<Handle<M>>();
+            }
+            continue;
+        };
+        let inputs = UiMaterialInputs {
+            size: node.size(),
+            background: background.map_or(Color::NONE, |c| c.0),
+            border: border.map_or(Color::NONE, |c| c.0),
+            opacity: opacity.map_or(1., |o| o.get()),
+        };
+        match handle {
+            Some(handle) if style.applied == style.name => {
+                if style.inputs != inputs {
+                    if let Some(material) = materials.get_mut(handle) {
+                        material.update_inputs(&inputs);
+                    }
+                    style.inputs = inputs;
+                }
+            }
+            _ => {
+                let mut material = factory();
+                material.update_inputs(&inputs);
+                commands.entity(entity).insert(materials.add(material));
+                style.applied = style.name;
+                style.inputs = inputs;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::render::render_resource::AsBindGroup;
+
+    #[derive(Asset, TypePath, AsBindGroup, Debug, Clone, Default)]
+    struct TintMaterial {
+        #[uniform(0)]
+        tint: Color,
+    }
+
+    impl UiMaterial for TintMaterial {}
+
+    impl ElementMaterial for TintMaterial {
+        fn update_inputs(&mut self, inputs: &UiMaterialInputs) {
+            self.tint = inputs.background;
+        }
+    }
+
+    #[test]
+    fn forward_inputs_to_materials() {
+        let mut world = World::new();
+        world.init_resource::<Assets<TintMaterial>>();
+        let mut registry = UiMaterials::default();
+        let factory: MaterialFactory<TintMaterial> = Box::new(TintMaterial::default);
+        registry.0.insert(Tag::new("tint"), Box::new(factory));
+        world.insert_resource(registry);
+        let entity = world
+            .spawn((
+                NodeBundle {
+                    background_color: Color::RED.into(),
+                    ..default()
+                },
+                UiMaterialStyle {
+                    name: Some(Tag::new("tint")),
+                    ..default()
+                },
+            ))
+            .id();
+        let tint = |world: &World| {
+            let handle = world.get::<Handle<TintMaterial>>(entity)?;
+            world.resource::<Assets<TintMaterial>>().get(handle).map(|m| m.tint)
+        };
+
+        world.run_system_once(update_element_materials_system::<TintMaterial>);
+        assert_eq!(tint(&world), Some(Color::RED));
+        world.get_mut::<BackgroundColor>(entity).unwrap().0 = Color::BLUE;
+        world.run_system_once(update_element_materials_system::<TintMaterial>);
+        assert_eq!(tint(&world), Some(Color::BLUE));
+        world.get_mut::<UiMaterialStyle>(entity).unwrap().name = Some(Tag::new("unknown"));
+        world.run_system_once(update_element_materials_system::<TintMaterial>);
+        assert!(world.get::<Handle<TintMaterial>>(entity).is_none());
+    }
+}
@@ -1,8 +1,23 @@
+pub mod anchor;
+pub mod backdrop;
+#[cfg(feature = "backdrop-blur")]
+pub mod backdrop_blur;
+pub mod bitmap_font;
+pub mod chart;
+pub mod clock;
+pub mod direction;
 pub mod flex_container;
 pub mod flex_item;
+pub mod gradient;
 pub mod grid;
 pub mod layout_control;
+pub mod mask;
+pub mod material;
+pub mod opacity;
+pub mod outline;
+pub mod responsive;
 pub mod size_constraints;
+pub mod sound;
 pub mod spacing;
 pub mod stylebox;
 pub mod text;
@@ -0,0 +1,280 @@
+use crate::ess::PropertyParser;
+use crate::ess::StyleProperty;
+use crate::ess::StylePropertyMethods;
+use crate::ess::StylePropertyToken;
+use crate::style_property;
+use crate::ElementsError;
+use bevy::prelude::*;
+
+/// Opacity of the element and its children, from `0.0` (transparent)
+/// to `1.0` (opaque). Can be changed directly or bound to fade elements.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct Opacity {
+    pub value: f32,
+}
+
+impl Default for Opacity {
+    fn default() -> Self {
+        Opacity { value: 1. }
+    }
+}
+
+/// <!-- @property-type=$opacity -->
+/// Opacity: number from `0.0` to `1.0` or percent value (`50%`).
+pub struct OpacityParser;
+impl PropertyParser<f32> for OpacityParser {
+    fn parse(prop: &StyleProperty) -> Result<f32, ElementsError> {
+        match prop.first() {
+            Some(StylePropertyToken::Number(val)) => Ok(val.to_float().clamp(0., 1.)),
+            Some(StylePropertyToken::Percentage(val)) => Ok((val.to_float() / 100.).clamp(0., 1.)),
+            Some(token) => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected $num|$percent, got `{}`",
+                token.to_string()
+            ))),
+            None => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected $num|$percent, got nothing"
+            ))),
+        }
+    }
+}
+
+style_property! {
+    #[doc = " Specify the opacity of the element and its children:"]
+    #[doc = " ```css"]
+    #[doc = " opacity: 0.5;"]
+    #[doc = " opacity: 50%;"]
+    #[doc = " ```"]
+    #[doc = " "]
+    #[doc = " The opacity multiplies the alpha of the background, border, image"]
+    #[doc = " and text colors. Fully transparent elements don't intercept the"]
+    #[doc = " pointer input, see `pointer-events`."]
+    #[doc = " <!-- @property-type=$opacity -->"]
+    #[doc = " <!-- @property-category=General -->"]
+    OpacityProperty("opacity") {
+        Default = "1";
+        Item = f32;
+        Components = Option<&'static mut Opacity>;
+        Filters = With<Node>;
+        Parser = OpacityParser;
+        Apply = |value, opacity, _assets, commands, entity| {
+            match opacity {
+                Some(mut opacity) if opacity.value != *value => opacity.value = *value,
+                Some(_) => {}
+                None if *value < 1. => {
+                    commands.entity(entity).insert(Opacity { value: *value });
+                }
+                None => {}
+            }
+        };
+    }
+}
+
+/// Controls when the element intercepts the pointer input.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PointerEvents {
+    /// The element receives the pointer input unless it is fully transparent.
+    #[default]
+    Visible,
+    /// The element receives the pointer input regardless of its opacity.
+    All,
+    /// The element never receives the pointer input, it passes through
+    /// to the elements below.
+    None,
+}
+
+impl TryFrom<&StyleProperty> for PointerEvents {
+    type Error = ElementsError;
+    fn try_from(value: &StyleProperty) -> Result<Self, Self::Error> {
+        match value.first() {
+            Some(StylePropertyToken::Identifier(ident)) if ident == "visible" => {
+                Ok(PointerEvents::Visible)
+            }
+            Some(StylePropertyToken::Identifier(ident)) if ident == "all" => Ok(PointerEvents::All),
+            Some(StylePropertyToken::Identifier(ident)) if ident == "none" => {
+                Ok(PointerEvents::None)
+            }
+            _ => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected visible|all|none, got `{}`",
+                value.to_string()
+            ))),
+        }
+    }
+}
+
+style_property! {
+    #[doc = " Specify when the element intercepts the pointer input:"]
+    #[doc = " ```css"]
+    #[doc = " pointer-events: none;"]
+    #[doc = " ```"]
+    #[doc = " "]
+    #[doc = " Supported values:"]
+    #[doc = " - `visible`: the element receives the input unless its opacity"]
+    #[doc = "   (including the opacity of the parents) is zero, so faded-out"]
+    #[doc = "   overlays don't block the elements below"]
+    #[doc = " - `all`: the element receives the input even when it is transparent"]
+    #[doc = " - `none`: the element never receives the input"]
+    #[doc = " <!-- @property-category=General -->"]
+    PointerEventsProperty("pointer-events") {
+        Default = "visible";
+        Item = PointerEvents;
+        Components = Option<&'static mut PointerEvents>;
+        Filters = With<Node>;
+        Parser = super::parse::IdentifierParser<PointerEvents>;
+        Apply = |value, pointer_events, _assets, commands, entity| {
+            match pointer_events {
+                Some(mut pointer_events) if *pointer_events != *value => *pointer_events = *value,
+                Some(_) => {}
+                None => {
+                    commands.entity(entity).insert(*value);
+                }
+            }
+        };
+    }
+}
+
+/// The opacity of the element multiplied by the opacity of its parents,
+/// and the colors of the element before the opacity was applied.
+#[derive(Component, Debug, Default)]
+pub struct ComputedOpacity {
+    value: f32,
+    background: FadedColor,
+    border: FadedColor,
+    sections: Vec<FadedColor>,
+}
+
+impl ComputedOpacity {
+    pub fn get(&self) -> f32 {
+        self.value
+    }
+}
+
+/// The styled color and the color written with the opacity applied.
+#[derive(Debug, Default, Clone, Copy)]
+struct FadedColor {
+    styled: Color,
+    written: Option<Color>,
+}
+
+impl FadedColor {
+    /// Returns the faded color if it differs from the current `color`.
+    fn fade(&mut self, color: Color, opacity: f32) -> Option<Color> {
+        // the color changed by somebody else since the last
+        // fade is the new styled color
+        if self.written != Some(color) {
+            self.styled = color;
+        }
+        let faded = self.styled.with_a(self.styled.a() * opacity);
+        self.written = Some(faded);
+        (color != faded).then_some(faded)
+    }
+}
+
+/// Resolves the opacity of the ui tree and applies it to the colors
+/// of the elements.
+pub fn apply_opacity_system(
+    mut commands: Commands,
+    roots: Query<Entity, (With<Node>, Without<Parent>)>,
+    children: Query<&Children>,
+    mut nodes: Query<
+        (
+            Option<&Opacity>,
+            Option<&mut ComputedOpacity>,
+            Option<&mut BackgroundColor>,
+            Option<&mut BorderColor>,
+            Option<&mut Text>,
+        ),
+        With<Node>,
+    >,
+) {
+    let mut stack: Vec<(Entity, f32)> = roots.iter().map(|e| (e, 1.)).collect();
+    while let Some((entity, parent_opacity)) = stack.pop() {
+        let mut opacity = parent_opacity;
+        if let Ok((own, computed, background, border, text)) = nodes.get_mut(entity) {
+            opacity *= own.map(|o| o.value.clamp(0., 1.)).unwrap_or(1.);
+            match computed {
+                Some(mut computed) => {
+                    let computed = computed.bypass_change_detection();
+                    computed.value = opacity;
+                    if let Some(mut background) = background {
+                        if let Some(color) = computed.background.fade(background.0, opacity) {
+                            background.0 = color;
+                        }
+                    }
+                    if let Some(mut border) = border {
+                        if let Some(color) = computed.border.fade(border.0, opacity) {
+                            border.0 = color;
+                        }
+                    }
+                    if let Some(mut text) = text {
+                        computed
+                            .sections
+                            .resize(text.sections.len(), Default::default());
+                        for idx in 0..text.sections.len() {
+                            let current = text.sections[idx].style.color;
+                            if let Some(color) = computed.sections[idx].fade(current, opacity) {
+                                text.sections[idx].style.color = color;
+                            }
+                        }
+                    }
+                }
+                None if opacity < 1. => {
+                    commands.entity(entity).insert(ComputedOpacity {
+                        value: opacity,
+                        ..default()
+                    });
+                }
+                None => {}
+            }
+        }
+        if let Ok(children) = children.get(entity) {
+            stack.extend(children.iter().map(|c| (*c, opacity)));
+        }
+    }
+}
+
+/// Returns `true` if the element with `pointer_events` and `opacity`
+/// should intercept the pointer input.
+pub fn accepts_pointer(
+    pointer_events: Option<&PointerEvents>,
+    opacity: Option<&ComputedOpacity>,
+) -> bool {
+    match pointer_events.copied().unwrap_or_default() {
+        PointerEvents::All => true,
+        PointerEvents::None => false,
+        PointerEvents::Visible => opacity.map(|o| o.value > 0.).unwrap_or(true),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn transparent_elements_ignore_pointer() {
+        let mut app = App::new();
+        app.add_systems(Update, apply_opacity_system);
+        let child = app
+            .world
+            .spawn((NodeBundle::default(), PointerEvents::Visible))
+            .id();
+        let parent = app
+            .world
+            .spawn((NodeBundle::default(), Opacity { value: 0. }))
+            .push_children(&[child])
+            .id();
+        app.update();
+        app.update();
+        let opacity = app.world.get::<ComputedOpacity>(child);
+        assert_eq!(opacity.map(|o| o.get()), Some(0.));
+        assert!(!accepts_pointer(Some(&PointerEvents::Visible), opacity));
+        assert!(accepts_pointer(Some(&PointerEvents::All), opacity));
+
+        app.world.get_mut::<Opacity>(parent).unwrap().value = 0.5;
+        app.world.get_mut::<BackgroundColor>(child).unwrap().0 = Color::WHITE;
+        app.update();
+        let opacity = app.world.get::<ComputedOpacity>(child);
+        assert!(accepts_pointer(None, opacity));
+        let background = app.world.get::<BackgroundColor>(child).unwrap().0;
+        assert_eq!(background.a(), 0.5);
+    }
+}
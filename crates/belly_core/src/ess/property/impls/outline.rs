@@ -0,0 +1,168 @@
+use crate::ess::parse;
+use crate::ess::PropertyParser;
+use crate::ess::StyleProperty;
+use crate::ess::StylePropertyMethods;
+use crate::ess::StylePropertyToken;
+use crate::style_property;
+use crate::ElementsError;
+use bevy::prelude::*;
+
+/// The outline of the element set by the `outline` and `outline-offset`
+/// properties. It is drawn outside of the border and doesn't affect
+/// the layout, see [`apply_outline_system`].
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct OutlineStyle {
+    pub width: Val,
+    pub offset: Val,
+    pub color: Color,
+}
+
+impl Default for OutlineStyle {
+    fn default() -> Self {
+        OutlineStyle {
+            width: Val::Px(0.),
+            offset: Val::Px(0.),
+            color: Color::NONE,
+        }
+    }
+}
+
+impl OutlineStyle {
+    /// Returns `true` if there is nothing to draw
+    pub fn is_none(&self) -> bool {
+        self.width == Val::Px(0.) || self.color.a() == 0.
+    }
+}
+
+/// <!-- @property-type=$outline -->
+/// Outline: `none` or the width (`px` or `%` suffixed value)
+/// followed by the color (`2px #4a90e2`).
+pub struct OutlineParser;
+impl PropertyParser<(Val, Color)> for OutlineParser {
+    fn parse(prop: &StyleProperty) -> Result<(Val, Color), ElementsError> {
+        match prop.first() {
+            Some(StylePropertyToken::Identifier(ident)) if ident == "none" && prop.len() == 1 => {
+                return Ok((Val::Px(0.), Color::NONE))
+            }
+            _ if prop.len() == 2 => {}
+            _ => {
+                return Err(ElementsError::InvalidPropertyValue(format!(
+                    "Expected none|$val $color, got `{}`",
+                    prop.to_string()
+                )))
+            }
+        }
+        let width = parse::val(&StyleProperty(prop[..1].iter().cloned().collect()))?;
+        let color = parse::color(&StyleProperty(prop[1..].iter().cloned().collect()))?;
+        Ok((width, color))
+    }
+}
+
+style_property! {
+    #[doc = " Specify the width and the color of the element outline:"]
+    #[doc = " ```css"]
+    #[doc = " outline: 2px #4a90e2;"]
+    #[doc = " outline: none;"]
+    #[doc = " ```"]
+    #[doc = " "]
+    #[doc = " The outline is drawn outside of the border and doesn't take the"]
+    #[doc = " space in the layout. The focused elements get the outline from the"]
+    #[doc = " default `*:focus-visible` rule, override it to restyle the focus ring."]
+    #[doc = " <!-- @property-type=$outline -->"]
+    #[doc = " <!-- @property-category=General -->"]
+    OutlineProperty("outline") {
+        Default = "none";
+        Item = (Val, Color);
+        Components = Option<&'static mut OutlineStyle>;
+        Filters = With<Node>;
+        Parser = OutlineParser;
+        Apply = |value, outline, _assets, commands, entity| {
+            let (width, color) = *value;
+            match outline {
+                Some(mut outline) if outline.width != width || outline.color != color => {
+                    outline.width = width;
+                    outline.color = color;
+                }
+                Some(_) => {}
+                None if width != Val::Px(0.) => {
+                    commands.entity(entity).insert(OutlineStyle {
+                        width,
+                        color,
+                        ..default()
+                    });
+                }
+                None => {}
+            }
+        };
+    }
+}
+
+style_property! {
+    #[doc = " Specify the gap between the border of the element and its outline:"]
+    #[doc = " ```css"]
+    #[doc = " outline-offset: 2px;"]
+    #[doc = " ```"]
+    #[doc = " <!-- @property-type=$val -->"]
+    #[doc = " <!-- @property-category=General -->"]
+    OutlineOffsetProperty("outline-offset") {
+        Default = "0px";
+        Item = Val;
+        Components = Option<&'static mut OutlineStyle>;
+        Filters = With<Node>;
+        Parser = parse::ValParser;
+        Apply = |value, outline, _assets, commands, entity| {
+            match outline {
+                Some(mut outline) if outline.offset != *value => outline.offset = *value,
+                Some(_) => {}
+                None if *value != Val::Px(0.) => {
+                    commands.entity(entity).insert(OutlineStyle {
+                        offset: *value,
+                        ..default()
+                    });
+                }
+                None => {}
+            }
+        };
+    }
+}
+
+/// Renders the [`OutlineStyle`]s with the bevy [`Outline`]s
+pub fn apply_outline_system(
+    mut commands: Commands,
+    styles: Query<(Entity, &OutlineStyle, Option<&Outline>), Changed<OutlineStyle>>,
+) {
+    for (entity, style, outline) in styles.iter() {
+        if style.is_none() {
+            if outline.is_some() {
+                commands.entity(entity).remove::<Outline>();
+            }
+            continue;
+        }
+        let unchanged = outline.map_or(false, |o| {
+            o.width == style.width && o.offset == style.offset && o.color == style.color
+        });
+        if !unchanged {
+            commands
+                .entity(entity)
+                .insert(Outline::new(style.width, style.offset, style.color));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn parse_outline() {
+        let parse = |s: &str| OutlineParser::parse(&StyleProperty::from_str(s).unwrap());
+        let (width, color) = parse("2px #ff0000").unwrap();
+        assert_eq!(width, Val::Px(2.));
+        assert_eq!(color, Color::rgb(1., 0., 0.));
+        let (width, color) = parse("none").unwrap();
+        assert!(OutlineStyle { width, color, ..default() }.is_none());
+        assert!(parse("2px").is_err());
+        assert!(parse("red 2px").is_err());
+    }
+}
@@ -0,0 +1,324 @@
+use super::parse;
+use crate::build::StyleProperty;
+use crate::ess::{PropertyParser, StylePropertyToken};
+use crate::style_property;
+use crate::ElementsError;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+/// Window size class the responsive properties (`hide-on`, `display-sm`,
+/// `display-md`, `display-lg`) are resolved against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Breakpoint {
+    Small,
+    Medium,
+    #[default]
+    Large,
+}
+
+impl Breakpoint {
+    fn index(&self) -> usize {
+        match self {
+            Breakpoint::Small => 0,
+            Breakpoint::Medium => 1,
+            Breakpoint::Large => 2,
+        }
+    }
+}
+
+impl TryFrom<&StylePropertyToken> for Breakpoint {
+    type Error = ElementsError;
+    fn try_from(token: &StylePropertyToken) -> Result<Self, Self::Error> {
+        match token {
+            StylePropertyToken::Identifier(ident) if ident == "small" || ident == "sm" => {
+                Ok(Breakpoint::Small)
+            }
+            StylePropertyToken::Identifier(ident) if ident == "medium" || ident == "md" => {
+                Ok(Breakpoint::Medium)
+            }
+            StylePropertyToken::Identifier(ident) if ident == "large" || ident == "lg" => {
+                Ok(Breakpoint::Large)
+            }
+            token => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected small|medium|large, got `{}`",
+                token.to_string()
+            ))),
+        }
+    }
+}
+
+/// Window width thresholds (in logical pixels) for responsive properties.
+/// Windows narrower then `small` are [`Breakpoint::Small`], narrower then
+/// `medium` are [`Breakpoint::Medium`], the rest are [`Breakpoint::Large`].
+#[derive(Resource, Debug, Clone)]
+pub struct Breakpoints {
+    pub small: f32,
+    pub medium: f32,
+    current: Breakpoint,
+}
+
+impl Default for Breakpoints {
+    fn default() -> Self {
+        Breakpoints {
+            small: 640.,
+            medium: 1024.,
+            current: Breakpoint::Large,
+        }
+    }
+}
+
+impl Breakpoints {
+    /// Currently active breakpoint
+    pub fn current(&self) -> Breakpoint {
+        self.current
+    }
+
+    pub fn resolve(&self, width: f32) -> Breakpoint {
+        if width < self.small {
+            Breakpoint::Small
+        } else if width < self.medium {
+            Breakpoint::Medium
+        } else {
+            Breakpoint::Large
+        }
+    }
+}
+
+/// Per-breakpoint display overrides collected from responsive properties.
+#[derive(Component, Default, Debug)]
+pub struct ResponsiveDisplay {
+    overrides: [Option<Display>; 3],
+    hidden: [bool; 3],
+    base: Option<Display>,
+}
+
+impl ResponsiveDisplay {
+    fn resolve(&self, breakpoint: Breakpoint) -> Option<Display> {
+        let idx = breakpoint.index();
+        if self.hidden[idx] {
+            Some(Display::None)
+        } else {
+            self.overrides[idx]
+        }
+    }
+}
+
+fn update_responsive_display<F: FnOnce(&mut ResponsiveDisplay) + Send + 'static>(
+    commands: &mut Commands,
+    entity: Entity,
+    update: F,
+) {
+    commands.add(move |world: &mut World| {
+        let Some(mut entity) = world.get_entity_mut(entity) else {
+            return;
+        };
+        if let Some(mut responsive) = entity.get_mut::<ResponsiveDisplay>() {
+            update(&mut responsive);
+        } else {
+            let mut responsive = ResponsiveDisplay::default();
+            update(&mut responsive);
+            entity.insert(responsive);
+        }
+    });
+}
+
+/// <!-- @property-type=$breakpoints -->
+/// Space separated list of breakpoints: `small` (`sm`), `medium` (`md`),
+/// `large` (`lg`) or `none`.
+pub struct BreakpointsParser;
+impl PropertyParser<Vec<Breakpoint>> for BreakpointsParser {
+    fn parse(value: &StyleProperty) -> Result<Vec<Breakpoint>, ElementsError> {
+        if value.len() == 1 && value[0].is_ident("none") {
+            return Ok(vec![]);
+        }
+        value.iter().map(Breakpoint::try_from).collect()
+    }
+}
+
+/// `auto`|`none`|`flex`|`grid`
+pub struct OptionalDisplayParser;
+impl PropertyParser<Option<Display>> for OptionalDisplayParser {
+    fn parse(value: &StyleProperty) -> Result<Option<Display>, ElementsError> {
+        if value.len() == 1 && value[0].is_ident("auto") {
+            return Ok(None);
+        }
+        parse::identifier::<Display>(value).map(Some)
+    }
+}
+
+style_property! {
+    #[doc = " Hides the element (`display: none`) when the window matches any of"]
+    #[doc = " the listed breakpoints:"]
+    #[doc = " ```css"]
+    #[doc = " hide-on: small medium;"]
+    #[doc = " ```"]
+    #[doc = " "]
+    #[doc = " Breakpoint thresholds are defined by `Breakpoints` resource."]
+    #[doc = " <!-- @property-type=$breakpoints -->"]
+    #[doc = " <!-- @property-category=Responsive -->"]
+    HideOnProperty("hide-on") {
+        Default = "none";
+        Item = Vec<Breakpoint>;
+        Components = ();
+        Filters = With<Node>;
+        Parser = BreakpointsParser;
+        Apply = |value, _components, _assets, commands, entity| {
+            let mut hidden = [false; 3];
+            for breakpoint in value.iter() {
+                hidden[breakpoint.index()] = true;
+            }
+            update_responsive_display(commands, entity, move |r| r.hidden = hidden);
+        };
+    }
+}
+
+style_property! {
+    #[doc = " Overrides `display` of the element when the window is small:"]
+    #[doc = " ```css"]
+    #[doc = " display-sm: none;"]
+    #[doc = " ```"]
+    #[doc = " "]
+    #[doc = " `auto` keeps the value of `display` property."]
+    #[doc = " <!-- @property-category=Responsive -->"]
+    DisplaySmProperty("display-sm") {
+        Default = "auto";
+        Item = Option<Display>;
+        Components = ();
+        Filters = With<Node>;
+        Parser = OptionalDisplayParser;
+        Apply = |value, _components, _assets, commands, entity| {
+            let value = *value;
+            update_responsive_display(commands, entity, move |r| {
+                r.overrides[Breakpoint::Small.index()] = value;
+            });
+        };
+    }
+}
+
+style_property! {
+    #[doc = " Overrides `display` of the element when the window is medium:"]
+    #[doc = " ```css"]
+    #[doc = " display-md: none;"]
+    #[doc = " ```"]
+    #[doc = " "]
+    #[doc = " `auto` keeps the value of `display` property."]
+    #[doc = " <!-- @property-category=Responsive -->"]
+    DisplayMdProperty("display-md") {
+        Default = "auto";
+        Item = Option<Display>;
+        Components = ();
+        Filters = With<Node>;
+        Parser = OptionalDisplayParser;
+        Apply = |value, _components, _assets, commands, entity| {
+            let value = *value;
+            update_responsive_display(commands, entity, move |r| {
+                r.overrides[Breakpoint::Medium.index()] = value;
+            });
+        };
+    }
+}
+
+style_property! {
+    #[doc = " Overrides `display` of the element when the window is large:"]
+    #[doc = " ```css"]
+    #[doc = " display-lg: none;"]
+    #[doc = " ```"]
+    #[doc = " "]
+    #[doc = " `auto` keeps the value of `display` property."]
+    #[doc = " <!-- @property-category=Responsive -->"]
+    DisplayLgProperty("display-lg") {
+        Default = "auto";
+        Item = Option<Display>;
+        Components = ();
+        Filters = With<Node>;
+        Parser = OptionalDisplayParser;
+        Apply = |value, _components, _assets, commands, entity| {
+            let value = *value;
+            update_responsive_display(commands, entity, move |r| {
+                r.overrides[Breakpoint::Large.index()] = value;
+            });
+        };
+    }
+}
+
+pub fn update_breakpoints_system(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut breakpoints: ResMut<Breakpoints>,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let current = breakpoints.resolve(window.width());
+    if breakpoints.current != current {
+        breakpoints.current = current;
+    }
+}
+
+pub fn apply_responsive_display_system(
+    breakpoints: Res<Breakpoints>,
+    mut elements: Query<(&mut ResponsiveDisplay, &mut Style)>,
+) {
+    let breakpoint = breakpoints.current();
+    for (mut responsive, mut style) in elements.iter_mut() {
+        match responsive.resolve(breakpoint) {
+            Some(display) if style.display != display => {
+                // the base is the display before the first override, moving
+                // between the overridden breakpoints keeps it
+                if responsive.base.is_none() {
+                    responsive.base = Some(style.display);
+                }
+                style.display = display;
+            }
+            None if responsive.base.is_some() => {
+                style.display = responsive.base.take().unwrap();
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn parse_breakpoints() {
+        let p = StyleProperty::from_str("sm medium").unwrap();
+        let b = BreakpointsParser::parse(&p).unwrap();
+        assert_eq!(b, vec![Breakpoint::Small, Breakpoint::Medium]);
+
+        let p = StyleProperty::from_str("none").unwrap();
+        assert!(BreakpointsParser::parse(&p).unwrap().is_empty());
+
+        let p = StyleProperty::from_str("tiny").unwrap();
+        assert!(BreakpointsParser::parse(&p).is_err());
+
+        let breakpoints = Breakpoints::default();
+        assert_eq!(breakpoints.resolve(320.), Breakpoint::Small);
+        assert_eq!(breakpoints.resolve(800.), Breakpoint::Medium);
+        assert_eq!(breakpoints.resolve(1920.), Breakpoint::Large);
+    }
+
+    #[test]
+    fn responsive_display_restores_base() {
+        use bevy::ecs::system::RunSystemOnce;
+        let mut world = World::new();
+        world.insert_resource(Breakpoints {
+            current: Breakpoint::Small,
+            ..default()
+        });
+        let mut responsive = ResponsiveDisplay::default();
+        responsive.hidden[Breakpoint::Small.index()] = true;
+        responsive.overrides[Breakpoint::Medium.index()] = Some(Display::Grid);
+        let entity = world.spawn((responsive, Style::default())).id();
+        let display_at = |world: &mut World, breakpoint| {
+            world.resource_mut::<Breakpoints>().current = breakpoint;
+            world.run_system_once(apply_responsive_display_system);
+            world.get::<Style>(entity).unwrap().display
+        };
+        assert_eq!(display_at(&mut world, Breakpoint::Small), Display::None);
+        assert_eq!(display_at(&mut world, Breakpoint::Medium), Display::Grid);
+        assert_eq!(display_at(&mut world, Breakpoint::Large), Display::Flex);
+    }
+}
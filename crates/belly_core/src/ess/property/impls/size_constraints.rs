@@ -1,5 +1,7 @@
 use super::parse;
 use crate::style_property;
+use crate::ess::units::{apply_length, LengthParser, RelativeLengths};
+use crate::ess::Length;
 use bevy::prelude::*;
 
 // impl_style_single_value!("width", WidthProperty, Val, val, size.width);
@@ -11,14 +13,19 @@ style_property! {
     #[doc = " <!-- @property-category=Size Constraints -->"]
     WidthProperty("width") {
         Default = "undefined";
-        Item = Val;
-        Components = &'static mut Style;
+        Item = Length;
+        Components = (&'static mut Style, Option<&'static mut RelativeLengths>);
         Filters = With<Node>;
-        Parser = parse::ValParser;
-        Apply = |value, style, _assets, _commands, _entity| {
-            if &style.width != value {
-                style.width = *value;
-            }
+        Parser = LengthParser;
+        Apply = |value, components, _assets, commands, entity| {
+            apply_length(
+                value,
+                components,
+                commands,
+                entity,
+                Self::name(),
+                |s| &mut s.width,
+            );
         };
     }
 }
@@ -31,14 +38,19 @@ style_property! {
     #[doc = " <!-- @property-category=Size Constraints -->"]
     HeightProperty("height") {
         Default = "undefined";
-        Item = Val;
-        Components = &'static mut Style;
+        Item = Length;
+        Components = (&'static mut Style, Option<&'static mut RelativeLengths>);
         Filters = With<Node>;
-        Parser = parse::ValParser;
-        Apply = |value, style, _assets, _commands, _entity| {
-            if &style.height != value {
-                style.height = *value;
-            }
+        Parser = LengthParser;
+        Apply = |value, components, _assets, commands, entity| {
+            apply_length(
+                value,
+                components,
+                commands,
+                entity,
+                Self::name(),
+                |s| &mut s.height,
+            );
         };
     }
 }
@@ -52,14 +64,19 @@ style_property! {
     #[doc = " <!-- @property-category=Size Constraints -->"]
     MinWidthProperty("min-width") {
         Default = "undefined";
-        Item = Val;
-        Components = &'static mut Style;
+        Item = Length;
+        Components = (&'static mut Style, Option<&'static mut RelativeLengths>);
         Filters = With<Node>;
-        Parser = parse::ValParser;
-        Apply = |value, style, _assets, _commands, _entity| {
-            if &style.min_width != value {
-                style.min_width = *value;
-            }
+        Parser = LengthParser;
+        Apply = |value, components, _assets, commands, entity| {
+            apply_length(
+                value,
+                components,
+                commands,
+                entity,
+                Self::name(),
+                |s| &mut s.min_width,
+            );
         };
     }
 }
@@ -72,14 +89,19 @@ style_property! {
     #[doc = " <!-- @property-category=Size Constraints -->"]
     MinHeightProperty("min-height") {
         Default = "undefined";
-        Item = Val;
-        Components = &'static mut Style;
+        Item = Length;
+        Components = (&'static mut Style, Option<&'static mut RelativeLengths>);
         Filters = With<Node>;
-        Parser = parse::ValParser;
-        Apply = |value, style, _assets, _commands, _entity| {
-            if &style.min_height != value {
-                style.min_height = *value;
-            }
+        Parser = LengthParser;
+        Apply = |value, components, _assets, commands, entity| {
+            apply_length(
+                value,
+                components,
+                commands,
+                entity,
+                Self::name(),
+                |s| &mut s.min_height,
+            );
         };
     }
 }
@@ -93,14 +115,19 @@ style_property! {
     #[doc = " <!-- @property-category=Size Constraints -->"]
     MaxWidthProperty("max-width") {
         Default = "undefined";
-        Item = Val;
-        Components = &'static mut Style;
+        Item = Length;
+        Components = (&'static mut Style, Option<&'static mut RelativeLengths>);
         Filters = With<Node>;
-        Parser = parse::ValParser;
-        Apply = |value, style, _assets, _commands, _entity| {
-            if &style.max_width != value {
-                style.max_width = *value;
-            }
+        Parser = LengthParser;
+        Apply = |value, components, _assets, commands, entity| {
+            apply_length(
+                value,
+                components,
+                commands,
+                entity,
+                Self::name(),
+                |s| &mut s.max_width,
+            );
         };
     }
 }
@@ -113,14 +140,19 @@ style_property! {
     #[doc = " <!-- @property-category=Size Constraints -->"]
     MaxHeightProperty("max-height") {
         Default = "undefined";
-        Item = Val;
-        Components = &'static mut Style;
+        Item = Length;
+        Components = (&'static mut Style, Option<&'static mut RelativeLengths>);
         Filters = With<Node>;
-        Parser = parse::ValParser;
-        Apply = |value, style, _assets, _commands, _entity| {
-            if &style.max_height != value {
-                style.max_height = *value;
-            }
+        Parser = LengthParser;
+        Apply = |value, components, _assets, commands, entity| {
+            apply_length(
+                value,
+                components,
+                commands,
+                entity,
+                Self::name(),
+                |s| &mut s.max_height,
+            );
         };
     }
 }
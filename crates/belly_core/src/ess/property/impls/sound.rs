@@ -0,0 +1,189 @@
+use crate::ess::PropertyParser;
+use crate::ess::StyleProperty;
+use crate::ess::StylePropertyMethods;
+use crate::ess::StylePropertyToken;
+use crate::sound::{SoundSource, UiSound, UiSoundStyle};
+use crate::style_property;
+use crate::ElementsError;
+use bevy::prelude::*;
+
+/// <!-- @property-type=$sound -->
+/// Sound: `auto` (the sound registered in `UiSounds`), `none` or
+/// the path to the audio asset: `url("sounds/click.ogg")`.
+pub struct SoundSourceParser;
+impl PropertyParser<SoundSource> for SoundSourceParser {
+    fn parse(prop: &StyleProperty) -> Result<SoundSource, ElementsError> {
+        let err = || {
+            ElementsError::InvalidPropertyValue(format!(
+                "Expected auto|none|url(\"path\"), got `{}`",
+                prop.to_string()
+            ))
+        };
+        if prop.len() != 1 {
+            return Err(err());
+        }
+        match &prop[0] {
+            StylePropertyToken::Identifier(ident) if ident == "auto" => Ok(SoundSource::Auto),
+            StylePropertyToken::Identifier(ident) if ident == "none" => Ok(SoundSource::None),
+            StylePropertyToken::String(path) => Ok(SoundSource::Path(path.clone())),
+            StylePropertyToken::Function(func) if func.name == "url" => match func.args.as_slice() {
+                [StylePropertyToken::String(path)] => Ok(SoundSource::Path(path.clone())),
+                _ => Err(err()),
+            },
+            _ => Err(err()),
+        }
+    }
+}
+
+/// The sources are stored only on the elements overriding the sounds
+fn update_sound(
+    style: Option<Mut<UiSoundStyle>>,
+    commands: &mut Commands,
+    entity: Entity,
+    sound: UiSound,
+    value: &SoundSource,
+) {
+    match style {
+        Some(mut style) if style.get(sound) != value => style.set(sound, value.clone()),
+        Some(_) => {}
+        None if *value != SoundSource::Auto => {
+            let mut style = UiSoundStyle::default();
+            style.set(sound, value.clone());
+            commands.entity(entity).insert(style);
+        }
+        None => {}
+    }
+}
+
+style_property! {
+    #[doc = " Specify the sound played when the button gets hovered:"]
+    #[doc = " ```css"]
+    #[doc = " sound-hover: url(\"sounds/tick.ogg\");"]
+    #[doc = " ```"]
+    #[doc = " <!-- @property-type=$sound -->"]
+    #[doc = " <!-- @property-category=Sound -->"]
+    SoundHoverProperty("sound-hover") {
+        Default = "auto";
+        Item = SoundSource;
+        Components = Option<&'static mut UiSoundStyle>;
+        Filters = With<Node>;
+        Parser = SoundSourceParser;
+        Apply = |value, style, _assets, commands, entity| {
+            update_sound(style, commands, entity, UiSound::Hover, value);
+        };
+    }
+}
+
+style_property! {
+    #[doc = " Specify the sound played when the button is pressed:"]
+    #[doc = " ```css"]
+    #[doc = " sound-press: url(\"sounds/click.ogg\");"]
+    #[doc = " ```"]
+    #[doc = " <!-- @property-type=$sound -->"]
+    #[doc = " <!-- @property-category=Sound -->"]
+    SoundPressProperty("sound-press") {
+        Default = "auto";
+        Item = SoundSource;
+        Components = Option<&'static mut UiSoundStyle>;
+        Filters = With<Node>;
+        Parser = SoundSourceParser;
+        Apply = |value, style, _assets, commands, entity| {
+            update_sound(style, commands, entity, UiSound::Press, value);
+        };
+    }
+}
+
+style_property! {
+    #[doc = " Specify the sound played when the `:pressed` state of the toggle"]
+    #[doc = " button changes:"]
+    #[doc = " ```css"]
+    #[doc = " sound-toggle: none;"]
+    #[doc = " ```"]
+    #[doc = " <!-- @property-type=$sound -->"]
+    #[doc = " <!-- @property-category=Sound -->"]
+    SoundToggleProperty("sound-toggle") {
+        Default = "auto";
+        Item = SoundSource;
+        Components = Option<&'static mut UiSoundStyle>;
+        Filters = With<Node>;
+        Parser = SoundSourceParser;
+        Apply = |value, style, _assets, commands, entity| {
+            update_sound(style, commands, entity, UiSound::Toggle, value);
+        };
+    }
+}
+
+style_property! {
+    #[doc = " Specify the sound played when the element gets the `:error` state:"]
+    #[doc = " ```css"]
+    #[doc = " sound-error: url(\"sounds/buzz.ogg\");"]
+    #[doc = " ```"]
+    #[doc = " <!-- @property-type=$sound -->"]
+    #[doc = " <!-- @property-category=Sound -->"]
+    SoundErrorProperty("sound-error") {
+        Default = "auto";
+        Item = SoundSource;
+        Components = Option<&'static mut UiSoundStyle>;
+        Filters = With<Node>;
+        Parser = SoundSourceParser;
+        Apply = |value, style, _assets, commands, entity| {
+            update_sound(style, commands, entity, UiSound::Error, value);
+        };
+    }
+}
+
+style_property! {
+    #[doc = " Specify the sound played when the element gets the `:open` state:"]
+    #[doc = " ```css"]
+    #[doc = " sound-open: url(\"sounds/whoosh.ogg\");"]
+    #[doc = " ```"]
+    #[doc = " <!-- @property-type=$sound -->"]
+    #[doc = " <!-- @property-category=Sound -->"]
+    SoundOpenProperty("sound-open") {
+        Default = "auto";
+        Item = SoundSource;
+        Components = Option<&'static mut UiSoundStyle>;
+        Filters = With<Node>;
+        Parser = SoundSourceParser;
+        Apply = |value, style, _assets, commands, entity| {
+            update_sound(style, commands, entity, UiSound::Open, value);
+        };
+    }
+}
+
+style_property! {
+    #[doc = " Specify the sound played when the element loses the `:open` state:"]
+    #[doc = " ```css"]
+    #[doc = " sound-close: none;"]
+    #[doc = " ```"]
+    #[doc = " <!-- @property-type=$sound -->"]
+    #[doc = " <!-- @property-category=Sound -->"]
+    SoundCloseProperty("sound-close") {
+        Default = "auto";
+        Item = SoundSource;
+        Components = Option<&'static mut UiSoundStyle>;
+        Filters = With<Node>;
+        Parser = SoundSourceParser;
+        Apply = |value, style, _assets, commands, entity| {
+            update_sound(style, commands, entity, UiSound::Close, value);
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn parse_sound_source() {
+        let parse = |s: &str| SoundSourceParser::parse(&StyleProperty::from_str(s).unwrap());
+        assert_eq!(parse("auto").unwrap(), SoundSource::Auto);
+        assert_eq!(parse("none").unwrap(), SoundSource::None);
+        let click = SoundSource::Path("click.ogg".to_string());
+        assert_eq!(parse("url(\"click.ogg\")").unwrap(), click);
+        assert_eq!(parse("\"click.ogg\"").unwrap(), click);
+        assert!(parse("loud").is_err());
+        assert!(parse("url(\"a.ogg\") none").is_err());
+    }
+}
@@ -1,7 +1,7 @@
-use super::parse;
 use crate::compound_style_property;
-use crate::ess::ToRectMap;
 use crate::style_property;
+use crate::ess::units::{apply_length, LengthParser, RelativeLengths};
+use crate::ess::{Length, LengthRect};
 use bevy::prelude::*;
 
 compound_style_property! {
@@ -15,7 +15,7 @@ compound_style_property! {
     #[doc = " <!-- @property-type=$rect -->"]
     #[doc = " <!-- @property-category=Spacing -->"]
     MarginProperty("margin", value) {
-        let rect = UiRect::try_from(value)?;
+        let rect = LengthRect::try_from(value)?;
         Ok(rect.to_rect_map("margin-"))
     }
 }
@@ -31,14 +31,19 @@ style_property! {
     #[doc = " <!-- @property-category=Spacing -->"]
     MarginLeftProperty("margin-left") {
         Default = "undefined";
-        Item = Val;
-        Components = &'static mut Style;
+        Item = Length;
+        Components = (&'static mut Style, Option<&'static mut RelativeLengths>);
         Filters = With<Node>;
-        Parser = parse::ValParser;
-        Apply = |value, style, _assets, _commands, _entity| {
-            if &style.margin.left != value {
-                style.margin.left = *value;
-            }
+        Parser = LengthParser;
+        Apply = |value, components, _assets, commands, entity| {
+            apply_length(
+                value,
+                components,
+                commands,
+                entity,
+                Self::name(),
+                |s| &mut s.margin.left,
+            );
         };
     }
 }
@@ -54,14 +59,19 @@ style_property! {
     #[doc = " <!-- @property-category=Spacing -->"]
     MarginRightProperty("margin-right") {
         Default = "undefined";
-        Item = Val;
-        Components = &'static mut Style;
+        Item = Length;
+        Components = (&'static mut Style, Option<&'static mut RelativeLengths>);
         Filters = With<Node>;
-        Parser = parse::ValParser;
-        Apply = |value, style, _assets, _commands, _entity| {
-            if &style.margin.right != value {
-                style.margin.right = *value;
-            }
+        Parser = LengthParser;
+        Apply = |value, components, _assets, commands, entity| {
+            apply_length(
+                value,
+                components,
+                commands,
+                entity,
+                Self::name(),
+                |s| &mut s.margin.right,
+            );
         };
     }
 }
@@ -77,14 +87,19 @@ style_property! {
     #[doc = " <!-- @property-category=Spacing -->"]
     MarginTopProperty("margin-top") {
         Default = "undefined";
-        Item = Val;
-        Components = &'static mut Style;
+        Item = Length;
+        Components = (&'static mut Style, Option<&'static mut RelativeLengths>);
         Filters = With<Node>;
-        Parser = parse::ValParser;
-        Apply = |value, style, _assets, _commands, _entity| {
-            if &style.margin.top != value {
-                style.margin.top = *value;
-            }
+        Parser = LengthParser;
+        Apply = |value, components, _assets, commands, entity| {
+            apply_length(
+                value,
+                components,
+                commands,
+                entity,
+                Self::name(),
+                |s| &mut s.margin.top,
+            );
         };
     }
 }
@@ -100,14 +115,19 @@ style_property! {
     #[doc = " <!-- @property-category=Spacing -->"]
     MarginBottomProperty("margin-bottom") {
         Default = "undefined";
-        Item = Val;
-        Components = &'static mut Style;
+        Item = Length;
+        Components = (&'static mut Style, Option<&'static mut RelativeLengths>);
         Filters = With<Node>;
-        Parser = parse::ValParser;
-        Apply = |value, style, _assets, _commands, _entity| {
-            if &style.margin.bottom != value {
-                style.margin.bottom = *value;
-            }
+        Parser = LengthParser;
+        Apply = |value, components, _assets, commands, entity| {
+            apply_length(
+                value,
+                components,
+                commands,
+                entity,
+                Self::name(),
+                |s| &mut s.margin.bottom,
+            );
         };
     }
 }
@@ -123,7 +143,7 @@ compound_style_property! {
     #[doc = " <!-- @property-type=$rect -->"]
     #[doc = " <!-- @property-category=Spacing -->"]
     PaddingProperty("padding", value) {
-        let rect = UiRect::try_from(value)?;
+        let rect = LengthRect::try_from(value)?;
         Ok(rect.to_rect_map("padding-"))
     }
 }
@@ -139,14 +159,19 @@ style_property! {
     #[doc = " <!-- @property-category=Spacing -->"]
     PaddingLeftProperty("padding-left") {
         Default = "undefined";
-        Item = Val;
-        Components = &'static mut Style;
+        Item = Length;
+        Components = (&'static mut Style, Option<&'static mut RelativeLengths>);
         Filters = With<Node>;
-        Parser = parse::ValParser;
-        Apply = |value, style, _assets, _commands, _entity| {
-            if &style.padding.left != value {
-                style.padding.left = *value;
-            }
+        Parser = LengthParser;
+        Apply = |value, components, _assets, commands, entity| {
+            apply_length(
+                value,
+                components,
+                commands,
+                entity,
+                Self::name(),
+                |s| &mut s.padding.left,
+            );
         };
     }
 }
@@ -162,14 +187,19 @@ style_property! {
     #[doc = " <!-- @property-category=Spacing -->"]
     PaddingRightProperty("padding-right") {
         Default = "undefined";
-        Item = Val;
-        Components = &'static mut Style;
+        Item = Length;
+        Components = (&'static mut Style, Option<&'static mut RelativeLengths>);
         Filters = With<Node>;
-        Parser = parse::ValParser;
-        Apply = |value, style, _assets, _commands, _entity| {
-            if &style.padding.right != value {
-                style.padding.right = *value;
-            }
+        Parser = LengthParser;
+        Apply = |value, components, _assets, commands, entity| {
+            apply_length(
+                value,
+                components,
+                commands,
+                entity,
+                Self::name(),
+                |s| &mut s.padding.right,
+            );
         };
     }
 }
@@ -185,14 +215,19 @@ style_property! {
     #[doc = " <!-- @property-category=Spacing -->"]
     PaddingTopProperty("padding-top") {
         Default = "undefined";
-        Item = Val;
-        Components = &'static mut Style;
+        Item = Length;
+        Components = (&'static mut Style, Option<&'static mut RelativeLengths>);
         Filters = With<Node>;
-        Parser = parse::ValParser;
-        Apply = |value, style, _assets, _commands, _entity| {
-            if &style.padding.top != value {
-                style.padding.top = *value;
-            }
+        Parser = LengthParser;
+        Apply = |value, components, _assets, commands, entity| {
+            apply_length(
+                value,
+                components,
+                commands,
+                entity,
+                Self::name(),
+                |s| &mut s.padding.top,
+            );
         };
     }
 }
@@ -208,14 +243,19 @@ style_property! {
     #[doc = " <!-- @property-category=Spacing -->"]
     PaddingBottomProperty("padding-bottom") {
         Default = "undefined";
-        Item = Val;
-        Components = &'static mut Style;
+        Item = Length;
+        Components = (&'static mut Style, Option<&'static mut RelativeLengths>);
         Filters = With<Node>;
-        Parser = parse::ValParser;
-        Apply = |value, style, _assets, _commands, _entity| {
-            if &style.padding.bottom != value {
-                style.padding.bottom = *value;
-            }
+        Parser = LengthParser;
+        Apply = |value, components, _assets, commands, entity| {
+            apply_length(
+                value,
+                components,
+                commands,
+                entity,
+                Self::name(),
+                |s| &mut s.padding.bottom,
+            );
         };
     }
 }
@@ -230,7 +270,7 @@ compound_style_property! {
     #[doc = " <!-- @property-type=$rect -->"]
     #[doc = " <!-- @property-category=Spacing -->"]
     BorderProperty("border-width", value) {
-        let rect = UiRect::try_from(value)?;
+        let rect = LengthRect::try_from(value)?;
         Ok(rect.to_rect_map("border-width-"))
     }
 }
@@ -243,14 +283,19 @@ style_property! {
     #[doc = " <!-- @property-category=Spacing -->"]
     BorderLeftProperty("border-width-left") {
         Default = "undefined";
-        Item = Val;
-        Components = &'static mut Style;
+        Item = Length;
+        Components = (&'static mut Style, Option<&'static mut RelativeLengths>);
         Filters = With<Node>;
-        Parser = parse::ValParser;
-        Apply = |value, style, _assets, _commands, _entity| {
-            if &style.border.left != value {
-                style.border.left = *value;
-            }
+        Parser = LengthParser;
+        Apply = |value, components, _assets, commands, entity| {
+            apply_length(
+                value,
+                components,
+                commands,
+                entity,
+                Self::name(),
+                |s| &mut s.border.left,
+            );
         };
     }
 }
@@ -264,14 +309,19 @@ style_property! {
     #[doc = " <!-- @property-category=Spacing -->"]
     BorderRightProperty("border-width-right") {
         Default = "undefined";
-        Item = Val;
-        Components = &'static mut Style;
+        Item = Length;
+        Components = (&'static mut Style, Option<&'static mut RelativeLengths>);
         Filters = With<Node>;
-        Parser = parse::ValParser;
-        Apply = |value, style, _assets, _commands, _entity| {
-            if &style.border.right != value {
-                style.border.right = *value;
-            }
+        Parser = LengthParser;
+        Apply = |value, components, _assets, commands, entity| {
+            apply_length(
+                value,
+                components,
+                commands,
+                entity,
+                Self::name(),
+                |s| &mut s.border.right,
+            );
         };
     }
 }
@@ -284,14 +334,19 @@ style_property! {
     #[doc = " <!-- @property-category=Spacing -->"]
     BorderTopProperty("border-width-top") {
         Default = "undefined";
-        Item = Val;
-        Components = &'static mut Style;
+        Item = Length;
+        Components = (&'static mut Style, Option<&'static mut RelativeLengths>);
         Filters = With<Node>;
-        Parser = parse::ValParser;
-        Apply = |value, style, _assets, _commands, _entity| {
-            if &style.border.top != value {
-                style.border.top = *value;
-            }
+        Parser = LengthParser;
+        Apply = |value, components, _assets, commands, entity| {
+            apply_length(
+                value,
+                components,
+                commands,
+                entity,
+                Self::name(),
+                |s| &mut s.border.top,
+            );
         };
     }
 }
@@ -304,14 +359,19 @@ style_property! {
     #[doc = " <!-- @property-category=Spacing -->"]
     BorderBottomProperty("border-width-bottom") {
         Default = "undefined";
-        Item = Val;
-        Components = &'static mut Style;
+        Item = Length;
+        Components = (&'static mut Style, Option<&'static mut RelativeLengths>);
         Filters = With<Node>;
-        Parser = parse::ValParser;
-        Apply = |value, style, _assets, _commands, _entity| {
-            if &style.border.bottom != value {
-                style.border.bottom = *value;
-            }
+        Parser = LengthParser;
+        Apply = |value, components, _assets, commands, entity| {
+            apply_length(
+                value,
+                components,
+                commands,
+                entity,
+                Self::name(),
+                |s| &mut s.border.bottom,
+            );
         };
     }
 }
@@ -327,14 +387,19 @@ style_property! {
     #[doc = " <!-- @property-category=Spacing -->"]
     ColumnGapProperty("column-gap") {
         Default = "0px";
-        Item = Val;
-        Components = &'static mut Style;
+        Item = Length;
+        Components = (&'static mut Style, Option<&'static mut RelativeLengths>);
         Filters = With<Node>;
-        Parser = parse::ValParser;
-        Apply = |value, style, _assets, _commands, _entity| {
-            if &style.column_gap != value {
-                style.column_gap = *value;
-            }
+        Parser = LengthParser;
+        Apply = |value, components, _assets, commands, entity| {
+            apply_length(
+                value,
+                components,
+                commands,
+                entity,
+                Self::name(),
+                |s| &mut s.column_gap,
+            );
         };
     }
 }
@@ -350,14 +415,19 @@ style_property! {
     #[doc = " <!-- @property-category=Spacing -->"]
     RowGapProperty("row-gap") {
         Default = "0px";
-        Item = Val;
-        Components = &'static mut Style;
+        Item = Length;
+        Components = (&'static mut Style, Option<&'static mut RelativeLengths>);
         Filters = With<Node>;
-        Parser = parse::ValParser;
-        Apply = |value, style, _assets, _commands, _entity| {
-            if &style.row_gap != value {
-                style.row_gap = *value;
-            }
+        Parser = LengthParser;
+        Apply = |value, components, _assets, commands, entity| {
+            apply_length(
+                value,
+                components,
+                commands,
+                entity,
+                Self::name(),
+                |s| &mut s.row_gap,
+            );
         };
     }
 }
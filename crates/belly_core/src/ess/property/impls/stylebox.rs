@@ -2,10 +2,13 @@ use super::parse;
 use crate::compound_style_property;
 use crate::element::Element;
 use crate::eml::Variant;
+use crate::ess::PropertyParser;
 use crate::ess::PropertyValue;
 use crate::ess::StyleProperty;
 use crate::ess::StylePropertyMethods;
+use crate::ess::StylePropertyToken;
 use crate::style_property;
+use crate::ElementsError;
 use bevy::prelude::*;
 use bevy::utils::HashMap;
 use bevy_stylebox::*;
@@ -181,3 +184,102 @@ style_property! {
         };
     }
 }
+
+/// <!-- @property-type=$flip -->
+/// Flip: `none`, `x`, `y` or `both`.
+pub struct FlipParser;
+impl PropertyParser<(bool, bool)> for FlipParser {
+    fn parse(prop: &StyleProperty) -> Result<(bool, bool), ElementsError> {
+        match prop.as_slice() {
+            [StylePropertyToken::Identifier(ident)] => match ident.as_str() {
+                "none" => Ok((false, false)),
+                "x" => Ok((true, false)),
+                "y" => Ok((false, true)),
+                "both" => Ok((true, true)),
+                _ => Err(ElementsError::InvalidPropertyValue(format!(
+                    "Expected none|x|y|both, got `{ident}`"
+                ))),
+            },
+            _ => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected none|x|y|both, got `{}`",
+                prop.to_string()
+            ))),
+        }
+    }
+}
+
+style_property! {
+    #[doc = " The `stylebox-flip` property mirrors the region of the image before slicing"]
+    #[doc = " it: `none`, `x`, `y` or `both`. The `stylebox-slice` describes the flipped"]
+    #[doc = " region, so one image serves the left and the right variant of the frame."]
+    #[doc = " <!-- @property-type=$flip -->"]
+    #[doc = " <!-- @property-category=Stylebox -->"]
+    StyleboxFlipProperty("stylebox-flip") {
+        Default = "none";
+        Item = (bool, bool);
+        Components = &'static mut Stylebox;
+        Filters = With<Node>;
+        Parser = FlipParser;
+        Apply = |value, stylebox, _assets, _commands, _entity| {
+            let (flip_x, flip_y) = *value;
+            if stylebox.flip_x != flip_x || stylebox.flip_y != flip_y {
+                stylebox.flip_x = flip_x;
+                stylebox.flip_y = flip_y;
+            }
+        };
+    }
+}
+
+/// <!-- @property-type=$snap -->
+/// Snap: `none` or `pixel`.
+pub struct SnapParser;
+impl PropertyParser<bool> for SnapParser {
+    fn parse(prop: &StyleProperty) -> Result<bool, ElementsError> {
+        match prop.as_slice() {
+            [StylePropertyToken::Identifier(ident)] if ident == "none" => Ok(false),
+            [StylePropertyToken::Identifier(ident)] if ident == "pixel" => Ok(true),
+            _ => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected none|pixel, got `{}`",
+                prop.to_string()
+            ))),
+        }
+    }
+}
+
+style_property! {
+    #[doc = " The `stylebox-snap` property rounds the edges of the slices to the physical"]
+    #[doc = " pixels when set to `pixel`. Use it for the pixel-art styleboxes to avoid the"]
+    #[doc = " seams between the slices at the fractional scale factors."]
+    #[doc = " <!-- @property-type=$snap -->"]
+    #[doc = " <!-- @property-category=Stylebox -->"]
+    StyleboxSnapProperty("stylebox-snap") {
+        Default = "none";
+        Item = bool;
+        Components = &'static mut Stylebox;
+        Filters = With<Node>;
+        Parser = SnapParser;
+        Apply = |value, stylebox, _assets, _commands, _entity| {
+            if stylebox.pixel_snap != *value {
+                stylebox.pixel_snap = *value;
+            }
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn parse_flip_and_snap() {
+        let prop = |s: &str| StyleProperty::from_str(s).unwrap();
+        assert_eq!(FlipParser::parse(&prop("x")).unwrap(), (true, false));
+        assert_eq!(FlipParser::parse(&prop("both")).unwrap(), (true, true));
+        assert_eq!(FlipParser::parse(&prop("none")).unwrap(), (false, false));
+        assert!(FlipParser::parse(&prop("x y")).is_err());
+        assert!(SnapParser::parse(&prop("pixel")).unwrap());
+        assert!(!SnapParser::parse(&prop("none")).unwrap());
+        assert!(SnapParser::parse(&prop("round")).is_err());
+    }
+}
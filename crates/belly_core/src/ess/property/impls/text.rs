@@ -1,11 +1,16 @@
+use super::bitmap_font::{grid_font, is_bitmap_font, parse_grid_font};
+use super::bitmap_font::{BitmapFont, BitmapGrid, BitmapText};
 use super::parse;
 use crate::ess::defaults::Defaults;
 use crate::ess::PropertyParser;
 use crate::ess::StyleProperty;
+use crate::ess::StylePropertyMethods;
 use crate::ess::StylePropertyToken;
 use crate::style_property;
 use crate::ElementsError;
+use ab_glyph::Font as _;
 use bevy::prelude::*;
+use bevy::text::TextLayoutInfo;
 
 #[derive(Default, Clone)]
 pub enum FontPath {
@@ -15,19 +20,52 @@ pub enum FontPath {
     Italic,
     BoldItalic,
     Custom(String),
+    /// The bitmap font cut from the grid atlas at the path
+    Grid(String, BitmapGrid),
 }
 
-/// regular|bold|italic|bold-italic|$string
-pub struct FontParser;
-impl PropertyParser<FontPath> for FontParser {
-    fn parse(prop: &StyleProperty) -> Result<FontPath, ElementsError> {
-        let Some(token) = prop.first() else {
-            return Err(ElementsError::InvalidPropertyValue(format!(
-                "Expected regular|bold|italic|bold-italic|$string, got nothing"
-            )));
+/// The font the [`FontPath`] points to
+pub enum ResolvedFont {
+    Font(Handle<Font>),
+    Bitmap(Handle<BitmapFont>),
+}
+
+impl FontPath {
+    fn resolve(&self, world: &mut World) -> ResolvedFont {
+        let path = match self {
+            FontPath::Custom(path) => path,
+            FontPath::Grid(path, grid) => return ResolvedFont::Bitmap(grid_font(world, path, grid)),
+            font => {
+                let defaults = world.resource::<Defaults>();
+                return match font {
+                    FontPath::Italic => ResolvedFont::Font(defaults.italic_font.clone()),
+                    FontPath::Bold => ResolvedFont::Font(defaults.bold_font.clone()),
+                    FontPath::BoldItalic => ResolvedFont::Font(defaults.bold_italic_font.clone()),
+                    _ => match &defaults.bitmap_font {
+                        Some(font) => ResolvedFont::Bitmap(font.clone()),
+                        None => ResolvedFont::Font(defaults.regular_font.clone()),
+                    },
+                };
+            }
         };
+        let asset_server = world.resource::<AssetServer>();
+        if is_bitmap_font(path) {
+            ResolvedFont::Bitmap(asset_server.load(path))
+        } else {
+            ResolvedFont::Font(asset_server.load(path))
+        }
+    }
+}
+
+impl TryFrom<&StylePropertyToken> for FontPath {
+    type Error = ElementsError;
+    fn try_from(token: &StylePropertyToken) -> Result<Self, Self::Error> {
         match token {
             StylePropertyToken::String(id) => Ok(FontPath::Custom(id.clone())),
+            StylePropertyToken::Function(func) if func.name == "grid" => {
+                let (path, grid) = parse_grid_font(func)?;
+                Ok(FontPath::Grid(path, grid))
+            }
             StylePropertyToken::Identifier(ident) => match ident.as_str() {
                 "regular" => Ok(FontPath::Regular),
                 "bold" => Ok(FontPath::Bold),
@@ -47,46 +85,204 @@ impl PropertyParser<FontPath> for FontParser {
     }
 }
 
+/// Updates the styles of the text sections only if some of them differ
+/// from the new value: bevy measures the text and runs the layout again
+/// each time the `Text` is changed, so restyling the element with the same
+/// values shouldn't touch it.
+fn update_sections<T: PartialEq + Clone>(
+    text: &mut Mut<Text>,
+    value: T,
+    field: impl Fn(&mut TextStyle) -> &mut T,
+) {
+    let same = text
+        .bypass_change_detection()
+        .sections
+        .iter_mut()
+        .all(|s| *field(&mut s.style) == value);
+    if !same {
+        text.sections
+            .iter_mut()
+            .for_each(|s| *field(&mut s.style) = value.clone());
+    }
+}
+
+/// The primary font followed by the fallback fonts used for the texts
+/// with glyphs missing in the primary font.
+#[derive(Default, Clone)]
+pub struct FontChain(pub Vec<FontPath>);
+
+/// regular|bold|italic|bold-italic|$string|grid(..)[, regular|bold|italic|bold-italic|$string]*
+pub struct FontParser;
+impl PropertyParser<FontChain> for FontParser {
+    fn parse(prop: &StyleProperty) -> Result<FontChain, ElementsError> {
+        if prop.first().is_none() {
+            return Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected regular|bold|italic|bold-italic|$string, got nothing"
+            )));
+        };
+        let mut chain = vec![];
+        let mut expect_font = true;
+        for token in prop.iter() {
+            match (expect_font, token) {
+                (false, StylePropertyToken::Comma) => expect_font = true,
+                (true, token) => {
+                    chain.push(FontPath::try_from(token)?);
+                    expect_font = false;
+                }
+                (false, token) => {
+                    return Err(ElementsError::InvalidPropertyValue(format!(
+                        "Expected `,` between fonts, got `{}`",
+                        token.to_string()
+                    )))
+                }
+            }
+        }
+        if expect_font {
+            return Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected font after `,`"
+            )));
+        }
+        Ok(FontChain(chain))
+    }
+}
+
+/// Fonts to choose from for each section of the text: the first font
+/// containing all the glyphs of the section is used.
+#[derive(Component, Default, Debug)]
+pub struct FontFallbacks(pub Vec<Handle<Font>>);
+
 style_property! {
-    #[doc = " TODO: wtite FontProperty description"]
+    #[doc = " Specify the font of the text, optionally followed by fallback fonts:"]
+    #[doc = " ```css"]
+    #[doc = " font: bold;"]
+    #[doc = " font: \"fonts/Inter.ttf\", \"fonts/NotoSansJP.ttf\", \"fonts/NotoEmoji.ttf\";"]
+    #[doc = " ```"]
+    #[doc = " "]
+    #[doc = " When fallback fonts provided, each text section uses the first font"]
+    #[doc = " that contains all glyphs of the section."]
+    #[doc = " "]
+    #[doc = " The texts are drawn with the bitmap font when the first font is the"]
+    #[doc = " BMFont `.fnt` file or the grid atlas:"]
+    #[doc = " ```css"]
+    #[doc = " font: \"fonts/retro.fnt\";"]
+    #[doc = " font: grid(\"fonts/retro.png\", 8px 8px, \"ABCDEFGHIJKLMNOPQRSTUVWXYZ\");"]
+    #[doc = " ```"]
     #[doc = " <!-- @property-category=Text -->"]
     FontProperty("font") {
         Default = "regular";
-        Item = FontPath;
-        Components = &'static mut Text;
+        Item = FontChain;
+        Components = (
+            &'static mut Text,
+            Option<&'static FontFallbacks>,
+            Option<&'static BitmapText>,
+        );
         Filters = With<Node>;
         AffectsVirtual = true;
         Parser = FontParser;
-        Apply = |value, text, assets, commands, entity| {
-            if let FontPath::Custom(path) = value {
-                text
-                    .sections
-                    .iter_mut()
-                    .for_each(|section| section.style.font = assets.load(path));
-            } else {
-                let path = value.clone();
-                commands.add(move |world: &mut World| {
-                    let defaults = world.resource::<Defaults>();
-                    let font = match path {
-                        FontPath::Regular => defaults.regular_font.clone(),
-                        FontPath::Italic => defaults.italic_font.clone(),
-                        FontPath::Bold => defaults.bold_font.clone(),
-                        FontPath::BoldItalic => defaults.bold_italic_font.clone(),
-                        _ => defaults.regular_font.clone(),
-                    };
-                    world
-                        .entity_mut(entity)
-                        .get_mut::<Text>()
-                        .unwrap()
-                        .sections
-                        .iter_mut()
-                        .for_each(|section| section.style.font = font.clone());
-                });
+        Apply = |value, components, assets, commands, entity| {
+            let (mut text, fallbacks, bitmap) = components;
+            match value.0.as_slice() {
+                [FontPath::Custom(path)] if !is_bitmap_font(path) => {
+                    update_sections(&mut text, assets.load(path), |s| &mut s.font);
+                    if fallbacks.is_some() {
+                        commands.entity(entity).remove::<FontFallbacks>();
+                    }
+                    if bitmap.is_some() {
+                        commands.entity(entity).remove::<BitmapText>();
+                    }
+                    return;
+                }
+                _ => {}
             }
+            let chain = value.clone();
+            let has_fallbacks = fallbacks.is_some();
+            let bitmap = bitmap.map(|bitmap| bitmap.font.clone());
+            commands.add(move |world: &mut World| {
+                let resolved: Vec<_> = chain.0.iter().map(|path| path.resolve(world)).collect();
+                // the bitmap font is used only as the primary font, bevy
+                // still measures the text with the ttf font
+                let primary = match resolved.first() {
+                    Some(ResolvedFont::Bitmap(font)) => Some(font.clone()),
+                    _ => None,
+                };
+                let mut fonts: Vec<_> = resolved
+                    .into_iter()
+                    .filter_map(|font| match font {
+                        ResolvedFont::Font(font) => Some(font),
+                        ResolvedFont::Bitmap(_) => None,
+                    })
+                    .collect();
+                if fonts.is_empty() {
+                    fonts.push(world.resource::<Defaults>().regular_font.clone());
+                }
+                let Some(mut entity) = world.get_entity_mut(entity) else {
+                    return;
+                };
+                match primary {
+                    Some(font) if bitmap.as_ref() != Some(&font) => {
+                        entity.insert(BitmapText::new(font));
+                    }
+                    None if bitmap.is_some() => {
+                        entity.remove::<BitmapText>();
+                    }
+                    _ => {}
+                }
+                let font = fonts[0].clone();
+                if let Some(mut text) = entity.get_mut::<Text>() {
+                    update_sections(&mut text, font, |s| &mut s.font);
+                }
+                if fonts.len() > 1 {
+                    entity.insert(FontFallbacks(fonts));
+                } else if has_fallbacks {
+                    entity.remove::<FontFallbacks>();
+                }
+            });
         };
     }
 }
 
+fn covered_glyphs(font: &Font, text: &str) -> usize {
+    text.chars()
+        .filter(|c| !c.is_whitespace() && font.font.glyph_id(*c).0 != 0)
+        .count()
+}
+
+/// Selects the font from the [`FontFallbacks`] for each text section.
+pub fn apply_font_fallbacks_system(
+    fonts: Res<Assets<Font>>,
+    mut font_events: EventReader<AssetEvent<Font>>,
+    mut texts: Query<(Ref<FontFallbacks>, &mut Text)>,
+) {
+    let fonts_changed = font_events.read().count() > 0;
+    for (fallbacks, mut text) in texts.iter_mut() {
+        if !fonts_changed && !fallbacks.is_changed() && !text.is_changed() {
+            continue;
+        }
+        for idx in 0..text.sections.len() {
+            let section = &text.sections[idx];
+            let required = section.value.chars().filter(|c| !c.is_whitespace()).count();
+            let mut best: Option<(&Handle<Font>, usize)> = None;
+            for handle in fallbacks.0.iter() {
+                let Some(font) = fonts.get(handle) else {
+                    continue;
+                };
+                let covered = covered_glyphs(font, &section.value);
+                if best.map(|(_, c)| covered > c).unwrap_or(true) {
+                    best = Some((handle, covered));
+                }
+                if covered == required {
+                    break;
+                }
+            }
+            if let Some((handle, _)) = best {
+                if &section.style.font != handle {
+                    text.sections[idx].style.font = handle.clone();
+                }
+            }
+        }
+    }
+}
+
 style_property! {
     #[doc = " TODO: remove depricate ColorProperty"]
     #[doc = " <!-- @property-category=Text -->"]
@@ -99,32 +295,425 @@ style_property! {
         Parser = parse::ColorParser;
         Apply = |value, text, _assets, _commands, _entity| {
             // TODO: mark it deprecated
-            text
-                .sections
-                .iter_mut()
-                .for_each(|section| section.style.color = *value);
+            let mut text = text;
+            update_sections(&mut text, *value, |s| &mut s.color);
         };
     }
 }
 
+/// Font size of the text: absolute or relative to the parent node height.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FontSize {
+    Px(f32),
+    Percent(f32),
+}
+
+impl Default for FontSize {
+    fn default() -> Self {
+        FontSize::Px(24.)
+    }
+}
+
+/// <!-- @property-type=$fontsize -->
+/// Font size: number or `px` suffixed value for absolute size (`24`, `24px`),
+/// `%` suffixed value for the size relative to the parent node height (`50%`).
+pub struct FontSizeParser;
+impl PropertyParser<FontSize> for FontSizeParser {
+    fn parse(prop: &StyleProperty) -> Result<FontSize, ElementsError> {
+        match prop.first() {
+            Some(StylePropertyToken::Percentage(val)) => Ok(FontSize::Percent(val.to_float())),
+            Some(StylePropertyToken::Number(val)) => Ok(FontSize::Px(val.to_float())),
+            Some(StylePropertyToken::Dimension(val, unit)) if unit == "px" => {
+                Ok(FontSize::Px(val.to_float()))
+            }
+            Some(token) => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected $num|$px|$percent, got `{}`",
+                token.to_string()
+            ))),
+            None => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected $num|$px|$percent, got nothing"
+            ))),
+        }
+    }
+}
+
+/// How the text reacts when it doesn't fit the parent node width.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum TextFit {
+    #[default]
+    None,
+    Shrink,
+}
+
+impl TryFrom<&StyleProperty> for TextFit {
+    type Error = ElementsError;
+    fn try_from(value: &StyleProperty) -> Result<Self, Self::Error> {
+        match value.first() {
+            Some(StylePropertyToken::Identifier(ident)) if ident == "none" => Ok(TextFit::None),
+            Some(StylePropertyToken::Identifier(ident)) if ident == "shrink" => {
+                Ok(TextFit::Shrink)
+            }
+            _ => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected none|shrink, got `{}`",
+                value.to_string()
+            ))),
+        }
+    }
+}
+
+/// Keeps the state of the relative font size and `text-fit` of the text.
+/// The font size is resolved by `update_text_sizing_system`.
+#[derive(Component, Debug, Default)]
+pub struct TextSizing {
+    percent: Option<f32>,
+    fit: TextFit,
+    base: f32,
+}
+
+fn update_text_sizing<F: FnOnce(&mut TextSizing) + Send + 'static>(
+    commands: &mut Commands,
+    entity: Entity,
+    update: F,
+) {
+    commands.add(move |world: &mut World| {
+        let Some(mut entity) = world.get_entity_mut(entity) else {
+            return;
+        };
+        if let Some(mut sizing) = entity.get_mut::<TextSizing>() {
+            update(&mut sizing);
+        } else {
+            let base = entity
+                .get::<Text>()
+                .and_then(|t| t.sections.first())
+                .map(|s| s.style.font_size)
+                .unwrap_or(24.);
+            let mut sizing = TextSizing {
+                base,
+                ..default()
+            };
+            update(&mut sizing);
+            entity.insert(sizing);
+        }
+    });
+}
+
 style_property! {
-    #[doc = " TODO: write FontSizeProperty description"]
+    #[doc = " Specify the font size of the text:"]
+    #[doc = " ```css"]
+    #[doc = " font-size: 24px;"]
+    #[doc = " font-size: 50%;"]
+    #[doc = " ```"]
+    #[doc = " "]
+    #[doc = " Percent values are relative to the height of the parent node."]
+    #[doc = " <!-- @property-type=$fontsize -->"]
     #[doc = " <!-- @property-category=Text -->"]
     FontSizeProperty("font-size") {
         Default = "24";
-        Item = f32;
-        Components = &'static mut Text;
+        Item = FontSize;
+        Components = (&'static mut Text, Option<&'static TextSizing>);
         Filters = With<Node>;
         AffectsVirtual = true;
-        Parser = parse::NumParser;
-        Apply = |value, text, _assets, _commands, _entity| {
-            text
+        Parser = FontSizeParser;
+        Apply = |value, components, _assets, commands, entity| {
+            let (mut text, sizing) = components;
+            match *value {
+                FontSize::Px(px) => {
+                    update_sections(&mut text, px, |s| &mut s.font_size);
+                    if sizing.is_some() {
+                        update_text_sizing(commands, entity, move |s| {
+                            s.percent = None;
+                            s.base = px;
+                        });
+                    }
+                }
+                FontSize::Percent(percent) => {
+                    update_text_sizing(commands, entity, move |s| s.percent = Some(percent));
+                }
+            }
+        };
+    }
+}
+
+style_property! {
+    #[doc = " Specify how the text reacts when it doesn't fit the width of the parent node:"]
+    #[doc = " ```css"]
+    #[doc = " text-fit: shrink;"]
+    #[doc = " ```"]
+    #[doc = " "]
+    #[doc = " Supported values:"]
+    #[doc = " - `none`: the text keeps its font size"]
+    #[doc = " - `shrink`: the font size is reduced until the text fits"]
+    #[doc = " <!-- @property-category=Text -->"]
+    TextFitProperty("text-fit") {
+        Default = "none";
+        Item = TextFit;
+        Components = Option<&'static TextSizing>;
+        Filters = (With<Node>, With<Text>);
+        AffectsVirtual = true;
+        Parser = parse::IdentifierParser<TextFit>;
+        Apply = |value, sizing, _assets, commands, entity| {
+            let fit = *value;
+            if sizing.map(|s| s.fit != fit).unwrap_or(fit != TextFit::None) {
+                update_text_sizing(commands, entity, move |s| s.fit = fit);
+            }
+        };
+    }
+}
+
+/// Resolves relative font sizes and shrinks the texts with `text-fit: shrink`
+/// using the parent node size and the text layout from the previous frame.
+/// Only the texts whose sizing, content, layout or node changed and the
+/// children of the resized nodes are visited, and the font size is written
+/// only when it differs.
+#[allow(clippy::type_complexity)]
+pub fn update_text_sizing_system(
+    mut texts: ParamSet<(
+        Query<
+            Entity,
+            (
+                With<TextSizing>,
+                Or<(
+                    Changed<TextSizing>,
+                    Changed<Text>,
+                    Changed<TextLayoutInfo>,
+                    Changed<Node>,
+                    Changed<Parent>,
+                )>,
+            ),
+        >,
+        Query<(&TextSizing, &mut Text, &Parent, Option<&TextLayoutInfo>)>,
+    )>,
+    resized: Query<&Children, Changed<Node>>,
+    nodes: Query<&Node>,
+    mut affected: Local<Vec<Entity>>,
+) {
+    affected.clear();
+    affected.extend(texts.p0().iter());
+    affected.extend(resized.iter().flat_map(|children| children.iter().copied()));
+    affected.sort_unstable();
+    affected.dedup();
+    let mut texts = texts.p1();
+    for entity in affected.iter() {
+        let Ok((sizing, mut text, parent, layout)) = texts.get_mut(*entity) else {
+            continue;
+        };
+        let Ok(parent_node) = nodes.get(parent.get()) else {
+            continue;
+        };
+        let available = parent_node.size();
+        let base = match sizing.percent {
+            Some(percent) => available.y * percent / 100.,
+            None => sizing.base,
+        };
+        if base <= 0. {
+            continue;
+        }
+        let mut size = base;
+        if let (TextFit::Shrink, Some(layout)) = (sizing.fit, layout) {
+            let current = text
                 .sections
+                .first()
+                .map(|s| s.style.font_size)
+                .unwrap_or(base);
+            if current > 0. && layout.logical_size.x > 0. && available.x > 0. {
+                let natural_width = layout.logical_size.x * base / current;
+                if natural_width > available.x {
+                    size = base * available.x / natural_width;
+                }
+            }
+        }
+        let size = size.max(1.);
+        if text
+            .sections
+            .iter()
+            .any(|s| (s.style.font_size - size).abs() > 0.1)
+        {
+            text.sections
                 .iter_mut()
-                .for_each(|section| section.style.font_size = *value);
+                .for_each(|section| section.style.font_size = size);
+        }
+    }
+}
+
+/// Outline of the text glyphs: the width in pixels and the color.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct TextStroke {
+    pub width: f32,
+    pub color: Color,
+}
+
+/// <!-- @property-type=$textstroke -->
+/// Text stroke: `none` or the width (number or `px` suffixed value)
+/// followed by the color (`2px #000`).
+pub struct TextStrokeParser;
+impl PropertyParser<TextStroke> for TextStrokeParser {
+    fn parse(prop: &StyleProperty) -> Result<TextStroke, ElementsError> {
+        let width = match prop.first() {
+            Some(StylePropertyToken::Identifier(ident)) if ident == "none" && prop.len() == 1 => {
+                return Ok(TextStroke::default())
+            }
+            Some(StylePropertyToken::Number(val)) => val.to_float(),
+            Some(StylePropertyToken::Dimension(val, unit)) if unit == "px" => val.to_float(),
+            Some(token) => {
+                return Err(ElementsError::InvalidPropertyValue(format!(
+                    "Expected none|$px $color, got `{}`",
+                    token.to_string()
+                )))
+            }
+            None => {
+                return Err(ElementsError::InvalidPropertyValue(format!(
+                    "Expected none|$px $color, got nothing"
+                )))
+            }
         };
+        if prop.len() != 2 {
+            return Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected none|$px $color, got `{}`",
+                prop.to_string()
+            )));
+        }
+        let color = parse::color(&StyleProperty(prop[1..].iter().cloned().collect()))?;
+        Ok(TextStroke { width, color })
     }
 }
+
+/// Keeps the stroke of the text and the layers rendering it: copies of the
+/// text shifted in 8 directions and painted with the stroke color, followed
+/// by the copy of the text itself. The glyphs of the original text are hidden
+/// by `hide_stroked_text_system`.
+#[derive(Component, Debug, Default)]
+pub struct TextStrokeLayers {
+    pub stroke: TextStroke,
+    layers: Vec<Entity>,
+}
+
+const STROKE_DIRECTIONS: [(f32, f32); 8] = [
+    (-1., -1.),
+    (0., -1.),
+    (1., -1.),
+    (-1., 0.),
+    (1., 0.),
+    (-1., 1.),
+    (0., 1.),
+    (1., 1.),
+];
+
+style_property! {
+    #[doc = " Specify the outline of the text glyphs:"]
+    #[doc = " ```css"]
+    #[doc = " text-stroke: 2px #000;"]
+    #[doc = " text-stroke: none;"]
+    #[doc = " ```"]
+    #[doc = " "]
+    #[doc = " The stroke is rendered with the copies of the text shifted by the"]
+    #[doc = " stroke width, so it works best with the small widths."]
+    #[doc = " <!-- @property-type=$textstroke -->"]
+    #[doc = " <!-- @property-category=Text -->"]
+    TextStrokeProperty("text-stroke") {
+        Default = "none";
+        Item = TextStroke;
+        Components = Option<&'static mut TextStrokeLayers>;
+        Filters = (With<Node>, With<Text>);
+        AffectsVirtual = true;
+        Parser = TextStrokeParser;
+        Apply = |value, layers, _assets, commands, entity| {
+            let stroke = *value;
+            match layers {
+                Some(mut layers) if stroke.width > 0. => {
+                    if layers.stroke != stroke {
+                        layers.stroke = stroke;
+                    }
+                }
+                Some(layers) => {
+                    for layer in layers.layers.iter() {
+                        commands.entity(*layer).despawn_recursive();
+                    }
+                    commands.add(move |world: &mut World| {
+                        let Some(mut entity) = world.get_entity_mut(entity) else {
+                            return;
+                        };
+                        entity.remove::<TextStrokeLayers>();
+                        // force the layout of the hidden glyphs
+                        if let Some(mut text) = entity.get_mut::<Text>() {
+                            text.set_changed();
+                        }
+                    });
+                }
+                None if stroke.width > 0. => {
+                    commands.entity(entity).insert(TextStrokeLayers {
+                        stroke,
+                        layers: vec![],
+                    });
+                }
+                None => {}
+            }
+        };
+    }
+}
+
+/// Spawns the layers of the stroked texts and keeps them in sync with the text.
+pub fn update_text_stroke_system(
+    mut commands: Commands,
+    mut strokes: Query<(Entity, Ref<Text>, &mut TextStrokeLayers)>,
+    mut layers: Query<(&mut Text, &mut Style), Without<TextStrokeLayers>>,
+) {
+    for (entity, text, mut stroke) in strokes.iter_mut() {
+        if stroke.layers.is_empty() {
+            let spawned: Vec<Entity> = (0..=STROKE_DIRECTIONS.len())
+                .map(|_| {
+                    commands
+                        .spawn(TextBundle {
+                            style: Style {
+                                position_type: PositionType::Absolute,
+                                width: Val::Percent(100.),
+                                height: Val::Percent(100.),
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .id()
+                })
+                .collect();
+            commands.entity(entity).push_children(&spawned);
+            stroke.layers = spawned;
+            continue;
+        }
+        if !text.is_changed() && !stroke.is_changed() {
+            continue;
+        }
+        let TextStroke { width, color } = stroke.stroke;
+        for (idx, layer) in stroke.layers.iter().enumerate() {
+            let Ok((mut layer_text, mut style)) = layers.get_mut(*layer) else {
+                continue;
+            };
+            *layer_text = text.clone();
+            let (dx, dy) = STROKE_DIRECTIONS.get(idx).copied().unwrap_or_default();
+            if dx != 0. || dy != 0. {
+                layer_text
+                    .sections
+                    .iter_mut()
+                    .for_each(|section| section.style.color = color);
+            }
+            style.left = Val::Px(dx * width);
+            style.top = Val::Px(dy * width);
+        }
+    }
+}
+
+/// Hides the glyphs of the stroked texts: the text is rendered by the
+/// [`TextStrokeLayers`] on top of the stroke. The texts drawn with the
+/// bitmap fonts are hidden the same way. The text layout is recalculated
+/// only when the text changes, so the glyphs are cleared right after it.
+pub fn hide_stroked_text_system(
+    mut texts: Query<&mut TextLayoutInfo, Or<(With<TextStrokeLayers>, With<BitmapText>)>>,
+) {
+    for mut layout in texts.iter_mut() {
+        if !layout.glyphs.is_empty() {
+            layout.glyphs.clear();
+        }
+    }
+}
+
 //     /// Applies the `vertical-align` property on [`TextAlignment::vertical`](`TextAlignment`) property of matched [`Text`] components.
 //     #[derive(Default)]
 //     pub(crate) struct VerticalAlignProperty;
@@ -234,3 +823,135 @@ style_property! {
 //                 .for_each(|section| section.value = cache.clone());
 //         }
 //     }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn parse_font_chain() {
+        let p = StyleProperty::from_str("bold").unwrap();
+        let chain = FontParser::parse(&p).unwrap();
+        assert!(matches!(chain.0.as_slice(), [FontPath::Bold]));
+
+        let p = StyleProperty::from_str("\"Inter.ttf\", regular, \"NotoEmoji.ttf\"").unwrap();
+        let chain = FontParser::parse(&p).unwrap();
+        assert!(matches!(
+            chain.0.as_slice(),
+            [FontPath::Custom(a), FontPath::Regular, FontPath::Custom(b)]
+                if a == "Inter.ttf" && b == "NotoEmoji.ttf"
+        ));
+
+        let p = StyleProperty::from_str("grid(\"retro.png\", 8px, \"AB\"), regular").unwrap();
+        let chain = FontParser::parse(&p).unwrap();
+        assert!(matches!(
+            chain.0.as_slice(),
+            [FontPath::Grid(path, grid), FontPath::Regular]
+                if path == "retro.png" && grid.cell == UVec2::splat(8)
+        ));
+
+        let p = StyleProperty::from_str("regular bold").unwrap();
+        assert!(FontParser::parse(&p).is_err());
+        let p = StyleProperty::from_str("regular,").unwrap();
+        assert!(FontParser::parse(&p).is_err());
+    }
+
+    #[test]
+    fn parse_font_size() {
+        let p = StyleProperty::from_str("24").unwrap();
+        assert_eq!(FontSizeParser::parse(&p).unwrap(), FontSize::Px(24.));
+        let p = StyleProperty::from_str("18px").unwrap();
+        assert_eq!(FontSizeParser::parse(&p).unwrap(), FontSize::Px(18.));
+        let p = StyleProperty::from_str("50%").unwrap();
+        assert_eq!(FontSizeParser::parse(&p).unwrap(), FontSize::Percent(50.));
+
+        let p = StyleProperty::from_str("2em").unwrap();
+        assert!(FontSizeParser::parse(&p).is_err());
+        let p = StyleProperty::from_str("big").unwrap();
+        assert!(FontSizeParser::parse(&p).is_err());
+    }
+
+    #[test]
+    fn parse_text_fit() {
+        let p = StyleProperty::from_str("shrink").unwrap();
+        let fit = parse::IdentifierParser::<TextFit>::parse(&p).unwrap();
+        assert_eq!(fit, TextFit::Shrink);
+        let p = StyleProperty::from_str("none").unwrap();
+        let fit = parse::IdentifierParser::<TextFit>::parse(&p).unwrap();
+        assert_eq!(fit, TextFit::None);
+
+        let p = StyleProperty::from_str("grow").unwrap();
+        assert!(parse::IdentifierParser::<TextFit>::parse(&p).is_err());
+    }
+
+    #[test]
+    fn parse_text_stroke() {
+        let p = StyleProperty::from_str("2px #000").unwrap();
+        let stroke = TextStrokeParser::parse(&p).unwrap();
+        assert_eq!(stroke.width, 2.);
+        assert_eq!(stroke.color, Color::rgb(0., 0., 0.));
+
+        let p = StyleProperty::from_str("none").unwrap();
+        assert_eq!(TextStrokeParser::parse(&p).unwrap(), TextStroke::default());
+
+        let p = StyleProperty::from_str("2px").unwrap();
+        assert!(TextStrokeParser::parse(&p).is_err());
+    }
+
+    #[test]
+    fn same_styles_keep_text_unchanged() {
+        let mut world = World::new();
+        let entity = world.spawn(Text::from_section("hello", default())).id();
+        world.clear_trackers();
+        let mut text = world.get_mut::<Text>(entity).unwrap();
+        let color = text.sections[0].style.color;
+        update_sections(&mut text, color, |s| &mut s.color);
+        assert!(!text.is_changed());
+        update_sections(&mut text, 20., |s| &mut s.font_size);
+        assert!(text.is_changed());
+        assert_eq!(text.sections[0].style.font_size, 20.);
+    }
+
+    #[test]
+    fn text_sizing_follows_changed_nodes_only() {
+        let mut world = World::new();
+        let parent = world.spawn(Node::default()).id();
+        let entity = world
+            .spawn((
+                Text::from_section("hello", TextStyle::default()),
+                TextSizing {
+                    base: 20.,
+                    ..default()
+                },
+            ))
+            .set_parent(parent)
+            .id();
+        let mut system = IntoSystem::into_system(update_text_sizing_system);
+        system.initialize(&mut world);
+        let font_size = |world: &World| {
+            let text = world.get::<Text>(entity).unwrap();
+            text.sections[0].style.font_size
+        };
+
+        system.run((), &mut world);
+        assert_eq!(font_size(&world), 20.);
+
+        world.clear_trackers();
+        world
+            .get_mut::<Text>(entity)
+            .unwrap()
+            .bypass_change_detection()
+            .sections[0]
+            .style
+            .font_size = 10.;
+        system.run((), &mut world);
+        assert_eq!(font_size(&world), 10.);
+
+        world.clear_trackers();
+        world.get_mut::<Node>(parent).unwrap().set_changed();
+        system.run((), &mut world);
+        assert_eq!(font_size(&world), 20.);
+    }
+}
@@ -3,7 +3,8 @@ pub mod enums;
 pub mod impls;
 pub mod parse;
 mod style;
-use std::any::{type_name, Any};
+pub mod units;
+use std::any::{type_name, Any, TypeId};
 use std::sync::{Arc, RwLock};
 
 pub use self::colors::*;
@@ -12,21 +13,27 @@ pub use self::style::StylePropertyFunction;
 pub use self::style::StylePropertyMethods;
 pub use self::style::StylePropertyToken;
 pub use self::style::ToRectMap;
+pub use self::units::Length;
+pub use self::units::LengthRect;
+pub use self::units::UiUnits;
 use crate::tags::*;
 use crate::{
     element::*,
     eml::Variant,
-    ess::{ElementsBranch, StyleSheet, Styles},
+    ess::{preceding_siblings, ElementsBranch, StyleRule, StyleSheet, Styles},
+    metrics::{register_property_metrics, UiMetrics},
     ElementsError,
 };
 use bevy::ecs::query::{QueryData, QueryFilter};
 use bevy::ui::UiSystem;
+use bevy::utils::Instant;
 use bevy::{
     ecs::query::QueryItem,
     prelude::*,
     utils::HashMap,
 };
 use itertools::Itertools;
+use smallvec::smallvec;
 
 pub struct PropertyPlugin;
 impl Plugin for PropertyPlugin {
@@ -35,6 +42,17 @@ impl Plugin for PropertyPlugin {
         app.register_property::<impls::BackgroundColorProperty>();
         app.register_property::<impls::ZIndexProperty>();
 
+        // units
+        app.init_resource::<UiUnits>();
+        app.add_systems(
+            PostUpdate,
+            units::resolve_relative_lengths_system
+                .after(ApplyStyleProperties)
+                .before(impls::direction::mirror_direction_system)
+                .before(impls::anchor::apply_anchors_system)
+                .before(UiSystem::Layout),
+        );
+
         // layout control
         app.register_compound_property::<impls::layout_control::PositionProperty>();
         app.register_property::<impls::layout_control::PositionTypeProperty>();
@@ -45,6 +63,65 @@ impl Plugin for PropertyPlugin {
         app.register_property::<impls::layout_control::OverflowProperty>();
         app.register_property::<impls::layout_control::DisplayProperty>();
 
+        // direction
+        app.register_property::<impls::direction::DirectionProperty>();
+        app.add_systems(
+            PostUpdate,
+            impls::direction::mirror_direction_system
+                .after(ApplyStyleProperties)
+                .before(UiSystem::Layout),
+        );
+
+        // clock
+        app.register_property::<impls::clock::UiClockProperty>();
+
+        // chart
+        app.register_property::<impls::chart::ChartPaletteProperty>();
+        app.register_property::<impls::chart::ChartAxisProperty>();
+
+        // sound
+        app.register_property::<impls::sound::SoundHoverProperty>();
+        app.register_property::<impls::sound::SoundPressProperty>();
+        app.register_property::<impls::sound::SoundToggleProperty>();
+        app.register_property::<impls::sound::SoundErrorProperty>();
+        app.register_property::<impls::sound::SoundOpenProperty>();
+        app.register_property::<impls::sound::SoundCloseProperty>();
+
+        // opacity
+        app.register_property::<impls::opacity::OpacityProperty>();
+        app.register_property::<impls::opacity::PointerEventsProperty>();
+        app.add_systems(
+            PostUpdate,
+            impls::opacity::apply_opacity_system
+                .after(ApplyStyleProperties)
+                .before(UiSystem::Layout),
+        );
+
+        // gradient
+        app.register_property::<impls::gradient::BackgroundImageProperty>();
+        app.register_compound_property::<impls::gradient::BackgroundProperty>();
+
+        // backdrop
+        app.register_property::<impls::backdrop::BackdropFilterProperty>();
+
+        // mask
+        app.register_property::<impls::mask::MaskImageProperty>();
+        app.add_systems(
+            PostUpdate,
+            impls::mask::apply_mask_system.after(ApplyStyleProperties),
+        );
+
+        // material
+        app.register_property::<impls::material::UiMaterialProperty>();
+
+        // outline
+        app.register_property::<impls::outline::OutlineProperty>();
+        app.register_property::<impls::outline::OutlineOffsetProperty>();
+        app.add_systems(
+            PostUpdate,
+            impls::outline::apply_outline_system.after(ApplyStyleProperties),
+        );
+
         // flex container
         app.register_property::<impls::flex_container::FlexDirectionProperty>();
         app.register_property::<impls::flex_container::FlexWrapProperty>();
@@ -90,6 +167,23 @@ impl Plugin for PropertyPlugin {
         app.register_property::<impls::text::ColorProperty>();
         app.register_property::<impls::text::FontProperty>();
         app.register_property::<impls::text::FontSizeProperty>();
+        app.register_property::<impls::text::TextFitProperty>();
+        app.register_property::<impls::text::TextStrokeProperty>();
+        app.register_property::<impls::bitmap_font::LetterSpacingProperty>();
+        app.add_systems(
+            PostUpdate,
+            (
+                impls::text::update_text_sizing_system,
+                impls::text::apply_font_fallbacks_system,
+                impls::text::update_text_stroke_system,
+            )
+                .after(ApplyStyleProperties)
+                .before(UiSystem::Layout),
+        );
+        app.add_systems(
+            PostUpdate,
+            impls::text::hide_stroked_text_system.after(bevy::ui::widget::text_system),
+        );
 
         // stylebox
         app.register_compound_property::<impls::stylebox::StyleboxProperty>();
@@ -98,6 +192,8 @@ impl Plugin for PropertyPlugin {
         app.register_property::<impls::stylebox::StyleboxRegionProperty>();
         app.register_property::<impls::stylebox::StyleboxSliceProperty>();
         app.register_property::<impls::stylebox::StyleboxWidthProperty>();
+        app.register_property::<impls::stylebox::StyleboxFlipProperty>();
+        app.register_property::<impls::stylebox::StyleboxSnapProperty>();
 
         // grid
         app.register_property::<impls::grid::GridAutoColumnsProperty>();
@@ -109,6 +205,43 @@ impl Plugin for PropertyPlugin {
         app.register_property::<impls::grid::GridAutoFlowProperty>();
         app.register_property::<impls::grid::JustifyItemsProperty>();
         app.register_property::<impls::grid::JustifySelfProperty>();
+        app.register_property::<impls::grid::GridTemplateAreasProperty>();
+        app.register_property::<impls::grid::GridAreaProperty>();
+        app.add_systems(
+            PostUpdate,
+            impls::grid::apply_grid_areas_system
+                .after(ApplyStyleProperties)
+                .before(UiSystem::Layout),
+        );
+
+        // anchor
+        app.init_resource::<impls::anchor::SafeAreaInsets>();
+        app.register_property::<impls::anchor::AnchorProperty>();
+        app.register_property::<impls::anchor::AnchorOffsetProperty>();
+        app.register_property::<impls::anchor::SafeAreaProperty>();
+        app.add_systems(
+            PostUpdate,
+            impls::anchor::apply_anchors_system
+                .after(ApplyStyleProperties)
+                .before(UiSystem::Layout),
+        );
+
+        // responsive
+        app.init_resource::<impls::responsive::Breakpoints>();
+        app.register_property::<impls::responsive::HideOnProperty>();
+        app.register_property::<impls::responsive::DisplaySmProperty>();
+        app.register_property::<impls::responsive::DisplayMdProperty>();
+        app.register_property::<impls::responsive::DisplayLgProperty>();
+        app.add_systems(
+            PostUpdate,
+            (
+                impls::responsive::update_breakpoints_system,
+                impls::responsive::apply_responsive_display_system,
+            )
+                .chain()
+                .after(ApplyStyleProperties)
+                .before(UiSystem::Layout),
+        );
     }
 }
 
@@ -171,6 +304,13 @@ pub trait PropertyParser<T: Default + Any + Send + Sync> {
     fn parse(value: &StyleProperty) -> Result<T, ElementsError>;
 }
 
+/// Associates the value type with the [`PropertyParser`] used by the
+/// `#[style_prop(name: Type)]` widget properties. Specify the parser
+/// explicitly for other types: `#[style_prop(name: Type => Parser)]`
+pub trait StylePropValue: Default + Clone + PartialEq + Any + Send + Sync {
+    type Parser: PropertyParser<Self>;
+}
+
 /// Determines how a property should interact and modify the [ecs world](`bevy::prelude::World`).
 ///
 /// Each implementation of this trait should be registered with [`RegisterProperty`](crate::RegisterProperty) trait, where
@@ -231,6 +371,12 @@ pub trait Property: Default + Sized + Send + Sync + 'static {
             Variant::String(s) => StyleProperty::try_from(s)
                 .and_then(|p| Self::Parser::parse(&p))
                 .map(|v| PropertyValue::new(v)),
+            // `Val` is accepted by the properties with `Length` values
+            Variant::Boxed(b)
+                if b.is::<Val>() && TypeId::of::<Self::Item>() == TypeId::of::<Length>() =>
+            {
+                Ok(PropertyValue::new(Length::from(*b.downcast::<Val>().unwrap())))
+            }
             Variant::Boxed(b) => Ok(PropertyValue::new(*b.downcast::<Self::Item>().map_err(
                 |e| {
                     ElementsError::InvalidPropertyValue(format!(
@@ -261,34 +407,42 @@ pub trait Property: Default + Sized + Send + Sync + 'static {
         entity: Entity,
     );
 
+    /// Indicates if the property may be applied to the matched entities in
+    /// parallel. Return `false` if [`apply`](Property::apply) relies on the
+    /// order the entities are processed in.
+    fn parallel() -> bool {
+        true
+    }
+
     /// The [`system`](https://docs.rs/bevy_ecs/0.8.1/bevy_ecs/system/index.html) which interacts with
     /// [ecs world](`bevy::prelude::World`) and call [`apply`](Property::apply) function on every matched entity.
     ///
     /// The default implementation will cover most use cases, by just implementing [`apply`](Property::apply)
     fn apply_defaults(
-        // mut cached_properties: Local<CachedProperties<Self>>,
-        mut components: Query<(Entity, Self::Components), (Changed<Element>, Self::Filters)>,
-        mut commands: Commands,
+        mut components: Query<
+            (Entity, Self::Components),
+            (Or<(Changed<Element>, With<Restyle>)>, Self::Filters),
+        >,
+        commands: ParallelCommands,
         asset_server: Res<AssetServer>,
-        styles: Res<Styles>,
+        sorted_rules: Res<SortedRules>,
         stylesheets: Res<Assets<StyleSheet>>,
         parents: Query<&Parent>,
+        children: Query<&Children>,
         elements: Query<&Element>,
+        metrics: Res<UiMetrics>,
     ) {
         if components.is_empty() {
             return;
         }
-        // info!("[prop] changed {}", components.iter().count());
-        // TODO: this should be cached
-        let mut rules: Vec<_> = styles
-            .iter()
-            .filter_map(|h| stylesheets.get(h))
-            .flat_map(|s| s.iter())
-            .filter(|r| r.properties.contains_key(&Self::name()))
-            .collect();
-        rules.sort_by_key(|r| -r.selector.weight);
-
-        for (entity, components) in components.iter_mut() {
+        let started = Instant::now();
+        let mut matches = 0;
+        let rules = sorted_rules.get(Self::name(), &stylesheets);
+        let with_siblings = rules.iter().any(|r| r.selector.has_siblings());
+
+        // resolve the values first, the matching reads the whole tree
+        let mut values: HashMap<Entity, &Self::Item> = HashMap::default();
+        for (entity, _) in components.iter() {
             let Ok(element) = elements.get(entity) else {
                 continue;
             };
@@ -324,11 +478,18 @@ pub trait Property: Default + Sized + Send + Sync + 'static {
             let mut branch = ElementsBranch::new();
             let mut tail = entity;
             while let Ok(element) = elements.get(tail) {
+                let parent = parents.get(tail).map(|p| p.get()).ok();
                 if !element.is_virtual() {
-                    branch.insert(element);
+                    let siblings = match parent.and_then(|p| children.get(p).ok()) {
+                        Some(children) if with_siblings => {
+                            preceding_siblings(tail, children, |e| elements.get(e).ok())
+                        }
+                        _ => smallvec![],
+                    };
+                    branch.insert_with_siblings(element, siblings);
                 }
-                if let Ok(parent) = parents.get(tail) {
-                    tail = parent.get();
+                if let Some(parent) = parent {
+                    tail = parent;
                 } else {
                     break;
                 }
@@ -338,6 +499,7 @@ pub trait Property: Default + Sized + Send + Sync + 'static {
                     .iter()
                     .filter_map(|r| {
                         if let Some(depth) = r.selector.match_depth(&branch) {
+                            matches += 1;
                             Some((
                                 r.properties.get(&Self::name()).unwrap(),
                                 depth,
@@ -361,7 +523,7 @@ pub trait Property: Default + Sized + Send + Sync + 'static {
 
             if let Some(property) = property {
                 if let Some(property) = property.downcast_ref::<Self::Item>() {
-                    Self::apply(property, components, &asset_server, &mut commands, entity);
+                    values.insert(entity, property);
                 } else {
                     error!(
                         "Unable to apply {} property: inconsistent Variant {:?}",
@@ -371,6 +533,80 @@ pub trait Property: Default + Sized + Send + Sync + 'static {
                 }
             }
         }
+
+        if Self::parallel() {
+            components.par_iter_mut().for_each(|(entity, components)| {
+                let Some(value) = values.get(&entity) else {
+                    return;
+                };
+                commands.command_scope(|mut commands| {
+                    Self::apply(value, components, &asset_server, &mut commands, entity);
+                });
+            });
+        } else {
+            commands.command_scope(|mut commands| {
+                for (entity, components) in components.iter_mut() {
+                    if let Some(value) = values.get(&entity) {
+                        Self::apply(value, components, &asset_server, &mut commands, entity);
+                    }
+                }
+            });
+        }
+        metrics.add_selector_matches(matches);
+        metrics.add_apply_time(Self::name(), started.elapsed());
+    }
+}
+
+/// The rules of all the stylesheets having the property, sorted from the
+/// most to the least weighted selector. Shared by the property systems and
+/// rebuilt when the stylesheets change.
+#[derive(Resource, Default)]
+pub struct SortedRules {
+    rules: HashMap<Tag, Vec<(AssetId<StyleSheet>, usize)>>,
+    has_siblings: bool,
+}
+
+impl SortedRules {
+    /// Any of the rules uses the `+` combinator, so the elements are
+    /// restyled with their following siblings.
+    pub fn has_siblings(&self) -> bool {
+        self.has_siblings
+    }
+
+    pub fn get<'a>(
+        &self,
+        property: Tag,
+        stylesheets: &'a Assets<StyleSheet>,
+    ) -> Vec<&'a StyleRule> {
+        self.rules
+            .get(&property)
+            .into_iter()
+            .flatten()
+            .filter_map(|(id, index)| stylesheets.get(*id).and_then(|s| s.get(*index)))
+            .collect()
+    }
+}
+
+pub fn sort_rules_system(
+    mut sorted_rules: ResMut<SortedRules>,
+    styles: Res<Styles>,
+    stylesheets: Res<Assets<StyleSheet>>,
+) {
+    if !styles.is_changed() && !stylesheets.is_changed() {
+        return;
+    }
+    let mut rules: Vec<_> = styles
+        .iter()
+        .filter_map(|h| stylesheets.get(h).map(|s| (h.id(), s)))
+        .flat_map(|(id, s)| s.iter().enumerate().map(move |(idx, r)| (id, idx, r)))
+        .collect();
+    rules.sort_by_key(|(_, _, r)| -r.selector.weight);
+    sorted_rules.rules.clear();
+    sorted_rules.has_siblings = rules.iter().any(|(_, _, r)| r.selector.has_siblings());
+    for (id, idx, rule) in rules {
+        for property in rule.properties.keys() {
+            sorted_rules.rules.entry(*property).or_default().push((id, idx));
+        }
     }
 }
 
@@ -452,6 +688,16 @@ impl RegisterProperty for bevy::prelude::App {
             .entry(T::name())
             .and_modify(|_| panic!("Property `{}` already registered.", T::name()))
             .or_insert(T::transform);
+        register_property_metrics(self, T::name());
+        if !self.world.contains_resource::<SortedRules>() {
+            self.init_resource::<SortedRules>();
+            self.add_systems(
+                PostUpdate,
+                sort_rules_system
+                    .after(InvalidateElements)
+                    .before(ApplyStyleProperties),
+            );
+        }
         self.add_systems(
             PostUpdate,
             T::apply_defaults
@@ -579,4 +825,135 @@ mod test {
         let value = "21% 22px";
         assert_eq!(Ok(expected), value.try_into());
     }
+
+    #[test]
+    fn sort_rules_by_weight() {
+        use crate::ess::StyleSheetParser;
+        use crate::tag;
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut app = App::new();
+        app.add_plugins(PropertyPlugin);
+        let extractor = app.world.resource::<PropertyExtractor>().clone();
+        let transformer = app.world.resource::<PropertyTransformer>().clone();
+        let parser = StyleSheetParser::new(transformer, extractor);
+        let mut assets = Assets::<StyleSheet>::default();
+        let mut styles = Styles::default();
+        for source in [
+            ".a { background-color: red; } .a.b { background-color: blue; }",
+            ".a { background-color: green; width: 10px; }",
+        ] {
+            let mut sheet = StyleSheet::new(parser.parse(source));
+            let handle = assets.add(StyleSheet::default());
+            sheet.set_extra_weight(styles.insert(handle.clone()));
+            assets.insert(&handle, sheet);
+        }
+        app.world.insert_resource(assets);
+        app.world.insert_resource(styles);
+        app.world.run_system_once(sort_rules_system);
+
+        let sorted = app.world.resource::<SortedRules>();
+        let assets = app.world.resource::<Assets<StyleSheet>>();
+        let weights: Vec<_> = sorted
+            .get(tag!("background-color"), assets)
+            .iter()
+            .map(|r| (r.selector.weight.specificity(), r.selector.weight.sheet()))
+            .collect();
+        let (class, classes) = (weights[1].0, weights[0].0);
+        assert!(classes > class);
+        assert_eq!(weights, vec![(classes, 1), (class, 2), (class, 1)]);
+        assert_eq!(sorted.get(tag!("width"), assets).len(), 1);
+    }
+
+    #[test]
+    fn sibling_styles_follow_changes() {
+        use crate::element::{Elements, ElementsPlugin};
+        use crate::ess::StyleSheetParser;
+        use crate::tag;
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), ElementsPlugin));
+        app.init_asset::<StyleSheet>();
+        app.init_resource::<UiMetrics>();
+        app.init_resource::<PropertyExtractor>();
+        app.register_property::<impls::BackgroundColorProperty>();
+        let parser = StyleSheetParser::new(
+            app.world.resource::<PropertyTransformer>().clone(),
+            app.world.resource::<PropertyExtractor>().clone(),
+        );
+        let sheet = StyleSheet::new(parser.parse(
+            "button { background-color: blue; } span.active + button { background-color: red; }",
+        ));
+        let handle = app.world.resource_mut::<Assets<StyleSheet>>().add(sheet);
+        let mut styles = Styles::default();
+        styles.insert(handle);
+        app.world.insert_resource(styles);
+
+        let element = |app: &mut App, name| {
+            let element = Element {
+                names: smallvec![name],
+                ..default()
+            };
+            app.world.spawn((element, NodeBundle::default())).id()
+        };
+        let span = element(&mut app, tag!("span"));
+        let button = element(&mut app, tag!("button"));
+        let menu = element(&mut app, tag!("div"));
+        app.world.entity_mut(menu).push_children(&[span, button]);
+        app.update();
+        let color = |app: &App| app.world.get::<BackgroundColor>(button).unwrap().0;
+        assert_eq!(color(&app), Color::BLUE);
+
+        // the class of the preceding sibling restyles the button
+        app.world.run_system_once(move |mut elements: Elements| {
+            elements.add_class(span, tag!("active"));
+        });
+        app.update();
+        assert_eq!(color(&app), Color::RED);
+
+        // the button is not preceded by the span anymore
+        app.world.entity_mut(menu).insert_children(0, &[button]);
+        app.update();
+        assert_eq!(color(&app), Color::BLUE);
+    }
+
+    #[test]
+    fn inserted_children_restyle_following_siblings_only() {
+        use crate::element::invalidate_elements;
+
+        let mut world = World::new();
+        world.insert_resource(SortedRules {
+            has_siblings: true,
+            ..default()
+        });
+        let mut invalidate = IntoSystem::into_system(invalidate_elements);
+        invalidate.initialize(&mut world);
+        let mut run = |world: &mut World| {
+            let mut changed = world.query_filtered::<Entity, Changed<Element>>();
+            world.clear_trackers();
+            invalidate.run((), world);
+            invalidate.apply_deferred(world);
+            let mut changed: Vec<_> = changed.iter(world).collect();
+            changed.sort();
+            changed
+        };
+        let first = world.spawn(Element::default()).id();
+        let second = world.spawn(Element::default()).id();
+        let third = world.spawn(Element::default()).id();
+        let menu = world
+            .spawn(Element::default())
+            .push_children(&[first, second])
+            .id();
+        run(&mut world);
+
+        world.entity_mut(menu).add_child(third);
+        assert_eq!(run(&mut world), vec![third], "Appended child only");
+
+        world.entity_mut(menu).insert_children(1, &[third]);
+        assert_eq!(run(&mut world), vec![second, third], "Moved child and its followers");
+
+        world.entity_mut(first).remove_parent();
+        assert_eq!(run(&mut world), vec![second, third], "Followers of removed child");
+    }
 }
@@ -3,7 +3,9 @@ use std::{any::Any, marker::PhantomData};
 use crate::ElementsError;
 use bevy::prelude::*;
 
-use super::{colors, PropertyParser, StyleProperty, StylePropertyMethods, StylePropertyToken};
+use super::{
+    colors, PropertyParser, StylePropValue, StyleProperty, StylePropertyMethods, StylePropertyToken,
+};
 
 pub fn identifier<S>(prop: &StyleProperty) -> Result<S, ElementsError>
 where
@@ -57,30 +59,48 @@ impl PropertyParser<Val> for ValParser {
     }
 }
 
-pub fn overflow(prop: &StyleProperty) -> Result<Overflow, ElementsError> {
-    let Some(prop) = prop.first() else {
-        return Err(ElementsError::InvalidPropertyValue(format!(
-            "Expected $val, found nothing"
-        )));
-    };
-    match prop {
-        StylePropertyToken::Identifier(val) if val.as_str() == "visible" => Ok(Overflow::visible()),
-        StylePropertyToken::Identifier(val) if val.as_str() == "clip" => Ok(Overflow::clip()),
-        StylePropertyToken::Identifier(val) if val.as_str() == "clip_x" => Ok(Overflow::clip_x()),
-        StylePropertyToken::Identifier(val) if val.as_str() == "clip_y" => Ok(Overflow::clip_y()),
+fn overflow_axis(token: &StylePropertyToken) -> Result<OverflowAxis, ElementsError> {
+    match token {
+        StylePropertyToken::Identifier(val) if val.as_str() == "visible" => {
+            Ok(OverflowAxis::Visible)
+        }
+        StylePropertyToken::Identifier(val) if matches!(val.as_str(), "clip" | "hidden") => {
+            Ok(OverflowAxis::Clip)
+        }
         p => Err(ElementsError::InvalidPropertyValue(format!(
-            "Expected $overflow, got `{}`",
+            "Expected visible|clip|hidden, got `{}`",
             p.to_string()
         ))),
     }
 }
 
+pub fn overflow(prop: &StyleProperty) -> Result<Overflow, ElementsError> {
+    match prop.as_slice() {
+        [StylePropertyToken::Identifier(val)] if val.as_str() == "clip_x" => Ok(Overflow::clip_x()),
+        [StylePropertyToken::Identifier(val)] if val.as_str() == "clip_y" => Ok(Overflow::clip_y()),
+        [axis] => overflow_axis(axis).map(|axis| Overflow { x: axis, y: axis }),
+        [x, y] => Ok(Overflow {
+            x: overflow_axis(x)?,
+            y: overflow_axis(y)?,
+        }),
+        [] => Err(ElementsError::InvalidPropertyValue(format!(
+            "Expected $overflow, found nothing"
+        ))),
+        _ => Err(ElementsError::InvalidPropertyValue(format!(
+            "Expected $overflow, got `{}`",
+            prop.to_string()
+        ))),
+    }
+}
+
 /// <!-- @property-type=$overflow -->
-/// Size type representing `bevy::prelude::Overflow` type. Possible values:
-/// - `visible` for `Overflow::visible()`
-/// - `clip` for `Overflow::clip()`
-/// - `clip_x` for `Overflow::clip_x()`
-/// - `clip_y` for `Overflow::clip_y()`
+/// Describes the `bevy::prelude::Overflow` type: one value for both axes or
+/// two values for the horizontal and the vertical axis (`clip visible`).
+/// Possible values of the axis:
+/// - `visible` for `OverflowAxis::Visible`
+/// - `clip` (or `hidden`) for `OverflowAxis::Clip`
+///
+/// The `clip_x` and `clip_y` shorthands clip the single axis.
 pub struct OverflowParser;
 impl PropertyParser<Overflow> for OverflowParser {
     fn parse(value: &StyleProperty) -> Result<Overflow, ElementsError> {
@@ -275,3 +295,25 @@ impl PropertyParser<Option<f32>> for OptionalNumParser {
         optional_num(value)
     }
 }
+
+impl StylePropValue for Color {
+    type Parser = ColorParser;
+}
+impl StylePropValue for f32 {
+    type Parser = NumParser;
+}
+impl StylePropValue for Option<f32> {
+    type Parser = OptionalNumParser;
+}
+impl StylePropValue for String {
+    type Parser = StringParser;
+}
+impl StylePropValue for Option<String> {
+    type Parser = OptionalStringParser;
+}
+impl StylePropValue for Val {
+    type Parser = ValParser;
+}
+impl StylePropValue for UiRect {
+    type Parser = RectParser;
+}
@@ -118,6 +118,10 @@ impl StylePropertyToken {
         match self {
             StylePropertyToken::Percentage(p) => Ok(Val::Percent(p.to_float())),
             StylePropertyToken::Dimension(d, u) if u == "px" => Ok(Val::Px(d.to_float())),
+            StylePropertyToken::Dimension(d, u) if u == "vw" => Ok(Val::Vw(d.to_float())),
+            StylePropertyToken::Dimension(d, u) if u == "vh" => Ok(Val::Vh(d.to_float())),
+            StylePropertyToken::Dimension(d, u) if u == "vmin" => Ok(Val::VMin(d.to_float())),
+            StylePropertyToken::Dimension(d, u) if u == "vmax" => Ok(Val::VMax(d.to_float())),
             StylePropertyToken::Identifier(i) if i == "auto" => Ok(Val::Auto),
             StylePropertyToken::Identifier(i) if i == "undefined" => Ok(Val::Px(0.)),
             StylePropertyToken::Identifier(i) if i == "undefined" => Ok(Val::Px(0.)),
@@ -0,0 +1,356 @@
+use super::{
+    PropertyParser, PropertyValue, StyleProperty, StylePropertyMethods, StylePropertyToken,
+};
+use crate::{eml::Variant, ElementsError};
+use bevy::{prelude::*, utils::HashMap};
+use tagstr::Tag;
+
+/// The global UI scale the relative units (`rem`, `x`) are resolved with.
+/// Changing the resource re-applies all relative values on the next frame,
+/// the `px` and `%` values stay untouched.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct UiUnits {
+    /// The size of the `1rem` in logical pixels before scaling
+    pub rem: f32,
+    /// The multiplier applied to the `rem` and `x` units
+    pub scale: f32,
+}
+
+impl Default for UiUnits {
+    fn default() -> Self {
+        UiUnits { rem: 16., scale: 1. }
+    }
+}
+
+/// The length value of the style property. Unlike [`Val`] it may be
+/// relative to the [`UiUnits`], such lengths are resolved when applied.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Length {
+    Val(Val),
+    /// `1.5rem`: the number of `UiUnits.rem` scaled by `UiUnits.scale`
+    Rem(f32),
+    /// `2x`: the number of pixels scaled by `UiUnits.scale`
+    Scaled(f32),
+    /// `1.2em`: the number of the element font sizes
+    Em(f32),
+}
+
+impl Default for Length {
+    fn default() -> Self {
+        Length::Val(Val::default())
+    }
+}
+
+impl From<Val> for Length {
+    fn from(val: Val) -> Self {
+        Length::Val(val)
+    }
+}
+
+impl Length {
+    /// Returns `true` if the length doesn't depend on [`UiUnits`].
+    pub fn is_absolute(&self) -> bool {
+        matches!(self, Length::Val(_))
+    }
+
+    /// Returns `true` if the length depends on the element font size.
+    pub fn is_font_relative(&self) -> bool {
+        matches!(self, Length::Em(_))
+    }
+
+    /// Resolves the length with the global `units` and the element `font_size`.
+    pub fn resolve(&self, units: &UiUnits, font_size: f32) -> Val {
+        match self {
+            Length::Val(val) => *val,
+            Length::Rem(rem) => Val::Px(rem * units.rem * units.scale),
+            Length::Scaled(px) => Val::Px(px * units.scale),
+            Length::Em(em) => Val::Px(em * font_size),
+        }
+    }
+}
+
+impl TryFrom<&StylePropertyToken> for Length {
+    type Error = ElementsError;
+    fn try_from(token: &StylePropertyToken) -> Result<Self, Self::Error> {
+        match token {
+            StylePropertyToken::Dimension(val, unit) if unit == "rem" => {
+                Ok(Length::Rem(val.to_float()))
+            }
+            StylePropertyToken::Dimension(val, unit) if unit == "x" => {
+                Ok(Length::Scaled(val.to_float()))
+            }
+            StylePropertyToken::Dimension(val, unit) if unit == "em" => {
+                Ok(Length::Em(val.to_float()))
+            }
+            token => token.val().map(Length::Val),
+        }
+    }
+}
+
+pub fn length(prop: &StyleProperty) -> Result<Length, ElementsError> {
+    let Some(token) = prop.first() else {
+        return Err(ElementsError::InvalidPropertyValue(format!(
+            "Expected $length, found nothing"
+        )));
+    };
+    Length::try_from(token)
+}
+
+/// <!-- @property-type=$length -->
+/// The [`$val`](#$val) extended with the relative units:
+/// - `rem` suffixed for the root size multiplied by the UI scale (`1.5rem`)
+/// - `x` suffixed for the pixels multiplied by the UI scale (`2x`)
+/// - `em` suffixed for the font size of the element (`1.2em`)
+/// - `vw`, `vh`, `vmin`, `vmax` suffixed for the percent of the window
+///   width, height, smaller or larger side (`50vw`)
+pub struct LengthParser;
+impl PropertyParser<Length> for LengthParser {
+    fn parse(value: &StyleProperty) -> Result<Length, ElementsError> {
+        length(value)
+    }
+}
+
+/// The [`UiRect`] made of [`Length`] values, used by
+/// `margin`, `padding`, `border` and `position` properties.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct LengthRect {
+    pub left: Length,
+    pub right: Length,
+    pub top: Length,
+    pub bottom: Length,
+}
+
+impl LengthRect {
+    pub fn new(left: Length, right: Length, top: Length, bottom: Length) -> Self {
+        LengthRect {
+            left,
+            right,
+            top,
+            bottom,
+        }
+    }
+
+    pub fn all(value: Length) -> Self {
+        LengthRect::new(value, value, value, value)
+    }
+
+    pub fn to_rect_map(&self, prefix: &str) -> HashMap<Tag, PropertyValue> {
+        let mut props = HashMap::default();
+        for (edge, value) in [
+            ("left", self.left),
+            ("right", self.right),
+            ("top", self.top),
+            ("bottom", self.bottom),
+        ] {
+            props.insert(Tag::new(format!("{prefix}{edge}")), PropertyValue::new(value));
+        }
+        props
+    }
+}
+
+impl From<UiRect> for LengthRect {
+    fn from(rect: UiRect) -> Self {
+        LengthRect::new(
+            rect.left.into(),
+            rect.right.into(),
+            rect.top.into(),
+            rect.bottom.into(),
+        )
+    }
+}
+
+impl TryFrom<&StyleProperty> for LengthRect {
+    type Error = ElementsError;
+    fn try_from(prop: &StyleProperty) -> Result<Self, Self::Error> {
+        let length = |idx: usize| Length::try_from(&prop[idx]);
+        match prop.len() {
+            1 => length(0).map(LengthRect::all),
+            2 => {
+                let (top_bottom, left_right) = (length(0)?, length(1)?);
+                Ok(LengthRect::new(left_right, left_right, top_bottom, top_bottom))
+            }
+            3 => {
+                let (top, left_right, bottom) = (length(0)?, length(1)?, length(2)?);
+                Ok(LengthRect::new(left_right, left_right, top, bottom))
+            }
+            4 => Ok(LengthRect::new(length(3)?, length(1)?, length(0)?, length(2)?)),
+            _ => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected $rect, got `{}`",
+                prop.to_string()
+            ))),
+        }
+    }
+}
+
+impl TryFrom<Variant> for LengthRect {
+    type Error = ElementsError;
+    fn try_from(value: Variant) -> Result<Self, Self::Error> {
+        match value {
+            Variant::String(unparsed) => {
+                StyleProperty::try_from(unparsed).and_then(|prop| LengthRect::try_from(&prop))
+            }
+            Variant::Style(prop) => LengthRect::try_from(&prop),
+            variant => variant
+                .take::<UiRect>()
+                .map(LengthRect::from)
+                .ok_or(ElementsError::InvalidPropertyValue(format!(
+                    "Can't extract rect from variant"
+                ))),
+        }
+    }
+}
+
+type StyleField = fn(&mut Style) -> &mut Val;
+
+/// The relative lengths applied to the element `Style`, they are resolved
+/// again each time the [`UiUnits`] or the font size of the element changes.
+#[derive(Component, Default)]
+pub struct RelativeLengths(HashMap<Tag, (StyleField, Length)>);
+
+/// Writes the `value` of the property `name` to the `field` of the
+/// element style. The relative values are stored in [`RelativeLengths`]
+/// and written by [`resolve_relative_lengths_system`].
+pub fn apply_length(
+    value: &Length,
+    (mut style, relative): (Mut<Style>, Option<Mut<RelativeLengths>>),
+    commands: &mut Commands,
+    entity: Entity,
+    name: Tag,
+    field: StyleField,
+) {
+    if let Length::Val(val) = value {
+        if let Some(mut relative) = relative {
+            if relative.0.contains_key(&name) {
+                relative.0.remove(&name);
+            }
+        }
+        if field(style.bypass_change_detection()) != val {
+            *field(&mut style) = *val;
+        }
+        return;
+    }
+    let value = *value;
+    match relative {
+        Some(mut relative) => {
+            if relative.0.get(&name).map(|(_, v)| *v) != Some(value) {
+                relative.0.insert(name, (field, value));
+            }
+        }
+        None => commands.add(move |world: &mut World| {
+            let Some(mut entity) = world.get_entity_mut(entity) else {
+                return;
+            };
+            if let Some(mut relative) = entity.get_mut::<RelativeLengths>() {
+                relative.0.insert(name, (field, value));
+            } else {
+                let mut relative = RelativeLengths::default();
+                relative.0.insert(name, (field, value));
+                entity.insert(relative);
+            }
+        }),
+    }
+}
+
+/// The font size of the element: the size of its own text, the text of its
+/// children or the font size of the closest ancestor.
+fn resolved_font_size(
+    entity: Entity,
+    texts: &Query<&Text>,
+    children: &Query<&Children>,
+    parents: &Query<&Parent>,
+) -> Option<f32> {
+    let font_size = |entity| {
+        let text = texts.get(entity).ok()?;
+        text.sections.first().map(|s| s.style.font_size)
+    };
+    let mut tail = entity;
+    loop {
+        if let Some(size) = font_size(tail) {
+            return Some(size);
+        }
+        if let Some(size) = children
+            .get(tail)
+            .ok()
+            .and_then(|children| children.iter().find_map(|child| font_size(*child)))
+        {
+            return Some(size);
+        }
+        tail = parents.get(tail).ok()?.get();
+    }
+}
+
+pub fn resolve_relative_lengths_system(
+    units: Res<UiUnits>,
+    changed_texts: Query<(), Changed<Text>>,
+    texts: Query<&Text>,
+    children: Query<&Children>,
+    parents: Query<&Parent>,
+    mut elements: Query<(Entity, Ref<RelativeLengths>, &mut Style)>,
+) {
+    let texts_changed = !changed_texts.is_empty();
+    for (entity, relative, mut style) in elements.iter_mut() {
+        let font_relative = relative.0.values().any(|(_, l)| l.is_font_relative());
+        if !units.is_changed() && !relative.is_changed() && !(font_relative && texts_changed) {
+            continue;
+        }
+        let font_size = font_relative
+            .then(|| resolved_font_size(entity, &texts, &children, &parents))
+            .flatten()
+            .unwrap_or(units.rem * units.scale);
+        for (field, length) in relative.0.values() {
+            let val = length.resolve(&units, font_size);
+            if field(style.bypass_change_detection()) != &val {
+                *field(&mut style) = val;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tag;
+    use bevy::ecs::system::RunSystemOnce;
+    use std::str::FromStr;
+
+    #[test]
+    fn resolve_relative_lengths() {
+        let units = UiUnits { rem: 16., scale: 2. };
+        let length = |value: &str| {
+            let prop = StyleProperty::from_str(value).unwrap();
+            LengthParser::parse(&prop).unwrap()
+        };
+        assert_eq!(length("1.5rem").resolve(&units, 0.), Val::Px(48.));
+        assert_eq!(length("3x").resolve(&units, 0.), Val::Px(6.));
+        assert_eq!(length("0.5em").resolve(&units, 24.), Val::Px(12.));
+        assert!(length("1em").is_font_relative());
+        assert_eq!(length("50vw"), Length::Val(Val::Vw(50.)));
+        assert!(length("10px").is_absolute());
+        assert_eq!(length("10px").resolve(&units, 0.), Val::Px(10.));
+
+        let p = StyleProperty::from_str("1rem 25%").unwrap();
+        let rect = LengthRect::try_from(&p).unwrap();
+        assert_eq!(rect.top, Length::Rem(1.));
+        assert_eq!(rect.left, Length::Val(Val::Percent(25.)));
+    }
+
+    #[test]
+    fn em_follows_font_size() {
+        let mut world = World::new();
+        let text = world
+            .spawn(TextBundle::from_section(
+                "label",
+                TextStyle {
+                    font_size: 20.,
+                    ..default()
+                },
+            ))
+            .id();
+        let mut relative = RelativeLengths::default();
+        relative.0.insert(tag!("width"), (|s| &mut s.width, Length::Em(2.)));
+        let element = world.spawn((NodeBundle::default(), relative)).add_child(text).id();
+        world.insert_resource(UiUnits::default());
+        world.run_system_once(resolve_relative_lengths_system);
+        assert_eq!(world.get::<Style>(element).unwrap().width, Val::Px(40.));
+    }
+}
@@ -1,5 +1,8 @@
 use crate::element::Element;
+use crate::ElementsError;
+use crate::tags;
 use bevy::prelude::{default, Changed, Entity, Parent, Query};
+use bevy::utils::HashSet;
 use smallvec::{smallvec, SmallVec};
 use std::ops::Neg;
 use tagstr::Tag;
@@ -14,6 +17,14 @@ impl SelectorWeight {
     pub fn zero() -> SelectorWeight {
         SelectorWeight(0, 0)
     }
+    /// The weight of the selector elements (ids, classes, tags, states)
+    pub fn specificity(&self) -> i32 {
+        self.0
+    }
+    /// The weight of the stylesheet the selector belongs to
+    pub fn sheet(&self) -> i32 {
+        self.1
+    }
 }
 
 impl PartialOrd for SelectorWeight {
@@ -34,15 +45,106 @@ impl Neg for SelectorWeight {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum SelectorElement {
     AnyChild,
     DirectChild,
+    /// The `+` combinator: the element right after the matched one
+    AdjacentSibling,
     Any,
     Id(Tag),
     Class(Tag),
     Tag(Tag),
     State(Tag),
+    /// The named part of the widget, matches the elements
+    /// with the `part` param (stored as `::name` class).
+    Part(Tag),
+    /// The `[name]` or `[name=value]` param of the element
+    Attribute(Tag, Option<Tag>),
+    /// The `:not(...)` of the compound selector (like `button.primary`)
+    Not(Vec<SelectorElement>),
+}
+
+/// The class the element with `part="name"` param receives.
+pub fn part_class(name: &str) -> Tag {
+    Tag::new(&format!("::{name}"))
+}
+
+/// Parses the content of the `[name=value]` attribute selector, the value
+/// may be the identifier, the quoted string or the number.
+pub(crate) fn parse_attribute<'i, 't, E>(
+    input: &mut cssparser::Parser<'i, 't>,
+) -> Result<SelectorElement, cssparser::ParseError<'i, E>> {
+    let name = input.expect_ident_cloned()?;
+    if input.is_exhausted() {
+        return Ok(SelectorElement::Attribute(Tag::new(&*name), None));
+    }
+    input.expect_delim('=')?;
+    let value = match input.next()?.clone() {
+        cssparser::Token::Ident(value) | cssparser::Token::QuotedString(value) => {
+            value.to_string()
+        }
+        cssparser::Token::Number {
+            int_value: Some(value),
+            ..
+        } => value.to_string(),
+        cssparser::Token::Number { value, .. } => value.to_string(),
+        token => return Err(input.new_unexpected_token_error(token)),
+    };
+    input.expect_exhausted()?;
+    Ok(SelectorElement::Attribute(Tag::new(&*name), Some(Tag::new(&value))))
+}
+
+/// Parses the compound selector (no combinators) inside the `:not(...)`.
+pub(crate) fn parse_not<'i, 't, E>(
+    input: &mut cssparser::Parser<'i, 't>,
+) -> Result<SelectorElement, cssparser::ParseError<'i, E>> {
+    use cssparser::Token::*;
+    let mut elements = vec![];
+    let mut state = false;
+    let mut class = false;
+    while let Ok(token) = input.next_including_whitespace() {
+        let element = match token.clone() {
+            Ident(v) if state => SelectorElement::State(Tag::new(&*v)),
+            Ident(v) if class => SelectorElement::Class(Tag::new(&*v)),
+            Ident(v) => SelectorElement::Tag(Tag::new(&*v)),
+            IDHash(v) => SelectorElement::Id(Tag::new(&*v)),
+            Delim('*') => SelectorElement::Any,
+            Delim('.') => {
+                class = true;
+                continue;
+            }
+            Colon => {
+                state = true;
+                continue;
+            }
+            SquareBracketBlock => input.parse_nested_block(parse_attribute)?,
+            WhiteSpace(_) if elements.is_empty() => continue,
+            WhiteSpace(_) => {
+                input.skip_whitespace();
+                input.expect_exhausted()?;
+                break;
+            }
+            token => return Err(input.new_unexpected_token_error(token)),
+        };
+        (state, class) = (false, false);
+        elements.push(element);
+    }
+    if elements.is_empty() || state || class {
+        return Err(input.new_error_for_next_token());
+    }
+    Ok(SelectorElement::Not(elements))
+}
+
+/// Inserts the part selector: `slider::thumb` selects the `thumb`
+/// part at any depth inside the `slider`.
+pub(crate) fn insert_part(elements: &mut SelectorElements, name: &str) {
+    if let Some(element) = elements.first() {
+        if element.is_value() {
+            elements.insert(0, SelectorElement::AnyChild);
+        }
+    }
+    elements.insert(0, SelectorElement::Part(part_class(name)));
 }
 
 impl SelectorElement {
@@ -60,14 +162,31 @@ impl SelectorElement {
         }
     }
 
+    pub fn is_adjacent_sibling(&self) -> bool {
+        matches!(self, SelectorElement::AdjacentSibling)
+    }
+
     pub fn is_separator(&self) -> bool {
-        self.is_any_child() || self.is_direct_child()
+        self.is_any_child() || self.is_direct_child() || self.is_adjacent_sibling()
     }
 
     pub fn is_value(&self) -> bool {
         !self.is_separator()
     }
 
+    /// Collects the `[name]` attributes, including the `:not([name])` ones
+    pub fn collect_attributes(&self, names: &mut HashSet<Tag>) {
+        match self {
+            SelectorElement::Attribute(attr, _) => {
+                names.insert(*attr);
+            }
+            SelectorElement::Not(elements) => {
+                elements.iter().for_each(|e| e.collect_attributes(names));
+            }
+            _ => {}
+        }
+    }
+
     pub fn describes_node(&self, node: &impl EmlNode) -> bool {
         match self {
             SelectorElement::Any => true,
@@ -75,6 +194,9 @@ impl SelectorElement {
             SelectorElement::State(attr) => node.has_state(attr),
             SelectorElement::Tag(tag) => node.has_tag(tag),
             SelectorElement::Class(class) => node.has_class(class),
+            SelectorElement::Part(part) => node.has_class(part),
+            SelectorElement::Attribute(name, value) => node.has_attribute(name, value.as_ref()),
+            SelectorElement::Not(elements) => !elements.iter().all(|e| e.describes_node(node)),
             _ => false,
         }
     }
@@ -83,11 +205,19 @@ impl SelectorElement {
         match self {
             SelectorElement::AnyChild => " ".to_string(),
             SelectorElement::DirectChild => " > ".to_string(),
+            SelectorElement::AdjacentSibling => " + ".to_string(),
             SelectorElement::Any => "*".to_string(),
             SelectorElement::State(s) => format!(":{}", s),
             SelectorElement::Tag(t) => format!("{}", t),
             SelectorElement::Class(c) => format!(".{}", c),
             SelectorElement::Id(i) => format!("#{}", i),
+            SelectorElement::Part(p) => format!("{}", p),
+            SelectorElement::Attribute(n, None) => format!("[{}]", n),
+            SelectorElement::Attribute(n, Some(v)) => format!("[{}={}]", n, v),
+            SelectorElement::Not(elements) => {
+                let elements: String = elements.iter().map(|e| e.to_string()).collect();
+                format!(":not({elements})")
+            }
         }
     }
 
@@ -95,11 +225,16 @@ impl SelectorElement {
         match self {
             SelectorElement::AnyChild => 0,
             SelectorElement::DirectChild => 1,
+            SelectorElement::AdjacentSibling => 0,
             SelectorElement::Any => 0,
             SelectorElement::Tag(_) => 1,
             SelectorElement::State(_) => 10,
             SelectorElement::Class(_) => 10,
+            SelectorElement::Part(_) => 10,
+            SelectorElement::Attribute(..) => 10,
             SelectorElement::Id(_) => 100,
+            // the specificity of the negated selector, like css does
+            SelectorElement::Not(elements) => elements.iter().map(|e| e.weight()).sum(),
         }
     }
 }
@@ -122,7 +257,7 @@ impl<'a> SelectorEntry<'a> {
     fn next(&self) -> Option<SelectorEntry<'a>> {
         let mut offset = self.offset;
         let elements = self.elements;
-        if elements[offset].is_any_child() || elements[offset].is_direct_child() {
+        if elements[offset].is_separator() {
             offset += 1;
             if offset >= elements.len() {
                 return None;
@@ -145,7 +280,7 @@ impl<'a> SelectorEntry<'a> {
     pub fn len(&self) -> u8 {
         let mut len = 0;
         for element in self.elements.iter().skip(self.offset) {
-            if element.is_any_child() || element.is_direct_child() {
+            if element.is_separator() {
                 return len;
             } else {
                 len += 1;
@@ -162,6 +297,10 @@ impl<'a> SelectorEntry<'a> {
         self.elements[self.offset].is_direct_child()
     }
 
+    pub fn is_adjacent_sibling(&self) -> bool {
+        self.elements[self.offset].is_adjacent_sibling()
+    }
+
     pub fn is_separator(&self) -> bool {
         self.elements[self.offset].is_separator()
     }
@@ -173,8 +312,7 @@ impl<'a> SelectorEntry<'a> {
     pub fn has_id(&self, id: Tag) -> bool {
         for element in self.elements.iter().skip(self.offset) {
             match element {
-                SelectorElement::DirectChild => return false,
-                SelectorElement::AnyChild => return false,
+                element if element.is_separator() => return false,
                 SelectorElement::Id(element_id) if id == *element_id => return true,
                 _ => continue,
             }
@@ -185,8 +323,7 @@ impl<'a> SelectorEntry<'a> {
     pub fn get_id(&self) -> Option<Tag> {
         for element in self.elements.iter().skip(self.offset) {
             match element {
-                SelectorElement::DirectChild => return None,
-                SelectorElement::AnyChild => return None,
+                element if element.is_separator() => return None,
                 SelectorElement::Id(id) => return Some(*id),
                 _ => continue,
             }
@@ -197,7 +334,7 @@ impl<'a> SelectorEntry<'a> {
     pub fn has_class(&self, class: Tag) -> bool {
         for element in self.elements.iter().skip(self.offset) {
             match element {
-                SelectorElement::AnyChild => return false,
+                element if element.is_separator() => return false,
                 SelectorElement::Class(element_class) if class == *element_class => return true,
                 _ => continue,
             }
@@ -208,7 +345,7 @@ impl<'a> SelectorEntry<'a> {
     pub fn has_tag(&self, tag: Tag) -> bool {
         for element in self.elements.iter().skip(self.offset) {
             match element {
-                SelectorElement::AnyChild => return false,
+                element if element.is_separator() => return false,
                 SelectorElement::Tag(element_tag) if tag == *element_tag => return true,
                 _ => continue,
             }
@@ -219,7 +356,7 @@ impl<'a> SelectorEntry<'a> {
     pub fn describes_node(&self, node: &impl EmlNode) -> bool {
         let mut offset = self.offset;
         let elements = self.elements;
-        if elements[offset].is_any_child() || elements[offset].is_direct_child() {
+        if elements[offset].is_separator() {
             return false;
         }
         while offset < elements.len() && elements[offset].is_value() {
@@ -233,7 +370,7 @@ impl<'a> SelectorEntry<'a> {
     }
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
 pub struct Selector {
     pub weight: SelectorWeight,
     pub elements: SelectorElements,
@@ -260,6 +397,13 @@ impl Selector {
         }
     }
 
+    /// Collects the `[name]` attributes of all the elements
+    pub fn collect_attributes(&self, names: &mut HashSet<Tag>) {
+        self.elements
+            .iter()
+            .for_each(|e| e.collect_attributes(names));
+    }
+
     pub fn entries(&self) -> SmallVec<[SelectorEntry; 8]> {
         let mut entries = smallvec![];
         let mut tail = Some(self.tail());
@@ -292,10 +436,25 @@ impl Selector {
 
     pub fn get_root_id(&self) -> Option<Tag> {
         let mut entry = self.tail();
+        let mut sibling = false;
         while let Some(next) = entry.next() {
+            if next.is_separator() {
+                sibling = next.is_adjacent_sibling();
+            }
             entry = next;
         }
-        entry.get_id()
+        // the leftmost element of `#a + b` is not the root of the branch
+        if sibling {
+            None
+        } else {
+            entry.get_id()
+        }
+    }
+
+    /// Checks the selector has the `+` combinator, the siblings of the
+    /// branch elements are required to match it.
+    pub fn has_siblings(&self) -> bool {
+        self.elements.iter().any(|e| e.is_adjacent_sibling())
     }
 
     pub fn overridable_by_props(&self) -> bool {
@@ -313,11 +472,23 @@ pub trait EmlNode: Sized {
     fn has_tag(&self, tag: &Tag) -> bool;
     fn has_state(&self, tag: &Tag) -> bool;
     fn has_class(&self, class: &Tag) -> bool;
+    /// Checks the element has the param, and its value is `value`
+    /// when provided.
+    fn has_attribute(&self, name: &Tag, value: Option<&Tag>) -> bool;
 
     fn next(&self) -> Option<Self>;
 
+    /// The sibling right before the node, the branches without
+    /// the siblings never match the `+` combinator.
+    fn prev_sibling(&self) -> Option<Self> {
+        None
+    }
+
     fn fits(&self, selector: &SelectorEntry) -> Option<u8> {
-        if selector.is_direct_child() {
+        if selector.is_adjacent_sibling() {
+            let next_selector = selector.next()?;
+            self.prev_sibling()?.fits(&next_selector)
+        } else if selector.is_direct_child() {
             let Some(next_selector) = selector.next() else {
                 return None;
             };
@@ -345,8 +516,9 @@ pub trait EmlNode: Sized {
             }
         } else if selector.describes_node(self) {
             match (self.next(), selector.next()) {
-                (None, None) => Some(1),
-                (Some(_node), None) => Some(1),
+                (_, None) => Some(1),
+                // the sibling shares the parent, stay on the node
+                (_, Some(next_slice)) if next_slice.is_adjacent_sibling() => self.fits(&next_slice),
                 (None, Some(_slice)) => None,
                 (Some(next_node), Some(next_slice)) => {
                     if let Some(weight) = next_node.fits(&next_slice) {
@@ -363,7 +535,27 @@ pub trait EmlNode: Sized {
 }
 
 #[derive(Default)]
-pub struct ElementsBranch<'e>(SmallVec<[&'e Element; 12]>);
+pub struct ElementsBranch<'e>(SmallVec<[&'e Element; 12]>, SmallVec<[Siblings<'e>; 12]>);
+
+/// The element siblings preceding the branch element, the nearest first.
+pub type Siblings<'e> = SmallVec<[&'e Element; 4]>;
+
+/// Collects the elements preceding the `entity` in the `children` of its
+/// parent, the virtual elements (texts) are skipped.
+pub fn preceding_siblings<'e>(
+    entity: Entity,
+    children: &[Entity],
+    element: impl Fn(Entity) -> Option<&'e Element>,
+) -> Siblings<'e> {
+    let mut siblings: Siblings = children
+        .iter()
+        .take_while(|child| **child != entity)
+        .filter_map(|child| element(*child))
+        .filter(|element| !element.is_virtual())
+        .collect();
+    siblings.reverse();
+    siblings
+}
 
 impl<'e> ElementsBranch<'e> {
     pub fn new() -> ElementsBranch<'e> {
@@ -371,15 +563,30 @@ impl<'e> ElementsBranch<'e> {
     }
 
     pub fn insert(&mut self, element: &'e Element) {
+        self.insert_with_siblings(element, smallvec![]);
+    }
+
+    /// Inserts the element (from the tail to the root) with the siblings
+    /// preceding it, they are required to match the `+` combinator.
+    pub fn insert_with_siblings(&mut self, element: &'e Element, siblings: Siblings<'e>) {
         self.0.push(element);
+        self.1.push(siblings);
     }
 
     pub fn append(&mut self, element: &'e Element) {
+        self.append_with_siblings(element, smallvec![]);
+    }
+
+    /// Inserts the element (from the root to the tail) with the siblings
+    /// preceding it.
+    pub fn append_with_siblings(&mut self, element: &'e Element, siblings: Siblings<'e>) {
         self.0.insert(0, element);
+        self.1.insert(0, siblings);
     }
 
     pub fn pop_tail(&mut self) {
         self.0.pop();
+        self.1.pop();
     }
 
     pub fn to_string(&self) -> String {
@@ -409,29 +616,55 @@ impl<'e> ElementsBranch<'e> {
 }
 pub struct ElementNode<'b, 'e> {
     idx: usize,
+    /// The offset of the preceding sibling, 0 for the branch element itself
+    sibling: usize,
     branch: &'b ElementsBranch<'e>,
 }
 
+impl<'b, 'e> ElementNode<'b, 'e> {
+    fn element(&self) -> &'e Element {
+        if self.sibling == 0 {
+            self.branch.0[self.idx]
+        } else {
+            self.branch.1[self.idx][self.sibling - 1]
+        }
+    }
+}
+
 impl<'b, 'e> EmlNode for ElementNode<'b, 'e> {
     fn id(&self) -> Option<Tag> {
-        self.branch.0[self.idx].id
+        self.element().id
     }
     fn has_tag(&self, tag: &Tag) -> bool {
-        self.branch.0[self.idx]
+        let element = self.element();
+        element
             .names
             .iter()
-            .chain(self.branch.0[self.idx].aliases.iter())
+            .chain(element.aliases.iter())
             .filter(|t| *t == tag)
             .next()
             .is_some()
     }
 
     fn has_class(&self, class: &Tag) -> bool {
-        self.branch.0[self.idx].classes.contains(class)
+        self.element().classes.contains(class)
+    }
+
+    fn has_attribute(&self, name: &Tag, value: Option<&Tag>) -> bool {
+        match (self.element().attributes.get(name), value) {
+            (Some(attribute), Some(value)) => attribute == value.as_str(),
+            (attribute, _) => attribute.is_some(),
+        }
     }
 
     fn has_state(&self, tag: &Tag) -> bool {
-        self.branch.0[self.idx].state.contains(tag)
+        let state = &self.element().state;
+        if *tag == tags::enabled() {
+            // there is no explicit enabled state, it is the absence of disabled
+            !state.contains(&tags::disabled())
+        } else {
+            state.contains(tag)
+        }
     }
 
     fn next(&self) -> Option<Self> {
@@ -440,7 +673,24 @@ impl<'b, 'e> EmlNode for ElementNode<'b, 'e> {
         if idx >= branch.0.len() {
             None
         } else {
-            Some(ElementNode { idx, branch })
+            Some(ElementNode {
+                idx,
+                sibling: 0,
+                branch,
+            })
+        }
+    }
+
+    fn prev_sibling(&self) -> Option<Self> {
+        let sibling = self.sibling + 1;
+        if sibling > self.branch.1.get(self.idx).map_or(0, |s| s.len()) {
+            None
+        } else {
+            Some(ElementNode {
+                idx: self.idx,
+                sibling,
+                branch: self.branch,
+            })
         }
     }
 }
@@ -451,6 +701,7 @@ impl<'b, 'e> EmlBranch for &'b ElementsBranch<'e> {
     fn tail(&self) -> Self::Node {
         ElementNode {
             idx: 0,
+            sibling: 0,
             branch: *self,
         }
     }
@@ -473,7 +724,7 @@ fn _example(
                 break;
             }
         }
-        let branch = ElementsBranch(branch);
+        let branch = ElementsBranch(branch, smallvec![]);
 
         // can now find all matching rules
         let selector: Selector = "div span".into();
@@ -484,12 +735,22 @@ fn _example(
 }
 
 impl From<&str> for Selector {
+    /// Panics on the malformed `source`, use [`Selector::parse`]
+    /// for the selectors coming from the user input.
     fn from(source: &str) -> Self {
+        Selector::parse(source).unwrap_or_else(|e| panic!("Invalid selector `{source}`: {e}"))
+    }
+}
+
+impl Selector {
+    /// Parses the `ess` selector, like `.inventory > button:hover`.
+    pub fn parse(source: &str) -> Result<Selector, ElementsError> {
         use cssparser::{Parser, ParserInput};
         use tagstr::*;
         const NEXT_TAG: u8 = 0;
         const NEXT_CLASS: u8 = 1;
         const NEXT_ATTR: u8 = 2;
+        const NEXT_PART: u8 = 3;
         let mut selector = Selector::default();
         // selector.elements.push(SelectorElement::AnyChild);
         let mut input = ParserInput::new(source);
@@ -509,51 +770,79 @@ impl From<&str> for Selector {
                         NEXT_ATTR => selector
                             .elements
                             .insert(0, SelectorElement::State(v.to_string().as_tag())),
-                        _ => panic!("Invalid NEXT_TAG"),
+                        NEXT_PART => insert_part(&mut selector.elements, v),
+                        _ => unreachable!("Invalid NEXT_TAG"),
                     };
                     next = NEXT_TAG;
                 }
                 IDHash(v) => {
                     if v.is_empty() {
-                        panic!("Invalid #id selector");
-                    } else {
-                        selector
-                            .elements
-                            .insert(0, SelectorElement::Id(v.to_string().as_tag()));
+                        return Err(ElementsError::InvalidSelector);
                     }
+                    selector
+                        .elements
+                        .insert(0, SelectorElement::Id(v.to_string().as_tag()));
                 }
                 WhiteSpace(_) => {
                     if let Some(token) = selector.elements.first() {
-                        if token.is_direct_child() || token.is_any_child() {
+                        if token.is_separator() {
                             continue;
                         }
                     }
                     selector.elements.insert(0, SelectorElement::AnyChild);
                 }
+                Colon if next == NEXT_ATTR => next = NEXT_PART,
                 Colon => next = NEXT_ATTR,
+                Function(name) if next == NEXT_ATTR && name.as_ref() == "not" => {
+                    let not = parser
+                        .parse_nested_block(parse_not::<()>)
+                        .map_err(|_| ElementsError::InvalidSelector)?;
+                    selector.elements.insert(0, not);
+                    next = NEXT_TAG;
+                }
+                Function(name) if next == NEXT_PART && name.as_ref() == "part" => {
+                    let part = parser
+                        .parse_nested_block(|p| {
+                            p.expect_ident_cloned()
+                                .map_err(cssparser::ParseError::<()>::from)
+                        })
+                        .map_err(|_| ElementsError::InvalidSelector)?;
+                    insert_part(&mut selector.elements, &part);
+                    next = NEXT_TAG;
+                }
+                SquareBracketBlock => {
+                    let attribute = parser
+                        .parse_nested_block(parse_attribute::<()>)
+                        .map_err(|_| ElementsError::InvalidSelector)?;
+                    selector.elements.insert(0, attribute);
+                }
                 Delim(c) if *c == '.' => next = NEXT_CLASS,
                 Delim(c) if *c == '*' => selector.elements.insert(0, SelectorElement::Any),
-                Delim(c) if *c == '>' => {
+                Delim(c) if *c == '>' || *c == '+' => {
+                    let combinator = match *c {
+                        '>' => SelectorElement::DirectChild,
+                        _ => SelectorElement::AdjacentSibling,
+                    };
                     if let Some(token) = selector.elements.first() {
                         if token.is_any_child() {
-                            selector.elements[0] = SelectorElement::DirectChild;
+                            selector.elements[0] = combinator;
                             continue;
                         }
                     }
-                    selector.elements.insert(0, SelectorElement::DirectChild);
+                    selector.elements.insert(0, combinator);
                 }
-                _ => panic!("Unexpected token: {token:?}"),
+                _ => return Err(ElementsError::UnexpectedToken(format!("{token:?}"))),
             }
         }
 
-        selector
+        Ok(Selector::new(selector.elements))
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use bevy::utils::HashSet;
+    use bevy::utils::{HashMap, HashSet};
     use tagstr::*;
 
     struct TestBranch(Vec<TestNodeData>);
@@ -575,6 +864,7 @@ mod test {
         tag: Tag,
         classes: HashSet<Tag>,
         params: HashSet<Tag>,
+        attributes: HashMap<Tag, Option<Tag>>,
     }
 
     struct TestNode<'a> {
@@ -595,6 +885,12 @@ mod test {
         fn has_class(&self, class: &Tag) -> bool {
             self.branch.0[self.index].classes.contains(class)
         }
+        fn has_attribute(&self, name: &Tag, value: Option<&Tag>) -> bool {
+            match (self.branch.0[self.index].attributes.get(name), value) {
+                (Some(attribute), Some(_)) => attribute.as_ref() == value,
+                (attribute, _) => attribute.is_some(),
+            }
+        }
         fn next(&self) -> Option<Self> {
             let index = self.index + 1;
             if index >= self.branch.0.len() {
@@ -616,7 +912,10 @@ mod test {
             let void = |_| ();
             for element in selector.elements {
                 match element {
-                    SelectorElement::Any | SelectorElement::DirectChild => {
+                    SelectorElement::Any
+                    | SelectorElement::DirectChild
+                    | SelectorElement::AdjacentSibling
+                    | SelectorElement::Not(_) => {
                         continue;
                     }
                     SelectorElement::AnyChild => {
@@ -629,6 +928,10 @@ mod test {
                     }
                     SelectorElement::State(attr) => void(node.params.insert(attr)),
                     SelectorElement::Class(class) => void(node.classes.insert(class)),
+                    SelectorElement::Part(part) => void(node.classes.insert(part)),
+                    SelectorElement::Attribute(name, value) => {
+                        node.attributes.insert(name, value);
+                    }
                     SelectorElement::Id(id) => node.id = Some(id),
                     SelectorElement::Tag(tag) => node.tag = tag,
                 };
@@ -771,4 +1074,81 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn selector_parts() {
+        let mut branch: TestBranch = "slider span.track".into();
+        let mut thumb = TestNodeData::default();
+        thumb.tag = "span".as_tag();
+        thumb.classes.insert(part_class("thumb"));
+        branch.0.insert(0, thumb);
+        for src in ["slider::thumb", "slider::part(thumb)", "::thumb", ".track > ::thumb"] {
+            let selector: Selector = src.into();
+            assert!(selector.matches(&branch), "Selector '{}' should be matched", src);
+        }
+        for src in ["slider::track", "button::thumb", ".thumb"] {
+            let selector: Selector = src.into();
+            assert!(!selector.matches(&branch), "Selector '{}' shouldn't be matched", src);
+        }
+    }
+
+    #[test]
+    fn selector_attributes() {
+        let branch: TestBranch = "div slider[mode=vertical][step=0.5]".into();
+        let valid_selectors = [
+            "slider[mode=vertical]",
+            "[mode]",
+            "slider[mode=\"vertical\"]",
+            "div [step=0.5]",
+        ];
+        for src in valid_selectors {
+            let selector: Selector = src.into();
+            assert!(selector.matches(&branch), "Selector '{}' should be matched", src);
+        }
+        for src in ["slider[mode=horizontal]", "[orientation]", "div[mode]"] {
+            let selector: Selector = src.into();
+            assert!(!selector.matches(&branch), "Selector '{}' shouldn't be matched", src);
+        }
+        let selector: Selector = "slider[mode=vertical]".into();
+        assert_eq!(selector.weight.specificity(), 11);
+    }
+
+    #[test]
+    fn selector_not() {
+        let branch: TestBranch = "div.menu span.red:hover".into();
+        let valid_selectors = [
+            "span:not(.green)",
+            ":not(div)",
+            ".menu > :not(.green):not(#id)",
+            "span:not(:pressed.green)",
+            "div:not([mode]) span",
+        ];
+        for src in valid_selectors {
+            let selector: Selector = src.into();
+            assert!(selector.matches(&branch), "Selector '{}' should be matched", src);
+        }
+        for src in ["span:not(.red)", ":not(span.red)", ":not(.menu) span", "div:not(*) span"] {
+            let selector: Selector = src.into();
+            assert!(!selector.matches(&branch), "Selector '{}' shouldn't be matched", src);
+        }
+        let selector: Selector = "span:not(.green)".into();
+        assert_eq!(selector.weight.specificity(), 11);
+        assert_eq!(selector.to_string(), "span:not(.green)");
+    }
+
+    #[test]
+    fn selector_parse_errors() {
+        for src in ["span:not()", "span:not(.)", "slider::part(1)", "[=vertical]", "[mode=]"] {
+            assert!(
+                matches!(Selector::parse(src), Err(ElementsError::InvalidSelector)),
+                "Selector '{}' should be rejected",
+                src
+            );
+        }
+        assert!(matches!(
+            Selector::parse("div { }"),
+            Err(ElementsError::UnexpectedToken(_))
+        ));
+        assert!(Selector::parse("div:not(.red) > slider::part(thumb)").is_ok());
+    }
 }
@@ -0,0 +1,256 @@
+use super::{
+    PropertyExtractor, PropertyTransformer, StyleProperty, StylePropertyFunction, StyleSheet,
+    StylePropertyToken,
+};
+use crate::{element::Elements, eml::Variant, ElementsError};
+use bevy::{prelude::*, utils::HashMap};
+use smallvec::SmallVec;
+use tagstr::Tag;
+
+/// The named set of semantic tokens (`surface`, `accent`, `text-muted`)
+/// the stylesheets refer to with `theme(accent)`:
+/// ```rust
+/// # use belly_core::build::*;
+/// let dark = Theme::new()
+///     .with("surface", "#212121")
+///     .with("accent", "#4caf50")
+///     .with("text-muted", "#9e9e9e");
+/// ```
+#[derive(Default, Debug, Clone)]
+pub struct Theme(HashMap<Tag, StyleProperty>);
+
+impl Theme {
+    pub fn new() -> Theme {
+        Theme::default()
+    }
+
+    /// Adds the `token` with the `value` parsed as the style property.
+    /// Invalid values are reported and ignored.
+    pub fn with(mut self, token: impl Into<Tag>, value: &str) -> Theme {
+        let token = token.into();
+        match StyleProperty::try_from(value) {
+            Ok(value) => {
+                self.0.insert(token, value);
+            }
+            Err(e) => error!("Ignoring theme token `{token}`: {e}"),
+        }
+        self
+    }
+
+    pub fn get(&self, token: Tag) -> Option<&StyleProperty> {
+        self.0.get(&token)
+    }
+}
+
+/// The registered themes and the active one. Switching the active theme
+/// re-resolves the `theme()` values of all stylesheets and restyles the UI:
+/// ```rust
+/// # use belly_core::build::*;
+/// # use bevy::prelude::*;
+/// fn toggle_theme(mut themes: ResMut<Themes>) {
+///     let next = if themes.active().as_str() == "dark" { "light" } else { "dark" };
+///     themes.set_active(next);
+/// }
+/// ```
+#[derive(Resource, Default, Debug)]
+pub struct Themes {
+    themes: HashMap<Tag, Theme>,
+    active: Tag,
+    generation: usize,
+}
+
+impl Themes {
+    pub fn insert(&mut self, name: impl Into<Tag>, theme: Theme) {
+        let name = name.into();
+        if self.themes.is_empty() {
+            self.active = name;
+        }
+        self.themes.insert(name, theme);
+        self.generation += 1;
+    }
+
+    pub fn get(&self, name: impl Into<Tag>) -> Option<&Theme> {
+        self.themes.get(&name.into())
+    }
+
+    pub fn active(&self) -> Tag {
+        self.active
+    }
+
+    pub fn set_active(&mut self, name: impl Into<Tag>) {
+        let name = name.into();
+        if !self.themes.contains_key(&name) {
+            warn!("Activating unregistered theme `{name}`");
+        }
+        if self.active != name {
+            self.active = name;
+            self.generation += 1;
+        }
+    }
+
+    /// Replaces `theme(token)` and `theme(token, fallback)` functions in the
+    /// `property` with the values from the active theme.
+    pub fn resolve(&self, property: &StyleProperty) -> Result<StyleProperty, ElementsError> {
+        let mut tokens = SmallVec::new();
+        for token in property.iter() {
+            self.substitute(token, &mut tokens)?;
+        }
+        Ok(StyleProperty(tokens))
+    }
+
+    fn substitute(
+        &self,
+        token: &StylePropertyToken,
+        output: &mut SmallVec<[StylePropertyToken; 8]>,
+    ) -> Result<(), ElementsError> {
+        match token {
+            StylePropertyToken::Function(func) if func.name == "theme" => {
+                let token = match func.args.first() {
+                    Some(StylePropertyToken::Identifier(token)) => Tag::new(token),
+                    _ => {
+                        return Err(ElementsError::InvalidPropertyValue(format!(
+                            "Expected theme($token) or theme($token, $fallback), got `{}`",
+                            token.to_string()
+                        )))
+                    }
+                };
+                let value = self.themes.get(&self.active).and_then(|t| t.get(token));
+                match (value, func.args.get(1)) {
+                    (Some(value), _) => output.extend(value.iter().cloned()),
+                    (None, Some(StylePropertyToken::Tokens(fallback))) => {
+                        output.extend(fallback.iter().cloned())
+                    }
+                    (None, Some(fallback)) => output.push(fallback.clone()),
+                    (None, None) => {
+                        return Err(ElementsError::InvalidPropertyValue(format!(
+                            "Theme `{}` has no `{token}` token",
+                            self.active
+                        )))
+                    }
+                }
+            }
+            StylePropertyToken::Function(func) => {
+                let mut args = vec![];
+                for arg in func.args.iter() {
+                    let mut resolved = SmallVec::new();
+                    self.substitute(arg, &mut resolved)?;
+                    if resolved.len() == 1 {
+                        args.push(resolved.pop().unwrap());
+                    } else {
+                        args.push(StylePropertyToken::Tokens(resolved.into_vec()));
+                    }
+                }
+                output.push(StylePropertyToken::Function(StylePropertyFunction {
+                    name: func.name.clone(),
+                    args,
+                }));
+            }
+            StylePropertyToken::Tokens(tokens) => {
+                let mut resolved = SmallVec::new();
+                for token in tokens.iter() {
+                    self.substitute(token, &mut resolved)?;
+                }
+                output.push(StylePropertyToken::Tokens(resolved.into_vec()));
+            }
+            token => output.push(token.clone()),
+        }
+        Ok(())
+    }
+}
+
+/// Returns `true` if the `property` refers to the theme tokens.
+pub fn is_themed(property: &StyleProperty) -> bool {
+    fn themed(token: &StylePropertyToken) -> bool {
+        match token {
+            StylePropertyToken::Function(func) => {
+                func.name == "theme" || func.args.iter().any(themed)
+            }
+            StylePropertyToken::Tokens(tokens) => tokens.iter().any(themed),
+            _ => false,
+        }
+    }
+    property.iter().any(themed)
+}
+
+pub trait RegisterTheme {
+    fn register_theme(&mut self, name: impl Into<Tag>, theme: Theme) -> &mut Self;
+}
+
+impl RegisterTheme for App {
+    fn register_theme(&mut self, name: impl Into<Tag>, theme: Theme) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(Themes::default)
+            .insert(name, theme);
+        self
+    }
+}
+
+pub(crate) fn resolve_themes_system(
+    themes: Res<Themes>,
+    extractor: Res<PropertyExtractor>,
+    transformer: Res<PropertyTransformer>,
+    mut stylesheets: ResMut<Assets<StyleSheet>>,
+    mut elements: Elements,
+) {
+    let stale: Vec<_> = stylesheets
+        .iter()
+        .filter(|(_, sheet)| {
+            sheet.is_themed() && sheet.theme_generation != Some(themes.generation)
+        })
+        .map(|(id, _)| id)
+        .collect();
+    if stale.is_empty() {
+        return;
+    }
+    for id in stale {
+        let sheet = stylesheets.get_mut(id).unwrap();
+        sheet.theme_generation = Some(themes.generation);
+        for rule in sheet.rules.iter_mut() {
+            for (name, raw) in rule.themed.iter() {
+                let resolved = themes.resolve(raw).and_then(|value| {
+                    if extractor.is_compound_property(*name) {
+                        extractor.extract(*name, Variant::style(value))
+                    } else {
+                        transformer
+                            .transform(*name, Variant::style(value))
+                            .map(|value| [(*name, value)].into_iter().collect())
+                    }
+                });
+                match resolved {
+                    Ok(properties) => rule.properties.extend(properties),
+                    Err(e) => error!("Unable to resolve `{name}` with theme: {e}"),
+                }
+            }
+        }
+    }
+    elements.invalidate_all();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn resolve_theme_tokens() {
+        let mut themes = Themes::default();
+        themes.insert("dark", Theme::new().with("accent", "#4caf50"));
+        themes.insert("light", Theme::new().with("accent", "#2196f3"));
+        assert_eq!(themes.active(), Tag::new("dark"));
+
+        let prop = StyleProperty::from_str("1px theme(accent)").unwrap();
+        assert!(is_themed(&prop));
+        let expected = StyleProperty::from_str("1px #4caf50").unwrap();
+        assert_eq!(themes.resolve(&prop).unwrap(), expected);
+
+        themes.set_active("light");
+        let expected = StyleProperty::from_str("1px #2196f3").unwrap();
+        assert_eq!(themes.resolve(&prop).unwrap(), expected);
+
+        let prop = StyleProperty::from_str("theme(surface, white)").unwrap();
+        let expected = StyleProperty::from_str("white").unwrap();
+        assert_eq!(themes.resolve(&prop).unwrap(), expected);
+        let prop = StyleProperty::from_str("theme(surface)").unwrap();
+        assert!(themes.resolve(&prop).is_err());
+    }
+}
@@ -1,4 +1,10 @@
-use crate::{element::Element, element::Elements, tags};
+use crate::{
+    element::Disabled,
+    element::Element,
+    element::Elements,
+    ess::impls::opacity::{accepts_pointer, ComputedOpacity, PointerEvents},
+    tags,
+};
 use bevy::{
     ecs::query::QueryData,
     prelude::*,
@@ -17,6 +23,7 @@ impl Plugin for ElementsInputPlugin {
             .add_systems(
                 PreUpdate,
                 (
+                    cascade_disabled_system,
                     pointer_input_system,
                     (
                         (hover_system, active_system),
@@ -179,6 +186,9 @@ pub struct NodeQuery {
     focus_policy: Option<&'static FocusPolicy>,
     calculated_clip: Option<&'static CalculatedClip>,
     view_visibility: Option<&'static ViewVisibility>,
+    pointer_events: Option<&'static PointerEvents>,
+    opacity: Option<&'static ComputedOpacity>,
+    element: Option<&'static Element>,
 }
 
 // pointer_input_system is the rewriten bevy's ui_focus_system
@@ -246,6 +256,11 @@ pub fn pointer_input_system(
                         return None;
                     }
                 }
+                // Transparent nodes and nodes with `pointer-events: none`
+                // should pass the input to the nodes below
+                if !accepts_pointer(node.pointer_events, node.opacity) {
+                    return None;
+                }
 
                 let position = node.global_transform.translation();
                 let ui_position = position.truncate();
@@ -301,6 +316,13 @@ pub fn pointer_input_system(
         if node.focus_policy.is_none() {
             continue;
         }
+        // disabled elements swallow the input without emitting it
+        if node.element.map(|e| e.disabled()).unwrap_or(false) {
+            match node.focus_policy.unwrap() {
+                FocusPolicy::Block => break,
+                FocusPolicy::Pass => continue,
+            }
+        }
         let entity = node.entity;
 
         if down {
@@ -417,23 +439,49 @@ pub fn pointer_input_system(
 // pub struct Focus(bool);
 
 #[derive(Resource, Default)]
-pub struct Focused(Option<Entity>);
+pub struct Focused(Option<Entity>, bool);
+
+impl Focused {
+    /// The focused element
+    pub fn get(&self) -> Option<Entity> {
+        self.0
+    }
+    /// Returns `true` if the focus was moved without the pointer
+    /// (with the `Tab` key or the [`RequestFocus`] event), the focused
+    /// element has the `:focus-visible` state then.
+    pub fn visible(&self) -> bool {
+        self.1
+    }
+}
 
 #[derive(Debug, Event)]
-pub struct RequestFocus(Entity);
+pub struct RequestFocus(pub Entity);
 
 pub fn focus_system(
     mut focused: ResMut<Focused>,
     // mut elements: Query<(Entity, &mut Element)>,
     mut elements: Elements,
-    interactable: Query<Entity, (With<Interaction>, With<Element>)>,
+    interactable: Query<(Entity, &Element), With<Interaction>>,
     mut signals: EventReader<PointerInput>,
     mut requests: EventReader<RequestFocus>,
 ) {
     let mut target_focus = None;
+    let mut visible = false;
     let mut update_required = false;
+    let disabled = |entity| {
+        interactable
+            .get(entity)
+            .map(|(_, e)| e.disabled())
+            .unwrap_or(false)
+    };
+    if focused.0.map(disabled).unwrap_or(false) {
+        update_required = true;
+    }
     for signal in signals.read().filter(|s| s.down()) {
-        for entity in interactable.iter_many(&signal.entities) {
+        for (entity, element) in interactable.iter_many(&signal.entities) {
+            if element.disabled() {
+                continue;
+            }
             update_required = true;
             if target_focus.is_none() {
                 target_focus = Some(entity);
@@ -441,18 +489,25 @@ pub fn focus_system(
         }
     }
     for RequestFocus(entity) in requests.read() {
+        if disabled(*entity) {
+            continue;
+        }
         update_required = true;
         target_focus = Some(*entity);
+        visible = true;
     }
 
-    if update_required && target_focus != focused.0 {
+    if update_required && (target_focus != focused.0 || visible != focused.1) {
         if let Some(was_focused) = focused.0 {
             elements.set_state(was_focused, tags::focus(), false);
+            elements.set_state(was_focused, tags::focus_visible(), false);
         }
         if let Some(target_focus) = target_focus {
             elements.set_state(target_focus, tags::focus(), true);
+            elements.set_state(target_focus, tags::focus_visible(), visible);
         }
         focused.0 = target_focus;
+        focused.1 = visible;
     }
 }
 
@@ -539,8 +594,63 @@ pub fn tab_focus_system(
     if !keyboard.just_pressed(KeyCode::Tab) {
         return;
     }
-    for (entity, _) in elements.iter() {
+    if let Some((entity, _)) = elements.iter().find(|(_, e)| !e.disabled()) {
         requests.send(RequestFocus(entity));
-        break;
+    }
+}
+
+/// Adds the `:disabled` state to the elements with the enabled [`Disabled`]
+/// component and to all their descendants, removes it from the rest.
+pub fn cascade_disabled_system(
+    mut elements: Elements,
+    flags: Query<&Disabled>,
+    children: Query<&Children>,
+) {
+    let mut stack: Vec<(Entity, bool)> = elements.roots.iter().map(|e| (e, false)).collect();
+    while let Some((entity, parent_disabled)) = stack.pop() {
+        let disabled = parent_disabled || flags.get(entity).map(|d| d.value).unwrap_or(false);
+        let changed = elements
+            .get(entity)
+            .map(|element| element.disabled() != disabled)
+            .unwrap_or(false);
+        if changed {
+            elements.set_state(entity, tags::disabled(), disabled);
+        }
+        if let Ok(children) = children.get(entity) {
+            stack.extend(children.iter().map(|c| (*c, disabled)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::element::ElementIdIndex;
+    use bevy::ecs::system::RunSystemOnce;
+
+    #[test]
+    fn disabled_cascades_to_descendants() {
+        let mut world = World::new();
+        world.init_resource::<ElementIdIndex>();
+        let button = world.spawn(Element::default()).id();
+        let container = world
+            .spawn((Element::default(), Disabled { value: true }))
+            .push_children(&[button])
+            .id();
+        let root = world
+            .spawn(Element::default())
+            .push_children(&[container])
+            .id();
+
+        world.run_system_once(cascade_disabled_system);
+        let disabled = |world: &World, entity| world.get::<Element>(entity).unwrap().disabled();
+        assert!(!disabled(&world, root));
+        assert!(disabled(&world, container));
+        assert!(disabled(&world, button));
+
+        world.get_mut::<Disabled>(container).unwrap().value = false;
+        world.run_system_once(cascade_disabled_system);
+        assert!(!disabled(&world, container));
+        assert!(!disabled(&world, button));
     }
 }
@@ -1,13 +1,25 @@
+pub mod ease;
 pub mod element;
 pub mod eml;
 pub mod ess;
 pub mod input;
+pub mod metrics;
 pub mod relations;
+pub mod scene;
+pub mod sound;
+pub mod states;
+#[cfg(feature = "svg")]
+pub mod svg;
 pub mod tags;
+pub mod time;
 use crate::eml::EmlPlugin;
 use crate::ess::EssPlugin;
 use crate::input::ElementsInputPlugin;
+use crate::metrics::MetricsPlugin;
 use crate::relations::RelationsPlugin;
+use crate::scene::UiScenePlugin;
+use crate::sound::UiSoundPlugin;
+use crate::time::UiTimePlugin;
 use bevy::prelude::*;
 use element::ElementsPlugin;
 use eml::BuildPlugin;
@@ -17,28 +29,69 @@ pub use tagstr::*;
 pub mod prelude {
     // funcs
     pub use crate::ess::managed;
+    pub use crate::scene::save_ui_scene;
 
     // macro
     pub use crate::bind;
+    pub use crate::element_signals;
     pub use crate::from;
+    pub use crate::sync;
     pub use crate::to;
 
     // traits
     pub use crate::eml::content::ExpandElementsExt;
     pub use crate::eml::content::IntoContent;
+    pub use crate::eml::IntoParams;
     pub use crate::eml::Widget;
     pub use crate::ess::ColorFromHexExtension;
+    pub use crate::ess::ColorHslExtension;
     pub use crate::relations::connect::ConnectCommandsExtension;
 
     // structs
+    pub use crate::element::Disabled;
     pub use crate::element::Element;
     pub use crate::element::Elements;
+    pub use crate::element::Layer;
+    pub use crate::element::Loading;
+    pub use crate::element::UiLayers;
     pub use crate::eml::asset::EmlAsset;
     pub use crate::eml::asset::EmlScene;
+    pub use crate::eml::handle::EmlHandle;
+    pub use crate::eml::StrictMode;
+    pub use crate::eml::View;
+    pub use crate::ess::impls::bitmap_font::BitmapFont;
+    pub use crate::ess::impls::material::UiMaterialInputs;
+    pub use crate::ess::impls::opacity::Opacity;
+    pub use crate::ess::impls::anchor::SafeAreaInsets;
+    pub use crate::ess::impls::responsive::Breakpoints;
+    pub use crate::ess::Theme;
+    pub use crate::ess::Themes;
+    pub use crate::ess::UiUnits;
     pub use crate::ess::StyleSheet;
+    pub use crate::ess::EssDiagnostics;
+    pub use crate::ess::StylesReloaded;
     pub use crate::relations::connect::Connect;
     pub use crate::relations::connect::EventSource;
+    pub use crate::relations::adapter::{BindAdapter, FromAdapter};
+    pub use crate::relations::list::ListBind;
+    pub use crate::relations::reflect::Bind;
+    pub use crate::relations::signal::{signal_source, ElementSignal};
     pub use crate::relations::EventContext;
+    pub use crate::relations::RelationsConfig;
+    pub use crate::relations::RelationsUnsettled;
+    pub use crate::scene::ElementBinds;
+    pub use crate::scene::ElementSnapshot;
+    pub use crate::sound::PlayUiSound;
+    pub use crate::sound::UiSound;
+    pub use crate::sound::UiSounds;
+    pub use crate::states::StateChanged;
+    pub use crate::states::StateVisibility;
+    pub use crate::time::DefaultUiClock;
+    pub use crate::time::ElementsTime;
+    pub use crate::time::Lifetime;
+    pub use crate::time::UiClock;
+    pub use crate::ease::Ease;
+    pub use crate::ease::Interpolate;
 }
 
 pub mod build {
@@ -51,11 +104,24 @@ pub mod build {
 
     // traits
     pub use crate::eml::FromWorldAndParams;
+    pub use crate::eml::migrations::RegisterWidgetMigration;
+    pub use crate::element::RegisterUiLayer;
+    pub use crate::ess::RegisterTheme;
+    pub use crate::eml::RegisterAttributeParam;
     pub use crate::eml::RegisterWidget;
     pub use crate::ess::RegisterProperty;
     pub use crate::ess::StylePropertyMethods;
+    pub use crate::ess::StylePropValue;
     pub use crate::relations::bind::AsTransformer;
     pub use crate::relations::bind::TransformationResult;
+    pub use crate::relations::format::RegisterTransformer;
+    pub use crate::relations::signal::RegisterSignal;
+    pub use crate::ess::impls::material::ElementMaterial;
+    pub use crate::ess::impls::material::RegisterUiMaterial;
+    pub use crate::sound::RegisterUiSound;
+    pub use crate::states::RegisterUiState;
+    pub use crate::states::RegisterWidgetStates;
+    pub use crate::states::WidgetStates;
     pub use crate::relations::props::impls::OptionProperties;
     pub use crate::relations::props::GetProperties;
 
@@ -65,6 +131,7 @@ pub mod build {
     pub use crate::eml::Variant;
     pub use crate::eml::WidgetContext;
     pub use crate::eml::WidgetData;
+    pub use crate::eml::WidgetPlugin;
     pub use crate::ess::PropertyValue;
     pub use crate::ess::StyleProperty;
     pub use crate::input::PointerInput;
@@ -82,7 +149,13 @@ impl Plugin for ElementsCorePlugin {
             .add_plugins(RelationsPlugin)
             .add_plugins(BuildPlugin)
             .add_plugins(EssPlugin)
-            .add_plugins(EmlPlugin);
+            .add_plugins(EmlPlugin)
+            .add_plugins(UiTimePlugin)
+            .add_plugins(UiScenePlugin)
+            .add_plugins(UiSoundPlugin)
+            .add_plugins(MetricsPlugin);
+        #[cfg(feature = "svg")]
+        app.add_plugins(svg::SvgPlugin);
     }
 }
 
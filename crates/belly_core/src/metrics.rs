@@ -0,0 +1,138 @@
+use crate::element::Element;
+use crate::Tag;
+use bevy::{
+    diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic},
+    prelude::*,
+    utils::HashMap,
+};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+/// The number of elements restyled during the frame.
+pub const INVALIDATED_ELEMENTS: DiagnosticPath =
+    DiagnosticPath::const_new("belly/invalidated_elements");
+/// The number of the selectors matched against the elements during the frame.
+pub const SELECTOR_MATCHES: DiagnosticPath = DiagnosticPath::const_new("belly/selector_matches");
+/// The number of the relations schedule runs during the frame.
+pub const RELATIONS_ITERATIONS: DiagnosticPath =
+    DiagnosticPath::const_new("belly/relations_iterations");
+
+/// The path of the time (in milliseconds) spent applying the property
+/// during the frame: `belly/apply/background-color`.
+pub fn property_apply_time(property: Tag) -> DiagnosticPath {
+    DiagnosticPath::new(format!("belly/apply/{property}"))
+}
+
+/// Adds the `belly` diagnostics to the bevy [`DiagnosticsStore`](bevy::diagnostic::DiagnosticsStore),
+/// add the `LogDiagnosticsPlugin` to print them:
+/// ```rust,ignore
+/// app.add_plugins(LogDiagnosticsPlugin::filtered(vec![
+///     belly::core::metrics::INVALIDATED_ELEMENTS,
+///     belly::core::metrics::SELECTOR_MATCHES,
+/// ]));
+/// ```
+pub struct MetricsPlugin;
+
+impl Plugin for MetricsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UiMetrics>();
+        app.register_diagnostic(Diagnostic::new(INVALIDATED_ELEMENTS));
+        app.register_diagnostic(Diagnostic::new(SELECTOR_MATCHES));
+        app.register_diagnostic(Diagnostic::new(RELATIONS_ITERATIONS));
+        app.add_systems(Last, report_metrics_system);
+    }
+}
+
+/// The counters of the current frame. The property systems run in
+/// parallel, so the counters are updated through the shared reference.
+#[derive(Resource, Default)]
+pub struct UiMetrics {
+    selector_matches: AtomicUsize,
+    relations_iterations: AtomicUsize,
+    apply_time: Mutex<HashMap<Tag, Duration>>,
+}
+
+impl UiMetrics {
+    pub(crate) fn add_selector_matches(&self, matches: usize) {
+        self.selector_matches.fetch_add(matches, Ordering::Relaxed);
+    }
+
+    pub(crate) fn add_relations_iterations(&self, iterations: usize) {
+        self.relations_iterations
+            .fetch_add(iterations, Ordering::Relaxed);
+    }
+
+    pub(crate) fn add_apply_time(&self, property: Tag, time: Duration) {
+        *self
+            .apply_time
+            .lock()
+            .unwrap()
+            .entry(property)
+            .or_default() += time;
+    }
+}
+
+/// Registers the apply time diagnostic of the property,
+/// called by `register_property`.
+pub(crate) fn register_property_metrics(app: &mut App, property: Tag) {
+    app.init_resource::<UiMetrics>();
+    app.register_diagnostic(Diagnostic::new(property_apply_time(property)).with_suffix("ms"));
+}
+
+fn report_metrics_system(
+    metrics: Res<UiMetrics>,
+    invalidated: Query<(), Changed<Element>>,
+    mut diagnostics: Diagnostics,
+) {
+    let invalidated = invalidated.iter().count();
+    diagnostics.add_measurement(&INVALIDATED_ELEMENTS, || invalidated as f64);
+    let matches = metrics.selector_matches.swap(0, Ordering::Relaxed);
+    diagnostics.add_measurement(&SELECTOR_MATCHES, || matches as f64);
+    let iterations = metrics.relations_iterations.swap(0, Ordering::Relaxed);
+    diagnostics.add_measurement(&RELATIONS_ITERATIONS, || iterations as f64);
+    for (property, time) in metrics.apply_time.lock().unwrap().drain() {
+        diagnostics.add_measurement(&property_apply_time(property), || {
+            time.as_nanos() as f64 / 1_000_000.
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tag;
+    use bevy::diagnostic::DiagnosticsStore;
+
+    #[test]
+    fn report_frame_metrics() {
+        let mut app = App::new();
+        app.add_plugins(MetricsPlugin);
+        register_property_metrics(&mut app, tag!("color"));
+        app.world.spawn(Element::default());
+        app.world.spawn(Element::default());
+        {
+            let metrics = app.world.resource::<UiMetrics>();
+            metrics.add_selector_matches(3);
+            metrics.add_relations_iterations(2);
+            metrics.add_apply_time(tag!("color"), Duration::from_millis(4));
+        }
+        app.update();
+        let value = |app: &App, path: &DiagnosticPath| {
+            let store = app.world.resource::<DiagnosticsStore>();
+            store.get(path).and_then(|d| d.value())
+        };
+        assert_eq!(value(&app, &INVALIDATED_ELEMENTS), Some(2.));
+        assert_eq!(value(&app, &SELECTOR_MATCHES), Some(3.));
+        assert_eq!(value(&app, &RELATIONS_ITERATIONS), Some(2.));
+        assert_eq!(value(&app, &property_apply_time(tag!("color"))), Some(4.));
+
+        app.update();
+        assert_eq!(value(&app, &INVALIDATED_ELEMENTS), Some(0.));
+        assert_eq!(value(&app, &SELECTOR_MATCHES), Some(0.));
+    }
+}
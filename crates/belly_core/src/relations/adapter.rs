@@ -0,0 +1,252 @@
+use super::{
+    bind::{
+        bind_id, BindableSource, BindableTarget, ChangesState, FromComponent, ToComponent,
+        ToComponentWithoutTransformer, TransformationError,
+    },
+    props::try_transform,
+    RelationsSystems,
+};
+use crate::eml::pool::is_released;
+use bevy::{ecs::system::Command, prelude::*};
+use std::{any::TypeId, marker::PhantomData};
+
+/// Adapts the values owned by other crates (replicated components and
+/// resources of networking crates, lobby lists, etc.) to the bind sources.
+/// The value is read by the `key` every time the relations are processed
+/// and the bind is triggered only when the value actually changes, so it
+/// doesn't depend on how the value is written by the owner:
+/// ```rust,ignore
+/// struct RemoteHealth;
+/// impl BindAdapter for RemoteHealth {
+///     type Key = PlayerId;
+///     type Value = f32;
+///     fn read(world: &World, player: &PlayerId) -> Option<f32> {
+///         world.resource::<Replicated<Players>>().get(player).map(|p| p.health)
+///     }
+/// }
+///
+/// commands.add(FromAdapter::<RemoteHealth>::new(player) >> to!(bar, ProgressBar:value));
+/// ```
+pub trait BindAdapter: Send + Sync + 'static {
+    type Key: Send + Sync + 'static;
+    type Value: BindableSource + PartialEq;
+    /// Returns the current value, `None` if there is no value for the `key` (yet).
+    fn read(world: &World, key: &Self::Key) -> Option<Self::Value>;
+}
+
+/// The key of the adapted bind source, lives on the separate entity
+/// and despawns with the bind target.
+#[derive(Component)]
+pub struct AdapterKey<A: BindAdapter> {
+    key: A::Key,
+    target: Entity,
+}
+
+/// The last value read by the [`BindAdapter`], it is the actual source of the bind.
+#[derive(Component)]
+pub struct AdaptedValue<A: BindAdapter>(pub A::Value);
+
+/// The source of the bind which value is read by the [`BindAdapter`].
+pub struct FromAdapter<A: BindAdapter> {
+    key: A::Key,
+    marker: PhantomData<A>,
+}
+
+impl<A: BindAdapter> FromAdapter<A> {
+    pub fn new(key: A::Key) -> FromAdapter<A> {
+        FromAdapter {
+            key,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn bind_component<W: Component, U: BindableTarget>(
+        self,
+        to: ToComponent<W, A::Value, U>,
+    ) -> AdapterToComponent<A, W, U> {
+        AdapterToComponent { from: self, to }
+    }
+}
+
+pub struct AdapterToComponent<A: BindAdapter, W: Component, U: BindableTarget> {
+    from: FromAdapter<A>,
+    to: ToComponent<W, A::Value, U>,
+}
+
+impl<A: BindAdapter, W: Component, U: BindableTarget> AdapterToComponent<A, W, U> {
+    pub fn write(self, world: &mut World) {
+        world
+            .resource::<RelationsSystems>()
+            .add_custom_system(TypeId::of::<AdapterKey<A>>(), read_adapters_system::<A>);
+        let helper = world
+            .spawn(AdapterKey::<A> {
+                key: self.from.key,
+                target: self.to.target,
+            })
+            .id();
+        let key = &world.get::<AdapterKey<A>>(helper).unwrap().key;
+        if let Some(value) = A::read(world, key) {
+            world.entity_mut(helper).insert(AdaptedValue::<A>(value));
+        }
+        FromComponent {
+            id: bind_id::<AdaptedValue<A>>("0"),
+            source: helper,
+            reader: |c: &AdaptedValue<A>| c.0.clone(),
+            ease: None,
+        }
+        .bind_component(self.to)
+        .write(world);
+    }
+}
+
+impl<A: BindAdapter, W: Component, U: BindableTarget> Command for AdapterToComponent<A, W, U> {
+    fn apply(self, world: &mut World) {
+        self.write(world);
+    }
+}
+
+/// Reads the adapted values and updates the changed ones. Despawns the
+/// adapted sources which targets are despawned or recycled.
+pub fn read_adapters_system<A: BindAdapter>(world: &mut World) {
+    let mut despawn = vec![];
+    let mut updates = vec![];
+    let mut sources = world.query::<(Entity, &AdapterKey<A>, Option<&AdaptedValue<A>>)>();
+    for (entity, source, current) in sources.iter(world) {
+        if is_released(world, source.target) {
+            despawn.push(entity);
+            continue;
+        }
+        let Some(value) = A::read(world, &source.key) else {
+            continue;
+        };
+        if current.map(|c| c.0 != value).unwrap_or(true) {
+            updates.push((entity, value));
+        }
+    }
+    for entity in despawn {
+        world.despawn(entity);
+    }
+    if updates.is_empty() {
+        return;
+    }
+    for (entity, value) in updates {
+        world.entity_mut(entity).insert(AdaptedValue::<A>(value));
+    }
+    world.resource::<ChangesState>().report_changed();
+}
+
+// FromAdapter::<A>::new(key) >> to!(entity, Component:property | filter)
+impl<A: BindAdapter, W: Component, U: BindableTarget> std::ops::Shr<ToComponent<W, A::Value, U>>
+    for FromAdapter<A>
+{
+    type Output = AdapterToComponent<A, W, U>;
+    fn shr(self, to: ToComponent<W, A::Value, U>) -> Self::Output {
+        self.bind_component(to)
+    }
+}
+
+// to!(entity, Component:property | filter) << FromAdapter::<A>::new(key)
+impl<A: BindAdapter, W: Component, U: BindableTarget> std::ops::Shl<FromAdapter<A>>
+    for ToComponent<W, A::Value, U>
+{
+    type Output = AdapterToComponent<A, W, U>;
+    fn shl(self, from: FromAdapter<A>) -> Self::Output {
+        from.bind_component(self)
+    }
+}
+
+// FromAdapter::<A>::new(key) >> to!(entity, Component:property)
+impl<A, W, U, E> std::ops::Shr<ToComponentWithoutTransformer<W, U>> for FromAdapter<A>
+where
+    E: Into<TransformationError>,
+    A: BindAdapter,
+    W: Component,
+    U: BindableTarget + TryFrom<A::Value, Error = E>,
+{
+    type Output = AdapterToComponent<A, W, U>;
+    fn shr(self, to: ToComponentWithoutTransformer<W, U>) -> Self::Output {
+        self.bind_component(ToComponent {
+            id: to.id,
+            target: to.target,
+            reader: to.reader,
+            writer: to.writer,
+            transformer: try_transform::<A::Value, U, E>,
+        })
+    }
+}
+
+// to!(entity, Component:property) << FromAdapter::<A>::new(key)
+impl<A, W, U, E> std::ops::Shl<FromAdapter<A>> for ToComponentWithoutTransformer<W, U>
+where
+    E: Into<TransformationError>,
+    A: BindAdapter,
+    W: Component,
+    U: BindableTarget + TryFrom<A::Value, Error = E>,
+{
+    type Output = AdapterToComponent<A, W, U>;
+    fn shl(self, from: FromAdapter<A>) -> Self::Output {
+        from.bind_component(ToComponent {
+            id: self.id,
+            target: self.target,
+            reader: self.reader,
+            writer: self.writer,
+            transformer: try_transform::<A::Value, U, E>,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::relations::RelationsPlugin;
+    use crate::*;
+
+    /// Emulates the state replicated by the networking crate.
+    #[derive(Resource, Default)]
+    struct Lobby {
+        players: Vec<(u32, f32)>,
+    }
+
+    struct LobbyHealth;
+    impl BindAdapter for LobbyHealth {
+        type Key = u32;
+        type Value = f32;
+        fn read(world: &World, key: &u32) -> Option<f32> {
+            world
+                .resource::<Lobby>()
+                .players
+                .iter()
+                .find(|(id, _)| id == key)
+                .map(|(_, health)| *health)
+        }
+    }
+
+    #[derive(Component, Default)]
+    struct HealthBar {
+        value: f32,
+    }
+
+    #[test]
+    fn bind_from_adapter() {
+        let mut app = App::new();
+        app.add_plugins(RelationsPlugin);
+        app.init_resource::<Lobby>();
+
+        let bar = app.world.spawn(HealthBar::default()).id();
+        let bind = FromAdapter::<LobbyHealth>::new(7) >> to!(bar, HealthBar: value);
+        bind.write(&mut app.world);
+        app.update();
+
+        app.world.resource_mut::<Lobby>().players.push((7, 40.));
+        app.update();
+        assert_eq!(app.world.get::<HealthBar>(bar).unwrap().value, 40.);
+
+        app.world.resource_mut::<Lobby>().players[0].1 = 25.;
+        app.update();
+        assert_eq!(app.world.get::<HealthBar>(bar).unwrap().value, 25.);
+
+        app.world.despawn(bar);
+        app.update();
+        assert_eq!(app.world.iter_entities().count(), 0);
+    }
+}
@@ -1,19 +1,32 @@
 use crate::build::GetProperties;
+use crate::ease::{Ease, Interpolate};
+use crate::eml::pool::ElementPool;
 
 use super::{
-    props::{Prop, PropertyDescriptor},
+    format::Formatters,
+    props::{Prop, PropertyDescriptor, SetGet},
+    registry::{BindRecord, RelationsRegistry},
     RelationsSystems,
 };
-use bevy::{ecs::system::Command, prelude::*, utils::HashMap};
+use bevy::{
+    ecs::system::Command,
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
 use itertools::Itertools;
 use smallvec::SmallVec;
 use std::{
-    any::type_name, convert::Infallible, fmt::Debug, marker::PhantomData, num::ParseFloatError,
+    any::type_name,
+    convert::Infallible,
+    fmt::Debug,
+    num::ParseFloatError,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
 };
 use tagstr::Tag;
 
 pub type SourceReader<R, S> = fn(&R) -> S;
-pub type Transformer<S, T> = fn(&S, Prop<T>) -> TransformationResult;
+pub type Transformer<S, T> = fn(&S, Prop<T>, &Formatters) -> TransformationResult;
 pub type RefReader<W, T> = for<'b> fn(&'b Mut<W>) -> &'b T;
 pub type MutReader<W, T> = for<'b> fn(&'b mut Mut<W>) -> &'b mut T;
 pub type TransformationResult = Result<(), TransformationError>;
@@ -25,27 +38,68 @@ impl<T: PartialEq + Send + Sync + 'static> BindableTarget for T {}
 
 fn write_component_changes<W: Component, S: BindableSource, T: BindableTarget>(
     changes: &ActiveChanges<S>,
-    writes: &mut Query<(&WriteComponent<W, S, T>, &mut W, &mut Change<W>)>,
+    writes: &mut Query<(&WriteComponent<W, S, T>, &mut W)>,
+    throttled: &mut ThrottledChanges<S>,
+    eased: &mut EasedChanges<S>,
+    now: Option<Duration>,
+    changes_state: &ChangesState,
+    formatters: &Formatters,
 ) {
+    let mut written = false;
     for (target, sources) in changes.iter() {
-        let Ok((writers, mut component, mut component_change)) = writes.get_mut(*target) else {
+        let Ok((writers, mut component)) = writes.get_mut(*target) else {
+            throttled.forget(*target);
+            eased.forget(*target);
             continue;
         };
         for (id, source) in sources {
             for write_descriptor in writers.iter().filter(|w| &w.id == id) {
+                if let (Some(interval), Some(now)) = (write_descriptor.throttle, now) {
+                    if !throttled.accept(*id, source, interval, now) {
+                        continue;
+                    }
+                }
+                let eased_source;
+                let source = match (&write_descriptor.ease, now) {
+                    (Some(ease), Some(now)) => {
+                        eased_source = eased.accept(*id, source, ease, now);
+                        &eased_source
+                    }
+                    _ => source,
+                };
                 let mut prop_descriptor = write_descriptor.prop_descripror(&mut component);
-                if let Err(e) = write_descriptor.transform(source, prop_descriptor.as_prop()) {
+                let prop = prop_descriptor.as_prop();
+                if let Err(e) = write_descriptor.transform(source, prop, formatters) {
                     error!("Error transforming {:?}: {}", id, e.0);
                 } else if prop_descriptor.changed() {
-                    // TODO: protect infinity circular loops by tracking property changes
-                    // info!("[bind] just writed {:?}", id);
-                    component_change.set_changed();
+                    written = true;
                 }
             }
         }
     }
+    // the binds reading the written component run once more
+    if written {
+        changes_state.report_changed();
+    }
 }
 
+/// Drops the throttling and easing state of the binds targeting the elements
+/// recycled by the [`ElementPool`], so the reused entities start from scratch.
+fn forget_recycled<S: BindableSource>(
+    pool: Option<Res<ElementPool>>,
+    throttled: &mut ThrottledChanges<S>,
+    eased: &mut EasedChanges<S>,
+) {
+    let Some(pool) = pool.filter(|pool| pool.is_changed()) else {
+        return;
+    };
+    for target in pool.recycled() {
+        throttled.forget(*target);
+        eased.forget(*target);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn component_to_component_system<
     R: Component,
     W: Component,
@@ -54,10 +108,19 @@ pub fn component_to_component_system<
 >(
     mut binds: ParamSet<(
         Query<(&ReadComponent<R, S>, &R), Changed<R>>,
-        Query<(&WriteComponent<W, S, T>, &mut W, &mut Change<W>)>,
+        Query<(&WriteComponent<W, S, T>, &mut W)>,
     )>,
     mut changes: Local<ActiveChanges<S>>,
+    mut throttled: Local<ThrottledChanges<S>>,
+    mut eased: Local<EasedChanges<S>>,
+    changes_state: Res<ChangesState>,
+    time: Option<Res<Time>>,
+    pool: Option<Res<ElementPool>>,
+    formatters: Res<Formatters>,
 ) {
+    forget_recycled(pool, &mut throttled, &mut eased);
+    // only the sources changed since the last run are visited,
+    // the idle binds are skipped by the change ticks
     changes.clear();
     for (readers, component) in binds.p0().iter() {
         for descriptor in readers.iter() {
@@ -65,10 +128,25 @@ pub fn component_to_component_system<
             changes.add_change(descriptor.id, value);
         }
     }
+    let now = time.map(|t| t.elapsed());
+    throttled.add_due_changes(&mut changes, now);
+    eased.add_running_changes(&mut changes);
+    if changes.is_empty() {
+        return;
+    }
     let mut writes = binds.p1();
-    write_component_changes(&mut changes, &mut writes);
+    write_component_changes(
+        &changes,
+        &mut writes,
+        &mut throttled,
+        &mut eased,
+        now,
+        &changes_state,
+        &formatters,
+    );
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn resource_to_component_system<
     R: Resource,
     W: Component,
@@ -77,28 +155,39 @@ pub fn resource_to_component_system<
 >(
     res: Res<R>,
     read: Res<ReadResource<R, S>>,
-    mut writes: Query<(&WriteComponent<W, S, T>, &mut W, &mut Change<W>)>,
+    mut writes: Query<(&WriteComponent<W, S, T>, &mut W)>,
     mut changes: Local<ActiveChanges<S>>,
+    mut throttled: Local<ThrottledChanges<S>>,
+    mut eased: Local<EasedChanges<S>>,
+    changes_state: Res<ChangesState>,
+    time: Option<Res<Time>>,
+    pool: Option<Res<ElementPool>>,
+    formatters: Res<Formatters>,
 ) {
-    if !res.is_changed() {
+    forget_recycled(pool, &mut throttled, &mut eased);
+    if !res.is_changed() && !throttled.has_pending() && !eased.has_running() {
         return;
     }
     changes.clear();
 
-    for descriptor in read.iter() {
-        let value = (descriptor.reader)(&res);
-        changes.add_change(descriptor.id, value);
-    }
-    write_component_changes(&mut changes, &mut writes);
-}
-
-pub(crate) fn watch_changes<W: Component>(
-    something_changed: Query<(), Changed<Change<W>>>,
-    mut changes: ResMut<ChangesState>,
-) {
-    if !something_changed.is_empty() {
-        changes.report_changed()
+    if res.is_changed() {
+        for descriptor in read.iter() {
+            let value = (descriptor.reader)(&res);
+            changes.add_change(descriptor.id, value);
+        }
     }
+    let now = time.map(|t| t.elapsed());
+    throttled.add_due_changes(&mut changes, now);
+    eased.add_running_changes(&mut changes);
+    write_component_changes(
+        &changes,
+        &mut writes,
+        &mut throttled,
+        &mut eased,
+        now,
+        &changes_state,
+        &formatters,
+    );
 }
 
 #[derive(Deref, DerefMut)]
@@ -115,6 +204,214 @@ impl<S: BindableSource> Default for ActiveChanges<S> {
     }
 }
 
+/// The last write time and the value postponed by the throttled bind.
+pub struct ThrottledChange<S: BindableSource> {
+    interval: Duration,
+    last_write: Duration,
+    pending: Option<S>,
+}
+
+/// The state of the throttled binds: the changes arrived faster then the
+/// bind interval are postponed and only the latest one is written when
+/// the interval passes. The binds with the postponed values are queued
+/// in `pending`, so the idle throttled binds are never visited.
+#[derive(Deref, DerefMut)]
+pub struct ThrottledChanges<S: BindableSource> {
+    #[deref]
+    changes: HashMap<BindId, ThrottledChange<S>>,
+    pending: HashSet<BindId>,
+}
+
+impl<S: BindableSource> Default for ThrottledChanges<S> {
+    fn default() -> Self {
+        ThrottledChanges {
+            changes: HashMap::default(),
+            pending: HashSet::default(),
+        }
+    }
+}
+
+impl<S: BindableSource> ThrottledChanges<S> {
+    fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    fn forget(&mut self, target: Entity) {
+        self.changes.retain(|id, _| id.target != target);
+        self.pending.retain(|id| id.target != target);
+    }
+
+    /// Returns `true` if the `value` should be written right now,
+    /// postpones it otherwise.
+    fn accept(&mut self, id: BindId, value: &S, interval: Duration, now: Duration) -> bool {
+        let Some(change) = self.get_mut(&id) else {
+            self.insert(
+                id,
+                ThrottledChange {
+                    interval,
+                    last_write: now,
+                    pending: None,
+                },
+            );
+            return true;
+        };
+        if now.saturating_sub(change.last_write) >= change.interval {
+            change.last_write = now;
+            change.pending = None;
+            self.pending.remove(&id);
+            true
+        } else {
+            change.pending = Some(value.clone());
+            self.pending.insert(id);
+            false
+        }
+    }
+
+    /// Moves the postponed values which interval passed to the `changes`,
+    /// unless there are fresh changes for the same binds.
+    fn add_due_changes(&mut self, changes: &mut ActiveChanges<S>, now: Option<Duration>) {
+        let Some(now) = now else {
+            return;
+        };
+        let ThrottledChanges {
+            changes: throttled,
+            pending,
+        } = self;
+        pending.retain(|id| {
+            let Some(change) = throttled.get_mut(id) else {
+                return false;
+            };
+            if change.pending.is_none() {
+                return false;
+            }
+            if now.saturating_sub(change.last_write) < change.interval {
+                return true;
+            }
+            let pending = change.pending.take().unwrap();
+            let fresh = changes
+                .get(&id.target)
+                .map(|c| c.iter().any(|(i, _)| i == id))
+                .unwrap_or(false);
+            if !fresh {
+                changes.add_change(*id, pending);
+            }
+            false
+        });
+    }
+}
+
+/// The easing of the bind source set by [`FromComponent::ease`]
+pub struct BindEase<S> {
+    ease: Ease,
+    duration: Duration,
+    interpolate: fn(&S, &S, f32) -> S,
+}
+
+impl<S: Interpolate> BindEase<S> {
+    pub fn new(ease: Ease, duration: f32) -> BindEase<S> {
+        BindEase {
+            ease,
+            duration: Duration::from_secs_f32(duration.max(0.)),
+            interpolate: S::interpolate,
+        }
+    }
+}
+
+/// The source value of the eased bind moving from the previous value
+/// to the latest one.
+pub struct EasedChange<S: BindableSource> {
+    from: S,
+    to: S,
+    current: S,
+    started: Duration,
+    /// The change is added by [`EasedChanges::add_running_changes`],
+    /// not by the source
+    tick: bool,
+}
+
+/// The state of the eased binds: the fresh source values restart the
+/// easing from the current value, the running binds are queued in
+/// `running` and get the interpolated value each time the bind runs.
+#[derive(Deref, DerefMut)]
+pub struct EasedChanges<S: BindableSource> {
+    #[deref]
+    changes: HashMap<BindId, EasedChange<S>>,
+    running: HashSet<BindId>,
+}
+
+impl<S: BindableSource> Default for EasedChanges<S> {
+    fn default() -> Self {
+        EasedChanges {
+            changes: HashMap::default(),
+            running: HashSet::default(),
+        }
+    }
+}
+
+impl<S: BindableSource> EasedChanges<S> {
+    fn has_running(&self) -> bool {
+        !self.running.is_empty()
+    }
+
+    fn forget(&mut self, target: Entity) {
+        self.changes.retain(|id, _| id.target != target);
+        self.running.retain(|id| id.target != target);
+    }
+
+    /// Returns the value the target should get: the first value is written
+    /// as is, the next ones are eased from the current value.
+    fn accept(&mut self, id: BindId, value: &S, ease: &BindEase<S>, now: Duration) -> S {
+        let Some(change) = self.changes.get_mut(&id) else {
+            self.changes.insert(
+                id,
+                EasedChange {
+                    from: value.clone(),
+                    to: value.clone(),
+                    current: value.clone(),
+                    started: now,
+                    tick: false,
+                },
+            );
+            return value.clone();
+        };
+        if change.tick {
+            change.tick = false;
+        } else {
+            change.from = change.current.clone();
+            change.to = value.clone();
+            change.started = now;
+            self.running.insert(id);
+        }
+        let elapsed = now.saturating_sub(change.started);
+        if elapsed >= ease.duration {
+            change.current = change.to.clone();
+            self.running.remove(&id);
+        } else {
+            let t = ease.ease.apply(elapsed.as_secs_f32() / ease.duration.as_secs_f32());
+            change.current = (ease.interpolate)(&change.from, &change.to, t);
+        }
+        change.current.clone()
+    }
+
+    /// Adds the running binds to the `changes`, unless there are
+    /// fresh changes for the same binds.
+    fn add_running_changes(&mut self, changes: &mut ActiveChanges<S>) {
+        for id in self.running.iter() {
+            let fresh = changes
+                .get(&id.target)
+                .map(|c| c.iter().any(|(i, _)| i == id))
+                .unwrap_or(false);
+            let Some(change) = self.changes.get_mut(id) else {
+                continue;
+            };
+            if !fresh {
+                change.tick = true;
+                changes.add_change(*id, change.to.clone());
+            }
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
 pub struct BindId {
     source: Option<Entity>,
@@ -134,22 +431,17 @@ impl BindId {
     }
 }
 
+/// The counter of the bind writes, the relations systems run again until
+/// it stops changing. The bind systems report the writes through the shared
+/// reference, so they don't block each other.
 #[derive(Resource, Default)]
-pub struct ChangesState(usize);
+pub struct ChangesState(AtomicUsize);
 impl ChangesState {
-    fn report_changed(&mut self) {
-        self.0 += 1;
+    pub(crate) fn report_changed(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
     }
     pub fn get(&self) -> usize {
-        self.0
-    }
-}
-
-#[derive(Component)]
-pub struct Change<W: Component>(PhantomData<W>);
-impl<W: Component> Change<W> {
-    fn new() -> Change<W> {
-        Change(PhantomData)
+        self.0.load(Ordering::Relaxed)
     }
 }
 
@@ -182,6 +474,8 @@ impl<R: Resource, S: BindableSource> Default for ReadResource<R, S> {
 
 pub struct WriteDescriptor<W, S: BindableSource, T: BindableTarget> {
     id: BindId,
+    throttle: Option<Duration>,
+    ease: Option<BindEase<S>>,
     transformer: Transformer<S, T>,
     ref_getter: RefReader<W, T>,
     mut_getter: MutReader<W, T>,
@@ -195,8 +489,8 @@ impl<W: Component, S: BindableSource, T: BindableTarget> WriteDescriptor<W, S, T
         PropertyDescriptor::new(component, self.ref_getter, self.mut_getter)
     }
 
-    fn transform(&self, source: &S, prop: Prop<T>) -> TransformationResult {
-        (self.transformer)(source, prop)
+    fn transform(&self, source: &S, prop: Prop<T>, formatters: &Formatters) -> TransformationResult {
+        (self.transformer)(source, prop, formatters)
     }
 }
 #[derive(Component, Deref, DerefMut, Default)]
@@ -208,6 +502,23 @@ pub struct FromComponent<R: Component, S: BindableSource> {
     pub id: Tag,
     pub source: Entity,
     pub reader: SourceReader<R, S>,
+    pub ease: Option<BindEase<S>>,
+}
+
+impl<R: Component, S: BindableSource + Interpolate> FromComponent<R, S> {
+    /// Eases the changes of the source: the target gets the values moving
+    /// from the previous source value to the new one during the `duration`
+    /// in seconds, the change arrived in the middle continues from the
+    /// current value. The transformer receives the eased values:
+    /// ```rust,ignore
+    /// commands.add(
+    ///     from!(player, Health:current).ease(Ease::OutCubic, 0.2) >> to!(bar, HealthBar:value)
+    /// );
+    /// ```
+    pub fn ease(mut self, ease: Ease, duration: f32) -> Self {
+        self.ease = Some(BindEase::new(ease, duration));
+        self
+    }
 }
 
 impl<R: Component, S: BindableSource> FromComponent<R, S> {
@@ -215,7 +526,11 @@ impl<R: Component, S: BindableSource> FromComponent<R, S> {
         self,
         to: ToComponent<W, S, T>,
     ) -> ComponentToComponent<R, W, S, T> {
-        ComponentToComponent { from: self, to }
+        ComponentToComponent {
+            from: self,
+            to,
+            throttle: None,
+        }
     }
 }
 
@@ -224,6 +539,16 @@ pub struct FromComponentWithTransformer<R: Component, S: BindableSource, T: Bind
     pub transformer: Transformer<S, T>,
 }
 
+impl<R: Component, S: BindableSource + Interpolate, T: BindableTarget>
+    FromComponentWithTransformer<R, S, T>
+{
+    /// Eases the changes of the source, see [`FromComponent::ease`].
+    pub fn ease(mut self, ease: Ease, duration: f32) -> Self {
+        self.from = self.from.ease(ease, duration);
+        self
+    }
+}
+
 impl<R: Component, S: BindableSource, T: BindableTarget> FromComponentWithTransformer<R, S, T> {
     pub fn bind<W: Component>(
         self,
@@ -243,6 +568,15 @@ impl<R: Component, S: BindableSource, T: BindableTarget> FromComponentWithTransf
 pub struct FromResource<R: Resource, S: BindableSource> {
     pub id: Tag,
     pub reader: SourceReader<R, S>,
+    pub ease: Option<BindEase<S>>,
+}
+
+impl<R: Resource, S: BindableSource + Interpolate> FromResource<R, S> {
+    /// Eases the changes of the source, see [`FromComponent::ease`].
+    pub fn ease(mut self, ease: Ease, duration: f32) -> Self {
+        self.ease = Some(BindEase::new(ease, duration));
+        self
+    }
 }
 
 impl<R: Resource, S: BindableSource> FromResource<R, S> {
@@ -250,7 +584,11 @@ impl<R: Resource, S: BindableSource> FromResource<R, S> {
         self,
         to: ToComponent<W, S, T>,
     ) -> ResourceToComponent<R, W, S, T> {
-        ResourceToComponent { from: self, to }
+        ResourceToComponent {
+            from: self,
+            to,
+            throttle: None,
+        }
     }
 }
 
@@ -258,6 +596,16 @@ pub struct FromResourceWithTransformer<R: Resource, S: BindableSource, T: Bindab
     pub from: FromResource<R, S>,
     pub transformer: Transformer<S, T>,
 }
+impl<R: Resource, S: BindableSource + Interpolate, T: BindableTarget>
+    FromResourceWithTransformer<R, S, T>
+{
+    /// Eases the changes of the source, see [`FromComponent::ease`].
+    pub fn ease(mut self, ease: Ease, duration: f32) -> Self {
+        self.from = self.from.ease(ease, duration);
+        self
+    }
+}
+
 impl<R: Resource, S: BindableSource, T: BindableTarget> FromResourceWithTransformer<R, S, T> {
     pub fn bind_component<W: Component>(
         self,
@@ -272,6 +620,7 @@ impl<R: Resource, S: BindableSource, T: BindableTarget> FromResourceWithTransfor
                 reader: to.reader,
                 transformer: self.transformer,
             },
+            throttle: None,
         }
     }
 }
@@ -302,13 +651,21 @@ impl<W: Component, S: BindableSource, T: BindableTarget> ToComponent<W, S, T> {
         self,
         from: FromComponent<R, S>,
     ) -> ComponentToComponent<R, W, S, T> {
-        ComponentToComponent { from, to: self }
+        ComponentToComponent {
+            from,
+            to: self,
+            throttle: None,
+        }
     }
     pub fn bind_resource<R: Resource>(
         self,
         from: FromResource<R, S>,
     ) -> ResourceToComponent<R, W, S, T> {
-        ResourceToComponent { from, to: self }
+        ResourceToComponent {
+            from,
+            to: self,
+            throttle: None,
+        }
     }
 }
 
@@ -362,33 +719,47 @@ pub struct ToComponentTransformable<W: Component, T: BindableTarget + GetPropert
 impl<W: Component, T: BindableTarget + GetProperties> ToComponentTransformable<W, T> {
     pub fn transformed<S: BindableSource>(
         self,
-        make_transformer: fn(&'static T::Item) -> Transformer<S, T>,
+        transformer: Transformer<S, T>,
     ) -> ToComponent<W, S, T> {
         ToComponent {
             id: self.id,
             target: self.target,
             reader: self.reader,
             writer: self.writer,
-            transformer: make_transformer(T::get_properties()),
+            transformer,
         }
     }
 }
 
+fn record_bind<R: 'static, W: 'static>(world: &mut World, id: BindId) {
+    world
+        .get_resource_or_insert_with(RelationsRegistry::default)
+        .add_bind(BindRecord {
+            source: id.source,
+            from: id.from,
+            source_type: type_name::<R>(),
+            target: id.target,
+            to: id.to,
+            target_type: type_name::<W>(),
+        });
+}
+
 fn register_component_writer<W: Component, S: BindableSource, T: BindableTarget>(
     world: &mut World,
     id: BindId,
     to: ToComponent<W, S, T>,
+    throttle: Option<Duration>,
+    ease: Option<BindEase<S>>,
 ) {
     let mut target_entity = world.entity_mut(to.target);
     let write_descriptor = WriteDescriptor {
         id,
+        throttle,
+        ease,
         ref_getter: to.reader,
         mut_getter: to.writer,
         transformer: to.transformer,
     };
-    if !target_entity.contains::<Change<W>>() {
-        target_entity.insert(Change::<W>::new());
-    }
     if let Some(mut writer_component) = target_entity.get_mut::<WriteComponent<W, S, T>>() {
         writer_component.push(write_descriptor);
     } else {
@@ -399,6 +770,7 @@ fn register_component_writer<W: Component, S: BindableSource, T: BindableTarget>
 pub struct ComponentToComponent<R: Component, W: Component, S: BindableSource, T: BindableTarget> {
     from: FromComponent<R, S>,
     to: ToComponent<W, S, T>,
+    throttle: Option<Duration>,
 }
 
 impl<R: Component, W: Component, S: BindableSource, T: BindableTarget> std::fmt::Display
@@ -422,6 +794,21 @@ impl<R: Component, W: Component, S: BindableSource, T: BindableTarget> Command
 impl<R: Component, W: Component, S: BindableSource, T: BindableTarget>
     ComponentToComponent<R, W, S, T>
 {
+    /// Limits the rate the bind target is updated: the target is written at
+    /// most once per `interval`, the latest source value is written when the
+    /// interval passes. Use it when the heavy targets (texture regeneration,
+    /// layout rebuilding) are driven by the high-frequency sources:
+    /// ```rust,ignore
+    /// commands.add(
+    ///     (from!(player, Transform:translation) >> to!(minimap, Minimap:center))
+    ///         .throttle(Duration::from_millis(250)),
+    /// );
+    /// ```
+    pub fn throttle(mut self, interval: Duration) -> Self {
+        self.throttle = Some(interval);
+        self
+    }
+
     pub fn write(self, world: &mut World) {
         world
             .resource::<RelationsSystems>()
@@ -442,13 +829,15 @@ impl<R: Component, W: Component, S: BindableSource, T: BindableTarget>
         } else {
             source_entity.insert(ReadComponent(vec![read_descriptor]));
         }
-        register_component_writer(world, id, self.to);
+        record_bind::<R, W>(world, id);
+        register_component_writer(world, id, self.to, self.throttle, self.from.ease);
     }
 }
 
 pub struct ResourceToComponent<R: Resource, W: Component, S: BindableSource, T: BindableTarget> {
     from: FromResource<R, S>,
     to: ToComponent<W, S, T>,
+    throttle: Option<Duration>,
 }
 
 impl<R: Resource, W: Component, S: BindableSource, T: BindableTarget> std::fmt::Display
@@ -464,6 +853,12 @@ impl<R: Resource, W: Component, S: BindableSource, T: BindableTarget> std::fmt::
 impl<R: Resource, W: Component, S: BindableSource, T: BindableTarget>
     ResourceToComponent<R, W, S, T>
 {
+    /// Limits the rate the bind target is updated, see [`ComponentToComponent::throttle`].
+    pub fn throttle(mut self, interval: Duration) -> Self {
+        self.throttle = Some(interval);
+        self
+    }
+
     pub fn write(self, world: &mut World) {
         world
             .resource::<RelationsSystems>()
@@ -476,7 +871,32 @@ impl<R: Resource, W: Component, S: BindableSource, T: BindableTarget>
         world
             .get_resource_or_insert_with(ReadResource::<R, S>::default)
             .push(read_descriptor);
-        register_component_writer(world, id, self.to);
+        record_bind::<R, W>(world, id);
+        register_component_writer(world, id, self.to, self.throttle, self.from.ease);
+    }
+}
+
+/// Two binds pointing in opposite directions, produced by the `sync!` macro.
+/// Writing it writes both the `forward` and the `backward` binds.
+pub struct SyncBind<A: Command, B: Command> {
+    forward: A,
+    backward: B,
+}
+
+impl<A: Command, B: Command> SyncBind<A, B> {
+    pub fn new(forward: A, backward: B) -> SyncBind<A, B> {
+        SyncBind { forward, backward }
+    }
+
+    pub fn write(self, world: &mut World) {
+        self.forward.apply(world);
+        self.backward.apply(world);
+    }
+}
+
+impl<A: Command, B: Command> Command for SyncBind<A, B> {
+    fn apply(self, world: &mut World) {
+        self.write(world);
     }
 }
 
@@ -539,6 +959,26 @@ pub fn bind_id<T>(field: &str) -> Tag {
     ))
 }
 
+/// Reads the value from associated property getter, used by
+/// `from!(entity, Component:property | transformer)` form.
+pub fn get_property<T: GetProperties + Clone, S>(
+    value: &T,
+    property: fn(&'static T::Item) -> SetGet<T, S>,
+) -> S {
+    let mut value = value.clone();
+    property(T::get_properties()).get((&mut value).into())
+}
+
+/// Writes the value with associated property setter, used by
+/// `to!(entity, Component:property | transformer)` form.
+pub fn set_property<T: GetProperties, S>(
+    source: &S,
+    target: Prop<T>,
+    property: fn(&'static T::Item) -> SetGet<T, S>,
+) -> TransformationResult {
+    (property(T::get_properties()).as_transformer())(source, target)
+}
+
 #[deprecated(
     note = "The colon form of transformer is deprecated, use dot notation: |fmt.val(\"{val}\""
 )]
@@ -551,14 +991,16 @@ macro_rules! bind {
         $crate::relations::bind::FromComponent {
             id: $crate::relations::bind::bind_id::<$cls>(stringify!($($prop)+)),
             source: $entity,
-            reader: |c: &$cls| c.$($prop)+.clone()
+            reader: |c: &$cls| c.$($prop)+.clone(),
+            ease: None,
         }
     };
     // from!(Resource:some.property)
     (@bind from resource $cls:ty, { $($prop:tt)+ }, default) => {
         $crate::relations::bind::FromResource {
             id: $crate::relations::bind::bind_id::<$cls>(stringify!($($prop)+)),
-            reader: |c: &$cls| c.$($prop)+.clone()
+            reader: |c: &$cls| c.$($prop)+.clone(),
+            ease: None,
         }
     };
     // from!(entity, Component:some.property | some:transformer)
@@ -568,17 +1010,28 @@ macro_rules! bind {
             from: $crate::relations::bind::FromComponent {
                 id: $crate::relations::bind::bind_id::<$cls>(stringify!($($prop)+)),
                 source: $entity,
-                reader: |c: &$cls| c.$($prop)+.clone()
+                reader: |c: &$cls| c.$($prop)+.clone(),
+                ease: None,
             }
         }
     };
+    // from!(entity, Component:some.property | transform)
+    (@bind from component $entity:expr, $cls:ty, { $($prop:tt)+ }, transformable $transformer:ident ) => {
+        $crate::relations::bind::FromComponent {
+            id: $crate::relations::bind::bind_id::<$cls>(stringify!($($prop)+.$transformer)),
+            source: $entity,
+            reader: |c: &$cls| $crate::relations::bind::get_property(&c.$($prop)+, |tr| tr.$transformer()),
+            ease: None,
+        }
+    };
     // from!(Resource:some.property | Struct.property)
     (@bind from resource $cls:ty, { $($prop:tt)+ }, $transformer:expr) => {
         $crate::relations::bind::FromResourceWithTransformer {
             transformer: $transformer,
             from: $crate::relations::bind::FromResource {
                 id: $crate::relations::bind::bind_id::<$cls>(stringify!($($prop)+)),
-                reader: |c: &$cls| c.$($prop)+.clone()
+                reader: |c: &$cls| c.$($prop)+.clone(),
+                ease: None,
             }
         }
     };
@@ -598,7 +1051,7 @@ macro_rules! bind {
             target: $entity,
             reader: |c: &::bevy::prelude::Mut<$cls>| &c.$($prop)+,
             writer: |c: &mut ::bevy::prelude::Mut<$cls>| &mut c.$($prop)+,
-        }.transformed(|tr| tr.$transformer().as_transformer())
+        }.transformed(|s, t, _| $crate::relations::bind::set_property(s, t, |tr| tr.$transformer()))
     };
     // to!(entity, Component | transform)
     (@bind to component $entity:expr, $cls:ty, - , transformable $transformer:ident ) => {
@@ -610,7 +1063,7 @@ macro_rules! bind {
             target: $entity,
             reader: |c: &::bevy::prelude::Mut<$cls>| c.deref(),
             writer: |c: &mut ::bevy::prelude::Mut<$cls>| c.deref_mut(),
-        }.transformed(|tr| tr.$transformer().as_transformer())
+        }.transformed(|s, t, _| $crate::relations::bind::set_property(s, t, |tr| tr.$transformer()))
     }};
     // to!(entity, Component:some.propery | Struct.transformer)
     (@bind to component $entity:expr, $cls:ty, { $($prop:tt)+ }, $transformer:expr) => {
@@ -625,7 +1078,7 @@ macro_rules! bind {
 
 
     (@transform fmt:$val:ident( $($fmt:tt)* ) ) => {
-        |s, mut t| {
+        |s, mut t, _| {
             $crate::relations::bind::deprecated_transformer();
             let $val = s;
             let $val = format!($($fmt)*);
@@ -637,7 +1090,7 @@ macro_rules! bind {
         }
     };
     (@transform fmt.$val:ident( $($fmt:tt)* ) ) => {
-        |s, mut t| {
+        |s, mut t, _| {
             let $val = s;
             let $val = format!($($fmt)*);
             if $val != *t {
@@ -647,21 +1100,26 @@ macro_rules! bind {
             Ok(())
         }
     };
+    (@transform fmt.$name:ident ) => {
+        |s, t, formatters| {
+            $crate::relations::format::format_transformer(stringify!($name), s, t, formatters)
+        }
+    };
     (@transform $converter:ident.$method:ident ) => {
-        |s, t| {
+        |s, t, _| {
             $converter::get_properties().$method().set(t, s);
             Ok(())
         }
     };
     (@transform $converter:ident:$method:ident ) => {
-        |s, t| {
+        |s, t, _| {
             $crate::relations::bind::deprecated_transformer();
             $converter::get_properties().$method().set(t, s);
             Ok(())
         }
     };
     (@transform $arg:pat_param | $filter:expr ) => {
-        |s, t| {
+        |s, mut t, _| {
             let tr = |$arg| $filter;
             let val = tr(s);
             if val != *t {
@@ -750,6 +1208,30 @@ macro_rules! bind {
 
 #[macro_export]
 macro_rules! from {
+    // from!((entity, Component:property), (Resource:property) => |a, b| compute(a, b))
+    ( ($($a:tt)+), ($($b:tt)+) => $compute:expr ) => {
+        $crate::relations::computed::computed2(
+            ($crate::from!($($a)+), $crate::from!($($b)+)),
+            $compute,
+        )
+    };
+    ( ($($a:tt)+), ($($b:tt)+), ($($c:tt)+) => $compute:expr ) => {
+        $crate::relations::computed::computed3(
+            ($crate::from!($($a)+), $crate::from!($($b)+), $crate::from!($($c)+)),
+            $compute,
+        )
+    };
+    ( ($($a:tt)+), ($($b:tt)+), ($($c:tt)+), ($($d:tt)+) => $compute:expr ) => {
+        $crate::relations::computed::computed4(
+            (
+                $crate::from!($($a)+),
+                $crate::from!($($b)+),
+                $crate::from!($($c)+),
+                $crate::from!($($d)+),
+            ),
+            $compute,
+        )
+    };
     ( $($bind:tt)* ) => { $crate::bind!(from $($bind)*) };
 }
 
@@ -758,6 +1240,66 @@ macro_rules! to {
     ( $($bind:tt)* ) => { $crate::bind!(to $($bind)*) };
 }
 
+/// Two-way bind between component properties:
+/// `sync!(a, CompA:field <=> b, CompB:field | transform)`.
+/// Each side may provide associated transformer, it is used for writing
+/// to this side and for reading from it.
+///
+/// The directions may be transformed separately, both transformers are
+/// optional: `sync!(a, CompA:field <=> b, CompB:field | fwd, | back)`
+/// writes to `b` with `fwd` and reads from `b` with `back`.
+#[macro_export]
+macro_rules! sync {
+    (@side $direction:ident { $($side:tt)+ } { }) => {
+        $crate::$direction!($($side)+)
+    };
+    (@side $direction:ident { $($side:tt)+ } { $($transformer:tt)+ }) => {
+        $crate::$direction!($($side)+ | $($transformer)+)
+    };
+    (@bind { $($lhs:tt)+ } { $($rhs:tt)+ } { $($fwd:tt)* } { $($back:tt)* }) => {
+        $crate::relations::bind::SyncBind::new(
+            $crate::from!($($lhs)+) >> $crate::sync!(@side to { $($rhs)+ } { $($fwd)* }),
+            $crate::sync!(@side from { $($rhs)+ } { $($back)* }) >> $crate::to!($($lhs)+),
+        )
+    };
+    // sync!(a <=> b | fwd, | back) and sync!(a <=> b | fwd,)
+    (@fwd $lhs:tt $rhs:tt { $($fwd:tt)+ } , | $($back:tt)+) => {
+        $crate::sync!(@bind $lhs $rhs { $($fwd)+ } { $($back)+ })
+    };
+    (@fwd $lhs:tt $rhs:tt { $($fwd:tt)+ } ,) => {
+        $crate::sync!(@bind $lhs $rhs { $($fwd)+ } { })
+    };
+    // sync!(a <=> b | transform) uses the same transformer both ways
+    (@fwd $lhs:tt $rhs:tt { $($fwd:tt)+ }) => {
+        $crate::sync!(@bind $lhs $rhs { $($fwd)+ } { $($fwd)+ })
+    };
+    (@fwd $lhs:tt $rhs:tt { $($fwd:tt)* } $part:tt $($rest:tt)*) => {
+        $crate::sync!(@fwd $lhs $rhs { $($fwd)* $part } $($rest)*)
+    };
+    // sync!(a <=> b, | back)
+    (@rhs $lhs:tt { $($rhs:tt)+ } , | $($back:tt)+) => {
+        $crate::sync!(@bind $lhs { $($rhs)+ } { } { $($back)+ })
+    };
+    (@rhs $lhs:tt { $($rhs:tt)+ } | $($rest:tt)+) => {
+        $crate::sync!(@fwd $lhs { $($rhs)+ } { } $($rest)+)
+    };
+    (@rhs $lhs:tt { $($rhs:tt)+ }) => {
+        $crate::sync!(@bind $lhs { $($rhs)+ } { } { })
+    };
+    (@rhs $lhs:tt { $($rhs:tt)* } $part:tt $($rest:tt)*) => {
+        $crate::sync!(@rhs $lhs { $($rhs)* $part } $($rest)*)
+    };
+    (@split { $($lhs:tt)+ } <=> $($rhs:tt)+ ) => {
+        $crate::sync!(@rhs { $($lhs)+ } { } $($rhs)+)
+    };
+    (@split { $($lhs:tt)* } $part:tt $($rest:tt)*) => {
+        $crate::sync!(@split { $($lhs)* $part } $($rest)*)
+    };
+    ( $($bind:tt)+ ) => {
+        $crate::sync!(@split { } $($bind)+)
+    };
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -847,12 +1389,21 @@ mod test {
         let _bind = to!(e, HealthBar: color | Color.r) << from!(e, Health: percent());
         let _bind = from!(e, Health: percent() | Color.r) >> to!(e, HealthBar: color);
 
+        let _bind = from!(e, HealthBar: color | r) >> to!(e, Health: current);
+        let _bind = to!(e, Health: current) << from!(e, HealthBar: color | r);
+
         let _bind = from!(e, HealthBar: output) >> to!(e, Btn: mode);
         let _bind = to!(e, Btn: mode) << from!(e, HealthBar: output);
 
         let _bind = btn_bind_from_mode(e) >> to!(e, HealthBar: output);
         let _bind = from!(e, HealthBar: output) >> btn_bind_mode_to(e);
 
+        let _bind = sync!(e, Health: current <=> e, HealthBar: value);
+        let _bind = sync!(e, Health: current <=> e, HealthBar: color | r);
+        let _bind = sync!(e, Health: current <=> e, HealthBar: value | v | v * 2., | v | v / 2.);
+        let _bind = sync!(e, Health: current <=> e, HealthBar: value | v | v * 2.,);
+        let _bind = sync!(e, Health: current <=> e, HealthBar: value, | v | v / 2.);
+
         // resources
         let _bind = from!(Time: elapsed_seconds()) >> to!(e, Health: current);
         let _bind = to!(e, Health: current) << from!(Time: elapsed_seconds());
@@ -928,6 +1479,134 @@ mod test {
         );
     }
 
+    #[test]
+    fn throttled_bind() {
+        let mut app = App::new();
+        app.add_plugins(RelationsPlugin);
+        app.init_resource::<Time>();
+        let player = app.world.spawn(Health::default()).id();
+        let bar = app.world.spawn(HealthBar::default()).id();
+        let bind = (from!(player, Health: current) >> to!(bar, HealthBar: value))
+            .throttle(Duration::from_millis(100));
+        bind.write(&mut app.world);
+        app.update();
+
+        let tick = |app: &mut App, millis: u64, health: Option<f32>| {
+            app.world
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_millis(millis));
+            if let Some(health) = health {
+                app.world.get_mut::<Health>(player).unwrap().current = health;
+            }
+            app.update();
+            app.world.get::<HealthBar>(bar).unwrap().value
+        };
+        assert_eq!(tick(&mut app, 50, Some(10.)), 0., "Write should be postponed");
+        assert_eq!(tick(&mut app, 30, Some(20.)), 0., "Write should be postponed");
+        assert_eq!(tick(&mut app, 30, None), 20., "Latest value should be written");
+        assert_eq!(tick(&mut app, 100, Some(30.)), 30., "Interval passed");
+    }
+
+    #[test]
+    fn recycled_target_drops_postponed_values() {
+        use crate::eml::pool::{self, ElementPool};
+        use bevy::ecs::system::RunSystemOnce;
+        let mut app = App::new();
+        app.add_plugins(RelationsPlugin);
+        app.init_resource::<Time>();
+        app.init_resource::<ElementPool>();
+        let player = app.world.spawn(Health::default()).id();
+        let bar = app.world.spawn(HealthBar::default()).id();
+        let throttled = |bar| {
+            (from!(player, Health: current) >> to!(bar, HealthBar: value))
+                .throttle(Duration::from_millis(100))
+        };
+        throttled(bar).write(&mut app.world);
+        app.update();
+
+        let tick = |app: &mut App, millis: u64| {
+            app.world
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_millis(millis));
+            app.update();
+        };
+        app.world.get_mut::<Health>(player).unwrap().current = 20.;
+        tick(&mut app, 50);
+        assert_eq!(app.world.get::<HealthBar>(bar).unwrap().value, 0.);
+
+        pool::recycle(&mut app.world, bar);
+        tick(&mut app, 10);
+        app.world.run_system_once(pool::release_parked_elements_system);
+        let reused = pool::spawn(&mut app.world);
+        assert_eq!(reused, bar);
+        app.world.entity_mut(reused).insert(HealthBar::default());
+        throttled(reused).write(&mut app.world);
+
+        tick(&mut app, 100);
+        assert_eq!(
+            app.world.get::<HealthBar>(reused).unwrap().value,
+            0.,
+            "Value postponed for the recycled element should be dropped"
+        );
+    }
+
+    #[test]
+    fn eased_bind() {
+        let mut app = App::new();
+        app.add_plugins(RelationsPlugin);
+        app.init_resource::<Time>();
+        let player = app.world.spawn(Health::default()).id();
+        let bar = app.world.spawn(HealthBar::default()).id();
+        let bind = from!(player, Health: current).ease(Ease::Linear, 0.5)
+            >> to!(bar, HealthBar: value);
+        bind.write(&mut app.world);
+        app.update();
+
+        let tick = |app: &mut App, millis: u64, health: Option<f32>| {
+            app.world
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_millis(millis));
+            if let Some(health) = health {
+                app.world.get_mut::<Health>(player).unwrap().current = health;
+            }
+            app.update();
+            app.world.get::<HealthBar>(bar).unwrap().value
+        };
+        assert_eq!(tick(&mut app, 10, Some(10.)), 0., "Easing should start from 0");
+        assert_eq!(tick(&mut app, 250, None), 5., "Half of the way");
+        assert_eq!(tick(&mut app, 125, Some(0.)), 5., "Should continue from the current value");
+        assert_eq!(tick(&mut app, 250, None), 2.5, "Half of the way back");
+        assert_eq!(tick(&mut app, 300, None), 0., "Easing finished");
+    }
+
+    #[test]
+    fn idle_binds_report_no_changes() {
+        let mut app = App::new();
+        app.add_plugins(RelationsPlugin);
+        let pairs: Vec<_> = (0..100)
+            .map(|_| {
+                let player = app.world.spawn(Health::default()).id();
+                let bar = app.world.spawn(HealthBar::default()).id();
+                let bind = from!(player, Health: current) >> to!(bar, HealthBar: value);
+                bind.write(&mut app.world);
+                (player, bar)
+            })
+            .collect();
+        app.update();
+        app.update();
+
+        let state = app.world.resource::<ChangesState>().get();
+        app.update();
+        assert_eq!(state, app.world.resource::<ChangesState>().get());
+
+        let (player, bar) = pairs[42];
+        app.world.get_mut::<Health>(player).unwrap().current = 5.;
+        app.update();
+        assert!(app.world.resource::<ChangesState>().get() > state);
+        assert_eq!(app.world.get::<HealthBar>(bar).unwrap().value, 5.);
+        assert_eq!(app.world.get::<HealthBar>(pairs[0].1).unwrap().value, 0.);
+    }
+
     #[test]
     fn chain_bind() {
         let mut app = App::new();
@@ -963,4 +1642,53 @@ mod test {
             "Chained values should be equals after single update"
         );
     }
+
+    #[test]
+    fn sync_bind() {
+        let mut app = App::new();
+        app.add_plugins(RelationsPlugin);
+
+        let player = app.world.spawn(Health::default()).id();
+        let bar = app.world.spawn(HealthBar::default()).id();
+        sync!(player, Health: current <=> bar, HealthBar: value).write(&mut app.world);
+        app.update();
+
+        app.world.get_mut::<Health>(player).unwrap().current = 20.;
+        app.update();
+        assert_eq!(
+            app.world.get::<HealthBar>(bar).unwrap().value,
+            20.,
+            "Target should follow the source after single update"
+        );
+
+        app.world.get_mut::<HealthBar>(bar).unwrap().value = 30.;
+        app.update();
+        assert_eq!(
+            app.world.get::<Health>(player).unwrap().current,
+            30.,
+            "Source should follow the target after single update"
+        );
+    }
+
+    #[test]
+    fn sync_bind_transforms_directions_separately() {
+        let mut app = App::new();
+        app.add_plugins(RelationsPlugin);
+
+        let player = app.world.spawn(Health::default()).id();
+        let bar = app.world.spawn(HealthBar::default()).id();
+        sync!(player, Health: current <=> bar, HealthBar: value | v | v * 2., | v | v / 2.)
+            .write(&mut app.world);
+        app.update();
+
+        app.world.get_mut::<Health>(player).unwrap().current = 20.;
+        app.update();
+        assert_eq!(app.world.get::<HealthBar>(bar).unwrap().value, 40.);
+        assert_eq!(app.world.get::<Health>(player).unwrap().current, 20.);
+
+        app.world.get_mut::<HealthBar>(bar).unwrap().value = 30.;
+        app.update();
+        assert_eq!(app.world.get::<Health>(player).unwrap().current, 15.);
+        assert_eq!(app.world.get::<HealthBar>(bar).unwrap().value, 30.);
+    }
 }
@@ -0,0 +1,383 @@
+use super::{
+    bind::{
+        bind_id, BindableSource, BindableTarget, FromComponent, FromResource, ToComponent,
+        ToComponentWithoutTransformer, TransformationError, TransformationResult,
+    },
+    format::Formatters,
+    props::{try_transform, Prop},
+    RelationsSystems,
+};
+use crate::eml::pool::Pooled;
+use bevy::{ecs::entity::Entities, ecs::system::Command, prelude::*};
+use std::any::TypeId;
+use tagstr::Tag;
+
+pub type ComputeFn<V, T> = Box<dyn Fn(&V) -> Option<T> + Send + Sync>;
+
+/// The single source of computed bind: `from!(entity, Component:property)`
+/// or `from!(Resource:property)` without transformer.
+pub trait ComputedSource: Send + Sync + 'static {
+    type Value: BindableSource + PartialEq;
+    fn id(&self) -> Tag;
+    fn bind_computed<W: Component>(
+        self,
+        world: &mut World,
+        to: ToComponent<W, Self::Value, Option<Self::Value>>,
+    );
+}
+
+impl<R: Component, S: BindableSource + PartialEq> ComputedSource for FromComponent<R, S> {
+    type Value = S;
+    fn id(&self) -> Tag {
+        self.id
+    }
+    fn bind_computed<W: Component>(self, world: &mut World, to: ToComponent<W, S, Option<S>>) {
+        self.bind_component(to).write(world);
+    }
+}
+
+impl<R: Resource, S: BindableSource + PartialEq> ComputedSource for FromResource<R, S> {
+    type Value = S;
+    fn id(&self) -> Tag {
+        self.id
+    }
+    fn bind_computed<W: Component>(self, world: &mut World, to: ToComponent<W, S, Option<S>>) {
+        self.bind_component(to).write(world);
+    }
+}
+
+/// Tuple of [`ComputedSource`]s.
+pub trait ComputedSources: Send + Sync + 'static {
+    type Values: Default + Send + Sync + 'static;
+    fn id(&self) -> Tag;
+    fn write<T: BindableSource>(self, world: &mut World, helper: Entity);
+}
+
+fn set_computed_source<S: BindableSource + PartialEq>(
+    source: &S,
+    mut value: Prop<Option<S>>,
+    _: &Formatters,
+) -> TransformationResult {
+    if value.as_ref() != Some(source) {
+        *value = Some(source.clone());
+    }
+    Ok(())
+}
+
+/// Keeps the last values of the computed bind sources and the computed value.
+/// It lives on the separate entity and despawns with the bind target.
+#[derive(Component)]
+pub struct ComputedValue<V, T> {
+    values: V,
+    value: T,
+    compute: ComputeFn<V, T>,
+    target: Entity,
+}
+
+macro_rules! impl_computed {
+    ($func:ident, $($src:ident $val:ident $idx:tt),+) => {
+        impl<$($src: ComputedSource),+> ComputedSources for ($($src,)+) {
+            type Values = ($(Option<$src::Value>,)+);
+            fn id(&self) -> Tag {
+                Tag::new(format!(
+                    "computed({})",
+                    [$(self.$idx.id().to_string()),+].join(",")
+                ))
+            }
+            fn write<T: BindableSource>(self, world: &mut World, helper: Entity) {
+                $(
+                    self.$idx.bind_computed(world, ToComponent {
+                        id: bind_id::<ComputedValue<Self::Values, T>>(stringify!($idx)),
+                        target: helper,
+                        transformer: set_computed_source::<$src::Value>,
+                        reader: |c: &Mut<ComputedValue<Self::Values, T>>| &c.values.$idx,
+                        writer: |c: &mut Mut<ComputedValue<Self::Values, T>>| {
+                            &mut c.values.$idx
+                        },
+                    });
+                )+
+            }
+        }
+
+        /// Creates computed bind source, used by `from!((..), (..) => |a, b| ..)` form.
+        pub fn $func<$($src: ComputedSource,)+ T, F>(
+            sources: ($($src,)+),
+            compute: F,
+        ) -> ComputedFrom<($($src,)+), T>
+        where
+            T: BindableSource + Default + PartialEq,
+            F: Fn($(&$src::Value),+) -> T + Send + Sync + 'static,
+        {
+            ComputedFrom {
+                sources,
+                compute: Box::new(move |values: &($(Option<$src::Value>,)+)| {
+                    let ($(Some($val),)+) = values else {
+                        return None;
+                    };
+                    Some(compute($($val),+))
+                }),
+            }
+        }
+    };
+}
+
+impl_computed!(computed2, A a 0, B b 1);
+impl_computed!(computed3, A a 0, B b 1, C c 2);
+impl_computed!(computed4, A a 0, B b 1, C c 2, D d 3);
+
+/// The source of the bind which value is computed from several other sources:
+/// ```rust,ignore
+/// from!((player, Health:current), (player, Health:max) => |c, m| format!("{c}/{m}"))
+///     >> to!(label, Label:value)
+/// ```
+/// The value is re-evaluated when any of the sources changes and only when
+/// all of the sources provided their values.
+pub struct ComputedFrom<S: ComputedSources, T> {
+    sources: S,
+    compute: ComputeFn<S::Values, T>,
+}
+
+impl<S: ComputedSources, T: BindableSource + Default + PartialEq> ComputedFrom<S, T> {
+    pub fn bind_component<W: Component, U: BindableTarget>(
+        self,
+        to: ToComponent<W, T, U>,
+    ) -> ComputedToComponent<S, W, T, U> {
+        ComputedToComponent { from: self, to }
+    }
+}
+
+pub struct ComputedToComponent<
+    S: ComputedSources,
+    W: Component,
+    T: BindableSource,
+    U: BindableTarget,
+> {
+    from: ComputedFrom<S, T>,
+    to: ToComponent<W, T, U>,
+}
+
+impl<S, W, T, U> ComputedToComponent<S, W, T, U>
+where
+    S: ComputedSources,
+    W: Component,
+    T: BindableSource + Default + PartialEq,
+    U: BindableTarget,
+{
+    pub fn write(self, world: &mut World) {
+        world.resource::<RelationsSystems>().add_custom_system(
+            TypeId::of::<ComputedValue<S::Values, T>>(),
+            compute_values_system::<S::Values, T>,
+        );
+        let id = self.from.sources.id();
+        let helper = world
+            .spawn(ComputedValue {
+                values: S::Values::default(),
+                value: T::default(),
+                compute: self.from.compute,
+                target: self.to.target,
+            })
+            .id();
+        self.from.sources.write::<T>(world, helper);
+        FromComponent {
+            id,
+            source: helper,
+            reader: |c: &ComputedValue<S::Values, T>| c.value.clone(),
+            ease: None,
+        }
+        .bind_component(self.to)
+        .write(world);
+    }
+}
+
+impl<S, W, T, U> Command for ComputedToComponent<S, W, T, U>
+where
+    S: ComputedSources,
+    W: Component,
+    T: BindableSource + Default + PartialEq,
+    U: BindableTarget,
+{
+    fn apply(self, world: &mut World) {
+        self.write(world);
+    }
+}
+
+/// Re-evaluates the changed computed values and despawns the computed values
+/// which targets are despawned or recycled.
+pub fn compute_values_system<V: Send + Sync + 'static, T: BindableSource + PartialEq>(
+    mut commands: Commands,
+    entities: &Entities,
+    pooled: Query<(), With<Pooled>>,
+    mut values: Query<(Entity, &mut ComputedValue<V, T>)>,
+) {
+    for (entity, mut computed) in values.iter_mut() {
+        if !entities.contains(computed.target) || pooled.contains(computed.target) {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        if !computed.is_changed() {
+            continue;
+        }
+        let Some(value) = (computed.compute)(&computed.values) else {
+            continue;
+        };
+        if value != computed.value {
+            computed.value = value;
+        }
+    }
+}
+
+// from!((..), (..) => compute) >> to!(entity, Component:property | filter)
+impl<S, W, T, U> std::ops::Shr<ToComponent<W, T, U>> for ComputedFrom<S, T>
+where
+    S: ComputedSources,
+    W: Component,
+    T: BindableSource + Default + PartialEq,
+    U: BindableTarget,
+{
+    type Output = ComputedToComponent<S, W, T, U>;
+    fn shr(self, to: ToComponent<W, T, U>) -> Self::Output {
+        self.bind_component(to)
+    }
+}
+
+// to!(entity, Component:property | filter) << from!((..), (..) => compute)
+impl<S, W, T, U> std::ops::Shl<ComputedFrom<S, T>> for ToComponent<W, T, U>
+where
+    S: ComputedSources,
+    W: Component,
+    T: BindableSource + Default + PartialEq,
+    U: BindableTarget,
+{
+    type Output = ComputedToComponent<S, W, T, U>;
+    fn shl(self, from: ComputedFrom<S, T>) -> Self::Output {
+        from.bind_component(self)
+    }
+}
+
+// from!((..), (..) => compute) >> to!(entity, Component:property)
+impl<S, W, T, U, E> std::ops::Shr<ToComponentWithoutTransformer<W, U>> for ComputedFrom<S, T>
+where
+    E: Into<TransformationError>,
+    S: ComputedSources,
+    W: Component,
+    T: BindableSource + Default + PartialEq,
+    U: BindableTarget + TryFrom<T, Error = E>,
+{
+    type Output = ComputedToComponent<S, W, T, U>;
+    fn shr(self, to: ToComponentWithoutTransformer<W, U>) -> Self::Output {
+        self.bind_component(ToComponent {
+            id: to.id,
+            target: to.target,
+            reader: to.reader,
+            writer: to.writer,
+            transformer: try_transform::<T, U, E>,
+        })
+    }
+}
+
+// to!(entity, Component:property) << from!((..), (..) => compute)
+impl<S, W, T, U, E> std::ops::Shl<ComputedFrom<S, T>> for ToComponentWithoutTransformer<W, U>
+where
+    E: Into<TransformationError>,
+    S: ComputedSources,
+    W: Component,
+    T: BindableSource + Default + PartialEq,
+    U: BindableTarget + TryFrom<T, Error = E>,
+{
+    type Output = ComputedToComponent<S, W, T, U>;
+    fn shl(self, from: ComputedFrom<S, T>) -> Self::Output {
+        from.bind_component(ToComponent {
+            id: self.id,
+            target: self.target,
+            reader: self.reader,
+            writer: self.writer,
+            transformer: try_transform::<T, U, E>,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::relations::RelationsPlugin;
+    use crate::*;
+    use bevy::prelude::*;
+
+    #[derive(Component, Default)]
+    struct Health {
+        max: f32,
+        current: f32,
+    }
+
+    #[derive(Component, Default)]
+    struct HealthBar {
+        output: String,
+    }
+
+    #[test]
+    fn computed_from_two_sources() {
+        let mut app = App::new();
+        app.add_plugins(RelationsPlugin);
+
+        let player = app.world.spawn(Health::default()).id();
+        let bar = app.world.spawn(HealthBar::default()).id();
+        let bind = from!(
+            (player, Health: current),
+            (player, Health: max) => |c, m| format!("{c}/{m}")
+        ) >> to!(bar, HealthBar: output);
+        bind.write(&mut app.world);
+        app.update();
+
+        let mut health = app.world.get_mut::<Health>(player).unwrap();
+        health.current = 20.;
+        health.max = 50.;
+        app.update();
+        assert_eq!(app.world.get::<HealthBar>(bar).unwrap().output, "20/50");
+
+        app.world.get_mut::<Health>(player).unwrap().max = 40.;
+        app.update();
+        assert_eq!(app.world.get::<HealthBar>(bar).unwrap().output, "20/40");
+
+        app.world.despawn(bar);
+        app.update();
+        app.update();
+        assert_eq!(
+            app.world.iter_entities().count(),
+            1,
+            "Computed value should be despawned with the target"
+        );
+    }
+
+    #[test]
+    fn computed_value_dropped_with_recycled_target() {
+        use crate::eml::pool::{self, ElementPool};
+        use bevy::ecs::system::RunSystemOnce;
+        let mut app = App::new();
+        app.add_plugins(RelationsPlugin);
+        app.init_resource::<ElementPool>();
+
+        let player = app.world.spawn(Health::default()).id();
+        let bar = app.world.spawn(HealthBar::default()).id();
+        let bind = from!(
+            (player, Health: current),
+            (player, Health: max) => |c, m| format!("{c}/{m}")
+        ) >> to!(bar, HealthBar: output);
+        bind.write(&mut app.world);
+        app.update();
+
+        pool::recycle(&mut app.world, bar);
+        app.update();
+        app.world.run_system_once(pool::release_parked_elements_system);
+        let reused = pool::spawn(&mut app.world);
+        assert_eq!(reused, bar);
+        app.world.entity_mut(reused).insert(HealthBar::default());
+
+        app.world.get_mut::<Health>(player).unwrap().max = 40.;
+        app.update();
+        assert_eq!(app.world.get::<HealthBar>(reused).unwrap().output, "");
+        assert_eq!(
+            app.world.iter_entities().count(),
+            2,
+            "Computed value should be despawned with the recycled target"
+        );
+    }
+}
@@ -1,8 +1,12 @@
-use crate::{element::Elements, relations::RelationsSystems};
+use crate::{
+    element::Elements,
+    relations::registry::{ConnectionRecord, RelationsRegistry},
+    relations::RelationsSystems,
+};
 use bevy::{
     ecs::{
         query::{QueryData, QueryItem, WorldQuery},
-        system::{Command, EntityCommands},
+        system::{Command, EntityCommands, SystemParam, SystemParamItem},
     },
     prelude::*,
     utils::HashMap,
@@ -59,14 +63,18 @@ impl<E: Event> EventFilter<E> {
         Connection {
             target: None,
             source: None,
-            handler: Handler(Box::new(move |ctx, _| func(ctx))),
+            handler: Handler(Box::new(move |ctx, _, _| func(ctx))),
             filter: self,
         }
     }
-    pub fn handle<Q: WorldQuery, F: 'static + Fn(&mut EventContext<E>, &mut QueryItem<Q>)>(
+    pub fn handle<
+        Q: WorldQuery,
+        S: SystemParam,
+        F: 'static + Fn(&mut EventContext<E>, &mut QueryItem<Q>, &mut SystemParamItem<S>),
+    >(
         self,
-        (_, target, handler): (PhantomData<Q>, Option<Entity>, F),
-    ) -> Connection<Q, E> {
+        (_, target, handler): (PhantomData<(Q, S)>, Option<Entity>, F),
+    ) -> Connection<Q, E, S> {
         Connection {
             target,
             source: None,
@@ -179,16 +187,26 @@ impl<'a, 'w, 's, E: Event> DerefMut for EventContext<'a, 'w, 's, E> {
     }
 }
 
-pub struct Handler<Q: WorldQuery, E: Event>(Box<dyn Fn(&mut EventContext<E>, &mut QueryItem<Q>)>);
-impl<Q: 'static + WorldQuery, E: Event> Handler<Q, E> {
-    pub fn run(&self, ctx: &mut EventContext<E>, args: &mut QueryItem<Q>) {
-        self.0(ctx, args)
+/// The connected function: receives the event context, the components of
+/// the target entity queried by `Q` and the system params `S`.
+pub struct Handler<Q: WorldQuery, E: Event, S: SystemParam = ()>(
+    #[allow(clippy::type_complexity)]
+    Box<dyn Fn(&mut EventContext<E>, &mut QueryItem<Q>, &mut SystemParamItem<S>)>,
+);
+impl<Q: 'static + WorldQuery, E: Event, S: SystemParam> Handler<Q, E, S> {
+    pub fn run(
+        &self,
+        ctx: &mut EventContext<E>,
+        args: &mut QueryItem<Q>,
+        params: &mut SystemParamItem<S>,
+    ) {
+        self.0(ctx, args, params)
     }
 
-    pub fn run_without_target(&self, ctx: &mut EventContext<E>) {
+    pub fn run_without_target(&self, ctx: &mut EventContext<E>, params: &mut SystemParamItem<S>) {
         let empty = &mut () as &mut dyn Any;
         if let Some(empty) = empty.downcast_mut::<QueryItem<Q>>() {
-            self.0(ctx, empty)
+            self.0(ctx, empty, params)
         } else {
             warn!(
                 "Can't invoke eventhandler without target for Handler<{}, {}>",
@@ -199,17 +217,17 @@ impl<Q: 'static + WorldQuery, E: Event> Handler<Q, E> {
     }
 }
 
-unsafe impl<Q: WorldQuery, E: Event> Send for Handler<Q, E> {}
-unsafe impl<Q: WorldQuery, E: Event> Sync for Handler<Q, E> {}
+unsafe impl<Q: WorldQuery, E: Event, S: SystemParam> Send for Handler<Q, E, S> {}
+unsafe impl<Q: WorldQuery, E: Event, S: SystemParam> Sync for Handler<Q, E, S> {}
 
-pub struct Connection<Q: WorldQuery, E: Event> {
+pub struct Connection<Q: WorldQuery, E: Event, S: SystemParam = ()> {
     pub(crate) source: Option<Entity>,
     pub(crate) target: Option<Entity>,
-    pub(crate) handler: Handler<Q, E>,
+    pub(crate) handler: Handler<Q, E, S>,
     pub(crate) filter: EventFilter<E>,
 }
 
-impl<Q: 'static + QueryData, E: Event> Connection<Q, E> {
+impl<Q: 'static + QueryData, E: Event, S: 'static + SystemParam> Connection<Q, E, S> {
     // pub fn handles(&self, event: &E) -> bool {
     //     (self.filter)(event)
     // }
@@ -222,29 +240,40 @@ impl<Q: 'static + QueryData, E: Event> Connection<Q, E> {
     pub fn write(self, world: &mut World) {
         world
             .resource::<RelationsSystems>()
-            .add_signals_processor::<Q, E>();
-        let mut connections = world.get_resource_or_insert_with(Connections::<Q, E>::default);
+            .add_signals_processor::<Q, E, S>();
+        world
+            .get_resource_or_insert_with(RelationsRegistry::default)
+            .add_connection(ConnectionRecord {
+                source: self.source,
+                target: self.target.or(self.source),
+                event: type_name::<E>(),
+            });
+        let mut connections = world.get_resource_or_insert_with(Connections::<Q, E, S>::default);
         connections.add(self);
     }
 }
 
-impl<Q: 'static + QueryData, E: Event> Command for Connection<Q, E> {
+impl<Q: 'static + QueryData, E: Event, S: 'static + SystemParam> Command
+    for Connection<Q, E, S>
+{
     fn apply(self, world: &mut World) {
         self.write(world);
     }
 }
 
 #[derive(Resource, Deref, DerefMut)]
-pub struct Connections<Q: WorldQuery, E: Event>(HashMap<EventFilter<E>, EntityConnections<Q, E>>);
+pub struct Connections<Q: WorldQuery, E: Event, S: SystemParam = ()>(
+    HashMap<EventFilter<E>, EntityConnections<Q, E, S>>,
+);
 
-impl<Q: WorldQuery, E: Event> Default for Connections<Q, E> {
+impl<Q: WorldQuery, E: Event, S: SystemParam> Default for Connections<Q, E, S> {
     fn default() -> Self {
         Connections(HashMap::new())
     }
 }
 
-impl<Q: 'static + WorldQuery, E: Event> Connections<Q, E> {
-    pub fn process<F: FnMut(&Vec<(Option<Entity>, Handler<Q, E>)>)>(
+impl<Q: 'static + WorldQuery, E: Event, S: SystemParam> Connections<Q, E, S> {
+    pub fn process<F: FnMut(&Vec<(Option<Entity>, Handler<Q, E, S>)>)>(
         &self,
         event: &E,
         mut processor: F,
@@ -267,7 +296,7 @@ impl<Q: 'static + WorldQuery, E: Event> Connections<Q, E> {
             }
         }
     }
-    pub fn add(&mut self, connection: Connection<Q, E>) {
+    pub fn add(&mut self, connection: Connection<Q, E, S>) {
         let source = connection.source;
         let filter = connection.filter;
         let handler = connection.handler;
@@ -332,19 +361,20 @@ impl<Q: 'static + WorldQuery, E: Event> Connections<Q, E> {
     }
 }
 
-pub struct EntityConnections<Q: WorldQuery, E: Event> {
-    pub(crate) sources: HashMap<Option<Entity>, Vec<(Option<Entity>, Handler<Q, E>)>>,
+pub struct EntityConnections<Q: WorldQuery, E: Event, S: SystemParam = ()> {
+    #[allow(clippy::type_complexity)]
+    pub(crate) sources: HashMap<Option<Entity>, Vec<(Option<Entity>, Handler<Q, E, S>)>>,
     pub(crate) targets: HashMap<Option<Entity>, Vec<Option<Entity>>>,
 }
 
-impl<Q: WorldQuery, E: Event> Deref for EntityConnections<Q, E> {
-    type Target = HashMap<Option<Entity>, Vec<(Option<Entity>, Handler<Q, E>)>>;
+impl<Q: WorldQuery, E: Event, S: SystemParam> Deref for EntityConnections<Q, E, S> {
+    type Target = HashMap<Option<Entity>, Vec<(Option<Entity>, Handler<Q, E, S>)>>;
     fn deref(&self) -> &Self::Target {
         &self.sources
     }
 }
 
-impl<Q: WorldQuery, E: Event> Default for EntityConnections<Q, E> {
+impl<Q: WorldQuery, E: Event, S: SystemParam> Default for EntityConnections<Q, E, S> {
     fn default() -> Self {
         EntityConnections {
             sources: Default::default(),
@@ -382,13 +412,17 @@ impl<E: Event> ConnectEvent<E> {
             target: None,
             source: None,
             filter: self.0,
-            handler: Handler(Box::new(move |ctx, _| func(ctx))),
+            handler: Handler(Box::new(move |ctx, _, _| func(ctx))),
         }
     }
-    pub fn to_handler<Q: WorldQuery, F: 'static + Fn(&mut EventContext<E>, &mut QueryItem<Q>)>(
+    pub fn to_handler<
+        Q: WorldQuery,
+        S: SystemParam,
+        F: 'static + Fn(&mut EventContext<E>, &mut QueryItem<Q>, &mut SystemParamItem<S>),
+    >(
         self,
-        (_, target, handler): (PhantomData<Q>, Option<Entity>, F),
-    ) -> Connection<Q, E> {
+        (_, target, handler): (PhantomData<(Q, S)>, Option<Entity>, F),
+    ) -> Connection<Q, E, S> {
         Connection {
             target,
             source: None,
@@ -405,13 +439,17 @@ impl<E: Event> ConnectEntityTo<E> {
             target: None,
             source: Some(self.0),
             filter: self.1,
-            handler: Handler(Box::new(move |ctx, _| func(ctx))),
+            handler: Handler(Box::new(move |ctx, _, _| func(ctx))),
         }
     }
-    pub fn handle<Q: WorldQuery, F: 'static + Fn(&mut EventContext<E>, &mut QueryItem<Q>)>(
+    pub fn handle<
+        Q: WorldQuery,
+        S: SystemParam,
+        F: 'static + Fn(&mut EventContext<E>, &mut QueryItem<Q>, &mut SystemParamItem<S>),
+    >(
         self,
-        (_, target, handler): (PhantomData<Q>, Option<Entity>, F),
-    ) -> Connection<Q, E> {
+        (_, target, handler): (PhantomData<(Q, S)>, Option<Entity>, F),
+    ) -> Connection<Q, E, S> {
         Connection {
             target,
             source: Some(self.0),
@@ -463,15 +501,16 @@ impl<'w, 's, 'a, E: Event> ConnectCommands<'w, 's, 'a, WorldEvent<E>> {
             target: None,
             source: None,
             filter: EventFilter::World(self.data),
-            handler: Handler::<(), E>(Box::new(move |ctx, _| func(ctx))),
+            handler: Handler::<(), E, ()>(Box::new(move |ctx, _, _| func(ctx))),
         })
     }
     pub fn to_handler<
         Q: 'static + QueryData,
-        F: 'static + Fn(&mut EventContext<E>, &mut QueryItem<Q>),
+        S: 'static + SystemParam,
+        F: 'static + Fn(&mut EventContext<E>, &mut QueryItem<Q>, &mut SystemParamItem<S>),
     >(
         self,
-        (_, target, handler): (PhantomData<Q>, Option<Entity>, F),
+        (_, target, handler): (PhantomData<(Q, S)>, Option<Entity>, F),
     ) {
         self.commands.add(Connection {
             target,
@@ -502,16 +541,17 @@ impl<'w, 's, 'a, E: Event> ConnectCommands<'w, 's, 'a, (Entity, EventFilter<E>)>
             filter,
             target: None,
             source: Some(entity),
-            handler: Handler::<(), E>(Box::new(move |ctx, _| func(ctx))),
+            handler: Handler::<(), E, ()>(Box::new(move |ctx, _, _| func(ctx))),
         })
     }
 
     pub fn handle<
         Q: 'static + QueryData,
-        F: 'static + Fn(&mut EventContext<E>, &mut QueryItem<Q>),
+        S: 'static + SystemParam,
+        F: 'static + Fn(&mut EventContext<E>, &mut QueryItem<Q>, &mut SystemParamItem<S>),
     >(
         self,
-        (_, target, handler): (PhantomData<Q>, Option<Entity>, F),
+        (_, target, handler): (PhantomData<(Q, S)>, Option<Entity>, F),
     ) {
         let (entity, filter) = self.data;
         self.commands.add(Connection {
@@ -522,3 +562,49 @@ impl<'w, 's, 'a, E: Event> ConnectCommands<'w, 's, 'a, (Entity, EventFilter<E>)>
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{element::ElementIdIndex, relations::RelationsPlugin};
+
+    #[derive(Event)]
+    struct Ping;
+
+    #[derive(Event)]
+    struct Pong(usize);
+
+    #[derive(Resource, Default)]
+    struct Score(usize);
+
+    #[test]
+    fn handler_with_system_params() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), RelationsPlugin));
+        app.init_resource::<ElementIdIndex>();
+        app.init_resource::<Score>();
+        app.add_event::<Ping>();
+        app.add_event::<Pong>();
+
+        // the same as run!(|_, score: ResMut<Score>, pong: EventWriter<Pong>| { .. })
+        EventFilter::<Ping>::world(|_| true)
+            .handle((
+                PhantomData::<((), (ResMut<Score>, EventWriter<Pong>))>,
+                None,
+                |_, _, (score, pong)| {
+                    score.0 += 1;
+                    pong.send(Pong(score.0));
+                },
+            ))
+            .write(&mut app.world);
+
+        app.world.send_event(Ping);
+        app.update();
+        app.world.send_event(Ping);
+        app.update();
+        assert_eq!(app.world.resource::<Score>().0, 2);
+        let pongs = app.world.resource::<Events<Pong>>();
+        let last = pongs.get_reader().read(pongs).last().map(|p| p.0);
+        assert_eq!(last, Some(2));
+    }
+}
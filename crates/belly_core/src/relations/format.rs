@@ -0,0 +1,149 @@
+use super::{
+    bind::{TransformationError, TransformationResult},
+    props::Prop,
+};
+use bevy::{prelude::*, utils::HashMap};
+use std::{
+    any::{type_name, Any, TypeId},
+    sync::{Arc, RwLock},
+};
+use tagstr::Tag;
+
+type FormatFn = Arc<dyn Fn(&dyn Any) -> Option<String> + Send + Sync>;
+
+/// Named format transformers registered by the application with
+/// [`RegisterTransformer::register_transformer`]. They are resolved at
+/// runtime, so the same transformer can be used from `from!` and `to!`
+/// macros (`|fmt.duration`) and from the `.eml` assets.
+#[derive(Resource, Clone, Default)]
+pub struct Formatters(Arc<RwLock<HashMap<(Tag, TypeId), FormatFn>>>);
+
+impl Formatters {
+    /// Registers the format transformer `name` for the values of type `S`.
+    /// The same name can be registered for the different types.
+    pub fn register<S, F>(&self, name: &str, format: F)
+    where
+        S: 'static,
+        F: Fn(&S) -> String + Send + Sync + 'static,
+    {
+        let format: FormatFn =
+            Arc::new(move |value: &dyn Any| value.downcast_ref::<S>().map(&format));
+        self.0
+            .write()
+            .unwrap()
+            .insert((Tag::new(name), TypeId::of::<S>()), format);
+    }
+
+    /// Formats the `value` with the registered format transformer `name`.
+    /// Returns `None` if there is no such transformer for the type of `value`.
+    pub fn format(&self, name: &str, value: &dyn Any) -> Option<String> {
+        let format = self
+            .0
+            .read()
+            .unwrap()
+            .get(&(Tag::new(name), value.type_id()))
+            .cloned()?;
+        format(value)
+    }
+
+    /// Returns `true` if the format transformer `name` is registered for any type.
+    pub fn contains(&self, name: &str) -> bool {
+        let name = Tag::new(name);
+        self.0.read().unwrap().keys().any(|(n, _)| *n == name)
+    }
+}
+
+pub trait RegisterTransformer {
+    /// Registers the named format transformer in the [`Formatters`]:
+    /// ```rust,ignore
+    /// app.register_transformer("duration", |secs: &f32| {
+    ///     format!("{}:{:02}", *secs as u32 / 60, *secs as u32 % 60)
+    /// });
+    /// // later
+    /// from!(ability, Cooldown:left|fmt.duration) >> to!(label, Label:value)
+    /// ```
+    fn register_transformer<S, F>(&mut self, name: &str, format: F) -> &mut Self
+    where
+        S: 'static,
+        F: Fn(&S) -> String + Send + Sync + 'static;
+}
+
+impl RegisterTransformer for App {
+    fn register_transformer<S, F>(&mut self, name: &str, format: F) -> &mut Self
+    where
+        S: 'static,
+        F: Fn(&S) -> String + Send + Sync + 'static,
+    {
+        self.world
+            .get_resource_or_insert_with(Formatters::default)
+            .register(name, format);
+        self
+    }
+}
+
+/// Transformer of the `|fmt.name` form: formats the source with the
+/// format transformer `name` registered in the app `formatters`.
+pub fn format_transformer<S: 'static>(
+    name: &str,
+    source: &S,
+    mut target: Prop<String>,
+    formatters: &Formatters,
+) -> TransformationResult {
+    let Some(value) = formatters.format(name, source) else {
+        return Err(TransformationError::new(format!(
+            "Format transformer `{name}` is not registered for {}",
+            type_name::<S>()
+        )));
+    };
+    if *target != value {
+        *target = value;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::relations::RelationsPlugin;
+    use crate::*;
+
+    #[derive(Component, Default)]
+    struct Cooldown {
+        left: f32,
+    }
+
+    #[derive(Component, Default)]
+    struct Label {
+        value: String,
+    }
+
+    #[test]
+    fn registered_format_transformer() {
+        let mut app = App::new();
+        app.add_plugins(RelationsPlugin);
+        app.register_transformer("test_duration", |secs: &f32| {
+            format!("{}:{:02}", *secs as u32 / 60, *secs as u32 % 60)
+        });
+
+        let e = app.world.spawn((Cooldown::default(), Label::default())).id();
+        let bind = from!(e, Cooldown: left | fmt.test_duration) >> to!(e, Label: value);
+        bind.write(&mut app.world);
+        app.update();
+
+        app.world.get_mut::<Cooldown>(e).unwrap().left = 75.;
+        app.update();
+        assert_eq!(app.world.get::<Label>(e).unwrap().value, "1:15");
+        let formatters = app.world.resource::<Formatters>();
+        assert_eq!(formatters.format("test_duration", &5_u32), None);
+
+        // the formatters belong to the app they are registered with
+        let mut other = App::new();
+        other.add_plugins(RelationsPlugin);
+        let e = other.world.spawn((Cooldown::default(), Label::default())).id();
+        let bind = from!(e, Cooldown: left | fmt.test_duration) >> to!(e, Label: value);
+        bind.write(&mut other.world);
+        other.world.get_mut::<Cooldown>(e).unwrap().left = 75.;
+        other.update();
+        assert_eq!(other.world.get::<Label>(e).unwrap().value, "");
+    }
+}
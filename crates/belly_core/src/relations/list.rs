@@ -0,0 +1,290 @@
+use super::{
+    bind::{bind_id, BindableSource, FromComponent, ToComponent, TransformationResult},
+    format::Formatters,
+    props::Prop,
+    RelationsSystems,
+};
+use crate::eml::{
+    pool::{ElementPool, Pooled, RecycleElementCommand},
+    Eml,
+};
+use bevy::{
+    ecs::entity::Entities,
+    prelude::*,
+    utils::hashbrown::HashMap as BevyHashMap,
+};
+use itertools::Itertools;
+use std::{
+    any::TypeId,
+    collections::{BTreeMap, HashMap},
+    hash::{BuildHasher, Hash},
+    mem,
+    sync::Arc,
+};
+
+pub type ItemTemplate<I> = Arc<dyn Fn(I) -> Eml + Send + Sync>;
+
+/// The collection that can be bound to the repeated child elements.
+/// Every item of the collection is rendered using the item template.
+pub trait ListSource: BindableSource + PartialEq + Default {
+    type Item: Clone + PartialEq + Send + Sync + 'static;
+    fn list_items(&self) -> Vec<Self::Item>;
+}
+
+impl<T: Clone + PartialEq + Send + Sync + 'static> ListSource for Vec<T> {
+    type Item = T;
+    fn list_items(&self) -> Vec<Self::Item> {
+        self.clone()
+    }
+}
+
+impl<K, V> ListSource for BTreeMap<K, V>
+where
+    K: Clone + Ord + Send + Sync + 'static,
+    V: Clone + PartialEq + Send + Sync + 'static,
+{
+    type Item = (K, V);
+    fn list_items(&self) -> Vec<Self::Item> {
+        self.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+    }
+}
+
+macro_rules! impl_hash_map_list_source {
+    ($map:ident) => {
+        /// Items of the hash map are ordered by key.
+        impl<K, V, H> ListSource for $map<K, V, H>
+        where
+            K: Clone + Ord + Hash + Send + Sync + 'static,
+            V: Clone + PartialEq + Send + Sync + 'static,
+            H: BuildHasher + Default + Clone + Send + Sync + 'static,
+        {
+            type Item = (K, V);
+            fn list_items(&self) -> Vec<Self::Item> {
+                self.iter()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .sorted_by(|(a, _), (b, _)| a.cmp(b))
+                    .collect()
+            }
+        }
+    };
+}
+impl_hash_map_list_source!(HashMap);
+impl_hash_map_list_source!(BevyHashMap);
+
+/// Keeps the bound collection and the elements rendered for its items.
+#[derive(Component)]
+pub struct ListItems<S: ListSource> {
+    pub items: S,
+    template: ItemTemplate<S::Item>,
+    rendered: Vec<(S::Item, Entity)>,
+}
+
+fn copy_items<S: ListSource>(
+    source: &S,
+    mut items: Prop<S>,
+    _: &Formatters,
+) -> TransformationResult {
+    if *source != *items {
+        *items = source.clone();
+    }
+    Ok(())
+}
+
+/// Binds the collection from the component to the children of the `container`.
+/// Each item of the collection is rendered with the `template`, the elements
+/// are added, removed and reordered when the collection changes:
+/// ```rust,ignore
+/// commands.add(ListBind::new(
+///     from!(inventory, Inventory:slots),
+///     container,
+///     |slot: String| eml! { <span>{slot}</span> },
+/// ));
+/// ```
+///
+/// The same can be achieved with `eml!` using `bind:items` and `<template>`:
+/// ```rust,ignore
+/// eml! {
+///     <span bind:items=from!(inventory, Inventory:slots)>
+///         <template slot>
+///             <span>{slot}</span>
+///         </template>
+///     </span>
+/// }
+/// ```
+pub struct ListBind<R: Component, S: ListSource> {
+    from: FromComponent<R, S>,
+    container: Entity,
+    template: ItemTemplate<S::Item>,
+}
+
+impl<R: Component, S: ListSource> ListBind<R, S> {
+    pub fn new<F>(from: FromComponent<R, S>, container: Entity, template: F) -> ListBind<R, S>
+    where
+        F: Fn(S::Item) -> Eml + Send + Sync + 'static,
+    {
+        ListBind {
+            from,
+            container,
+            template: Arc::new(template),
+        }
+    }
+
+    pub fn write(self, world: &mut World) {
+        world
+            .resource::<RelationsSystems>()
+            .add_custom_system(TypeId::of::<ListItems<S>>(), list_items_system::<S>);
+        let items = world
+            .get::<R>(self.from.source)
+            .map(|c| (self.from.reader)(c))
+            .unwrap_or_default();
+        world.entity_mut(self.container).insert(ListItems {
+            items,
+            template: self.template,
+            rendered: vec![],
+        });
+        self.from
+            .bind_component(ToComponent {
+                id: bind_id::<ListItems<S>>("items"),
+                target: self.container,
+                transformer: copy_items::<S>,
+                reader: |c: &Mut<ListItems<S>>| &c.items,
+                writer: |c: &mut Mut<ListItems<S>>| &mut c.items,
+            })
+            .write(world);
+    }
+}
+
+impl<R: Component, S: ListSource> bevy::ecs::system::Command for ListBind<R, S> {
+    fn apply(self, world: &mut World) {
+        self.write(world);
+    }
+}
+
+/// Diffs the bound collections against the rendered elements: elements of the
+/// unchanged items are reused, the elements of the removed items are despawned
+/// and the new items are rendered with the item template.
+pub fn list_items_system<S: ListSource>(
+    mut commands: Commands,
+    mut lists: Query<(Entity, &mut ListItems<S>), Changed<ListItems<S>>>,
+    mut pool: Option<ResMut<ElementPool>>,
+    entities: &Entities,
+) {
+    for (container, mut list) in lists.iter_mut() {
+        let list = list.bypass_change_detection();
+        let mut previous: Vec<_> = mem::take(&mut list.rendered)
+            .into_iter()
+            .map(Some)
+            .collect();
+        for item in list.items.list_items() {
+            let reused = previous
+                .iter_mut()
+                .find(|rendered| matches!(rendered, Some((i, _)) if i == &item))
+                .and_then(Option::take);
+            if let Some(rendered) = reused {
+                list.rendered.push(rendered);
+            } else {
+                let entity = match pool.as_mut().and_then(|p| p.take(entities)) {
+                    Some(entity) => {
+                        commands.entity(entity).remove::<Pooled>();
+                        entity
+                    }
+                    None => commands.spawn_empty().id(),
+                };
+                commands.add((list.template)(item.clone()).render_to(entity));
+                list.rendered.push((item, entity));
+            }
+        }
+        for (_, entity) in previous.into_iter().flatten() {
+            commands.add(RecycleElementCommand(entity));
+        }
+        let order: Vec<Entity> = list.rendered.iter().map(|(_, e)| *e).collect();
+        commands.add(move |world: &mut World| {
+            let Some(mut container) = world.get_entity_mut(container) else {
+                return;
+            };
+            let mut children: Vec<Entity> = container
+                .get::<Children>()
+                .map(|c| c.iter().filter(|e| !order.contains(e)).copied().collect())
+                .unwrap_or_default();
+            children.extend(order);
+            container.replace_children(&children);
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::relations::RelationsPlugin;
+    use crate::*;
+
+    #[derive(Component, Default)]
+    struct Inventory {
+        slots: Vec<u32>,
+    }
+
+    #[derive(Component)]
+    struct Slot(u32);
+
+    fn rendered_slots(app: &App, container: Entity) -> Vec<(u32, Entity)> {
+        app.world
+            .get::<Children>(container)
+            .map(|children| {
+                children
+                    .iter()
+                    .map(|e| (app.world.get::<Slot>(*e).unwrap().0, *e))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn list_elements_follow_items() {
+        let mut app = App::new();
+        app.add_plugins(RelationsPlugin);
+        let inventory = app
+            .world
+            .spawn(Inventory {
+                slots: vec![1, 2, 3],
+            })
+            .id();
+        let container = app.world.spawn_empty().id();
+        let template = |slot: u32| {
+            Eml::new(move |world, entity| {
+                let entity = entity.unwrap_or_else(|| world.spawn_empty().id());
+                world.entity_mut(entity).insert(Slot(slot));
+                entity
+            })
+        };
+        ListBind::new(from!(inventory, Inventory:slots), container, template).write(&mut app.world);
+        app.update();
+        let rendered = rendered_slots(&app, container);
+        assert_eq!(
+            rendered.iter().map(|(s, _)| *s).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        let entity = |slot: u32| rendered.iter().find(|(s, _)| *s == slot).unwrap().1;
+        let (one, two, three) = (entity(1), entity(2), entity(3));
+
+        app.world.get_mut::<Inventory>(inventory).unwrap().slots = vec![3, 1, 4];
+        app.update();
+        let rendered = rendered_slots(&app, container);
+        assert_eq!(
+            rendered.iter().map(|(s, _)| *s).collect::<Vec<_>>(),
+            vec![3, 1, 4]
+        );
+        assert_eq!(rendered[0].1, three);
+        assert_eq!(rendered[1].1, one);
+        assert!(![one, two, three].contains(&rendered[2].1));
+        assert!(app.world.get_entity(two).is_none());
+    }
+
+    #[test]
+    fn hash_map_items_are_ordered_by_key() {
+        let mut map: HashMap<u32, &str> = HashMap::new();
+        map.insert(3, "c");
+        map.insert(1, "a");
+        map.insert(2, "b");
+        assert_eq!(map.list_items(), vec![(1, "a"), (2, "b"), (3, "c")]);
+    }
+}
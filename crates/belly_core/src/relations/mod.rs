@@ -1,14 +1,31 @@
+pub mod adapter;
 pub mod bind;
+pub mod computed;
 pub mod connect;
+pub mod format;
+pub mod list;
 pub mod ops;
 pub mod props;
+pub mod reflect;
+pub mod registry;
+pub mod signal;
 
-use crate::{element::Elements, eml::ReadySystemSet, input::InputSystemsSet};
+use crate::{
+    element::Elements, eml::pool::Pooled, eml::ReadySystemSet, input::InputSystemsSet,
+    metrics::UiMetrics,
+};
 
 use self::bind::{BindableSource, BindableTarget, ChangesState};
 pub use self::connect::{Connections, EventContext, Handler};
+pub use self::format::Formatters;
+pub use self::registry::RelationsRegistry;
+use self::registry::{BindGraph, BindRecord};
 use bevy::{
-    ecs::{entity::Entities, query::{QueryData, WorldQuery}},
+    ecs::{
+        entity::Entities,
+        query::{QueryData, WorldQuery},
+        system::{StaticSystemParam, SystemParam},
+    },
     log::Level,
     prelude::*,
     utils::{tracing::span, HashSet},
@@ -26,6 +43,11 @@ impl Plugin for RelationsPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<RelationsSystems>();
         app.init_resource::<ChangesState>();
+        app.init_resource::<RelationsRegistry>();
+        app.init_resource::<RelationsConfig>();
+        app.init_resource::<Formatters>();
+        app.add_event::<RelationsUnsettled>();
+        app.add_systems(Last, registry::cleanup_relations_registry_system);
         app.add_systems(PreUpdate, process_relations_system.after(InputSystemsSet));
         app.add_systems(PostUpdate, process_relations_system.after(ReadySystemSet));
     }
@@ -39,6 +61,58 @@ pub enum RelationsSystemSet {
     Custom,
 }
 
+/// The relations settings, insert the resource to change them.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct RelationsConfig {
+    /// How many times the relations systems run per stage before giving up,
+    /// the binds with the cycles (`a.value >> b.value` and `b.value >> a.value`
+    /// with transformers) may never settle down otherwise.
+    pub max_iterations: usize,
+}
+
+impl Default for RelationsConfig {
+    fn default() -> Self {
+        RelationsConfig { max_iterations: 32 }
+    }
+}
+
+/// Sent when the relations systems are still changing the data after
+/// [`RelationsConfig::max_iterations`] runs. The `cycles` are the bind
+/// chains leading back to their first source, found with [`BindGraph::cycles`].
+#[derive(Event, Debug, Clone)]
+pub struct RelationsUnsettled {
+    pub iterations: usize,
+    pub cycles: Vec<Vec<BindRecord>>,
+}
+
+fn report_unsettled_relations(world: &mut World, iterations: usize) {
+    let cycles: Vec<Vec<BindRecord>> = world
+        .get_resource::<RelationsRegistry>()
+        .map(|r| {
+            r.graph()
+                .cycles()
+                .into_iter()
+                .map(|c| c.into_iter().cloned().collect())
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut message = format!("Relations are still changing after {iterations} iterations");
+    if cycles.is_empty() {
+        message.push_str(", no bind cycles found: check the custom systems and the handlers");
+    }
+    for cycle in cycles.iter() {
+        message.push_str("\n  bind cycle:");
+        for bind in cycle.iter() {
+            let (source, target) = (BindGraph::source(bind), BindGraph::target(bind));
+            message.push_str(&format!("\n    {source}.{} >> {target}.{}", bind.from, bind.to));
+        }
+    }
+    warn!("{message}");
+    if world.contains_resource::<Events<RelationsUnsettled>>() {
+        world.send_event(RelationsUnsettled { iterations, cycles });
+    }
+}
+
 pub fn process_relations_system(world: &mut World) {
     let relations = world.resource::<RelationsSystems>().clone();
     relations.run(world);
@@ -48,13 +122,14 @@ pub fn process_relations_system_b(world: &mut World) {
     relations.run(world);
 }
 
-pub fn process_signals_system<P: 'static + QueryData, E: Event>(
+pub fn process_signals_system<P: 'static + QueryData, E: Event, S: 'static + SystemParam>(
     asset_server: Res<AssetServer>,
-    connections: Res<Connections<P, E>>,
+    connections: Res<Connections<P, E, S>>,
     time: Res<Time>,
     mut elements: Elements,
     mut events: EventReader<E>,
     mut components: Query<P>,
+    mut params: StaticSystemParam<S>,
 ) {
     for signal in events.read() {
         let mut context = EventContext {
@@ -70,11 +145,11 @@ pub fn process_signals_system<P: 'static + QueryData, E: Event>(
                         continue;
                     };
                     for (_, handler) in group {
-                        handler.run(&mut context, &mut args);
+                        handler.run(&mut context, &mut args, &mut params);
                     }
                 } else {
                     for (_, handler) in group {
-                        handler.run_without_target(&mut context);
+                        handler.run_without_target(&mut context, &mut params);
                     }
                 }
             }
@@ -82,11 +157,12 @@ pub fn process_signals_system<P: 'static + QueryData, E: Event>(
     }
 }
 
-pub fn cleanup_signals_system<P: 'static + WorldQuery, E: Event>(
-    mut connections: ResMut<Connections<P, E>>,
+pub fn cleanup_signals_system<P: 'static + WorldQuery, E: Event, S: 'static + SystemParam>(
+    mut connections: ResMut<Connections<P, E, S>>,
     entities: &Entities,
+    pooled: Query<(), With<Pooled>>,
 ) {
-    connections.drain(|e| !entities.contains(e));
+    connections.drain(|e| !entities.contains(e) || pooled.contains(e));
 }
 #[derive(Default, Clone, Resource, Deref)]
 pub struct RelationsSystems(pub(crate) Arc<BindingSystemsInternal>);
@@ -96,17 +172,18 @@ unsafe impl Sync for RelationsSystems {}
 pub struct BindingSystemsInternal {
     schedule: RwLock<Schedule>,
     system_queue: RwLock<Vec<Box<dyn FnOnce(&mut Schedule)>>>,
-    processors: RwLock<HashSet<(TypeId, TypeId)>>,
+    processors: RwLock<HashSet<(TypeId, TypeId, TypeId)>>,
     custom: RwLock<HashSet<TypeId>>,
 
     // new `bound` added system hashes
     systems: RwLock<HashSet<(TypeId, TypeId, TypeId, TypeId)>>,
-    watchers: RwLock<HashSet<TypeId>>,
 }
 
 impl BindingSystemsInternal {
-    pub fn add_signals_processor<P: 'static + QueryData, E: Event>(&self) {
-        let entry = (TypeId::of::<P>(), TypeId::of::<E>());
+    pub fn add_signals_processor<P: 'static + QueryData, E: Event, S: 'static + SystemParam>(
+        &self,
+    ) {
+        let entry = (TypeId::of::<P>(), TypeId::of::<E>(), TypeId::of::<S>());
         if self.processors.read().unwrap().contains(&entry) {
             return;
         }
@@ -120,10 +197,10 @@ impl BindingSystemsInternal {
             .unwrap()
             .push(Box::new(|schedule| {
                 schedule.add_systems(
-                    process_signals_system::<P, E>.in_set(RelationsSystemSet::Signals),
+                    process_signals_system::<P, E, S>.in_set(RelationsSystemSet::Signals),
                 );
                 schedule.add_systems(
-                    cleanup_signals_system::<P, E>.in_set(RelationsSystemSet::Signals),
+                    cleanup_signals_system::<P, E, S>.in_set(RelationsSystemSet::Signals),
                 );
             }));
     }
@@ -151,7 +228,9 @@ impl BindingSystemsInternal {
         let span = span!(Level::INFO, "belly");
         let _enter = span.enter();
         let mut last_state = world.resource::<ChangesState>().get();
+        let mut iterations = 0;
         loop {
+            iterations += 1;
             self.schedule.write().unwrap().run(world);
             {
                 let mut queue = self.system_queue.write().unwrap();
@@ -163,9 +242,16 @@ impl BindingSystemsInternal {
             let current_state = world.resource::<ChangesState>().get();
             if last_state == current_state {
                 break;
-            } else {
-                last_state = current_state;
             }
+            last_state = current_state;
+            let config = world.get_resource::<RelationsConfig>();
+            if iterations >= config.copied().unwrap_or_default().max_iterations {
+                report_unsettled_relations(world, iterations);
+                break;
+            }
+        }
+        if let Some(metrics) = world.get_resource::<UiMetrics>() {
+            metrics.add_relations_iterations(iterations);
         }
     }
 
@@ -177,28 +263,12 @@ impl BindingSystemsInternal {
     >(
         &self,
     ) {
-        let watcher = TypeId::of::<R>();
         let entry = (
             TypeId::of::<R>(),
             TypeId::of::<W>(),
             TypeId::of::<S>(),
             TypeId::of::<T>(),
         );
-        if !self.watchers.read().unwrap().contains(&watcher) {
-            let mut watchers = self.watchers.write().unwrap();
-            if !watchers.contains(&watcher) {
-                watchers.insert(watcher);
-                self.system_queue
-                    .write()
-                    .unwrap()
-                    .push(Box::new(move |schedule| {
-                        schedule.add_systems(
-                            bind::watch_changes::<R>.in_set(RelationsSystemSet::Changes),
-                        );
-                    }));
-            }
-        }
-
         if self.systems.read().unwrap().contains(&entry) {
             return;
         }
@@ -258,7 +328,6 @@ impl Default for BindingSystemsInternal {
 
         // new `bound` hashes
         let systems = HashSet::default();
-        let watchers = HashSet::default();
 
         let mut schedule = Schedule::default();
         schedule.configure_sets(
@@ -277,7 +346,6 @@ impl Default for BindingSystemsInternal {
 
             // new `bound` hashes
             systems: RwLock::new(systems),
-            watchers: RwLock::new(watchers),
             system_queue: RwLock::new(vec![]),
         }
     }
@@ -3,7 +3,7 @@ use std::marker::PhantomData;
 use crate::{
     build::{Prop, TransformationResult},
     impl_properties,
-    prelude::ColorFromHexExtension,
+    prelude::{ColorFromHexExtension, ColorHslExtension},
     relations::bind::{BindableSource, BindableTarget},
 };
 use bevy::prelude::*;
@@ -20,6 +20,10 @@ impl_properties! { ColorProperties for Color {
     one_minus_b(set_b, b) => |v: f32| (1.0 - v).min(1.).max(0.);
     one_minus_a(set_a, a) => |v: f32| (1.0 - v).min(1.).max(0.);
     hex(set_hex, get_hex) => |v: String| v.clone();
+    h(set_hue, hue) => |v: f32| v.rem_euclid(360.);
+    s(set_saturation, saturation) => |v: f32| v.min(1.).max(0.);
+    l(set_lightness, lightness) => |v: f32| v.min(1.).max(0.);
+    luminance(set_luminance, luminance) => |v: f32| v.min(1.).max(0.);
 }}
 
 pub struct OptionProperties<T>(PhantomData<T>);
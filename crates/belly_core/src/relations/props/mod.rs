@@ -1,7 +1,7 @@
 pub mod impls;
 
 use super::bind::{BindableSource, BindableTarget, TransformationError};
-use crate::relations::bind::TransformationResult;
+use crate::relations::{bind::TransformationResult, format::Formatters};
 use bevy::prelude::*;
 
 pub fn try_transform<
@@ -11,6 +11,7 @@ pub fn try_transform<
 >(
     incoming: &S,
     mut current: Prop<T>,
+    _: &Formatters,
 ) -> TransformationResult {
     let new_value = T::try_from(incoming.clone()).map_err(E::into)?;
     if new_value != *current {
@@ -0,0 +1,587 @@
+use super::{bind::ChangesState, format::Formatters, RelationsSystems};
+use crate::{element::ElementIdIndex, eml::pool::is_released};
+use bevy::{
+    ecs::system::Command,
+    prelude::*,
+    reflect::{GetPath, ReflectRef, TypeInfo, TypeRegistry, VariantInfo},
+};
+use std::any::TypeId;
+use tagstr::Tag;
+
+/// Entry point for the binds resolved at runtime using reflection:
+/// ```rust,ignore
+/// commands.add(
+///     Bind::from_path(player, "Health.current") >> Bind::to_path(bar, "HealthBar.value")
+/// );
+/// ```
+/// The path consists of the component name (short or full type path) and the
+/// field path of the component. The components should be registered in the
+/// [`AppTypeRegistry`] and reflect [`ReflectComponent`]. The source path may
+/// end with the registered format transformer: `"Cooldown.left|fmt.duration"`.
+pub struct Bind;
+
+impl Bind {
+    pub fn from_path(entity: Entity, path: &str) -> PathSource {
+        PathSource::new(PathEntity::Entity(entity), path)
+    }
+    pub fn from_id_path(id: impl Into<Tag>, path: &str) -> PathSource {
+        PathSource::new(PathEntity::Id(id.into()), path)
+    }
+    pub fn to_path(entity: Entity, path: &str) -> PathTarget {
+        PathTarget::new(PathEntity::Entity(entity), path)
+    }
+    pub fn to_id_path(id: impl Into<Tag>, path: &str) -> PathTarget {
+        PathTarget::new(PathEntity::Id(id.into()), path)
+    }
+}
+
+/// The entity of the reflection bind: provided directly or referenced by
+/// the element id (or [`Name`]) and resolved when it appears.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathEntity {
+    Entity(Entity),
+    Id(Tag),
+}
+
+impl PathEntity {
+    fn resolve(&mut self, world: &mut World) -> Option<Entity> {
+        let entity = match *self {
+            PathEntity::Entity(entity) => return Some(entity),
+            PathEntity::Id(id) => world
+                .get_resource::<ElementIdIndex>()
+                .and_then(|index| index.get(&id).copied())
+                .or_else(|| {
+                    world
+                        .query::<(Entity, &Name)>()
+                        .iter(world)
+                        .find(|(_, name)| name.as_str() == id.as_str())
+                        .map(|(entity, _)| entity)
+                })?,
+        };
+        *self = PathEntity::Entity(entity);
+        Some(entity)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ComponentPath {
+    entity: PathEntity,
+    component: String,
+    field: String,
+}
+
+impl ComponentPath {
+    fn new(entity: PathEntity, path: &str) -> ComponentPath {
+        let path = path.trim();
+        let (component, field) = path.split_once('.').unwrap_or((path, ""));
+        ComponentPath {
+            entity,
+            component: component.to_string(),
+            field: field.to_string(),
+        }
+    }
+
+    fn reflect_component<'a>(&self, registry: &'a TypeRegistry) -> Option<&'a ReflectComponent> {
+        let registration = registry
+            .get_with_short_type_path(&self.component)
+            .or_else(|| registry.get_with_type_path(&self.component));
+        let Some(registration) = registration else {
+            warn!("Can't bind {}: type is not registered", self.component);
+            return None;
+        };
+        let component = registration.data::<ReflectComponent>();
+        if component.is_none() {
+            warn!(
+                "Can't bind {}: type doesn't reflect Component",
+                self.component
+            );
+        }
+        component
+    }
+
+    fn read(
+        &self,
+        world: &World,
+        registry: &TypeRegistry,
+        entity: Entity,
+    ) -> Option<Box<dyn Reflect>> {
+        let component = self.reflect_component(registry)?;
+        let value = component.reflect(world.get_entity(entity)?)?;
+        if self.field.is_empty() {
+            return Some(value.clone_value());
+        }
+        match value.reflect_path(self.field.as_str()) {
+            Ok(value) => Some(value.clone_value()),
+            Err(e) => {
+                warn!("Can't read {}.{}: {e}", self.component, self.field);
+                None
+            }
+        }
+    }
+
+    fn write(
+        &self,
+        world: &mut World,
+        registry: &TypeRegistry,
+        entity: Entity,
+        value: &dyn Reflect,
+    ) -> bool {
+        let Some(component) = self.reflect_component(registry) else {
+            return false;
+        };
+        let Some(mut entity) = world.get_entity_mut(entity) else {
+            return false;
+        };
+        let Some(mut target) = component.reflect_mut(&mut entity) else {
+            return false;
+        };
+        let target = if self.field.is_empty() {
+            target.as_reflect_mut()
+        } else {
+            match target.reflect_path_mut(self.field.as_str()) {
+                Ok(target) => target,
+                Err(e) => {
+                    warn!("Can't write {}.{}: {e}", self.component, self.field);
+                    return false;
+                }
+            }
+        };
+        if target.reflect_partial_eq(value) == Some(true) {
+            return false;
+        }
+        apply_reflect(target, value)
+    }
+}
+
+fn kind(value: &dyn Reflect) -> u8 {
+    match value.reflect_ref() {
+        ReflectRef::Struct(_) => 0,
+        ReflectRef::TupleStruct(_) => 1,
+        ReflectRef::Tuple(_) => 2,
+        ReflectRef::List(_) => 3,
+        ReflectRef::Array(_) => 4,
+        ReflectRef::Map(_) => 5,
+        ReflectRef::Enum(_) => 6,
+        ReflectRef::Value(_) => 7,
+    }
+}
+
+macro_rules! reflect_to {
+    ($value:ident, $into:ty, $($ty:ty),+) => {{
+        let any = $value.as_any();
+        $(
+            if let Some(v) = any.downcast_ref::<$ty>() {
+                return Some(*v as $into);
+            }
+        )+
+        None
+    }};
+}
+
+fn reflect_to_f64(value: &dyn Reflect) -> Option<f64> {
+    reflect_to!(value, f64, f32, f64, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize)
+}
+
+fn reflect_to_string(value: &dyn Reflect) -> Option<String> {
+    let any = value.as_any();
+    if let Some(v) = any.downcast_ref::<String>() {
+        Some(v.clone())
+    } else if let Some(v) = any.downcast_ref::<bool>() {
+        Some(v.to_string())
+    } else {
+        reflect_to_f64(value).map(|v| v.to_string())
+    }
+}
+
+/// Checks the `value` has the type of `type_id` or represents it (the
+/// dynamic values cloned from the concrete ones).
+fn represents(value: &dyn Reflect, type_id: TypeId) -> bool {
+    value.as_any().type_id() == type_id
+        || value
+            .get_represented_type_info()
+            .map(|info| info.type_id() == type_id)
+            .unwrap_or(false)
+}
+
+/// Checks `target.apply(value)` doesn't panic: `Reflect::apply` panics
+/// on the mismatched kinds, value types, enum variants and array sizes.
+fn can_apply(target: &dyn Reflect, value: &dyn Reflect) -> bool {
+    if represents(value, target.as_any().type_id()) && kind(value) == 7 {
+        return true;
+    }
+    match (target.reflect_ref(), value.reflect_ref()) {
+        (ReflectRef::Struct(target), ReflectRef::Struct(value)) => {
+            (0..value.field_len()).all(|idx| {
+                let name = value.name_at(idx).unwrap_or_default();
+                match (target.field(name), value.field_at(idx)) {
+                    (Some(target), Some(value)) => can_apply(target, value),
+                    _ => true,
+                }
+            })
+        }
+        (ReflectRef::TupleStruct(target), ReflectRef::TupleStruct(value)) => {
+            value.iter_fields().enumerate().all(|(idx, value)| {
+                target
+                    .field(idx)
+                    .map(|target| can_apply(target, value))
+                    .unwrap_or(true)
+            })
+        }
+        (ReflectRef::Tuple(target), ReflectRef::Tuple(value)) => {
+            value.iter_fields().enumerate().all(|(idx, value)| {
+                target
+                    .field(idx)
+                    .map(|target| can_apply(target, value))
+                    .unwrap_or(true)
+            })
+        }
+        (ReflectRef::Array(target), ReflectRef::Array(value)) => {
+            target.len() == value.len()
+                && target.iter().zip(value.iter()).all(|(t, v)| can_apply(t, v))
+        }
+        (ReflectRef::List(target), ReflectRef::List(value)) => {
+            let Some(TypeInfo::List(info)) = target.get_represented_type_info() else {
+                return false;
+            };
+            value.iter().enumerate().all(|(idx, value)| match target.get(idx) {
+                Some(target) => can_apply(target, value),
+                None => represents(value, info.item_type_id()),
+            })
+        }
+        (ReflectRef::Map(target), ReflectRef::Map(value)) => {
+            let Some(TypeInfo::Map(info)) = target.get_represented_type_info() else {
+                return false;
+            };
+            value.iter().all(|(key, value)| match target.get(key) {
+                Some(target) => can_apply(target, value),
+                None => {
+                    represents(key, info.key_type_id()) && represents(value, info.value_type_id())
+                }
+            })
+        }
+        (ReflectRef::Enum(target), ReflectRef::Enum(value)) => {
+            if target.variant_name() == value.variant_name() {
+                return value.iter_fields().enumerate().all(|(idx, field)| {
+                    let target = match field.name() {
+                        Some(name) => target.field(name),
+                        None => target.field_at(idx),
+                    };
+                    target
+                        .map(|target| can_apply(target, field.value()))
+                        .unwrap_or(true)
+                });
+            }
+            // the variant switch constructs the new variant from the value fields
+            let Some(TypeInfo::Enum(info)) = target.get_represented_type_info() else {
+                return false;
+            };
+            match info.variant(value.variant_name()) {
+                Some(VariantInfo::Unit(_)) => true,
+                Some(VariantInfo::Struct(variant)) => variant.iter().all(|field| {
+                    value
+                        .field(field.name())
+                        .map(|value| represents(value, field.type_id()))
+                        .unwrap_or(false)
+                }),
+                Some(VariantInfo::Tuple(variant)) => variant.iter().all(|field| {
+                    value
+                        .field_at(field.index())
+                        .map(|value| represents(value, field.type_id()))
+                        .unwrap_or(false)
+                }),
+                None => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+/// Applies the `value` to the `target` converting numbers and strings when
+/// the types don't match. Returns `true` if the target was changed.
+fn apply_reflect(target: &mut dyn Reflect, value: &dyn Reflect) -> bool {
+    if can_apply(target, value) {
+        target.apply(value);
+        return true;
+    }
+    let target_type = target.as_any().type_id();
+    if target_type == TypeId::of::<String>() {
+        if let Some(value) = reflect_to_string(value) {
+            target.apply(&value);
+            return true;
+        }
+    } else if target_type == TypeId::of::<f32>() {
+        if let Some(value) = reflect_to_f64(value) {
+            target.apply(&(value as f32));
+            return true;
+        }
+    } else if target_type == TypeId::of::<f64>() {
+        if let Some(value) = reflect_to_f64(value) {
+            target.apply(&value);
+            return true;
+        }
+    }
+    error!(
+        "Can't bind {} to {}",
+        value.reflect_type_path(),
+        target.reflect_type_path()
+    );
+    false
+}
+
+pub struct PathSource {
+    path: ComponentPath,
+    format: Option<Tag>,
+}
+
+impl PathSource {
+    pub fn new(entity: PathEntity, path: &str) -> PathSource {
+        let (path, format) = match path.split_once('|') {
+            Some((path, transformer)) => {
+                let transformer = transformer.trim();
+                let name = transformer.strip_prefix("fmt.").unwrap_or(transformer);
+                (path, Some(Tag::new(name.trim())))
+            }
+            None => (path, None),
+        };
+        PathSource {
+            path: ComponentPath::new(entity, path),
+            format,
+        }
+    }
+
+    /// Parses `#id Component.field` or `Component.field` (the path of
+    /// the component at `default` entity) form used by the `eml` assets.
+    pub fn parse(value: &str, default: Entity) -> PathSource {
+        let value = value.trim();
+        let with_id = value
+            .strip_prefix('#')
+            .and_then(|v| v.split_once(char::is_whitespace));
+        match with_id {
+            Some((id, path)) => PathSource::new(PathEntity::Id(Tag::new(id)), path),
+            None => PathSource::new(PathEntity::Entity(default), value),
+        }
+    }
+}
+
+pub struct PathTarget(ComponentPath);
+
+impl PathTarget {
+    pub fn new(entity: PathEntity, path: &str) -> PathTarget {
+        PathTarget(ComponentPath::new(entity, path))
+    }
+}
+
+/// The bind between two component paths, see [`Bind`].
+pub struct PathBind {
+    from: ComponentPath,
+    format: Option<Tag>,
+    to: ComponentPath,
+    last: Option<Box<dyn Reflect>>,
+}
+
+impl PathBind {
+    pub fn new(from: PathSource, to: PathTarget) -> PathBind {
+        PathBind {
+            from: from.path,
+            format: from.format,
+            to: to.0,
+            last: None,
+        }
+    }
+
+    pub fn write(self, world: &mut World) {
+        world
+            .resource::<RelationsSystems>()
+            .add_custom_system(TypeId::of::<ReflectBinds>(), reflect_binds_system);
+        world
+            .get_resource_or_insert_with(ReflectBinds::default)
+            .0
+            .push(self);
+    }
+
+    /// Returns `None` if the bind is not valid anymore, or if
+    /// the target was changed.
+    fn process(&mut self, world: &mut World, registry: &TypeRegistry) -> Option<bool> {
+        let (Some(source), Some(target)) = (
+            self.from.entity.resolve(world),
+            self.to.entity.resolve(world),
+        ) else {
+            // keep waiting for the entities by id
+            return Some(false);
+        };
+        if is_released(world, source) || is_released(world, target) {
+            return None;
+        }
+        let value = self.from.read(world, registry, source)?;
+        if let Some(last) = &self.last {
+            if last.reflect_partial_eq(value.as_ref()) == Some(true) {
+                return Some(false);
+            }
+        }
+        let changed = match self.format {
+            Some(name) => {
+                let formatted = world
+                    .get_resource::<Formatters>()
+                    .and_then(|formatters| formatters.format(name.as_str(), value.as_any()));
+                let Some(formatted) = formatted else {
+                    warn!(
+                        "Can't bind {}.{}: format transformer `{name}` is not registered for {}",
+                        self.from.component,
+                        self.from.field,
+                        value.reflect_type_path()
+                    );
+                    return None;
+                };
+                self.to.write(world, registry, target, &formatted)
+            }
+            None => self.to.write(world, registry, target, value.as_ref()),
+        };
+        self.last = Some(value);
+        Some(changed)
+    }
+}
+
+impl std::ops::Shr<PathTarget> for PathSource {
+    type Output = PathBind;
+    fn shr(self, rhs: PathTarget) -> Self::Output {
+        PathBind::new(self, rhs)
+    }
+}
+
+impl std::ops::Shl<PathSource> for PathTarget {
+    type Output = PathBind;
+    fn shl(self, rhs: PathSource) -> Self::Output {
+        PathBind::new(rhs, self)
+    }
+}
+
+impl Command for PathBind {
+    fn apply(self, world: &mut World) {
+        self.write(world);
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct ReflectBinds(Vec<PathBind>);
+
+/// Reads all the reflection bind sources and writes changed values to targets.
+/// Binds with despawned source or target (or invalid paths) are dropped.
+pub fn reflect_binds_system(world: &mut World) {
+    let Some(mut binds) = world.remove_resource::<ReflectBinds>() else {
+        return;
+    };
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+    let mut changed = false;
+    binds.0.retain_mut(|bind| match bind.process(world, &registry) {
+        Some(bind_changed) => {
+            changed |= bind_changed;
+            true
+        }
+        None => false,
+    });
+    if changed {
+        world.resource::<ChangesState>().report_changed();
+    }
+    if let Some(added) = world.remove_resource::<ReflectBinds>() {
+        binds.0.extend(added.0);
+    }
+    world.insert_resource(binds);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::relations::RelationsPlugin;
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Health {
+        current: f32,
+    }
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct HealthBar {
+        value: f32,
+        output: String,
+    }
+
+    #[test]
+    fn bind_by_path() {
+        let mut app = App::new();
+        app.add_plugins(RelationsPlugin);
+        app.init_resource::<ElementIdIndex>();
+        app.register_type::<Health>();
+        app.register_type::<HealthBar>();
+
+        let player = app.world.spawn((Health::default(), Name::new("player"))).id();
+        let bar = app.world.spawn(HealthBar::default()).id();
+        (Bind::from_path(player, "Health.current") >> Bind::to_path(bar, "HealthBar.value"))
+            .write(&mut app.world);
+        let bind = Bind::from_id_path("player", "Health.current")
+            >> Bind::to_path(bar, "HealthBar.output");
+        bind.write(&mut app.world);
+        app.update();
+
+        app.world.get_mut::<Health>(player).unwrap().current = 20.;
+        app.update();
+        let bar = app.world.get::<HealthBar>(bar).unwrap();
+        assert_eq!(bar.value, 20.);
+        assert_eq!(bar.output, "20");
+    }
+
+    #[derive(Reflect, Default, Debug, PartialEq, Clone, Copy)]
+    enum Side {
+        #[default]
+        Left,
+        Right,
+    }
+
+    #[derive(Reflect, Default, Debug, PartialEq, Clone, Copy)]
+    enum Align {
+        #[default]
+        Start,
+        End,
+    }
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Layout {
+        side: Side,
+        align: Align,
+        sizes: Vec<f32>,
+        names: Vec<String>,
+    }
+
+    #[test]
+    fn mismatched_types_are_not_applied() {
+        let mut app = App::new();
+        app.add_plugins(RelationsPlugin);
+        app.init_resource::<ElementIdIndex>();
+        app.register_type::<Layout>();
+
+        let source = app
+            .world
+            .spawn(Layout {
+                side: Side::Right,
+                sizes: vec![1., 2.],
+                ..default()
+            })
+            .id();
+        let target = app.world.spawn(Layout::default()).id();
+        for (from, to) in [
+            ("Layout.side", "Layout.align"),
+            ("Layout.sizes", "Layout.names"),
+            ("Layout.side", "Layout.side"),
+        ] {
+            (Bind::from_path(source, from) >> Bind::to_path(target, to)).write(&mut app.world);
+        }
+        app.update();
+
+        let layout = app.world.get::<Layout>(target).unwrap();
+        assert_eq!(layout.align, Align::Start);
+        assert!(layout.names.is_empty());
+        assert_eq!(layout.side, Side::Right);
+    }
+}
@@ -0,0 +1,310 @@
+use crate::eml::pool::Pooled;
+use bevy::{
+    ecs::entity::Entities,
+    prelude::*,
+    utils::{get_short_name, HashMap},
+};
+use std::fmt::{Display, Formatter, Result, Write};
+use tagstr::Tag;
+
+/// The bind written to the world, recorded for the debugging tools.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BindRecord {
+    /// The source entity, `None` for the resource binds
+    pub source: Option<Entity>,
+    pub from: Tag,
+    pub source_type: &'static str,
+    pub target: Entity,
+    pub to: Tag,
+    pub target_type: &'static str,
+}
+
+impl Display for BindRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self.source {
+            Some(source) => write!(f, "{source:?}.{}", self.from)?,
+            None => write!(f, "{}", self.from)?,
+        }
+        write!(f, " >> {:?}.{}", self.target, self.to)
+    }
+}
+
+/// The signal connection written to the world, recorded for the debugging tools.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConnectionRecord {
+    pub source: Option<Entity>,
+    pub target: Option<Entity>,
+    pub event: &'static str,
+}
+
+impl Display for ConnectionRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let entity = |e: Option<Entity>| e.map_or("world".to_string(), |e| format!("{e:?}"));
+        write!(
+            f,
+            "{} on {} -> {}",
+            self.event,
+            entity(self.source),
+            entity(self.target)
+        )
+    }
+}
+
+/// The active binds and connections. The records of the despawned
+/// entities are removed when the elements are despawned.
+#[derive(Resource, Default, Debug)]
+pub struct RelationsRegistry {
+    binds: Vec<BindRecord>,
+    connections: Vec<ConnectionRecord>,
+}
+
+impl RelationsRegistry {
+    pub fn binds(&self) -> &[BindRecord] {
+        &self.binds
+    }
+
+    pub fn connections(&self) -> &[ConnectionRecord] {
+        &self.connections
+    }
+
+    /// The binds reading from or writing to the `entity`.
+    pub fn binds_of(&self, entity: Entity) -> impl Iterator<Item = &BindRecord> {
+        self.binds
+            .iter()
+            .filter(move |b| b.source == Some(entity) || b.target == entity)
+    }
+
+    /// The connections listening to the `entity` or handled by it.
+    pub fn connections_of(&self, entity: Entity) -> impl Iterator<Item = &ConnectionRecord> {
+        self.connections
+            .iter()
+            .filter(move |c| c.source == Some(entity) || c.target == Some(entity))
+    }
+
+    /// The topology of the active binds.
+    pub fn graph(&self) -> BindGraph {
+        BindGraph { binds: &self.binds }
+    }
+
+    pub(crate) fn add_bind(&mut self, bind: BindRecord) {
+        self.binds.push(bind);
+    }
+
+    pub(crate) fn add_connection(&mut self, connection: ConnectionRecord) {
+        self.connections.push(connection);
+    }
+
+    fn retain(&mut self, alive: impl Fn(Entity) -> bool) {
+        self.binds
+            .retain(|b| b.source.map_or(true, &alive) && alive(b.target));
+        self.connections
+            .retain(|c| c.source.map_or(true, &alive) && c.target.map_or(true, &alive));
+    }
+}
+
+/// The component (or the resource, when the entity is `None`) the binds
+/// read from or write to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BindNode {
+    pub entity: Option<Entity>,
+    pub component: &'static str,
+}
+
+impl Display for BindNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let component = get_short_name(self.component);
+        match self.entity {
+            Some(entity) => write!(f, "{component}({entity:?})"),
+            None => write!(f, "{component}"),
+        }
+    }
+}
+
+/// The binds as the directed graph: each bind is the edge from the source
+/// component to the target component. Use `to_dot()` to dump the graph
+/// for the graphviz or `cycles()` to find the binds that never settle.
+pub struct BindGraph<'a> {
+    binds: &'a [BindRecord],
+}
+
+impl<'a> BindGraph<'a> {
+    pub fn source(bind: &BindRecord) -> BindNode {
+        BindNode {
+            entity: bind.source,
+            component: bind.source_type,
+        }
+    }
+
+    pub fn target(bind: &BindRecord) -> BindNode {
+        BindNode {
+            entity: Some(bind.target),
+            component: bind.target_type,
+        }
+    }
+
+    pub fn edges(&self) -> &'a [BindRecord] {
+        self.binds
+    }
+
+    pub fn nodes(&self) -> Vec<BindNode> {
+        let mut nodes = vec![];
+        for bind in self.binds.iter() {
+            for node in [Self::source(bind), Self::target(bind)] {
+                if !nodes.contains(&node) {
+                    nodes.push(node);
+                }
+            }
+        }
+        nodes
+    }
+
+    /// The bind chains leading back to their first source. Each cycle
+    /// is reported once, starting from the first bind found.
+    pub fn cycles(&self) -> Vec<Vec<&'a BindRecord>> {
+        let mut outgoing: HashMap<BindNode, Vec<&BindRecord>> = HashMap::default();
+        for bind in self.binds.iter() {
+            outgoing.entry(Self::source(bind)).or_default().push(bind);
+        }
+        let mut visited: HashMap<BindNode, bool> = HashMap::default();
+        let mut cycles = vec![];
+        for node in self.nodes() {
+            let mut path = vec![];
+            Self::visit(node, &outgoing, &mut visited, &mut path, &mut cycles);
+        }
+        cycles
+    }
+
+    /// Depth-first search, `visited` holds `true` while the node is on the path.
+    fn visit(
+        node: BindNode,
+        outgoing: &HashMap<BindNode, Vec<&'a BindRecord>>,
+        visited: &mut HashMap<BindNode, bool>,
+        path: &mut Vec<&'a BindRecord>,
+        cycles: &mut Vec<Vec<&'a BindRecord>>,
+    ) {
+        if visited.contains_key(&node) {
+            return;
+        }
+        visited.insert(node, true);
+        for bind in outgoing.get(&node).into_iter().flatten() {
+            let next = Self::target(bind);
+            path.push(bind);
+            if visited.get(&next) == Some(&true) {
+                let start = path
+                    .iter()
+                    .position(|b| Self::source(b) == next)
+                    .unwrap_or_default();
+                cycles.push(path[start..].to_vec());
+            } else {
+                Self::visit(next, outgoing, visited, path, cycles);
+            }
+            path.pop();
+        }
+        visited.insert(node, false);
+    }
+
+    /// The graph in the graphviz `dot` format.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph binds {\n");
+        for bind in self.binds.iter() {
+            let _ = writeln!(
+                dot,
+                "    \"{}\" -> \"{}\" [label=\"{} >> {}\"];",
+                Self::source(bind),
+                Self::target(bind),
+                bind.from,
+                bind.to
+            );
+        }
+        dot.push('}');
+        dot
+    }
+}
+
+impl Display for BindGraph<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        for bind in self.binds.iter() {
+            let (from, to) = (bind.from, bind.to);
+            let (source, target) = (Self::source(bind), Self::target(bind));
+            writeln!(f, "{source}.{from} >> {target}.{to}")?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn cleanup_relations_registry_system(
+    mut removed: RemovedComponents<crate::element::Element>,
+    mut registry: ResMut<RelationsRegistry>,
+    entities: &Entities,
+    pooled: Query<(), With<Pooled>>,
+) {
+    if removed.read().count() > 0 {
+        registry.retain(|e| entities.contains(e) && !pooled.contains(e));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tag;
+
+    #[test]
+    fn registry_drops_despawned_entities() {
+        let mut world = World::new();
+        let source = world.spawn_empty().id();
+        let target = world.spawn_empty().id();
+        let mut registry = RelationsRegistry::default();
+        registry.add_bind(BindRecord {
+            source: Some(source),
+            from: tag!("value"),
+            source_type: "Slider",
+            target,
+            to: tag!("text"),
+            target_type: "Label",
+        });
+        registry.add_connection(ConnectionRecord {
+            source: Some(target),
+            target: None,
+            event: "PointerInput",
+        });
+        assert_eq!(registry.binds_of(target).count(), 1);
+        assert_eq!(
+            registry.binds()[0].to_string(),
+            format!("{source:?}.value >> {target:?}.text")
+        );
+        world.despawn(source);
+        registry.retain(|e| world.entities().contains(e));
+        assert!(registry.binds().is_empty());
+        assert_eq!(registry.connections_of(target).count(), 1);
+    }
+
+    #[test]
+    fn graph_reports_cycles() {
+        let mut world = World::new();
+        let slider = world.spawn_empty().id();
+        let label = world.spawn_empty().id();
+        let bind = |source, from, target, to| BindRecord {
+            source: Some(source),
+            from,
+            source_type: "belly::Value",
+            target,
+            to,
+            target_type: "belly::Value",
+        };
+        let mut registry = RelationsRegistry::default();
+        registry.add_bind(bind(slider, tag!("value"), label, tag!("value")));
+        assert!(registry.graph().cycles().is_empty());
+        registry.add_bind(bind(label, tag!("value"), slider, tag!("value")));
+        let graph = registry.graph();
+        let cycles = graph.cycles();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].len(), 2);
+        assert_eq!(graph.nodes().len(), 2);
+        let (slider, label) = (format!("Value({slider:?})"), format!("Value({label:?})"));
+        assert_eq!(
+            graph.to_string(),
+            format!("{slider}.value >> {label}.value\n{label}.value >> {slider}.value\n")
+        );
+        assert!(graph.to_dot().starts_with("digraph binds {"));
+    }
+}
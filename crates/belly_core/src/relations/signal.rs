@@ -0,0 +1,186 @@
+use super::connect::EventSource;
+use crate::{element::Elements, eml::WidgetContext};
+use bevy::{ecs::system::Command, prelude::*, utils::HashMap};
+use std::any::{type_name, TypeId};
+use tagstr::Tag;
+
+/// The custom signal of type `T` emitted by the `source` element. Signals
+/// are emitted with [`Elements::emit`] or [`WidgetContext::emit`] and can be
+/// connected like any other signal once registered by
+/// [`RegisterSignal::register_signal`].
+#[derive(Event, Deref)]
+pub struct ElementSignal<T: Send + Sync + 'static> {
+    pub source: Entity,
+    #[deref]
+    pub value: T,
+}
+
+/// Filters the [`ElementSignal`]s by the source element, use it to connect
+/// the signals without declaring them with [`element_signals!`](crate::element_signals):
+/// ```rust,ignore
+/// commands.connect().entity(slot).on(signal_source::<SaveGame>).func(|ctx| {
+///     info!("saved to slot {}", ctx.event().slot);
+/// });
+/// ```
+pub fn signal_source<T: Send + Sync + 'static>(signal: &ElementSignal<T>) -> EventSource {
+    EventSource::single(signal.source)
+}
+
+/// Names of the registered signals.
+#[derive(Resource, Default)]
+pub struct RegisteredSignals(HashMap<TypeId, Tag>);
+
+impl RegisteredSignals {
+    pub fn name<T: 'static>(&self) -> Option<Tag> {
+        self.0.get(&TypeId::of::<T>()).copied()
+    }
+}
+
+pub trait RegisterSignal {
+    /// Registers the custom signal `T` with the `name`, so it can be emitted
+    /// by the elements and connected with `on:name` within `eml!` (see
+    /// [`element_signals!`](crate::element_signals)).
+    fn register_signal<T: Send + Sync + 'static>(&mut self, name: &str) -> &mut Self;
+}
+
+impl RegisterSignal for App {
+    fn register_signal<T: Send + Sync + 'static>(&mut self, name: &str) -> &mut Self {
+        self.add_event::<ElementSignal<T>>();
+        self.world
+            .get_resource_or_insert_with(RegisteredSignals::default)
+            .0
+            .insert(TypeId::of::<T>(), Tag::new(name));
+        self
+    }
+}
+
+pub struct EmitSignal<T: Send + Sync + 'static> {
+    pub source: Entity,
+    pub value: T,
+}
+
+impl<T: Send + Sync + 'static> Command for EmitSignal<T> {
+    fn apply(self, world: &mut World) {
+        let Some(mut events) = world.get_resource_mut::<Events<ElementSignal<T>>>() else {
+            warn!(
+                "Unable to emit unregistered signal {}, use app.register_signal()",
+                type_name::<T>()
+            );
+            return;
+        };
+        events.send(ElementSignal {
+            source: self.source,
+            value: self.value,
+        });
+    }
+}
+
+impl<'w, 's> Elements<'w, 's> {
+    /// Emits the custom signal `value` from the `source` element.
+    pub fn emit<T: Send + Sync + 'static>(&mut self, source: Entity, value: T) {
+        self.commands().add(EmitSignal { source, value });
+    }
+}
+
+impl<'w, 's> WidgetContext<'w, 's> {
+    /// Emits the custom signal `value` from the element being built.
+    pub fn emit<T: Send + Sync + 'static>(&mut self, value: T) {
+        let source = self.entity();
+        self.add(EmitSignal { source, value });
+    }
+}
+
+/// Declares the `on:<name>` signals for the custom signal types, so
+/// they can be connected within `eml!`. The declared trait should be
+/// in scope where the signals are connected:
+/// ```rust,ignore
+/// element_signals! {
+///     pub GameSignals {
+///         saved: SaveGame,
+///     }
+/// }
+///
+/// app.register_signal::<SaveGame>("saved");
+/// commands.add(eml! {
+///     <slot on:saved=|ctx| info!("saved to slot {}", ctx.event().slot)/>
+/// });
+/// ```
+#[macro_export]
+macro_rules! element_signals {
+    ($vis:vis $trait:ident { $($name:ident: $ty:ty),* $(,)? }) => {
+        $vis trait $trait {
+            $(
+                fn $name(&self) -> $crate::relations::connect::EventFilter<
+                    $crate::relations::signal::ElementSignal<$ty>
+                >;
+            )*
+        }
+        impl $trait for $crate::eml::DefaultSignals {
+            $(
+                fn $name(&self) -> $crate::relations::connect::EventFilter<
+                    $crate::relations::signal::ElementSignal<$ty>
+                > {
+                    $crate::relations::connect::EventFilter::Entity(
+                        $crate::relations::signal::signal_source::<$ty>
+                    )
+                }
+            )*
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{element::ElementIdIndex, relations::RelationsPlugin};
+
+    struct SaveGame {
+        slot: usize,
+    }
+
+    #[derive(Resource, Default)]
+    struct LastSaved(Option<usize>);
+
+    element_signals! {
+        TestSignals {
+            saved: SaveGame,
+        }
+    }
+
+    #[test]
+    fn emit_custom_signal() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), RelationsPlugin));
+        app.init_resource::<ElementIdIndex>();
+        app.init_resource::<LastSaved>();
+        app.register_signal::<SaveGame>("saved");
+        assert_eq!(
+            app.world.resource::<RegisteredSignals>().name::<SaveGame>(),
+            Some(Tag::new("saved"))
+        );
+
+        let slot = app.world.spawn_empty().id();
+        let other = app.world.spawn_empty().id();
+        crate::eml::DefaultSignals
+            .saved()
+            .func(|ctx| {
+                let slot = ctx.event().slot;
+                ctx.add(move |world: &mut World| {
+                    world.resource_mut::<LastSaved>().0 = Some(slot);
+                });
+            })
+            .from(slot)
+            .write(&mut app.world);
+
+        app.add_systems(Update, move |mut elements: Elements, mut once: Local<bool>| {
+            if !*once {
+                *once = true;
+                elements.emit(other, SaveGame { slot: 1 });
+                elements.emit(slot, SaveGame { slot: 2 });
+            }
+        });
+        app.update();
+        app.update();
+        assert_eq!(app.world.resource::<LastSaved>().0, Some(2));
+    }
+}
@@ -0,0 +1,285 @@
+use crate::element::{Disabled, Element, ElementIdIndex, Loading};
+use crate::eml::migrations::{MigrationError, SavedWidgetState, WidgetMigrations};
+use crate::eml::Variant;
+use crate::ess::{PropertyExtractor, PropertyTransformer, StyleProperty, StylePropertyMethods};
+use crate::relations::reflect::{PathBind, PathEntity, PathSource, PathTarget};
+use bevy::prelude::*;
+use bevy::scene::scene_spawner_system;
+use bevy::utils::HashMap;
+use itertools::Itertools;
+use std::any::TypeId;
+use tagstr::*;
+
+/// Makes the elements savable into the bevy [`DynamicScene`]s and
+/// restores them when the scene is spawned.
+pub struct UiScenePlugin;
+impl Plugin for UiScenePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ElementSnapshot>();
+        app.register_type::<ElementBinds>();
+        app.register_type::<Disabled>();
+        app.register_type::<Loading>();
+        app.add_systems(
+            SpawnScene,
+            restore_elements_system.after(scene_spawner_system),
+        );
+    }
+}
+
+/// The serializable copy of the [`Element`]: the names, the id, the
+/// classes, the states, the attributes and the sources of the inline
+/// styles. The `version` is the version of the widget state the attributes
+/// were saved by, see [`WidgetMigrations`]. It is inserted by [`save_ui_scene`] for the time of the scene
+/// extraction and turned back into the [`Element`] once the scene is spawned.
+#[derive(Component, Reflect, Default, Clone, Debug, PartialEq)]
+#[reflect(Component)]
+pub struct ElementSnapshot {
+    pub names: Vec<String>,
+    pub aliases: Vec<String>,
+    pub id: Option<String>,
+    pub classes: Vec<String>,
+    pub state: Vec<String>,
+    pub attributes: Vec<(String, String)>,
+    pub styles: Vec<(String, String)>,
+    #[reflect(default)]
+    pub version: u32,
+}
+
+impl ElementSnapshot {
+    pub fn new(element: &Element) -> ElementSnapshot {
+        ElementSnapshot {
+            names: element.names.iter().map(|t| t.to_string()).collect(),
+            aliases: element.aliases.iter().map(|t| t.to_string()).collect(),
+            id: element.id.map(|id| id.to_string()),
+            classes: element.classes.iter().map(|t| t.to_string()).sorted().collect(),
+            state: element.state.iter().map(|t| t.to_string()).sorted().collect(),
+            attributes: sorted_pairs(&element.attributes),
+            styles: sorted_pairs(&element.style_sources),
+            version: 0,
+        }
+    }
+
+    /// Brings the saved attributes to the current version of the widget
+    /// state. The snapshot is left untouched if it can't be migrated.
+    pub fn migrate(&mut self, migrations: &WidgetMigrations) -> Result<(), MigrationError> {
+        let Some(widget) = self.names.first() else {
+            return Ok(());
+        };
+        let mut state = SavedWidgetState {
+            version: self.version,
+            values: self.attributes.iter().cloned().collect(),
+        };
+        migrations.migrate(widget.as_str(), &mut state)?;
+        self.version = state.version;
+        self.attributes = state.values.into_iter().sorted().collect();
+        Ok(())
+    }
+
+    /// Builds the [`Element`] back, the inline styles are parsed again
+    pub fn restore(
+        &self,
+        transformer: &PropertyTransformer,
+        extractor: &PropertyExtractor,
+    ) -> Element {
+        let mut element = Element {
+            names: self.names.iter().map(|n| n.as_tag()).collect(),
+            aliases: self.aliases.iter().map(|n| n.as_tag()).collect(),
+            id: self.id.as_ref().map(|id| id.as_tag()),
+            classes: self.classes.iter().map(|c| c.as_tag()).collect(),
+            state: self.state.iter().map(|s| s.as_tag()).collect(),
+            attributes: self
+                .attributes
+                .iter()
+                .map(|(k, v)| (k.as_tag(), v.clone()))
+                .collect(),
+            ..default()
+        };
+        for (name, source) in self.styles.iter() {
+            let tag = name.as_tag();
+            let variant = match StyleProperty::try_from(source) {
+                Ok(property) => Variant::style(property),
+                Err(e) => {
+                    error!("Ignoring property {name}: {e}");
+                    continue;
+                }
+            };
+            let restored = if extractor.is_compound_property(tag) {
+                extractor.extract(tag, variant).map(|p| p.into_iter().collect())
+            } else {
+                transformer.transform(tag, variant).map(|p| vec![(tag, p)])
+            };
+            match restored {
+                Ok(properties) => element.styles.extend(properties),
+                Err(e) => error!("Ignoring property {name}: {e}"),
+            }
+            element.style_sources.insert(tag, source.clone());
+        }
+        element
+    }
+}
+
+fn sorted_pairs(map: &HashMap<Tag, String>) -> Vec<(String, String)> {
+    map.iter()
+        .map(|(k, v)| (k.to_string(), v.clone()))
+        .sorted()
+        .collect()
+}
+
+/// The `bind:Component.field="#source Component.field"` params of the
+/// element loaded from the `.eml` asset as `(target, source)` pairs. The
+/// binds are written again when the element is restored from the scene.
+#[derive(Component, Reflect, Default, Clone, Debug, PartialEq)]
+#[reflect(Component)]
+pub struct ElementBinds(pub Vec<(String, String)>);
+
+/// The source of the inline style param: the connected `ess` tokens or
+/// the plain string. The values computed in code can't be saved.
+pub(crate) fn style_source(variant: &Variant) -> Option<String> {
+    match variant {
+        Variant::String(source) => Some(source.clone()),
+        Variant::Style(property) => {
+            Some(property.tokens().iter().map(|t| t.to_string()).join(" "))
+        }
+        _ => None,
+    }
+}
+
+/// Extracts the `root` element and its descendants into the [`DynamicScene`].
+/// All the reflected components registered in the [`AppTypeRegistry`]
+/// are saved (the bevy ui components, the reflected widget components),
+/// the [`Element`]s are saved as [`ElementSnapshot`]s. The `Parent` of the
+/// `root` is dropped, so the scene is attached to the entity it is spawned
+/// with. The connections made in code (the `on:` signals) are not saved:
+/// connect them again when the scene is spawned.
+pub fn save_ui_scene(world: &mut World, root: Entity) -> DynamicScene {
+    let migrations = world.get_resource::<WidgetMigrations>().cloned();
+    let mut entities = vec![];
+    let mut stack = vec![root];
+    while let Some(entity) = stack.pop() {
+        entities.push(entity);
+        if let Some(children) = world.get::<Children>(entity) {
+            stack.extend(children.iter().rev());
+        }
+    }
+    for entity in entities.iter() {
+        if let Some(mut snapshot) = world.get::<Element>(*entity).map(ElementSnapshot::new) {
+            if let (Some(migrations), Some(widget)) = (&migrations, snapshot.names.first()) {
+                snapshot.version = migrations.current_version(widget.as_str());
+            }
+            world.entity_mut(*entity).insert(snapshot);
+        }
+    }
+    let mut scene = DynamicSceneBuilder::from_world(world)
+        .extract_entities(entities.iter().copied())
+        .build();
+    for entity in entities.iter() {
+        world.entity_mut(*entity).remove::<ElementSnapshot>();
+    }
+    if let Some(root) = scene.entities.iter_mut().find(|e| e.entity == root) {
+        root.components.retain(|c| {
+            c.get_represented_type_info().map(|i| i.type_id()) != Some(TypeId::of::<Parent>())
+        });
+    }
+    scene
+}
+
+/// Turns the spawned [`ElementSnapshot`]s into the [`Element`]s, indexes
+/// their ids and writes the [`ElementBinds`] again. The snapshots saved by
+/// the older widget versions are migrated first.
+pub fn restore_elements_system(world: &mut World) {
+    let mut snapshots = world.query_filtered::<(Entity, &ElementSnapshot), Without<Element>>();
+    let restored: Vec<_> = snapshots
+        .iter(world)
+        .map(|(entity, snapshot)| (entity, snapshot.clone()))
+        .collect();
+    if restored.is_empty() {
+        return;
+    }
+    let transformer = world.resource::<PropertyTransformer>().clone();
+    let extractor = world.resource::<PropertyExtractor>().clone();
+    let migrations = world.get_resource::<WidgetMigrations>().cloned();
+    for (entity, mut snapshot) in restored {
+        if let Some(migrations) = &migrations {
+            if let Err(e) = snapshot.migrate(migrations) {
+                error!("Restoring the saved state as is: {e}");
+            }
+        }
+        let element = snapshot.restore(&transformer, &extractor);
+        if let Some(id) = element.id {
+            world.resource_mut::<ElementIdIndex>().insert(id, entity);
+        }
+        let binds = world.get::<ElementBinds>(entity).cloned();
+        world
+            .entity_mut(entity)
+            .remove::<ElementSnapshot>()
+            .insert(element);
+        for (target, source) in binds.into_iter().flat_map(|b| b.0) {
+            PathBind::new(
+                PathSource::parse(&source, entity),
+                PathTarget::new(PathEntity::Entity(entity), &target),
+            )
+            .write(world);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::eml::migrations::RegisterWidgetMigration;
+    use smallvec::smallvec;
+
+    #[test]
+    fn restore_element_from_snapshot() {
+        let mut world = World::new();
+        world.init_resource::<ElementIdIndex>();
+        world.init_resource::<PropertyTransformer>();
+        world.init_resource::<PropertyExtractor>();
+        let mut element = Element {
+            names: smallvec![tag!("button")],
+            id: Some(tag!("save")),
+            classes: [tag!("primary"), tag!("big")].into_iter().collect(),
+            ..default()
+        };
+        element.attributes.insert(tag!("mode"), "toggle".to_string());
+        let snapshot = ElementSnapshot::new(&element);
+        assert_eq!(snapshot.classes, vec!["big", "primary"]);
+        let entity = world.spawn(snapshot).id();
+
+        restore_elements_system(&mut world);
+        let restored = world.get::<Element>(entity).unwrap();
+        assert_eq!(restored.names.as_slice(), &[tag!("button")]);
+        assert_eq!(restored.id(), Some(tag!("save")));
+        assert!(restored.classes.contains(&tag!("big")));
+        assert_eq!(restored.attributes.get(&tag!("mode")).unwrap(), "toggle");
+        assert!(world.get::<ElementSnapshot>(entity).is_none());
+        assert_eq!(world.resource::<ElementIdIndex>().get(&tag!("save")), Some(&entity));
+
+        let source = StyleProperty::try_from("1px 2px").unwrap();
+        assert_eq!(style_source(&Variant::style(source)).unwrap(), "1px 2px");
+    }
+
+    #[test]
+    fn restore_element_from_old_version() {
+        let mut app = App::new();
+        app.init_resource::<ElementIdIndex>();
+        app.init_resource::<PropertyTransformer>();
+        app.init_resource::<PropertyExtractor>();
+        app.register_widget_migration("slider", 1, |state| {
+            if let Some(value) = state.values.remove("value") {
+                state.values.insert("absolute".into(), value);
+            }
+        });
+        let snapshot = ElementSnapshot {
+            names: vec!["slider".into()],
+            attributes: vec![("value".into(), "0.5".into())],
+            ..default()
+        };
+        let entity = app.world.spawn(snapshot).id();
+
+        restore_elements_system(&mut app.world);
+        let restored = app.world.get::<Element>(entity).unwrap();
+        assert_eq!(restored.attributes.get(&tag!("absolute")).unwrap(), "0.5");
+        assert!(!restored.attributes.contains_key(&tag!("value")));
+    }
+}
@@ -0,0 +1,217 @@
+use crate::element::InvalidateElements;
+use crate::states::StateChanged;
+use bevy::audio::Volume;
+use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+
+/// The kinds of the interactions the UI plays the sounds for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UiSound {
+    /// The button gets hovered
+    Hover,
+    /// The button is pressed
+    Press,
+    /// The `:pressed` state of the toggle button changes
+    Toggle,
+    /// The element gets the `:error` state
+    Error,
+    /// The element gets the `:open` state
+    Open,
+    /// The element loses the `:open` state
+    Close,
+}
+
+impl UiSound {
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// The sound of the element for the single [`UiSound`], set with the
+/// `sound-press: url("click.ogg")` like properties
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum SoundSource {
+    /// The sound registered in [`UiSounds`]
+    #[default]
+    Auto,
+    /// No sound
+    None,
+    /// The path of the audio asset
+    Path(String),
+}
+
+/// The sounds overridden by the element with the `sound-*` properties
+#[derive(Component, Debug, Clone, Default, PartialEq)]
+pub struct UiSoundStyle([SoundSource; 6]);
+
+impl UiSoundStyle {
+    pub fn get(&self, sound: UiSound) -> &SoundSource {
+        &self.0[sound.index()]
+    }
+    pub fn set(&mut self, sound: UiSound, source: SoundSource) {
+        self.0[sound.index()] = source;
+    }
+}
+
+/// The default sounds of the interactions, see [`RegisterUiSound`]
+#[derive(Resource, Debug)]
+pub struct UiSounds {
+    sounds: HashMap<UiSound, Handle<AudioSource>>,
+    /// The volume of all the UI sounds
+    pub volume: f32,
+    /// Silences all the UI sounds, including the overridden ones
+    pub muted: bool,
+}
+
+impl Default for UiSounds {
+    fn default() -> Self {
+        UiSounds {
+            sounds: HashMap::default(),
+            volume: 1.,
+            muted: false,
+        }
+    }
+}
+
+impl UiSounds {
+    pub fn get(&self, sound: UiSound) -> Option<&Handle<AudioSource>> {
+        self.sounds.get(&sound)
+    }
+    pub fn set(&mut self, sound: UiSound, handle: Handle<AudioSource>) {
+        self.sounds.insert(sound, handle);
+    }
+    pub fn remove(&mut self, sound: UiSound) {
+        self.sounds.remove(&sound);
+    }
+}
+
+/// Requests the `sound` of the `entity`: the sound from its `sound-*`
+/// property or the default one from [`UiSounds`]. Send it from the custom
+/// widgets to play the sounds the same way the built-in ones do.
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct PlayUiSound {
+    pub entity: Entity,
+    pub sound: UiSound,
+}
+
+pub trait RegisterUiSound {
+    /// Loads the audio asset and plays it for the `sound` interactions:
+    /// ```rust,ignore
+    /// app.register_ui_sound(UiSound::Press, "sounds/click.ogg");
+    /// ```
+    fn register_ui_sound(&mut self, sound: UiSound, path: &str) -> &mut Self;
+}
+
+impl RegisterUiSound for App {
+    fn register_ui_sound(&mut self, sound: UiSound, path: &str) -> &mut Self {
+        let handle = self.world.resource::<AssetServer>().load(path.to_string());
+        self.world
+            .get_resource_or_insert_with(UiSounds::default)
+            .set(sound, handle);
+        self
+    }
+}
+
+pub struct UiSoundPlugin;
+impl Plugin for UiSoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UiSounds>();
+        app.add_event::<PlayUiSound>();
+        if !app.world.contains_resource::<Events<StateChanged>>() {
+            app.add_event::<StateChanged>();
+        }
+        app.add_systems(
+            PostUpdate,
+            (state_sounds_system, play_ui_sounds_system)
+                .chain()
+                .after(InvalidateElements),
+        );
+    }
+}
+
+/// The sound the state change plays
+pub fn state_sound(change: &StateChanged) -> Option<UiSound> {
+    match (change.state.as_str(), change.value) {
+        ("open", true) => Some(UiSound::Open),
+        ("open", false) => Some(UiSound::Close),
+        ("pressed", _) => Some(UiSound::Toggle),
+        ("error", true) => Some(UiSound::Error),
+        _ => None,
+    }
+}
+
+fn state_sounds_system(
+    mut changes: EventReader<StateChanged>,
+    mut sounds: EventWriter<PlayUiSound>,
+) {
+    for change in changes.read() {
+        if let Some(sound) = state_sound(change) {
+            sounds.send(PlayUiSound {
+                entity: change.entity,
+                sound,
+            });
+        }
+    }
+}
+
+/// Plays each requested sound once per frame: toggling the button in the
+/// group releases the other one, but it is a single click.
+fn play_ui_sounds_system(
+    mut commands: Commands,
+    mut requests: EventReader<PlayUiSound>,
+    sounds: Res<UiSounds>,
+    styles: Query<&UiSoundStyle>,
+    assets: Res<AssetServer>,
+) {
+    if sounds.muted {
+        requests.clear();
+        return;
+    }
+    let mut played = HashSet::new();
+    for request in requests.read() {
+        let source = styles
+            .get(request.entity)
+            .map(|style| style.get(request.sound))
+            .unwrap_or(&SoundSource::Auto);
+        let handle = match source {
+            SoundSource::Auto => sounds.get(request.sound).cloned(),
+            SoundSource::None => None,
+            SoundSource::Path(path) => Some(assets.load(path.clone())),
+        };
+        let Some(handle) = handle else {
+            continue;
+        };
+        if !played.insert(handle.id()) {
+            continue;
+        }
+        commands.spawn(AudioBundle {
+            source: handle,
+            settings: PlaybackSettings::DESPAWN.with_volume(Volume::new(sounds.volume)),
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tags;
+    use tagstr::Tag;
+
+    #[test]
+    fn state_changes_play_sounds() {
+        let change = |state: Tag, value| StateChanged {
+            entity: Entity::PLACEHOLDER,
+            state,
+            value,
+        };
+        assert_eq!(state_sound(&change(Tag::new("open"), false)), Some(UiSound::Close));
+        assert_eq!(state_sound(&change(tags::pressed(), false)), Some(UiSound::Toggle));
+        assert_eq!(state_sound(&change(Tag::new("error"), false)), None);
+        assert_eq!(state_sound(&change(tags::loading(), true)), None);
+
+        let mut style = UiSoundStyle::default();
+        style.set(UiSound::Press, SoundSource::None);
+        assert_eq!(style.get(UiSound::Press), &SoundSource::None);
+        assert_eq!(style.get(UiSound::Hover), &SoundSource::Auto);
+    }
+}
@@ -0,0 +1,245 @@
+use crate::element::{Elements, InvalidateElements};
+use crate::eml::DefaultSignals;
+use crate::ess::ApplyStyleProperties;
+use crate::relations::connect::{EventFilter, EventSource};
+use bevy::prelude::*;
+use bevy::ui::UiSystem;
+use tagstr::Tag;
+
+/// Maps the widget component to the element states (`:pressed`,
+/// `:dragging`, `:expanded`, etc). Once registered with
+/// [`RegisterWidgetStates::register_widget_states`], the states are
+/// updated whenever the component changes, and each change emits
+/// the `state_change` signal:
+/// ```rust,ignore
+/// #[derive(Component, Default)]
+/// struct Dropdown {
+///     expanded: bool,
+/// }
+///
+/// impl WidgetStates for Dropdown {
+///     fn states(&self) -> Vec<(Tag, bool)> {
+///         vec![(tag!("expanded"), self.expanded)]
+///     }
+/// }
+///
+/// app.register_widget_states::<Dropdown>();
+/// ```
+/// Now `dropdown:expanded` selects the expanded dropdowns, and
+/// `<dropdown on:state_change=|ctx| ...>` reacts to the changes.
+pub trait WidgetStates: Component {
+    /// The states driven by the component with their current values.
+    fn states(&self) -> Vec<(Tag, bool)>;
+}
+
+/// Emitted when the state driven by [`WidgetStates`] changes.
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct StateChanged {
+    pub entity: Entity,
+    pub state: Tag,
+    pub value: bool,
+}
+
+impl StateChanged {
+    pub fn is(&self, state: impl Into<Tag>) -> bool {
+        self.state == state.into()
+    }
+}
+
+impl DefaultSignals {
+    pub fn state_change(&self) -> EventFilter<StateChanged> {
+        EventFilter::Entity(|e| EventSource::single(e.entity))
+    }
+}
+
+pub trait RegisterWidgetStates {
+    fn register_widget_states<T: WidgetStates>(&mut self) -> &mut Self;
+}
+
+impl RegisterWidgetStates for App {
+    fn register_widget_states<T: WidgetStates>(&mut self) -> &mut Self {
+        if !self.world.contains_resource::<Events<StateChanged>>() {
+            self.add_event::<StateChanged>();
+        }
+        self.add_systems(
+            PostUpdate,
+            sync_widget_states::<T>.before(InvalidateElements),
+        )
+    }
+}
+
+fn sync_widget_states<T: WidgetStates>(
+    mut elements: Elements,
+    widgets: Query<(Entity, &T), Changed<T>>,
+    mut events: EventWriter<StateChanged>,
+) {
+    for (entity, widget) in widgets.iter() {
+        for (state, value) in widget.states() {
+            let Ok(element) = elements.get(entity) else {
+                continue;
+            };
+            if element.state.contains(&state) != value {
+                elements.set_state(entity, state, value);
+                events.send(StateChanged {
+                    entity,
+                    state,
+                    value,
+                });
+            }
+        }
+    }
+}
+
+/// Shows the element with its subtree only while the bevy state `S`
+/// is `state` (or hides it while the state is `state`). Inserted by the
+/// `state:visible=...` and `state:hidden=...` attributes within `eml!`:
+/// ```rust,ignore
+/// app.init_state::<GameState>();
+/// app.register_ui_state::<GameState>();
+///
+/// commands.add(eml! {
+///     <body>
+///         <div c:hud state:hidden=GameState::Paused>...</div>
+///         <div c:pause-menu state:visible=GameState::Paused>...</div>
+///     </body>
+/// });
+/// ```
+/// The hidden element gets `display: none` and keeps its content, binds
+/// and connections, so it is shown again the way it was left.
+#[derive(Component)]
+pub struct StateVisibility<S: States> {
+    pub state: S,
+    /// Hides the element while the `state` instead of showing it
+    pub hidden: bool,
+    base: Option<Display>,
+}
+
+impl<S: States> StateVisibility<S> {
+    pub fn visible(state: S) -> StateVisibility<S> {
+        StateVisibility {
+            state,
+            hidden: false,
+            base: None,
+        }
+    }
+
+    pub fn hidden(state: S) -> StateVisibility<S> {
+        StateVisibility {
+            state,
+            hidden: true,
+            base: None,
+        }
+    }
+
+    pub fn is_visible(&self, current: Option<&S>) -> bool {
+        (current == Some(&self.state)) != self.hidden
+    }
+}
+
+pub trait RegisterUiState {
+    /// Registers the bevy state `S` for the `state:visible` and
+    /// `state:hidden` attributes, the elements follow the state
+    /// transitions since the next frame.
+    fn register_ui_state<S: States>(&mut self) -> &mut Self;
+}
+
+impl RegisterUiState for App {
+    fn register_ui_state<S: States>(&mut self) -> &mut Self {
+        self.add_systems(
+            PostUpdate,
+            apply_state_visibility::<S>
+                .after(ApplyStyleProperties)
+                .before(UiSystem::Layout),
+        )
+    }
+}
+
+fn apply_state_visibility<S: States>(
+    state: Option<Res<State<S>>>,
+    mut elements: Query<(&mut StateVisibility<S>, &mut Style)>,
+) {
+    let current = state.as_ref().map(|s| s.get());
+    for (mut visibility, mut style) in elements.iter_mut() {
+        if !visibility.is_visible(current) {
+            // the restyled element gets its display back, hide it again
+            if style.display != Display::None {
+                visibility.base = Some(style.display);
+                style.display = Display::None;
+            }
+        } else if visibility.base.is_some() {
+            style.display = visibility.base.take().unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::element::{Element, ElementIdIndex};
+    use tagstr::tag;
+
+    #[derive(Component, Default)]
+    struct Dropdown {
+        expanded: bool,
+    }
+
+    impl WidgetStates for Dropdown {
+        fn states(&self) -> Vec<(Tag, bool)> {
+            vec![(tag!("expanded"), self.expanded)]
+        }
+    }
+
+    #[test]
+    fn widget_states_follow_component() {
+        let mut app = App::new();
+        app.init_resource::<ElementIdIndex>();
+        app.register_widget_states::<Dropdown>();
+        let dropdown = app
+            .world
+            .spawn((Element::default(), Dropdown::default()))
+            .id();
+        app.update();
+        assert!(app.world.resource::<Events<StateChanged>>().is_empty());
+
+        app.world.get_mut::<Dropdown>(dropdown).unwrap().expanded = true;
+        app.update();
+        let element = app.world.get::<Element>(dropdown).unwrap();
+        assert!(element.state.contains(&tag!("expanded")));
+        let events = app.world.resource::<Events<StateChanged>>();
+        let mut reader = events.get_reader();
+        let changes: Vec<_> = reader.read(events).collect();
+        assert_eq!(changes.len(), 1);
+        assert!(changes[0].is("expanded") && changes[0].value);
+    }
+
+    #[derive(States, Default, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum Game {
+        #[default]
+        Running,
+        Paused,
+    }
+
+    #[test]
+    fn toggle_elements_on_state_transitions() {
+        let mut app = App::new();
+        app.init_state::<Game>();
+        app.register_ui_state::<Game>();
+        let menu = app
+            .world
+            .spawn((Style::default(), StateVisibility::visible(Game::Paused)))
+            .id();
+        let hud = app
+            .world
+            .spawn((Style::default(), StateVisibility::hidden(Game::Paused)))
+            .id();
+        let display = |app: &App, entity: Entity| app.world.get::<Style>(entity).unwrap().display;
+        app.update();
+        assert_eq!(display(&app, menu), Display::None);
+        assert_eq!(display(&app, hud), Display::Flex);
+
+        app.world.resource_mut::<NextState<Game>>().set(Game::Paused);
+        app.update();
+        assert_eq!(display(&app, menu), Display::Flex);
+        assert_eq!(display(&app, hud), Display::None);
+    }
+}
@@ -0,0 +1,321 @@
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt};
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::ui::UiSystem;
+use bevy::utils::{BoxedFuture, HashMap};
+use bevy::window::PrimaryWindow;
+use bevy_stylebox::Stylebox;
+use resvg::tiny_skia;
+use resvg::usvg::{self, TreeParsing, TreePostProc};
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use thiserror::Error;
+
+/// Loads the `.svg` files as the images, so `<img src="icon.svg">` and
+/// `stylebox-source: "frame.svg"` accept them, and keeps them crisp:
+/// the images and the styleboxes using the svg image are drawn with the
+/// copy rasterized at the size they take on the screen.
+pub struct SvgPlugin;
+impl Plugin for SvgPlugin {
+    fn build(&self, app: &mut App) {
+        let sources = SvgSources::default();
+        app.register_asset_loader(SvgImageLoader {
+            sources: sources.clone(),
+        });
+        app.insert_resource(sources);
+        app.init_resource::<SvgRasters>();
+        app.add_systems(
+            PostUpdate,
+            (track_svg_images_system, rasterize_svg_system)
+                .chain()
+                .after(UiSystem::Layout),
+        );
+    }
+}
+
+/// Possible errors of the [`SvgImageLoader`]
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum SvgLoaderError {
+    #[error("Could not read svg: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid svg: {0}")]
+    Svg(#[from] usvg::Error),
+    #[error("Svg has no size")]
+    Empty,
+}
+
+/// The sources of the loaded svg files by their asset paths, shared
+/// between the [`SvgImageLoader`] and the world
+#[derive(Resource, Clone, Default)]
+pub struct SvgSources(Arc<RwLock<HashMap<PathBuf, Arc<[u8]>>>>);
+
+/// Loads the `.svg` file as the [`Image`] rasterized at the size the
+/// svg declares and remembers the source for [`rasterize_svg_system`].
+pub struct SvgImageLoader {
+    sources: SvgSources,
+}
+
+impl AssetLoader for SvgImageLoader {
+    type Settings = ();
+    type Error = SvgLoaderError;
+    type Asset = Image;
+
+    fn extensions(&self) -> &[&str] {
+        &["svg"]
+    }
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _: &'a Self::Settings,
+        load_context: &'a mut bevy::asset::LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut source = Vec::new();
+            reader.read_to_end(&mut source).await?;
+            let tree = parse_svg(&source)?;
+            let size = UVec2::new(
+                tree.size.width().ceil() as u32,
+                tree.size.height().ceil() as u32,
+            );
+            let image = rasterize_svg(&tree, size).ok_or(SvgLoaderError::Empty)?;
+            self.sources
+                .0
+                .write()
+                .unwrap()
+                .insert(load_context.path().to_path_buf(), source.into());
+            Ok(image)
+        })
+    }
+}
+
+/// Parses the svg `source` into the tree ready for rendering. The svg
+/// texts are not rendered: no fonts are loaded for them.
+pub fn parse_svg(source: &[u8]) -> Result<usvg::Tree, usvg::Error> {
+    let mut tree = usvg::Tree::from_data(source, &usvg::Options::default())?;
+    tree.postprocess(
+        usvg::PostProcessingSteps::default(),
+        &usvg::fontdb::Database::new(),
+    );
+    Ok(tree)
+}
+
+/// Renders the svg `tree` stretched to the `size` in pixels, returns `None`
+/// if the `size` is empty.
+pub fn rasterize_svg(tree: &usvg::Tree, size: UVec2) -> Option<Image> {
+    let mut pixmap = tiny_skia::Pixmap::new(size.x, size.y)?;
+    let transform = tiny_skia::Transform::from_scale(
+        size.x as f32 / tree.size.width(),
+        size.y as f32 / tree.size.height(),
+    );
+    resvg::render(tree, transform, &mut pixmap.as_mut());
+    // the pixmap is premultiplied, the images are not
+    let data = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let color = pixel.demultiply();
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect();
+    let size = Extent3d {
+        width: size.x,
+        height: size.y,
+        depth_or_array_layers: 1,
+    };
+    Some(Image::new(
+        size,
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    ))
+}
+
+struct SvgRaster {
+    image: Handle<Image>,
+    /// Keeps the svg image alive while the elements use the raster
+    source: Handle<Image>,
+}
+
+/// The svg images and their copies rasterized at the sizes the elements need
+#[derive(Resource, Default)]
+pub struct SvgRasters {
+    /// The svg sources by the ids of the images loaded from them
+    images: HashMap<AssetId<Image>, Arc<[u8]>>,
+    /// The rasterized copies by the svg image and the size in pixels
+    rasters: HashMap<(AssetId<Image>, UVec2), SvgRaster>,
+    /// The keys of the `rasters` by the ids of the copies
+    keys: HashMap<AssetId<Image>, (AssetId<Image>, UVec2)>,
+}
+
+impl SvgRasters {
+    /// Returns the svg image the `texture` is or is rasterized from
+    fn source(&self, texture: &Handle<Image>) -> Option<Handle<Image>> {
+        if self.images.contains_key(&texture.id()) {
+            return Some(texture.clone());
+        }
+        let key = self.keys.get(&texture.id())?;
+        self.rasters.get(key).map(|raster| raster.source.clone())
+    }
+
+    /// Returns the copy of the svg `source` rasterized at the `size`,
+    /// rasterizes it if there is no such copy yet.
+    fn raster(
+        &mut self,
+        source: &Handle<Image>,
+        size: UVec2,
+        images: &mut Assets<Image>,
+    ) -> Option<Handle<Image>> {
+        let key = (source.id(), size);
+        if let Some(raster) = self.rasters.get(&key) {
+            return Some(raster.image.clone());
+        }
+        let svg = self.images.get(&source.id())?;
+        let tree = parse_svg(svg).ok()?;
+        let image = images.add(rasterize_svg(&tree, size)?);
+        self.keys.insert(image.id(), key);
+        self.rasters.insert(
+            key,
+            SvgRaster {
+                image: image.clone(),
+                source: source.clone(),
+            },
+        );
+        Some(image)
+    }
+
+    /// Replaces the copies of the svg `source` with the fresh ones
+    fn rasterize_again(&mut self, source: AssetId<Image>, images: &mut Assets<Image>) {
+        let Some(svg) = self.images.get(&source) else {
+            return;
+        };
+        let Ok(tree) = parse_svg(svg) else {
+            return;
+        };
+        for ((image, size), raster) in self.rasters.iter() {
+            if *image != source {
+                continue;
+            }
+            if let Some(fresh) = rasterize_svg(&tree, *size) {
+                images.insert(raster.image.id(), fresh);
+            }
+        }
+    }
+
+    /// Drops the copies no element uses anymore
+    fn drop_unused(&mut self) {
+        let keys = &mut self.keys;
+        self.rasters.retain(|_, raster| {
+            let used = matches!(&raster.image, Handle::Strong(h) if Arc::strong_count(h) > 1);
+            if !used {
+                keys.remove(&raster.image.id());
+            }
+            used
+        });
+    }
+}
+
+/// Remembers the images loaded from the svg files and rasterizes their
+/// copies again when the files are reloaded.
+pub fn track_svg_images_system(
+    asset_server: Res<AssetServer>,
+    sources: Res<SvgSources>,
+    mut rasters: ResMut<SvgRasters>,
+    mut images: ResMut<Assets<Image>>,
+    mut events: EventReader<AssetEvent<Image>>,
+) {
+    for event in events.read() {
+        match event {
+            AssetEvent::Added { id } | AssetEvent::Modified { id } => {
+                let Some(path) = asset_server.get_path(*id) else {
+                    continue;
+                };
+                let Some(source) = sources.0.read().unwrap().get(path.path()).cloned() else {
+                    continue;
+                };
+                let previous = rasters.images.insert(*id, source.clone());
+                if previous.map_or(false, |previous| !Arc::ptr_eq(&previous, &source)) {
+                    // the file is reloaded
+                    rasters.rasterize_again(*id, &mut images);
+                }
+            }
+            AssetEvent::Removed { id } => {
+                rasters.images.remove(id);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Draws the images and the styleboxes using the svg images with the copies
+/// rasterized at the physical size: the images take the size of the node,
+/// the styleboxes take the size of the svg multiplied by the scale factor.
+pub fn rasterize_svg_system(
+    ui_scale: Res<UiScale>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut rasters: ResMut<SvgRasters>,
+    mut images: ResMut<Assets<Image>>,
+    mut ui_images: Query<(&Node, &mut UiImage)>,
+    mut styleboxes: Query<&mut Stylebox>,
+) {
+    if rasters.images.is_empty() {
+        return;
+    }
+    let scale_factor = windows.get_single().map_or(1., |w| w.scale_factor()) * ui_scale.0;
+    for (node, mut image) in ui_images.iter_mut() {
+        let Some(source) = rasters.source(&image.texture) else {
+            continue;
+        };
+        let size = (node.size() * scale_factor).round().as_uvec2();
+        if size.x == 0 || size.y == 0 {
+            continue;
+        }
+        let Some(raster) = rasters.raster(&source, size, &mut images) else {
+            continue;
+        };
+        if image.texture != raster {
+            image.texture = raster;
+        }
+    }
+    for mut stylebox in styleboxes.iter_mut() {
+        let Some(source) = rasters.source(&stylebox.texture) else {
+            continue;
+        };
+        let Some(source_size) = images.get(&source).map(|i| i.size()) else {
+            continue;
+        };
+        let size = (source_size.as_vec2() * scale_factor).round().as_uvec2();
+        let Some(raster) = rasters.raster(&source, size.max(UVec2::ONE), &mut images) else {
+            continue;
+        };
+        if stylebox.texture != raster || stylebox.texture_scale != scale_factor {
+            stylebox.texture = raster;
+            stylebox.texture_scale = scale_factor;
+        }
+    }
+    rasters.drop_unused();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rasterize_svg_at_any_size() {
+        let source = br##"<svg xmlns="http://www.w3.org/2000/svg" width="4" height="4">
+            <rect x="0" y="0" width="2" height="4" fill="#ff0000"/>
+        </svg>"##;
+        let tree = parse_svg(source).unwrap();
+        let image = rasterize_svg(&tree, UVec2::new(8, 8)).unwrap();
+        assert_eq!(image.size(), UVec2::new(8, 8));
+        // the left half is red, the right one is transparent
+        let pixel = |x: usize, y: usize| &image.data[(y * 8 + x) * 4..(y * 8 + x) * 4 + 4];
+        assert_eq!(pixel(1, 4), [255, 0, 0, 255]);
+        assert_eq!(pixel(6, 4)[3], 0);
+        assert!(rasterize_svg(&tree, UVec2::new(0, 8)).is_none());
+    }
+}
@@ -19,10 +19,18 @@ pub fn with() -> Tag {
     tag!("with")
 }
 
+pub fn state_visibility() -> Tag {
+    tag!("state-visibility")
+}
+
 pub fn focus() -> Tag {
     tag!("focus")
 }
 
+pub fn focus_visible() -> Tag {
+    tag!("focus-visible")
+}
+
 pub fn hover() -> Tag {
     tag!("hover")
 }
@@ -34,3 +42,19 @@ pub fn active() -> Tag {
 pub fn pressed() -> Tag {
     tag!("pressed")
 }
+
+pub fn disabled() -> Tag {
+    tag!("disabled")
+}
+
+pub fn enabled() -> Tag {
+    tag!("enabled")
+}
+
+pub fn loading() -> Tag {
+    tag!("loading")
+}
+
+pub fn leaving() -> Tag {
+    tag!("leaving")
+}
@@ -0,0 +1,264 @@
+use crate::{element::Elements, eml::Variant, tags};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use std::time::Duration;
+
+/// The clock the element (and its children) animate with. Set it with
+/// the `ui-clock` style property or insert it directly to the root
+/// of the subtree:
+/// - `Real`: unscaled time, keeps running when the game is paused
+/// - `Virtual`: the game time, respects `Time<Virtual>` speed and pause
+/// - `Scaled(f32)`: unscaled time multiplied by the factor
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub enum UiClock {
+    Real,
+    Virtual,
+    Scaled(f32),
+}
+
+/// The clock of the elements without `UiClock` in the ancestors.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Deref, DerefMut)]
+pub struct DefaultUiClock(pub UiClock);
+
+impl Default for DefaultUiClock {
+    fn default() -> Self {
+        DefaultUiClock(UiClock::Real)
+    }
+}
+
+/// Resolves the time of the elements according to their `UiClock`:
+/// ```rust,ignore
+/// fn spin(time: ElementsTime, mut spinners: Query<(Entity, &mut Transform), With<Spinner>>) {
+///     for (entity, mut transform) in spinners.iter_mut() {
+///         transform.rotate_z(time.delta_seconds(entity));
+///     }
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct ElementsTime<'w, 's> {
+    real: Res<'w, Time<Real>>,
+    virt: Res<'w, Time<Virtual>>,
+    default_clock: Res<'w, DefaultUiClock>,
+    clocks: Query<'w, 's, &'static UiClock>,
+    parents: Query<'w, 's, &'static Parent>,
+}
+
+impl<'w, 's> ElementsTime<'w, 's> {
+    /// The clock of the `entity`: its own `UiClock`, the
+    /// clock of the closest ancestor or the default one.
+    pub fn clock(&self, entity: Entity) -> UiClock {
+        let mut current = entity;
+        loop {
+            if let Ok(clock) = self.clocks.get(current) {
+                return *clock;
+            }
+            let Ok(parent) = self.parents.get(current) else {
+                return self.default_clock.0;
+            };
+            current = parent.get();
+        }
+    }
+
+    pub fn delta_seconds(&self, entity: Entity) -> f32 {
+        match self.clock(entity) {
+            UiClock::Real => self.real.delta_seconds(),
+            UiClock::Virtual => self.virt.delta_seconds(),
+            UiClock::Scaled(scale) => self.real.delta_seconds() * scale,
+        }
+    }
+
+    pub fn elapsed_seconds(&self, entity: Entity) -> f32 {
+        match self.clock(entity) {
+            UiClock::Real => self.real.elapsed_seconds(),
+            UiClock::Virtual => self.virt.elapsed_seconds(),
+            UiClock::Scaled(scale) => self.real.elapsed_seconds() * scale,
+        }
+    }
+}
+
+/// Despawns the element after `remaining` seconds of its clock. When
+/// the `exit` is set, the element gets the `:leaving` state first and
+/// lives `exit` seconds more, so the styles can play the exit animation.
+/// Inserted by the `lifetime` param:
+/// ```rust,ignore
+/// eml! { <span c:toast lifetime="2s">"Saved"</span> }
+/// eml! { <span c:damage lifetime="1s 300ms">{damage}</span> }
+/// ```
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct Lifetime {
+    pub remaining: f32,
+    pub exit: f32,
+    leaving: bool,
+}
+
+impl Lifetime {
+    pub fn new(lifetime: Duration) -> Lifetime {
+        Lifetime {
+            remaining: lifetime.as_secs_f32(),
+            exit: 0.,
+            leaving: false,
+        }
+    }
+
+    pub fn with_exit(mut self, exit: Duration) -> Lifetime {
+        self.exit = exit.as_secs_f32();
+        self
+    }
+
+    pub fn leaving(&self) -> bool {
+        self.leaving
+    }
+}
+
+/// Parses `2s`, `500ms` or `1.5` (seconds) into seconds.
+fn parse_seconds(value: &str) -> Result<f32, String> {
+    let (num, scale) = if let Some(ms) = value.strip_suffix("ms") {
+        (ms, 0.001)
+    } else if let Some(s) = value.strip_suffix('s') {
+        (s, 1.)
+    } else {
+        (value, 1.)
+    };
+    match num.parse::<f32>() {
+        Ok(num) if num >= 0. => Ok(num * scale),
+        _ => Err(format!("Expected $duration, got `{value}`")),
+    }
+}
+
+impl TryFrom<Variant> for Lifetime {
+    type Error = String;
+    fn try_from(variant: Variant) -> Result<Self, Self::Error> {
+        let (remaining, exit) = match variant {
+            Variant::String(value) => {
+                let mut parts = value.split_whitespace();
+                let remaining = parts
+                    .next()
+                    .ok_or_else(|| format!("Expected $duration [$exit], got nothing"))
+                    .and_then(parse_seconds)?;
+                let exit = parts.next().map(parse_seconds).transpose()?.unwrap_or(0.);
+                if parts.next().is_some() {
+                    return Err(format!("Expected $duration [$exit], got `{value}`"));
+                }
+                (remaining, exit)
+            }
+            variant => match variant.get::<Duration>() {
+                Some(duration) => (duration.as_secs_f32(), 0.),
+                None => (f32::try_from(variant)?, 0.),
+            },
+        };
+        Ok(Lifetime {
+            remaining,
+            exit,
+            leaving: false,
+        })
+    }
+}
+
+impl<'w, 's> Elements<'w, 's> {
+    /// Despawns the `entity` (with its children) after `lifetime`
+    /// of its [`UiClock`].
+    pub fn despawn_after(&mut self, entity: Entity, lifetime: Duration) {
+        if let Some(mut commands) = self.commands().get_entity(entity) {
+            commands.insert(Lifetime::new(lifetime));
+        }
+    }
+}
+
+fn lifetime_system(
+    time: ElementsTime,
+    mut elements: Elements,
+    mut lifetimes: Query<(Entity, &mut Lifetime)>,
+) {
+    for (entity, mut lifetime) in lifetimes.iter_mut() {
+        lifetime.remaining -= time.delta_seconds(entity);
+        if lifetime.remaining > 0. {
+            continue;
+        }
+        if !lifetime.leaving && lifetime.exit > 0. {
+            lifetime.leaving = true;
+            lifetime.remaining += lifetime.exit;
+            elements.set_state(entity, tags::leaving(), true);
+            continue;
+        }
+        elements.commands().entity(entity).despawn_recursive();
+    }
+}
+
+pub struct UiTimePlugin;
+impl Plugin for UiTimePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DefaultUiClock>();
+        app.add_systems(Update, lifetime_system);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::element::{Element, ElementIdIndex};
+    use bevy::ecs::system::RunSystemOnce;
+
+    #[test]
+    fn subtree_clock() {
+        let mut app = App::new();
+        app.add_plugins(UiTimePlugin);
+        app.init_resource::<Time<Real>>();
+        let mut virt = Time::<Virtual>::default();
+        virt.pause();
+        app.insert_resource(virt);
+        // the first update only starts the clock
+        let mut real = app.world.resource_mut::<Time<Real>>();
+        real.update_with_duration(Duration::ZERO);
+        real.update_with_duration(Duration::from_millis(100));
+
+        let item = app.world.spawn_empty().id();
+        let menu = app
+            .world
+            .spawn(UiClock::Scaled(0.5))
+            .push_children(&[item])
+            .id();
+        let hud = app.world.spawn(UiClock::Virtual).id();
+        let other = app.world.spawn_empty().id();
+
+        let deltas = app.world.run_system_once(move |time: ElementsTime| {
+            [item, menu, hud, other].map(|e| time.delta_seconds(e))
+        });
+        assert_eq!(deltas, [0.05, 0.05, 0., 0.1]);
+    }
+
+    #[test]
+    fn despawn_after_lifetime() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, UiTimePlugin));
+        app.init_resource::<ElementIdIndex>();
+        // the first update only starts the clock
+        app.world
+            .resource_mut::<Time<Real>>()
+            .update_with_duration(Duration::ZERO);
+        let toast = app.world.spawn(Element::default()).id();
+        let popup = app
+            .world
+            .spawn((
+                Element::default(),
+                Lifetime::try_from(Variant::from("0.1s 100ms".to_string())).unwrap(),
+            ))
+            .id();
+        app.world.run_system_once(move |mut elements: Elements| {
+            elements.despawn_after(toast, Duration::from_millis(50));
+        });
+
+        let advance = |app: &mut App, ms| {
+            app.world
+                .resource_mut::<Time<Real>>()
+                .update_with_duration(Duration::from_millis(ms));
+            app.world.run_system_once(lifetime_system);
+        };
+        advance(&mut app, 60);
+        assert!(app.world.get_entity(toast).is_none());
+        assert!(app.world.get_entity(popup).is_some());
+        advance(&mut app, 60);
+        let element = app.world.get::<Element>(popup).unwrap();
+        assert!(element.state.contains(&tags::leaving()));
+        advance(&mut app, 120);
+        assert!(app.world.get_entity(popup).is_none());
+    }
+}
@@ -1,12 +1,50 @@
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use quote::*;
+use toml::map::Map;
+use toml::Value;
 
 pub struct Context {
     core_path: TokenStream,
     is_interal: bool,
 }
 
+/// Returns the name the crate depends on the `package` with (it may be
+/// renamed with `alias = { package = "belly" }`)
+fn dependency_name(manifest: &Map<String, Value>, package: &str) -> Option<String> {
+    let targets = manifest
+        .get("target")
+        .and_then(|t| t.as_table())
+        .into_iter()
+        .flat_map(|t| t.values())
+        .filter_map(|t| t.as_table());
+    let tables = [manifest]
+        .into_iter()
+        .chain(targets)
+        .flat_map(|t| ["dependencies", "dev-dependencies"].map(|key| t.get(key)))
+        .flatten()
+        .filter_map(|deps| deps.as_table());
+    for deps in tables {
+        for (name, dep) in deps.iter() {
+            let dep_package = dep
+                .get("package")
+                .and_then(|p| p.as_str())
+                .unwrap_or(name.as_str());
+            if dep_package == package {
+                return Some(name.replace('-', "_"));
+            }
+        }
+    }
+    None
+}
+
 impl Context {
+    /// Resolves the path to `belly_core` from the manifest of the crate
+    /// using the macro:
+    /// - `::belly_core` within `belly_widgets`
+    /// - `::belly::core` when the crate depends on `belly` (or is `belly`),
+    ///   the renamed dependency is respected
+    /// - `::belly_core` when the crate depends on `belly_core` only, so
+    ///   the widget packs may avoid depending on the whole `belly`
     pub fn new() -> Context {
         let mut context = Context {
             core_path: quote! { ::belly_core },
@@ -24,7 +62,7 @@ impl Context {
         let Ok(manifest) = std::fs::read_to_string(&manifest_path) else {
             return context;
         };
-        let Ok(manifest) = toml::from_str::<toml::map::Map<String, toml::Value>>(&manifest) else {
+        let Ok(manifest) = toml::from_str::<Map<String, Value>>(&manifest) else {
             return context;
         };
 
@@ -41,11 +79,20 @@ impl Context {
             return context;
         };
         if pkg.trim() == "belly_widgets" {
-            context.core_path = quote! { ::belly_core };
+            return context;
+        }
+        context.is_interal = false;
+        if pkg.trim() == "belly" {
+            context.core_path = quote! { ::belly::core };
+        } else if let Some(belly) = dependency_name(&manifest, "belly") {
+            let belly = syn::Ident::new(&belly, Span::call_site());
+            context.core_path = quote! { ::#belly::core };
+        } else if let Some(core) = dependency_name(&manifest, "belly_core") {
+            let core = syn::Ident::new(&core, Span::call_site());
+            context.core_path = quote! { ::#core };
         } else {
             context.core_path = quote! { ::belly::core };
-            context.is_interal = false;
-        };
+        }
         context
     }
     pub fn core_path(&self) -> &TokenStream {
@@ -1,6 +1,6 @@
-use proc_macro2::TokenStream;
+use proc_macro2::{Delimiter, Spacing, TokenStream, TokenTree};
 use quote::*;
-use syn::{spanned::Spanned, Expr, ExprPath, Ident};
+use syn::{spanned::Spanned, Expr, ExprLit, ExprPath, Ident, Lit, LitStr, RangeLimits, Stmt};
 use syn_rsx::{Node, NodeAttribute, NodeElement};
 
 use super::context::*;
@@ -11,6 +11,207 @@ macro_rules! throw {
     };
 }
 
+/// The attribute name the `c:{expr}` dynamic classes are rewritten to
+const DYNAMIC_CLASS: &str = "c:__class";
+
+/// `syn_rsx` can't parse the braced attribute names, so the `c:{expr}`
+/// dynamic classes are rewritten to `c:__class={expr}` before parsing.
+/// Only the top-level tokens are visited, the rust blocks are left as is.
+pub fn expand_dynamic_classes(tokens: TokenStream) -> TokenStream {
+    let mut tokens: Vec<_> = tokens.into_iter().collect();
+    let mut idx = 0;
+    while idx + 2 < tokens.len() {
+        let is_dynamic_class = match (&tokens[idx], &tokens[idx + 1], &tokens[idx + 2]) {
+            (TokenTree::Ident(c), TokenTree::Punct(colon), TokenTree::Group(group)) => {
+                c == "c"
+                    && colon.as_char() == ':'
+                    && colon.spacing() == Spacing::Alone
+                    && group.delimiter() == Delimiter::Brace
+            }
+            _ => false,
+        };
+        if is_dynamic_class {
+            let span = tokens[idx + 2].span();
+            let rewrite: Vec<TokenTree> = quote_spanned! {span=> __class = }
+                .into_iter()
+                .collect();
+            let len = rewrite.len();
+            tokens.splice(idx + 2..idx + 2, rewrite);
+            idx += len;
+        }
+        idx += 1;
+    }
+    tokens.into_iter().collect()
+}
+
+/// Returns `format!(...)` of the string literal when it has the `{name}`
+/// (or `{name:spec}`) placeholders. `{{` and `}}` are the escaped braces
+/// with or without the placeholders, the other braces are kept as is.
+fn interpolate(expr: &Expr) -> Option<TokenStream> {
+    let Expr::Lit(ExprLit {
+        lit: Lit::Str(lit), ..
+    }) = expr
+    else {
+        return None;
+    };
+    let value = lit.value();
+    let (format, text, placeholders) = split_placeholders(&value);
+    if placeholders {
+        let format = LitStr::new(&format, lit.span());
+        Some(quote_spanned! {lit.span()=> ::std::format!(#format) })
+    } else if text != value {
+        let text = LitStr::new(&text, lit.span());
+        Some(text.to_token_stream())
+    } else {
+        None
+    }
+}
+
+/// Splits the literal into the `format!` string with the lone braces
+/// escaped and the plain text with `{{` and `}}` unescaped. Returns `true`
+/// if the literal has at least one `{ident}` placeholder.
+fn split_placeholders(value: &str) -> (String, String, bool) {
+    let mut format = String::new();
+    let mut text = String::new();
+    let mut placeholders = false;
+    let mut rest = value;
+    while let Some(ch) = rest.chars().next() {
+        if let Some(tail) = rest.strip_prefix("{{").or_else(|| rest.strip_prefix("}}")) {
+            format.push_str(&rest[..2]);
+            text.push(ch);
+            rest = tail;
+            continue;
+        }
+        if ch == '{' {
+            if let Some(end) = rest.find('}') {
+                let inner = &rest[1..end];
+                let name = inner.split_once(':').map_or(inner, |(name, _)| name);
+                if is_ident(name) {
+                    format.push_str(&rest[..=end]);
+                    text.push_str(&rest[..=end]);
+                    placeholders = true;
+                    rest = &rest[end + 1..];
+                    continue;
+                }
+            }
+        }
+        match ch {
+            '{' => format.push_str("{{"),
+            '}' => format.push_str("}}"),
+            ch => format.push(ch),
+        }
+        text.push(ch);
+        rest = &rest[ch.len_utf8()..];
+    }
+    (format, text, placeholders)
+}
+
+fn is_ident(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|ch| ch.is_alphabetic() || ch == '_')
+        .unwrap_or(false)
+        && chars.all(|ch| ch.is_alphanumeric() || ch == '_')
+}
+
+/// Returns the ident of the tag, signal or bind property name, or the error
+/// pointing to the name when it can't be the rust identifier.
+fn name_ident(name: &str, span: proc_macro2::Span, kind: &str) -> syn::Result<Ident> {
+    if !is_ident(name) {
+        match kind {
+            "tag" => throw!(
+                span,
+                "Unknown eml tag <{name}>: widget tags should be valid rust identifiers \
+                 registered with #[widget]"
+            ),
+            "signal" => throw!(span, "Invalid signal name on:{name}"),
+            _ => throw!(span, "Invalid property name {kind}{name}"),
+        }
+    }
+    Ok(Ident::new(name, span))
+}
+
+/// The `white-space="pre-line"` attribute sets the `WhiteSpace` of the
+/// element texts, it is not passed to the widget.
+const WHITE_SPACE: &str = "white-space";
+
+fn white_space_variant(ctx: &Context, attr: &NodeAttribute) -> syn::Result<TokenStream> {
+    let core = ctx.core_path();
+    let value = attr.value.as_ref().map(|v| v.as_ref());
+    let Some(Expr::Lit(ExprLit {
+        lit: Lit::Str(value),
+        ..
+    })) = value
+    else {
+        throw!(attr.span(), "{WHITE_SPACE} expects \"normal\", \"pre-line\" or \"pre\"")
+    };
+    let variant = match value.value().as_str() {
+        "normal" => quote! { Normal },
+        "pre-line" => quote! { PreLine },
+        "pre" => quote! { Pre },
+        other => throw!(value.span(), "Invalid {WHITE_SPACE} value: {other}"),
+    };
+    Ok(quote! { #core::eml::text::WhiteSpace::#variant })
+}
+
+/// Returns the `params` of the `{..params}` spread block.
+fn spread(block: &Expr) -> Option<&Expr> {
+    let Expr::Block(block) = block else {
+        return None;
+    };
+    let [Stmt::Expr(Expr::Range(range))] = block.block.stmts.as_slice() else {
+        return None;
+    };
+    match (&range.from, &range.to, &range.limits) {
+        (None, Some(params), RangeLimits::HalfOpen(_)) => Some(params),
+        _ => None,
+    }
+}
+
+/// Returns the name of the macro the `expr` invokes: `sync` for both
+/// `sync!(..)` and `belly::sync!(..)`.
+fn macro_name(expr: &Expr) -> Option<String> {
+    let Expr::Macro(mac) = expr else {
+        return None;
+    };
+    mac.mac.path.segments.last().map(|s| s.ident.to_string())
+}
+
+fn is_punct(token: Option<&TokenTree>, ch: char) -> bool {
+    matches!(token, Some(TokenTree::Punct(punct)) if punct.as_char() == ch)
+}
+
+/// Splits the `sync!(target | forward, | backward)` tokens into the target
+/// and the optional transformers of both directions. The single transformer
+/// of `sync!(target | transform)` is used in both directions.
+fn split_sync(tokens: TokenStream) -> (TokenStream, Option<TokenStream>, Option<TokenStream>) {
+    let tokens: Vec<_> = tokens.into_iter().collect();
+    let separated = (0..tokens.len())
+        .find(|&idx| is_punct(tokens.get(idx), ',') && is_punct(tokens.get(idx + 1), '|'));
+    let trailing = separated.is_none() && is_punct(tokens.last(), ',');
+    let (head, backward) = match separated {
+        Some(idx) => (
+            &tokens[..idx],
+            Some(tokens[idx + 2..].iter().cloned().collect()),
+        ),
+        None if trailing => (&tokens[..tokens.len() - 1], None),
+        None => (&tokens[..], None),
+    };
+    let (target, forward) = match head.iter().position(|t| is_punct(Some(t), '|')) {
+        Some(idx) => (
+            head[..idx].iter().cloned().collect(),
+            Some(head[idx + 1..].iter().cloned().collect::<TokenStream>()),
+        ),
+        None => (head.iter().cloned().collect(), None),
+    };
+    if separated.is_some() || trailing {
+        (target, forward, backward)
+    } else {
+        (target, forward.clone(), forward)
+    }
+}
+
 fn create_single_command_stmt(expr: &ExprPath) -> syn::Result<TokenStream> {
     let component_span = expr.span();
     if let Some(component) = expr.path.get_ident() {
@@ -79,6 +280,23 @@ fn create_attr_stmt(ctx: &Context, attr: &NodeAttribute) -> syn::Result<TokenStr
                 Ok(quote_spanned! {attr_span=>
                     __ctx.params.merge(#attr_value);
                 })
+            } else if attr_name == DYNAMIC_CLASS {
+                Ok(quote_spanned! {attr_span=>
+                    let __classes = ::std::string::ToString::to_string(&#attr_value);
+                    for __class in __classes.split_whitespace() {
+                        __ctx.params.add(#core::eml::Param::new(
+                            &::std::format!("c:{}", __class),
+                            #core::eml::Variant::Undefined
+                        ));
+                    }
+                })
+            } else if let Some(attr_value) = interpolate(attr_value) {
+                Ok(quote_spanned! {attr_span=>
+                    __ctx.params.add(#core::eml::Param::new(
+                        #attr_name.into(),
+                        (#attr_value).into()
+                    ));
+                })
             } else {
                 Ok(quote_spanned! {attr_span=>
                     __ctx.params.add(#core::eml::Param::new(
@@ -171,9 +389,7 @@ fn process_slots(ctx: &Context, node: &NodeElement) -> syn::Result<TokenStream>
         Ok(quote! {
             let mut __slot_value: Vec<Entity> = vec![];
             #slot_content
-            __world.resource::<#core::eml::Slots>()
-                .clone()
-                .insert(#core::tagstr::Tag::new(#slot_name), __slot_value);
+            __ctx.slots.insert(#core::tagstr::Tag::new(#slot_name), __slot_value);
         })
     } else {
         if &attr.key.to_string() != "define" {
@@ -184,9 +400,9 @@ fn process_slots(ctx: &Context, node: &NodeElement) -> syn::Result<TokenStream>
         }
         let slot_name = attr.value.as_ref().unwrap().as_ref();
         Ok(quote! {
-            let __slot_value = __world.resource::<#core::eml::Slots>()
-                .clone()
-                .remove(#core::tagstr::Tag::new(#slot_name));
+            let __slot_value = __world
+                .get_resource_mut::<#core::eml::Slots>()
+                .and_then(|mut slots| slots.remove(#core::tagstr::Tag::new(#slot_name)));
             if let Some(__slot_value) = __slot_value {
                 __ctx.children.extend(__slot_value);
             } else {
@@ -198,6 +414,115 @@ fn process_slots(ctx: &Context, node: &NodeElement) -> syn::Result<TokenStream>
     }
 }
 
+fn process_list_template(ctx: &Context, node: &NodeElement) -> syn::Result<(Ident, TokenStream)> {
+    let span = node.span();
+    if node.attributes.len() != 1 {
+        throw!(
+            span,
+            "<template> tag should have exactly 1 attribute: <template item>"
+        )
+    }
+    let Node::Attribute(item_attr) = &node.attributes[0] else {
+        throw!(span, "Can't threat node as Node::Attribute")
+    };
+    if item_attr.value.is_some() {
+        throw!(
+            span,
+            "The attribute of <template> tag shouldn't has any value: <template item>"
+        )
+    }
+    let item_ident = Ident::new(&item_attr.key.to_string(), item_attr.span());
+    let mut elements = node
+        .children
+        .iter()
+        .filter(|ch| matches!(ch, Node::Element(_)));
+    let (Some(root), None) = (elements.next(), elements.next()) else {
+        throw!(span, "<template> tag should have exactly 1 child element")
+    };
+    Ok((item_ident, construct(ctx, root)?))
+}
+
+/// Builds the children nodes into the `__ctx.children`. The `template` is
+/// the `<template>` child not consumed by the `bind:items` of the parent,
+/// the `white_space` is the `WhiteSpace` applied to the texts.
+fn parse_children(
+    ctx: &Context,
+    nodes: &[Node],
+    template: Option<&NodeElement>,
+    white_space: &TokenStream,
+) -> syn::Result<TokenStream> {
+    let core = ctx.core_path();
+    let mut children = quote! {};
+    for child in nodes.iter() {
+        match child {
+            Node::Element(element) => {
+                let element_name = element.name.to_string();
+                let expr = match element_name.as_str() {
+                    "for" => process_for_loop(ctx, element)?,
+                    "slot" => process_slots(ctx, element)?,
+                    "template" => {
+                        if template.is_some() {
+                            throw!(
+                                element.span(),
+                                "<template> tag requires bind:items=from!(...) on the parent element"
+                            )
+                        }
+                        quote! {}
+                    }
+                    _ => {
+                        let expr = parse(ctx, child)?;
+                        quote! {
+                            __ctx.children.push( #expr );
+                        }
+                    }
+                };
+                children = quote! {
+                    #children
+                    #expr
+                }
+            }
+            Node::Text(text) => {
+                let text = text.value.as_ref();
+                let text = interpolate(text).unwrap_or_else(|| text.to_token_stream());
+                children = quote! {
+                    #children
+                    let __text = #core::eml::pool::spawn(__world);
+                    __world.entity_mut(__text).insert((
+                        ::bevy::prelude::TextBundle {
+                            text: ::bevy::prelude::Text::from_section(
+                                #white_space.normalize(&#text),
+                                ::std::default::Default::default()
+                            ),
+                            ..default()
+                        },
+                        #core::element::Element::inline(),
+                    ));
+                    __ctx.children.push(__text);
+                };
+            }
+            Node::Fragment(fragment) => {
+                let expr = parse_children(ctx, &fragment.children, template, white_space)?;
+                children = quote! {
+                    #children
+                    #expr
+                }
+            }
+            Node::Block(block) => {
+                let block = block.value.as_ref();
+                let block_span = block.span();
+                children = quote_spanned! { block_span=>
+                    #children
+                    for __child in #block.into_content(__parent, __world) {
+                        __ctx.children.push( __child );
+                    }
+                }
+            }
+            _ => (),
+        };
+    }
+    Ok(children)
+}
+
 fn parse<'a>(ctx: &Context, element: &'a Node) -> syn::Result<TokenStream> {
     let core = ctx.core_path();
     let mut children = quote! {};
@@ -206,17 +531,34 @@ fn parse<'a>(ctx: &Context, element: &'a Node) -> syn::Result<TokenStream> {
         let __parent = if __root_builder {
             __parent
         } else {
-            __world.spawn_empty().id()
+            #core::eml::pool::spawn(__world)
         };
     };
     let Node::Element(element) = element else {
         throw!(element.span(), "Expected eml element")
     };
     let mut parent_defined = false;
+    let mut template = element.children.iter().find_map(|ch| match ch {
+        Node::Element(elem) if &elem.name.to_string() == "template" => Some(elem),
+        _ => None,
+    });
+    let mut white_space = quote! { #core::eml::text::WhiteSpace::Normal };
+    // the required params are checked at compile time only when all
+    // the params are known
+    let mut provided = Some(vec![]);
+    let mut state_defined = false;
     for attr in element.attributes.iter() {
         if let Node::Block(entity) = attr {
             let entity_span = entity.value.span();
             let entity = entity.value.as_ref();
+            if let Some(params) = spread(entity) {
+                provided = None;
+                children = quote_spanned! {entity_span=>
+                    #children
+                    __ctx.params.merge(#core::eml::IntoParams::into_params(#params));
+                };
+                continue;
+            }
             if parent_defined {
                 throw!(entity_span, "Entity already provided by entity attribute")
             }
@@ -234,15 +576,13 @@ fn parse<'a>(ctx: &Context, element: &'a Node) -> syn::Result<TokenStream> {
                 let Some(handler) = attr.value.as_ref() else {
                     throw!(attr_span, "on:{signal} param should provide connection")
                 };
-                let signal_ident = syn::Ident::new(signal, handler.span());
+                let signal_ident = name_ident(signal, attr.key.span(), "signal")?;
                 let handler = handler.as_ref();
-                let handler_stream = handler.to_token_stream().to_string().trim().to_string();
-                let method =
-                    if handler_stream.starts_with("run!") || handler_stream.starts_with("run !") {
-                        quote! { handle }
-                    } else {
-                        quote! { func }
-                    };
+                let method = if macro_name(handler).as_deref() == Some("run") {
+                    quote! { handle }
+                } else {
+                    quote! { func }
+                };
                 connections = quote! {
                     #connections
                     __builder.on().#signal_ident().#method(#handler).from(__parent).write(__world);
@@ -252,20 +592,73 @@ fn parse<'a>(ctx: &Context, element: &'a Node) -> syn::Result<TokenStream> {
                     throw!(attr_span, "bind:{prop} param should provide connection")
                 };
                 let bind = bind.as_ref();
-                let prop = syn::Ident::new(prop, attr.key.span());
-                let stream = bind.to_token_stream().to_string();
-                if stream.trim().starts_with("to!") || stream.trim().starts_with("to !") {
+                let prop = name_ident(&prop.replace('-', "_"), attr.key.span(), "bind:")?;
+                let bind_macro = macro_name(bind);
+                let from_bind = bind_macro.as_deref() == Some("from");
+                if prop == "items" && from_bind && template.is_some() {
+                    let template = template.take().unwrap();
+                    let (item, item_template) = process_list_template(ctx, template)?;
+                    connections = quote_spanned! {attr_span=>
+                        #connections
+                        #core::relations::list::ListBind::new(
+                            #bind,
+                            __parent,
+                            move |#item| #item_template
+                        ).write(__world);
+                    };
+                } else if bind_macro.as_deref() == Some("to") {
                     connections = quote_spanned! {attr_span=>
                         #connections
                         (__builder.bind_from().#prop(__parent) >> #bind).write(__world);
                     };
-                } else if stream.trim().starts_with("from!") || stream.trim().starts_with("from !")
-                {
+                } else if from_bind {
                     connections = quote_spanned! {attr_span=>
                         #connections
                         (__builder.bind_to().#prop(__parent) << #bind).write(__world);
                     };
+                } else if let (Some("sync"), Expr::Macro(sync)) = (bind_macro.as_deref(), bind) {
+                    let (target, forward, backward) = split_sync(sync.mac.tokens.clone());
+                    let forward = forward.map(|transformer| quote! { | #transformer });
+                    let backward = backward.map(|transformer| quote! { | #transformer });
+                    connections = quote_spanned! {attr_span=>
+                        #connections
+                        (__builder.bind_from().#prop(__parent) >> #core::to!(#target #forward)).write(__world);
+                        (__builder.bind_to().#prop(__parent) << #core::from!(#target #backward)).write(__world);
+                    };
+                } else {
+                    throw!(
+                        bind.span(),
+                        "bind:{prop} expects from!(...), to!(...) or sync!(...) as value"
+                    )
                 }
+            } else if let Some(visibility) = attr_name.strip_prefix("state:") {
+                let Some(state) = attr.value.as_ref() else {
+                    throw!(attr_span, "state:{visibility} param should provide the state value")
+                };
+                let state = state.as_ref();
+                let constructor = match visibility {
+                    "visible" => quote! { visible },
+                    "hidden" => quote! { hidden },
+                    _ => throw!(
+                        attr.key.span(),
+                        "Expected state:visible or state:hidden, got state:{visibility}"
+                    ),
+                };
+                if state_defined {
+                    throw!(attr_span, "Only one state:visible or state:hidden param is allowed")
+                }
+                state_defined = true;
+                children = quote_spanned! {attr_span=>
+                    #children
+                    __ctx.params.add(#core::eml::Param::from_commands(
+                        "state-visibility",
+                        ::std::boxed::Box::new(move |c| {
+                            c.insert(#core::states::StateVisibility::#constructor(#state));
+                        })
+                    ));
+                };
+            } else if attr_name == WHITE_SPACE {
+                white_space = white_space_variant(ctx, attr)?;
             } else if &attr_name == "entity" {
                 if parent_defined {
                     throw!(attr_span, "Entity already provided by braced block")
@@ -283,6 +676,13 @@ fn parse<'a>(ctx: &Context, element: &'a Node) -> syn::Result<TokenStream> {
                     }
                 };
             } else {
+                if attr_name == "params" {
+                    provided = None;
+                } else if !attr_name.contains(':') && attr_name != "with" {
+                    if let Some(provided) = provided.as_mut() {
+                        provided.push(attr_name.clone());
+                    }
+                }
                 let attr_stmt = create_attr_stmt(ctx, attr)?;
                 children = quote! {
                     #children
@@ -291,65 +691,43 @@ fn parse<'a>(ctx: &Context, element: &'a Node) -> syn::Result<TokenStream> {
             }
         }
     }
-    for child in element.children.iter() {
-        match child {
-            Node::Element(element) => {
-                let element_name = element.name.to_string();
-                let expr = match element_name.as_str() {
-                    "for" => process_for_loop(ctx, element)?,
-                    "slot" => process_slots(ctx, element)?,
-                    _ => {
-                        let expr = parse(ctx, child)?;
-                        quote! {
-                            __ctx.children.push( #expr );
-                        }
-                    }
-                };
-                children = quote! {
-                    #children
-                    #expr
-                }
-            }
-            Node::Text(text) => {
-                let text = text.value.as_ref();
-                children = quote! {
-                    #children
-                    __ctx.children.push(
-                        __world.spawn(::bevy::prelude::TextBundle {
-                            text: ::bevy::prelude::Text::from_section(
-                                #text,
-                                ::std::default::Default::default()
-                            ),
-                            ..default()
-                        })
-                        .insert(#core::element::Element::inline())
-                        .id()
-                    );
-                };
-            }
-            Node::Block(block) => {
-                let block = block.value.as_ref();
-                let block_span = block.span();
-                children = quote_spanned! { block_span=>
-                    #children
-                    for __child in #block.into_content(__parent, __world) {
-                        __ctx.children.push( __child );
-                    }
-                }
-            }
-            _ => (),
-        };
-    }
+    let content = parse_children(ctx, &element.children, template, &white_space)?;
+    children = quote! {
+        #children
+        #content
+    };
 
-    let tag = syn::Ident::new(&element.name.to_string(), element.span());
+    let tag = name_ident(&element.name.to_string(), element.name.span(), "tag")?;
+    let tag_span = tag.span();
+    // the unknown tags are reported by rustc as missing `Widgets::tag()`,
+    // the span points the error to the tag instead of the whole macro
+    let builder = quote_spanned! {tag_span=> #core::Widgets::#tag() };
+    // `line!()` and `column!()` spanned to the tag report the tag position
+    let location = quote_spanned! {tag_span=>
+        #core::eml::SourceLocation::new(::std::file!(), ::std::line!(), ::std::column!())
+    };
+    let check_required = match provided {
+        Some(provided) => {
+            let required = format_ident!("{}_REQUIRED_PARAMS", tag.to_string().to_uppercase());
+            quote_spanned! {tag_span=>
+                const _: () = #core::eml::check_required_params(
+                    #core::Widgets::#required,
+                    &[#(#provided),*]
+                );
+            }
+        }
+        None => quote! {},
+    };
     Ok(quote! {
         {
+            #check_required
             #parent
             __root_builder = false;
             let mut __ctx = #core::eml::WidgetData::new(__parent);
+            __ctx.location = Some(#location);
 
             #children
-            let __builder = #core::Widgets::#tag();
+            let __builder = #builder;
             __builder.build(__world, __ctx);
             #connections
             __parent
@@ -357,7 +735,15 @@ fn parse<'a>(ctx: &Context, element: &'a Node) -> syn::Result<TokenStream> {
     })
 }
 
-pub fn construct(ctx: &Context, root: &Node) -> syn::Result<TokenStream> {
+pub fn construct(ctx: &Context, nodes: &[Node]) -> syn::Result<TokenStream> {
+    match nodes {
+        [root @ Node::Element(_)] => construct_element(ctx, root),
+        [Node::Fragment(fragment)] => construct_fragment(ctx, &fragment.children),
+        nodes => construct_fragment(ctx, nodes),
+    }
+}
+
+fn construct_element(ctx: &Context, root: &Node) -> syn::Result<TokenStream> {
     let body = parse(ctx, root)?;
     let core = ctx.core_path();
     Ok(quote! {
@@ -366,26 +752,15 @@ pub fn construct(ctx: &Context, root: &Node) -> syn::Result<TokenStream> {
                 __world: &mut ::bevy::prelude::World,
                 __parent: Option<::bevy::prelude::Entity>,
             | {
-                let mut __slots_resource = __world.resource::<#core::eml::Slots>().clone();
-                let __defined_slots = __slots_resource.keys();
                 let __provided_parent = __parent;
                 let __parent = if let Some(parent) = __parent {
                     parent
                 } else {
-                    __world.spawn_empty().id()
+                    #core::eml::pool::spawn(__world)
                 };
                 let mut __root_builder = true;
                 let mut __root_entity_defined = false;
                 let result = #body;
-                for __slot in __slots_resource.keys() {
-                    if !__defined_slots.contains(&__slot) {
-                        warn!("Detected unused slot '{}', despawning it contnent.", __slot);
-                        use ::bevy::ecs::system::Command;
-                        for __entity in __slots_resource.remove(__slot).unwrap() {
-                            __world.entity_mut(__entity).despawn_recursive();
-                        }
-                    }
-                }
                 if __root_entity_defined {
                     if let Some(parent) = __provided_parent {
                         __world.entity_mut(parent).despawn_recursive();
@@ -396,3 +771,179 @@ pub fn construct(ctx: &Context, root: &Node) -> syn::Result<TokenStream> {
         )
     })
 }
+
+/// The fragment (`<>...</>` or multiple root nodes) appends its content to
+/// the provided parent, or to the new `div` when there is no parent.
+fn construct_fragment(ctx: &Context, nodes: &[Node]) -> syn::Result<TokenStream> {
+    let core = ctx.core_path();
+    let template = nodes.iter().find_map(|ch| match ch {
+        Node::Element(elem) if &elem.name.to_string() == "template" => Some(elem),
+        _ => None,
+    });
+    let white_space = quote! { #core::eml::text::WhiteSpace::Normal };
+    let children = parse_children(ctx, nodes, template, &white_space)?;
+    Ok(quote! {
+        #core::eml::Eml::new(
+            move |
+                __world: &mut ::bevy::prelude::World,
+                __provided_parent: Option<::bevy::prelude::Entity>,
+            | {
+                let __parent = if let Some(parent) = __provided_parent {
+                    parent
+                } else {
+                    #core::eml::pool::spawn(__world)
+                };
+                #[allow(unused)]
+                let mut __root_builder = false;
+                #[allow(unused)]
+                let mut __root_entity_defined = false;
+                let mut __ctx = #core::eml::WidgetData::new(__parent);
+                #children
+                if __provided_parent.is_some() {
+                    __world.entity_mut(__parent).push_children(&__ctx.children);
+                } else {
+                    #core::Widgets::div().build(__world, __ctx);
+                }
+                __parent
+            }
+        )
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Expands the `eml!` body the way the macro does
+    fn expand(source: &str) -> syn::Result<TokenStream> {
+        let tokens = expand_dynamic_classes(source.parse().unwrap());
+        let nodes = syn_rsx::parse2(tokens)?;
+        construct(&Context::new(), &nodes)
+    }
+
+    /// Returns the compile error of the `eml!` body and the column it points to
+    fn compile_error(source: &str) -> (String, usize) {
+        let Err(error) = expand(source) else {
+            panic!("`{source}` should not compile");
+        };
+        (error.to_string(), error.span().start().column)
+    }
+
+    /// Returns the span of the `tag` in the `Widgets::tag()` call
+    fn widget_call_span(tokens: TokenStream, tag: &str) -> Option<proc_macro2::Span> {
+        let tokens: Vec<_> = tokens.into_iter().collect();
+        let call = tokens.windows(4).find_map(|window| match window {
+            [TokenTree::Ident(widgets), _, _, TokenTree::Ident(name)]
+                if widgets == "Widgets" && name == tag =>
+            {
+                Some(name.span())
+            }
+            _ => None,
+        });
+        call.or_else(|| {
+            tokens.iter().find_map(|token| match token {
+                TokenTree::Group(group) => widget_call_span(group.stream(), tag),
+                _ => None,
+            })
+        })
+    }
+
+    #[test]
+    fn unknown_tag_error_points_to_tag() {
+        let tokens = expand("<span><spam/></span>").unwrap();
+        let span = widget_call_span(tokens, "spam").expect("no Widgets::spam() call");
+        assert_eq!(span.start().column, 7);
+    }
+
+    #[test]
+    fn invalid_names_are_reported() {
+        assert_eq!(
+            compile_error("<my-widget/>"),
+            (
+                "Unknown eml tag <my-widget>: widget tags should be valid rust identifiers \
+                 registered with #[widget]"
+                    .to_string(),
+                1
+            )
+        );
+        assert_eq!(
+            compile_error("<span on:my-signal=run!(|| {})/>"),
+            ("Invalid signal name on:my-signal".to_string(), 6)
+        );
+    }
+
+    #[test]
+    fn invalid_bind_is_reported() {
+        assert_eq!(
+            compile_error("<span bind:value=42/>"),
+            (
+                "bind:value expects from!(...), to!(...) or sync!(...) as value".to_string(),
+                17
+            )
+        );
+        assert_eq!(
+            compile_error("<span bind:value/>"),
+            ("bind:value param should provide connection".to_string(), 6)
+        );
+    }
+
+    #[test]
+    fn sync_transformers_are_split() {
+        let split = |source: &str| {
+            let (target, forward, backward) = split_sync(source.parse().unwrap());
+            (
+                target.to_string(),
+                forward.map(|f| f.to_string()),
+                backward.map(|b| b.to_string()),
+            )
+        };
+        let target = "e , Health : current".to_string();
+        let transformer = |t: &str| Some(t.to_string());
+        assert_eq!(split("e, Health:current"), (target.clone(), None, None));
+        assert_eq!(
+            split("e, Health:current | r"),
+            (target.clone(), transformer("r"), transformer("r"))
+        );
+        assert_eq!(
+            split("e, Health:current | v | v * 2., | v | v / 2."),
+            (
+                target.clone(),
+                transformer("v | v * 2."),
+                transformer("v | v / 2.")
+            )
+        );
+        assert_eq!(
+            split("e, Health:current | r,"),
+            (target.clone(), transformer("r"), None)
+        );
+        assert_eq!(
+            split("e, Health:current, | r"),
+            (target, None, transformer("r"))
+        );
+    }
+
+    #[test]
+    fn test_placeholders() {
+        let (format, text, placeholders) = split_placeholders("Score: {score} {{points}}");
+        assert_eq!(format, "Score: {score} {{points}}");
+        assert_eq!(text, "Score: {score} {points}");
+        assert!(placeholders);
+
+        let (format, _, placeholders) = split_placeholders("{value:.2}%");
+        assert_eq!(format, "{value:.2}%");
+        assert!(placeholders);
+
+        let (_, text, placeholders) = split_placeholders("{{literal}} braces");
+        assert_eq!(text, "{literal} braces");
+        assert!(!placeholders);
+
+        let (format, text, placeholders) = split_placeholders("{ color: red } {0} {name}");
+        assert_eq!(format, "{{ color: red }} {{0}} {name}");
+        assert_eq!(text, "{ color: red } {0} {name}");
+        assert!(placeholders);
+
+        let (_, text, placeholders) = split_placeholders("{ }");
+        assert_eq!(text, "{ }");
+        assert!(!placeholders);
+    }
+}
@@ -2,6 +2,7 @@ mod context;
 mod eml;
 mod ess;
 mod ext;
+mod params;
 mod run;
 mod widgets;
 
@@ -14,22 +15,41 @@ use syn_rsx::parse;
 #[proc_macro]
 pub fn eml(tree: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let ctx = context::Context::new();
-    match parse(tree.into()) {
+    match parse(eml::expand_dynamic_classes(tree.into())) {
         Err(err) => err.to_compile_error().into(),
-        Ok(root) => proc_macro::TokenStream::from(match eml::construct(&ctx, &root[0]) {
+        Ok(root) => proc_macro::TokenStream::from(match eml::construct(&ctx, &root) {
             Ok(stream) => stream,
             Err(e) => e.to_compile_error(),
         }),
     }
 }
 
+#[proc_macro_derive(IntoParams, attributes(param))]
+pub fn into_params(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let ast = parse_macro_input!(input as syn::DeriveInput);
+    proc_macro::TokenStream::from(match params::derive_into_params(ast) {
+        Err(e) => e.to_compile_error(),
+        Ok(stream) => stream,
+    })
+}
+
+#[proc_macro_derive(EnumParam, attributes(param))]
+pub fn enum_param(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let ast = parse_macro_input!(input as syn::DeriveInput);
+    proc_macro::TokenStream::from(match params::derive_enum_param(ast) {
+        Err(e) => e.to_compile_error(),
+        Ok(stream) => stream,
+    })
+}
+
 #[proc_macro_attribute]
 pub fn widget(
-    _args: proc_macro::TokenStream,
+    args: proc_macro::TokenStream,
     input: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
+    let args = parse_macro_input!(args as widgets::WidgetArgs);
     let ast = parse_macro_input!(input as syn::ItemFn);
-    proc_macro::TokenStream::from(match widgets::widget(ast) {
+    proc_macro::TokenStream::from(match widgets::widget(args, ast) {
         Err(e) => e.to_compile_error(),
         Ok(stream) => stream,
     })
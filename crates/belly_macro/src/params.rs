@@ -0,0 +1,206 @@
+use super::context::Context;
+use proc_macro2::TokenStream;
+use quote::*;
+use syn::{spanned::Spanned, Data, DeriveInput, Fields, Lit, Meta, NestedMeta, Type};
+
+macro_rules! throw {
+    ($span:expr, $msg:literal $($args:tt)*) => {
+        return Err(syn::Error::new($span, format!($msg $($args)*)))
+    };
+}
+
+struct ParamField {
+    name: String,
+    skip: bool,
+}
+
+impl ParamField {
+    fn parse(field: &syn::Field) -> syn::Result<ParamField> {
+        let ident = field.ident.as_ref().unwrap();
+        let mut param = ParamField {
+            name: ident.to_string().trim_start_matches("r#").replace('_', "-"),
+            skip: false,
+        };
+        for attr in field.attrs.iter().filter(|a| a.path.is_ident("param")) {
+            let Meta::List(list) = attr.parse_meta()? else {
+                throw!(attr.span(), "Expected #[param(name = \"...\")] or #[param(skip)]")
+            };
+            for item in list.nested.iter() {
+                match item {
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("skip") => {
+                        param.skip = true;
+                    }
+                    NestedMeta::Meta(Meta::NameValue(value)) if value.path.is_ident("name") => {
+                        let Lit::Str(name) = &value.lit else {
+                            throw!(value.lit.span(), "Param name should be a string")
+                        };
+                        param.name = name.value();
+                    }
+                    _ => throw!(item.span(), "Unsupported param attribute"),
+                }
+            }
+        }
+        Ok(param)
+    }
+}
+
+fn is_option(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+    path.path
+        .segments
+        .last()
+        .map(|s| s.ident == "Option")
+        .unwrap_or(false)
+}
+
+pub fn derive_into_params(ast: DeriveInput) -> syn::Result<TokenStream> {
+    let ctx = Context::new();
+    let core = ctx.core_path();
+    let Data::Struct(data) = &ast.data else {
+        throw!(ast.span(), "IntoParams can be derived only for structs")
+    };
+    let Fields::Named(fields) = &data.fields else {
+        throw!(ast.span(), "IntoParams can be derived only for structs with named fields")
+    };
+    let mut inserts = quote! {};
+    for field in fields.named.iter() {
+        let param = ParamField::parse(field)?;
+        if param.skip {
+            continue;
+        }
+        let ident = field.ident.as_ref().unwrap();
+        let name = &param.name;
+        let span = field.span();
+        let insert = if name.starts_with("c:") {
+            quote_spanned! {span=>
+                if self.#ident {
+                    __params.insert(#name, #core::eml::Variant::Undefined);
+                }
+            }
+        } else if is_option(&field.ty) {
+            quote_spanned! {span=>
+                if let Some(value) = self.#ident {
+                    __params.insert(#name, value.into());
+                }
+            }
+        } else {
+            quote_spanned! {span=>
+                __params.insert(#name, self.#ident.into());
+            }
+        };
+        inserts = quote! {
+            #inserts
+            #insert
+        };
+    }
+    let ident = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #core::eml::IntoParams for #ident #ty_generics #where_clause {
+            fn into_params(self) -> #core::eml::Params {
+                let mut __params = #core::eml::Params::default();
+                #inserts
+                __params
+            }
+        }
+    })
+}
+
+/// The name of the enum variant in `eml`: `RightToLeft` becomes `right-to-left`
+fn variant_name(ident: &syn::Ident) -> String {
+    let mut name = String::new();
+    for ch in ident.to_string().chars() {
+        if ch.is_uppercase() && !name.is_empty() {
+            name.push('-');
+        }
+        name.extend(ch.to_lowercase());
+    }
+    name
+}
+
+/// Returns the `#[param(name = "...")]` and the `#[param(alias = "...")]`
+/// names of the variant
+fn variant_names(variant: &syn::Variant) -> syn::Result<Vec<String>> {
+    let mut name = variant_name(&variant.ident);
+    let mut aliases = vec![];
+    for attr in variant.attrs.iter().filter(|a| a.path.is_ident("param")) {
+        let Meta::List(list) = attr.parse_meta()? else {
+            throw!(attr.span(), "Expected #[param(name = \"...\")] or #[param(alias = \"...\")]")
+        };
+        for item in list.nested.iter() {
+            let NestedMeta::Meta(Meta::NameValue(value)) = item else {
+                throw!(item.span(), "Unsupported param attribute")
+            };
+            let Lit::Str(lit) = &value.lit else {
+                throw!(value.lit.span(), "Param name should be a string")
+            };
+            if value.path.is_ident("name") {
+                name = lit.value();
+            } else if value.path.is_ident("alias") {
+                aliases.push(lit.value());
+            } else {
+                throw!(item.span(), "Unsupported param attribute")
+            }
+        }
+    }
+    aliases.insert(0, name);
+    Ok(aliases)
+}
+
+pub fn derive_enum_param(ast: DeriveInput) -> syn::Result<TokenStream> {
+    let ctx = Context::new();
+    let core = ctx.core_path();
+    let Data::Enum(data) = &ast.data else {
+        throw!(ast.span(), "EnumParam can be derived only for enums")
+    };
+    let ident = &ast.ident;
+    let mut arms = quote! {};
+    let mut expected = vec![];
+    for variant in data.variants.iter() {
+        if !matches!(variant.fields, Fields::Unit) {
+            throw!(variant.span(), "EnumParam can be derived only for enums with unit variants")
+        }
+        let names = variant_names(variant)?;
+        let variant_ident = &variant.ident;
+        arms = quote! {
+            #arms
+            #(#names)|* => Ok(#ident::#variant_ident),
+        };
+        expected.push(format!("`{}`", names[0]));
+    }
+    let error = format!(
+        "Can't parse `{{}}` as {ident}, expected one of: {}",
+        expected.join(", ")
+    );
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::std::str::FromStr for #ident #ty_generics #where_clause {
+            type Err = ::std::string::String;
+            fn from_str(value: &str) -> ::std::result::Result<Self, Self::Err> {
+                match value {
+                    #arms
+                    value => Err(::std::format!(#error, value)),
+                }
+            }
+        }
+
+        impl #impl_generics ::std::convert::TryFrom<#core::eml::Variant>
+            for #ident #ty_generics #where_clause
+        {
+            type Error = ::std::string::String;
+            fn try_from(value: #core::eml::Variant) -> ::std::result::Result<Self, Self::Error> {
+                value.get_or_parse()
+            }
+        }
+
+        impl #impl_generics ::std::convert::From<#ident #ty_generics>
+            for #core::eml::Variant #where_clause
+        {
+            fn from(value: #ident #ty_generics) -> Self {
+                #core::eml::Variant::boxed(value)
+            }
+        }
+    })
+}
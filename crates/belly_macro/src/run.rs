@@ -32,24 +32,72 @@ impl Run {
         };
         let mut types = quote! {};
         let mut sys_args = quote! {};
+        let mut param_types = quote! {};
+        let mut param_args = quote! {};
         for arg in self.system_args.iter() {
             let syn::FnArg::Typed(arg) = arg else {
                 continue;
             };
             let arg_pat = &arg.pat;
             let arg_type = &arg.ty;
-            sys_args = quote! { #sys_args #arg_pat, };
-            types = quote! { #types #arg_type, };
+            if is_system_param(arg_type) {
+                param_args = quote! { #param_args #arg_pat, };
+                param_types = quote! { #param_types #arg_type, };
+            } else {
+                sys_args = quote! { #sys_args #arg_pat, };
+                types = quote! { #types #arg_type, };
+            }
         }
         let body = &self.body;
         quote! {
-            (::std::marker::PhantomData::<(#types)>, #target, move |#ctx, (#sys_args)| {
-                #body;
-            })
+            (
+                ::std::marker::PhantomData::<((#types), (#param_types))>,
+                #target,
+                move |#ctx, (#sys_args), (#param_args)| {
+                    #body;
+                }
+            )
         }
     }
 }
 
+/// Arguments of these types are resolved as system params instead of
+/// being queried from the target entity.
+const SYSTEM_PARAMS: &[&str] = &[
+    "Res",
+    "ResMut",
+    "NonSend",
+    "NonSendMut",
+    "Local",
+    "Query",
+    "ParamSet",
+    "Commands",
+    "EventReader",
+    "EventWriter",
+    "Gizmos",
+];
+
+fn is_system_param(ty: &syn::Type) -> bool {
+    let syn::Type::Path(path) = ty else {
+        return false;
+    };
+    let Some(segment) = path.path.segments.last() else {
+        return false;
+    };
+    let name = segment.ident.to_string();
+    if name == "Option" {
+        // Option<Res<T>> is a system param, Option<&T> is a query
+        let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+            return false;
+        };
+        return match args.args.first() {
+            Some(syn::GenericArgument::Type(ty)) => is_system_param(ty),
+            _ => false,
+        };
+    }
+    SYSTEM_PARAMS.contains(&name.as_str())
+}
+
 fn args_done(i: &mut syn::parse::ParseStream) -> bool {
     if i.peek(Token![|]) {
         i.parse::<Token![|]>().unwrap();
@@ -17,7 +17,50 @@ macro_rules! ident {
     };
 }
 
-pub fn widget(ast: syn::ItemFn) -> Result<TokenStream, syn::Error> {
+/// #[widget(state = MyState)]
+#[derive(Default)]
+pub struct WidgetArgs {
+    state: Option<syn::Type>,
+}
+
+impl syn::parse::Parse for WidgetArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut args = WidgetArgs::default();
+        while !input.is_empty() {
+            let key = input.parse::<syn::Ident>()?;
+            input.parse::<syn::Token![=]>()?;
+            if key == "state" {
+                args.state = Some(input.parse()?);
+            } else {
+                throw!(key.span(), "Unsupported widget argument `{key}`, expected `state`")
+            }
+            if !input.is_empty() {
+                input.parse::<syn::Token![,]>()?;
+            }
+        }
+        Ok(args)
+    }
+}
+
+impl WidgetArgs {
+    fn impl_instantiate_state(&self, core: &TokenStream) -> TokenStream {
+        let Some(state) = &self.state else {
+            return quote! {};
+        };
+        quote! {
+            fn instantiate_state(
+                &self,
+                world: &mut ::bevy::prelude::World,
+                params: &mut #core::eml::Params
+            ) -> Option<Box<dyn #core::eml::WidgetState>> {
+                use #core::eml::FromWorldAndParams;
+                Some(Box::new(<#state>::from_world_and_params(world, params)))
+            }
+        }
+    }
+}
+
+pub fn widget(args: WidgetArgs, ast: syn::ItemFn) -> Result<TokenStream, syn::Error> {
     let ctx = Context::new();
     let core = ctx.core_path();
     let attrs = WidgetAttributes::parse(&ast, &ctx)?;
@@ -62,6 +105,12 @@ pub fn widget(ast: syn::ItemFn) -> Result<TokenStream, syn::Error> {
     let signals_impl = attrs.impl_signals();
     let signals_deref = attrs.impl_signals_deref();
     let default_styles_impl = attrs.impl_default_styles();
+    let parts_impl = attrs.impl_parts();
+    let style_props = attrs.style_props();
+    let register_style_props_impl = attrs.impl_register_style_props();
+    let instantiate_state_impl = args.impl_instantiate_state(core);
+    let required_params = attrs.required_params();
+    let required_params_const = format_ident!("{}_REQUIRED_PARAMS", widget_name.to_uppercase());
     let docs = attrs.build_docs();
 
     let alias = if let Some(extends) = &attrs.extends {
@@ -77,6 +126,7 @@ pub fn widget(ast: syn::ItemFn) -> Result<TokenStream, syn::Error> {
     Ok(quote! {
         #docs
         pub struct #widget_struct;
+        #style_props
         impl #core::eml::Widget for #widget_struct {
             type Components = #components_associated_type;
             type BuildComponents = #build_components_associated_type;
@@ -114,7 +164,13 @@ pub fn widget(ast: syn::ItemFn) -> Result<TokenStream, syn::Error> {
 
             #split_components_impl
 
+            #instantiate_state_impl
+
             #default_styles_impl
+
+            #parts_impl
+
+            #register_style_props_impl
         }
         mod #mod_relations {
             pub struct BindingsFrom;
@@ -157,6 +213,10 @@ pub fn widget(ast: syn::ItemFn) -> Result<TokenStream, syn::Error> {
             #signals_deref
         }
         pub trait #widget_extenstion {
+            #[doc(hidden)]
+            const #required_params_const: &'static [(&'static str, &'static str)] =
+                #required_params;
+
             #docs
             #[allow(non_snake_case)]
             fn #widget_ident() -> &'static #widget_struct {
@@ -209,14 +269,41 @@ struct ParamTarget {
     transformer: Option<Transfomer>,
 }
 
-/// #[param(value: String => Label, value)]
+/// #[param(value: String => Label:value, default = "text")]
 struct Param {
     name: syn::Ident,
     ty: syn::Type,
     target: ParamTarget,
+    default: Option<syn::Expr>,
+    required: bool,
     docs: Vec<String>,
 }
 
+/// Collects the tokens until the top-level `stop` punct
+fn tokens_until(input: syn::parse::ParseStream, stop: &[char]) -> syn::Result<TokenStream> {
+    input.step(|cursor| {
+        let mut rest = *cursor;
+        let mut stream = quote! {};
+        while let Some((tt, next)) = rest.token_tree() {
+            match &tt {
+                TokenTree::Punct(punct) if stop.contains(&punct.as_char()) => {
+                    return Ok((stream, rest));
+                }
+                _ => rest = next,
+            };
+            stream = quote! { #stream #tt };
+        }
+        Ok((stream, rest))
+    })
+}
+
+impl Param {
+    /// The name of the param in `eml`: `flip_x` becomes `flip-x`
+    fn key(&self) -> String {
+        self.name.to_string().replace('_', "-")
+    }
+}
+
 impl syn::parse::Parse for Param {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         let name = input.parse::<syn::Ident>()?;
@@ -229,20 +316,7 @@ impl syn::parse::Parse for Param {
         let component = input.parse::<syn::Type>()?;
         let property = if input.peek(syn::Token![:]) {
             input.parse::<syn::Token![:]>()?;
-            Some(input.step(|cursor| {
-                let mut rest = *cursor;
-                let mut stream = quote! {};
-                while let Some((tt, next)) = rest.token_tree() {
-                    match &tt {
-                        TokenTree::Punct(punct) if punct.as_char() == '|' => {
-                            return Ok((stream, rest));
-                        }
-                        _ => rest = next,
-                    };
-                    stream = quote! { #stream #tt };
-                }
-                Ok((stream, rest))
-            })?)
+            Some(tokens_until(input, &['|', ','])?)
         } else {
             None
         };
@@ -250,7 +324,7 @@ impl syn::parse::Parse for Param {
             input.parse::<syn::Token![|]>()?;
             let transformer = input.parse::<syn::Ident>()?;
             let ty = component.clone();
-            if input.is_empty() {
+            if input.is_empty() || input.peek(syn::Token![,]) {
                 Some(Transfomer {
                     ty: quote! { #ty },
                     expr: quote! { #transformer },
@@ -259,12 +333,38 @@ impl syn::parse::Parse for Param {
                 input.parse::<syn::Token![.]>()?;
                 Some(Transfomer {
                     ty: quote! { #transformer },
-                    expr: input.parse::<TokenStream>()?,
+                    expr: tokens_until(input, &[','])?,
                 })
             }
         } else {
             None
         };
+        let mut default = None;
+        let mut required = false;
+        while input.peek(syn::Token![,]) {
+            input.parse::<syn::Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let option = input.parse::<syn::Ident>()?;
+            if option == "default" {
+                input.parse::<syn::Token![=]>()?;
+                default = Some(input.parse::<syn::Expr>()?);
+            } else if option == "required" {
+                required = true;
+            } else {
+                throw!(
+                    option.span(),
+                    "Unsupported param option `{option}`, expected `default = ...` or `required`"
+                )
+            }
+        }
+        if required && default.is_some() {
+            throw!(
+                name.span(),
+                "Param `{name}` can't be both required and have the default value"
+            )
+        }
         Ok(Param {
             name,
             ty,
@@ -273,6 +373,8 @@ impl syn::parse::Parse for Param {
                 property,
                 transformer,
             },
+            default,
+            required,
             docs: vec![],
         })
         // let property = if lookahead.peek(Token![:]) {
@@ -287,6 +389,56 @@ impl syn::parse::Parse for Param {
     }
 }
 
+/// #[part(thumb)]
+struct Part {
+    name: syn::Ident,
+    docs: Vec<String>,
+}
+
+impl Part {
+    /// The name of the part in `eml` and `ess`: `tab_header` becomes `tab-header`
+    fn key(&self) -> String {
+        self.name.to_string().replace('_', "-")
+    }
+}
+
+/// #[style_prop(handle-color: Color)]
+/// #[style_prop(handle-shape: Shape => ShapeParser)]
+struct StyleProp {
+    name: String,
+    field: syn::Ident,
+    ty: syn::Type,
+    parser: Option<syn::Type>,
+    docs: Vec<String>,
+}
+
+impl syn::parse::Parse for StyleProp {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let first = input.parse::<syn::Ident>()?;
+        let mut name = first.to_string();
+        while input.peek(syn::Token![-]) {
+            input.parse::<syn::Token![-]>()?;
+            name = format!("{name}-{}", input.parse::<syn::Ident>()?);
+        }
+        let field = ident!(first.span(), "{}", name.replace('-', "_"));
+        input.parse::<syn::Token![:]>()?;
+        let ty = input.parse::<syn::Type>()?;
+        let parser = if input.peek(syn::Token![=>]) {
+            input.parse::<syn::Token![=>]>()?;
+            Some(input.parse::<syn::Type>()?)
+        } else {
+            None
+        };
+        Ok(StyleProp {
+            name,
+            field,
+            ty,
+            parser,
+            docs: vec![],
+        })
+    }
+}
+
 struct Signal {
     name: syn::Ident,
     ty: syn::Type,
@@ -364,6 +516,9 @@ struct WidgetAttributes<'a> {
     rest_components: Components,
     params: Vec<Param>,
     signals: HashMap<String, Signal>,
+    parts: Vec<Part>,
+    style_props: Vec<StyleProp>,
+    style_component: syn::Ident,
     default_styles: DefaultStyles,
     extends: Option<syn::Type>,
     docs: Vec<String>,
@@ -379,6 +534,13 @@ impl<'a> WidgetAttributes<'a> {
             rest_components: Components::default(),
             params: Vec::new(),
             signals: HashMap::new(),
+            parts: Vec::new(),
+            style_props: Vec::new(),
+            style_component: ident!(
+                ast.sig.ident.span(),
+                "{}Style",
+                ast.sig.ident.to_string().to_camel_case()
+            ),
             default_styles: DefaultStyles::new(),
             extends: None,
             docs: vec![],
@@ -395,11 +557,11 @@ impl<'a> WidgetAttributes<'a> {
                         attrs.rest_components.0.push(param.target.component.clone());
                     }
                 }
-                let name = param.name.to_string();
+                let name = param.key();
                 if attrs
                     .params
                     .iter()
-                    .filter(|p| p.name == name)
+                    .filter(|p| p.key() == name)
                     .next()
                     .is_some()
                 {
@@ -413,6 +575,22 @@ impl<'a> WidgetAttributes<'a> {
                 signal.docs = docs;
                 docs = vec![];
                 attrs.signals.insert(signal.name.to_string(), signal);
+            } else if attr.path.is_ident("part") {
+                let name = attr.parse_args::<syn::Ident>()?;
+                let part = Part { name, docs };
+                docs = vec![];
+                if attrs.parts.iter().any(|p| p.key() == part.key()) {
+                    throw!(attr.span(), "Part `{}` already defined", part.key())
+                }
+                attrs.parts.push(part);
+            } else if attr.path.is_ident("style_prop") {
+                let mut prop = attr.parse_args::<StyleProp>()?;
+                prop.docs = docs;
+                docs = vec![];
+                if attrs.style_props.iter().any(|p| p.name == prop.name) {
+                    throw!(attr.span(), "Style property `{}` already defined", prop.name)
+                }
+                attrs.style_props.push(prop);
             } else if attr.path.is_ident("styles") {
                 if let Ok(AttributeValue::<syn::Ident> { value }) = syn::parse2(attr.tokens.clone())
                 {
@@ -426,6 +604,13 @@ impl<'a> WidgetAttributes<'a> {
         }
 
         attrs.docs = docs;
+        if !attrs.style_props.is_empty() {
+            let style = &attrs.style_component;
+            let style: syn::Type = syn::parse_quote!(#style);
+            if !attrs.build_components.0.contains(&style) {
+                attrs.rest_components.0.push(style);
+            }
+        }
         attrs.components.0.extend(attrs.build_components.0.clone());
         attrs.components.0.extend(attrs.rest_components.0.clone());
         Ok(attrs)
@@ -440,6 +625,105 @@ impl<'a> WidgetAttributes<'a> {
         }
     }
 
+    fn impl_parts(&self) -> TokenStream {
+        let parts = self.parts.iter().map(|p| p.key());
+        quote! {
+            fn parts(&self) -> &'static [&'static str] {
+                &[#(#parts),*]
+            }
+        }
+    }
+
+    /// The `{Widget}Style` component and the properties delivering
+    /// the `#[style_prop]` values into it
+    fn style_props(&self) -> TokenStream {
+        if self.style_props.is_empty() {
+            return quote! {};
+        }
+        let core = self.ctx.core_path();
+        let style = &self.style_component;
+        let style_doc = format!(" The style properties of the `<{}>` widget", self.name);
+        let mut fields = quote! {};
+        let mut properties = quote! {};
+        for prop in self.style_props.iter() {
+            let field = &prop.field;
+            let ty = &prop.ty;
+            let docs = &prop.docs;
+            let name = &prop.name;
+            let property = format_ident!("{}{}Property", style, name.to_camel_case());
+            let parser = match &prop.parser {
+                Some(parser) => quote! { #parser },
+                None => quote! { <#ty as #core::ess::StylePropValue>::Parser },
+            };
+            let name_doc = format!(" <!-- @property-name={name} -->");
+            let docstring = docs.join("\n");
+            fields = quote! {
+                #fields
+                #(#[doc = #docs])*
+                pub #field: #ty,
+            };
+            properties = quote! {
+                #properties
+                #[derive(Default)]
+                #(#[doc = #docs])*
+                #[doc = #name_doc]
+                pub struct #property;
+                impl #core::ess::Property for #property {
+                    type Item = #ty;
+                    type Components = &'static mut #style;
+                    type Filters = ();
+                    type Parser = #parser;
+
+                    fn name() -> #core::Tag {
+                        #core::tag!(#name)
+                    }
+
+                    fn docstring() -> &'static str {
+                        #docstring
+                    }
+
+                    fn apply(
+                        value: &Self::Item,
+                        mut style: ::bevy::ecs::query::QueryItem<Self::Components>,
+                        _assets: &::bevy::prelude::AssetServer,
+                        _commands: &mut ::bevy::prelude::Commands,
+                        _entity: ::bevy::prelude::Entity,
+                    ) {
+                        if &style.#field != value {
+                            style.#field = value.clone();
+                        }
+                    }
+                }
+            };
+        }
+        quote! {
+            #[doc = #style_doc]
+            #[derive(::bevy::prelude::Component, Default, Clone)]
+            pub struct #style {
+                #fields
+            }
+            #properties
+        }
+    }
+
+    fn impl_register_style_props(&self) -> TokenStream {
+        if self.style_props.is_empty() {
+            return quote! {};
+        }
+        let core = self.ctx.core_path();
+        let style = &self.style_component;
+        let properties = self
+            .style_props
+            .iter()
+            .map(|p| format_ident!("{}{}Property", style, p.name.to_camel_case()));
+        quote! {
+            fn register_style_props(&self, app: &mut ::bevy::prelude::App) {
+                use #core::ess::RegisterProperty;
+                #(app.register_property::<#properties>();)*
+            }
+        }
+    }
+
     fn impl_split_components(&self) -> TokenStream {
         let mut all_components = quote! {};
         let mut build_components = quote! {};
@@ -603,6 +887,29 @@ impl<'a> WidgetAttributes<'a> {
     fn impl_instantiate_components(&self) -> Result<TokenStream, syn::Error> {
         let core = self.ctx.core_path();
         let widget_name = &self.name;
+        let mut prepare_params = quote! {};
+        for param in self.params.iter() {
+            let param_name = param.key();
+            if let Some(default) = &param.default {
+                prepare_params = quote! {
+                    #prepare_params
+                    if !params.contains(#param_name.into()) {
+                        params.insert(#param_name, (#default).into());
+                    }
+                };
+            } else if param.required {
+                prepare_params = quote! {
+                    #prepare_params
+                    if !params.contains(#param_name.into()) {
+                        ::bevy::prelude::error!(
+                            "<{}> requires the `{}` param",
+                            #widget_name,
+                            #param_name
+                        );
+                    }
+                };
+            }
+        }
         let mut instantiate_body = quote! {};
         for component in self.components.0.iter() {
             let mut params = quote! {
@@ -614,7 +921,7 @@ impl<'a> WidgetAttributes<'a> {
                 .iter()
                 .filter(|p| component == &p.target.component)
             {
-                let param_name = param.name.to_string();
+                let param_name = param.key();
                 let param_type = &param.ty;
                 params = quote! {
                     #params
@@ -643,7 +950,7 @@ impl<'a> WidgetAttributes<'a> {
                 setters = quote! {
                     #setters
                     if let Some(value) = component_params.drop_variant(#param_name.into()) {
-                        match #param_type::try_from(value) {
+                        match <#param_type>::try_from(value) {
                             Ok(value) => #prop_body,
                             Err(err) => ::bevy::prelude::error!("Can't set {}.{}: {}", #widget_name, #param_name, err),
                         }
@@ -667,11 +974,23 @@ impl<'a> WidgetAttributes<'a> {
                 world: &mut ::bevy::prelude::World,
                 params: &mut #core::eml::Params
             ) -> Self::Components {
+                #prepare_params
                 (#instantiate_body)
             }
         })
     }
 
+    /// The `(name, error)` pairs of the required params checked by `eml!`
+    /// at compile time
+    fn required_params(&self) -> TokenStream {
+        let required = self.params.iter().filter(|p| p.required).map(|p| {
+            let name = p.key();
+            let error = format!("<{}> requires the `{}` param", self.name, name);
+            quote! { (#name, #error) }
+        });
+        quote! { &[#(#required),*] }
+    }
+
     pub fn build_docs(&self) -> TokenStream {
         let name = format!(" <!-- @widget-name={} -->", self.name.to_string());
         let mut docs = quote! {
@@ -702,13 +1021,25 @@ impl<'a> WidgetAttributes<'a> {
         for param in self.params.iter() {
             let param_signature = format!(
                 " - `{}:` [`{}`]",
-                param.name.to_string(),
+                param.key(),
                 param.ty.to_token_stream().to_string().replace(" ", "")
             );
             docs = quote! {
                 #docs
                 #[doc = #param_signature]
             };
+            if param.required {
+                docs = quote! {
+                    #docs
+                    #[doc = " Required."]
+                }
+            } else if let Some(default) = &param.default {
+                let default = format!(" Defaults to `{}`.", default.to_token_stream());
+                docs = quote! {
+                    #docs
+                    #[doc = #default]
+                }
+            }
             for doc in param.docs.iter() {
                 docs = quote! {
                     #docs
@@ -762,6 +1093,76 @@ impl<'a> WidgetAttributes<'a> {
             #docs
             #[doc = " <!-- @widget-signals-end -->"]
         };
+
+        if !self.parts.is_empty() {
+            docs = quote! {
+                #docs
+                #[doc = " "]
+                #[doc = " Parts:"]
+            }
+        }
+        docs = quote! {
+            #docs
+            #[doc = " <!-- @widget-parts-begin -->"]
+        };
+        for part in self.parts.iter() {
+            let part_signature = format!(" - `::{}`", part.key());
+            docs = quote! {
+                #docs
+                #[doc = #part_signature]
+            };
+            for doc in part.docs.iter() {
+                docs = quote! {
+                    #docs
+                    #[doc = #doc]
+                }
+            }
+            docs = quote! {
+                #docs
+                #[doc = " "]
+            }
+        }
+        docs = quote! {
+            #docs
+            #[doc = " <!-- @widget-parts-end -->"]
+        };
+
+        if !self.style_props.is_empty() {
+            docs = quote! {
+                #docs
+                #[doc = " "]
+                #[doc = " Style properties:"]
+            }
+        }
+        docs = quote! {
+            #docs
+            #[doc = " <!-- @widget-style-props-begin -->"]
+        };
+        for prop in self.style_props.iter() {
+            let prop_signature = format!(
+                " - `{}:` [`{}`]",
+                prop.name,
+                prop.ty.to_token_stream().to_string().replace(" ", "")
+            );
+            docs = quote! {
+                #docs
+                #[doc = #prop_signature]
+            };
+            for doc in prop.docs.iter() {
+                docs = quote! {
+                    #docs
+                    #[doc = #doc]
+                }
+            }
+            docs = quote! {
+                #docs
+                #[doc = " "]
+            }
+        }
+        docs = quote! {
+            #docs
+            #[doc = " <!-- @widget-style-props-end -->"]
+        };
         docs
     }
 }
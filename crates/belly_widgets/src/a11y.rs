@@ -0,0 +1,242 @@
+use crate::input::button::{Btn, BtnMode};
+use crate::input::text::TextInput;
+use crate::range::{Range, RangeValue};
+use belly_core::build::*;
+use belly_core::input::{Focused, PointerInputData, RequestFocus};
+use bevy::a11y::accesskit::{Action, ActionData, Checked, NodeBuilder, Role};
+use bevy::a11y::{AccessibilityNode, ActionRequest, Focus};
+use bevy::prelude::*;
+
+pub(crate) struct A11yPlugin;
+impl Plugin for A11yPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ActionRequest>();
+        app.register_attribute_param("aria-label");
+        app.add_systems(
+            PreUpdate,
+            handle_action_requests_system.before(belly_core::input::InputSystemsSet),
+        );
+        app.add_systems(
+            PostUpdate,
+            (
+                update_button_nodes_system,
+                update_range_nodes_system,
+                update_text_input_nodes_system,
+                sync_focus_system,
+            ),
+        );
+    }
+}
+
+/// The name of the element for the assistive tech: the `aria-label` param
+/// or the text of the element content
+fn accessible_name(
+    entity: Entity,
+    element: &Element,
+    children: &Query<&Children>,
+    texts: &Query<&Text>,
+) -> Option<String> {
+    if let Some(label) = element.attributes.get(&Tag::new("aria-label")) {
+        return Some(label.clone());
+    }
+    let mut words = vec![];
+    let mut stack = vec![entity];
+    while let Some(entity) = stack.pop() {
+        if let Ok(text) = texts.get(entity) {
+            words.extend(text.sections.iter().map(|s| s.value.trim().to_string()));
+        }
+        if let Ok(children) = children.get(entity) {
+            stack.extend(children.iter().rev());
+        }
+    }
+    let name = words.into_iter().filter(|w| !w.is_empty()).collect::<Vec<_>>().join(" ");
+    (!name.is_empty()).then_some(name)
+}
+
+fn node(role: Role, element: &Element, name: Option<String>) -> NodeBuilder {
+    let mut node = NodeBuilder::new(role);
+    if let Some(name) = name {
+        node.set_name(name);
+    }
+    if element.disabled() {
+        node.set_disabled();
+    } else {
+        node.add_action(Action::Focus);
+    }
+    node
+}
+
+fn update_button_nodes_system(
+    mut commands: Commands,
+    buttons: Query<(Entity, &Btn, &Element), Or<(Changed<Btn>, Changed<Element>)>>,
+    children: Query<&Children>,
+    texts: Query<&Text>,
+) {
+    for (entity, btn, element) in buttons.iter() {
+        let name = accessible_name(entity, element, &children, &texts);
+        let role = match btn.mode {
+            BtnMode::Toggle => Role::CheckBox,
+            BtnMode::Group(_) => Role::RadioButton,
+            _ => Role::Button,
+        };
+        let mut node = node(role, element, name);
+        if role != Role::Button {
+            node.set_checked(match btn.pressed {
+                true => Checked::True,
+                false => Checked::False,
+            });
+        }
+        if !element.disabled() {
+            node.add_action(Action::Default);
+        }
+        commands.entity(entity).insert(AccessibilityNode(node));
+    }
+}
+
+fn update_range_nodes_system(
+    mut commands: Commands,
+    ranges: Query<(Entity, &Range, &Element), Or<(Changed<Range>, Changed<Element>)>>,
+) {
+    for (entity, range, element) in ranges.iter() {
+        let name = element
+            .attributes
+            .get(&Tag::new("aria-label"))
+            .cloned();
+        let slider = element.names.contains(&Tag::new("slider"));
+        let role = match slider {
+            true => Role::Slider,
+            false => Role::ProgressIndicator,
+        };
+        let mut node = node(role, element, name);
+        let value = &range.value;
+        node.set_numeric_value(value.absolute() as f64);
+        node.set_min_numeric_value(value.minimum() as f64);
+        node.set_max_numeric_value(value.maximum() as f64);
+        if value.step() > 0. {
+            node.set_numeric_value_step(value.step() as f64);
+        }
+        if slider && !element.disabled() {
+            node.add_action(Action::Increment);
+            node.add_action(Action::Decrement);
+            node.add_action(Action::SetValue);
+        }
+        commands.entity(entity).insert(AccessibilityNode(node));
+    }
+}
+
+fn update_text_input_nodes_system(
+    mut commands: Commands,
+    inputs: Query<(Entity, &TextInput, &Element), Or<(Changed<TextInput>, Changed<Element>)>>,
+) {
+    for (entity, input, element) in inputs.iter() {
+        let name = element
+            .attributes
+            .get(&Tag::new("aria-label"))
+            .cloned();
+        let mut node = node(Role::TextInput, element, name);
+        node.set_value(input.value.clone());
+        if !element.disabled() {
+            node.add_action(Action::SetValue);
+        }
+        commands.entity(entity).insert(AccessibilityNode(node));
+    }
+}
+
+/// Reports the element focused with the pointer or the keyboard
+fn sync_focus_system(focused: Res<Focused>, focus: Option<ResMut<Focus>>) {
+    let Some(mut focus) = focus else {
+        return;
+    };
+    if focused.is_changed() && focus.0 != focused.get() {
+        focus.0 = focused.get();
+    }
+}
+
+/// The value of the range after the increment (`direction` is `1.`) or
+/// the decrement (`-1.`): the range moves by its step or by the tenth of
+/// the range when the step is not set
+pub fn step_range(value: &RangeValue, direction: f32) -> f32 {
+    let step = match value.step() > 0. {
+        true => value.step(),
+        false => (value.maximum() - value.minimum()) * 0.1,
+    };
+    let next = value.absolute() + step * direction;
+    next.clamp(value.minimum(), value.maximum())
+}
+
+/// Turns the actions of the assistive tech into the regular input: the
+/// `Default` action clicks the element, so the buttons, menus, chips, etc.
+/// react the same way they react to the pointer.
+fn handle_action_requests_system(
+    mut requests: EventReader<ActionRequest>,
+    mut pointer: EventWriter<PointerInput>,
+    mut focus: EventWriter<RequestFocus>,
+    mut ranges: Query<&mut Range>,
+    mut inputs: Query<&mut TextInput>,
+    elements: Query<&Element>,
+) {
+    for request in requests.read() {
+        let Ok(entity) = Entity::try_from_bits(request.target.0) else {
+            continue;
+        };
+        if elements.get(entity).map_or(true, |e| e.disabled()) {
+            continue;
+        }
+        match (request.action, &request.data) {
+            (Action::Focus, _) => {
+                focus.send(RequestFocus(entity));
+            }
+            (Action::Default, _) => {
+                let click = [
+                    PointerInputData::Down { presses: 1 },
+                    PointerInputData::Up { presses: 1 },
+                    PointerInputData::Pressed { presses: 1 },
+                ];
+                pointer.send_batch(click.into_iter().map(|data| PointerInput {
+                    entities: vec![entity],
+                    pos: Vec2::ZERO,
+                    delta: Vec2::ZERO,
+                    data,
+                }));
+            }
+            (Action::Increment | Action::Decrement, _) => {
+                if let Ok(mut range) = ranges.get_mut(entity) {
+                    let direction = match request.action {
+                        Action::Increment => 1.,
+                        _ => -1.,
+                    };
+                    let value = step_range(&range.value, direction);
+                    range.value.set_absolute(value);
+                }
+            }
+            (Action::SetValue, Some(ActionData::NumericValue(value))) => {
+                if let Ok(mut range) = ranges.get_mut(entity) {
+                    range.value.set_absolute(*value as f32);
+                }
+            }
+            (Action::SetValue, Some(ActionData::Value(value))) => {
+                if let Ok(mut input) = inputs.get_mut(entity) {
+                    input.value = value.to_string();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn step_range_by_step_or_tenth() {
+        let mut value = RangeValue::default();
+        value.set_maximum(10.);
+        value.set_absolute(5.);
+        assert_eq!(step_range(&value, 1.), 6.);
+        value.set_step(2.);
+        assert_eq!(step_range(&value, -1.), 4.);
+        value.set_absolute(10.);
+        assert_eq!(step_range(&value, 1.), 10.);
+    }
+}
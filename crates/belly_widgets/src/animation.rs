@@ -0,0 +1,341 @@
+use crate::img::{AssetSource, ImageSource};
+use belly_core::build::*;
+use belly_macro::*;
+use bevy::asset::LoadedFolder;
+use bevy::prelude::*;
+use std::str::FromStr;
+
+pub mod prelude {
+    pub use super::Animation;
+    pub use super::AnimationEvent;
+    pub use super::AnimationFrames;
+    pub use super::AnimationWidgetExtension;
+}
+
+pub(crate) struct AnimationPlugin;
+impl Plugin for AnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_widget::<AnimationWidget>();
+        app.register_widget_states::<Animation>();
+        app.add_event::<AnimationEvent>();
+        app.add_systems(
+            Update,
+            (load_animation, resolve_animation_frames, play_animation).chain(),
+        );
+    }
+}
+
+#[widget]
+#[signal(finished:AnimationEvent => animation_finished)]
+/// Specifies the sprite sheet image, it is split to
+/// `columns` x `rows` frames
+#[param( src: ImageSource => Animation:src )]
+/// Specifies the number of frames in the sprite sheet row
+#[param( columns: u8 => Animation:columns )]
+/// Specifies the number of rows in the sprite sheet
+#[param( rows: u8 => Animation:rows )]
+/// Specifies the list of image paths used as frames, separated by spaces
+#[param( frames: AnimationFrames => Animation:frames )]
+/// Specifies the folder with the frame images, frames are sorted by name
+#[param( folder: String => Animation:folder )]
+/// Specifies the number of frames per second
+#[param( fps: f32 => Animation:fps )]
+/// Plays the animation if `true`, pauses it otherwise
+#[param( playing: bool => Animation:playing )]
+/// Starts the animation over when it reaches the last frame
+#[param( looping: bool => Animation:looping )]
+/// The `<animation>` plays the sequence of frames at the `fps` rate. The
+/// frames are either the cells of the sprite sheet, the list of images or
+/// the images from the folder. The element has the `:playing` state while
+/// the animation plays. The `finished` signal is emitted when the
+/// not-looping animation reaches the last frame.
+fn animation(ctx: &mut WidgetContext, anim: &mut Animation) {
+    let content = ctx.content();
+    ctx.commands().entity(anim.entity).insert(ImageBundle {
+        style: Style {
+            width: Val::Percent(100.),
+            height: Val::Percent(100.),
+            ..default()
+        },
+        ..default()
+    });
+    ctx.insert(ElementBundle::default())
+        .push_children(&[anim.entity]);
+    ctx.commands().entity(anim.entity).push_children(&content);
+}
+
+#[derive(Event)]
+pub enum AnimationEvent {
+    Finished(Entity),
+}
+
+fn animation_finished(event: &AnimationEvent) -> EventSource {
+    match event {
+        AnimationEvent::Finished(entity) => EventSource::single(*entity),
+    }
+}
+
+/// The list of image paths: `frames="run/1.png run/2.png run/3.png"`
+#[derive(Default, Clone, PartialEq, Debug)]
+pub struct AnimationFrames(pub Vec<String>);
+
+impl FromStr for AnimationFrames {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(AnimationFrames(
+            s.split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|p| !p.is_empty())
+                .map(|p| p.to_string())
+                .collect(),
+        ))
+    }
+}
+
+impl From<Vec<String>> for AnimationFrames {
+    fn from(frames: Vec<String>) -> Self {
+        AnimationFrames(frames)
+    }
+}
+
+impl TryFrom<Variant> for AnimationFrames {
+    type Error = String;
+    fn try_from(value: Variant) -> Result<Self, Self::Error> {
+        match value {
+            Variant::Boxed(b) if b.is::<Vec<String>>() => Ok(AnimationFrames(
+                *b.downcast::<Vec<String>>().map_err(|_| "Can't cast to Vec<String>")?,
+            )),
+            value => value.get_or_parse(),
+        }
+    }
+}
+
+impl From<AnimationFrames> for Variant {
+    fn from(frames: AnimationFrames) -> Self {
+        Variant::boxed(frames)
+    }
+}
+
+#[derive(Component)]
+pub struct Animation {
+    pub src: ImageSource,
+    pub columns: u8,
+    pub rows: u8,
+    pub frames: AnimationFrames,
+    pub folder: String,
+    pub fps: f32,
+    pub playing: bool,
+    pub looping: bool,
+    pub frame: usize,
+    elapsed: f32,
+    entity: Entity,
+    source: String,
+    images: Vec<Handle<Image>>,
+    sheet: Handle<Image>,
+    layout: Handle<TextureAtlasLayout>,
+    loading_folder: Handle<LoadedFolder>,
+}
+
+impl Animation {
+    /// The number of the resolved frames.
+    pub fn len(&self) -> usize {
+        if self.sheet != Handle::default() {
+            self.columns.max(1) as usize * self.rows.max(1) as usize
+        } else {
+            self.images.len()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Starts the animation from the first frame.
+    pub fn restart(&mut self) {
+        self.frame = 0;
+        self.elapsed = 0.;
+        self.playing = true;
+    }
+
+    fn source_key(&self) -> String {
+        let src = match &self.src {
+            AssetSource::Path(p) => p.clone(),
+            AssetSource::Handle(h) => format!("{:?}", h.id()),
+        };
+        format!(
+            "{src}|{}x{}|{}|{}",
+            self.columns,
+            self.rows,
+            self.frames.0.join(","),
+            self.folder
+        )
+    }
+}
+
+impl WidgetStates for Animation {
+    fn states(&self) -> Vec<(Tag, bool)> {
+        vec![(Tag::new("playing"), self.playing)]
+    }
+}
+
+impl FromWorldAndParams for Animation {
+    fn from_world_and_params(world: &mut World, params: &mut belly_core::eml::Params) -> Self {
+        Animation {
+            src: params.try_get("src").unwrap_or_default(),
+            columns: params.try_get("columns").unwrap_or(1),
+            rows: params.try_get("rows").unwrap_or(1),
+            frames: params.try_get("frames").unwrap_or_default(),
+            folder: params.try_get("folder").unwrap_or_default(),
+            fps: params.try_get("fps").unwrap_or(12.),
+            playing: params.try_get("playing").unwrap_or(true),
+            looping: params.try_get("looping").unwrap_or(true),
+            frame: 0,
+            elapsed: 0.,
+            entity: world.spawn_empty().id(),
+            source: String::new(),
+            images: vec![],
+            sheet: Handle::default(),
+            layout: Handle::default(),
+            loading_folder: Handle::default(),
+        }
+    }
+}
+
+fn load_animation(
+    asset_server: Res<AssetServer>,
+    mut animations: Query<&mut Animation, Changed<Animation>>,
+    mut commands: Commands,
+) {
+    for mut anim in animations.iter_mut() {
+        let key = anim.source_key();
+        if key == anim.source {
+            continue;
+        }
+        anim.source = key;
+        anim.images.clear();
+        anim.sheet = Handle::default();
+        anim.layout = Handle::default();
+        anim.loading_folder = Handle::default();
+        anim.frame = 0;
+        anim.elapsed = 0.;
+        commands.entity(anim.entity).remove::<TextureAtlas>();
+        let sheet = match &anim.src {
+            AssetSource::Path(p) if p.is_empty() => Handle::default(),
+            AssetSource::Path(p) => asset_server.load(p.clone()),
+            AssetSource::Handle(h) => h.clone(),
+        };
+        if sheet != Handle::default() {
+            anim.sheet = sheet;
+        } else if !anim.frames.0.is_empty() {
+            let images = anim
+                .frames
+                .0
+                .iter()
+                .map(|p| asset_server.load(p.clone()))
+                .collect();
+            anim.images = images;
+        } else if !anim.folder.is_empty() {
+            let folder = asset_server.load_folder(anim.folder.clone());
+            anim.loading_folder = folder;
+        }
+    }
+}
+
+fn resolve_animation_frames(
+    mut animations: Query<&mut Animation>,
+    folders: Res<Assets<LoadedFolder>>,
+    images: Res<Assets<Image>>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut commands: Commands,
+) {
+    for mut anim in animations.iter_mut() {
+        if anim.loading_folder != Handle::default() {
+            let Some(folder) = folders.get(&anim.loading_folder) else {
+                continue;
+            };
+            let mut frames: Vec<_> = folder
+                .handles
+                .iter()
+                .filter_map(|h| h.clone().try_typed::<Image>().ok())
+                .collect();
+            frames.sort_by_key(|h| h.path().map(|p| p.to_string()));
+            anim.images = frames;
+            anim.loading_folder = Handle::default();
+        }
+        if anim.sheet != Handle::default() && anim.layout == Handle::default() {
+            let Some(image) = images.get(&anim.sheet) else {
+                continue;
+            };
+            let (columns, rows) = (anim.columns.max(1) as usize, anim.rows.max(1) as usize);
+            let tile = image.size_f32() / Vec2::new(columns as f32, rows as f32);
+            anim.layout = layouts.add(TextureAtlasLayout::from_grid(
+                tile, columns, rows, None, None,
+            ));
+            commands.entity(anim.entity).insert(TextureAtlas {
+                layout: anim.layout.clone(),
+                index: anim.frame,
+            });
+        }
+    }
+}
+
+fn play_animation(
+    time: ElementsTime,
+    mut animations: Query<(Entity, &mut Animation)>,
+    mut images: Query<(&mut UiImage, Option<&mut TextureAtlas>)>,
+    mut events: EventWriter<AnimationEvent>,
+) {
+    for (entity, mut anim) in animations.iter_mut() {
+        let len = anim.len();
+        if len == 0 {
+            continue;
+        }
+        if anim.playing && anim.fps > 0. {
+            let elapsed = anim.elapsed + time.delta_seconds(entity);
+            let frame_time = 1. / anim.fps;
+            let steps = (elapsed / frame_time).floor() as usize;
+            anim.bypass_change_detection().elapsed = elapsed - steps as f32 * frame_time;
+            if steps > 0 {
+                let next = anim.frame + steps;
+                if next < len {
+                    anim.frame = next;
+                } else if anim.looping {
+                    anim.frame = next % len;
+                } else {
+                    anim.frame = len - 1;
+                    anim.playing = false;
+                    events.send(AnimationEvent::Finished(entity));
+                }
+            }
+        }
+        let frame = anim.frame.min(len - 1);
+        let Ok((mut image, atlas)) = images.get_mut(anim.entity) else {
+            continue;
+        };
+        let texture = if anim.sheet != Handle::default() {
+            &anim.sheet
+        } else {
+            &anim.images[frame]
+        };
+        if &image.texture != texture {
+            image.texture = texture.clone();
+        }
+        if let Some(mut atlas) = atlas {
+            if atlas.index != frame {
+                atlas.index = frame;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_animation_frames() {
+        let frames: AnimationFrames = "run/1.png, run/2.png\n run/3.png".parse().unwrap();
+        assert_eq!(frames.0, vec!["run/1.png", "run/2.png", "run/3.png"]);
+        let frames: AnimationFrames = Variant::from("idle.png").try_into().unwrap();
+        assert_eq!(frames.0, vec!["idle.png"]);
+    }
+}
@@ -0,0 +1,80 @@
+use crate::common::Label;
+use belly_core::build::*;
+use belly_macro::*;
+use bevy::prelude::*;
+
+pub mod prelude {
+    pub use super::Badge;
+    pub use super::BadgeWidgetExtension;
+}
+
+pub(crate) struct BadgePlugin;
+impl Plugin for BadgePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_widget::<BadgeWidget>();
+        app.register_widget_states::<Badge>();
+    }
+}
+
+#[widget]
+/// Specifies the text of the counter, the empty value hides it
+#[param(value: String => Badge:value)]
+/// The counter placed over the top right corner of the content
+#[part(counter)]
+#[styles = BADGE_STYLES]
+/// The `<badge>` places the counter over the top right corner of its
+/// content: `<badge value="3"><button>"Inbox"</button></badge>`. Bind the
+/// `value` to show the live count: `bind:value=from!(inbox, Inbox:unread|fmt.c("{c}"))`.
+/// The element has the `:empty` state when there is nothing to show.
+fn badge(ctx: &mut WidgetContext, badge: &mut Badge) {
+    let this = ctx.entity();
+    let content = ctx.content();
+    let counter = badge.counter;
+    ctx.add(from!(this, Badge: value) >> to!(counter, Label: value));
+    ctx.render(eml! {
+        <span c:badge>
+            {content}
+            <label {counter} part="counter" c:badge-counter/>
+        </span>
+    })
+}
+
+ess_define! {
+    BADGE_STYLES,
+    .badge-counter {
+        position-type: absolute;
+        top: -6px;
+        right: -6px;
+        min-width: 16px;
+        padding: 0px 4px;
+        justify-content: center;
+        font-size: 12px;
+        color: #ffffff;
+        background-color: #d32f2f;
+    }
+    badge:empty > .badge-counter {
+        display: none;
+    }
+}
+
+#[derive(Component)]
+/// The state of the `<badge>`
+pub struct Badge {
+    pub value: String,
+    counter: Entity,
+}
+
+impl FromWorldAndParams for Badge {
+    fn from_world_and_params(world: &mut World, params: &mut belly_core::eml::Params) -> Self {
+        Badge {
+            value: params.try_get("value").unwrap_or_default(),
+            counter: world.spawn_empty().id(),
+        }
+    }
+}
+
+impl WidgetStates for Badge {
+    fn states(&self) -> Vec<(Tag, bool)> {
+        vec![(Tag::new("empty"), self.value.is_empty())]
+    }
+}
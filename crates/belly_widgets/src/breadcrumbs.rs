@@ -0,0 +1,210 @@
+use belly_core::build::*;
+use belly_core::eml::pool::RecycleElementCommand;
+use belly_core::input;
+use belly_macro::*;
+use bevy::prelude::*;
+
+pub mod prelude {
+    pub use super::BreadcrumbEvent;
+    pub use super::Breadcrumbs;
+    pub use super::BreadcrumbsWidgetExtension;
+}
+
+pub(crate) struct BreadcrumbsPlugin;
+impl Plugin for BreadcrumbsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<BreadcrumbEvent>();
+        app.register_widget::<BreadcrumbsWidget>();
+        app.add_systems(
+            PreUpdate,
+            handle_breadcrumbs_input.in_set(input::InputSystemsSet),
+        );
+        app.add_systems(Update, render_breadcrumbs_system);
+    }
+}
+
+#[widget]
+#[signal(select: BreadcrumbEvent => breadcrumb_selected)]
+/// Specifies the segments of the path, from the root to the current one
+#[param(path: Vec<String> => Breadcrumbs:path)]
+/// Specifies the text placed between the segments, `/` by default
+#[param(separator: String => Breadcrumbs:separator)]
+/// The pressable segment of the path
+#[part(segment)]
+/// The separator between the segments
+#[part(divider)]
+#[styles = BREADCRUMBS_STYLES]
+/// The `<breadcrumbs>` shows the path as the row of the pressable segments:
+/// `<breadcrumbs bind:path=from!(browser, Browser:path)/>`. Pressing the
+/// segment emits the `select` signal with the index and the text of the
+/// segment, so the handler may truncate the path to the selected one:
+/// `on:select=run!(for browser |ctx, b: &mut Browser| b.open(ctx.event().index()))`.
+fn breadcrumbs(ctx: &mut WidgetContext) {
+    ctx.render(eml! {
+        <span c:breadcrumbs/>
+    })
+}
+
+ess_define! {
+    BREADCRUMBS_STYLES,
+    breadcrumbs {
+        align-items: center;
+    }
+    .breadcrumbs-segments {
+        align-items: center;
+    }
+    .breadcrumb {
+        padding: 2px 4px;
+    }
+    .breadcrumb:hover {
+        background-color: #ffffff20;
+    }
+    .breadcrumb-divider {
+        display: none;
+        padding: 2px;
+        color: #9e9e9e;
+    }
+    .breadcrumb + .breadcrumb-divider {
+        display: flex;
+    }
+}
+
+#[derive(Component)]
+/// The state of the `<breadcrumbs>`
+pub struct Breadcrumbs {
+    pub path: Vec<String>,
+    pub separator: String,
+    rendered: Option<(Vec<String>, String)>,
+    segments: Option<Entity>,
+}
+
+impl Default for Breadcrumbs {
+    fn default() -> Self {
+        Breadcrumbs {
+            path: vec![],
+            separator: "/".to_string(),
+            rendered: None,
+            segments: None,
+        }
+    }
+}
+
+#[derive(Component)]
+struct BreadcrumbSegment {
+    breadcrumbs: Entity,
+    index: usize,
+    text: String,
+}
+
+#[derive(Event, Debug)]
+pub enum BreadcrumbEvent {
+    Select {
+        breadcrumbs: Entity,
+        index: usize,
+        segment: String,
+    },
+}
+
+impl BreadcrumbEvent {
+    /// The index of the selected segment in the path
+    pub fn index(&self) -> usize {
+        match self {
+            BreadcrumbEvent::Select { index, .. } => *index,
+        }
+    }
+
+    /// The text of the selected segment
+    pub fn segment(&self) -> &str {
+        match self {
+            BreadcrumbEvent::Select { segment, .. } => segment,
+        }
+    }
+}
+
+fn breadcrumb_selected(event: &BreadcrumbEvent) -> EventSource {
+    match event {
+        BreadcrumbEvent::Select { breadcrumbs, .. } => EventSource::single(*breadcrumbs),
+    }
+}
+
+/// Re-renders the segments when the path or the separator changes
+fn render_breadcrumbs_system(
+    mut commands: Commands,
+    mut crumbs: Query<(Entity, &mut Breadcrumbs), Changed<Breadcrumbs>>,
+) {
+    for (entity, mut crumbs) in crumbs.iter_mut() {
+        let crumbs = crumbs.bypass_change_detection();
+        let state = (crumbs.path.clone(), crumbs.separator.clone());
+        if crumbs.rendered.as_ref() == Some(&state) {
+            continue;
+        }
+        crumbs.rendered = Some(state);
+        if let Some(segments) = crumbs.segments.take() {
+            commands.add(RecycleElementCommand(segments));
+        }
+        let separator = crumbs.separator.clone();
+        let items: Vec<_> = crumbs
+            .path
+            .iter()
+            .enumerate()
+            .map(|(index, text)| {
+                let segment = BreadcrumbSegment {
+                    breadcrumbs: entity,
+                    index,
+                    text: text.clone(),
+                };
+                (commands.spawn(segment).id(), text.clone())
+            })
+            .collect();
+        let segments = commands.spawn_empty().id();
+        crumbs.segments = Some(segments);
+        commands.add(
+            eml! {
+                <span c:breadcrumbs-segments>
+                    <for item in=items>
+                        <span c:breadcrumb-divider part="divider">{separator.clone()}</span>
+                        <span entity=item.0 c:breadcrumb part="segment" interactable>
+                            {item.1}
+                        </span>
+                    </for>
+                </span>
+            }
+            .render_to(segments),
+        );
+        commands.entity(entity).add_child(segments);
+    }
+}
+
+fn handle_breadcrumbs_input(
+    mut events: EventReader<PointerInput>,
+    segments: Query<&BreadcrumbSegment>,
+    mut signals: EventWriter<BreadcrumbEvent>,
+) {
+    for ev in events.read().filter(|ev| ev.pressed()) {
+        let Some(segment) = ev.entities.iter().find_map(|e| segments.get(*e).ok()) else {
+            continue;
+        };
+        signals.send(BreadcrumbEvent::Select {
+            breadcrumbs: segment.breadcrumbs,
+            index: segment.index,
+            segment: segment.text.clone(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn breadcrumb_event_source() {
+        let entity = Entity::from_raw(3);
+        let event = BreadcrumbEvent::Select {
+            breadcrumbs: entity,
+            index: 1,
+            segment: "maps".to_string(),
+        };
+        assert_eq!((event.index(), event.segment()), (1, "maps"));
+        assert_eq!(breadcrumb_selected(&event).collect::<Vec<_>>(), vec![entity]);
+    }
+}
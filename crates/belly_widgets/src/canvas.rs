@@ -0,0 +1,687 @@
+use belly_core::build::*;
+use belly_macro::*;
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+    ui::UiSystem,
+};
+use std::f32::consts::TAU;
+use std::str::FromStr;
+use std::sync::Arc;
+
+pub mod prelude {
+    pub use super::ArcCap;
+    pub use super::Canvas;
+    pub use super::CanvasDraw;
+    pub use super::CanvasPainter;
+    pub use super::CanvasWidgetExtension;
+}
+
+pub(crate) struct CanvasPlugin;
+impl Plugin for CanvasPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_widget::<CanvasWidget>();
+        app.add_systems(
+            PostUpdate,
+            (mark_canvas_dirty, draw_canvas_system)
+                .chain()
+                .after(UiSystem::Layout),
+        );
+    }
+}
+
+#[widget]
+/// The function called to draw the canvas content, see `CanvasDraw`
+#[param(draw: CanvasDraw => Canvas:draw)]
+/// Calls the `draw` function only when the canvas changes instead of every frame
+#[param(retained: bool => Canvas:retained)]
+/// Any value the retained canvas depends on, the canvas is redrawn when it changes
+#[param(watch: String => Canvas:watch)]
+/// The `<canvas>` is a drawing surface: the `draw` function paints the lines,
+/// polylines, rects, circles, images and text in the element-local coordinates.
+/// The content is clipped to the element. By default the `draw` function is
+/// called every frame. The `retained` canvas is redrawn only when it is resized,
+/// when its params change (bind the data it depends on to the `watch` param)
+/// or when `Canvas::redraw` is called.
+fn canvas(ctx: &mut WidgetContext, canvas: &mut Canvas) {
+    ctx.commands().entity(canvas.entity).insert(ImageBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.),
+            height: Val::Percent(100.),
+            ..default()
+        },
+        image: UiImage::new(canvas.image.clone()),
+        ..default()
+    });
+    ctx.insert(ElementBundle::default())
+        .push_children(&[canvas.entity]);
+}
+
+type DrawFn = dyn Fn(&mut CanvasPainter, &World) + Send + Sync;
+
+/// The draw function of the `<canvas>`. It receives the painter sized to
+/// the element and read-only access to the world:
+/// ```rust,ignore
+/// commands.add(eml! {
+///     <canvas s:width="200px" s:height="200px" draw=CanvasDraw::new(|painter, world| {
+///         let angle = world.resource::<Time>().elapsed_seconds();
+///         let center = painter.size() * 0.5;
+///         painter.clear(Color::BLACK);
+///         painter.stroke_circle(center, 90., 2., Color::GREEN);
+///         painter.line(center, center + 90. * Vec2::from_angle(angle), 3., Color::GREEN);
+///     })/>
+/// });
+/// ```
+#[derive(Clone, Default)]
+pub struct CanvasDraw(Option<Arc<DrawFn>>);
+
+impl CanvasDraw {
+    pub fn new<F: Fn(&mut CanvasPainter, &World) + Send + Sync + 'static>(draw: F) -> Self {
+        CanvasDraw(Some(Arc::new(draw)))
+    }
+}
+
+impl PartialEq for CanvasDraw {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl From<CanvasDraw> for Variant {
+    fn from(draw: CanvasDraw) -> Self {
+        Variant::boxed(draw)
+    }
+}
+
+impl TryFrom<Variant> for CanvasDraw {
+    type Error = String;
+    fn try_from(value: Variant) -> Result<Self, Self::Error> {
+        value
+            .take::<CanvasDraw>()
+            .ok_or_else(|| "Invalid value for CanvasDraw".to_string())
+    }
+}
+
+#[derive(Component)]
+pub struct Canvas {
+    pub draw: CanvasDraw,
+    pub retained: bool,
+    pub watch: String,
+    image: Handle<Image>,
+    entity: Entity,
+    labels: Vec<Entity>,
+    dirty: bool,
+}
+
+impl Canvas {
+    /// The image the canvas is painted to.
+    pub fn image(&self) -> &Handle<Image> {
+        &self.image
+    }
+
+    /// Requests the retained canvas to be redrawn.
+    pub fn redraw(&mut self) {
+        self.dirty = true;
+    }
+}
+
+impl FromWorldAndParams for Canvas {
+    fn from_world_and_params(world: &mut World, params: &mut belly_core::eml::Params) -> Self {
+        let image = world
+            .resource_mut::<Assets<Image>>()
+            .add(canvas_image(UVec2::ONE));
+        Canvas {
+            draw: params.try_get("draw").unwrap_or_default(),
+            retained: params.try_get("retained").unwrap_or_default(),
+            watch: params.try_get("watch").unwrap_or_default(),
+            image,
+            entity: world.spawn_empty().id(),
+            labels: vec![],
+            dirty: true,
+        }
+    }
+}
+
+fn canvas_image(size: UVec2) -> Image {
+    Image::new_fill(
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    )
+}
+
+enum DrawCommand {
+    Clear(Color),
+    Line {
+        from: Vec2,
+        to: Vec2,
+        width: f32,
+        color: Color,
+    },
+    Rect {
+        rect: Rect,
+        color: Color,
+    },
+    Circle {
+        center: Vec2,
+        radius: f32,
+        stroke: Option<f32>,
+        color: Color,
+    },
+    Image {
+        image: Handle<Image>,
+        rect: Rect,
+        tint: Color,
+    },
+    Arc {
+        center: Vec2,
+        radius: f32,
+        from: f32,
+        sweep: f32,
+        width: f32,
+        cap: ArcCap,
+        color: Color,
+    },
+}
+
+/// The shape of the arc ends:
+/// - `butt`: the arc ends exactly at its angles
+/// - `round`: the arc ends are rounded
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub enum ArcCap {
+    #[default]
+    Butt,
+    Round,
+}
+
+impl FromStr for ArcCap {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "butt" => Ok(ArcCap::Butt),
+            "round" => Ok(ArcCap::Round),
+            err => Err(format!("Can't parse `{}` as ArcCap", err)),
+        }
+    }
+}
+
+impl TryFrom<Variant> for ArcCap {
+    type Error = String;
+    fn try_from(value: Variant) -> Result<Self, Self::Error> {
+        value.get_or_parse()
+    }
+}
+
+impl From<ArcCap> for Variant {
+    fn from(cap: ArcCap) -> Self {
+        Variant::boxed(cap)
+    }
+}
+
+struct CanvasLabel {
+    position: Vec2,
+    text: String,
+    font_size: f32,
+    color: Color,
+}
+
+/// Records the drawing commands of the canvas. Coordinates are in the
+/// element-local pixels: `(0, 0)` is the top-left corner of the element,
+/// `size()` is the bottom-right one.
+pub struct CanvasPainter {
+    size: Vec2,
+    commands: Vec<DrawCommand>,
+    labels: Vec<CanvasLabel>,
+}
+
+impl CanvasPainter {
+    fn new(size: Vec2) -> Self {
+        CanvasPainter {
+            size,
+            commands: vec![],
+            labels: vec![],
+        }
+    }
+
+    /// The size of the element.
+    pub fn size(&self) -> Vec2 {
+        self.size
+    }
+
+    /// Fills the whole canvas with the `color`.
+    pub fn clear(&mut self, color: Color) {
+        self.commands.push(DrawCommand::Clear(color));
+    }
+
+    pub fn line(&mut self, from: Vec2, to: Vec2, width: f32, color: Color) {
+        self.commands.push(DrawCommand::Line {
+            from,
+            to,
+            width,
+            color,
+        });
+    }
+
+    /// Connects the `points` with the lines.
+    pub fn polyline(&mut self, points: &[Vec2], width: f32, color: Color) {
+        for segment in points.windows(2) {
+            self.line(segment[0], segment[1], width, color);
+        }
+    }
+
+    pub fn rect(&mut self, rect: Rect, color: Color) {
+        self.commands.push(DrawCommand::Rect { rect, color });
+    }
+
+    pub fn stroke_rect(&mut self, rect: Rect, width: f32, color: Color) {
+        let (min, max) = (rect.min, rect.max);
+        let w = width.min(rect.width() * 0.5).min(rect.height() * 0.5);
+        self.rect(Rect::new(min.x, min.y, max.x, min.y + w), color);
+        self.rect(Rect::new(min.x, max.y - w, max.x, max.y), color);
+        self.rect(Rect::new(min.x, min.y + w, min.x + w, max.y - w), color);
+        self.rect(Rect::new(max.x - w, min.y + w, max.x, max.y - w), color);
+    }
+
+    pub fn circle(&mut self, center: Vec2, radius: f32, color: Color) {
+        self.commands.push(DrawCommand::Circle {
+            center,
+            radius,
+            stroke: None,
+            color,
+        });
+    }
+
+    pub fn stroke_circle(&mut self, center: Vec2, radius: f32, width: f32, color: Color) {
+        self.commands.push(DrawCommand::Circle {
+            center,
+            radius,
+            stroke: Some(width),
+            color,
+        });
+    }
+
+    /// Draws the `image` stretched to the `rect` and multiplied by the `tint`.
+    /// Only `rgba8` images are supported.
+    pub fn image(&mut self, image: Handle<Image>, rect: Rect, tint: Color) {
+        self.commands.push(DrawCommand::Image { image, rect, tint });
+    }
+
+    /// Strokes the arc of the circle. The angles are in radians and measured
+    /// clockwise from the top of the circle, the negative `sweep` draws the
+    /// arc counterclockwise.
+    #[allow(clippy::too_many_arguments)]
+    pub fn arc(
+        &mut self,
+        center: Vec2,
+        radius: f32,
+        from: f32,
+        sweep: f32,
+        width: f32,
+        cap: ArcCap,
+        color: Color,
+    ) {
+        self.commands.push(DrawCommand::Arc {
+            center,
+            radius,
+            from,
+            sweep,
+            width,
+            cap,
+            color,
+        });
+    }
+
+    /// Places the `text` with the top-left corner at the `position`. The text
+    /// is rendered by the text pipeline above the painted content.
+    pub fn text(&mut self, position: Vec2, text: impl Into<String>, font_size: f32, color: Color) {
+        self.labels.push(CanvasLabel {
+            position,
+            text: text.into(),
+            font_size,
+            color,
+        });
+    }
+
+    fn rasterize(&self, size: UVec2, images: &Assets<Image>) -> Vec<u8> {
+        let mut pixels = Pixels {
+            size,
+            data: vec![0; (size.x * size.y * 4) as usize],
+        };
+        for command in self.commands.iter() {
+            match command {
+                DrawCommand::Clear(color) => {
+                    let color = color.as_rgba_u8();
+                    for pixel in pixels.data.chunks_exact_mut(4) {
+                        pixel.copy_from_slice(&color);
+                    }
+                }
+                DrawCommand::Line {
+                    from,
+                    to,
+                    width,
+                    color,
+                } => {
+                    let half = 0.5 * width.max(0.);
+                    let bounds = Rect::from_corners(*from, *to).inset(half + 1.);
+                    pixels.fill(bounds, *color, |p| {
+                        half + 0.5 - distance_to_segment(p, *from, *to)
+                    });
+                }
+                DrawCommand::Rect { rect, color } => {
+                    pixels.fill(*rect, *color, |p| {
+                        let x = ((p.x + 0.5).min(rect.max.x) - (p.x - 0.5).max(rect.min.x))
+                            .clamp(0., 1.);
+                        let y = ((p.y + 0.5).min(rect.max.y) - (p.y - 0.5).max(rect.min.y))
+                            .clamp(0., 1.);
+                        x * y
+                    });
+                }
+                DrawCommand::Circle {
+                    center,
+                    radius,
+                    stroke,
+                    color,
+                } => {
+                    let half = 0.5 * stroke.unwrap_or(0.);
+                    let bounds = Rect::from_center_half_size(*center, Vec2::splat(radius + half))
+                        .inset(1.);
+                    pixels.fill(bounds, *color, |p| {
+                        let distance = p.distance(*center);
+                        match stroke {
+                            None => radius + 0.5 - distance,
+                            Some(_) => half + 0.5 - (distance - radius).abs(),
+                        }
+                    });
+                }
+                DrawCommand::Image { image, rect, tint } => {
+                    let Some(image) = images.get(image) else {
+                        continue;
+                    };
+                    pixels.blit(image, *rect, *tint);
+                }
+                DrawCommand::Arc {
+                    center,
+                    radius,
+                    from,
+                    sweep,
+                    width,
+                    cap,
+                    color,
+                } => {
+                    let half = 0.5 * width.max(0.);
+                    let bounds = Rect::from_center_half_size(*center, Vec2::splat(radius + half))
+                        .inset(1.);
+                    let point = |angle: f32| *center + *radius * Vec2::new(angle.sin(), -angle.cos());
+                    let ends = [point(*from), point(*from + *sweep)];
+                    pixels.fill(bounds, *color, |p| {
+                        // the angle of the pixel, clockwise from the top
+                        let offset = p - *center;
+                        let angle = offset.x.atan2(-offset.y);
+                        let relative = (angle - from) * sweep.signum();
+                        if relative.rem_euclid(TAU) <= sweep.abs() || sweep.abs() >= TAU {
+                            half + 0.5 - (offset.length() - radius).abs()
+                        } else if *cap == ArcCap::Round {
+                            half + 0.5 - p.distance(ends[0]).min(p.distance(ends[1]))
+                        } else {
+                            0.
+                        }
+                    });
+                }
+            }
+        }
+        pixels.data
+    }
+}
+
+fn distance_to_segment(p: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let ab = b - a;
+    let len = ab.length_squared();
+    if len < f32::EPSILON {
+        return p.distance(a);
+    }
+    let t = ((p - a).dot(ab) / len).clamp(0., 1.);
+    p.distance(a + ab * t)
+}
+
+struct Pixels {
+    size: UVec2,
+    data: Vec<u8>,
+}
+
+impl Pixels {
+    /// Iterates the pixels within the `bounds` clipped to the canvas.
+    fn pixels(&self, bounds: Rect) -> impl Iterator<Item = (u32, u32)> {
+        let min = bounds.min.floor().max(Vec2::ZERO).as_uvec2();
+        let max = bounds.max.ceil().as_uvec2().min(self.size);
+        (min.y..max.y).flat_map(move |y| (min.x..max.x).map(move |x| (x, y)))
+    }
+
+    /// Blends the `color` into the pixels within the `bounds`, the coverage
+    /// of each pixel is calculated by `coverage` from the pixel center.
+    fn fill<F: Fn(Vec2) -> f32>(&mut self, bounds: Rect, color: Color, coverage: F) {
+        for (x, y) in self.pixels(bounds) {
+            let center = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+            let alpha = coverage(center).clamp(0., 1.);
+            if alpha > 0. {
+                self.blend(x, y, color.as_rgba_u8(), alpha);
+            }
+        }
+    }
+
+    fn blit(&mut self, image: &Image, rect: Rect, tint: Color) {
+        if !matches!(
+            image.texture_descriptor.format,
+            TextureFormat::Rgba8UnormSrgb | TextureFormat::Rgba8Unorm
+        ) {
+            warn!(
+                "Unsupported canvas image format {:?}",
+                image.texture_descriptor.format
+            );
+            return;
+        }
+        if rect.is_empty() {
+            return;
+        }
+        let source = image.size();
+        let tint = tint.as_rgba_f32();
+        for (x, y) in self.pixels(rect) {
+            let uv = (Vec2::new(x as f32 + 0.5, y as f32 + 0.5) - rect.min) / rect.size();
+            if uv.x < 0. || uv.y < 0. || uv.x >= 1. || uv.y >= 1. {
+                continue;
+            }
+            let texel = (uv * source.as_vec2()).as_uvec2().min(source - UVec2::ONE);
+            let offset = ((texel.y * source.x + texel.x) * 4) as usize;
+            let Some(texel) = image.data.get(offset..offset + 4) else {
+                continue;
+            };
+            let color = [
+                (texel[0] as f32 * tint[0]) as u8,
+                (texel[1] as f32 * tint[1]) as u8,
+                (texel[2] as f32 * tint[2]) as u8,
+                (texel[3] as f32 * tint[3]) as u8,
+            ];
+            self.blend(x, y, color, 1.);
+        }
+    }
+
+    fn blend(&mut self, x: u32, y: u32, color: [u8; 4], coverage: f32) {
+        let offset = ((y * self.size.x + x) * 4) as usize;
+        let dst = &mut self.data[offset..offset + 4];
+        let src_a = color[3] as f32 / 255. * coverage;
+        let dst_a = dst[3] as f32 / 255.;
+        let out_a = src_a + dst_a * (1. - src_a);
+        if out_a <= 0. {
+            return;
+        }
+        for i in 0..3 {
+            let src = color[i] as f32 * src_a;
+            let dst_c = dst[i] as f32 * dst_a * (1. - src_a);
+            dst[i] = ((src + dst_c) / out_a).round() as u8;
+        }
+        dst[3] = (out_a * 255.).round() as u8;
+    }
+}
+
+fn mark_canvas_dirty(mut canvases: Query<&mut Canvas, Or<(Changed<Canvas>, Changed<Node>)>>) {
+    for mut canvas in canvases.iter_mut() {
+        canvas.bypass_change_detection().dirty = true;
+    }
+}
+
+fn draw_canvas_system(world: &mut World) {
+    let mut canvases = world.query::<(Entity, &mut Canvas, &Node)>();
+    let jobs: Vec<_> = canvases
+        .iter_mut(world)
+        .filter(|(_, canvas, _)| canvas.draw.0.is_some())
+        .filter(|(_, canvas, _)| !canvas.retained || canvas.dirty)
+        .map(|(entity, mut canvas, node)| {
+            canvas.bypass_change_detection().dirty = false;
+            (
+                entity,
+                canvas.draw.clone(),
+                canvas.image.clone(),
+                node.size().round().as_uvec2(),
+            )
+        })
+        .collect();
+    for (entity, draw, handle, size) in jobs {
+        if size.x == 0 || size.y == 0 {
+            continue;
+        }
+        let Some(draw) = draw.0 else {
+            continue;
+        };
+        let mut painter = CanvasPainter::new(size.as_vec2());
+        draw(&mut painter, world);
+        let data = painter.rasterize(size, world.resource::<Assets<Image>>());
+        let mut images = world.resource_mut::<Assets<Image>>();
+        let Some(image) = images.get_mut(handle) else {
+            continue;
+        };
+        if image.size() != size {
+            *image = canvas_image(size);
+        }
+        image.data = data;
+        sync_canvas_labels(world, entity, painter.labels);
+    }
+}
+
+/// Updates the text entities of the canvas to show the painted `labels`.
+fn sync_canvas_labels(world: &mut World, entity: Entity, labels: Vec<CanvasLabel>) {
+    let Some(canvas) = world.get::<Canvas>(entity) else {
+        return;
+    };
+    let (parent, mut entities) = (canvas.entity, canvas.labels.clone());
+    while entities.len() > labels.len() {
+        let label = entities.pop().unwrap();
+        if let Some(label) = world.get_entity_mut(label) {
+            label.despawn_recursive();
+        }
+    }
+    while entities.len() < labels.len() {
+        let label = world
+            .spawn(TextBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                ..default()
+            })
+            .set_parent(parent)
+            .id();
+        entities.push(label);
+    }
+    for (label, entity) in labels.into_iter().zip(entities.iter()) {
+        let text = Text::from_section(
+            label.text,
+            TextStyle {
+                font_size: label.font_size,
+                color: label.color,
+                ..default()
+            },
+        );
+        let left = Val::Px(label.position.x);
+        let top = Val::Px(label.position.y);
+        let mut entity = world.entity_mut(*entity);
+        if let Some(mut style) = entity.get_mut::<Style>() {
+            if style.left != left || style.top != top {
+                style.left = left;
+                style.top = top;
+            }
+        }
+        if let Some(mut current) = entity.get_mut::<Text>() {
+            if current.sections[0].value != text.sections[0].value
+                || current.sections[0].style.font_size != label.font_size
+                || current.sections[0].style.color != label.color
+            {
+                *current = text;
+            }
+        }
+    }
+    if let Some(mut canvas) = world.get_mut::<Canvas>(entity) {
+        canvas.bypass_change_detection().labels = entities;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::f32::consts::FRAC_PI_2;
+
+    #[test]
+    fn rasterize_clipped_shapes() {
+        let mut painter = CanvasPainter::new(Vec2::new(4., 4.));
+        painter.clear(Color::BLACK);
+        painter.rect(Rect::new(2., 2., 10., 10.), Color::WHITE);
+        painter.line(Vec2::new(0., 0.5), Vec2::new(4., 0.5), 1., Color::RED);
+        let data = painter.rasterize(UVec2::new(4, 4), &Assets::default());
+        let pixel = |x: usize, y: usize| &data[(y * 4 + x) * 4..(y * 4 + x) * 4 + 4];
+        assert_eq!(pixel(1, 1), &[0, 0, 0, 255]);
+        assert_eq!(pixel(3, 3), &[255, 255, 255, 255]);
+        assert_eq!(pixel(2, 0), &[255, 0, 0, 255]);
+        assert_eq!(data.len(), 4 * 4 * 4);
+    }
+
+    #[test]
+    fn polyline_and_labels() {
+        let mut painter = CanvasPainter::new(Vec2::new(4., 4.));
+        painter.polyline(
+            &[Vec2::new(0., 0.5), Vec2::new(4., 0.5), Vec2::new(4., 4.)],
+            1.,
+            Color::RED,
+        );
+        painter.text(Vec2::new(1., 2.), "42", 12., Color::WHITE);
+        assert_eq!(painter.commands.len(), 2);
+        assert_eq!(painter.labels.len(), 1);
+        assert_eq!(painter.labels[0].text, "42");
+        let data = painter.rasterize(UVec2::new(4, 4), &Assets::default());
+        assert_eq!(&data[8..12], &[255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn rasterize_arc() {
+        let mut painter = CanvasPainter::new(Vec2::new(10., 10.));
+        let center = Vec2::splat(5.);
+        painter.arc(center, 4., 0., FRAC_PI_2, 2., ArcCap::Butt, Color::WHITE);
+        let data = painter.rasterize(UVec2::new(10, 10), &Assets::default());
+        let alpha = |x: usize, y: usize| data[(y * 10 + x) * 4 + 3];
+        // top-right quarter is painted, the rest of the ring is not
+        assert_eq!(alpha(7, 2), 255);
+        assert_eq!(alpha(2, 2), 0);
+        assert_eq!(alpha(5, 8), 0);
+    }
+}
@@ -0,0 +1,574 @@
+use crate::canvas::{Canvas, CanvasDraw, CanvasPainter};
+use belly_core::build::*;
+use belly_core::ess::impls::chart::ChartStyle;
+use belly_core::input;
+use belly_macro::*;
+use bevy::prelude::*;
+use std::str::FromStr;
+
+pub mod prelude {
+    pub use super::Chart;
+    pub use super::ChartEvent;
+    pub use super::ChartMode;
+    pub use super::ChartWidgetExtension;
+}
+
+pub(crate) struct ChartPlugin;
+impl Plugin for ChartPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ChartEvent>();
+        app.register_widget::<ChartWidget>();
+        app.add_systems(PreUpdate, hover_charts.in_set(input::InputSystemsSet));
+        app.add_systems(Update, redraw_charts);
+    }
+}
+
+#[widget]
+#[signal(hover: ChartEvent => chart_hovered)]
+#[styles(
+    chart {
+      min-width: 100px;
+      min-height: 50px;
+    }
+)]
+/// Specifies the values plotted at the evenly spaced positions
+#[param(values: Vec<f32> => Chart:values)]
+/// Specifies the `(x, y)` points to plot, used when there are no `values`
+#[param(points: Vec<(f32, f32)> => Chart:points)]
+/// Specifies the several data series, used instead of the `values`
+#[param(series: Vec<Vec<f32>> => Chart:series)]
+/// <!-- @inline ChartMode -->
+#[param(mode: ChartMode => Chart:mode)]
+/// The `<chart>` plots the bindable data series as lines, bars, stacked bars
+/// or the heatmap. The axes are scaled to fit the data automatically, the
+/// colors are specified by the `chart-palette` and `chart-axis` style
+/// properties. Moving the pointer over the data emits the `hover` signal
+/// with the series and the index of the point under the pointer.
+fn chart(ctx: &mut WidgetContext, chart: &mut Chart) {
+    let this = ctx.entity();
+    let canvas = chart.canvas;
+    let draw = CanvasDraw::new(move |painter, world| {
+        let (Some(chart), Some(style)) = (world.get::<Chart>(this), world.get::<ChartStyle>(this))
+        else {
+            return;
+        };
+        chart.paint(painter, style);
+    });
+    ctx.insert(ChartStyle::default());
+    ctx.render(eml! {
+        <span c:chart>
+            <canvas {canvas} c:chart-canvas draw=draw retained=true interactable
+                s:width="100%" s:height="100%"/>
+        </span>
+    });
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+/// Specifies how the chart shows the data:
+/// - `line`: connect the data points with the line
+/// - `bar`: show each data point as the bar, the series are grouped side by side
+/// - `stacked-bar`: stack the points of the series with the same index
+/// - `heatmap`: show the series as the rows of cells colored by the
+///   `chart-palette` gradient
+pub enum ChartMode {
+    #[default]
+    Line,
+    Bar,
+    StackedBar,
+    Heatmap,
+}
+
+impl FromStr for ChartMode {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "line" => Ok(ChartMode::Line),
+            "bar" => Ok(ChartMode::Bar),
+            "stacked-bar" => Ok(ChartMode::StackedBar),
+            "heatmap" => Ok(ChartMode::Heatmap),
+            err => Err(format!("Can't parse `{}` as ChartMode", err)),
+        }
+    }
+}
+
+impl TryFrom<Variant> for ChartMode {
+    type Error = String;
+    fn try_from(value: Variant) -> Result<Self, Self::Error> {
+        value.get_or_parse()
+    }
+}
+
+impl From<ChartMode> for Variant {
+    fn from(mode: ChartMode) -> Self {
+        Variant::boxed(mode)
+    }
+}
+
+#[derive(Event, Debug)]
+pub enum ChartEvent {
+    Hover {
+        chart: Entity,
+        series: usize,
+        index: usize,
+    },
+}
+
+impl ChartEvent {
+    /// The index of the hovered data series, always `0` for the `values`
+    /// and the `points`
+    pub fn series(&self) -> usize {
+        match self {
+            ChartEvent::Hover { series, .. } => *series,
+        }
+    }
+
+    /// The index of the hovered point in its series
+    pub fn index(&self) -> usize {
+        match self {
+            ChartEvent::Hover { index, .. } => *index,
+        }
+    }
+}
+
+fn chart_hovered(event: &ChartEvent) -> EventSource {
+    match event {
+        ChartEvent::Hover { chart, .. } => EventSource::single(*chart),
+    }
+}
+
+#[derive(Component)]
+pub struct Chart {
+    pub values: Vec<f32>,
+    pub points: Vec<(f32, f32)>,
+    pub series: Vec<Vec<f32>>,
+    pub mode: ChartMode,
+    canvas: Entity,
+    hovered: Option<(usize, usize)>,
+}
+
+impl FromWorldAndParams for Chart {
+    fn from_world_and_params(world: &mut World, params: &mut belly_core::eml::Params) -> Self {
+        Chart {
+            values: params.try_get("values").unwrap_or_default(),
+            points: params.try_get("points").unwrap_or_default(),
+            series: params.try_get("series").unwrap_or_default(),
+            mode: params.try_get("mode").unwrap_or_default(),
+            canvas: world.spawn_empty().id(),
+            hovered: None,
+        }
+    }
+}
+
+const PADDING: f32 = 4.;
+const LABEL_SIZE: f32 = 10.;
+
+/// The part of the canvas the data is plotted to and the data range it shows.
+struct Plot {
+    bounds: Rect,
+    area: Rect,
+}
+
+impl Plot {
+    fn project(&self, p: Vec2) -> Vec2 {
+        // data space is y-up, canvas space is y-down
+        let t = (p - self.bounds.min) / self.bounds.size();
+        Vec2::new(
+            self.area.min.x + t.x * self.area.width(),
+            self.area.max.y - t.y * self.area.height(),
+        )
+    }
+
+    fn y(&self, y: f32) -> f32 {
+        self.project(Vec2::new(self.bounds.min.x, y)).y
+    }
+}
+
+/// The part of the stacked bar showing the point `index` of the `series`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Segment {
+    series: usize,
+    index: usize,
+    from: f32,
+    to: f32,
+}
+
+impl Chart {
+    /// The plotted data series: each of the `series`, the `values` at
+    /// their indices or the `points`.
+    pub fn data(&self) -> Vec<Vec<Vec2>> {
+        if !self.series.is_empty() {
+            self.series.iter().map(|values| indexed(values)).collect()
+        } else if self.values.is_empty() {
+            vec![self.points.iter().map(|(x, y)| Vec2::new(*x, *y)).collect()]
+        } else {
+            vec![indexed(&self.values)]
+        }
+    }
+
+    /// The `(series, index)` of the point under `pos` on the canvas of
+    /// `size`: the nearest point for the line charts, the bar or the cell
+    /// under the pointer otherwise.
+    pub fn hit(&self, pos: Vec2, size: Vec2) -> Option<(usize, usize)> {
+        let data = self.data();
+        let plot = self.plot(&data, size)?;
+        if !plot.area.contains(pos) {
+            return None;
+        }
+        if self.mode != ChartMode::Line {
+            return self
+                .cells(&data, &plot)
+                .into_iter()
+                .find(|(_, _, rect)| rect.contains(pos))
+                .map(|(series, index, _)| (series, index));
+        }
+        let mut nearest = None;
+        let mut distance = f32::INFINITY;
+        for (series, points) in data.iter().enumerate() {
+            for (index, point) in points.iter().enumerate() {
+                let d = plot.project(*point).distance_squared(pos);
+                if d < distance {
+                    distance = d;
+                    nearest = Some((series, index));
+                }
+            }
+        }
+        nearest
+    }
+
+    fn plot(&self, data: &[Vec<Vec2>], size: Vec2) -> Option<Plot> {
+        let area = Rect::from_corners(Vec2::splat(PADDING), size - PADDING);
+        if area.is_empty() {
+            return None;
+        }
+        let bounds = if self.mode == ChartMode::StackedBar {
+            let ends: Vec<_> = stacked_segments(data)
+                .iter()
+                .map(|s| Vec2::new(s.index as f32, s.to))
+                .collect();
+            chart_bounds(&ends, self.mode)
+        } else {
+            chart_bounds(&data.concat(), self.mode)
+        };
+        Some(Plot { bounds, area })
+    }
+
+    /// The `(series, index, rect)` shapes the bar, the stacked bar and
+    /// the heatmap charts are drawn with, in the canvas space.
+    fn cells(&self, data: &[Vec<Vec2>], plot: &Plot) -> Vec<(usize, usize, Rect)> {
+        let columns = data.iter().map(Vec::len).max().unwrap_or(0).max(1) as f32;
+        let slot = plot.area.width() / columns;
+        // the bars of the same index share 80% of the slot
+        let bar = |index: usize, series: usize, count: usize| {
+            let width = slot * 0.8 / count as f32;
+            let left = plot.area.min.x + slot * (index as f32 + 0.1) + width * series as f32;
+            (left, left + width)
+        };
+        let mut cells = vec![];
+        match self.mode {
+            ChartMode::Line => {}
+            ChartMode::Bar => {
+                let base = plot.y(0.);
+                for (series, points) in data.iter().enumerate() {
+                    for (index, point) in points.iter().enumerate() {
+                        let (left, right) = bar(index, series, data.len());
+                        let top = plot.y(point.y);
+                        let rect = Rect::from_corners(Vec2::new(left, base), Vec2::new(right, top));
+                        cells.push((series, index, rect));
+                    }
+                }
+            }
+            ChartMode::StackedBar => {
+                for segment in stacked_segments(data) {
+                    let (left, right) = bar(segment.index, 0, 1);
+                    let rect = Rect::from_corners(
+                        Vec2::new(left, plot.y(segment.from)),
+                        Vec2::new(right, plot.y(segment.to)),
+                    );
+                    cells.push((segment.series, segment.index, rect));
+                }
+            }
+            ChartMode::Heatmap => {
+                // the series are the rows from the top, the indices are the columns
+                let size = plot.area.size() / Vec2::new(columns, data.len().max(1) as f32);
+                for (series, points) in data.iter().enumerate() {
+                    for index in 0..points.len() {
+                        let min = plot.area.min + size * Vec2::new(index as f32, series as f32);
+                        cells.push((series, index, Rect::from_corners(min, min + size)));
+                    }
+                }
+            }
+        }
+        cells
+    }
+
+    fn paint(&self, painter: &mut CanvasPainter, style: &ChartStyle) {
+        let data = self.data();
+        let Some(plot) = self.plot(&data, painter.size()) else {
+            return;
+        };
+        match self.mode {
+            ChartMode::Line => {
+                for (series, points) in data.iter().enumerate() {
+                    let points: Vec<_> = points.iter().map(|p| plot.project(*p)).collect();
+                    painter.polyline(&points, 2., style.color(series));
+                }
+            }
+            ChartMode::Bar | ChartMode::StackedBar => {
+                for (series, _, rect) in self.cells(&data, &plot) {
+                    painter.rect(rect, style.color(series));
+                }
+            }
+            ChartMode::Heatmap => {
+                let bounds = plot.bounds;
+                for (series, index, rect) in self.cells(&data, &plot) {
+                    let t = (data[series][index].y - bounds.min.y) / bounds.height();
+                    painter.rect(rect, style.gradient(t));
+                }
+                // the cells fill the whole area, there is no place for the axes
+                return;
+            }
+        }
+        let axis = style.axis;
+        if axis.width > 0. {
+            let area = plot.area;
+            let zero = plot.y(0.);
+            painter.line(
+                Vec2::new(area.min.x, area.min.y),
+                Vec2::new(area.min.x, area.max.y),
+                axis.width,
+                axis.color,
+            );
+            painter.line(
+                Vec2::new(area.min.x, zero),
+                Vec2::new(area.max.x, zero),
+                axis.width,
+                axis.color,
+            );
+            let label = |v: f32| format!("{}", (v * 100.).round() / 100.);
+            let offset = Vec2::new(axis.width + 2., 0.);
+            painter.text(
+                area.min + offset,
+                label(plot.bounds.max.y),
+                LABEL_SIZE,
+                axis.color,
+            );
+            painter.text(
+                Vec2::new(area.min.x, area.max.y - LABEL_SIZE) + offset,
+                label(plot.bounds.min.y),
+                LABEL_SIZE,
+                axis.color,
+            );
+        }
+    }
+}
+
+fn indexed(values: &[f32]) -> Vec<Vec2> {
+    values
+        .iter()
+        .enumerate()
+        .map(|(x, y)| Vec2::new(x as f32, *y))
+        .collect()
+}
+
+/// Stacks the points with the same index: the positive values grow
+/// up from zero, the negative ones grow down.
+fn stacked_segments(data: &[Vec<Vec2>]) -> Vec<Segment> {
+    let columns = data.iter().map(Vec::len).max().unwrap_or(0);
+    let mut segments = vec![];
+    for index in 0..columns {
+        let (mut above, mut below) = (0., 0.);
+        for (series, points) in data.iter().enumerate() {
+            let Some(point) = points.get(index) else {
+                continue;
+            };
+            let from = if point.y < 0. { &mut below } else { &mut above };
+            segments.push(Segment {
+                series,
+                index,
+                from: *from,
+                to: *from + point.y,
+            });
+            *from += point.y;
+        }
+    }
+    segments
+}
+
+/// The data range the chart shows. The bar charts always include zero,
+/// the empty ranges are expanded to keep the data visible.
+fn chart_bounds(data: &[Vec2], mode: ChartMode) -> Rect {
+    let mut bounds = data
+        .iter()
+        .fold(None, |bounds: Option<Rect>, p| {
+            Some(bounds.map_or(Rect::from_corners(*p, *p), |b| {
+                b.union_point(*p)
+            }))
+        })
+        .unwrap_or(Rect::new(0., 0., 1., 1.));
+    if matches!(mode, ChartMode::Bar | ChartMode::StackedBar) {
+        bounds.min.y = bounds.min.y.min(0.);
+        bounds.max.y = bounds.max.y.max(0.);
+    }
+    if bounds.width() < f32::EPSILON {
+        bounds.max.x = bounds.min.x + 1.;
+    }
+    if bounds.height() < f32::EPSILON {
+        bounds.min.y -= 1.;
+        bounds.max.y += 1.;
+    }
+    bounds
+}
+
+fn redraw_charts(
+    charts: Query<&Chart, Or<(Changed<Chart>, Changed<ChartStyle>)>>,
+    mut canvases: Query<&mut Canvas>,
+) {
+    for chart in charts.iter() {
+        if let Ok(mut canvas) = canvases.get_mut(chart.canvas) {
+            canvas.redraw();
+        }
+    }
+}
+
+/// Emits the `hover` signal when the pointer moves to another point,
+/// the hovered point is stored without touching the change detection,
+/// so the chart isn't redrawn.
+fn hover_charts(
+    mut events: EventReader<PointerInput>,
+    mut charts: Query<(Entity, &mut Chart)>,
+    nodes: Query<(&GlobalTransform, &Node)>,
+    mut signals: EventWriter<ChartEvent>,
+) {
+    for ev in events.read().filter(|ev| ev.motion()) {
+        for (entity, mut chart) in charts.iter_mut() {
+            let hit = match nodes.get(chart.canvas) {
+                Ok((tr, node)) if ev.contains(chart.canvas) => {
+                    let pos = ev.pos - tr.translation().truncate() + node.size() * 0.5;
+                    chart.hit(pos, node.size())
+                }
+                _ => None,
+            };
+            if hit == chart.hovered {
+                continue;
+            }
+            chart.bypass_change_detection().hovered = hit;
+            if let Some((series, index)) = hit {
+                signals.send(ChartEvent::Hover {
+                    chart: entity,
+                    series,
+                    index,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn chart_bounds_fit_data() {
+        let data = [Vec2::new(0., 2.), Vec2::new(1., 5.), Vec2::new(2., 3.)];
+        assert_eq!(chart_bounds(&data, ChartMode::Line), Rect::new(0., 2., 2., 5.));
+        assert_eq!(chart_bounds(&data, ChartMode::Bar), Rect::new(0., 0., 2., 5.));
+        let flat = [Vec2::new(3., 1.)];
+        assert_eq!(chart_bounds(&flat, ChartMode::Line), Rect::new(3., 0., 4., 2.));
+        assert_eq!(chart_bounds(&[], ChartMode::Line), Rect::new(0., 0., 1., 1.));
+    }
+
+    fn series_chart(mode: ChartMode, series: Vec<Vec<f32>>) -> Chart {
+        Chart {
+            values: vec![],
+            points: vec![],
+            series,
+            mode,
+            canvas: Entity::PLACEHOLDER,
+            hovered: None,
+        }
+    }
+
+    // the plotted area of this canvas is 100x100 starting at (4, 4)
+    const SIZE: Vec2 = Vec2::splat(108.);
+
+    #[test]
+    fn chart_mode_parses_all_modes() {
+        assert_eq!("stacked-bar".parse(), Ok(ChartMode::StackedBar));
+        assert_eq!("heatmap".parse(), Ok(ChartMode::Heatmap));
+        assert!("pie".parse::<ChartMode>().is_err());
+    }
+
+    #[test]
+    fn stacked_segments_split_signs() {
+        let data = [indexed(&[1., 1.]), indexed(&[2., -1.]), indexed(&[-3.])];
+        let segments: Vec<_> = stacked_segments(&data)
+            .iter()
+            .map(|s| (s.series, s.index, s.from, s.to))
+            .collect();
+        assert_eq!(
+            segments,
+            vec![
+                (0, 0, 0., 1.),
+                (1, 0, 1., 3.),
+                (2, 0, 0., -3.),
+                (0, 1, 0., 1.),
+                (1, 1, 0., -1.),
+            ]
+        );
+    }
+
+    #[test]
+    fn line_chart_hits_nearest_point() {
+        let chart = series_chart(ChartMode::Line, vec![vec![0., 10.], vec![10., 0.]]);
+        assert_eq!(chart.hit(Vec2::new(10., 95.), SIZE), Some((0, 0)));
+        assert_eq!(chart.hit(Vec2::new(10., 10.), SIZE), Some((1, 0)));
+        assert_eq!(chart.hit(Vec2::new(90., 20.), SIZE), Some((0, 1)));
+        assert_eq!(chart.hit(Vec2::new(1., 1.), SIZE), None);
+    }
+
+    #[test]
+    fn bar_chart_hits_bars() {
+        let chart = series_chart(ChartMode::Bar, vec![vec![1., 2.]]);
+        // the bars span 9..49 and 59..99, the value of 1 reaches y=54
+        assert_eq!(chart.hit(Vec2::new(20., 80.), SIZE), Some((0, 0)));
+        assert_eq!(chart.hit(Vec2::new(70., 10.), SIZE), Some((0, 1)));
+        assert_eq!(chart.hit(Vec2::new(20., 40.), SIZE), None);
+        assert_eq!(chart.hit(Vec2::new(52., 80.), SIZE), None);
+
+        let grouped = series_chart(ChartMode::Bar, vec![vec![1., 1.], vec![1., 1.]]);
+        assert_eq!(grouped.hit(Vec2::new(20., 80.), SIZE), Some((0, 0)));
+        assert_eq!(grouped.hit(Vec2::new(40., 80.), SIZE), Some((1, 0)));
+    }
+
+    #[test]
+    fn stacked_bar_chart_hits_segments() {
+        let chart = series_chart(ChartMode::StackedBar, vec![vec![1., 1.], vec![2., -1.]]);
+        // the data range is -1..3, the zero is at y=79
+        assert_eq!(chart.hit(Vec2::new(20., 60.), SIZE), Some((0, 0)));
+        assert_eq!(chart.hit(Vec2::new(20., 30.), SIZE), Some((1, 0)));
+        assert_eq!(chart.hit(Vec2::new(70., 70.), SIZE), Some((0, 1)));
+        assert_eq!(chart.hit(Vec2::new(70., 90.), SIZE), Some((1, 1)));
+        assert_eq!(chart.hit(Vec2::new(70., 30.), SIZE), None);
+    }
+
+    #[test]
+    fn heatmap_chart_hits_cells() {
+        let chart = series_chart(ChartMode::Heatmap, vec![vec![0., 1., 2.], vec![3., 4.]]);
+        assert_eq!(chart.hit(Vec2::new(10., 10.), SIZE), Some((0, 0)));
+        assert_eq!(chart.hit(Vec2::new(90., 10.), SIZE), Some((0, 2)));
+        assert_eq!(chart.hit(Vec2::new(50., 80.), SIZE), Some((1, 1)));
+        assert_eq!(chart.hit(Vec2::new(90., 80.), SIZE), None);
+    }
+
+    #[test]
+    fn chart_event_source() {
+        let entity = Entity::from_raw(5);
+        let event = ChartEvent::Hover {
+            chart: entity,
+            series: 1,
+            index: 2,
+        };
+        assert_eq!((event.series(), event.index()), (1, 2));
+        assert_eq!(chart_hovered(&event).collect::<Vec<_>>(), vec![entity]);
+    }
+}
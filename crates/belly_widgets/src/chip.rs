@@ -0,0 +1,264 @@
+use crate::input::text::TextInput;
+use belly_core::build::*;
+use belly_core::eml::pool::RecycleElementCommand;
+use belly_core::input;
+use belly_macro::*;
+use bevy::prelude::*;
+
+pub mod prelude {
+    pub use super::Chip;
+    pub use super::ChipEvent;
+    pub use super::ChipWidgetExtension;
+    pub use super::TagInput;
+    pub use super::TaginputWidgetExtension;
+}
+
+pub(crate) struct ChipPlugin;
+impl Plugin for ChipPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ChipEvent>();
+        app.register_widget::<ChipWidget>();
+        app.register_type::<Chip>();
+        app.register_widget::<TaginputWidget>();
+        app.add_systems(
+            PreUpdate,
+            handle_chip_input.in_set(input::InputSystemsSet),
+        );
+        app.add_systems(
+            Update,
+            (add_tags_system, remove_tags_system, render_tags_system).chain(),
+        );
+    }
+}
+
+#[widget]
+#[signal(remove: ChipEvent => chip_removed)]
+/// Specifies the value of the chip, available to the `remove` handlers
+#[param(value: String => Chip:value)]
+/// The button removing the chip
+#[part(remove)]
+#[styles = CHIP_STYLES]
+/// The `<chip>` is the small token with the remove button. Pressing the
+/// button emits the `remove` signal, the chip itself stays in place, so
+/// the handler decides what to do with it.
+fn chip(ctx: &mut WidgetContext) {
+    let this = ctx.entity();
+    let content = ctx.content();
+    let remove = ChipRemove { chip: this };
+    ctx.render(eml! {
+        <span c:chip>
+            <span c:chip-content>{content}</span>
+            <span with=remove part="remove" c:chip-remove interactable>"x"</span>
+        </span>
+    })
+}
+
+#[widget]
+/// Specifies the entered tags
+#[param(tags: Vec<String> => TagInput:tags)]
+/// The text input receiving the new tags
+#[part(input)]
+#[styles = CHIP_STYLES]
+/// The `<taginput>` is the text input turning the entered text into the
+/// `<chip>`s: type the tag and press `Enter` to add it, press the remove
+/// button of the chip to remove the tag. The tags are bindable both ways:
+/// `<taginput bind:tags=to!(filter, Filter:tags)/>`. The duplicates and the
+/// empty tags are ignored.
+fn taginput(ctx: &mut WidgetContext, tags: &mut TagInput) {
+    let input = tags.input;
+    ctx.render(eml! {
+        <span c:taginput>
+            <textinput {input} part="input" c:taginput-input/>
+        </span>
+    })
+}
+
+ess_define! {
+    CHIP_STYLES,
+    chip {
+        align-items: center;
+        margin: 2px;
+        padding: 1px 2px 1px 6px;
+        background-color: #bfbfbf;
+    }
+    chip * {
+        color: #2f2f2f;
+    }
+    .chip-remove {
+        margin-left: 4px;
+        padding: 0px 4px;
+    }
+    .chip-remove:hover {
+        background-color: #9f9f9f;
+    }
+    taginput {
+        flex-wrap: wrap;
+        align-items: center;
+    }
+    .taginput-input {
+        flex-grow: 1;
+        min-width: 80px;
+    }
+}
+
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
+/// The state of the `<chip>`
+pub struct Chip {
+    pub value: String,
+}
+
+#[derive(Component)]
+struct ChipRemove {
+    chip: Entity,
+}
+
+#[derive(Event, Debug)]
+pub enum ChipEvent {
+    Remove(Entity),
+}
+
+fn chip_removed(event: &ChipEvent) -> EventSource {
+    match event {
+        ChipEvent::Remove(entity) => EventSource::single(*entity),
+    }
+}
+
+fn handle_chip_input(
+    mut events: EventReader<PointerInput>,
+    buttons: Query<&ChipRemove>,
+    mut signals: EventWriter<ChipEvent>,
+) {
+    for ev in events.read().filter(|ev| ev.pressed()) {
+        let Some(button) = ev.entities.iter().find_map(|e| buttons.get(*e).ok()) else {
+            continue;
+        };
+        signals.send(ChipEvent::Remove(button.chip));
+    }
+}
+
+#[derive(Component)]
+/// The state of the `<taginput>`
+pub struct TagInput {
+    pub tags: Vec<String>,
+    input: Entity,
+    rendered: Option<Vec<String>>,
+    chips: Vec<Entity>,
+}
+
+impl FromWorldAndParams for TagInput {
+    fn from_world_and_params(world: &mut World, params: &mut belly_core::eml::Params) -> Self {
+        TagInput {
+            tags: params.try_get("tags").unwrap_or_default(),
+            input: world.spawn_empty().id(),
+            rendered: None,
+            chips: vec![],
+        }
+    }
+}
+
+impl TagInput {
+    /// Adds the trimmed `tag`, returns `false` when the tag is empty or
+    /// already added
+    pub fn add(&mut self, tag: &str) -> bool {
+        let tag = tag.trim();
+        if tag.is_empty() || self.tags.iter().any(|t| t == tag) {
+            return false;
+        }
+        self.tags.push(tag.to_string());
+        true
+    }
+}
+
+#[derive(Component)]
+struct TagChip {
+    taginput: Entity,
+    tag: String,
+}
+
+fn add_tags_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut taginputs: Query<&mut TagInput>,
+    mut inputs: Query<(&mut TextInput, &Element)>,
+) {
+    if !keyboard.just_pressed(KeyCode::Enter) {
+        return;
+    }
+    for mut taginput in taginputs.iter_mut() {
+        let Ok((mut input, element)) = inputs.get_mut(taginput.input) else {
+            continue;
+        };
+        if !element.focused() || input.value.trim().is_empty() {
+            continue;
+        }
+        taginput.add(&input.value);
+        input.set_value("");
+    }
+}
+
+fn remove_tags_system(
+    mut events: EventReader<ChipEvent>,
+    chips: Query<&TagChip>,
+    mut taginputs: Query<&mut TagInput>,
+) {
+    for ChipEvent::Remove(chip) in events.read() {
+        let Ok(chip) = chips.get(*chip) else {
+            continue;
+        };
+        let Ok(mut taginput) = taginputs.get_mut(chip.taginput) else {
+            continue;
+        };
+        taginput.tags.retain(|tag| tag != &chip.tag);
+    }
+}
+
+/// Re-renders the chips when the tags change, the chips are placed
+/// before the text input
+fn render_tags_system(
+    mut commands: Commands,
+    mut taginputs: Query<(Entity, &mut TagInput), Changed<TagInput>>,
+) {
+    for (entity, mut taginput) in taginputs.iter_mut() {
+        let taginput = taginput.bypass_change_detection();
+        if taginput.rendered.as_ref() == Some(&taginput.tags) {
+            continue;
+        }
+        taginput.rendered = Some(taginput.tags.clone());
+        for chip in taginput.chips.drain(..) {
+            commands.add(RecycleElementCommand(chip));
+        }
+        for tag in taginput.tags.iter() {
+            let chip = commands
+                .spawn(TagChip {
+                    taginput: entity,
+                    tag: tag.clone(),
+                })
+                .id();
+            let value = tag.clone();
+            let text = tag.clone();
+            commands.add(eml! { <chip value=value>{text}</chip> }.render_to(chip));
+            taginput.chips.push(chip);
+        }
+        let chips = taginput.chips.clone();
+        commands.entity(entity).insert_children(0, &chips);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn add_unique_tags() {
+        let mut taginput = TagInput {
+            tags: vec!["rust".to_string()],
+            input: Entity::PLACEHOLDER,
+            rendered: None,
+            chips: vec![],
+        };
+        assert!(taginput.add(" bevy "));
+        assert!(!taginput.add("rust"));
+        assert!(!taginput.add("  "));
+        assert_eq!(taginput.tags, vec!["rust", "bevy"]);
+    }
+}
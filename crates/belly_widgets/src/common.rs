@@ -1,7 +1,9 @@
 use super::range::*;
 use belly_core::build::*;
+use belly_core::relations::process_relations_system;
 use belly_macro::*;
 use bevy::prelude::*;
+use bevy::ui::UiSystem;
 
 #[doc(hidden)]
 pub(crate) struct CommonsPlugin;
@@ -19,6 +21,7 @@ pub mod prelude {
 
 impl Plugin for CommonsPlugin {
     fn build(&self, app: &mut App) {
+        app.register_type::<Label>();
         app.register_widget::<BodyWidget>();
         app.register_widget::<DivWidget>();
         app.register_widget::<LabelWidget>();
@@ -26,6 +29,12 @@ impl Plugin for CommonsPlugin {
         app.register_widget::<ProgressbarWidget>();
         app.register_widget::<SpanWidget>();
         app.register_widget::<StrongWidget>();
+        app.add_systems(
+            PostUpdate,
+            update_labels
+                .after(process_relations_system)
+                .before(UiSystem::Layout),
+        );
     }
 }
 
@@ -92,17 +101,134 @@ fn strong(ctx: &mut WidgetContext) {
     ctx.insert(ElementBundle::default()).push_children(&content);
 }
 
-#[derive(Component, Default)]
+#[derive(Component, Reflect)]
+#[reflect(Component)]
 pub struct Label {
     pub value: String,
+    /// The time in seconds the numeric value counts to the new one,
+    /// `0.` shows the new value at once
+    pub tween: f32,
+    /// The easing of the counting
+    pub ease: Ease,
+    #[reflect(ignore)]
+    counter: Option<LabelCounter>,
+}
+
+impl Default for Label {
+    fn default() -> Self {
+        Label {
+            value: String::new(),
+            tween: 0.,
+            ease: Ease::OutCubic,
+            counter: None,
+        }
+    }
+}
+
+/// The numeric value of the [`Label`] counting to the new one
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct LabelCounter {
+    from: f64,
+    to: f64,
+    elapsed: f32,
+    /// The number of the decimal places of the new value
+    decimals: usize,
+}
+
+impl LabelCounter {
+    /// The value shown after the counter runs `elapsed` of `duration` seconds
+    fn value(&self, duration: f32, ease: Ease) -> f64 {
+        let t = ease.apply(self.elapsed / duration.max(f32::EPSILON));
+        self.from.interpolate(&self.to, t)
+    }
+
+    fn format(&self, duration: f32, ease: Ease) -> String {
+        format!("{:.*}", self.decimals, self.value(duration, ease))
+    }
 }
 
 #[widget]
+/// Specifies the text of the label
 #[param(value:String => Label:value)]
+/// Specifies the time in seconds the numeric `value` counts up or down
+/// to the new one instead of snapping to it
+#[param(tween:f32 => Label:tween)]
+/// Specifies the easing of the counting: `linear`, `ease-out` (default),
+/// `spring(170, 26)`, `steps(5)`, etc.
+#[param(ease:Ease => Label:ease)]
 /// The `<label>` tag is a binable single line of text. It consumes
 /// the children and renders the content of bindable `value` param.
+/// With the `tween` param the numeric values count to the new ones,
+/// keeping the decimal places of the new value.
 fn label(ctx: &mut WidgetContext) {
-    let this = ctx.this().id();
-    ctx.add(from!(this, Label: value) >> to!(this, Text:sections[0].value));
     ctx.insert(TextElementBundle::default());
 }
+
+/// Shows the values of the labels, starts counting when the numeric
+/// value of the label with the `tween` changes.
+fn update_labels(time: ElementsTime, mut labels: Query<(Entity, &mut Label, &mut Text)>) {
+    for (entity, mut label, mut text) in labels.iter_mut() {
+        if text.sections.is_empty() {
+            continue;
+        }
+        if label.is_changed() {
+            let added = label.is_added();
+            let target = label.value.trim().parse::<f64>().ok();
+            let current = match &label.counter {
+                Some(counter) => Some(counter.value(label.tween, label.ease)),
+                None => text.sections[0].value.trim().parse::<f64>().ok(),
+            };
+            let label = label.bypass_change_detection();
+            label.counter = match (current, target) {
+                (Some(from), Some(to)) if label.tween > 0. && from != to && !added => {
+                    Some(LabelCounter {
+                        from,
+                        to,
+                        elapsed: 0.,
+                        decimals: label.value.trim().split_once('.').map_or(0, |(_, d)| d.len()),
+                    })
+                }
+                _ => None,
+            };
+        }
+        let (tween, ease) = (label.tween, label.ease);
+        let value = match label.bypass_change_detection().counter.as_mut() {
+            Some(counter) if counter.elapsed < tween => {
+                counter.elapsed += time.delta_seconds(entity);
+                counter.format(tween, ease)
+            }
+            _ => {
+                label.bypass_change_detection().counter = None;
+                label.value.clone()
+            }
+        };
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn count_labels_to_new_values() {
+        let counter = LabelCounter {
+            from: 100.,
+            to: 200.,
+            elapsed: 0.,
+            decimals: 0,
+        };
+        assert_eq!(counter.format(1., Ease::OutCubic), "100");
+        let half = LabelCounter { elapsed: 0.5, ..counter };
+        // slows down at the end: the half of the time counts most of the way
+        assert_eq!(half.format(1., Ease::OutCubic), "188");
+        assert_eq!(half.format(1., Ease::Linear), "150");
+        assert_eq!(half.format(1., Ease::Steps(4)), "150");
+        let done = LabelCounter { elapsed: 2., decimals: 2, ..counter };
+        assert_eq!(done.format(1., Ease::OutCubic), "200.00");
+        let down = LabelCounter { from: 5., to: 1., elapsed: 1., decimals: 1 };
+        assert_eq!(down.format(1., Ease::OutCubic), "1.0");
+    }
+}
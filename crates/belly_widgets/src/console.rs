@@ -0,0 +1,416 @@
+use crate::common::Label;
+use crate::input::text::TextInput;
+use belly_core::build::*;
+use belly_core::input::RequestFocus;
+use belly_macro::*;
+use bevy::{ecs::system::Command, prelude::*, utils::HashMap};
+use std::sync::Arc;
+
+pub mod prelude {
+    pub use super::Console;
+    pub use super::ConsoleCommands;
+    pub use super::ConsoleWidgetExtension;
+    pub use super::ExecuteConsoleLine;
+    pub use super::RegisterConsoleCommand;
+}
+
+/// The commands handled by the console itself
+const BUILTIN_COMMANDS: &[&str] = &["clear", "help"];
+
+pub(crate) struct ConsolePlugin;
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ConsoleCommands>();
+        app.register_widget::<ConsoleWidget>();
+        app.register_widget_states::<Console>();
+        app.add_systems(
+            Update,
+            (process_console_input_system, render_console_output_system).chain(),
+        );
+    }
+}
+
+type ConsoleHandler = Arc<dyn Fn(&mut World, &[&str]) -> String + Send + Sync>;
+
+/// The commands available in the `<console>`, registered with
+/// [`RegisterConsoleCommand::register_console_command`].
+#[derive(Resource, Default, Clone)]
+pub struct ConsoleCommands(HashMap<String, ConsoleHandler>);
+
+impl ConsoleCommands {
+    /// The names of the commands (including the built-in `clear` and
+    /// `help`) starting with the `prefix`, sorted
+    pub fn complete(&self, prefix: &str) -> Vec<String> {
+        let mut names: Vec<_> = self
+            .0
+            .keys()
+            .map(|name| name.as_str())
+            .chain(BUILTIN_COMMANDS.iter().copied())
+            .filter(|name| name.starts_with(prefix))
+            .map(|name| name.to_string())
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+}
+
+pub trait RegisterConsoleCommand {
+    /// Registers the console command `name`. The handler receives the
+    /// whitespace separated arguments and returns the text printed to
+    /// the console (nothing is printed for the empty text):
+    /// ```rust,ignore
+    /// app.register_console_command("gravity", |world, args| {
+    ///     let Some(Ok(value)) = args.first().map(|v| v.parse::<f32>()) else {
+    ///         return "Usage: gravity <value>".into();
+    ///     };
+    ///     world.resource_mut::<Gravity>().0 = value;
+    ///     format!("Gravity set to {value}")
+    /// });
+    /// ```
+    fn register_console_command<F>(&mut self, name: &str, handler: F) -> &mut Self
+    where
+        F: Fn(&mut World, &[&str]) -> String + Send + Sync + 'static;
+}
+
+impl RegisterConsoleCommand for App {
+    fn register_console_command<F>(&mut self, name: &str, handler: F) -> &mut Self
+    where
+        F: Fn(&mut World, &[&str]) -> String + Send + Sync + 'static,
+    {
+        self.world
+            .get_resource_or_insert_with(ConsoleCommands::default)
+            .0
+            .insert(name.to_string(), Arc::new(handler));
+        self
+    }
+}
+
+#[derive(Component)]
+/// The state of the `<console>`: the scrollback and the history of the
+/// executed commands.
+pub struct Console {
+    /// Shows the console, the `console:open` selector matches the open one
+    pub open: bool,
+    /// The key toggling the console
+    pub toggle_key: KeyCode,
+    /// The max number of the printed lines kept in the scrollback
+    pub capacity: usize,
+    /// The printed lines, the latest last
+    pub scrollback: Vec<String>,
+    /// The executed lines, the latest last
+    pub history: Vec<String>,
+    history_index: Option<usize>,
+    input: Entity,
+    output: Entity,
+}
+
+impl FromWorldAndParams for Console {
+    fn from_world_and_params(world: &mut World, _params: &mut belly_core::eml::Params) -> Self {
+        Console {
+            open: false,
+            toggle_key: KeyCode::F1,
+            capacity: 200,
+            scrollback: vec![],
+            history: vec![],
+            history_index: None,
+            input: world.spawn_empty().id(),
+            output: world.spawn_empty().id(),
+        }
+    }
+}
+
+impl WidgetStates for Console {
+    fn states(&self) -> Vec<(Tag, bool)> {
+        vec![(Tag::new("open"), self.open)]
+    }
+}
+
+impl Console {
+    /// Prints the `text` (may be multiline) to the scrollback
+    pub fn print(&mut self, text: impl AsRef<str>) {
+        self.scrollback
+            .extend(text.as_ref().lines().map(|line| line.to_string()));
+        let overflow = self.scrollback.len().saturating_sub(self.capacity);
+        self.scrollback.drain(..overflow);
+    }
+
+    pub fn clear(&mut self) {
+        self.scrollback.clear();
+    }
+
+    fn push_history(&mut self, line: &str) {
+        self.history_index = None;
+        if self.history.last().map_or(true, |last| last != line) {
+            self.history.push(line.to_string());
+        }
+    }
+
+    /// Steps back in the history, returns the line to edit
+    fn history_prev(&mut self) -> Option<String> {
+        let index = match self.history_index {
+            _ if self.history.is_empty() => return None,
+            None => self.history.len() - 1,
+            Some(index) => index.saturating_sub(1),
+        };
+        self.history_index = Some(index);
+        Some(self.history[index].clone())
+    }
+
+    /// Steps forward in the history, returns the empty line after the
+    /// latest one
+    fn history_next(&mut self) -> Option<String> {
+        let index = self.history_index?;
+        if index + 1 < self.history.len() {
+            self.history_index = Some(index + 1);
+            Some(self.history[index + 1].clone())
+        } else {
+            self.history_index = None;
+            Some(String::new())
+        }
+    }
+}
+
+#[widget]
+#[param(open: bool => Console:open)]
+/// The printed lines
+#[part(output)]
+/// The command line
+#[part(input)]
+#[styles = CONSOLE_STYLES]
+/// The `<console>` is the developer console overlay: press `F1` (see
+/// [`Console::toggle_key`]) to open it, type the command registered with
+/// [`RegisterConsoleCommand::register_console_command`] and press `Enter`
+/// to execute it. `Up` and `Down` walk the history, `Tab` completes the
+/// command name. The built-in `help` lists the commands, `clear` clears
+/// the scrollback.
+fn console(ctx: &mut WidgetContext, console: &mut Console) {
+    let input = console.input;
+    let output = console.output;
+    ctx.render(eml! {
+        <div c:console layer="debug">
+            <div part="output" c:console-output>
+                <label {output} c:console-lines/>
+            </div>
+            <textinput {input} part="input" c:console-input/>
+        </div>
+    })
+}
+
+ess_define! {
+    CONSOLE_STYLES,
+    console {
+        display: none;
+        position-type: absolute;
+        left: 0px;
+        top: 0px;
+        width: 100%;
+        height: 40%;
+        flex-direction: column;
+        padding: 4px;
+        background-color: #1e1e1ee8;
+    }
+    console:open {
+        display: flex;
+    }
+    .console-output {
+        flex-grow: 1;
+        flex-direction: column;
+        justify-content: flex-end;
+        overflow: clip;
+    }
+    .console-lines {
+        font-size: 14px;
+        color: #cfcfcf;
+    }
+    .console-input {
+        width: 100%;
+    }
+}
+
+/// Executes the `line` in the `console` as if it was typed in: prints the
+/// line, adds it to the history and prints the output of the command.
+pub struct ExecuteConsoleLine {
+    pub console: Entity,
+    pub line: String,
+}
+
+impl ExecuteConsoleLine {
+    pub fn new(console: Entity, line: impl Into<String>) -> ExecuteConsoleLine {
+        ExecuteConsoleLine {
+            console,
+            line: line.into(),
+        }
+    }
+}
+
+impl Command for ExecuteConsoleLine {
+    fn apply(self, world: &mut World) {
+        let line = self.line.trim();
+        let mut words = line.split_whitespace();
+        let Some(name) = words.next() else {
+            return;
+        };
+        let args: Vec<_> = words.collect();
+        let registry = world.get_resource::<ConsoleCommands>().cloned().unwrap_or_default();
+        let Some(mut console) = world.get_mut::<Console>(self.console) else {
+            return;
+        };
+        console.push_history(line);
+        console.print(format!("> {line}"));
+        let output = match name {
+            "clear" => {
+                console.clear();
+                return;
+            }
+            "help" => registry.complete("").join("  "),
+            name => match registry.0.get(name) {
+                Some(handler) => handler(world, &args),
+                None => format!("Unknown command `{name}`, type `help` to list the commands"),
+            },
+        };
+        if output.is_empty() {
+            return;
+        }
+        if let Some(mut console) = world.get_mut::<Console>(self.console) {
+            console.print(output);
+        }
+    }
+}
+
+fn process_console_input_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    registry: Res<ConsoleCommands>,
+    mut consoles: Query<(Entity, &mut Console)>,
+    mut inputs: Query<&mut TextInput>,
+    mut focus: EventWriter<RequestFocus>,
+    mut commands: Commands,
+) {
+    for (entity, mut console) in consoles.iter_mut() {
+        if keyboard.just_pressed(console.toggle_key) {
+            console.open = !console.open;
+            if console.open {
+                focus.send(RequestFocus(console.input));
+            }
+        }
+        if !console.open {
+            continue;
+        }
+        let Ok(mut input) = inputs.get_mut(console.input) else {
+            continue;
+        };
+        if keyboard.just_pressed(KeyCode::Enter) {
+            let line = std::mem::take(&mut input.value);
+            input.set_value("");
+            commands.add(ExecuteConsoleLine::new(entity, line));
+        } else if keyboard.just_pressed(KeyCode::ArrowUp) {
+            if let Some(line) = console.history_prev() {
+                input.set_value(line);
+            }
+        } else if keyboard.just_pressed(KeyCode::ArrowDown) {
+            if let Some(line) = console.history_next() {
+                input.set_value(line);
+            }
+        } else if keyboard.just_pressed(KeyCode::Tab) {
+            // the tab moves the focus, take it back
+            focus.send(RequestFocus(console.input));
+            let prefix = input.value.trim_start().to_string();
+            if prefix.contains(' ') {
+                continue;
+            }
+            let names = registry.complete(&prefix);
+            match names.as_slice() {
+                [] => {}
+                [name] => input.set_value(format!("{name} ")),
+                names => {
+                    let common = common_prefix(names);
+                    if common.len() > prefix.len() {
+                        input.set_value(common);
+                    } else {
+                        console.print(names.join("  "));
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn common_prefix(names: &[String]) -> String {
+    let first = &names[0];
+    let len = names[1..].iter().fold(first.len(), |len, name| {
+        first
+            .chars()
+            .zip(name.chars())
+            .take_while(|(a, b)| a == b)
+            .map(|(a, _)| a.len_utf8())
+            .sum::<usize>()
+            .min(len)
+    });
+    first[..len].to_string()
+}
+
+fn render_console_output_system(
+    consoles: Query<&Console, Changed<Console>>,
+    mut labels: Query<&mut Label>,
+) {
+    for console in consoles.iter() {
+        let Ok(mut label) = labels.get_mut(console.output) else {
+            continue;
+        };
+        let text = console.scrollback.join("\n");
+        if label.value != text {
+            label.value = text;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn console() -> Console {
+        Console::from_world_and_params(&mut World::new(), &mut Default::default())
+    }
+
+    #[test]
+    fn walk_console_history() {
+        let mut console = console();
+        assert_eq!(console.history_prev(), None);
+        console.push_history("spawn");
+        console.push_history("help");
+        console.push_history("help");
+        assert_eq!(console.history, vec!["spawn", "help"]);
+        assert_eq!(console.history_prev().as_deref(), Some("help"));
+        assert_eq!(console.history_prev().as_deref(), Some("spawn"));
+        assert_eq!(console.history_prev().as_deref(), Some("spawn"));
+        assert_eq!(console.history_next().as_deref(), Some("help"));
+        assert_eq!(console.history_next().as_deref(), Some(""));
+        assert_eq!(console.history_next(), None);
+    }
+
+    #[test]
+    fn execute_console_commands() {
+        let mut app = App::new();
+        app.register_console_command("spawn", |_, args| format!("spawned {}", args.join(",")));
+        app.register_console_command("speed", |_, _| String::new());
+        let entity = app.world.spawn(console()).id();
+        app.world.get_mut::<Console>(entity).unwrap().capacity = 4;
+        ExecuteConsoleLine::new(entity, "spawn a b").apply(&mut app.world);
+        ExecuteConsoleLine::new(entity, "unknown").apply(&mut app.world);
+        ExecuteConsoleLine::new(entity, "help").apply(&mut app.world);
+        let console = app.world.get::<Console>(entity).unwrap();
+        assert_eq!(
+            console.scrollback,
+            vec![
+                "> unknown",
+                "Unknown command `unknown`, type `help` to list the commands",
+                "> help",
+                "clear  help  spawn  speed",
+            ]
+        );
+        let registry = app.world.resource::<ConsoleCommands>();
+        assert_eq!(registry.complete("sp"), vec!["spawn", "speed"]);
+        assert_eq!(common_prefix(&registry.complete("sp")), "sp");
+        assert_eq!(common_prefix(&registry.complete("c")), "clear");
+    }
+}
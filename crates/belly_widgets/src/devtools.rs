@@ -0,0 +1,445 @@
+use belly_core::build::*;
+use belly_core::element::Element;
+use belly_core::ess::{preceding_siblings, Defaults, ElementsBranch, EssLocation, Styles};
+use belly_macro::*;
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*,
+    ui::UiStack,
+    window::PrimaryWindow,
+};
+
+pub mod prelude {
+    pub use super::BellyDevToolsPlugin;
+    pub use super::DevTools;
+}
+
+/// The in-game style inspector. Press `F12` (see [`DevTools::toggle_key`])
+/// to show the overlay: hover an element to inspect it, click to pin the
+/// inspected element and press `Escape` to unpin it. The overlay shows the
+/// element branch, the matched rules with their origin and specificity, and
+/// the resolved property values.
+pub struct BellyDevToolsPlugin;
+
+impl Plugin for BellyDevToolsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DevTools>();
+        app.add_systems(Startup, setup_devtools_system);
+        app.add_systems(
+            Update,
+            (
+                toggle_devtools_system,
+                pick_element_system,
+                highlight_element_system,
+                render_inspection_system,
+            )
+                .chain(),
+        );
+    }
+}
+
+#[derive(Resource)]
+pub struct DevTools {
+    pub enabled: bool,
+    pub toggle_key: KeyCode,
+    /// The element under the cursor
+    pub hovered: Option<Entity>,
+    /// The element pinned by click, inspected instead of the hovered one
+    pub picked: Option<Entity>,
+    root: Entity,
+    highlight: Entity,
+    info: Entity,
+    shown: Option<Entity>,
+}
+
+impl Default for DevTools {
+    fn default() -> Self {
+        DevTools {
+            enabled: false,
+            toggle_key: KeyCode::F12,
+            hovered: None,
+            picked: None,
+            root: Entity::PLACEHOLDER,
+            highlight: Entity::PLACEHOLDER,
+            info: Entity::PLACEHOLDER,
+            shown: None,
+        }
+    }
+}
+
+impl DevTools {
+    pub fn inspected(&self) -> Option<Entity> {
+        self.picked.or(self.hovered)
+    }
+}
+
+/// The rule matching the inspected element.
+#[derive(Debug, Clone)]
+pub struct MatchedRule {
+    pub selector: String,
+    /// The asset path of the stylesheet or its description
+    pub origin: String,
+    pub location: EssLocation,
+    pub specificity: i32,
+    /// The `name: value` declarations as written in the stylesheet
+    pub declarations: Vec<String>,
+}
+
+/// Everything the inspector knows about the element.
+#[derive(Debug, Clone)]
+pub struct Inspection {
+    pub element: String,
+    pub size: Vec2,
+    /// The element and its ancestors, from the root to the element
+    pub branch: Vec<String>,
+    /// The matched rules, from the most to the least specific
+    pub rules: Vec<MatchedRule>,
+    /// The `name: value` of the applied properties with their origin
+    pub properties: Vec<String>,
+}
+
+#[derive(SystemParam)]
+pub struct Inspector<'w, 's> {
+    parents: Query<'w, 's, &'static Parent>,
+    children: Query<'w, 's, &'static Children>,
+    nodes: Query<'w, 's, &'static Node>,
+    styles: Res<'w, Styles>,
+    sheets: Res<'w, Assets<StyleSheet>>,
+    defaults: Option<Res<'w, Defaults>>,
+    asset_server: Option<Res<'w, AssetServer>>,
+}
+
+impl<'w, 's> Inspector<'w, 's> {
+    pub fn inspect(&self, entity: Entity, elements: &Elements) -> Option<Inspection> {
+        let target = elements.get(entity).ok()?;
+        let mut ancestors = vec![];
+        let mut branch = ElementsBranch::new();
+        let mut tail = entity;
+        while let Ok(element) = elements.get(tail) {
+            let parent = self.parents.get(tail).map(|p| p.get()).ok();
+            if !element.is_virtual() {
+                let siblings = match parent.and_then(|p| self.children.get(p).ok()) {
+                    Some(children) => preceding_siblings(tail, children, |e| {
+                        elements.get(e).ok().map(|e| e.element())
+                    }),
+                    None => Default::default(),
+                };
+                branch.insert_with_siblings(element.element(), siblings);
+                ancestors.push(element);
+            }
+            let Some(parent) = parent else {
+                break;
+            };
+            tail = parent;
+        }
+        let names = ancestors.iter().rev().map(|e| e.to_string()).collect();
+
+        let mut rules: Vec<_> = self
+            .styles
+            .iter()
+            .filter_map(|handle| self.sheets.get(handle).map(|sheet| (handle, sheet)))
+            .flat_map(|(handle, sheet)| sheet.iter().map(move |rule| (handle, rule)))
+            .filter(|(_, rule)| rule.selector.matches(&branch))
+            .collect();
+        rules.sort_by_key(|(_, rule)| -rule.selector.weight);
+
+        let mut properties = vec![];
+        let mut resolved = target.styles.keys().copied().collect::<Vec<_>>();
+        for name in resolved.iter() {
+            properties.push(format!("{name}: <inline>"));
+        }
+        let mut matched = vec![];
+        for (handle, rule) in rules {
+            let origin = self.origin(handle);
+            let mut declarations: Vec<_> = rule.declarations.iter().collect();
+            declarations.sort_by_key(|(name, _)| name.as_str());
+            for (name, value) in declarations.iter() {
+                if !resolved.contains(*name) {
+                    resolved.push(**name);
+                    let value = value.to_string();
+                    properties.push(format!("{name}: {value}  ({origin}:{})", rule.location));
+                }
+            }
+            matched.push(MatchedRule {
+                selector: rule.selector.to_string(),
+                origin,
+                location: rule.location,
+                specificity: rule.selector.weight.specificity(),
+                declarations: declarations
+                    .iter()
+                    .map(|(name, value)| format!("{name}: {};", value.to_string()))
+                    .collect(),
+            });
+        }
+        properties.sort();
+
+        Some(Inspection {
+            element: target.to_string(),
+            size: self.nodes.get(entity).map(Node::size).unwrap_or_default(),
+            branch: names,
+            rules: matched,
+            properties,
+        })
+    }
+
+    fn origin(&self, handle: &Handle<StyleSheet>) -> String {
+        if self.defaults.as_ref().map_or(false, |d| &d.style_sheet == handle) {
+            return "default styles".to_string();
+        }
+        self.asset_server
+            .as_ref()
+            .and_then(|server| server.get_path(handle.id()))
+            .map(|path| path.to_string())
+            .unwrap_or_else(|| format!("stylesheet #{}", self.styles.weight(handle)))
+    }
+}
+
+fn setup_devtools_system(mut commands: Commands, mut devtools: ResMut<DevTools>) {
+    devtools.root = commands.spawn_empty().id();
+    devtools.highlight = commands.spawn_empty().id();
+    devtools.info = commands.spawn_empty().id();
+    let (root, highlight, info) = (devtools.root, devtools.highlight, devtools.info);
+    commands.add(StyleSheet::parse(DEVTOOLS_STYLES));
+    commands.add(eml! {
+        <div {root} c:devtools layer="debug">
+            <span {highlight} c:devtools-highlight/>
+            <div c:devtools-panel>
+                <div {info} c:devtools-info/>
+            </div>
+        </div>
+    });
+}
+
+fn toggle_devtools_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut devtools: ResMut<DevTools>,
+    mut elements: Elements,
+) {
+    if keyboard.just_pressed(devtools.toggle_key) {
+        devtools.enabled = !devtools.enabled;
+        elements.toggle_class(devtools.root, "devtools-enabled".into());
+    }
+    if devtools.enabled && keyboard.just_pressed(KeyCode::Escape) {
+        devtools.picked = None;
+    }
+}
+
+fn pick_element_system(
+    mut devtools: ResMut<DevTools>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    stack: Res<UiStack>,
+    nodes: Query<(&Node, &GlobalTransform, &Element)>,
+    parents: Query<&Parent>,
+) {
+    if !devtools.enabled {
+        return;
+    }
+    let cursor = windows.get_single().ok().and_then(|w| w.cursor_position());
+    let root = devtools.root;
+    let is_overlay = |entity| {
+        let mut tail = entity;
+        loop {
+            if tail == root {
+                return true;
+            }
+            match parents.get(tail) {
+                Ok(parent) => tail = parent.get(),
+                Err(_) => return false,
+            }
+        }
+    };
+    devtools.hovered = cursor.and_then(|cursor| {
+        stack.uinodes.iter().rev().copied().find(|entity| {
+            let Ok((node, transform, element)) = nodes.get(*entity) else {
+                return false;
+            };
+            let center = transform.translation().truncate();
+            let extents = node.size() / 2.;
+            !element.is_virtual()
+                && node.size() != Vec2::ZERO
+                && Rect::from_corners(center - extents, center + extents).contains(cursor)
+                && !is_overlay(*entity)
+        })
+    });
+    if mouse.just_pressed(MouseButton::Left) && devtools.hovered.is_some() {
+        devtools.picked = devtools.hovered;
+    }
+}
+
+fn highlight_element_system(
+    devtools: Res<DevTools>,
+    nodes: Query<(&Node, &GlobalTransform)>,
+    mut styles: Query<&mut Style>,
+) {
+    let Ok(mut style) = styles.get_mut(devtools.highlight) else {
+        return;
+    };
+    let rect = devtools
+        .inspected()
+        .filter(|_| devtools.enabled)
+        .and_then(|entity| nodes.get(entity).ok())
+        .map(|(node, transform)| {
+            let center = transform.translation().truncate();
+            Rect::from_center_size(center, node.size())
+        });
+    let Some(rect) = rect else {
+        if style.display != Display::None {
+            style.display = Display::None;
+        }
+        return;
+    };
+    style.display = Display::Flex;
+    style.left = Val::Px(rect.min.x);
+    style.top = Val::Px(rect.min.y);
+    style.width = Val::Px(rect.width());
+    style.height = Val::Px(rect.height());
+}
+
+fn render_inspection_system(
+    mut devtools: ResMut<DevTools>,
+    changed: Query<(), Changed<Element>>,
+    inspector: Inspector,
+    mut elements: Elements,
+) {
+    let inspected = devtools.inspected().filter(|_| devtools.enabled);
+    let element_changed = inspected.map_or(false, |e| changed.contains(e));
+    if inspected == devtools.shown && !element_changed {
+        return;
+    }
+    devtools.shown = inspected;
+    let info = devtools.info;
+    elements.commands().entity(info).despawn_descendants();
+    let Some(inspection) = inspected.and_then(|e| inspector.inspect(e, &elements)) else {
+        return;
+    };
+    let title = format!(
+        "{}  {}x{}",
+        inspection.element, inspection.size.x, inspection.size.y
+    );
+    let branch = inspection.branch.join(" ");
+    let rules: Vec<_> = inspection
+        .rules
+        .iter()
+        .flat_map(|rule| {
+            let header = format!(
+                "{}  [{}]  {}:{}",
+                rule.selector, rule.specificity, rule.origin, rule.location
+            );
+            let declarations = rule.declarations.iter().map(|d| format!("    {d}"));
+            std::iter::once(header).chain(declarations)
+        })
+        .collect();
+    let properties = inspection.properties;
+    elements.entity(info).add_child(eml! {
+        <div c:devtools-lines>
+            <span c:devtools-title>{title}</span>
+            <span c:devtools-section>"Branch"</span>
+            <span c:devtools-line>{branch}</span>
+            <span c:devtools-section>"Matched rules"</span>
+            <for rule in=rules>
+                <span c:devtools-line>{rule}</span>
+            </for>
+            <span c:devtools-section>"Properties"</span>
+            <for property in=properties>
+                <span c:devtools-line>{property}</span>
+            </for>
+        </div>
+    });
+}
+
+const DEVTOOLS_STYLES: &str = r#"
+    .devtools {
+        display: none;
+        position-type: absolute;
+        left: 0px;
+        top: 0px;
+        width: 100%;
+        height: 100%;
+    }
+    .devtools.devtools-enabled {
+        display: flex;
+    }
+    .devtools-highlight {
+        position-type: absolute;
+        background-color: #2196f340;
+    }
+    .devtools-panel {
+        position-type: absolute;
+        right: 0px;
+        top: 0px;
+        bottom: 0px;
+        width: 420px;
+        padding: 8px;
+        overflow: clip;
+        background-color: #1e1e1ee8;
+    }
+    .devtools-lines {
+        flex-direction: column;
+    }
+    .devtools-lines * {
+        font-size: 14px;
+        color: #cfcfcf;
+    }
+    .devtools-title {
+        font: bold;
+        color: #ffffff;
+    }
+    .devtools-section {
+        margin-top: 8px;
+        color: #4caf50;
+    }
+"#;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use belly_core::element::ElementIdIndex;
+    use belly_core::ess::{
+        PropertyExtractor, PropertyPlugin, PropertyTransformer, StyleSheetParser,
+    };
+    use bevy::ecs::system::RunSystemOnce;
+
+    #[test]
+    fn inspect_matched_rules() {
+        let mut world = World::new();
+        world.init_resource::<ElementIdIndex>();
+        world.init_resource::<Styles>();
+        world.init_resource::<Assets<StyleSheet>>();
+        let mut app = App::new();
+        app.add_plugins(PropertyPlugin);
+        let extractor = app.world.resource::<PropertyExtractor>().clone();
+        let transformer = app.world.resource::<PropertyTransformer>().clone();
+        let parser = StyleSheetParser::new(transformer, extractor);
+        let rules = parser.parse(
+            r#"
+            div { width: 10px; }
+            .panel div { width: 20px; height: 5px; }
+            span { width: 30px; }
+            "#,
+        );
+        let handle = world.resource_mut::<Assets<StyleSheet>>().add(StyleSheet::new(rules));
+        let weight = world.resource_mut::<Styles>().insert(handle);
+
+        let mut panel = Element::default();
+        panel.names.push("div".into());
+        panel.classes.insert("panel".into());
+        let mut child = Element::default();
+        child.names.push("div".into());
+        let panel = world.spawn(panel).id();
+        let child = world.spawn(child).set_parent(panel).id();
+
+        let inspection = world
+            .run_system_once(move |inspector: Inspector, elements: Elements| {
+                inspector.inspect(child, &elements)
+            })
+            .unwrap();
+        assert_eq!(inspection.element, "div");
+        assert_eq!(inspection.branch, vec!["div.panel", "div"]);
+        assert_eq!(inspection.rules.len(), 2);
+        assert_eq!(inspection.rules[0].selector, ".panel div");
+        assert_eq!(inspection.rules[0].origin, format!("stylesheet #{weight}"));
+        assert_eq!(inspection.rules[0].location.line, 3);
+        assert_eq!(inspection.properties[1], "width: 20px  (stylesheet #1:3:13)");
+    }
+}
@@ -0,0 +1,196 @@
+use crate::common::Label;
+use belly_core::build::*;
+use belly_core::metrics;
+use belly_macro::*;
+use bevy::{
+    diagnostic::{
+        DiagnosticPath, DiagnosticsStore, EntityCountDiagnosticsPlugin,
+        FrameTimeDiagnosticsPlugin,
+    },
+    prelude::*,
+};
+
+pub mod prelude {
+    pub use super::DiagnosticsPanel;
+    pub use super::DiagnosticsWidgetExtension;
+}
+
+pub(crate) struct DiagnosticsPlugin;
+impl Plugin for DiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_widget::<DiagnosticsWidget>();
+        app.add_systems(Update, update_diagnostics_system);
+    }
+}
+
+/// Resolves the metric name used by the `metrics` param into the path of
+/// the diagnostic. The unknown names are used as the paths as is.
+pub fn metric_path(name: &str) -> DiagnosticPath {
+    match name {
+        "fps" => FrameTimeDiagnosticsPlugin::FPS,
+        "frame-time" => FrameTimeDiagnosticsPlugin::FRAME_TIME,
+        "entities" => EntityCountDiagnosticsPlugin::ENTITY_COUNT,
+        "invalidated" => metrics::INVALIDATED_ELEMENTS,
+        "selectors" => metrics::SELECTOR_MATCHES,
+        "relations" => metrics::RELATIONS_ITERATIONS,
+        path => DiagnosticPath::new(path.to_string()),
+    }
+}
+
+/// Formats the smoothed value of the diagnostic with its suffix, `-` is
+/// returned when there is no such diagnostic or it has no measurements yet.
+pub fn format_metric(store: &DiagnosticsStore, path: &DiagnosticPath) -> String {
+    let Some(diagnostic) = store.get(path) else {
+        return "-".to_string();
+    };
+    match diagnostic.smoothed() {
+        Some(value) if value.fract() == 0. => format!("{value}{}", diagnostic.suffix),
+        Some(value) => format!("{value:.1}{}", diagnostic.suffix),
+        None => "-".to_string(),
+    }
+}
+
+struct MetricRow {
+    name: String,
+    path: DiagnosticPath,
+    value: Entity,
+}
+
+#[derive(Component)]
+/// The state of the `<diagnostics>` widget: the displayed metrics and the
+/// update throttle.
+pub struct DiagnosticsPanel {
+    /// The interval between the updates of the values, in seconds
+    pub interval: f32,
+    elapsed: f32,
+    rows: Vec<MetricRow>,
+}
+
+impl FromWorldAndParams for DiagnosticsPanel {
+    fn from_world_and_params(world: &mut World, params: &mut belly_core::eml::Params) -> Self {
+        let metrics: String = params
+            .try_get("metrics")
+            .unwrap_or_else(|| "fps frame-time".to_string());
+        DiagnosticsPanel {
+            interval: params.try_get("interval").unwrap_or(0.5),
+            elapsed: f32::INFINITY,
+            rows: metrics
+                .split_whitespace()
+                .map(|name| MetricRow {
+                    name: name.to_string(),
+                    path: metric_path(name),
+                    value: world.spawn_empty().id(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl DiagnosticsPanel {
+    /// The names of the displayed metrics
+    pub fn metrics(&self) -> impl Iterator<Item = &str> {
+        self.rows.iter().map(|row| row.name.as_str())
+    }
+}
+
+#[widget]
+#[param(interval: f32 => DiagnosticsPanel:interval)]
+#[styles = DIAGNOSTICS_STYLES]
+/// The `<diagnostics>` shows the values of the bevy `DiagnosticsStore`.
+/// The `metrics` param is the whitespace separated list of the displayed
+/// metrics, read once when the widget is built (`fps frame-time` by
+/// default). The `fps`, `frame-time` and `entities` names are resolved to
+/// the bevy diagnostics (add the `FrameTimeDiagnosticsPlugin` and the
+/// `EntityCountDiagnosticsPlugin`), the `invalidated`, `selectors` and
+/// `relations` names to the `belly` metrics, any other name is used as
+/// the diagnostic path (`belly/apply/color`). The values are updated
+/// every `interval` seconds (`0.5` by default).
+fn diagnostics(ctx: &mut WidgetContext, panel: &mut DiagnosticsPanel) {
+    let rows: Vec<_> = panel
+        .rows
+        .iter()
+        .map(|row| (row.name.clone(), row.value))
+        .collect();
+    ctx.render(eml! {
+        <span c:diagnostics>
+            <for row in=rows>
+                <span c:diagnostics-row>
+                    <label c:diagnostics-name value=row.0.clone()/>
+                    <label entity=row.1 c:diagnostics-value value="-"/>
+                </span>
+            </for>
+        </span>
+    })
+}
+
+ess_define! {
+    DIAGNOSTICS_STYLES,
+    diagnostics {
+        position-type: absolute;
+        right: 4px;
+        top: 4px;
+        padding: 4px;
+        flex-direction: column;
+        min-width: 120px;
+        background-color: #1e1e1ec0;
+    }
+    .diagnostics-row {
+        justify-content: space-between;
+    }
+    .diagnostics-name {
+        font-size: 12px;
+        color: #9e9e9e;
+        margin-right: 8px;
+    }
+    .diagnostics-value {
+        font-size: 12px;
+        color: #ffffff;
+    }
+}
+
+fn update_diagnostics_system(
+    time: Res<Time<Real>>,
+    store: Option<Res<DiagnosticsStore>>,
+    mut panels: Query<&mut DiagnosticsPanel>,
+    mut labels: Query<&mut Label>,
+) {
+    let Some(store) = store else {
+        return;
+    };
+    for mut panel in panels.iter_mut() {
+        let elapsed = panel.elapsed + time.delta_seconds();
+        if elapsed < panel.interval {
+            panel.elapsed = elapsed;
+            continue;
+        }
+        panel.elapsed = 0.;
+        for row in panel.rows.iter() {
+            let Ok(mut label) = labels.get_mut(row.value) else {
+                continue;
+            };
+            let value = format_metric(&store, &row.path);
+            if label.value != value {
+                label.value = value;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use belly_core::metrics::MetricsPlugin;
+
+    #[test]
+    fn format_diagnostics_metrics() {
+        let mut app = App::new();
+        app.add_plugins(MetricsPlugin);
+        app.world.spawn(belly_core::element::Element::default());
+        app.world.spawn(belly_core::element::Element::default());
+        app.update();
+        let store = app.world.resource::<DiagnosticsStore>();
+        assert_eq!(format_metric(store, &metric_path("invalidated")), "2");
+        assert_eq!(format_metric(store, &metric_path("fps")), "-");
+        assert_eq!(metric_path("belly/apply/color"), metrics::property_apply_time("color".into()));
+    }
+}
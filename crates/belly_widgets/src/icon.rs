@@ -0,0 +1,399 @@
+use belly_core::build::*;
+use belly_macro::*;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+pub mod prelude {
+    pub use super::Icon;
+    pub use super::IconGlyph;
+    pub use super::IconSet;
+    pub use super::IconSets;
+    pub use super::IconWidgetExtension;
+    pub use super::RegisterIconSet;
+}
+
+pub(crate) struct IconPlugin;
+impl Plugin for IconPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<IconSets>();
+        app.register_widget::<IconWidget>();
+        app.register_type::<Icon>();
+        app.add_systems(Update, update_icons_system);
+    }
+}
+
+#[widget]
+/// Specifies the icon: the `name` is looked up in all registered sets
+/// (the sets registered later go first), the `set:name` uses the exact set
+#[param(name: String => Icon:name)]
+/// Specifies the size of the icon in pixels, `0` follows the `font-size`
+#[param(size: f32 => Icon:size)]
+/// Specifies the color of the icon, the transparent one follows the `color`
+#[param(color: Color => Icon:color)]
+/// The `<icon>` shows the icon from the registered icon font or atlas:
+/// `<icon name="fa:gear" size=32/>`. The icon is the text element, so by
+/// default it takes the `font-size` and the `color` from the styles like
+/// the labels do: `button:hover icon { color: white }`. The built-in
+/// `default` set uses the glyphs of the default font: `close`, `plus`,
+/// `minus`, `more`, `bullet`, `chevron-left`, `chevron-right`,
+/// `chevrons-left` and `chevrons-right`.
+fn icon(ctx: &mut WidgetContext) {
+    ctx.insert(TextElementBundle::default());
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+/// The state of the `<icon>`
+pub struct Icon {
+    pub name: String,
+    /// The size of the icon, `0` follows the `font-size`
+    pub size: f32,
+    /// The color of the icon, the transparent one follows the `color`
+    pub color: Color,
+}
+
+impl Default for Icon {
+    fn default() -> Self {
+        Icon {
+            name: String::new(),
+            size: 0.,
+            color: Color::NONE,
+        }
+    }
+}
+
+/// The source of the icons
+#[derive(Clone, Debug)]
+pub enum IconSet {
+    /// The glyphs of the font, the `None` font uses the font of the element
+    Font {
+        font: Option<String>,
+        glyphs: HashMap<String, char>,
+    },
+    /// The cells of the grid image, numbered row by row
+    Atlas {
+        image: String,
+        tile: Vec2,
+        columns: usize,
+        icons: HashMap<String, usize>,
+    },
+}
+
+impl IconSet {
+    fn glyph<'a>(&'a self, set: &'a str, name: &str) -> Option<IconGlyph<'a>> {
+        match self {
+            IconSet::Font { font, glyphs } => glyphs.get(name).map(|glyph| IconGlyph::Char {
+                font: font.as_deref(),
+                glyph: *glyph,
+            }),
+            IconSet::Atlas {
+                image,
+                tile,
+                columns,
+                icons,
+            } => icons.get(name).map(|index| IconGlyph::Cell {
+                set,
+                image,
+                tile: *tile,
+                columns: *columns,
+                rows: (icons.values().max().unwrap_or(&0) + 1).div_ceil(*columns),
+                index: *index,
+            }),
+        }
+    }
+}
+
+/// The resolved icon
+#[derive(Clone, Debug, PartialEq)]
+pub enum IconGlyph<'a> {
+    Char {
+        font: Option<&'a str>,
+        glyph: char,
+    },
+    Cell {
+        set: &'a str,
+        image: &'a str,
+        tile: Vec2,
+        columns: usize,
+        rows: usize,
+        index: usize,
+    },
+}
+
+/// The registered icon sets, see [`RegisterIconSet`]
+#[derive(Resource)]
+pub struct IconSets(Vec<(String, IconSet)>);
+
+impl Default for IconSets {
+    fn default() -> Self {
+        let glyphs = [
+            ("close", '×'),
+            ("plus", '+'),
+            ("minus", '−'),
+            ("more", '…'),
+            ("bullet", '•'),
+            ("chevron-left", '‹'),
+            ("chevron-right", '›'),
+            ("chevrons-left", '«'),
+            ("chevrons-right", '»'),
+        ];
+        let glyphs = glyphs.iter().map(|(n, g)| (n.to_string(), *g)).collect();
+        IconSets(vec![(
+            "default".to_string(),
+            IconSet::Font { font: None, glyphs },
+        )])
+    }
+}
+
+impl IconSets {
+    /// Adds the set `name`, replacing the previously registered one
+    pub fn insert(&mut self, name: &str, set: IconSet) {
+        self.0.retain(|(n, _)| n != name);
+        self.0.push((name.to_string(), set));
+    }
+
+    /// Resolves the `name` or the `set:name` icon
+    pub fn find(&self, name: &str) -> Option<IconGlyph> {
+        match name.split_once(':') {
+            Some((set, name)) => self
+                .0
+                .iter()
+                .find(|(n, _)| n == set)
+                .and_then(|(n, s)| s.glyph(n, name)),
+            None => self.0.iter().rev().find_map(|(n, s)| s.glyph(n, name)),
+        }
+    }
+}
+
+pub trait RegisterIconSet {
+    /// Registers the icon font `name`, the glyphs map the icon names to
+    /// the chars of the font:
+    /// ```rust,ignore
+    /// app.register_icon_font(
+    ///     "fa",
+    ///     "fonts/fa-solid-900.ttf",
+    ///     &[("gear", '\u{f013}'), ("house", '\u{f015}')],
+    /// );
+    /// ```
+    fn register_icon_font(
+        &mut self,
+        name: &str,
+        font: &str,
+        glyphs: &[(&str, char)],
+    ) -> &mut Self;
+
+    /// Registers the icon atlas `name`: the image is split to the `tile`
+    /// sized cells, `columns` per row, the `icons` name the cells row by row
+    fn register_icon_atlas(
+        &mut self,
+        name: &str,
+        image: &str,
+        tile: Vec2,
+        columns: usize,
+        icons: &[&str],
+    ) -> &mut Self;
+}
+
+impl RegisterIconSet for App {
+    fn register_icon_font(
+        &mut self,
+        name: &str,
+        font: &str,
+        glyphs: &[(&str, char)],
+    ) -> &mut Self {
+        let glyphs = glyphs.iter().map(|(n, g)| (n.to_string(), *g)).collect();
+        self.world.get_resource_or_insert_with(IconSets::default).insert(
+            name,
+            IconSet::Font {
+                font: Some(font.to_string()),
+                glyphs,
+            },
+        );
+        self
+    }
+
+    fn register_icon_atlas(
+        &mut self,
+        name: &str,
+        image: &str,
+        tile: Vec2,
+        columns: usize,
+        icons: &[&str],
+    ) -> &mut Self {
+        let icons = icons.iter().enumerate().map(|(i, n)| (n.to_string(), i)).collect();
+        self.world.get_resource_or_insert_with(IconSets::default).insert(
+            name,
+            IconSet::Atlas {
+                image: image.to_string(),
+                tile,
+                columns: columns.max(1),
+                icons,
+            },
+        );
+        self
+    }
+}
+
+/// Shows the glyph in the text of the icon or the cell of the atlas as
+/// its image. The atlas icons are sized explicitly and tinted with the
+/// color of the text.
+#[allow(clippy::type_complexity)]
+fn update_icons_system(
+    mut commands: Commands,
+    sets: Res<IconSets>,
+    assets: Res<AssetServer>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut cache: Local<HashMap<String, Handle<TextureAtlasLayout>>>,
+    mut icons: Query<(
+        Entity,
+        Ref<Icon>,
+        &mut Text,
+        &mut Style,
+        &mut BackgroundColor,
+        Option<(&mut UiImage, &mut TextureAtlas)>,
+    )>,
+) {
+    if sets.is_changed() {
+        cache.clear();
+    }
+    for (entity, icon, mut text, mut style, mut background, atlas) in icons.iter_mut() {
+        if !sets.is_changed() && !icon.is_changed() && !text.is_changed() {
+            continue;
+        }
+        let Some(section) = text.sections.first() else {
+            continue;
+        };
+        let current = (section.value.clone(), section.style.clone());
+        let size = match icon.size > 0. {
+            true => icon.size,
+            false => section.style.font_size,
+        };
+        let color = match icon.color.a() > 0. {
+            true => icon.color,
+            false => section.style.color,
+        };
+        let glyph = sets.find(&icon.name);
+        if glyph.is_none() && icon.is_changed() {
+            warn!("Unknown icon `{}`", icon.name);
+        }
+        let value = match glyph {
+            Some(IconGlyph::Char { glyph, .. }) => glyph.to_string(),
+            _ => String::new(),
+        };
+        let font = match glyph {
+            Some(IconGlyph::Char {
+                font: Some(font), ..
+            }) => Some(assets.load::<Font>(font.to_string())),
+            _ => None,
+        };
+        let font_differs = font.as_ref().is_some_and(|f| &current.1.font != f);
+        if current.0 != value
+            || font_differs
+            || current.1.font_size != size
+            || current.1.color != color
+        {
+            let section = &mut text.sections[0];
+            section.value = value;
+            section.style.font_size = size;
+            section.style.color = color;
+            if let Some(font) = font {
+                section.style.font = font;
+            }
+        }
+        let Some(IconGlyph::Cell {
+            set,
+            image,
+            tile,
+            columns,
+            rows,
+            index,
+        }) = glyph
+        else {
+            if atlas.is_some() {
+                commands.entity(entity).remove::<(UiImage, TextureAtlas)>();
+                background.0 = Color::NONE;
+                style.width = Val::Auto;
+                style.height = Val::Auto;
+            }
+            continue;
+        };
+        let layout = cache
+            .entry(set.to_string())
+            .or_insert_with(|| {
+                layouts.add(TextureAtlasLayout::from_grid(tile, columns, rows, None, None))
+            })
+            .clone();
+        let texture = assets.load::<Image>(image.to_string());
+        match atlas {
+            Some((mut ui_image, mut atlas)) => {
+                if ui_image.texture != texture {
+                    ui_image.texture = texture;
+                }
+                if atlas.layout != layout || atlas.index != index {
+                    atlas.layout = layout;
+                    atlas.index = index;
+                }
+            }
+            None => {
+                commands
+                    .entity(entity)
+                    .insert((UiImage::new(texture), TextureAtlas { layout, index }));
+            }
+        }
+        if background.0 != color {
+            background.0 = color;
+        }
+        if style.width != Val::Px(size) || style.height != Val::Px(size) {
+            style.width = Val::Px(size);
+            style.height = Val::Px(size);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn find_icons_in_sets() {
+        let mut sets = IconSets::default();
+        let glyphs = [("close".to_string(), '\u{f00d}')].into_iter().collect();
+        sets.insert(
+            "fa",
+            IconSet::Font {
+                font: Some("fa.ttf".to_string()),
+                glyphs,
+            },
+        );
+        let icons = [("gear".to_string(), 0), ("home".to_string(), 3)];
+        sets.insert(
+            "ui",
+            IconSet::Atlas {
+                image: "icons.png".to_string(),
+                tile: Vec2::splat(16.),
+                columns: 2,
+                icons: icons.into_iter().collect(),
+            },
+        );
+        let fa_close = IconGlyph::Char {
+            font: Some("fa.ttf"),
+            glyph: '\u{f00d}',
+        };
+        assert_eq!(sets.find("close"), Some(fa_close));
+        let default_close = IconGlyph::Char {
+            font: None,
+            glyph: '×',
+        };
+        assert_eq!(sets.find("default:close"), Some(default_close));
+        let home = IconGlyph::Cell {
+            set: "ui",
+            image: "icons.png",
+            tile: Vec2::splat(16.),
+            columns: 2,
+            rows: 2,
+            index: 3,
+        };
+        assert_eq!(sets.find("home"), Some(home));
+        assert_eq!(sets.find("fa:home"), None);
+    }
+}
@@ -1,6 +1,7 @@
 use belly_core::build::*;
 use belly_macro::*;
 
+use bevy::asset::LoadState;
 use bevy::prelude::*;
 use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
@@ -16,11 +17,19 @@ pub(crate) struct ImgPlugin;
 impl Plugin for ImgPlugin {
     fn build(&self, app: &mut App) {
         app.register_widget::<ImgWidget>();
+        app.register_widget_states::<Img>();
 
         app.init_resource::<ImageRegistry>();
         app.add_systems(
             Update,
-            (load_img, update_img_size, update_img_layout).chain(),
+            (
+                load_img,
+                update_img_size,
+                detect_img_failures,
+                update_img_layout,
+                sync_img_loading,
+            )
+                .chain(),
         );
         app.add_event::<ImgEvent>();
     }
@@ -29,13 +38,24 @@ impl Plugin for ImgPlugin {
 #[widget]
 #[signal(load:ImgEvent => img_loaded)]
 #[signal(unload:ImgEvent => img_unloaded)]
+#[signal(error:ImgEvent => img_failed)]
 /// Specifies the path to the image or custom `Handle<Image>`
 #[param( src: ImageSource => Img:src )]
 /// <!-- @inline ImgMode -->
 #[param( mode: ImgMode => Img:mode )]
+/// The alias for the `mode` param
+#[param( fit: ImgMode => Img:mode )]
 /// Specifies the color the image should be multiplied
 #[param( modulate: Color => Img:modulate )]
+/// Flips the image horizontally
+#[param( flip_x: bool => Img:flip_x )]
+/// Flips the image vertically
+#[param( flip_y: bool => Img:flip_y )]
 /// The `<img>` is used to load image and show it content on the UI screen.
+/// While the image is loading the element has the `:loading` state, if
+/// the image fails to load the element gets the `:error` state and emits
+/// the `error` signal, so the placeholders can react:
+/// `img:loading { background-color: #3f3f3f }`
 fn img(ctx: &mut WidgetContext, img: &mut Img) {
     let this = ctx.entity();
     let content = ctx.content();
@@ -48,6 +68,7 @@ fn img(ctx: &mut WidgetContext, img: &mut Img) {
         ..default()
     });
     ctx.insert(ElementBundle::default())
+        .insert(Loading::default())
         .push_children(&[img.entity]);
     ctx.commands().entity(img.entity).push_children(&content);
 }
@@ -59,21 +80,20 @@ struct ImageRegistry(HashMap<Handle<Image>, HashSet<Entity>>);
 pub enum ImgEvent {
     Loaded(Vec<Entity>),
     Unloaded(Vec<Entity>),
+    Failed(Vec<Entity>),
 }
 
 impl ImgEvent {
     pub fn loaded(&self) -> bool {
-        match self {
-            ImgEvent::Loaded(_) => true,
-            ImgEvent::Unloaded(_) => false,
-        }
+        matches!(self, ImgEvent::Loaded(_))
     }
 
     pub fn unloaded(&self) -> bool {
-        match self {
-            ImgEvent::Loaded(_) => false,
-            ImgEvent::Unloaded(_) => true,
-        }
+        matches!(self, ImgEvent::Unloaded(_))
+    }
+
+    pub fn failed(&self) -> bool {
+        matches!(self, ImgEvent::Failed(_))
     }
 }
 
@@ -89,13 +109,19 @@ fn img_unloaded(event: &ImgEvent) -> EventSource {
         _ => EventSource::none(),
     }
 }
+fn img_failed(event: &ImgEvent) -> EventSource {
+    match event {
+        ImgEvent::Failed(entities) => EventSource::vec(entities),
+        _ => EventSource::none(),
+    }
+}
 
 #[derive(Default, Clone, Copy, PartialEq, Debug)]
 /// Specifies how an image should fits the space:
-/// - `fit`: resize the image to fit the box keeping it aspect ratio
+/// - `fit` (or `contain`): resize the image to fit the box keeping it aspect ratio
 /// - `cover`: resize the image to cover the box keeping it aspect ratio
-/// - `stretch`: resize image to take all the space ignoring the aspect ratio
-/// - `source`: keep image at original size
+/// - `stretch` (or `fill`): resize image to take all the space ignoring the aspect ratio
+/// - `source` (or `none`): keep image at original size
 pub enum ImgMode {
     #[default]
     Fit,
@@ -109,10 +135,10 @@ impl FromStr for ImgMode {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "" => Ok(ImgMode::Fit),
-            "fit" => Ok(ImgMode::Fit),
+            "fit" | "contain" => Ok(ImgMode::Fit),
             "cover" => Ok(ImgMode::Cover),
-            "stretch" => Ok(ImgMode::Stretch),
-            "source" => Ok(ImgMode::Source),
+            "stretch" | "fill" => Ok(ImgMode::Stretch),
+            "source" | "none" => Ok(ImgMode::Source),
             err => Err(format!("Can't parse `{}` as ImgMode", err)),
         }
     }
@@ -201,9 +227,30 @@ pub struct Img {
     pub src: AssetSource<Image>,
     pub mode: ImgMode,
     pub modulate: Color,
+    pub flip_x: bool,
+    pub flip_y: bool,
     handle: Handle<Image>,
     entity: Entity,
     size: Vec2,
+    failed: bool,
+}
+
+impl Img {
+    /// The image source is set, but the asset is not loaded yet.
+    pub fn loading(&self) -> bool {
+        self.handle != Handle::default() && self.size == Vec2::ZERO && !self.failed
+    }
+
+    /// The image asset failed to load.
+    pub fn failed(&self) -> bool {
+        self.failed
+    }
+}
+
+impl WidgetStates for Img {
+    fn states(&self) -> Vec<(Tag, bool)> {
+        vec![(Tag::new("error"), self.failed)]
+    }
 }
 
 impl FromWorldAndParams for Img {
@@ -212,9 +259,12 @@ impl FromWorldAndParams for Img {
             src: params.try_get("src").unwrap_or_default(),
             mode: params.try_get("mode").unwrap_or_default(),
             modulate: params.try_get("modulate").unwrap_or_default(),
+            flip_x: params.try_get("flip-x").unwrap_or_default(),
+            flip_y: params.try_get("flip-y").unwrap_or_default(),
             handle: Default::default(),
             entity: world.spawn_empty().id(),
             size: Default::default(),
+            failed: false,
         }
     }
 }
@@ -247,6 +297,7 @@ fn load_img(
                 .or_default()
                 .insert(entity);
             img.handle = handle.clone();
+            img.failed = false;
         }
         if let Some(asset) = assets.get(&img.handle) {
             let asset_size = Vec2::new(asset.size().x as f32, asset.size().y as f32);
@@ -257,6 +308,8 @@ fn load_img(
         }
         let (mut image, mut style) = images.get_mut(img.entity).unwrap();
         image.texture = handle.clone();
+        image.flip_x = img.flip_x;
+        image.flip_y = img.flip_y;
 
         // force inner image size recalculation if Image asset already loaded
         if assets.contains(&handle) {
@@ -320,6 +373,35 @@ fn update_img_size(
     }
 }
 
+/// Toggles the [`Loading`] of the images, so the `:loading` state (and the
+/// placeholders depending on it) follow the image asset
+fn sync_img_loading(mut elements: Query<(&Img, &mut Loading), Changed<Img>>) {
+    for (img, mut loading) in elements.iter_mut() {
+        if loading.value != img.loading() {
+            loading.value = img.loading();
+        }
+    }
+}
+
+fn detect_img_failures(
+    asset_server: Res<AssetServer>,
+    mut elements: Query<(Entity, &mut Img)>,
+    mut signals: EventWriter<ImgEvent>,
+) {
+    let failed: Vec<_> = elements
+        .iter_mut()
+        .filter(|(_, img)| img.loading())
+        .filter(|(_, img)| asset_server.get_load_state(img.handle.id()) == Some(LoadState::Failed))
+        .map(|(entity, mut img)| {
+            img.failed = true;
+            entity
+        })
+        .collect();
+    if !failed.is_empty() {
+        signals.send(ImgEvent::Failed(failed));
+    }
+}
+
 fn update_img_layout(
     elements: Query<(&Img, &Node), Or<(Changed<Img>, Changed<Node>)>>,
     mut styles: Query<&mut Style>,
@@ -431,3 +513,17 @@ fn update_img_layout(
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_img_mode_aliases() {
+        assert_eq!("contain".parse(), Ok(ImgMode::Fit));
+        assert_eq!("cover".parse(), Ok(ImgMode::Cover));
+        assert_eq!("fill".parse(), Ok(ImgMode::Stretch));
+        assert_eq!("none".parse(), Ok(ImgMode::Source));
+        assert!("scale-down".parse::<ImgMode>().is_err());
+    }
+}
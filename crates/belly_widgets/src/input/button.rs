@@ -29,8 +29,10 @@ impl Plugin for ButtonPlugin {
         app.init_resource::<BtnGroups>();
         app.register_widget::<ButtonWidget>();
         app.register_widget::<ButtongroupWidget>();
+        app.register_widget_states::<Btn>();
         app.add_systems(Update, process_btngroups_system);
         app.add_systems(Update, force_btngroups_reconfiguration_system);
+        app.add_systems(Update, button_sounds_system);
         app.add_systems(
             PreUpdate,
             (
@@ -50,6 +52,7 @@ impl Plugin for ButtonPlugin {
 /// corresponding button will become pressed, and all other buttons in the
 /// group will have their pressed state removed.
 #[param(value: String => BtnGroup:value)]
+#[styles = BUTTONGROUP_STYLES]
 /// A container for multiple toggle buttons. When a button inside a
 /// `<buttongroup>` is clicked, it will toggle its pressed state and emit the
 /// `pressed` and `released` signals as appropriate. The `<buttongroup>` will
@@ -68,6 +71,7 @@ fn buttongroup(ctx: &mut WidgetContext) {
 #[widget]
 #[signal(press:BtnEvent => button_pressed)]
 #[signal(release:BtnEvent => button_released)]
+#[signal(toggle:BtnEvent => button_toggled)]
 /// if button is pressed or not
 #[param(pressed:bool => Btn:pressed)]
 /// <!-- @inline BtnMode -->
@@ -75,11 +79,16 @@ fn buttongroup(ctx: &mut WidgetContext) {
 /// Specifies the `<button>` value passed to parent `<buttongroup>`
 /// when this button becomes pressed.
 #[param(value:String => Btn:value)]
+/// The face of the button
+#[part(background)]
+/// The content holder inside the `background`
+#[part(foreground)]
 #[styles = BUTTON_STYLES]
 /// The `<button>` tag defines a clickable button.
 /// Inside a `<button>` widget you can put text (and tags
 /// like `<strong>`, `<img>`, etc.)
-/// A button can emit `pressed` and `released` signals.
+/// A button can emit `press` and `release` signals, the `toggle` mode
+/// buttons emit the `toggle` signal each time their pressed state flips.
 /// The button behaviour is defined by the `mode` param.
 /// When changing its pressed state, button adds `:pressed` ess
 /// state to element if it pressed and remove `:pressed` if it releases.
@@ -96,8 +105,8 @@ fn button(ctx: &mut WidgetContext) {
         eml! {
             <span c:button interactable>
                 <span c:button-shadow s:position-type="absolute"/>
-                <span c:button-background>
-                    <span c:button-foreground>
+                <span part="background" c:button-background>
+                    <span part="foreground" c:button-foreground>
                         {content}
                     </span>
                 </span>
@@ -106,6 +115,17 @@ fn button(ctx: &mut WidgetContext) {
     });
 }
 
+ess_define! {
+    BUTTONGROUP_STYLES,
+    buttongroup {
+        flex-direction: row;
+        align-items: center;
+    }
+    buttongroup button {
+        margin: 5px 1px;
+    }
+}
+
 ess_define! {
     BUTTON_STYLES,
     button {
@@ -126,6 +146,9 @@ ess_define! {
     button:pressed > span > .button-foreground {
         background-color: #bfbfbf;
     }
+    button:disabled {
+        opacity: 0.5;
+    }
     .button-shadow {
         background-color: #4f4f4fb8;
         top: 1px;
@@ -155,10 +178,12 @@ ess_define! {
     }
 }
 
-#[derive(Event)]
+#[derive(Event, Clone, Debug)]
 pub enum BtnEvent {
     Pressed(Entity),
     Released(Entity),
+    /// The `toggle` mode button flipped its pressed state
+    Toggled(Entity),
 }
 impl BtnEvent {
     pub fn pressed(&self) -> bool {
@@ -173,6 +198,12 @@ impl BtnEvent {
             _ => false,
         }
     }
+    pub fn toggled(&self) -> bool {
+        match self {
+            BtnEvent::Toggled(_) => true,
+            _ => false,
+        }
+    }
 }
 
 fn button_pressed(event: &BtnEvent) -> EventSource {
@@ -189,7 +220,14 @@ fn button_released(event: &BtnEvent) -> EventSource {
     }
 }
 
-#[derive(Event)]
+fn button_toggled(event: &BtnEvent) -> EventSource {
+    match event {
+        BtnEvent::Toggled(entity) => EventSource::single(*entity),
+        _ => EventSource::none(),
+    }
+}
+
+#[derive(Event, Clone, Debug)]
 pub struct ValueChanged<T> {
     entity: Entity,
     old_value: T,
@@ -404,6 +442,14 @@ pub struct Btn {
     pub value: String,
 }
 
+impl WidgetStates for Btn {
+    fn states(&self) -> Vec<(Tag, bool)> {
+        // `press` buttons are pressed for a single frame,
+        // it is not a state worth styling
+        vec![(tags::pressed(), self.pressed && self.mode != BtnMode::Press)]
+    }
+}
+
 #[derive(Component, Default)]
 pub struct BtnGroup {
     pub value: String,
@@ -500,11 +546,16 @@ fn handle_input_system(
     mut state_changes: Local<HashMap<BtnModeGroup, (Entity, String)>>,
     mut repeat_state: Local<RepeatState>,
     mut instant_pressed: Local<HashSet<Entity>>,
-    time: Res<Time>,
+    time: ElementsTime,
 ) {
     state_changes.clear();
 
-    if let Some(entity) = repeat_state.hits(time.delta_seconds()) {
+    let delta = repeat_state
+        .button
+        .as_ref()
+        .map(|(entity, _)| time.delta_seconds(*entity))
+        .unwrap_or_default();
+    if let Some(entity) = repeat_state.hits(delta) {
         button_events.send(BtnEvent::Pressed(entity));
     }
 
@@ -569,6 +620,7 @@ fn handle_input_system(
                         btn.pressed = true;
                         button_events.send(BtnEvent::Pressed(*entity));
                     }
+                    button_events.send(BtnEvent::Toggled(*entity));
                 }
                 (BtnMode::Group(group), PointerInputData::Pressed { presses: _ }) => {
                     if !btn.pressed {
@@ -612,18 +664,12 @@ fn handle_input_system(
 
 fn handle_states_system(
     mut groups: ResMut<BtnGroups>,
-    mut elements: Elements,
     mut buttons: Query<(Entity, &mut Btn), Changed<Btn>>,
     mut drop_pressed: Local<HashSet<Entity>>,
     mut button_events: EventWriter<BtnEvent>,
 ) {
     drop_pressed.clear();
     for (entity, mut btn) in buttons.iter_mut() {
-        match &btn.mode {
-            // BtnMode::Instant => elements.set_state(entity, tags::pressed(), false),
-            BtnMode::Press => elements.set_state(entity, tags::pressed(), false),
-            _ => elements.set_state(entity, tags::pressed(), btn.pressed),
-        }
         if let BtnMode::Group(group) = &btn.mode {
             if let Some(state) = groups.get_mut(group) {
                 if !state.buttons.contains(&entity) {
@@ -638,7 +684,6 @@ fn handle_states_system(
                     btn.pressed = true;
                     button_events.send(BtnEvent::Pressed(entity));
                 }
-                elements.set_state(entity, tags::pressed(), true);
             }
         }
     }
@@ -648,7 +693,45 @@ fn handle_states_system(
                 btn.pressed = false;
                 button_events.send(BtnEvent::Released(entity));
             }
-            elements.set_state(entity, tags::pressed(), false);
+        }
+    }
+}
+
+/// Requests the `hover` sound when the button gets hovered and the `press`
+/// sound when the button is pressed. The toggle buttons play the `toggle`
+/// sound instead, when their `:pressed` state changes.
+fn button_sounds_system(
+    mut button_events: EventReader<BtnEvent>,
+    buttons: Query<(Entity, &Btn, Ref<Element>)>,
+    mut hovered: Local<HashSet<Entity>>,
+    mut sounds: EventWriter<PlayUiSound>,
+) {
+    for event in button_events.read() {
+        let BtnEvent::Pressed(entity) = event else {
+            continue;
+        };
+        let Ok((_, btn, _)) = buttons.get(*entity) else {
+            continue;
+        };
+        if !matches!(btn.mode, BtnMode::Toggle | BtnMode::Group(_)) {
+            sounds.send(PlayUiSound {
+                entity: *entity,
+                sound: UiSound::Press,
+            });
+        }
+    }
+    hovered.retain(|entity| buttons.contains(*entity));
+    for (entity, _, element) in buttons.iter() {
+        if !element.is_changed() {
+            continue;
+        }
+        if !element.hovered() {
+            hovered.remove(&entity);
+        } else if hovered.insert(entity) {
+            sounds.send(PlayUiSound {
+                entity,
+                sound: UiSound::Hover,
+            });
         }
     }
 }
@@ -1,4 +1,5 @@
 pub mod button;
+pub mod scrollbar;
 pub mod slider;
 pub mod text;
 
@@ -6,6 +7,7 @@ use bevy::prelude::Plugin;
 
 pub mod prelude {
     pub use super::button::prelude::*;
+    pub use super::scrollbar::prelude::*;
     pub use super::slider::prelude::*;
     pub use super::text::prelude::*;
 }
@@ -14,6 +16,7 @@ pub struct InputPlugins;
 impl Plugin for InputPlugins {
     fn build(&self, app: &mut bevy::prelude::App) {
         app.add_plugins(button::ButtonPlugin);
+        app.add_plugins(scrollbar::ScrollbarPlugin);
         app.add_plugins(slider::SliderPlugin);
         app.add_plugins(text::TextInputPlugin);
     }
@@ -0,0 +1,277 @@
+use crate::range::LayoutMode;
+use belly_core::build::*;
+use belly_core::input;
+use belly_macro::*;
+use bevy::prelude::*;
+
+pub mod prelude {
+    pub use super::Scrollbar;
+    pub use super::ScrollbarWidgetExtension;
+}
+
+pub(crate) struct ScrollbarPlugin;
+impl Plugin for ScrollbarPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            handle_scrollbar_input.in_set(input::InputSystemsSet),
+        );
+        app.register_widget::<ScrollbarWidget>();
+        app.register_widget_states::<Scrollbar>();
+        app.add_systems(Update, update_scrollbar_representation);
+    }
+}
+
+#[widget]
+#[styles = SCROLLBAR_STYLES]
+/// Specifies the scroll position, from `0` (the start) to `1` (the end)
+#[param(value: f32 => Scrollbar:value)]
+/// Specifies the visible part of the content, from `0` to `1`, the thumb
+/// takes the same part of the track
+#[param(page_size: f32 => Scrollbar:page_size)]
+/// <!-- @inline LayoutMode -->
+#[param(mode: LayoutMode => Scrollbar:mode)]
+/// The background of the scrollbar
+#[part(track)]
+/// The draggable thumb
+#[part(thumb)]
+/// The `<scrollbar>` shows and changes the scroll position: drag the thumb
+/// or press the track to move the position by the page. Bind the `value`
+/// and the `page-size` to any scrolled or panned content:
+/// `<scrollbar mode="vertical" bind:value=to!(map, Map:scroll)/>`.
+fn scrollbar(ctx: &mut WidgetContext, bar: &mut Scrollbar) {
+    let this = ctx.entity();
+    let track = bar.track;
+    let thumb = bar.thumb;
+    let grabber = ScrollbarThumb { scrollbar: this };
+    ctx.render(eml! {
+        <span c:scrollbar>
+            <span {track} part="track" c:scrollbar-track>
+                <span {thumb} with=grabber part="thumb" c:scrollbar-thumb
+                    s:left=managed()
+                    s:top=managed()
+                    s:width=managed()
+                    s:height=managed()/>
+            </span>
+        </span>
+    })
+}
+
+ess_define! {
+    SCROLLBAR_STYLES,
+    scrollbar:horizontal {
+        width: 100%;
+        height: 12px;
+    }
+    scrollbar:vertical {
+        width: 12px;
+        height: 100%;
+    }
+    scrollbar .scrollbar-track {
+        width: 100%;
+        height: 100%;
+        background-color: #2f2f2f7f;
+    }
+    scrollbar .scrollbar-thumb {
+        position-type: absolute;
+        background-color: #bfbfbf;
+    }
+    scrollbar .scrollbar-thumb:hover {
+        background-color: #dfdfdf;
+    }
+}
+
+#[derive(Component)]
+/// The state of the `<scrollbar>`
+pub struct Scrollbar {
+    /// The scroll position, from `0` (the start) to `1` (the end)
+    pub value: f32,
+    /// The visible part of the content, from `0` to `1`
+    pub page_size: f32,
+    pub mode: LayoutMode,
+    pub track: Entity,
+    pub thumb: Entity,
+}
+
+impl FromWorldAndParams for Scrollbar {
+    fn from_world_and_params(world: &mut World, params: &mut belly_core::eml::Params) -> Self {
+        Scrollbar {
+            value: params.try_get("value").unwrap_or_default(),
+            page_size: params.try_get("page-size").unwrap_or(0.25),
+            mode: params.try_get("mode").unwrap_or_default(),
+            track: world.spawn_empty().id(),
+            thumb: world.spawn_empty().id(),
+        }
+    }
+}
+
+impl WidgetStates for Scrollbar {
+    fn states(&self) -> Vec<(Tag, bool)> {
+        vec![
+            (Tag::new("horizontal"), self.mode == LayoutMode::Horizontal),
+            (Tag::new("vertical"), self.mode == LayoutMode::Vertical),
+        ]
+    }
+}
+
+impl Scrollbar {
+    /// The minimum size of the thumb, in pixels
+    pub const MIN_THUMB_SIZE: f32 = 16.;
+
+    /// Returns the offset and the size of the thumb along the `track` size
+    pub fn thumb_span(&self, track: f32) -> (f32, f32) {
+        let size = (track * self.page_size.clamp(0., 1.))
+            .max(Self::MIN_THUMB_SIZE)
+            .min(track);
+        let offset = (track - size) * self.value.clamp(0., 1.);
+        (offset, size)
+    }
+
+    /// Returns the scroll position of the thumb placed at the `offset`
+    /// along the `track` size
+    pub fn value_at(&self, track: f32, offset: f32) -> f32 {
+        let (_, size) = self.thumb_span(track);
+        if track <= size {
+            return 0.;
+        }
+        (offset / (track - size)).clamp(0., 1.)
+    }
+
+    /// Moves the position by the page towards the end (or the start when
+    /// the `pages` is negative)
+    pub fn scroll_pages(&mut self, pages: f32) {
+        self.value = (self.value + self.page_size * pages).clamp(0., 1.);
+    }
+
+    fn axis(&self, v: Vec2) -> f32 {
+        match self.mode {
+            LayoutMode::Horizontal => v.x,
+            LayoutMode::Vertical => v.y,
+        }
+    }
+}
+
+#[derive(Component)]
+struct ScrollbarThumb {
+    scrollbar: Entity,
+}
+
+fn update_scrollbar_representation(
+    scrollbars: Query<&Scrollbar, Or<(Changed<Scrollbar>, Changed<Node>)>>,
+    nodes: Query<&Node>,
+    mut styles: Query<&mut Style>,
+) {
+    for bar in scrollbars.iter() {
+        let Ok(track) = nodes.get(bar.track) else {
+            continue;
+        };
+        let Ok(mut style) = styles.get_mut(bar.thumb) else {
+            continue;
+        };
+        let (offset, size) = bar.thumb_span(bar.axis(track.size()));
+        let full = Val::Percent(100.);
+        let (left, top, width, height) = match bar.mode {
+            LayoutMode::Horizontal => (Val::Px(offset), Val::Px(0.), Val::Px(size), full),
+            LayoutMode::Vertical => (Val::Px(0.), Val::Px(offset), full, Val::Px(size)),
+        };
+        if style.left != left || style.top != top || style.width != width || style.height != height
+        {
+            style.left = left;
+            style.top = top;
+            style.width = width;
+            style.height = height;
+        }
+    }
+}
+
+fn handle_scrollbar_input(
+    mut events: EventReader<PointerInput>,
+    mut scrollbars: Query<&mut Scrollbar>,
+    thumbs: Query<&ScrollbarThumb>,
+    nodes: Query<(&GlobalTransform, &Node)>,
+    // the dragged thumb and the pointer offset from its start
+    mut active: Local<Option<(Entity, f32)>>,
+) {
+    for ev in events.read() {
+        if ev.drag_start() && active.is_none() {
+            let Some((entity, thumb)) = ev
+                .entities
+                .iter()
+                .find_map(|e| thumbs.get(*e).ok().map(|t| (*e, t)))
+            else {
+                continue;
+            };
+            let Ok(bar) = scrollbars.get(thumb.scrollbar) else {
+                continue;
+            };
+            let Ok((tr, node)) = nodes.get(entity) else {
+                continue;
+            };
+            let start = tr.translation().truncate() - node.size() * 0.5;
+            *active = Some((entity, bar.axis(ev.pos - start)));
+        } else if let Some((entity, grab)) = *active {
+            if !ev.dragging() && !ev.drag_stop() {
+                continue;
+            }
+            if ev.drag_stop() {
+                *active = None;
+            }
+            let Ok(thumb) = thumbs.get(entity) else {
+                continue;
+            };
+            let Ok(mut bar) = scrollbars.get_mut(thumb.scrollbar) else {
+                continue;
+            };
+            let Ok((tr, node)) = nodes.get(bar.track) else {
+                continue;
+            };
+            let start = tr.translation().truncate() - node.size() * 0.5;
+            let offset = bar.axis(ev.pos - start) - grab;
+            let value = bar.value_at(bar.axis(node.size()), offset);
+            if bar.value != value {
+                bar.value = value;
+            }
+        } else if ev.down() {
+            // pressing the track outside of the thumb pages towards the pointer
+            for mut bar in scrollbars.iter_mut() {
+                if !ev.contains(bar.track) || ev.contains(bar.thumb) {
+                    continue;
+                }
+                let Ok((tr, node)) = nodes.get(bar.thumb) else {
+                    continue;
+                };
+                let start = tr.translation().truncate() - node.size() * 0.5;
+                let pages = match bar.axis(ev.pos - start) < 0. {
+                    true => -1.,
+                    false => 1.,
+                };
+                bar.scroll_pages(pages);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn scrollbar_thumb_follows_value() {
+        let mut bar = Scrollbar {
+            value: 0.5,
+            page_size: 0.25,
+            mode: LayoutMode::Vertical,
+            track: Entity::PLACEHOLDER,
+            thumb: Entity::PLACEHOLDER,
+        };
+        assert_eq!(bar.thumb_span(200.), (75., 50.));
+        assert_eq!(bar.value_at(200., 150.), 1.);
+        assert_eq!(bar.value_at(200., 30.), 0.2);
+        bar.page_size = 0.01;
+        assert_eq!(bar.thumb_span(200.), (92., 16.));
+        bar.scroll_pages(-100.);
+        assert_eq!(bar.value, 0.);
+        bar.page_size = 2.;
+        assert_eq!(bar.value_at(200., 30.), 0.);
+    }
+}
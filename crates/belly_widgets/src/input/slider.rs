@@ -1,11 +1,14 @@
 use crate::input::button::*;
 use crate::range::*;
 use belly_core::build::*;
-use belly_core::input;
+use belly_core::{impl_properties, input};
 use belly_macro::*;
 use bevy::prelude::*;
 
 pub mod prelude {
+    pub use super::DualRangeValue;
+    pub use super::RangeSlider;
+    pub use super::RangesliderWidgetExtension;
     pub use super::SliderWidgetExtension;
 }
 
@@ -16,12 +19,21 @@ impl Plugin for SliderPlugin {
             PreUpdate,
             handle_grabber_input.in_set(input::InputSystemsSet),
         );
+        app.add_systems(
+            PreUpdate,
+            handle_rangeslider_input.in_set(input::InputSystemsSet),
+        );
         app.register_widget::<SliderWidget>();
+        app.register_widget::<RangesliderWidget>();
+        app.register_widget_states::<RangeSlider>();
+        app.add_systems(Update, update_rangeslider_representation);
     }
 }
 
 #[widget]
 #[extends(RangeWidget)]
+/// The grabber moving the value
+#[part(thumb)]
 #[styles(
     slider .slider-grabber {
       margin: 0px;
@@ -39,7 +51,7 @@ fn slider(ctx: &mut WidgetContext) {
     ctx.render(eml! {
         <range c:slider params=params>
             <slot separator>
-                <button with=grabber mode="instant" c:slider-grabber>
+                <button with=grabber mode="instant" part="thumb" c:slider-grabber>
                 </button>
             </slot>
         </range>
@@ -118,3 +130,392 @@ fn handle_grabber_input(
         }
     }
 }
+
+#[widget]
+#[styles = RANGESLIDER_STYLES]
+/// Specifies the minimum value
+#[param(minimum:f32 => RangeSlider:value|DualRangeValue.minimum)]
+/// Specifies the maximum value
+#[param(maximum:f32 => RangeSlider:value|DualRangeValue.maximum)]
+/// Specifies the lower bound of the selected range
+#[param(low:f32 => RangeSlider:value|DualRangeValue.low)]
+/// Specifies the upper bound of the selected range
+#[param(high:f32 => RangeSlider:value|DualRangeValue.high)]
+/// Specifies the step the values snap to, `0` disables snapping
+#[param(step:f32 => RangeSlider:value|DualRangeValue.step)]
+/// <!-- @inline LayoutMode -->
+#[param(mode:LayoutMode => RangeSlider:mode)]
+/// The background of the whole range
+#[part(track)]
+/// The grabbers moving the `low` and `high` values
+#[part(thumb)]
+/// The selected `low..high` range
+#[part(fill)]
+/// The `<rangeslider>` selects the `low..high` range inside the
+/// `minimum..maximum` with two grabbers. The grabbers are exposed
+/// as the `thumb` part, the selected range is the `fill` part.
+fn rangeslider(ctx: &mut WidgetContext, rng: &mut RangeSlider) {
+    let this = ctx.entity();
+    let holder = rng.holder;
+    let before = rng.before_span;
+    let between = rng.between_span;
+    let low = rng.low_grabber;
+    let high = rng.high_grabber;
+    let low_grabber = RangeSliderGrabber {
+        slider: this,
+        high: false,
+    };
+    let high_grabber = RangeSliderGrabber {
+        slider: this,
+        high: true,
+    };
+    ctx.render(eml! {
+        <span c:rangeslider>
+            <span part="track" c:range-back/>
+            <span {holder} c:range-holder s:flex-direction=managed()>
+                <span {before} c:rangeslider-before
+                    s:min-width=managed()
+                    s:min-height=managed()/>
+                <button {low} with=low_grabber mode="instant" part="thumb" c:slider-grabber/>
+                <span {between} c:rangeslider-between
+                    s:min-width=managed()
+                    s:min-height=managed()>
+                    <span part="fill" c:rangeslider-fill/>
+                </span>
+                <button {high} with=high_grabber mode="instant" part="thumb" c:slider-grabber/>
+            </span>
+        </span>
+    })
+}
+
+ess_define! {
+    RANGESLIDER_STYLES,
+
+    rangeslider:horizontal {
+        padding: 5px 3px;
+    }
+    rangeslider:vertical {
+        padding: 3px 5px;
+    }
+    rangeslider .range-back {
+        position-type: absolute;
+        background-color: #ffffff;
+    }
+    rangeslider:horizontal .range-back {
+        left: 0px;
+        right: 0px;
+        top: 10px;
+        bottom: 9px;
+    }
+    rangeslider:vertical .range-back {
+        left: 10px;
+        right: 9px;
+        top: 0px;
+        bottom: 0px;
+    }
+    /** @layout-aware */
+    rangeslider .range-holder {
+        width: 100%;
+        height: 100%;
+    }
+    rangeslider .slider-grabber {
+        margin: 0px;
+        min-width: 16px;
+        min-height: 16px;
+        width: 16px;
+        height: 16px;
+    }
+    rangeslider .rangeslider-fill {
+        position-type: absolute;
+        background-color: #4f4f4fdf;
+    }
+    rangeslider:horizontal .rangeslider-fill {
+        left: -8px;
+        right: -8px;
+        top: 6px;
+        bottom: 5px;
+    }
+    rangeslider:vertical .rangeslider-fill {
+        top: -8px;
+        bottom: -8px;
+        left: 6px;
+        right: 5px;
+    }
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct DualRangeValue {
+    minimum: f32,
+    low: f32,
+    high: f32,
+    maximum: f32,
+    step: f32,
+}
+
+impl Default for DualRangeValue {
+    fn default() -> Self {
+        DualRangeValue {
+            minimum: 0.0,
+            low: 0.0,
+            high: 1.0,
+            maximum: 1.0,
+            step: 0.0,
+        }
+    }
+}
+
+impl DualRangeValue {
+    pub fn minimum(&self) -> f32 {
+        self.minimum
+    }
+
+    pub fn maximum(&self) -> f32 {
+        self.maximum
+    }
+
+    pub fn low(&self) -> f32 {
+        self.low
+    }
+
+    pub fn high(&self) -> f32 {
+        self.high
+    }
+
+    pub fn step(&self) -> f32 {
+        self.step
+    }
+
+    pub fn relative_low(&self) -> f32 {
+        self.relative(self.low)
+    }
+
+    pub fn relative_high(&self) -> f32 {
+        self.relative(self.high)
+    }
+
+    /// Sets the lower bound, it never gets above the `high` one.
+    pub fn set_low(&mut self, value: f32) {
+        self.low = self.snap(value).min(self.high);
+    }
+
+    /// Sets the upper bound, it never gets below the `low` one.
+    pub fn set_high(&mut self, value: f32) {
+        self.high = self.snap(value).max(self.low);
+    }
+
+    pub fn set_relative_low(&mut self, relative: f32) {
+        self.set_low(self.absolute(relative));
+    }
+
+    pub fn set_relative_high(&mut self, relative: f32) {
+        self.set_high(self.absolute(relative));
+    }
+
+    pub fn set_minimum(&mut self, minimum: f32) {
+        self.minimum = minimum.min(self.maximum);
+        self.low = self.low.max(self.minimum);
+        self.high = self.high.max(self.minimum);
+    }
+
+    pub fn set_maximum(&mut self, maximum: f32) {
+        self.maximum = maximum.max(self.minimum);
+        self.low = self.low.min(self.maximum);
+        self.high = self.high.min(self.maximum);
+    }
+
+    pub fn set_step(&mut self, step: f32) {
+        self.step = step.max(0.0);
+        self.low = self.snap(self.low);
+        self.high = self.snap(self.high).max(self.low);
+    }
+
+    fn relative(&self, value: f32) -> f32 {
+        (value - self.minimum) / (self.maximum - self.minimum)
+    }
+
+    fn absolute(&self, relative: f32) -> f32 {
+        self.minimum + relative.min(1.0).max(0.0) * (self.maximum - self.minimum)
+    }
+
+    fn snap(&self, value: f32) -> f32 {
+        let value = if self.step > 0.0 {
+            self.minimum + ((value - self.minimum) / self.step).round() * self.step
+        } else {
+            value
+        };
+        value.min(self.maximum).max(self.minimum)
+    }
+}
+
+impl_properties! { DualRangeValueProperties for DualRangeValue {
+    minimum(set_minimum, minimum) => |v: f32| v.clone();
+    maximum(set_maximum, maximum) => |v: f32| v.clone();
+    low(set_low, low) => |v: f32| v.clone();
+    high(set_high, high) => |v: f32| v.clone();
+    step(set_step, step) => |v: f32| v.clone();
+}}
+
+#[derive(Component)]
+pub struct RangeSlider {
+    pub value: DualRangeValue,
+    pub mode: LayoutMode,
+
+    pub holder: Entity,
+    pub before_span: Entity,
+    pub between_span: Entity,
+    pub low_grabber: Entity,
+    pub high_grabber: Entity,
+}
+
+impl WidgetStates for RangeSlider {
+    fn states(&self) -> Vec<(Tag, bool)> {
+        vec![
+            (Tag::new("horizontal"), self.mode == LayoutMode::Horizontal),
+            (Tag::new("vertical"), self.mode == LayoutMode::Vertical),
+        ]
+    }
+}
+
+impl FromWorldAndParams for RangeSlider {
+    fn from_world_and_params(world: &mut World, params: &mut belly_core::eml::Params) -> Self {
+        RangeSlider {
+            value: DualRangeValue::default(),
+            mode: params.try_get("mode").unwrap_or_default(),
+            holder: world.spawn_empty().id(),
+            before_span: world.spawn_empty().id(),
+            between_span: world.spawn_empty().id(),
+            low_grabber: world.spawn_empty().id(),
+            high_grabber: world.spawn_empty().id(),
+        }
+    }
+}
+
+#[derive(Component)]
+struct RangeSliderGrabber {
+    slider: Entity,
+    high: bool,
+}
+
+fn update_rangeslider_representation(
+    sliders: Query<&RangeSlider, Or<(Changed<RangeSlider>, Changed<Node>)>>,
+    nodes: Query<&Node>,
+    mut styles: Query<&mut Style>,
+) {
+    for slider in sliders.iter() {
+        let Ok(holder) = nodes.get(slider.holder) else {
+            continue;
+        };
+        let Ok(low) = nodes.get(slider.low_grabber) else {
+            continue;
+        };
+        let Ok(high) = nodes.get(slider.high_grabber) else {
+            continue;
+        };
+        let free = (holder.size() - low.size() - high.size()).max(Vec2::ZERO);
+        let before = free * slider.value.relative_low();
+        let between = free * (slider.value.relative_high() - slider.value.relative_low());
+        if let Ok(mut style) = styles.get_mut(slider.holder) {
+            let direction = match slider.mode {
+                LayoutMode::Horizontal => FlexDirection::Row,
+                LayoutMode::Vertical => FlexDirection::ColumnReverse,
+            };
+            if style.flex_direction != direction {
+                style.flex_direction = direction;
+            }
+        }
+        for (span, offset) in [(slider.before_span, before), (slider.between_span, between)] {
+            let Ok(mut style) = styles.get_mut(span) else {
+                continue;
+            };
+            let (width, height) = match slider.mode {
+                LayoutMode::Horizontal => (Val::Px(offset.x), Val::Px(0.)),
+                LayoutMode::Vertical => (Val::Px(0.), Val::Px(offset.y)),
+            };
+            if style.min_width != width || style.min_height != height {
+                style.min_width = width;
+                style.min_height = height;
+            }
+        }
+    }
+}
+
+fn handle_rangeslider_input(
+    mut events: EventReader<PointerInput>,
+    mut sliders: Query<&mut RangeSlider>,
+    grabbers: Query<(Entity, &RangeSliderGrabber)>,
+    nodes: Query<(&GlobalTransform, &Node)>,
+    mut active_grabber: Local<Option<Entity>>,
+) {
+    for ev in events.read() {
+        if ev.drag_start() && active_grabber.is_none() {
+            *active_grabber = ev
+                .entities
+                .iter()
+                .find_map(|e| grabbers.get(*e).ok())
+                .map(|(entity, _)| entity);
+        } else if active_grabber.is_some() && (ev.dragging() || ev.drag_stop()) {
+            let entity = active_grabber.unwrap();
+            if ev.drag_stop() {
+                *active_grabber = None;
+            }
+            let Ok((_, grabber)) = grabbers.get(entity) else {
+                continue;
+            };
+            let Ok(mut slider) = sliders.get_mut(grabber.slider) else {
+                continue;
+            };
+            let Ok((htr, holder)) = nodes.get(slider.holder) else {
+                continue;
+            };
+            let Ok((_, low)) = nodes.get(slider.low_grabber) else {
+                continue;
+            };
+            let Ok((_, high)) = nodes.get(slider.high_grabber) else {
+                continue;
+            };
+            // the low grabber is placed before the high one, so the high
+            // grabber center is shifted by the whole low grabber size
+            let free = (holder.size() - low.size() - high.size()).max(Vec2::ONE);
+            let shift = if grabber.high {
+                low.size() + high.size() * 0.5
+            } else {
+                low.size() * 0.5
+            };
+            let mut pos = ev.pos - htr.translation().truncate() + holder.size() * 0.5;
+            pos.y = holder.size().y - pos.y;
+            let relative = (pos - shift) / free;
+            let relative = match slider.mode {
+                LayoutMode::Horizontal => relative.x,
+                LayoutMode::Vertical => relative.y,
+            };
+            match grabber.high {
+                false => slider.value.set_relative_low(relative),
+                true => slider.value.set_relative_high(relative),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn dual_range_keeps_bounds_ordered() {
+        let mut value = DualRangeValue::default();
+        value.set_maximum(100.);
+        value.set_step(10.);
+        value.set_high(64.);
+        assert_eq!(value.high(), 60.);
+        value.set_low(80.);
+        assert_eq!(value.low(), 60.);
+        value.set_low(23.);
+        assert_eq!(value.low(), 20.);
+        value.set_relative_high(0.);
+        assert_eq!(value.high(), 20.);
+        value.set_relative_high(2.);
+        assert_eq!(value.high(), 100.);
+        value.set_maximum(50.);
+        assert_eq!((value.low(), value.high()), (20., 50.));
+    }
+}
@@ -1,5 +1,6 @@
 use crate::common::*;
 use ab_glyph::ScaleFont;
+use belly_core::ess::impls::direction::{bidi_layout, shape_arabic};
 use belly_core::{build::*, input};
 use belly_macro::*;
 use bevy::{input::keyboard::KeyboardInput, prelude::*};
@@ -29,6 +30,14 @@ impl Plugin for TextInputPlugin {
 
 #[widget]
 #[param(value: String => TextInput:value)]
+/// The field behind the text
+#[part(background)]
+/// The selected text highlight
+#[part(selection)]
+/// The label with the text
+#[part(value)]
+/// The text cursor
+#[part(cursor)]
 #[styles = TEXTINPUT_STYLES]
 fn textinput(ctx: &mut WidgetContext, ti: &mut TextInput) {
     let this = ctx.this().id();
@@ -45,16 +54,16 @@ fn textinput(ctx: &mut WidgetContext, ti: &mut TextInput) {
     ctx.add(from!(this, TextInput: value) >> to!(text, Label: value));
     ctx.render(eml! {
         <span interactable="block" c:text-input c:text-input-border>
-            <span c:text-input-background>
+            <span part="background" c:text-input-background>
                 <span {container} c:text-input-container>
-                    <span {selection}
+                    <span {selection} part="selection"
                         c:text-input-selection
                         s:display=managed()
                         s:left=managed()
                         s:width=managed()
                     />
-                    <label {text} c:text-input-value/>
-                    <span {cursor} c:text-input-cursor
+                    <label {text} part="value" c:text-input-value/>
+                    <span {cursor} part="cursor" c:text-input-cursor
                         s:position-type="absolute"
                         s:width=format!("{:.0}px", CURSOR_WIDTH)
                         s:display=managed()
@@ -70,6 +79,9 @@ ess_define! {
     .text-input {
         width: 200px;
     }
+    .text-input:disabled {
+        opacity: 0.5;
+    }
     .text-input-border {
         background-color: #2f2f2f00;
         padding: 1px;
@@ -85,6 +97,7 @@ ess_define! {
         height: 100%;
         width: 100%;
         overflow: clip;
+        direction: ltr;
     }
     .text-input-selection {
         position-type: absolute;
@@ -129,6 +142,15 @@ impl FromWorldAndParams for TextInput {
     }
 }
 
+impl TextInput {
+    /// Replaces the value and moves the cursor to the end of it
+    pub fn set_value(&mut self, value: impl Into<String>) {
+        self.value = value.into();
+        self.index = self.value.chars().count();
+        self.selected.stop();
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
 pub struct Selection {
     min: usize,
@@ -208,6 +230,104 @@ fn get_char_advance(ch: char, font: &Font, font_size: f32) -> f32 {
     font.h_advance(glyph)
 }
 
+/// The horizontal positions of the chars of the value, which is shaped
+/// and laid out in the visual order when rendered (see [`bidi_layout`]).
+/// All the vectors are indexed by the logical char index.
+#[derive(Debug, Default)]
+struct CharPositions {
+    left: Vec<f32>,
+    advance: Vec<f32>,
+    rtl: Vec<bool>,
+    width: f32,
+}
+
+impl CharPositions {
+    fn new(value: &str, font: &Font, font_size: f32) -> CharPositions {
+        let shaped: Vec<char> = shape_arabic(value).chars().collect();
+        Self::from_advances(value, |idx| get_char_advance(shaped[idx], font, font_size))
+    }
+
+    fn from_advances(value: &str, advance: impl Fn(usize) -> f32) -> CharPositions {
+        let layout = bidi_layout(value, false);
+        let len = layout.order.len();
+        let mut positions = CharPositions {
+            left: vec![0.; len],
+            advance: vec![0.; len],
+            rtl: layout.rtl,
+            width: 0.,
+        };
+        for idx in layout.order {
+            positions.left[idx] = positions.width;
+            positions.advance[idx] = advance(idx);
+            positions.width += positions.advance[idx];
+        }
+        positions
+    }
+
+    /// The position of the cursor placed before the char at `index`:
+    /// on the left side of the left-to-right chars and on the right side
+    /// of the right-to-left ones
+    fn cursor(&self, index: usize) -> f32 {
+        let len = self.left.len();
+        if len == 0 {
+            0.
+        } else if index < len {
+            match self.rtl[index] {
+                true => self.left[index] + self.advance[index],
+                false => self.left[index],
+            }
+        } else {
+            match self.rtl[len - 1] {
+                true => self.left[len - 1],
+                false => self.left[len - 1] + self.advance[len - 1],
+            }
+        }
+    }
+
+    /// The horizontal span of the selected chars. The selection of the
+    /// mixed-direction text may be visually split, the span covers all parts.
+    fn span(&self, range: std::ops::Range<usize>) -> (f32, f32) {
+        range
+            .filter(|idx| *idx < self.left.len())
+            .map(|idx| (self.left[idx], self.left[idx] + self.advance[idx]))
+            .reduce(|(from, to), (left, right)| (from.min(left), to.max(right)))
+            .unwrap_or_default()
+    }
+
+    /// The index of the char under the `x` position and the index of the
+    /// cursor closest to it
+    fn hit(&self, x: f32) -> Option<(usize, usize)> {
+        let len = self.left.len();
+        let x = x.clamp(0., (self.width - f32::EPSILON).max(0.));
+        let char_idx = (0..len).find(|idx| {
+            self.left[*idx] <= x && x < self.left[*idx] + self.advance[*idx].max(f32::EPSILON)
+        })?;
+        let leading = x < self.left[char_idx] + self.advance[char_idx] * 0.5;
+        let index = match leading != self.rtl[char_idx] {
+            true => char_idx,
+            false => char_idx + 1,
+        };
+        Some((char_idx, index))
+    }
+}
+
+/// The chars of the word (or the whitespaces) around the char at `idx`
+fn word_range(chars: &[char], idx: usize) -> std::ops::Range<usize> {
+    let Some(whitespace) = chars.get(idx).map(|c| c.is_whitespace()) else {
+        return chars.len()..chars.len();
+    };
+    let same = |c: &char| c.is_whitespace() == whitespace;
+    let start = chars[..idx]
+        .iter()
+        .rposition(|c| !same(c))
+        .map_or(0, |p| p + 1);
+    let end = chars[idx..]
+        .iter()
+        .position(|c| !same(c))
+        .map_or(chars.len(), |p| p + idx);
+    start..end
+}
+
 fn process_keyboard_input(
     changed_elements: Query<(), Changed<Element>>,
     mut keyboard_input: EventReader<KeyboardInput>,
@@ -240,7 +360,7 @@ fn process_keyboard_input(
     // may be platform dependent compilation here?
     let cmd = keyboard.any_pressed([KeyCode::SuperLeft, KeyCode::SuperRight]);
     let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
-    let mut index = input.index;
+    let mut index = input.index.min(input.value.chars().count());
     let mut selected = input.selected.clone();
 
     let mut chars: Vec<_> = input.value.chars().collect();
@@ -348,27 +468,14 @@ fn process_keyboard_input(
         return;
     };
     let container_width = node.size().x;
-    let mut position_from_start = 0.;
-    let mut selection_from = 0.;
-    let mut selection_to = 0.;
-    let mut text_width = 0.;
     let Some(font) = fonts.get(&text.sections[0].style.font) else {
         return;
     };
     let font_size = text.sections[0].style.font_size;
-    for (idx, ch) in chars.iter().enumerate() {
-        let advance = get_char_advance(*ch, font, font_size);
-        text_width += advance;
-        if idx < index {
-            position_from_start += advance;
-        }
-        if idx < selected.min {
-            selection_from += advance;
-        }
-        if idx < selected.max {
-            selection_to += advance;
-        }
-    }
+    let positions = CharPositions::new(&input.value, font, font_size);
+    let position_from_start = positions.cursor(index);
+    let (mut selection_from, mut selection_to) = positions.span(selected.range());
+    let text_width = positions.width;
     let mut offset = if let Ok(contaienr_style) = styles.get_mut(input.container) {
         match contaienr_style.padding.left {
             Val::Px(x) => x,
@@ -472,7 +579,7 @@ fn process_mouse(
             let Ok((container, tr, node)) = styles.get(input.container) else {
                 continue;
             };
-            let mut offset = if let Val::Px(offset) = container.padding.left {
+            let offset = if let Val::Px(offset) = container.padding.left {
                 offset
             } else {
                 0.
@@ -485,40 +592,16 @@ fn process_mouse(
             };
             let font_size = text.sections[0].style.font_size;
             let pos = (evt.pos - tr.translation().truncate() + node.size() * 0.5).x;
-            let mut index = 0;
-            let mut idx_found = false;
-            let mut word_start = 0;
-            let mut word_end = 0;
-            let mut whitespace = false;
-            for (idx, ch) in input.value.chars().enumerate() {
-                let advance = get_char_advance(ch, font, font_size);
-                if offset < pos && !idx_found {
-                    index = idx;
-                    if offset + advance * 0.5 < pos {
-                        index += 1;
-                    }
-                } else {
-                    idx_found = true;
-                }
-                offset += advance;
-                if !whitespace && !ch.is_whitespace() {
-                    word_end = idx + 1;
-                } else if whitespace && ch.is_whitespace() {
-                    word_end = idx + 1;
-                } else if idx_found {
-                    break;
-                } else {
-                    whitespace = !whitespace;
-                    word_start = idx;
-                    word_end = idx + 1;
-                }
-            }
+            let positions = CharPositions::new(&input.value, font, font_size);
+            let chars: Vec<char> = input.value.chars().collect();
+            let (char_idx, mut index) = positions.hit(pos - offset).unwrap_or_default();
+            let word = word_range(&chars, char_idx);
 
             let mut selected = input.selected.clone();
             let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
             if evt.down() && evt.presses() == 2 {
-                selected.start(word_start);
-                selected.extend(word_end);
+                selected.start(word.start);
+                selected.extend(word.end);
                 index = selected.max;
             } else if evt.down() && evt.presses() > 2 {
                 selected.start(0);
@@ -541,9 +624,12 @@ fn process_mouse(
     }
 }
 
-fn blink_cursor(time: Res<Time>, mut cursor: Query<(&mut TextInputCursor, &mut Style)>) {
-    for (mut cursor, mut style) in cursor.iter_mut() {
-        cursor.state -= time.delta_seconds();
+fn blink_cursor(
+    time: ElementsTime,
+    mut cursor: Query<(Entity, &mut TextInputCursor, &mut Style)>,
+) {
+    for (entity, mut cursor, mut style) in cursor.iter_mut() {
+        cursor.state -= time.delta_seconds(entity);
         if cursor.state < 0. {
             cursor.state = 1.;
         }
@@ -555,3 +641,26 @@ fn blink_cursor(time: Res<Time>, mut cursor: Query<(&mut TextInputCursor, &mut S
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cursor_follows_visual_order() {
+        // "ab" followed by the space and the reversed hebrew word
+        let positions = CharPositions::from_advances("ab שלום", |_| 1.);
+        assert_eq!(positions.width, 7.);
+        assert_eq!(positions.cursor(0), 0.);
+        assert_eq!(positions.cursor(3), 7.);
+        assert_eq!(positions.cursor(7), 3.);
+        assert_eq!(positions.span(1..4), (1., 7.));
+        assert_eq!(positions.hit(6.2), Some((3, 4)));
+        assert_eq!(positions.hit(6.8), Some((3, 3)));
+        assert_eq!(positions.hit(-5.), Some((0, 0)));
+
+        let chars: Vec<char> = "ab  cd".chars().collect();
+        assert_eq!(word_range(&chars, 5), 4..6);
+        assert_eq!(word_range(&chars, 2), 2..4);
+    }
+}
@@ -1,8 +1,23 @@
+pub mod a11y;
+pub mod animation;
+pub mod badge;
+pub mod breadcrumbs;
+pub mod canvas;
+pub mod chart;
+pub mod chip;
 pub mod common;
+pub mod console;
+pub mod devtools;
+pub mod diagnostics;
 pub mod follow;
+pub mod icon;
 pub mod img;
 pub mod input;
+pub mod loading;
+pub mod menu;
+pub mod radial;
 pub mod range;
+pub mod skin;
 use bevy::prelude::Plugin;
 
 #[derive(Default)]
@@ -10,19 +25,46 @@ pub struct WidgetsPlugin;
 
 impl Plugin for WidgetsPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_plugins(a11y::A11yPlugin);
+        app.add_plugins(animation::AnimationPlugin);
+        app.add_plugins(badge::BadgePlugin);
+        app.add_plugins(breadcrumbs::BreadcrumbsPlugin);
+        app.add_plugins(canvas::CanvasPlugin);
+        app.add_plugins(chart::ChartPlugin);
+        app.add_plugins(chip::ChipPlugin);
         app.add_plugins(common::CommonsPlugin);
+        app.add_plugins(console::ConsolePlugin);
+        app.add_plugins(diagnostics::DiagnosticsPlugin);
         app.add_plugins(range::RangePlugin);
         app.add_plugins(img::ImgPlugin);
         app.add_plugins(input::InputPlugins);
+        app.add_plugins(loading::LoadingPlugin);
+        app.add_plugins(menu::MenuPlugin);
+        app.add_plugins(radial::RadialPlugin);
         app.add_plugins(follow::FollowPlugin);
+        app.add_plugins(icon::IconPlugin);
     }
 }
 
 pub mod prelude {
+    pub use crate::animation::prelude::*;
+    pub use crate::badge::prelude::*;
+    pub use crate::breadcrumbs::prelude::*;
+    pub use crate::canvas::prelude::*;
+    pub use crate::chart::prelude::*;
+    pub use crate::chip::prelude::*;
     pub use crate::common::prelude::*;
+    pub use crate::console::prelude::*;
+    pub use crate::devtools::prelude::*;
+    pub use crate::diagnostics::prelude::*;
     pub use crate::follow::prelude::*;
+    pub use crate::icon::prelude::*;
     pub use crate::img::prelude::*;
     pub use crate::input::prelude::*;
+    pub use crate::loading::prelude::*;
+    pub use crate::menu::prelude::*;
+    pub use crate::radial::prelude::*;
+    pub use crate::skin::prelude::*;
 }
 
 pub mod tags {
@@ -0,0 +1,204 @@
+use crate::canvas::{ArcCap, Canvas, CanvasDraw};
+use belly_core::build::*;
+use belly_macro::*;
+use bevy::prelude::*;
+use std::f32::consts::TAU;
+
+pub mod prelude {
+    pub use super::Skeleton;
+    pub use super::SkeletonStyle;
+    pub use super::SkeletonWidgetExtension;
+    pub use super::Spinner;
+    pub use super::SpinnerStyle;
+    pub use super::SpinnerWidgetExtension;
+}
+
+pub(crate) struct LoadingPlugin;
+impl Plugin for LoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_widget::<SpinnerWidget>();
+        app.register_widget::<SkeletonWidget>();
+        app.register_type::<Skeleton>();
+        app.add_systems(Update, (redraw_spinners, rotate_spinners, pulse_skeletons));
+    }
+}
+
+#[widget]
+/// Rotates the spinner if `true`, pauses it otherwise
+#[param(spinning: bool => Spinner:spinning)]
+/// The color of the rotating arc
+#[style_prop(spinner-color: Color)]
+/// The color of the circle behind the arc
+#[style_prop(spinner-track: Color)]
+/// The width of the arc in pixels
+#[style_prop(spinner-thickness: f32)]
+/// The number of turns per second
+#[style_prop(spinner-speed: f32)]
+#[styles(
+    spinner {
+        width: 32px;
+        height: 32px;
+        spinner-color: #4caf50;
+        spinner-track: #ffffff20;
+        spinner-thickness: 4;
+        spinner-speed: 1;
+    }
+)]
+/// The `<spinner>` is the rotating arc showing that something is in
+/// progress. The look and the speed are configured by the styles:
+/// `.busy spinner { spinner-color: orange; spinner-speed: 2; }`
+fn spinner(ctx: &mut WidgetContext, spinner: &mut Spinner) {
+    let this = ctx.entity();
+    let canvas = spinner.canvas;
+    let draw = CanvasDraw::new(move |painter, world| {
+        let Some(style) = world.get::<SpinnerStyle>(this) else {
+            return;
+        };
+        let center = painter.size() * 0.5;
+        let thickness = style.spinner_thickness;
+        let radius = center.min_element() - thickness * 0.5;
+        if radius <= 0. {
+            return;
+        }
+        painter.arc(center, radius, 0., TAU, thickness, ArcCap::Butt, style.spinner_track);
+        painter.arc(
+            center,
+            radius,
+            0.,
+            TAU * 0.25,
+            thickness,
+            ArcCap::Round,
+            style.spinner_color,
+        );
+    });
+    ctx.render(eml! {
+        <span c:spinner>
+            <canvas {canvas} draw=draw retained=true s:width="100%" s:height="100%"/>
+        </span>
+    });
+}
+
+#[widget]
+/// The base color of the placeholder
+#[style_prop(skeleton-color: Color)]
+/// The duration of the single pulse in seconds, `0` stops pulsing
+#[style_prop(skeleton-pulse: f32)]
+#[styles(
+    skeleton {
+        width: 100%;
+        height: 100%;
+        min-height: 12px;
+        skeleton-color: #ffffff30;
+        skeleton-pulse: 1.5;
+    }
+)]
+/// The `<skeleton>` is the pulsing placeholder block taking the whole
+/// space of its container. Put a few of them where the content is going
+/// to appear and hide them once it is loaded:
+/// `.profile:not(:loading) skeleton { display: none; }`
+fn skeleton(ctx: &mut WidgetContext, _skeleton: &mut Skeleton) {
+    let content = ctx.content();
+    ctx.insert(ElementBundle::default()).push_children(&content);
+}
+
+#[derive(Component)]
+/// The state of the `<spinner>`
+pub struct Spinner {
+    pub spinning: bool,
+    /// The current rotation in radians
+    pub angle: f32,
+    canvas: Entity,
+}
+
+impl FromWorldAndParams for Spinner {
+    fn from_world_and_params(world: &mut World, params: &mut belly_core::eml::Params) -> Self {
+        Spinner {
+            spinning: params.try_get("spinning").unwrap_or(true),
+            angle: 0.,
+            canvas: world.spawn_empty().id(),
+        }
+    }
+}
+
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
+/// The state of the `<skeleton>`
+pub struct Skeleton {
+    /// The time passed since the pulse started, in seconds
+    pub elapsed: f32,
+}
+
+impl Skeleton {
+    /// The opacity multiplier of the placeholder, it goes from `1` down to
+    /// `0.4` and back during the `period`
+    pub fn pulse(&self, period: f32) -> f32 {
+        if period <= 0. {
+            return 1.;
+        }
+        let phase = (self.elapsed / period).fract();
+        0.7 + 0.3 * (phase * TAU).cos()
+    }
+}
+
+fn redraw_spinners(
+    spinners: Query<&Spinner, Changed<SpinnerStyle>>,
+    mut canvases: Query<&mut Canvas>,
+) {
+    for spinner in spinners.iter() {
+        if let Ok(mut canvas) = canvases.get_mut(spinner.canvas) {
+            canvas.redraw();
+        }
+    }
+}
+
+/// The arc is drawn once, the spinner rotates the canvas node instead
+/// of redrawing it every frame
+fn rotate_spinners(
+    time: ElementsTime,
+    mut spinners: Query<(Entity, &mut Spinner, &SpinnerStyle)>,
+    mut transforms: Query<&mut Transform>,
+) {
+    for (entity, mut spinner, style) in spinners.iter_mut() {
+        if !spinner.spinning || style.spinner_speed == 0. {
+            continue;
+        }
+        let turn = style.spinner_speed * TAU * time.delta_seconds(entity);
+        spinner.angle = (spinner.angle + turn).rem_euclid(TAU);
+        if let Ok(mut transform) = transforms.get_mut(spinner.canvas) {
+            transform.rotation = Quat::from_rotation_z(-spinner.angle);
+        }
+    }
+}
+
+fn pulse_skeletons(
+    time: ElementsTime,
+    mut skeletons: Query<(Entity, &mut Skeleton, &SkeletonStyle, &mut BackgroundColor)>,
+) {
+    for (entity, mut skeleton, style, mut background) in skeletons.iter_mut() {
+        let period = style.skeleton_pulse;
+        if period > 0. {
+            skeleton.elapsed = (skeleton.elapsed + time.delta_seconds(entity)) % period;
+        }
+        let color = style.skeleton_color;
+        let color = color.with_a(color.a() * skeleton.pulse(period));
+        if background.0 != color {
+            background.0 = color;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn skeleton_pulse_goes_down_and_back() {
+        let mut skeleton = Skeleton::default();
+        assert_eq!(skeleton.pulse(2.), 1.);
+        skeleton.elapsed = 1.;
+        assert!((skeleton.pulse(2.) - 0.4).abs() < 1e-5);
+        skeleton.elapsed = 2.;
+        assert_eq!(skeleton.pulse(2.), 1.);
+        assert_eq!(skeleton.pulse(0.), 1.);
+    }
+}
@@ -0,0 +1,512 @@
+use crate::common::Label;
+use belly_core::build::*;
+use belly_core::input;
+use belly_macro::*;
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+pub mod prelude {
+    pub use super::Menu;
+    pub use super::MenuEvent;
+    pub use super::MenuItem;
+    pub use super::MenuWidgetExtension;
+    pub use super::MenubarWidgetExtension;
+    pub use super::MenuitemWidgetExtension;
+}
+
+pub(crate) struct MenuPlugin;
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<MenuEvent>();
+        app.register_widget::<MenubarWidget>();
+        app.register_widget::<MenuWidget>();
+        app.register_widget::<MenuitemWidget>();
+        app.register_type::<MenuItem>();
+        app.register_widget_states::<Menu>();
+        app.register_widget_states::<MenuItem>();
+        app.add_systems(
+            PreUpdate,
+            (handle_menu_pointer_system, handle_menu_keyboard_system)
+                .chain()
+                .in_set(input::InputSystemsSet),
+        );
+    }
+}
+
+#[widget]
+#[styles = MENU_STYLES]
+/// The `<menubar>` is the row of the `<menu>`s. Press the menu title to
+/// open it, when one of the menus is open hovering the other titles
+/// switches between them. While the menu is open the keyboard navigates
+/// it: `Up`/`Down` move between the entries, `Right` opens the submenu
+/// (or the next menu of the bar), `Left` closes it (or opens the previous
+/// menu), `Enter` selects the entry and `Escape` closes the menu.
+fn menubar(ctx: &mut WidgetContext) {
+    let content = ctx.content();
+    ctx.render(eml! {
+        <span c:menubar>{content}</span>
+    })
+}
+
+#[widget]
+/// Specifies the title of the menu
+#[param(label: String => Menu:label)]
+/// Opens the menu if `true`
+#[param(open: bool => Menu:open)]
+/// The pressable title of the menu
+#[part(title)]
+/// The container of the menu entries
+#[part(popup)]
+/// The `<menu>` is the titled popup with the `<menuitem>`s and the nested
+/// `<menu>`s (the submenus open on hover). The element has the `:open`
+/// state while the popup is shown and the `:highlighted` state when it is
+/// selected with the keyboard.
+fn menu(ctx: &mut WidgetContext, menu: &mut Menu) {
+    let this = ctx.entity();
+    let content = ctx.content();
+    let title = menu.title;
+    let popup = menu.popup;
+    let label = ctx.empty();
+    let marker = MenuTitle { menu: this };
+    ctx.add(from!(this, Menu: label) >> to!(label, Label: value));
+    ctx.render(eml! {
+        <span c:menu>
+            <span {title} with=marker part="title" c:menu-title interactable>
+                <label {label}/>
+            </span>
+            <span {popup} part="popup" c:menu-popup layer="popup">{content}</span>
+        </span>
+    })
+}
+
+#[widget]
+#[signal(select: MenuEvent => menu_item_selected)]
+/// Specifies the value of the item, available to the `select` handlers
+#[param(value: String => MenuItem:value)]
+/// The `<menuitem>` is the entry of the `<menu>`, pressing it (or pressing
+/// `Enter` while it is highlighted) emits the `select` signal and closes
+/// the menus. The element has the `:highlighted` state when it is selected
+/// with the keyboard.
+fn menuitem(ctx: &mut WidgetContext) {
+    let content = ctx.content();
+    ctx.render(eml! {
+        <span c:menuitem interactable>{content}</span>
+    })
+}
+
+ess_define! {
+    MENU_STYLES,
+    menubar {
+        background-color: #dfdfdf;
+    }
+    menubar * {
+        color: #2f2f2f;
+    }
+    .menu-title {
+        padding: 2px 8px;
+    }
+    .menu-title:hover {
+        background-color: #bfbfbf;
+    }
+    menu:open > .menu-title {
+        background-color: #bfbfbf;
+    }
+    menu:highlighted > .menu-title {
+        background-color: #bfbfbf;
+    }
+    .menu-popup {
+        display: none;
+        position-type: absolute;
+        top: 100%;
+        left: 0px;
+        min-width: 140px;
+        flex-direction: column;
+        padding: 2px;
+        background-color: #dfdfdf;
+    }
+    .menu-popup .menu-popup {
+        top: 0px;
+        left: 100%;
+    }
+    menu:open > .menu-popup {
+        display: flex;
+    }
+    menuitem {
+        padding: 2px 8px;
+    }
+    menuitem:hover {
+        background-color: #bfbfbf;
+    }
+    menuitem:highlighted {
+        background-color: #bfbfbf;
+    }
+}
+
+#[derive(Component)]
+/// The state of the `<menu>`
+pub struct Menu {
+    pub label: String,
+    pub open: bool,
+    pub highlighted: bool,
+    title: Entity,
+    popup: Entity,
+}
+
+impl FromWorldAndParams for Menu {
+    fn from_world_and_params(world: &mut World, params: &mut belly_core::eml::Params) -> Self {
+        Menu {
+            label: params.try_get("label").unwrap_or_default(),
+            open: params.try_get("open").unwrap_or_default(),
+            highlighted: false,
+            title: world.spawn_empty().id(),
+            popup: world.spawn_empty().id(),
+        }
+    }
+}
+
+impl WidgetStates for Menu {
+    fn states(&self) -> Vec<(Tag, bool)> {
+        vec![
+            (Tag::new("open"), self.open),
+            (Tag::new("highlighted"), self.highlighted),
+        ]
+    }
+}
+
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
+/// The state of the `<menuitem>`
+pub struct MenuItem {
+    pub value: String,
+    pub highlighted: bool,
+}
+
+impl WidgetStates for MenuItem {
+    fn states(&self) -> Vec<(Tag, bool)> {
+        vec![(Tag::new("highlighted"), self.highlighted)]
+    }
+}
+
+#[derive(Component)]
+struct MenuTitle {
+    menu: Entity,
+}
+
+#[derive(Event, Debug)]
+pub enum MenuEvent {
+    Select(Entity),
+}
+
+fn menu_item_selected(event: &MenuEvent) -> EventSource {
+    match event {
+        MenuEvent::Select(entity) => EventSource::single(*entity),
+    }
+}
+
+/// The menus with their entries
+#[derive(SystemParam)]
+struct MenuTree<'w, 's> {
+    parents: Query<'w, 's, &'static Parent>,
+    children: Query<'w, 's, &'static Children>,
+    menus: Query<'w, 's, (Entity, &'static mut Menu)>,
+    items: Query<'w, 's, &'static mut MenuItem>,
+}
+
+impl<'w, 's> MenuTree<'w, 's> {
+    /// Returns the closest `<menu>` containing the `entity`
+    fn parent(&self, entity: Entity) -> Option<Entity> {
+        self.parents
+            .iter_ancestors(entity)
+            .find(|ancestor| self.menus.contains(*ancestor))
+    }
+
+    /// Returns the `<menu>`s placed directly into the `<menubar>` (or any
+    /// other container) together with the `menu`
+    fn bar(&self, menu: Entity) -> Vec<Entity> {
+        let mut root = menu;
+        while let Some(parent) = self.parent(root) {
+            root = parent;
+        }
+        self.parents
+            .get(root)
+            .and_then(|parent| self.children.get(parent.get()))
+            .map(|children| {
+                children
+                    .iter()
+                    .copied()
+                    .filter(|e| self.menus.contains(*e))
+                    .collect()
+            })
+            .unwrap_or_else(|_| vec![root])
+    }
+
+    /// Returns the `<menuitem>`s and the `<menu>`s placed into the popup
+    fn entries(&self, menu: Entity) -> Vec<Entity> {
+        let Ok((_, menu)) = self.menus.get(menu) else {
+            return vec![];
+        };
+        self.children
+            .get(menu.popup)
+            .map(|children| {
+                children
+                    .iter()
+                    .copied()
+                    .filter(|e| self.menus.contains(*e) || self.items.contains(*e))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the open menu without the open submenus
+    fn current(&self) -> Option<Entity> {
+        let open: Vec<_> = self
+            .menus
+            .iter()
+            .filter(|(_, menu)| menu.open)
+            .map(|(entity, _)| entity)
+            .collect();
+        open.iter()
+            .copied()
+            .find(|menu| !open.iter().any(|e| self.parent(*e) == Some(*menu)))
+    }
+
+    fn is_highlighted(&self, entry: Entity) -> bool {
+        match (self.menus.get(entry), self.items.get(entry)) {
+            (Ok((_, menu)), _) => menu.highlighted,
+            (_, Ok(item)) => item.highlighted,
+            _ => false,
+        }
+    }
+
+    /// Highlights the entry at the `index`, the rest are unhighlighted
+    fn highlight(&mut self, entries: &[Entity], index: Option<usize>) {
+        for (idx, entry) in entries.iter().enumerate() {
+            let highlighted = Some(idx) == index;
+            if let Ok((_, mut menu)) = self.menus.get_mut(*entry) {
+                if menu.highlighted != highlighted {
+                    menu.highlighted = highlighted;
+                }
+            } else if let Ok(mut item) = self.items.get_mut(*entry) {
+                if item.highlighted != highlighted {
+                    item.highlighted = highlighted;
+                }
+            }
+        }
+    }
+
+    /// Opens the `target` menu with all its parent menus and closes the
+    /// rest, `None` closes all the menus
+    fn open(&mut self, target: Option<Entity>) {
+        let mut branch = vec![];
+        let mut next = target;
+        while let Some(menu) = next {
+            branch.push(menu);
+            next = self.parent(menu);
+        }
+        let mut closed = vec![];
+        for (entity, mut menu) in self.menus.iter_mut() {
+            let open = branch.contains(&entity);
+            if menu.open != open {
+                menu.open = open;
+                if !open {
+                    closed.push(entity);
+                }
+            }
+        }
+        for menu in closed {
+            let entries = self.entries(menu);
+            self.highlight(&entries, None);
+        }
+    }
+
+    /// Opens the `menu` with its first entry highlighted
+    fn enter(&mut self, menu: Entity) {
+        self.open(Some(menu));
+        let entries = self.entries(menu);
+        self.highlight(&entries, Some(0));
+    }
+
+    /// Enters the next (or the previous when the `step` is negative) menu
+    /// of the bar containing the `current` menu
+    fn step_bar(&mut self, current: Entity, step: isize) {
+        let bar = self.bar(current);
+        let mut root = current;
+        while let Some(parent) = self.parent(root) {
+            root = parent;
+        }
+        let Some(idx) = bar.iter().position(|e| *e == root) else {
+            return;
+        };
+        let len = bar.len() as isize;
+        self.enter(bar[(idx as isize + step).rem_euclid(len) as usize]);
+    }
+}
+
+fn handle_menu_pointer_system(
+    mut events: EventReader<PointerInput>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    titles: Query<&MenuTitle>,
+    mut tree: MenuTree,
+    mut signals: EventWriter<MenuEvent>,
+) {
+    let mut handled = false;
+    for ev in events.read() {
+        let title = ev
+            .entities
+            .iter()
+            .find_map(|e| titles.get(*e).ok())
+            .map(|title| title.menu);
+        let item = ev
+            .entities
+            .iter()
+            .copied()
+            .find(|e| tree.items.contains(*e));
+        if ev.down() {
+            handled |= title.is_some() || item.is_some();
+            let Some(menu) = title else {
+                continue;
+            };
+            let open = tree.menus.get(menu).map(|(_, m)| m.open).unwrap_or(false);
+            let target = if open { tree.parent(menu) } else { Some(menu) };
+            tree.open(target);
+        } else if ev.pressed() {
+            let Some(item) = item else {
+                continue;
+            };
+            signals.send(MenuEvent::Select(item));
+            tree.open(None);
+        } else if ev.motion() {
+            let Some(menu) = title else {
+                continue;
+            };
+            if tree.menus.get(menu).map(|(_, m)| m.open).unwrap_or(true) {
+                continue;
+            }
+            // the submenus open on hover, the other menus of the bar open
+            // on hover only when one of them is already open
+            let switch = tree.parent(menu).is_some()
+                || tree
+                    .bar(menu)
+                    .iter()
+                    .any(|e| tree.menus.get(*e).map(|(_, m)| m.open).unwrap_or(false));
+            if switch {
+                tree.open(Some(menu));
+            }
+        }
+    }
+    if !handled && mouse.just_pressed(MouseButton::Left) && tree.current().is_some() {
+        tree.open(None);
+    }
+}
+
+fn handle_menu_keyboard_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut tree: MenuTree,
+    mut signals: EventWriter<MenuEvent>,
+) {
+    let Some(current) = tree.current() else {
+        return;
+    };
+    let entries = tree.entries(current);
+    let highlighted = entries.iter().position(|e| tree.is_highlighted(*e));
+    let entry = highlighted.map(|idx| entries[idx]);
+    let submenu = entry.filter(|e| tree.menus.contains(*e));
+    if keyboard.just_pressed(KeyCode::ArrowDown) && !entries.is_empty() {
+        let next = highlighted.map(|idx| (idx + 1) % entries.len()).unwrap_or(0);
+        tree.highlight(&entries, Some(next));
+    } else if keyboard.just_pressed(KeyCode::ArrowUp) && !entries.is_empty() {
+        let len = entries.len();
+        let prev = highlighted.map(|idx| (idx + len - 1) % len).unwrap_or(len - 1);
+        tree.highlight(&entries, Some(prev));
+    } else if keyboard.just_pressed(KeyCode::ArrowRight) {
+        match submenu {
+            Some(submenu) => tree.enter(submenu),
+            None => tree.step_bar(current, 1),
+        }
+    } else if keyboard.just_pressed(KeyCode::ArrowLeft) {
+        match tree.parent(current) {
+            Some(parent) => tree.open(Some(parent)),
+            None => tree.step_bar(current, -1),
+        }
+    } else if keyboard.just_pressed(KeyCode::Enter) {
+        match (submenu, entry) {
+            (Some(submenu), _) => tree.enter(submenu),
+            (None, Some(item)) => {
+                signals.send(MenuEvent::Select(item));
+                tree.open(None);
+            }
+            _ => {}
+        }
+    } else if keyboard.just_pressed(KeyCode::Escape) {
+        let parent = tree.parent(current);
+        tree.open(parent);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn navigate_menus_with_keyboard() {
+        let mut app = App::new();
+        app.add_event::<MenuEvent>();
+        app.init_resource::<ButtonInput<KeyCode>>();
+        app.add_systems(Update, handle_menu_keyboard_system);
+        let world = &mut app.world;
+        let menu = |world: &mut World, open: bool| {
+            let popup = world.spawn_empty().id();
+            let menu = Menu {
+                label: String::new(),
+                open,
+                highlighted: false,
+                title: world.spawn_empty().id(),
+                popup,
+            };
+            (world.spawn(menu).add_child(popup).id(), popup)
+        };
+        let (file, file_popup) = menu(world, true);
+        let (edit, _) = menu(world, false);
+        let (recent, recent_popup) = menu(world, false);
+        let new = world.spawn(MenuItem::default()).id();
+        let last = world.spawn(MenuItem::default()).id();
+        world.spawn_empty().push_children(&[file, edit]);
+        world.entity_mut(file_popup).push_children(&[new, recent]);
+        world.entity_mut(recent_popup).push_children(&[last]);
+
+        let press = |app: &mut App, key: KeyCode| {
+            let mut keyboard = app.world.resource_mut::<ButtonInput<KeyCode>>();
+            keyboard.reset_all();
+            keyboard.press(key);
+            app.update();
+        };
+        let highlighted = |app: &App, entity: Entity| match app.world.get::<MenuItem>(entity) {
+            Some(item) => item.highlighted,
+            None => app.world.get::<Menu>(entity).unwrap().highlighted,
+        };
+        let open = |app: &App, entity: Entity| app.world.get::<Menu>(entity).unwrap().open;
+
+        press(&mut app, KeyCode::ArrowUp);
+        assert!(highlighted(&app, recent));
+        press(&mut app, KeyCode::ArrowRight);
+        assert!(open(&app, recent));
+        assert!(highlighted(&app, last));
+        press(&mut app, KeyCode::ArrowLeft);
+        assert!(!open(&app, recent));
+        assert!(!highlighted(&app, last));
+        press(&mut app, KeyCode::ArrowRight);
+        press(&mut app, KeyCode::Enter);
+        assert!(!open(&app, file));
+        let selected: Vec<_> = app
+            .world
+            .resource_mut::<Events<MenuEvent>>()
+            .drain()
+            .map(|MenuEvent::Select(entity)| entity)
+            .collect();
+        assert_eq!(selected, vec![last]);
+
+        app.world.get_mut::<Menu>(edit).unwrap().open = true;
+        press(&mut app, KeyCode::ArrowRight);
+        assert!(open(&app, file));
+        assert!(!open(&app, edit));
+    }
+}
@@ -0,0 +1,163 @@
+use crate::canvas::{ArcCap, Canvas, CanvasDraw, CanvasPainter};
+use belly_core::build::*;
+use belly_macro::*;
+use bevy::prelude::*;
+
+pub mod prelude {
+    pub use super::ArcDirection;
+    pub use super::RadialProgress;
+    pub use super::RadialprogressWidgetExtension;
+}
+
+pub(crate) struct RadialPlugin;
+impl Plugin for RadialPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_widget::<RadialprogressWidget>();
+        app.add_systems(Update, redraw_radial_progress);
+    }
+}
+
+#[widget]
+#[styles(
+    radialprogress {
+      width: 32px;
+      height: 32px;
+    }
+)]
+/// Specifies the progress in 0..1 range
+#[param(value: f32 => RadialProgress:value)]
+/// Specifies the angle in degrees the arc starts from, `0` is the top
+#[param(start: f32 => RadialProgress:start)]
+/// <!-- @inline ArcDirection -->
+#[param(direction: ArcDirection => RadialProgress:direction)]
+/// Specifies the width of the arc in pixels
+#[param(thickness: f32 => RadialProgress:thickness)]
+/// <!-- @inline ArcCap -->
+#[param(cap: ArcCap => RadialProgress:cap)]
+/// Specifies the color of the filled arc
+#[param(color: Color => RadialProgress:color)]
+/// Specifies the color of the unfilled part of the circle
+#[param(track: Color => RadialProgress:track)]
+/// The `<radialprogress>` shows the progress as the arc filling
+/// the circle, useful for cooldowns and loading indicators.
+fn radialprogress(ctx: &mut WidgetContext, progress: &mut RadialProgress) {
+    let this = ctx.entity();
+    let canvas = progress.canvas;
+    let draw = CanvasDraw::new(move |painter, world| {
+        if let Some(progress) = world.get::<RadialProgress>(this) {
+            progress.paint(painter);
+        }
+    });
+    ctx.render(eml! {
+        <span c:radial-progress>
+            <canvas {canvas} draw=draw retained=true s:width="100%" s:height="100%"/>
+        </span>
+    });
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Debug, EnumParam)]
+/// Specifies the direction the arc grows:
+/// - `clockwise`: grow the arc clockwise
+/// - `counterclockwise`: grow the arc counterclockwise
+pub enum ArcDirection {
+    #[default]
+    #[param(alias = "cw")]
+    Clockwise,
+    #[param(alias = "ccw")]
+    Counterclockwise,
+}
+
+#[derive(Component)]
+pub struct RadialProgress {
+    pub value: f32,
+    pub start: f32,
+    pub direction: ArcDirection,
+    pub thickness: f32,
+    pub cap: ArcCap,
+    pub color: Color,
+    pub track: Color,
+    canvas: Entity,
+}
+
+impl FromWorldAndParams for RadialProgress {
+    fn from_world_and_params(world: &mut World, params: &mut belly_core::eml::Params) -> Self {
+        RadialProgress {
+            value: params.try_get("value").unwrap_or_default(),
+            start: params.try_get("start").unwrap_or_default(),
+            direction: params.try_get("direction").unwrap_or_default(),
+            thickness: params.try_get("thickness").unwrap_or(4.),
+            cap: params.try_get("cap").unwrap_or_default(),
+            color: params
+                .try_get("color")
+                .unwrap_or(Color::rgb(0.298, 0.686, 0.314)),
+            track: params
+                .try_get("track")
+                .unwrap_or(Color::rgba(1., 1., 1., 0.2)),
+            canvas: world.spawn_empty().id(),
+        }
+    }
+}
+
+impl RadialProgress {
+    /// The signed sweep of the filled arc in radians.
+    pub fn sweep(&self) -> f32 {
+        let sweep = self.value.clamp(0., 1.) * std::f32::consts::TAU;
+        match self.direction {
+            ArcDirection::Clockwise => sweep,
+            ArcDirection::Counterclockwise => -sweep,
+        }
+    }
+
+    fn paint(&self, painter: &mut CanvasPainter) {
+        let center = painter.size() * 0.5;
+        let radius = center.min_element() - self.thickness * 0.5;
+        if radius <= 0. {
+            return;
+        }
+        let start = self.start.to_radians();
+        let full = std::f32::consts::TAU;
+        painter.arc(center, radius, 0., full, self.thickness, ArcCap::Butt, self.track);
+        if self.value > 0. {
+            painter.arc(
+                center,
+                radius,
+                start,
+                self.sweep(),
+                self.thickness,
+                self.cap,
+                self.color,
+            );
+        }
+    }
+}
+
+fn redraw_radial_progress(
+    progresses: Query<&RadialProgress, Changed<RadialProgress>>,
+    mut canvases: Query<&mut Canvas>,
+) {
+    for progress in progresses.iter() {
+        if let Ok(mut canvas) = canvases.get_mut(progress.canvas) {
+            canvas.redraw();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::f32::consts::PI;
+
+    #[test]
+    fn radial_progress_sweep() {
+        let mut progress = RadialProgress::from_world_and_params(
+            &mut World::new(),
+            &mut belly_core::eml::Params::default(),
+        );
+        progress.value = 0.5;
+        assert_eq!(progress.sweep(), PI);
+        progress.direction = "ccw".parse().unwrap();
+        assert_eq!(progress.sweep(), -PI);
+        progress.value = 2.;
+        assert_eq!(progress.sweep(), -2. * PI);
+    }
+}
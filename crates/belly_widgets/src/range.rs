@@ -3,7 +3,6 @@ use belly_core::{build::*, impl_properties};
 use belly_macro::*;
 use bevy::prelude::*;
 use std::collections::HashMap;
-use std::str::FromStr;
 
 pub mod prelude {
     pub use super::LayoutMode;
@@ -15,6 +14,7 @@ pub(crate) struct RangePlugin;
 impl Plugin for RangePlugin {
     fn build(&self, app: &mut App) {
         app.register_widget::<RangeWidget>();
+        app.register_widget_states::<Range>();
         app.add_systems(Update, update_range_representation);
         app.add_systems(Update, configure_range_layout);
     }
@@ -30,24 +30,45 @@ impl Plugin for RangePlugin {
 #[param(value:f32 => Range:value|RangeValue.absolute)]
 /// Specifies raltive value in 0..1 range
 #[param(relative:f32 => Range:value|RangeValue.relative)]
+/// Specifies the step the value snaps to, `0` disables snapping
+#[param(step:f32 => Range:value|RangeValue.step)]
 /// <!-- @inline LayoutMode -->
 #[param(mode:LayoutMode => Range:mode)]
+/// The background of the whole range
+#[part(track)]
+/// The span before the value
+#[part(low)]
+/// The span after the value
+#[part(high)]
+/// The tick marks rendered with the `ticks` param
+#[part(tick)]
+/// The `<range>` shows the value in minimum..maximum range. Pass the `ticks`
+/// param to render the number of evenly placed tick marks along the track,
+/// they are exposed as the `tick` part: `range::tick { background-color: red }`
 fn range(ctx: &mut WidgetContext, rng: &mut Range) {
     let holder = rng.holder;
     let low = rng.low_span;
     let hight = rng.high_span;
+    let ticks = rng.ticks.clone();
     ctx.render(eml! {
         <span c:range>
-            <span c:range-back/>
+            <span part="track" c:range-back/>
+            <span c:range-ticks>
+                <for tick in=ticks>
+                    <span {tick} part="tick" c:range-tick
+                        s:left=managed()
+                        s:bottom=managed()/>
+                </for>
+            </span>
             <span {holder} c:range-holder s:flex-direction=managed()>
                 <span {low} c:range-low-internals
                     s:min-height=managed()
                     s:min-width=managed()>
-                    <span c:range-low/>
+                    <span part="low" c:range-low/>
                 </span>
                 <slot define="separator"/>
                 <span {hight} c:range-high-internals>
-                    <span c:range-high/>
+                    <span part="high" c:range-high/>
                 </span>
             </span>
         </span>
@@ -111,6 +132,27 @@ ess_define! {
         left: 6px;
         right: 5px;
     }
+    range .range-ticks {
+        position-type: absolute;
+        left: 11px;
+        right: 11px;
+        top: 11px;
+        bottom: 11px;
+    }
+    range .range-tick {
+        position-type: absolute;
+        background-color: #4f4f4f;
+    }
+    range:horizontal .range-tick {
+        width: 1px;
+        top: -6px;
+        bottom: -6px;
+    }
+    range:vertical .range-tick {
+        height: 1px;
+        left: -6px;
+        right: -6px;
+    }
     /** @layout-aware */
     range .range-holder {
         width: 100%;
@@ -138,6 +180,7 @@ pub struct RangeValue {
     minimum: f32,
     absolute: f32,
     maximum: f32,
+    step: f32,
 }
 
 impl Default for RangeValue {
@@ -146,6 +189,7 @@ impl Default for RangeValue {
             minimum: 0.0,
             absolute: 0.0,
             maximum: 1.0,
+            step: 0.0,
         }
     }
 }
@@ -163,17 +207,37 @@ impl RangeValue {
         self.maximum
     }
 
+    pub fn step(&self) -> f32 {
+        self.step
+    }
+
     pub fn relative(&self) -> f32 {
         (self.absolute - self.minimum) / (self.maximum - self.minimum)
     }
 
     pub fn set_absolute(&mut self, value: f32) {
-        self.absolute = value.min(self.maximum).max(self.minimum);
+        self.absolute = self.snap(value);
     }
 
     pub fn set_relative(&mut self, relative: f32) {
         let relative = relative.min(1.0).max(0.0);
-        self.absolute = self.minimum + relative * (self.maximum - self.minimum);
+        self.absolute = self.snap(self.minimum + relative * (self.maximum - self.minimum));
+    }
+
+    /// Sets the step the value snaps to (counting from the `minimum`).
+    /// Zero or negative step disables snapping.
+    pub fn set_step(&mut self, step: f32) {
+        self.step = step.max(0.0);
+        self.absolute = self.snap(self.absolute);
+    }
+
+    fn snap(&self, value: f32) -> f32 {
+        let value = if self.step > 0.0 {
+            self.minimum + ((value - self.minimum) / self.step).round() * self.step
+        } else {
+            value
+        };
+        value.min(self.maximum).max(self.minimum)
     }
 
     pub fn set_minimum(&mut self, minimum: f32) {
@@ -196,6 +260,7 @@ impl_properties! { RangeValueProperties for RangeValue {
     minimum(set_minimum, minimum) => |v: f32| v.clone();
     maximum(set_maximum, maximum) => |v: f32| v.clone();
     relative(set_relative, relative) => |v: f32| v.clone();
+    step(set_step, step) => |v: f32| v.clone();
 }}
 
 #[derive(Component)]
@@ -206,11 +271,23 @@ pub struct Range {
     pub holder: Entity,
     pub low_span: Entity,
     pub high_span: Entity,
+    pub ticks: Vec<Entity>,
+}
+
+impl WidgetStates for Range {
+    fn states(&self) -> Vec<(Tag, bool)> {
+        vec![
+            (Tag::new("horizontal"), self.mode == LayoutMode::Horizontal),
+            (Tag::new("vertical"), self.mode == LayoutMode::Vertical),
+        ]
+    }
 }
 
 impl FromWorldAndParams for Range {
     fn from_world_and_params(world: &mut World, params: &mut belly_core::eml::Params) -> Self {
+        let ticks: u8 = params.try_get("ticks").unwrap_or_default();
         Range {
+            ticks: (0..ticks).map(|_| world.spawn_empty().id()).collect(),
             value: RangeValue::default(),
             holder: world.spawn_empty().id(),
             low_span: world.spawn_empty().id(),
@@ -220,7 +297,7 @@ impl FromWorldAndParams for Range {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[derive(Clone, Copy, PartialEq, Debug, Default, EnumParam)]
 /// Specifies the widget layout arrange.
 /// <!-- @alter
 /// - `verrtical`: arrange the widget vertically
@@ -234,30 +311,6 @@ pub enum LayoutMode {
     Horizontal,
 }
 
-impl From<LayoutMode> for Variant {
-    fn from(m: LayoutMode) -> Self {
-        Variant::boxed(m)
-    }
-}
-
-impl FromStr for LayoutMode {
-    type Err = String;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "vertical" => Ok(LayoutMode::Vertical),
-            "horizontal" => Ok(LayoutMode::Horizontal),
-            s => Err(format!("Don't know how to parse '{s}' as LayoutMode")),
-        }
-    }
-}
-
-impl TryFrom<Variant> for LayoutMode {
-    type Error = String;
-    fn try_from(value: Variant) -> Result<Self, Self::Error> {
-        value.get_or_parse()
-    }
-}
-
 pub fn update_range_representation(
     ranges: Query<&Range, Or<(Changed<Range>, Changed<Node>)>>,
     nodes: Query<&Node>,
@@ -287,7 +340,6 @@ pub fn update_range_representation(
 }
 
 pub fn configure_range_layout(
-    mut elements: Elements,
     progres_components: Query<(Entity, &Range), Changed<Range>>,
     mut styles: Query<&mut Style>,
     mut configured_modes: Local<HashMap<Entity, LayoutMode>>,
@@ -298,18 +350,6 @@ pub fn configure_range_layout(
             continue;
         }
         configured_modes.insert(entity, mode);
-        {
-            match mode {
-                LayoutMode::Horizontal => {
-                    elements.set_state(entity, Tag::new("horizontal"), true);
-                    elements.set_state(entity, Tag::new("vertical"), false);
-                }
-                LayoutMode::Vertical => {
-                    elements.set_state(entity, Tag::new("horizontal"), false);
-                    elements.set_state(entity, Tag::new("vertical"), true);
-                }
-            }
-        }
         {
             let Ok(mut holder) = styles.get_mut(progress.holder) else {
                 continue;
@@ -328,5 +368,44 @@ pub fn configure_range_layout(
                 LayoutMode::Vertical => low.min_width = Val::Px(0.),
             }
         }
+        let last = progress.ticks.len().saturating_sub(1).max(1) as f32;
+        for (idx, tick) in progress.ticks.iter().enumerate() {
+            let Ok(mut style) = styles.get_mut(*tick) else {
+                continue;
+            };
+            let position = Val::Percent(100. * idx as f32 / last);
+            match mode {
+                LayoutMode::Horizontal => {
+                    style.left = position;
+                    style.bottom = Val::Auto;
+                }
+                LayoutMode::Vertical => {
+                    style.bottom = position;
+                    style.left = Val::Auto;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn range_value_snaps_to_step() {
+        let mut value = RangeValue::default();
+        value.set_maximum(10.);
+        value.set_minimum(2.);
+        value.set_step(3.);
+        value.set_absolute(6.4);
+        assert_eq!(value.absolute(), 5.);
+        value.set_absolute(7.);
+        assert_eq!(value.absolute(), 8.);
+        value.set_relative(1.);
+        assert_eq!(value.absolute(), 10.);
+        value.set_step(0.);
+        value.set_absolute(6.4);
+        assert_eq!(value.absolute(), 6.4);
     }
 }
@@ -0,0 +1,209 @@
+use belly_core::build::*;
+use bevy::asset::embedded_asset;
+use bevy::prelude::*;
+
+pub mod prelude {
+    pub use super::Skin;
+    pub use super::SkinPlugin;
+}
+
+/// The look of the built-in widgets. The skin is the stylesheet added
+/// right above the widget default styles: it overrides the defaults, and
+/// any stylesheet added by the application overrides the skin.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Skin {
+    /// The default widget styles without any overrides
+    #[default]
+    Default,
+    /// Solid colors without shadows and bevels
+    Flat,
+    /// Rounded 9-patch images for buttons, fields and tracks
+    NinePatch,
+}
+
+impl Skin {
+    /// The `ess` source of the skin stylesheet.
+    pub fn styles(&self) -> &'static str {
+        match self {
+            Skin::Default => "",
+            Skin::Flat => FLAT_SKIN,
+            Skin::NinePatch => NINEPATCH_SKIN,
+        }
+    }
+}
+
+/// Adds the [`Skin`] stylesheet, use it with `BellyPlugin::with_skin`.
+pub struct SkinPlugin(pub Skin);
+
+impl Plugin for SkinPlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "skin/button.png");
+        embedded_asset!(app, "skin/button-pressed.png");
+        embedded_asset!(app, "skin/panel.png");
+        embedded_asset!(app, "skin/grabber.png");
+        let skin = self.0;
+        if skin != Skin::Default {
+            // PreStartup: the skin should be weighted below the stylesheets
+            // added by the application
+            app.add_systems(PreStartup, move |mut commands: Commands| {
+                commands.add(StyleSheet::parse(skin.styles()));
+            });
+        }
+    }
+}
+
+const FLAT_SKIN: &str = r#"
+    button {
+        margin: 3px;
+    }
+    .button-shadow {
+        display: none;
+    }
+    .button-background {
+        margin: 0px;
+        padding: 0px;
+        background-color: transparent;
+    }
+    button:active > .button-background {
+        margin: 0px;
+    }
+    button:pressed > .button-background {
+        margin: 0px;
+    }
+    .button-foreground {
+        background-color: #3f51b5;
+        color: #ffffff;
+    }
+    .button-foreground * {
+        color: #ffffff;
+    }
+    button:hover > span > .button-foreground {
+        background-color: #5c6bc0;
+    }
+    button:pressed > span > .button-foreground {
+        background-color: #283593;
+    }
+    buttongroup button {
+        margin: 3px 0px;
+    }
+    range .range-back {
+        background-color: #cfcfcf;
+    }
+    range .range-low {
+        background-color: #3f51b5;
+    }
+    range .range-high {
+        background-color: #cfcfcf;
+    }
+    range .range-tick {
+        background-color: #7f7f7f;
+    }
+    rangeslider .range-back {
+        background-color: #cfcfcf;
+    }
+    rangeslider .rangeslider-fill {
+        background-color: #3f51b5;
+    }
+    .text-input-border {
+        background-color: #3f51b5;
+    }
+    .text-input-background {
+        background-color: #ffffff;
+    }
+    .text-input-selection {
+        background-color: #c5cae9;
+    }
+    .text-input-cursor {
+        background-color: #3f51b5;
+    }
+"#;
+
+const NINEPATCH_SKIN: &str = r#"
+    .button-shadow {
+        display: none;
+    }
+    .button-background {
+        margin: 0px;
+        padding: 0px;
+        background-color: transparent;
+        stylebox: "embedded://belly_widgets/skin/button.png", 4px, 4px, 100%, #dfdfdf;
+    }
+    button:active > .button-background {
+        margin: 0px;
+        stylebox-source: "embedded://belly_widgets/skin/button-pressed.png";
+    }
+    button:pressed > .button-background {
+        margin: 0px;
+        stylebox-source: "embedded://belly_widgets/skin/button-pressed.png";
+        stylebox-modulate: #bfbfbf;
+    }
+    .button-foreground {
+        background-color: transparent;
+        padding: 5px 5px 7px 5px;
+    }
+    button:hover > span > .button-foreground {
+        background-color: transparent;
+    }
+    button:pressed > span > .button-foreground {
+        background-color: transparent;
+        padding: 7px 5px 5px 5px;
+    }
+    range .range-back {
+        background-color: transparent;
+        stylebox: "embedded://belly_widgets/skin/panel.png", 4px, 2px, 100%, #ffffff;
+    }
+    range .range-low {
+        background-color: transparent;
+        stylebox: "embedded://belly_widgets/skin/panel.png", 4px, 3px, 100%, #4f4f4f;
+    }
+    range .range-high {
+        background-color: transparent;
+        stylebox: "embedded://belly_widgets/skin/panel.png", 4px, 3px, 100%, #bfbfbf;
+    }
+    rangeslider .range-back {
+        background-color: transparent;
+        stylebox: "embedded://belly_widgets/skin/panel.png", 4px, 2px, 100%, #ffffff;
+    }
+    rangeslider .rangeslider-fill {
+        background-color: transparent;
+        stylebox: "embedded://belly_widgets/skin/panel.png", 4px, 3px, 100%, #4f4f4f;
+    }
+    slider .slider-grabber .button-background {
+        stylebox: "embedded://belly_widgets/skin/grabber.png", 4px, 4px, 100%, #dfdfdf;
+    }
+    rangeslider .slider-grabber .button-background {
+        stylebox: "embedded://belly_widgets/skin/grabber.png", 4px, 4px, 100%, #dfdfdf;
+    }
+    .text-input-border {
+        background-color: transparent;
+        padding: 2px;
+        stylebox: "embedded://belly_widgets/skin/panel.png", 4px, 4px, 100%, #2f2f2f;
+    }
+    .text-input-background {
+        background-color: transparent;
+        stylebox: "embedded://belly_widgets/skin/panel.png", 4px, 4px, 100%, #efefef;
+    }
+"#;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use belly_core::ess::{
+        PropertyExtractor, PropertyPlugin, PropertyTransformer, StyleSheetParser,
+    };
+
+    #[test]
+    fn skins_parse_all_rules() {
+        let mut app = App::new();
+        app.add_plugins(PropertyPlugin);
+        let extractor = app.world.resource::<PropertyExtractor>().clone();
+        let transformer = app.world.resource::<PropertyTransformer>().clone();
+        let parser = StyleSheetParser::new(transformer, extractor);
+        for skin in [Skin::Flat, Skin::NinePatch] {
+            let expected = skin.styles().matches('{').count();
+            let (rules, diagnostics) = parser.parse_with_diagnostics(skin.styles(), None);
+            assert_eq!(rules.len(), expected, "{skin:?} has invalid rules");
+            assert!(diagnostics.is_empty(), "{skin:?}: {diagnostics:?}");
+        }
+    }
+}
@@ -6,7 +6,11 @@
 use bevy::{
     prelude::*,
     render::{Extract, RenderApp},
-    ui::{ExtractedUiNode, ExtractedUiNodes, FocusPolicy, RenderUiSystem, UiStack},
+    transform::TransformSystem,
+    ui::{
+        DefaultUiCamera, ExtractedUiNode, ExtractedUiNodes, FocusPolicy, RenderUiSystem, UiStack,
+    },
+    window::PrimaryWindow,
 };
 
 /// `Stylebox` plugin for `bevy` engine. Dont forget to register it:
@@ -30,7 +34,10 @@ const ONE_MINUS_TWO_EPSILONS: f32 = ONE_MINUS_EPSILON - EPSILON;
 impl Plugin for StyleboxPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(Update, compute_stylebox_configuration)
-            .add_systems(PostUpdate, compute_stylebox_slices)
+            .add_systems(
+                PostUpdate,
+                compute_stylebox_slices.after(TransformSystem::TransformPropagate),
+            )
             .sub_app_mut(RenderApp)
             .add_systems(
                 ExtractSchedule,
@@ -88,6 +95,17 @@ pub struct Stylebox {
     pub region: UiRect,
     /// specifies what color the original image should be multiplied by
     pub modulate: Color,
+    /// rounds the edges of the slices to the physical pixels, so there are
+    /// no seams between the slices at the fractional scale factors
+    pub pixel_snap: bool,
+    /// mirrors the `region` horizontally before slicing it
+    pub flip_x: bool,
+    /// mirrors the `region` vertically before slicing it
+    pub flip_y: bool,
+    /// the number of the image pixels per ui pixel, `2.` for the image drawn at
+    /// the double resolution. The `Val::Px` values of `slice`, `region` and `width`
+    /// are in ui pixels, the edges of the image are resized by the scale.
+    pub texture_scale: f32,
 }
 
 impl Default for Stylebox {
@@ -98,6 +116,10 @@ impl Default for Stylebox {
             width: UiRect::all(Val::Percent(100.)),
             modulate: Color::WHITE,
             region: UiRect::all(Val::Px(0.)),
+            pixel_snap: false,
+            flip_x: false,
+            flip_y: false,
+            texture_scale: 1.,
         }
     }
 }
@@ -142,6 +164,10 @@ pub struct ComputedStylebox {
     // edges width relative to slices edges
     width: UiRectF32,
     region: Rect,
+    pixel_snap: bool,
+    flip_x: bool,
+    flip_y: bool,
+    texture_scale: f32,
 }
 /// Calculates exact values for `Stylebox` when image size is available and stores it into `ComputedStylebox`.
 pub fn compute_stylebox_configuration(
@@ -173,27 +199,28 @@ pub fn compute_stylebox_configuration(
             }
             Some(image) => {
                 let size = image.size();
+                let scale = stylebox.texture_scale.max(f32::EPSILON);
 
                 let (size_x, size_y) = (size.x as f32, size.y as f32);
 
                 let region_left = match stylebox.region.left {
                     Val::Percent(percent) => size_x * percent * 0.01,
-                    Val::Px(px) => px,
+                    Val::Px(px) => px * scale,
                     _ => 0.,
                 };
                 let region_right = match stylebox.region.right {
                     Val::Percent(percent) => size_x * percent * 0.01,
-                    Val::Px(px) => px,
+                    Val::Px(px) => px * scale,
                     _ => 0.,
                 };
                 let region_top = match stylebox.region.top {
                     Val::Percent(percent) => size_y * percent * 0.01,
-                    Val::Px(px) => px,
+                    Val::Px(px) => px * scale,
                     _ => 0.,
                 };
                 let region_bottom = match stylebox.region.bottom {
                     Val::Percent(percent) => size_y * percent * 0.01,
-                    Val::Px(px) => px,
+                    Val::Px(px) => px * scale,
                     _ => 0.,
                 };
                 let region = Rect {
@@ -208,44 +235,44 @@ pub fn compute_stylebox_configuration(
 
                 let slice_left = match stylebox.slice.left {
                     Val::Percent(percent) => percent * 0.01,
-                    Val::Px(px) => px / size_x,
+                    Val::Px(px) => px * scale / size_x,
                     _ => 0.5,
                 };
                 let slice_right = match stylebox.slice.right {
                     Val::Percent(percent) => percent * 0.01,
-                    Val::Px(px) => px / size_x,
+                    Val::Px(px) => px * scale / size_x,
                     _ => 0.5,
                 };
                 let slice_top = match stylebox.slice.top {
                     Val::Percent(percent) => percent * 0.01,
-                    Val::Px(px) => px / size_y,
+                    Val::Px(px) => px * scale / size_y,
                     _ => 0.5,
                 };
                 let slice_bottom = match stylebox.slice.bottom {
                     Val::Percent(percent) => percent * 0.01,
-                    Val::Px(px) => px / size_y,
+                    Val::Px(px) => px * scale / size_y,
                     _ => 0.5,
                 };
                 let slice = UiRectF32::new(slice_left, slice_right, slice_top, slice_bottom);
 
                 let width_left = match stylebox.width.left {
                     Val::Percent(percent) => percent * 0.01,
-                    Val::Px(px) => px / (size_x * slice.left),
+                    Val::Px(px) => px * scale / (size_x * slice.left),
                     _ => 1.0,
                 };
                 let width_right = match stylebox.width.right {
                     Val::Percent(percent) => percent * 0.01,
-                    Val::Px(px) => px / (size_x * slice.right),
+                    Val::Px(px) => px * scale / (size_x * slice.right),
                     _ => 1.0,
                 };
                 let width_top = match stylebox.width.top {
                     Val::Percent(percent) => percent * 0.01,
-                    Val::Px(px) => px / (size_y * slice.top),
+                    Val::Px(px) => px * scale / (size_y * slice.top),
                     _ => 1.0,
                 };
                 let width_bottom = match stylebox.width.bottom {
                     Val::Percent(percent) => percent * 0.01,
-                    Val::Px(px) => px / (size_y * slice.bottom),
+                    Val::Px(px) => px * scale / (size_y * slice.bottom),
                     _ => 1.0,
                 };
                 let width = UiRectF32::new(width_left, width_right, width_top, width_bottom);
@@ -254,6 +281,10 @@ pub fn compute_stylebox_configuration(
                     computed.region = region;
                     computed.slice = slice;
                     computed.width = width;
+                    computed.pixel_snap = stylebox.pixel_snap;
+                    computed.flip_x = stylebox.flip_x;
+                    computed.flip_y = stylebox.flip_y;
+                    computed.texture_scale = scale;
                 } else {
                     commands
                         .entity(entity)
@@ -262,6 +293,10 @@ pub fn compute_stylebox_configuration(
                             region,
                             slice,
                             width,
+                            pixel_snap: stylebox.pixel_snap,
+                            flip_x: stylebox.flip_x,
+                            flip_y: stylebox.flip_y,
+                            texture_scale: scale,
                         });
                 }
             }
@@ -272,6 +307,8 @@ pub fn compute_stylebox_configuration(
 struct StyleboxSlice {
     transform: Mat4,
     region: Rect,
+    flip_x: bool,
+    flip_y: bool,
 }
 
 #[derive(Component, Default)]
@@ -279,14 +316,28 @@ struct StyleboxSlice {
 pub struct StyleboxSlices {
     items: Vec<StyleboxSlice>,
 }
-/// Calculates transforms for each slice based on `Node.size()` and `ComputedStylebox`
+/// Calculates transforms for each slice based on `Node.size()` and `ComputedStylebox`.
+/// All the slices are recalculated when the scale factor changes, the slices of the
+/// styleboxes with `pixel_snap` are recalculated when the node moves as well.
 pub fn compute_stylebox_slices(
-    mut query: Query<
-        (&mut StyleboxSlices, &Node, &ComputedStylebox),
-        Or<(Changed<Node>, Changed<ComputedStylebox>)>,
-    >,
+    mut last_scale_factor: Local<f32>,
+    ui_scale: Res<UiScale>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut query: Query<(
+        &mut StyleboxSlices,
+        Ref<Node>,
+        Ref<GlobalTransform>,
+        Ref<ComputedStylebox>,
+    )>,
 ) {
-    for (mut transforms, uinode, stylebox) in query.iter_mut() {
+    let scale_factor = windows.get_single().map_or(1., |w| w.scale_factor()) * ui_scale.0;
+    let rescaled = *last_scale_factor != scale_factor;
+    *last_scale_factor = scale_factor;
+    for (mut transforms, uinode, transform, stylebox) in query.iter_mut() {
+        let moved = stylebox.pixel_snap && transform.is_changed();
+        if !rescaled && !moved && !uinode.is_changed() && !stylebox.is_changed() {
+            continue;
+        }
         if uinode.size() == Vec2::ZERO {
             continue;
         }
@@ -302,17 +353,37 @@ pub fn compute_stylebox_slices(
         let bot = stylebox.slice.bottom;
 
         // compute part sizes in uinode space
-        let w0 = left * rsize.x * stylebox.width.left;
-        let w2 = right * rsize.x * stylebox.width.right;
+        let ui_rsize = rsize / stylebox.texture_scale.max(f32::EPSILON);
+        let w0 = left * ui_rsize.x * stylebox.width.left;
+        let w2 = right * ui_rsize.x * stylebox.width.right;
         let w1 = size.x - w0 - w2;
-        let h0 = top * rsize.y * stylebox.width.top;
-        let h2 = bot * rsize.y * stylebox.width.bottom;
+        let h0 = top * ui_rsize.y * stylebox.width.top;
+        let h2 = bot * ui_rsize.y * stylebox.width.bottom;
         let h1 = size.y - h0 - h2;
 
-        let ui_x = &[0., w0, w0 + w1];
-        let ui_y = &[0., h0, h0 + h1];
-        let ui_width = &[w0, w1, w2];
-        let ui_height = &[h0, h1, h2];
+        // the edges of the slices in uinode space
+        let mut edges_x = [0., w0, w0 + w1, size.x];
+        let mut edges_y = [0., h0, h0 + h1, size.y];
+        if stylebox.pixel_snap {
+            let origin = transform.translation().truncate() - size * 0.5;
+            let snap = |edge: f32, origin: f32| {
+                ((origin + edge) * scale_factor).round() / scale_factor - origin
+            };
+            edges_x = edges_x.map(|edge| snap(edge, origin.x));
+            edges_y = edges_y.map(|edge| snap(edge, origin.y));
+        }
+        let ui_x = &[edges_x[0], edges_x[1], edges_x[2]];
+        let ui_y = &[edges_y[0], edges_y[1], edges_y[2]];
+        let ui_width = &[
+            edges_x[1] - edges_x[0],
+            edges_x[2] - edges_x[1],
+            edges_x[3] - edges_x[2],
+        ];
+        let ui_height = &[
+            edges_y[1] - edges_y[0],
+            edges_y[2] - edges_y[1],
+            edges_y[3] - edges_y[2],
+        ];
 
         // make sure there is a minimum gap betwenn 0, left, right and 1
         let (left, right) = normalize_axis(left, right);
@@ -346,10 +417,21 @@ pub fn compute_stylebox_slices(
                     max: Vec2::new(ui_x[row] + ui_width[row], ui_y[col] + ui_height[col]),
                 };
 
-                let imgrect = Rect {
+                let mut imgrect = Rect {
                     min: Vec2::new(img_x[row], img_y[col]),
                     max: Vec2::new(img_x[row] + img_width[row], img_y[col] + img_height[col]),
                 };
+                // mirror the slice inside of the region
+                if stylebox.flip_x {
+                    let (min, max) = (imgrect.min.x, imgrect.max.x);
+                    imgrect.min.x = 2. * rpos.x + rsize.x - max;
+                    imgrect.max.x = 2. * rpos.x + rsize.x - min;
+                }
+                if stylebox.flip_y {
+                    let (min, max) = (imgrect.min.y, imgrect.max.y);
+                    imgrect.min.y = 2. * rpos.y + rsize.y - max;
+                    imgrect.max.y = 2. * rpos.y + rsize.y - min;
+                }
 
                 let center = 0.5 * (uirect.min + uirect.max);
                 let offset = center - size * 0.5;
@@ -360,6 +442,8 @@ pub fn compute_stylebox_slices(
                 transforms.items.push(StyleboxSlice {
                     transform: tr,
                     region: imgrect,
+                    flip_x: stylebox.flip_x,
+                    flip_y: stylebox.flip_y,
                 });
             }
         }
@@ -392,11 +476,14 @@ fn normalize_axis(left: f32, right: f32) -> (f32, f32) {
 }
 
 /// Extracts stylebox vertices into render pipeline based on `Stylebox.texture`,
-/// `Stylebox.modulate` and `StyleboxSlices`
+/// `Stylebox.modulate` and `StyleboxSlices`. Every slice is extracted as the
+/// separate ui node.
 pub fn extract_stylebox(
+    mut commands: Commands,
     mut extracted_uinodes: ResMut<ExtractedUiNodes>,
     ui_stack: Extract<Res<UiStack>>,
     images: Extract<Res<Assets<Image>>>,
+    default_ui_camera: Extract<DefaultUiCamera>,
     uinode_query: Extract<
         Query<(
             &Node,
@@ -405,11 +492,17 @@ pub fn extract_stylebox(
             &StyleboxSlices,
             &InheritedVisibility,
             Option<&CalculatedClip>,
+            Option<&TargetCamera>,
         )>,
     >,
 ) {
     for (stack_index, entity) in ui_stack.uinodes.iter().enumerate() {
-        let Ok((uinode, transform, stylebox, slices, visibility, clip)) = uinode_query.get(*entity)
+        let Ok((uinode, transform, stylebox, slices, visibility, clip, camera)) =
+            uinode_query.get(*entity)
+        else {
+            continue;
+        };
+        let Some(camera_entity) = camera.map(TargetCamera::entity).or(default_ui_camera.get())
         else {
             continue;
         };
@@ -435,8 +528,9 @@ pub fn extract_stylebox(
         let img_size = Vec2::new(img_size.x as f32, img_size.y as f32);
 
         for patch in slices.items.iter() {
+            // the entity itself is taken by the background of the node
             extracted_uinodes.uinodes.insert(
-                *entity,
+                commands.spawn_empty().id(),
                 ExtractedUiNode {
                     transform: tr * patch.transform,
                     color: stylebox.modulate,
@@ -445,9 +539,9 @@ pub fn extract_stylebox(
                     atlas_size: Some(img_size),
                     clip: clip.map(|clip| clip.clip),
                     stack_index: stack_index as u32,
-                    flip_x: false,
-                    flip_y: false,
-                    camera_entity: Entity::PLACEHOLDER
+                    flip_x: patch.flip_x,
+                    flip_y: patch.flip_y,
+                    camera_entity,
                 },
             );
 
@@ -31,20 +31,16 @@ fn setup(mut commands: Commands) {
         <body>
             <span c:controls>
                 <slider c:red
-                    bind:value=to!(colorbox, BackgroundColor:0|r)
-                    bind:value=from!(colorbox, BackgroundColor:0.r())
+                    bind:value=sync!(colorbox, BackgroundColor:0|r)
                 />
                 <slider c:green
-                    bind:value=to!(colorbox, BackgroundColor:0|g)
-                    bind:value=from!(colorbox, BackgroundColor:0.g())
+                    bind:value=sync!(colorbox, BackgroundColor:0|g)
                 />
                 <slider c:blue
-                    bind:value=to!(colorbox, BackgroundColor:0|b)
-                    bind:value=from!(colorbox, BackgroundColor:0.b())
+                    bind:value=sync!(colorbox, BackgroundColor:0|b)
                 />
                 <slider c:alpha
-                    bind:value=to!(colorbox, BackgroundColor:0|a)
-                    bind:value=from!(colorbox, BackgroundColor:0.a())
+                    bind:value=sync!(colorbox, BackgroundColor:0|a)
                 />
             </span>
             <img c:colorbox-holder src="trbg.png">
@@ -0,0 +1,145 @@
+//! The [`bevy-inspector-egui`](https://github.com/jakobhellermann/bevy-inspector-egui)
+//! integration, enabled with the `inspector-egui` feature. The
+//! [`BellyInspectorPlugin`] mirrors every element into the [`ElementInspect`]
+//! component, so the element, its resolved styles, binds and connections
+//! are shown by the `WorldInspectorPlugin` next to the other components:
+//! ```rust,ignore
+//! App::new()
+//!     .add_plugins(DefaultPlugins)
+//!     .add_plugins(BellyPlugin)
+//!     .add_plugins(BellyInspectorPlugin)
+//!     .add_plugins(bevy_inspector_egui::quick::WorldInspectorPlugin::new())
+//!     .run();
+//! ```
+//! Editing `classes` or `states` of the `ElementInspect` applies them
+//! back to the element.
+use belly_core::element::{Element, Elements};
+use belly_core::relations::RelationsRegistry;
+use belly_widgets::devtools::Inspector;
+use bevy::{prelude::*, utils::HashSet};
+use tagstr::Tag;
+
+pub struct BellyInspectorPlugin;
+
+impl Plugin for BellyInspectorPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ElementInspect>();
+        app.add_systems(
+            PostUpdate,
+            (apply_element_edits_system, sync_element_inspect_system).chain(),
+        );
+    }
+}
+
+/// The inspectable mirror of the [`Element`].
+#[derive(Component, Reflect, Default, Debug, Clone)]
+#[reflect(Component)]
+pub struct ElementInspect {
+    /// The element formatted like the selector: `button#save.primary:hover`
+    pub element: String,
+    /// The element and its ancestors
+    pub path: String,
+    /// The space-separated classes, editable
+    pub classes: String,
+    /// The space-separated states, editable
+    pub states: String,
+    /// The matched rules with their origin and specificity
+    pub rules: Vec<String>,
+    /// The applied property values with their origin
+    pub properties: Vec<String>,
+    pub binds: Vec<String>,
+    pub connections: Vec<String>,
+    #[reflect(ignore)]
+    applied_classes: String,
+    #[reflect(ignore)]
+    applied_states: String,
+}
+
+fn words(value: &str) -> HashSet<Tag> {
+    value.split_whitespace().map(Tag::new).collect()
+}
+
+fn sorted(tags: &HashSet<Tag>) -> String {
+    let mut tags: Vec<_> = tags.iter().map(|t| t.as_str()).collect();
+    tags.sort();
+    tags.join(" ")
+}
+
+fn apply_element_edits_system(
+    mut inspects: Query<(Entity, &mut ElementInspect), Changed<ElementInspect>>,
+    mut elements: Elements,
+) {
+    for (entity, mut inspect) in inspects.iter_mut() {
+        if inspect.classes != inspect.applied_classes {
+            let (old, new) = (words(&inspect.applied_classes), words(&inspect.classes));
+            old.difference(&new)
+                .for_each(|class| elements.remove_class(entity, *class));
+            new.difference(&old)
+                .for_each(|class| elements.add_class(entity, *class));
+            let inspect = inspect.bypass_change_detection();
+            inspect.applied_classes = inspect.classes.clone();
+        }
+        if inspect.states != inspect.applied_states {
+            let (old, new) = (words(&inspect.applied_states), words(&inspect.states));
+            old.difference(&new)
+                .for_each(|state| elements.set_state(entity, *state, false));
+            new.difference(&old)
+                .for_each(|state| elements.set_state(entity, *state, true));
+            let inspect = inspect.bypass_change_detection();
+            inspect.applied_states = inspect.states.clone();
+        }
+    }
+}
+
+fn sync_element_inspect_system(
+    mut commands: Commands,
+    changed: Query<Entity, Changed<Element>>,
+    mut inspects: Query<&mut ElementInspect>,
+    registry: Res<RelationsRegistry>,
+    inspector: Inspector,
+    elements: Elements,
+) {
+    let entities: Vec<_> = if registry.is_changed() {
+        elements.iter().map(|e| e.entity).collect()
+    } else {
+        changed.iter().collect()
+    };
+    for entity in entities {
+        let Some(inspection) = inspector.inspect(entity, &elements) else {
+            continue;
+        };
+        let Ok(element) = elements.get(entity) else {
+            continue;
+        };
+        let classes = sorted(&element.classes);
+        let states = sorted(&element.state);
+        let value = ElementInspect {
+            element: inspection.element,
+            path: elements.path(entity),
+            classes: classes.clone(),
+            states: states.clone(),
+            rules: inspection
+                .rules
+                .iter()
+                .map(|r| {
+                    let (selector, specificity) = (&r.selector, r.specificity);
+                    format!("{selector}  [{specificity}]  {}:{}", r.origin, r.location)
+                })
+                .collect(),
+            properties: inspection.properties,
+            binds: registry.binds_of(entity).map(|b| b.to_string()).collect(),
+            connections: registry
+                .connections_of(entity)
+                .map(|c| c.to_string())
+                .collect(),
+            applied_classes: classes,
+            applied_states: states,
+        };
+        match inspects.get_mut(entity) {
+            Ok(mut inspect) => *inspect.bypass_change_detection() = value,
+            Err(_) => {
+                commands.entity(entity).insert(value);
+            }
+        }
+    }
+}
@@ -46,20 +46,16 @@
 //!         <body>
 //!             <span c:controls>
 //!                 <slider c:red
-//!                     bind:value=to!(colorbox, BackgroundColor:0|r)
-//!                     bind:value=from!(colorbox, BackgroundColor:0.r())
+//!                     bind:value=sync!(colorbox, BackgroundColor:0|r)
 //!                 />
 //!                 <slider c:green
-//!                     bind:value=to!(colorbox, BackgroundColor:0|g)
-//!                     bind:value=from!(colorbox, BackgroundColor:0.g())
+//!                     bind:value=sync!(colorbox, BackgroundColor:0|g)
 //!                 />
 //!                 <slider c:blue
-//!                     bind:value=to!(colorbox, BackgroundColor:0|b)
-//!                     bind:value=from!(colorbox, BackgroundColor:0.b())
+//!                     bind:value=sync!(colorbox, BackgroundColor:0|b)
 //!                 />
 //!                 <slider c:alpha
-//!                     bind:value=to!(colorbox, BackgroundColor:0|a)
-//!                     bind:value=from!(colorbox, BackgroundColor:0.a())
+//!                     bind:value=sync!(colorbox, BackgroundColor:0|a)
 //!                 />
 //!             </span>
 //!             <img c:colorbox-holder src="trbg.png">
@@ -88,22 +84,43 @@
 //!
 #![doc = ::embed_doc_image::embed_image!("color_picker", "docs/img/examples/color-picker.gif")]
 
+// lets the `eml!` expand to `::belly::core` inside the crate
+extern crate self as belly;
+
 pub use belly_core as core;
 pub use belly_widgets as widgets;
 
+#[cfg(feature = "inspector-egui")]
+pub mod inspector;
+pub mod test;
+
 /// `use belly::prelude::*` for adding the UI to your project
 pub mod prelude {
     use belly_core::ElementsCorePlugin;
+    use belly_widgets::skin::{Skin, SkinPlugin};
     use belly_widgets::WidgetsPlugin;
     use bevy::prelude::*;
 
     pub use belly_core::prelude::*;
     pub use belly_macro::eml;
+    pub use belly_macro::EnumParam;
     pub use belly_macro::ess;
+    pub use belly_macro::IntoParams;
     pub use belly_macro::run;
     pub use belly_widgets::prelude::*;
 
     pub struct BellyPlugin;
+    impl BellyPlugin {
+        /// Adds the plugin with the built-in widgets styled by the `skin`:
+        /// ```rust
+        /// # use belly::prelude::*;
+        /// # use bevy::prelude::*;
+        /// App::new().add_plugins(BellyPlugin::with_skin(Skin::Flat));
+        /// ```
+        pub fn with_skin(skin: Skin) -> (BellyPlugin, SkinPlugin) {
+            (BellyPlugin, SkinPlugin(skin))
+        }
+    }
     impl Plugin for BellyPlugin {
         fn build(&self, app: &mut App) {
             app.add_plugins(ElementsCorePlugin);
@@ -0,0 +1,661 @@
+//! The headless harness for testing the `belly` UIs without a window
+//! or a renderer. The [`TestApp`] runs the same style, bind and signal
+//! schedules as the real app, so the tests can spawn the `eml!` trees,
+//! feed the pointer input and check the elements, their styles and
+//! the emitted signals:
+//! ```rust,ignore
+//! use belly::prelude::*;
+//! use belly::test::TestApp;
+//! use belly::widgets::input::button::BtnEvent;
+//!
+//! #[test]
+//! fn button_press_changes_label() {
+//!     let mut app = TestApp::new();
+//!     app.capture::<BtnEvent>();
+//!     let label = app.world().spawn_empty().id();
+//!     app.spawn(eml! {
+//!         <body>
+//!             <button id="ok" on:press=run!(for label |t: &mut Text| {
+//!                 t.sections[0].value = "pressed".into()
+//!             })>
+//!                 <span {label}>"idle"</span>
+//!             </button>
+//!         </body>
+//!     });
+//!     app.click("#ok");
+//!     assert_eq!(app.signals::<BtnEvent>().len(), 2);
+//!     assert_eq!(app.get::<Text>(label).unwrap().sections[0].value, "pressed");
+//! }
+//! ```
+use crate::prelude::*;
+use belly_core::eml::Eml;
+use belly_core::input::{PointerInput, PointerInputData};
+use belly_widgets::devtools::Inspector;
+use bevy::{
+    ecs::system::{Command, RunSystemOnce},
+    input::InputPlugin,
+    prelude::*,
+    ui::UiStack,
+    window::{ExitCondition, WindowPlugin},
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tagstr::Tag;
+
+/// The `App` with the `BellyPlugin` and the minimal set of bevy plugins
+/// required to run it headless. The layout is not computed, so the
+/// `Node` sizes stay zero, but the elements, styles, binds and signals
+/// work the same way they work in the real app.
+pub struct TestApp {
+    pub app: App,
+    /// The number of updates [`TestApp::pump`] runs, the default three are
+    /// enough for the changes to reach the styles and for the signals
+    /// to reach their handlers.
+    pub frames: usize,
+}
+
+impl Default for TestApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestApp {
+    pub fn new() -> TestApp {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            InputPlugin,
+            WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                close_when_requested: false,
+            },
+        ));
+        app.init_asset::<Image>();
+        app.init_asset::<Font>();
+        app.init_asset::<TextureAtlasLayout>();
+        app.init_resource::<UiStack>();
+        app.add_plugins(BellyPlugin);
+        let mut test = TestApp { app, frames: 3 };
+        test.pump();
+        test
+    }
+
+    pub fn world(&mut self) -> &mut World {
+        &mut self.app.world
+    }
+
+    /// Applies the command (the `eml!` tree, the `ess!` stylesheet, etc.)
+    /// and pumps the app.
+    pub fn add<C: Command>(&mut self, command: C) -> &mut Self {
+        command.apply(&mut self.app.world);
+        self.pump();
+        self
+    }
+
+    /// Builds the `eml!` tree, pumps the app and returns the root entity.
+    pub fn spawn(&mut self, eml: Eml) -> Entity {
+        let root = eml.build(&mut self.app.world);
+        self.pump();
+        root
+    }
+
+    /// Runs the app [`TestApp::frames`] times.
+    pub fn pump(&mut self) -> &mut Self {
+        for _ in 0..self.frames {
+            self.app.update();
+        }
+        self
+    }
+
+    /// Returns the entities matching the `ess` query.
+    pub fn select(&mut self, query: &str) -> Vec<Entity> {
+        let query = query.to_string();
+        self.app
+            .world
+            .run_system_once(move |elements: Elements| elements.matching(&query))
+    }
+
+    /// Returns the single entity matching the `ess` query.
+    /// Panics if there are no matches or there are more than one.
+    pub fn one(&mut self, query: &str) -> Entity {
+        match self.select(query).as_slice() {
+            [entity] => *entity,
+            entities => panic!(
+                "Expected one element matching '{query}', found {}",
+                entities.len()
+            ),
+        }
+    }
+
+    /// Returns the element and its ancestors formatted like the
+    /// selector: `body div.inventory button:hover`
+    pub fn path(&mut self, entity: Entity) -> String {
+        self.app
+            .world
+            .run_system_once(move |elements: Elements| elements.path(entity))
+    }
+
+    pub fn element(&self, entity: Entity) -> &Element {
+        self.app
+            .world
+            .get::<Element>(entity)
+            .unwrap_or_else(|| panic!("{entity:?} is not an element"))
+    }
+
+    /// Returns the component of the entity, handy for checking
+    /// the resolved styles (`Style`, `BackgroundColor`, etc.).
+    pub fn get<C: Component>(&self, entity: Entity) -> Option<&C> {
+        self.app.world.get::<C>(entity)
+    }
+
+    pub fn has_class(&self, entity: Entity, class: &str) -> bool {
+        self.element(entity).classes.contains(&Tag::new(class))
+    }
+
+    pub fn has_state(&self, entity: Entity, state: &str) -> bool {
+        self.element(entity).state.contains(&Tag::new(state))
+    }
+
+    /// Starts recording the `E` events (signals), read them
+    /// with [`TestApp::signals`].
+    pub fn capture<E: Event + Clone>(&mut self) -> &mut Self {
+        if !self.app.world.contains_resource::<Captured<E>>() {
+            self.app.world.insert_resource(Captured::<E>(vec![]));
+            self.app.add_systems(Last, capture_events_system::<E>);
+        }
+        self
+    }
+
+    /// Takes the `E` events recorded since the last call.
+    /// Panics if the events are not captured.
+    pub fn signals<E: Event + Clone>(&mut self) -> Vec<E> {
+        let Some(mut captured) = self.app.world.get_resource_mut::<Captured<E>>() else {
+            panic!(
+                "{} is not captured, call TestApp::capture first",
+                std::any::type_name::<E>()
+            );
+        };
+        std::mem::take(&mut captured.0)
+    }
+
+    /// Sends the [`PointerInput`] to the entities matching the
+    /// target and pumps the app.
+    pub fn pointer(&mut self, target: impl PointerTarget, data: PointerInputData) -> &mut Self {
+        let entities = target.entities(self);
+        self.app.world.send_event(PointerInput {
+            entities,
+            pos: Vec2::ZERO,
+            delta: Vec2::ZERO,
+            data,
+        });
+        self.pump()
+    }
+
+    /// Moves the pointer over the target, the previously
+    /// hovered elements lose the `:hover` state.
+    pub fn hover(&mut self, target: impl PointerTarget) -> &mut Self {
+        self.pointer(target, PointerInputData::Motion)
+    }
+
+    /// Presses the pointer down over the target.
+    pub fn press(&mut self, target: impl PointerTarget) -> &mut Self {
+        self.pointer(target, PointerInputData::Down { presses: 1 })
+    }
+
+    /// Releases the pointer over the target.
+    pub fn release(&mut self, target: impl PointerTarget) -> &mut Self {
+        let entities = target.entities(self);
+        self.app.world.send_event(PointerInput {
+            entities: entities.clone(),
+            pos: Vec2::ZERO,
+            delta: Vec2::ZERO,
+            data: PointerInputData::Up { presses: 1 },
+        });
+        self.pointer(entities, PointerInputData::Pressed { presses: 1 })
+    }
+
+    /// Presses and releases the pointer over the target.
+    pub fn click(&mut self, target: impl PointerTarget) -> &mut Self {
+        let entities = target.entities(self);
+        self.press(entities.clone()).release(entities)
+    }
+
+    /// Serializes the `root` subtree to the stable text: the element
+    /// selectors (without the entity ids), the resolved properties
+    /// (`| name: value`) and the text content, indented by the depth:
+    /// ```text
+    /// body
+    ///   | width: 100%
+    ///   button#save.primary
+    ///     | width: 100px
+    ///     "Save"
+    /// ```
+    /// The virtual elements are skipped, their children (and the text
+    /// of the text nodes) are shown at the virtual element depth.
+    pub fn snapshot(&mut self, root: Entity) -> String {
+        self.app.world.run_system_once(
+            move |elements: Elements,
+                  inspector: Inspector,
+                  children: Query<&Children>,
+                  texts: Query<&Text>| {
+                let mut snapshot = String::new();
+                write_snapshot(root, 0, &mut snapshot, &elements, &inspector, &children, &texts);
+                snapshot
+            },
+        )
+    }
+
+    /// Compares the [`TestApp::snapshot`] of the `root` with the golden
+    /// file. The relative `path` is resolved against the crate manifest
+    /// directory. The missing golden file is written instead of compared,
+    /// set the `BELLY_UPDATE_SNAPSHOTS` env variable to rewrite the existing
+    /// ones after the intended changes.
+    pub fn assert_snapshot(&mut self, root: Entity, path: impl AsRef<Path>) {
+        let snapshot = self.snapshot(root);
+        let mut golden = std::env::var_os("CARGO_MANIFEST_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_default();
+        golden.push(path);
+        let update = std::env::var_os("BELLY_UPDATE_SNAPSHOTS").is_some();
+        if update || !golden.exists() {
+            if let Some(dir) = golden.parent() {
+                fs::create_dir_all(dir).unwrap();
+            }
+            fs::write(&golden, snapshot).unwrap();
+            return;
+        }
+        let expected = fs::read_to_string(&golden).unwrap();
+        if expected != snapshot {
+            let diff = expected
+                .lines()
+                .zip(snapshot.lines())
+                .position(|(expected, actual)| expected != actual)
+                .unwrap_or(expected.lines().count().min(snapshot.lines().count()));
+            panic!(
+                "Snapshot {} differs at line {}, run with BELLY_UPDATE_SNAPSHOTS=1 to update it.\n\
+                 expected:\n{expected}\nactual:\n{snapshot}",
+                golden.display(),
+                diff + 1
+            );
+        }
+    }
+}
+
+fn write_snapshot(
+    entity: Entity,
+    depth: usize,
+    snapshot: &mut String,
+    elements: &Elements,
+    inspector: &Inspector,
+    children: &Query<&Children>,
+    texts: &Query<&Text>,
+) {
+    let mut depth = depth;
+    if let Ok(element) = elements.get(entity) {
+        if !element.is_virtual() {
+            let indent = "  ".repeat(depth);
+            snapshot.push_str(&format!("{indent}{}\n", &*element));
+            let properties = inspector
+                .inspect(entity, elements)
+                .map(|i| i.properties)
+                .unwrap_or_default();
+            for property in properties {
+                // drop the origin, it depends on the stylesheets load order
+                let property = property.split("  (").next().unwrap_or_default();
+                snapshot.push_str(&format!("{indent}  | {property}\n"));
+            }
+            depth += 1;
+        }
+    }
+    // the text nodes are virtual, the text is shown as the parent content
+    if let Ok(text) = texts.get(entity) {
+        let indent = "  ".repeat(depth);
+        let value: String = text.sections.iter().map(|s| s.value.as_str()).collect();
+        snapshot.push_str(&format!("{indent}{value:?}\n"));
+    }
+    for child in children.get(entity).into_iter().flat_map(|c| c.iter()) {
+        write_snapshot(*child, depth, snapshot, elements, inspector, children, texts);
+    }
+}
+
+/// The target of the synthesized pointer input: the entity, the list
+/// of entities or the `ess` query.
+pub trait PointerTarget {
+    fn entities(self, app: &mut TestApp) -> Vec<Entity>;
+}
+
+impl PointerTarget for Entity {
+    fn entities(self, _: &mut TestApp) -> Vec<Entity> {
+        vec![self]
+    }
+}
+
+impl PointerTarget for Vec<Entity> {
+    fn entities(self, _: &mut TestApp) -> Vec<Entity> {
+        self
+    }
+}
+
+impl PointerTarget for &str {
+    fn entities(self, app: &mut TestApp) -> Vec<Entity> {
+        app.select(self)
+    }
+}
+
+#[derive(Resource)]
+struct Captured<E>(Vec<E>);
+
+fn capture_events_system<E: Event + Clone>(
+    mut events: EventReader<E>,
+    mut captured: ResMut<Captured<E>>,
+) {
+    captured.0.extend(events.read().cloned());
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use belly_widgets::input::button::BtnEvent;
+
+    #[test]
+    fn click_button_in_headless_app() {
+        let mut app = TestApp::new();
+        app.capture::<BtnEvent>();
+        app.add(StyleSheet::parse(".big { width: 100px; }"));
+        app.spawn(eml! {
+            <body>
+                <button id="ok" c:big>"Ok"</button>
+            </body>
+        });
+        let button = app.one("#ok");
+        assert!(app.path(button).starts_with("body button#ok"));
+        assert!(app.has_class(button, "big"));
+        assert_eq!(app.get::<Style>(button).unwrap().width, Val::Px(100.));
+
+        app.hover(button);
+        assert!(app.has_state(button, "hover"));
+        app.press(button);
+        assert!(app.has_state(button, "active"));
+        app.release(button);
+        assert!(!app.has_state(button, "active"));
+        let signals = app.signals::<BtnEvent>();
+        assert_eq!(signals.len(), 2);
+        assert!(signals[0].pressed() && signals[1].released());
+    }
+
+    #[test]
+    fn toggle_signal_fires_once_per_flip() {
+        let mut app = TestApp::new();
+        app.capture::<BtnEvent>();
+        app.spawn(eml! {
+            <body>
+                <button id="toggle" mode="toggle">"Toggle"</button>
+                <button id="instant" mode="instant">"Instant"</button>
+            </body>
+        });
+        let toggled = |app: &mut TestApp| {
+            let signals = app.signals::<BtnEvent>();
+            signals.iter().filter(|signal| signal.toggled()).count()
+        };
+        let toggle = app.one("#toggle");
+        app.click(toggle);
+        assert_eq!(toggled(&mut app), 1);
+        assert!(app.has_state(toggle, "pressed"));
+        app.click(toggle);
+        assert_eq!(toggled(&mut app), 1);
+        app.click("#instant");
+        assert_eq!(toggled(&mut app), 0, "Instant buttons never toggle");
+    }
+
+    #[test]
+    fn snapshot_element_tree() {
+        let mut app = TestApp::new();
+        app.add(StyleSheet::parse(
+            ".panel { width: 100px; } .panel span { height: 10px; }",
+        ));
+        let root = app.spawn(eml! {
+            <div c:panel>
+                <span c:title>"Title"</span>
+                <span>"Body"</span>
+            </div>
+        });
+        let snapshot = app.snapshot(root);
+        assert!(snapshot.starts_with("div.panel\n  | width: 100px\n"));
+        assert!(snapshot.contains("\n  span.title\n    | height: 10px\n"));
+        assert!(snapshot.contains("\n    \"Title\"\n"));
+        assert!(snapshot.contains("\n    \"Body\"\n"));
+
+        let golden = std::env::temp_dir().join("belly-snapshot-element-tree.txt");
+        let _ = fs::remove_file(&golden);
+        app.assert_snapshot(root, &golden);
+        assert_eq!(fs::read_to_string(&golden).unwrap(), snapshot);
+        app.assert_snapshot(root, &golden);
+    }
+
+    #[test]
+    fn interpolate_eml_text_and_attributes() {
+        let mut app = TestApp::new();
+        let (score, kind) = (42, "danger");
+        let root = app.spawn(eml! {
+            <span id="score" c:{kind} c:{"wide tall"} c:fixed s:width="{score}px">
+                "Score: {score} {{points}}"
+            </span>
+        });
+        for class in ["danger", "wide", "tall", "fixed"] {
+            assert!(app.has_class(root, class), "missing {class} class");
+        }
+        assert_eq!(app.get::<Style>(root).unwrap().width, Val::Px(42.));
+        let text = app.get::<Children>(root).unwrap()[0];
+        let text = &app.get::<Text>(text).unwrap().sections[0].value;
+        assert_eq!(text, "Score: 42 {points}");
+
+        let root = app.spawn(eml! {
+            <span>"{{escaped}} { not a placeholder }"</span>
+        });
+        let text = app.get::<Children>(root).unwrap()[0];
+        let text = &app.get::<Text>(text).unwrap().sections[0].value;
+        assert_eq!(text, "{escaped} { not a placeholder }");
+    }
+
+    #[test]
+    fn spread_params_into_element() {
+        #[derive(IntoParams)]
+        struct SpanParams {
+            #[param(name = "c:primary")]
+            primary: bool,
+            #[param(name = "c:hidden")]
+            hidden: bool,
+            #[param(name = "s:width")]
+            width: String,
+            id: Option<String>,
+        }
+        let mut app = TestApp::new();
+        let span = SpanParams {
+            primary: true,
+            hidden: false,
+            width: "20px".into(),
+            id: Some("spread".into()),
+        };
+        let mut params = belly_core::eml::Params::default();
+        params.insert("c:extra", belly_core::eml::Variant::Undefined);
+        app.spawn(eml! {
+            <div>
+                <span {..span} {..params} c:manual/>
+            </div>
+        });
+        let span = app.one("#spread");
+        for class in ["primary", "extra", "manual"] {
+            assert!(app.has_class(span, class), "missing {class} class");
+        }
+        assert!(!app.has_class(span, "hidden"));
+        assert_eq!(app.get::<Style>(span).unwrap().width, Val::Px(20.));
+    }
+
+    #[test]
+    fn build_fragment_roots() {
+        let mut app = TestApp::new();
+        let list = app.spawn(eml! {
+            <div id="list">
+                <span c:first/>
+            </div>
+        });
+        let fragment = eml! {
+            <>
+                <span c:second/>
+                "text"
+            </>
+        };
+        app.add(fragment.render_to(list));
+        let children = app.get::<Children>(list).unwrap().to_vec();
+        assert_eq!(children.len(), 3);
+        assert!(app.has_class(children[1], "second"));
+        assert!(app.get::<Text>(children[2]).is_some());
+
+        let root = app.spawn(eml! {
+            <span c:a/>
+            <span c:b/>
+        });
+        assert_eq!(app.path(root), "div");
+        assert_eq!(app.get::<Children>(root).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn normalize_eml_text_whitespace() {
+        let mut app = TestApp::new();
+        let root = app.spawn(eml! {
+            <div>
+                <span id="normal">"Hello,
+                    big   world&nbsp;&amp; more"</span>
+                <span id="lines" white-space="pre-line">"
+                    First line
+                    Second   line
+                "</span>
+            </div>
+        });
+        let text = |app: &mut TestApp, query: &str| {
+            let span = app.one(query);
+            let text = app.get::<Children>(span).unwrap()[0];
+            app.get::<Text>(text).unwrap().sections[0].value.clone()
+        };
+        assert_eq!(text(&mut app, "#normal"), "Hello, big world\u{a0}& more");
+        assert_eq!(text(&mut app, "#lines"), "First line\nSecond line");
+        assert_eq!(app.get::<Children>(root).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn declare_widget_parts() {
+        assert_eq!(Widgets::button().parts(), &["background", "foreground"]);
+        assert_eq!(Widgets::slider().parts(), &["thumb"]);
+        assert_eq!(Widgets::div().parts(), &[] as &[&str]);
+        let mut app = TestApp::new();
+        app.spawn(eml! { <slider/> });
+        assert_eq!(app.select("slider::thumb").len(), 1);
+    }
+
+    #[derive(Component, Default)]
+    struct Clicks(usize);
+
+    #[widget(state = Clicks)]
+    fn clicker(ctx: &mut WidgetContext) {
+        ctx.state::<Clicks>().0 = 1;
+    }
+
+    #[test]
+    fn keep_widget_local_state() {
+        let mut app = TestApp::new();
+        let root = app.spawn(eml! { <clicker/> });
+        assert_eq!(app.get::<Clicks>(root).map(|c| c.0), Some(1));
+    }
+
+    #[derive(Component, Default)]
+    struct Badge {
+        text: String,
+        size: f32,
+    }
+
+    #[widget]
+    #[param(text: String => Badge:text, required)]
+    #[param(size: f32 => Badge:size, default = 16.0)]
+    fn badge(_ctx: &mut WidgetContext) {}
+
+    #[test]
+    fn fill_default_widget_params() {
+        let mut app = TestApp::new();
+        let root = app.spawn(eml! { <badge text="new"/> });
+        let badge = app.get::<Badge>(root).unwrap();
+        assert_eq!((badge.text.as_str(), badge.size), ("new", 16.0));
+        let root = app.spawn(eml! { <badge text="hot" size=24.0/> });
+        assert_eq!(app.get::<Badge>(root).unwrap().size, 24.0);
+    }
+
+    #[derive(Debug, PartialEq, EnumParam)]
+    enum Direction {
+        LeftToRight,
+        #[param(name = "rtl")]
+        RightToLeft,
+    }
+
+    #[test]
+    fn parse_enum_params() {
+        use belly_core::eml::Variant;
+        assert_eq!("left-to-right".parse(), Ok(Direction::LeftToRight));
+        assert_eq!(Direction::try_from(Variant::from("rtl")), Ok(Direction::RightToLeft));
+        assert_eq!(
+            Direction::try_from(Variant::from(Direction::LeftToRight)),
+            Ok(Direction::LeftToRight)
+        );
+        assert_eq!(
+            "up".parse::<Direction>(),
+            Err("Can't parse `up` as Direction, expected one of: `left-to-right`, `rtl`".into())
+        );
+    }
+
+    #[widget]
+    /// The color of the knob handle
+    #[style_prop(handle-color: Color)]
+    #[style_prop(handle-size: f32)]
+    fn knob(_ctx: &mut WidgetContext) {}
+
+    #[test]
+    fn apply_widget_style_props() {
+        use belly_core::build::RegisterWidget;
+        let mut app = TestApp::new();
+        app.app.register_widget::<KnobWidget>();
+        app.add(StyleSheet::parse(".red { handle-color: #ff0000; handle-size: 8; }"));
+        let root = app.spawn(eml! { <knob c:red/> });
+        let style = app.get::<KnobStyle>(root).unwrap();
+        assert_eq!((style.handle_color, style.handle_size), (Color::RED, 8.));
+        let root = app.spawn(eml! { <knob/> });
+        assert_eq!(app.get::<KnobStyle>(root).unwrap().handle_size, 0.);
+    }
+
+    #[derive(States, Default, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum Mode {
+        #[default]
+        Play,
+        Pause,
+    }
+
+    #[test]
+    fn show_elements_in_state() {
+        use belly_core::build::RegisterUiState;
+        let mut app = TestApp::new();
+        app.app.init_state::<Mode>();
+        app.app.register_ui_state::<Mode>();
+        app.spawn(eml! {
+            <body>
+                <div id="menu" state:visible=Mode::Pause/>
+            </body>
+        });
+        let menu = app.one("#menu");
+        assert_eq!(app.get::<Style>(menu).unwrap().display, Display::None);
+        app.world().resource_mut::<NextState<Mode>>().set(Mode::Pause);
+        app.pump();
+        assert_eq!(app.get::<Style>(menu).unwrap().display, Display::Flex);
+    }
+}